@@ -0,0 +1,891 @@
+//! Expansion of `#[derive(ComponentMeta)]`
+
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Data, DeriveInput, Expr, ExprLit, ExprUnary, Fields, GenericArgument, Lit, LitInt, LitStr, Meta, PathArguments, Token, Type, UnOp};
+
+/// Component-level `#[component(...)]` attributes
+#[derive(Default)]
+struct ComponentAttributes {
+    name: Option<String>,
+    display_name: Option<String>,
+    description: Option<String>,
+    category: Option<String>,
+    version: Option<String>,
+    icon: Option<String>,
+    tags: Vec<String>,
+    krate: Option<syn::Path>,
+}
+
+/// `#[event(...)]` attributes on the struct or on a handler field
+#[derive(Default)]
+struct EventAttributes {
+    name: Option<String>,
+    description: Option<String>,
+    bubbles: bool,
+    parameters: Vec<EventParameter>,
+}
+
+/// `param(...)` inside `#[event(...)]`
+struct EventParameter {
+    name: String,
+    parameter_type: LitStr,
+    description: String,
+}
+
+/// Field-level `#[property(...)]` attributes
+#[derive(Default)]
+struct PropertyAttributes {
+    name: Option<String>,
+    display_name: Option<String>,
+    description: Option<String>,
+    default: Option<Expr>,
+    property_type: Option<LitStr>,
+    category: Option<String>,
+    required: bool,
+    readonly: bool,
+    advanced: bool,
+    skip: bool,
+    constraints: Vec<Constraint>,
+    control: Option<String>,
+    placeholder: Option<String>,
+    help: Option<String>,
+    unit: Option<String>,
+    step: Option<f64>,
+    options: Vec<String>,
+    renderer: Option<String>,
+}
+
+/// Mirrors `PropertyConstraint`
+enum Constraint {
+    Min(f64),
+    Max(f64),
+    Range(f64, f64),
+    MinLength(usize),
+    MaxLength(usize),
+    Pattern(String),
+    MinItems(usize),
+    MaxItems(usize),
+    UniqueItems,
+    Custom(String),
+}
+
+/// A field turned into a property
+struct Property {
+    field: syn::Ident,
+    name: String,
+    attributes: PropertyAttributes,
+    event: Option<EventAttributes>,
+    ty: Type,
+}
+
+/// Generate the `ComponentMeta` and `FromProperties` impls
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(&input.ident, "ComponentMeta can only be derived for structs with named fields")),
+        },
+        _ => return Err(syn::Error::new_spanned(&input.ident, "ComponentMeta can only be derived for structs")),
+    };
+
+    let component = parse_component_attributes(&input.attrs)?;
+    let mut events = Vec::new();
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("event")) {
+        let event = parse_event_attributes(attr)?;
+        if event.name.is_none() {
+            return Err(syn::Error::new_spanned(attr, "struct-level #[event] needs a `name`"));
+        }
+        events.push(event);
+    }
+
+    let mut properties = Vec::new();
+    let mut skipped = Vec::new();
+    let mut names = HashSet::new();
+    for field in fields {
+        let ident = field.ident.clone().expect("named field");
+        let attributes = parse_property_attributes(&field.attrs)?;
+        if attributes.skip {
+            skipped.push(ident);
+            continue;
+        }
+        let event = match field.attrs.iter().find(|attr| attr.path().is_ident("event")) {
+            Some(attr) => Some(parse_event_attributes(attr)?),
+            None => None,
+        };
+        let name = attributes.name.clone().unwrap_or_else(|| ident.unraw().to_string());
+        if !names.insert(name.clone()) {
+            return Err(syn::Error::new_spanned(&ident, format!("duplicate property `{}`", name)));
+        }
+        properties.push(Property { field: ident, name, attributes, event, ty: field.ty.clone() });
+    }
+
+    let registry = match &component.krate {
+        Some(krate) => quote! { #krate::rcl::component_registry },
+        None => quote! { ::ide_rs::rcl::component_registry },
+    };
+
+    let struct_name = input.ident.unraw().to_string();
+    let name = component.name.clone().unwrap_or(struct_name);
+    let display_name = component.display_name.clone().unwrap_or_else(|| name.clone());
+    let description = component.description.clone().unwrap_or_else(|| format!("{} component", name));
+    let category = category_tokens(component.category.as_deref().unwrap_or("Custom"), &registry);
+    let version = component.version.clone().unwrap_or_else(|| "1.0.0".to_string());
+    let icon = optional_string(&component.icon);
+    let tags = &component.tags;
+
+    let definitions = properties
+        .iter()
+        .map(|property| property_definition(property, &registry))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let defaults = properties
+        .iter()
+        .filter_map(|property| {
+            let default = property.attributes.default.as_ref()?;
+            let name = &property.name;
+            Some(default_value(default, is_float(&property.ty), &registry).map(|value| {
+                quote! { defaults.insert(#name.to_string(), #value); }
+            }))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let required: Vec<&String> = properties.iter().filter(|p| p.attributes.required).map(|p| &p.name).collect();
+    let groups = property_groups(&properties, &registry);
+
+    let field_events = properties.iter().filter_map(|property| {
+        let event = property.event.as_ref()?;
+        let default_name = property.name.strip_prefix("on_").unwrap_or(&property.name);
+        Some(event_definition(event, default_name, &registry))
+    });
+    let struct_events = events.iter().map(|event| event_definition(event, "", &registry));
+    let event_definitions = field_events.chain(struct_events).collect::<syn::Result<Vec<_>>>()?;
+
+    let field_values = properties.iter().map(|property| {
+        let field = &property.field;
+        let name = &property.name;
+        quote! {
+            #field: properties
+                .get(#name)
+                .and_then(#registry::FromPropertyValue::from_property_value)
+                .or_else(|| defaults.get(#name).and_then(#registry::FromPropertyValue::from_property_value))
+                .unwrap_or_default()
+        }
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let hash_map = quote! { ::std::collections::HashMap };
+
+    Ok(quote! {
+        impl #impl_generics #registry::ComponentMeta for #ident #ty_generics #where_clause {
+            fn metadata() -> #registry::ComponentMetadata {
+                #registry::ComponentMetadata {
+                    component_type: #name.to_string(),
+                    display_name: #display_name.to_string(),
+                    description: #description.to_string(),
+                    category: #category,
+                    version: #version.to_string(),
+                    schema: <Self as #registry::ComponentMeta>::schema(),
+                    defaults: <Self as #registry::ComponentMeta>::defaults(),
+                    events: <Self as #registry::ComponentMeta>::events().into_iter().map(|event| event.name).collect(),
+                    icon: #icon,
+                    tags: vec![#(#tags.to_string()),*],
+                }
+            }
+
+            fn schema() -> #registry::PropertySchema {
+                let mut properties = #hash_map::new();
+                #(#definitions)*
+                #registry::PropertySchema {
+                    properties,
+                    required: vec![#(#required.to_string()),*],
+                    groups: vec![#(#groups),*],
+                }
+            }
+
+            fn defaults() -> #hash_map<String, #registry::PropertyValue> {
+                #[allow(unused_mut)]
+                let mut defaults = #hash_map::new();
+                #(#defaults)*
+                defaults
+            }
+
+            fn events() -> Vec<#registry::EventDefinition> {
+                vec![#(#event_definitions),*]
+            }
+        }
+
+        impl #impl_generics #registry::FromProperties for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn from_properties(properties: &#hash_map<String, #registry::PropertyValue>) -> Self {
+                let defaults = <Self as #registry::ComponentMeta>::defaults();
+                Self {
+                    #(#field_values,)*
+                    #(#skipped: ::core::default::Default::default(),)*
+                }
+            }
+        }
+    })
+}
+
+fn parse_component_attributes(attrs: &[Attribute]) -> syn::Result<ComponentAttributes> {
+    let mut component = ComponentAttributes::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("component")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                component.name = Some(parse_string(&meta)?);
+            } else if meta.path.is_ident("display_name") {
+                component.display_name = Some(parse_string(&meta)?);
+            } else if meta.path.is_ident("description") {
+                component.description = Some(parse_string(&meta)?);
+            } else if meta.path.is_ident("category") {
+                component.category = Some(parse_string(&meta)?);
+            } else if meta.path.is_ident("version") {
+                component.version = Some(parse_string(&meta)?);
+            } else if meta.path.is_ident("icon") {
+                component.icon = Some(parse_string(&meta)?);
+            } else if meta.path.is_ident("tags") {
+                component.tags = parse_strings(&meta)?;
+            } else if meta.path.is_ident("crate") {
+                component.krate = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+            } else {
+                return Err(meta.error("unknown component attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(component)
+}
+
+fn parse_property_attributes(attrs: &[Attribute]) -> syn::Result<PropertyAttributes> {
+    let mut property = PropertyAttributes::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("property")) {
+        attr.parse_nested_meta(|meta| {
+            let path = &meta.path;
+            if path.is_ident("name") {
+                property.name = Some(parse_string(&meta)?);
+            } else if path.is_ident("display_name") {
+                property.display_name = Some(parse_string(&meta)?);
+            } else if path.is_ident("description") {
+                property.description = Some(parse_string(&meta)?);
+            } else if path.is_ident("default") {
+                property.default = Some(meta.value()?.parse()?);
+            } else if path.is_ident("type") {
+                property.property_type = Some(meta.value()?.parse()?);
+            } else if path.is_ident("category") {
+                property.category = Some(parse_string(&meta)?);
+            } else if path.is_ident("required") {
+                property.required = parse_flag(&meta)?;
+            } else if path.is_ident("readonly") {
+                property.readonly = parse_flag(&meta)?;
+            } else if path.is_ident("advanced") {
+                property.advanced = parse_flag(&meta)?;
+            } else if path.is_ident("skip") {
+                property.skip = parse_flag(&meta)?;
+            } else if path.is_ident("min") {
+                property.constraints.push(Constraint::Min(parse_number(&meta)?));
+            } else if path.is_ident("max") {
+                property.constraints.push(Constraint::Max(parse_number(&meta)?));
+            } else if path.is_ident("range") {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                let bounds = Punctuated::<Expr, Token![,]>::parse_terminated(&content)?;
+                let bounds = bounds.iter().map(number).collect::<syn::Result<Vec<f64>>>()?;
+                let [min, max] = bounds[..] else {
+                    return Err(meta.error("expected `range(min, max)`"));
+                };
+                property.constraints.push(Constraint::Range(min, max));
+            } else if path.is_ident("min_length") {
+                property.constraints.push(Constraint::MinLength(parse_count(&meta)?));
+            } else if path.is_ident("max_length") {
+                property.constraints.push(Constraint::MaxLength(parse_count(&meta)?));
+            } else if path.is_ident("pattern") {
+                property.constraints.push(Constraint::Pattern(parse_string(&meta)?));
+            } else if path.is_ident("min_items") {
+                property.constraints.push(Constraint::MinItems(parse_count(&meta)?));
+            } else if path.is_ident("max_items") {
+                property.constraints.push(Constraint::MaxItems(parse_count(&meta)?));
+            } else if path.is_ident("unique_items") {
+                if parse_flag(&meta)? {
+                    property.constraints.push(Constraint::UniqueItems);
+                }
+            } else if path.is_ident("validator") {
+                property.constraints.push(Constraint::Custom(parse_string(&meta)?));
+            } else if path.is_ident("control") {
+                property.control = Some(parse_string(&meta)?);
+            } else if path.is_ident("placeholder") {
+                property.placeholder = Some(parse_string(&meta)?);
+            } else if path.is_ident("help") {
+                property.help = Some(parse_string(&meta)?);
+            } else if path.is_ident("unit") {
+                property.unit = Some(parse_string(&meta)?);
+            } else if path.is_ident("step") {
+                property.step = Some(parse_number(&meta)?);
+            } else if path.is_ident("options") {
+                property.options = parse_strings(&meta)?;
+            } else if path.is_ident("renderer") {
+                property.renderer = Some(parse_string(&meta)?);
+            } else {
+                return Err(meta.error("unknown property attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(property)
+}
+
+fn parse_event_attributes(attr: &Attribute) -> syn::Result<EventAttributes> {
+    let mut event = EventAttributes::default();
+    if let Meta::Path(_) = attr.meta {
+        return Ok(event);
+    }
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("name") {
+            event.name = Some(parse_string(&meta)?);
+        } else if meta.path.is_ident("description") {
+            event.description = Some(parse_string(&meta)?);
+        } else if meta.path.is_ident("bubbles") {
+            event.bubbles = parse_flag(&meta)?;
+        } else if meta.path.is_ident("param") {
+            let mut name = None;
+            let mut parameter_type = None;
+            let mut description = String::new();
+            meta.parse_nested_meta(|param| {
+                if param.path.is_ident("name") {
+                    name = Some(parse_string(&param)?);
+                } else if param.path.is_ident("type") {
+                    parameter_type = Some(param.value()?.parse::<LitStr>()?);
+                } else if param.path.is_ident("description") {
+                    description = parse_string(&param)?;
+                } else {
+                    return Err(param.error("unknown event parameter attribute"));
+                }
+                Ok(())
+            })?;
+            let (Some(name), Some(parameter_type)) = (name, parameter_type) else {
+                return Err(meta.error("event parameters need a `name` and a `type`"));
+            };
+            event.parameters.push(EventParameter { name, parameter_type, description });
+        } else {
+            return Err(meta.error("unknown event attribute"));
+        }
+        Ok(())
+    })?;
+    Ok(event)
+}
+
+fn parse_string(meta: &ParseNestedMeta) -> syn::Result<String> {
+    Ok(meta.value()?.parse::<LitStr>()?.value())
+}
+
+fn parse_strings(meta: &ParseNestedMeta) -> syn::Result<Vec<String>> {
+    let content;
+    syn::parenthesized!(content in meta.input);
+    let values = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+    Ok(values.iter().map(LitStr::value).collect())
+}
+
+/// `flag` or `flag = true/false`
+fn parse_flag(meta: &ParseNestedMeta) -> syn::Result<bool> {
+    if meta.input.peek(Token![=]) {
+        Ok(meta.value()?.parse::<syn::LitBool>()?.value)
+    } else {
+        Ok(true)
+    }
+}
+
+fn parse_number(meta: &ParseNestedMeta) -> syn::Result<f64> {
+    number(&meta.value()?.parse()?)
+}
+
+fn parse_count(meta: &ParseNestedMeta) -> syn::Result<usize> {
+    meta.value()?.parse::<LitInt>()?.base10_parse()
+}
+
+fn number(expr: &Expr) -> syn::Result<f64> {
+    match expr {
+        Expr::Lit(ExprLit { lit: Lit::Int(value), .. }) => value.base10_parse(),
+        Expr::Lit(ExprLit { lit: Lit::Float(value), .. }) => value.base10_parse(),
+        Expr::Unary(ExprUnary { op: UnOp::Neg(_), expr, .. }) => Ok(-number(expr)?),
+        _ => Err(syn::Error::new_spanned(expr, "expected a number")),
+    }
+}
+
+fn property_definition(property: &Property, registry: &TokenStream) -> syn::Result<TokenStream> {
+    let attributes = &property.attributes;
+    let name = &property.name;
+    let display_name = attributes.display_name.clone().unwrap_or_else(|| humanize(name));
+    let description = attributes.description.clone().unwrap_or_else(|| display_name.clone());
+
+    let property_type = match &attributes.property_type {
+        Some(type_name) => named_property_type(type_name, &attributes.options, registry)?,
+        None if property.event.is_some() => quote! { #registry::PropertyType::EventHandler },
+        None if !attributes.options.is_empty() => {
+            let options = &attributes.options;
+            quote! { #registry::PropertyType::Enum(vec![#(#options.to_string()),*]) }
+        }
+        None => infer_property_type(&property.ty, registry),
+    };
+    let default_value = match &attributes.default {
+        Some(default) => default_value(default, is_float(&property.ty), registry)?,
+        None => quote! { #registry::PropertyValue::Null },
+    };
+    let constraints = attributes.constraints.iter().map(|constraint| constraint_tokens(constraint, registry));
+    let control_type = match &attributes.control {
+        Some(control) => control_tokens(control, registry),
+        None => quote! { #registry::ControlType::for_property_type(&property_type) },
+    };
+    let placeholder = optional_string(&attributes.placeholder);
+    let help_text = optional_string(&attributes.help);
+    let unit = optional_string(&attributes.unit);
+    let step = match attributes.step {
+        Some(step) => quote! { Some(#step) },
+        None => quote! { None },
+    };
+    let options = if attributes.options.is_empty() {
+        quote! { None }
+    } else {
+        let options = attributes.options.iter().map(|option| {
+            quote! {
+                #registry::SelectOption { value: #option.to_string(), label: #option.to_string(), icon: None }
+            }
+        });
+        quote! { Some(vec![#(#options),*]) }
+    };
+    let custom_renderer = optional_string(&attributes.renderer);
+    let readonly = attributes.readonly;
+    let advanced = attributes.advanced;
+
+    Ok(quote! {
+        {
+            let property_type = #property_type;
+            properties.insert(
+                #name.to_string(),
+                #registry::PropertyDefinition {
+                    name: #name.to_string(),
+                    display_name: #display_name.to_string(),
+                    description: #description.to_string(),
+                    default_value: #default_value,
+                    constraints: vec![#(#constraints),*],
+                    ui_hints: #registry::PropertyUIHints {
+                        control_type: #control_type,
+                        placeholder: #placeholder,
+                        help_text: #help_text,
+                        unit: #unit,
+                        step: #step,
+                        options: #options,
+                        custom_renderer: #custom_renderer,
+                    },
+                    property_type,
+                    readonly: #readonly,
+                    advanced: #advanced,
+                },
+            );
+        }
+    })
+}
+
+/// One collapsible group per property category, in field order
+fn property_groups(properties: &[Property], registry: &TokenStream) -> Vec<TokenStream> {
+    let mut categories: Vec<(&str, Vec<&str>)> = Vec::new();
+    for property in properties {
+        let Some(category) = property.attributes.category.as_deref() else {
+            continue;
+        };
+        match categories.iter_mut().find(|(name, _)| *name == category) {
+            Some((_, members)) => members.push(&property.name),
+            None => categories.push((category, vec![&property.name])),
+        }
+    }
+    categories
+        .into_iter()
+        .map(|(category, members)| {
+            let name = category.to_lowercase().replace(' ', "_");
+            quote! {
+                #registry::PropertyGroup {
+                    name: #name.to_string(),
+                    display_name: #category.to_string(),
+                    properties: vec![#(#members.to_string()),*],
+                    collapsible: true,
+                    collapsed: false,
+                }
+            }
+        })
+        .collect()
+}
+
+fn event_definition(event: &EventAttributes, default_name: &str, registry: &TokenStream) -> syn::Result<TokenStream> {
+    let name = event.name.clone().unwrap_or_else(|| default_name.to_string());
+    let description = event.description.clone().unwrap_or_else(|| format!("{} event", humanize(&name)));
+    let bubbles = event.bubbles;
+    let parameters = event
+        .parameters
+        .iter()
+        .map(|parameter| {
+            let name = &parameter.name;
+            let description = &parameter.description;
+            let parameter_type = named_property_type(&parameter.parameter_type, &[], registry)?;
+            Ok(quote! {
+                #registry::EventParameter {
+                    name: #name.to_string(),
+                    parameter_type: #parameter_type,
+                    description: #description.to_string(),
+                }
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    Ok(quote! {
+        #registry::EventDefinition {
+            name: #name.to_string(),
+            description: #description.to_string(),
+            parameters: vec![#(#parameters),*],
+            bubbles: #bubbles,
+        }
+    })
+}
+
+/// Property type named in `type = "..."`
+fn named_property_type(type_name: &LitStr, options: &[String], registry: &TokenStream) -> syn::Result<TokenStream> {
+    let variant = match type_name.value().as_str() {
+        "string" => "String",
+        "integer" => "Integer",
+        "float" => "Float",
+        "boolean" => "Boolean",
+        "enum" => {
+            if options.is_empty() {
+                return Err(syn::Error::new_spanned(type_name, "enum properties need `options(...)`"));
+            }
+            return Ok(quote! { #registry::PropertyType::Enum(vec![#(#options.to_string()),*]) });
+        }
+        "color" => "Color",
+        "size" => "Size",
+        "position" => "Position",
+        "margin" => "Margin",
+        "padding" => "Padding",
+        "font" => "Font",
+        "flex_direction" => "FlexDirection",
+        "alignment" => "Alignment",
+        "justify_content" => "JustifyContent",
+        "event_handler" => "EventHandler",
+        "component_ref" => "ComponentRef",
+        "resource_ref" => "ResourceRef",
+        other => return Ok(quote! { #registry::PropertyType::Custom(#other.to_string()) }),
+    };
+    let variant = format_ident!("{}", variant);
+    Ok(quote! { #registry::PropertyType::#variant })
+}
+
+/// Property type for a Rust field type
+fn infer_property_type(ty: &Type, registry: &TokenStream) -> TokenStream {
+    let Type::Path(type_path) = ty else {
+        let name = quote!(#ty).to_string();
+        return quote! { #registry::PropertyType::Custom(#name.to_string()) };
+    };
+    let segment = type_path.path.segments.last().expect("type path has a segment");
+    let type_name = segment.ident.to_string();
+    match type_name.as_str() {
+        "String" | "str" | "char" | "PathBuf" => quote! { #registry::PropertyType::String },
+        "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize" => {
+            quote! { #registry::PropertyType::Integer }
+        }
+        "f32" | "f64" => quote! { #registry::PropertyType::Float },
+        "bool" => quote! { #registry::PropertyType::Boolean },
+        "Option" | "Box" => match first_type_argument(ty) {
+            Some(inner) => infer_property_type(inner, registry),
+            None => quote! { #registry::PropertyType::Custom(#type_name.to_string()) },
+        },
+        "Vec" => {
+            let inner = match first_type_argument(ty) {
+                Some(inner) => infer_property_type(inner, registry),
+                None => quote! { #registry::PropertyType::String },
+            };
+            quote! { #registry::PropertyType::Array(Box::new(#inner)) }
+        }
+        "HashMap" | "BTreeMap" => quote! {
+            #registry::PropertyType::Object(#registry::PropertySchema {
+                properties: ::std::collections::HashMap::new(),
+                required: vec![],
+                groups: vec![],
+            })
+        },
+        "Color" | "Size" | "Position" | "Margin" | "Padding" | "FlexDirection" | "Alignment" | "JustifyContent" => {
+            let variant = &segment.ident;
+            quote! { #registry::PropertyType::#variant }
+        }
+        _ => quote! { #registry::PropertyType::Custom(#type_name.to_string()) },
+    }
+}
+
+fn first_type_argument(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else { return None };
+    let PathArguments::AngleBracketed(arguments) = &type_path.path.segments.last()?.arguments else {
+        return None;
+    };
+    arguments.args.iter().find_map(|argument| match argument {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+/// Whether numeric defaults of the field should be floats
+fn is_float(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else { return false };
+    match type_path.path.segments.last().map(|segment| segment.ident.to_string()).as_deref() {
+        Some("f32" | "f64") => true,
+        Some("Option" | "Vec" | "Box") => first_type_argument(ty).is_some_and(is_float),
+        _ => false,
+    }
+}
+
+/// `PropertyValue` for a `default = ...`; literals are converted, any other
+/// expression must evaluate to a `PropertyValue`
+fn default_value(expr: &Expr, float: bool, registry: &TokenStream) -> syn::Result<TokenStream> {
+    let value = match expr {
+        Expr::Lit(ExprLit { lit, .. }) => match lit {
+            Lit::Str(value) => quote! { #registry::PropertyValue::String(#value.to_string()) },
+            Lit::Bool(value) => quote! { #registry::PropertyValue::Boolean(#value) },
+            Lit::Int(value) if !float => {
+                let value: i64 = value.base10_parse()?;
+                quote! { #registry::PropertyValue::Integer(#value) }
+            }
+            Lit::Int(_) | Lit::Float(_) => {
+                let value = number(expr)?;
+                quote! { #registry::PropertyValue::Float(#value) }
+            }
+            _ => return Err(syn::Error::new_spanned(lit, "unsupported default value")),
+        },
+        Expr::Unary(ExprUnary { op: UnOp::Neg(_), expr: inner, .. }) => match &**inner {
+            Expr::Lit(ExprLit { lit: Lit::Int(value), .. }) if !float => {
+                let value = -value.base10_parse::<i64>()?;
+                quote! { #registry::PropertyValue::Integer(#value) }
+            }
+            _ => {
+                let value = number(expr)?;
+                quote! { #registry::PropertyValue::Float(#value) }
+            }
+        },
+        Expr::Array(array) => {
+            let items = array
+                .elems
+                .iter()
+                .map(|item| default_value(item, float, registry))
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! { #registry::PropertyValue::Array(vec![#(#items),*]) }
+        }
+        other => quote! { #other },
+    };
+    Ok(value)
+}
+
+fn constraint_tokens(constraint: &Constraint, registry: &TokenStream) -> TokenStream {
+    let constraint_type = quote! { #registry::PropertyConstraint };
+    match constraint {
+        Constraint::Min(min) => quote! { #constraint_type::Min(#min) },
+        Constraint::Max(max) => quote! { #constraint_type::Max(#max) },
+        Constraint::Range(min, max) => quote! { #constraint_type::Range(#min, #max) },
+        Constraint::MinLength(length) => quote! { #constraint_type::MinLength(#length) },
+        Constraint::MaxLength(length) => quote! { #constraint_type::MaxLength(#length) },
+        Constraint::Pattern(pattern) => quote! { #constraint_type::Pattern(#pattern.to_string()) },
+        Constraint::MinItems(count) => quote! { #constraint_type::MinItems(#count) },
+        Constraint::MaxItems(count) => quote! { #constraint_type::MaxItems(#count) },
+        Constraint::UniqueItems => quote! { #constraint_type::UniqueItems },
+        Constraint::Custom(validator) => quote! { #constraint_type::Custom(#validator.to_string()) },
+    }
+}
+
+fn control_tokens(control: &str, registry: &TokenStream) -> TokenStream {
+    let variant = match control {
+        "text_input" => "TextInput",
+        "text_area" => "TextArea",
+        "number_input" => "NumberInput",
+        "checkbox" => "Checkbox",
+        "select" => "Select",
+        "radio" => "Radio",
+        "color_picker" => "ColorPicker",
+        "slider" => "Slider",
+        "range_slider" => "RangeSlider",
+        "file_picker" => "FilePicker",
+        "spacing" => "SpacingControl",
+        "alignment" => "AlignmentControl",
+        other => return quote! { #registry::ControlType::Custom(#other.to_string()) },
+    };
+    let variant = format_ident!("{}", variant);
+    quote! { #registry::ControlType::#variant }
+}
+
+/// Parse component category string to ComponentCategory tokens
+fn category_tokens(category: &str, registry: &TokenStream) -> TokenStream {
+    match category {
+        "Layout" | "Input" | "Display" | "Navigation" | "Data" | "Media" | "Advanced" => {
+            let variant = format_ident!("{}", category);
+            quote! { #registry::ComponentCategory::#variant }
+        }
+        other => quote! { #registry::ComponentCategory::Custom(#other.to_string()) },
+    }
+}
+
+fn optional_string(value: &Option<String>) -> TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value.to_string()) },
+        None => quote! { None },
+    }
+}
+
+/// `font_size` -> `Font Size`
+fn humanize(name: &str) -> String {
+    name.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn expand_to_string(input: DeriveInput) -> String {
+        expand(&input).unwrap().to_string()
+    }
+
+    fn expand_error(input: DeriveInput) -> String {
+        expand(&input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_component_attributes() {
+        let output = expand_to_string(parse_quote! {
+            #[component(name = "Button", description = "A clickable button", category = "Input", tags("click", "form"))]
+            struct Button {
+                text: String,
+            }
+        });
+        assert!(output.contains("ComponentCategory :: Input"));
+        assert!(output.contains("\"A clickable button\""));
+        assert!(output.contains("\"click\" . to_string ()"));
+        assert!(output.contains(":: ide_rs :: rcl :: component_registry :: ComponentMeta for Button"));
+        assert!(output.contains("FromProperties for Button"));
+    }
+
+    #[test]
+    fn test_crate_path_override() {
+        let output = expand_to_string(parse_quote! {
+            #[component(crate = "crate")]
+            struct Label {
+                text: String,
+            }
+        });
+        assert!(output.contains("crate :: rcl :: component_registry :: ComponentMeta for Label"));
+        assert!(!output.contains("ide_rs"));
+    }
+
+    #[test]
+    fn test_map_rust_type_to_property_type() {
+        let registry = quote! { r };
+        let infer = |ty: Type| infer_property_type(&ty, &registry).to_string();
+        assert_eq!(infer(parse_quote!(String)), "r :: PropertyType :: String");
+        assert_eq!(infer(parse_quote!(bool)), "r :: PropertyType :: Boolean");
+        assert_eq!(infer(parse_quote!(u16)), "r :: PropertyType :: Integer");
+        assert_eq!(infer(parse_quote!(Option<f32>)), "r :: PropertyType :: Float");
+        assert_eq!(infer(parse_quote!(Vec<String>)), "r :: PropertyType :: Array (Box :: new (r :: PropertyType :: String))");
+        assert_eq!(infer(parse_quote!(Color)), "r :: PropertyType :: Color");
+        assert!(infer(parse_quote!(Widget)).contains("Custom (\"Widget\""));
+    }
+
+    #[test]
+    fn test_convert_default_value() {
+        let registry = quote! { r };
+        let convert = |expr: Expr, float: bool| default_value(&expr, float, &registry).unwrap().to_string();
+        assert_eq!(convert(parse_quote!(true), false), "r :: PropertyValue :: Boolean (true)");
+        assert_eq!(convert(parse_quote!(42), false), "r :: PropertyValue :: Integer (42i64)");
+        assert_eq!(convert(parse_quote!(-3), false), "r :: PropertyValue :: Integer (- 3i64)");
+        assert_eq!(convert(parse_quote!(42), true), "r :: PropertyValue :: Float (42f64)");
+        assert_eq!(convert(parse_quote!(3.5), true), "r :: PropertyValue :: Float (3.5f64)");
+        assert_eq!(convert(parse_quote!("hello"), false), "r :: PropertyValue :: String (\"hello\" . to_string ())");
+        assert!(convert(parse_quote!(["a", "b"]), false).starts_with("r :: PropertyValue :: Array"));
+        assert_eq!(convert(parse_quote!(custom_value()), false), "custom_value ()");
+    }
+
+    #[test]
+    fn test_property_attributes() {
+        let output = expand_to_string(parse_quote! {
+            struct Slider {
+                #[property(default = 5, range(0, 10), step = 0.5, control = "slider", unit = "px", category = "Value", required)]
+                value: f64,
+                #[property(options("left", "right"), advanced, readonly)]
+                side: String,
+                #[property(skip)]
+                dragging: bool,
+            }
+        });
+        assert!(output.contains("PropertyConstraint :: Range (0f64 , 10f64)"));
+        assert!(output.contains("PropertyValue :: Float (5f64)"));
+        assert!(output.contains("ControlType :: Slider"));
+        assert!(output.contains("PropertyType :: Enum"));
+        assert!(output.contains("required : vec ! [\"value\" . to_string ()]"));
+        assert!(output.contains("display_name : \"Value\" . to_string ()"));
+        assert!(output.contains("dragging : :: core :: default :: Default :: default ()"));
+        assert!(!output.contains("\"dragging\""));
+    }
+
+    #[test]
+    fn test_events() {
+        let output = expand_to_string(parse_quote! {
+            #[event(name = "hover", param(name = "x", type = "float"))]
+            struct Button {
+                #[event(description = "Clicked", bubbles)]
+                on_click: String,
+            }
+        });
+        assert!(output.contains("name : \"click\" . to_string ()"));
+        assert!(output.contains("bubbles : true"));
+        assert!(output.contains("PropertyType :: EventHandler"));
+        assert!(output.contains("name : \"hover\" . to_string ()"));
+        assert!(output.contains("parameter_type : :: ide_rs :: rcl :: component_registry :: PropertyType :: Float"));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(expand_error(parse_quote! { struct Point(f32, f32); }).contains("named fields"));
+        assert!(expand_error(parse_quote! { enum Mode { A } }).contains("only be derived for structs"));
+        assert!(expand_error(parse_quote! {
+            struct Button {
+                #[property(colour = "red")]
+                text: String,
+            }
+        })
+        .contains("unknown property attribute"));
+        assert!(expand_error(parse_quote! {
+            struct Button {
+                #[property(type = "enum")]
+                mode: String,
+            }
+        })
+        .contains("need `options"));
+        assert!(expand_error(parse_quote! {
+            struct Button {
+                text: String,
+                #[property(name = "text")]
+                label: String,
+            }
+        })
+        .contains("duplicate property"));
+    }
+
+    #[test]
+    fn test_humanize() {
+        assert_eq!(humanize("font_size"), "Font Size");
+        assert_eq!(humanize("text"), "Text");
+    }
+}
@@ -0,0 +1,56 @@
+//! Derive macros for ide-rs
+//!
+//! `#[derive(ComponentMeta)]` generates the component metadata, property
+//! schema, events and factory that `ComponentRegistry` needs to register an
+//! RCL component. Use it through the re-export in `ide_rs::rcl`.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod component_meta;
+
+/// Derive `ComponentMeta` and `FromProperties` for a component struct
+///
+/// Every named field becomes a property. The property type is inferred from
+/// the field type unless it is given explicitly.
+///
+/// ```ignore
+/// #[derive(Default, ComponentMeta)]
+/// #[component(name = "Slider", category = "Input", tags("range", "number"))]
+/// #[event(name = "change", description = "Value changed", param(name = "value", type = "float"))]
+/// struct Slider {
+///     #[property(default = 50.0, range(0, 100), control = "slider", step = 1.0, category = "Value")]
+///     value: f64,
+///
+///     #[property(description = "Label shown next to the slider", max_length = 40, required)]
+///     label: String,
+///
+///     #[event(name = "click", bubbles)]
+///     on_click: String,
+///
+///     #[property(skip)]
+///     dragging: bool,
+/// }
+/// ```
+///
+/// Struct attributes:
+/// - `#[component(...)]`: `name`, `display_name`, `description`, `category`,
+///   `version`, `icon`, `tags("a", ...)` and `crate = "path"` for the path of
+///   the `ide_rs` crate (`crate` inside ide-rs itself)
+/// - `#[event(...)]`: an event without a handler property
+///
+/// Field attributes:
+/// - `#[property(...)]`: `name`, `display_name`, `description`, `default`,
+///   `type`, `category`, `required`, `readonly`, `advanced`, `skip`;
+///   constraints `min`, `max`, `range(min, max)`, `min_length`, `max_length`,
+///   `pattern`, `min_items`, `max_items`, `unique_items`, `validator`;
+///   UI hints `control`, `placeholder`, `help`, `unit`, `step`,
+///   `options("a", ...)` and `renderer`
+/// - `#[event(...)]`: the field holds the handler of an event; `name`
+///   (defaults to the field name without `on_`), `description`, `bubbles`
+///   and `param(name = "...", type = "...", description = "...")`
+#[proc_macro_derive(ComponentMeta, attributes(component, property, event))]
+pub fn derive_component_meta(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    component_meta::expand(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
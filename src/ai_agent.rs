@@ -23,22 +23,25 @@
 //! - Conversation history for contextual responses
 //! - Specialized knowledge for Rust and RAD development
 //!
-//! ## Integration with Ollama
+//! ## Model Providers
 //!
-//! The system integrates with Ollama for local AI model execution, providing:
+//! Requests go through an [`AiProvider`], by default a local Ollama server, providing:
 //! - Privacy-preserving local AI processing
-//! - Customizable model selection (Code Llama, Llama 2, etc.)
-//! - Offline capability for secure development environments
+//! - Any OpenAI-compatible local server (llama.cpp, vLLM, LM Studio) as an alternative
+//! - Per-task model selection (e.g. a code model for generation, a small model for reviews)
+//! - Token-by-token streaming with cancellation for the AI panel
 //! - Specialized prompt engineering for development tasks
 
-use ollama_rs::Ollama;
-use ollama_rs::generation::completion::request::GenerationRequest;
+use crate::ai_provider::{AiProvider, AiStream, AiStreamEvent, CompletionRequest, OllamaProvider};
+use crate::editor::ai_edits::{edit_prompt, parse_edits, EditResponse, EDIT_FORMAT};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Enhanced AI Agent with specialized capabilities for software development
 #[allow(dead_code)]
 pub struct AiAgent {
-    ollama: Ollama,
+    provider: Arc<dyn AiProvider>,
+    task_models: HashMap<AiTaskType, String>,
     context: AiContext,
     conversation_history: Vec<AiConversation>,
     specialized_prompts: HashMap<AiTaskType, String>,
@@ -73,6 +76,36 @@ pub enum AiTaskType {
     LearningAssistance,
 }
 
+impl AiTaskType {
+    /// Every task type, in display order
+    pub const ALL: [AiTaskType; 15] = [
+        AiTaskType::CodeGeneration,
+        AiTaskType::BugFixing,
+        AiTaskType::CodeReview,
+        AiTaskType::Architecture,
+        AiTaskType::UIDesign,
+        AiTaskType::Testing,
+        AiTaskType::Documentation,
+        AiTaskType::Refactoring,
+        AiTaskType::Performance,
+        AiTaskType::Security,
+        AiTaskType::PropertySuggestion,
+        AiTaskType::ComponentDesign,
+        AiTaskType::ProjectStructure,
+        AiTaskType::DeploymentGuidance,
+        AiTaskType::LearningAssistance,
+    ];
+}
+
+/// A streamed request whose response is added to the conversation once complete
+pub struct AiExchange {
+    pub user_input: String,
+    pub task_type: AiTaskType,
+    /// The response is a set of structured edits, see [`parse_edits`]
+    pub expects_edits: bool,
+    pub stream: AiStream,
+}
+
 /// Context information for better AI responses
 #[derive(Clone, Debug)]
 pub struct AiContext {
@@ -102,7 +135,7 @@ impl Default for AiContext {
                 "serde".to_string(),
                 "anyhow".to_string(),
                 "syntect".to_string(),
-                "ollama-rs".to_string(),
+                "reqwest".to_string(),
             ],
             component_context: Vec::new(),
             project_structure: Vec::new(),
@@ -117,8 +150,14 @@ impl Default for AiContext {
 #[allow(dead_code)]
 impl AiAgent {
     pub fn new() -> Self {
+        Self::with_provider(Arc::new(OllamaProvider::default()))
+    }
+
+    /// Create an agent that sends its requests to `provider`
+    pub fn with_provider(provider: Arc<dyn AiProvider>) -> Self {
         let mut agent = Self {
-            ollama: Ollama::default(),
+            provider,
+            task_models: HashMap::new(),
             context: AiContext::default(),
             conversation_history: Vec::new(),
             specialized_prompts: HashMap::new(),
@@ -185,15 +224,95 @@ impl AiAgent {
         );
     }
 
+    /// Switch to another model backend
+    pub fn set_provider(&mut self, provider: Arc<dyn AiProvider>) {
+        self.provider = provider;
+    }
+
+    /// Backend requests are sent to
+    pub fn provider(&self) -> &dyn AiProvider {
+        self.provider.as_ref()
+    }
+
+    /// Run `task_type` requests on `model` instead of the provider's default model
+    pub fn set_task_model(&mut self, task_type: AiTaskType, model: &str) {
+        if model.is_empty() {
+            self.task_models.remove(&task_type);
+        } else {
+            self.task_models.insert(task_type, model.to_string());
+        }
+    }
+
+    /// Model chosen for `task_type`, if any
+    pub fn task_model(&self, task_type: &AiTaskType) -> Option<&str> {
+        self.task_models.get(task_type).map(String::as_str)
+    }
+
+    /// Model `task_type` requests run on
+    pub fn model_for(&self, task_type: &AiTaskType) -> &str {
+        self.task_model(task_type).unwrap_or_else(|| self.provider.default_model())
+    }
+
     /// Enhanced ask method with context and task-specific handling
     pub async fn ask_with_context(&mut self, prompt: &str, task_type: AiTaskType) -> anyhow::Result<String> {
-        let enhanced_prompt = self.build_contextual_prompt(prompt, &task_type);
-        let response = self.ask(&enhanced_prompt).await?;
-        
-        // Store conversation for context
+        let request = self.build_request(prompt, &task_type);
+        let response = self.provider.complete(&request).await?;
+        self.record_conversation(prompt, &response, task_type);
+        Ok(response)
+    }
+
+    /// Start a contextual request whose response streams in token by token
+    ///
+    /// Poll the exchange with [`AiAgent::poll_exchange`] so the finished
+    /// response joins the conversation history.
+    pub fn stream_with_context(&self, prompt: &str, task_type: AiTaskType) -> AiExchange {
+        let request = self.build_request(prompt, &task_type);
+        AiExchange {
+            user_input: prompt.to_string(),
+            task_type,
+            expects_edits: false,
+            stream: AiStream::start(Arc::clone(&self.provider), request),
+        }
+    }
+
+    /// Ask for structured edits to `files` instead of code in chat text
+    ///
+    /// `files` are workspace-relative paths with their current text. The
+    /// edits still need validating against the buffers before they are shown.
+    pub async fn request_edits(&mut self, instruction: &str, files: &[(String, String)], task_type: AiTaskType) -> anyhow::Result<EditResponse> {
+        let request = self.build_edit_request(instruction, files, &task_type);
+        let response = self.provider.complete(&request).await?;
+        self.record_conversation(instruction, &response, task_type);
+        Ok(parse_edits(&response)?)
+    }
+
+    /// Start a streamed edit request; the finished response is parsed with [`parse_edits`]
+    pub fn stream_edits(&self, instruction: &str, files: &[(String, String)], task_type: AiTaskType) -> AiExchange {
+        let request = self.build_edit_request(instruction, files, &task_type);
+        AiExchange {
+            user_input: instruction.to_string(),
+            task_type,
+            expects_edits: true,
+            stream: AiStream::start(Arc::clone(&self.provider), request),
+        }
+    }
+
+    /// Collect the events of a streamed exchange, recording it once it finishes
+    pub fn poll_exchange(&mut self, exchange: &mut AiExchange) -> Vec<AiStreamEvent> {
+        let events = exchange.stream.poll_events();
+        for event in &events {
+            if let AiStreamEvent::Finished(response) = event {
+                self.record_conversation(&exchange.user_input, response, exchange.task_type.clone());
+            }
+        }
+        events
+    }
+
+    /// Store a completed exchange for context in later prompts
+    fn record_conversation(&mut self, prompt: &str, response: &str, task_type: AiTaskType) {
         self.conversation_history.push(AiConversation {
             user_input: prompt.to_string(),
-            ai_response: response.clone(),
+            ai_response: response.to_string(),
             task_type,
             timestamp: std::time::SystemTime::now(),
         });
@@ -202,8 +321,27 @@ impl AiAgent {
         if self.conversation_history.len() > 10 {
             self.conversation_history.remove(0);
         }
+    }
 
-        Ok(response)
+    /// Build a request for edits: the task's system prompt plus the edit
+    /// format, and the files with numbered lines
+    fn build_edit_request(&self, instruction: &str, files: &[(String, String)], task_type: &AiTaskType) -> CompletionRequest {
+        let system = match self.specialized_prompts.get(task_type) {
+            Some(system_prompt) => format!("{}\n\n{}", system_prompt, EDIT_FORMAT),
+            None => EDIT_FORMAT.to_string(),
+        };
+        CompletionRequest::new(self.model_for(task_type), edit_prompt(instruction, files)).with_system(system)
+    }
+
+    /// Build the provider request for a task: its model, its specialized
+    /// system prompt and the contextual prompt
+    fn build_request(&self, prompt: &str, task_type: &AiTaskType) -> CompletionRequest {
+        CompletionRequest {
+            model: self.model_for(task_type).to_string(),
+            system: self.specialized_prompts.get(task_type).cloned(),
+            prompt: self.build_contextual_prompt(prompt),
+            ..Default::default()
+        }
     }
 
     /// Build a contextual prompt with project information
@@ -212,16 +350,12 @@ impl AiAgent {
     /// effectiveness by providing comprehensive project state, error context, and conversation
     /// history. The prompt engineering follows best practices for getting high-quality responses
     /// from language models while maintaining conversation continuity and relevance.
-    fn build_contextual_prompt(&self, user_prompt: &str, task_type: &AiTaskType) -> String {
+    ///
+    /// The task-specific system prompt travels separately as the request's
+    /// system instructions, see [`AiAgent::build_request`].
+    fn build_contextual_prompt(&self, user_prompt: &str) -> String {
         let mut prompt = String::new();
 
-        // System Role and Task-Specific Instructions
-        // Each task type gets a specialized system prompt to prime the AI with appropriate
-        // knowledge, perspective, and response style for that specific type of request
-        if let Some(system_prompt) = self.specialized_prompts.get(task_type) {
-            prompt.push_str(&format!("System: {}\n\n", system_prompt));
-        }
-
         // Project Metadata Section
         // Provides essential project context that helps the AI understand the environment
         // and constraints it's working within for more targeted suggestions
@@ -284,9 +418,8 @@ impl AiAgent {
 
     /// Original ask method for backward compatibility
     pub async fn ask(&self, prompt: &str) -> anyhow::Result<String> {
-        let req = GenerationRequest::new("llama2".to_string(), prompt);
-        let resp = self.ollama.generate(req).await?;
-        Ok(resp.response)
+        let request = CompletionRequest::new(self.provider.default_model(), prompt);
+        Ok(self.provider.complete(&request).await?)
     }
 
     /// Generate Rust code with specific requirements
@@ -310,6 +443,27 @@ impl AiAgent {
         self.ask_with_context(&prompt, AiTaskType::BugFixing).await
     }
 
+    /// Propose edits to `file` that fix `error`
+    pub async fn propose_bug_fix(&mut self, file: &str, code: &str, error: &str) -> anyhow::Result<EditResponse> {
+        let instruction = format!("Fix this Rust error with minimal edits:\n{}", error);
+        self.request_edits(&instruction, &[(file.to_string(), code.to_string())], AiTaskType::BugFixing).await
+    }
+
+    /// Propose edits to `file` that implement `description`
+    pub async fn propose_rust_code(&mut self, file: &str, code: &str, description: &str, requirements: &[String]) -> anyhow::Result<EditResponse> {
+        let mut instruction = format!("Implement in {}: {}\n", file, description);
+        for req in requirements {
+            instruction.push_str(&format!("- {}\n", req));
+        }
+        self.request_edits(&instruction, &[(file.to_string(), code.to_string())], AiTaskType::CodeGeneration).await
+    }
+
+    /// Propose edits that add tests for the code in `file`
+    pub async fn propose_tests(&mut self, file: &str, code: &str) -> anyhow::Result<EditResponse> {
+        let instruction = format!("Add unit tests covering the code in {}, including edge cases.", file);
+        self.request_edits(&instruction, &[(file.to_string(), code.to_string())], AiTaskType::Testing).await
+    }
+
     /// Review code for improvements
     pub async fn review_code(&mut self, code: &str) -> anyhow::Result<String> {
         let prompt = format!(
@@ -0,0 +1,560 @@
+//! # AI Providers - Model Backends for the AI Agent
+//!
+//! The AI agent talks to language models through the [`AiProvider`] trait so
+//! the backend can be swapped without touching prompt handling. Two
+//! implementations speak HTTP to locally hosted servers:
+//!
+//! - [`OllamaProvider`]: Ollama's native `/api/generate` endpoint
+//! - [`OpenAiCompatibleProvider`]: the `/v1/chat/completions` endpoint served by
+//!   llama.cpp, vLLM, LM Studio and other OpenAI-compatible servers
+//!
+//! Responses are streamed token by token. [`AiStream`] runs a request on a
+//! background thread so the egui panel can poll tokens once per frame and
+//! cancel the request at any time; [`ModelListing`] fetches the server's
+//! models the same way for the provider settings.
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::future::Future;
+use std::thread;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Default address of a local Ollama server
+pub const OLLAMA_URL: &str = "http://localhost:11434";
+/// Default address of a local llama.cpp server
+pub const OPENAI_COMPATIBLE_URL: &str = "http://localhost:8080/v1";
+
+/// Time allowed for connecting to a provider
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A single completion request
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompletionRequest {
+    /// Model to run, empty for the provider's default model
+    pub model: String,
+    /// System instructions sent ahead of the prompt
+    pub system: Option<String>,
+    /// User prompt
+    pub prompt: String,
+    /// Sampling temperature, `None` for the server default
+    pub temperature: Option<f32>,
+    /// Maximum number of tokens to generate, `None` for the server default
+    pub max_tokens: Option<u32>,
+}
+
+impl CompletionRequest {
+    /// Creates a request for `prompt` on `model`
+    pub fn new(model: impl Into<String>, prompt: impl Into<String>) -> Self {
+        Self { model: model.into(), prompt: prompt.into(), ..Default::default() }
+    }
+
+    /// Adds system instructions
+    pub fn with_system(mut self, system: impl Into<String>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+}
+
+/// Errors reported by AI providers
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum AiError {
+    /// The server could not be reached or the connection dropped
+    #[error("Cannot reach {url}: {message}")]
+    Connection { url: String, message: String },
+    /// The server answered with an error status
+    #[error("Server returned HTTP {status}: {message}")]
+    Status { status: u16, message: String },
+    /// The server reported an error in the middle of a response
+    #[error("Server error: {0}")]
+    Server(String),
+    /// The response could not be understood
+    #[error("Malformed response: {0}")]
+    Protocol(String),
+    /// The request was cancelled before it finished
+    #[error("Request cancelled")]
+    Cancelled,
+}
+
+/// A language model backend
+#[async_trait]
+pub trait AiProvider: Send + Sync {
+    /// Display name of the backend
+    fn name(&self) -> &str;
+
+    /// Model used for requests that do not name one
+    fn default_model(&self) -> &str;
+
+    /// Address requests are sent to
+    fn base_url(&self) -> &str;
+
+    /// Lists the models the server can run
+    async fn list_models(&self) -> Result<Vec<String>, AiError>;
+
+    /// Runs `request`, passing each token to `on_token` as it arrives
+    ///
+    /// Returns the complete response text. Cancelling `cancel` stops the
+    /// request and returns [`AiError::Cancelled`].
+    async fn stream(
+        &self,
+        request: &CompletionRequest,
+        on_token: &mut (dyn for<'t> FnMut(&'t str) + Send),
+        cancel: &CancellationToken,
+    ) -> Result<String, AiError>;
+
+    /// Runs `request` and returns the complete response text
+    async fn complete(&self, request: &CompletionRequest) -> Result<String, AiError> {
+        self.stream(request, &mut |_: &str| {}, &CancellationToken::new()).await
+    }
+}
+
+/// Provider for Ollama's native API
+pub struct OllamaProvider {
+    base_url: String,
+    model: String,
+    client: reqwest::Client,
+}
+
+impl OllamaProvider {
+    /// Creates a provider for the server at `base_url` using `model` by default
+    pub fn new(base_url: &str, model: &str) -> Self {
+        Self { base_url: base_url.trim_end_matches('/').to_string(), model: model.to_string(), client: http_client() }
+    }
+}
+
+impl Default for OllamaProvider {
+    fn default() -> Self {
+        Self::new(OLLAMA_URL, "llama2")
+    }
+}
+
+#[async_trait]
+impl AiProvider for OllamaProvider {
+    fn name(&self) -> &str {
+        "Ollama"
+    }
+
+    fn default_model(&self) -> &str {
+        &self.model
+    }
+
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, AiError> {
+        let url = format!("{}/api/tags", self.base_url);
+        let tags = fetch_json(self.client.get(&url), &url).await?;
+        Ok(names(&tags["models"], "name"))
+    }
+
+    async fn stream(
+        &self,
+        request: &CompletionRequest,
+        on_token: &mut (dyn for<'t> FnMut(&'t str) + Send),
+        cancel: &CancellationToken,
+    ) -> Result<String, AiError> {
+        let url = format!("{}/api/generate", self.base_url);
+        let mut body = json!({
+            "model": model_or(&request.model, &self.model),
+            "prompt": request.prompt,
+            "stream": true,
+        });
+        if let Some(system) = &request.system {
+            body["system"] = json!(system);
+        }
+        if let Some(temperature) = request.temperature {
+            body["options"]["temperature"] = json!(temperature);
+        }
+        if let Some(max_tokens) = request.max_tokens {
+            body["options"]["num_predict"] = json!(max_tokens);
+        }
+
+        let response = send(self.client.post(&url).json(&body), &url, cancel).await?;
+        let mut text = String::new();
+        // Each line is a JSON object carrying the next piece of the response
+        read_lines(response, &url, cancel, |line| {
+            if line.is_empty() {
+                return Ok(true);
+            }
+            let chunk = parse_json(line)?;
+            if let Some(error) = chunk["error"].as_str() {
+                return Err(AiError::Server(error.to_string()));
+            }
+            if let Some(token) = chunk["response"].as_str().filter(|token| !token.is_empty()) {
+                on_token(token);
+                text.push_str(token);
+            }
+            Ok(!chunk["done"].as_bool().unwrap_or(false))
+        })
+        .await?;
+        Ok(text)
+    }
+}
+
+/// Provider for servers implementing the OpenAI chat completions API
+pub struct OpenAiCompatibleProvider {
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+    client: reqwest::Client,
+}
+
+impl OpenAiCompatibleProvider {
+    /// Creates a provider for the server at `base_url` using `model` by default
+    ///
+    /// The `/v1` suffix is added to `base_url` when it is missing.
+    pub fn new(base_url: &str, model: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        let base_url = if base_url.ends_with("/v1") { base_url.to_string() } else { format!("{}/v1", base_url) };
+        Self { base_url, model: model.to_string(), api_key: None, client: http_client() }
+    }
+
+    /// Sends `api_key` as a bearer token with every request
+    pub fn with_api_key(mut self, api_key: &str) -> Self {
+        self.api_key = Some(api_key.to_string()).filter(|key| !key.is_empty());
+        self
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        }
+    }
+}
+
+#[async_trait]
+impl AiProvider for OpenAiCompatibleProvider {
+    fn name(&self) -> &str {
+        "OpenAI-compatible"
+    }
+
+    fn default_model(&self) -> &str {
+        &self.model
+    }
+
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, AiError> {
+        let url = format!("{}/models", self.base_url);
+        let models = fetch_json(self.authorize(self.client.get(&url)), &url).await?;
+        Ok(names(&models["data"], "id"))
+    }
+
+    async fn stream(
+        &self,
+        request: &CompletionRequest,
+        on_token: &mut (dyn for<'t> FnMut(&'t str) + Send),
+        cancel: &CancellationToken,
+    ) -> Result<String, AiError> {
+        let url = format!("{}/chat/completions", self.base_url);
+        let mut messages = Vec::new();
+        if let Some(system) = &request.system {
+            messages.push(json!({"role": "system", "content": system}));
+        }
+        messages.push(json!({"role": "user", "content": request.prompt}));
+        let mut body = json!({
+            "model": model_or(&request.model, &self.model),
+            "messages": messages,
+            "stream": true,
+        });
+        if let Some(temperature) = request.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        if let Some(max_tokens) = request.max_tokens {
+            body["max_tokens"] = json!(max_tokens);
+        }
+
+        let response = send(self.authorize(self.client.post(&url).json(&body)), &url, cancel).await?;
+        let mut text = String::new();
+        // Server-sent events; comments and other fields are ignored
+        read_lines(response, &url, cancel, |line| {
+            let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+                return Ok(true);
+            };
+            if data == "[DONE]" {
+                return Ok(false);
+            }
+            let chunk = parse_json(data)?;
+            if !chunk["error"].is_null() {
+                return Err(AiError::Server(error_text(&chunk["error"])));
+            }
+            if let Some(token) = chunk["choices"][0]["delta"]["content"].as_str().filter(|token| !token.is_empty()) {
+                on_token(token);
+                text.push_str(token);
+            }
+            Ok(true)
+        })
+        .await?;
+        Ok(text)
+    }
+}
+
+/// Backends selectable in the AI panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    Ollama,
+    OpenAiCompatible,
+}
+
+impl ProviderKind {
+    /// Every backend, in display order
+    pub const ALL: [ProviderKind; 2] = [ProviderKind::Ollama, ProviderKind::OpenAiCompatible];
+
+    /// Display name
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProviderKind::Ollama => "Ollama",
+            ProviderKind::OpenAiCompatible => "OpenAI-compatible (llama.cpp, vLLM, LM Studio)",
+        }
+    }
+
+    /// Address the backend usually listens on
+    pub fn default_url(&self) -> &'static str {
+        match self {
+            ProviderKind::Ollama => OLLAMA_URL,
+            ProviderKind::OpenAiCompatible => OPENAI_COMPATIBLE_URL,
+        }
+    }
+}
+
+/// User-editable provider configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct AiProviderSettings {
+    pub kind: ProviderKind,
+    pub base_url: String,
+    /// Bearer token, empty for none; Ollama ignores it
+    pub api_key: String,
+    pub default_model: String,
+}
+
+impl Default for AiProviderSettings {
+    fn default() -> Self {
+        Self {
+            kind: ProviderKind::Ollama,
+            base_url: OLLAMA_URL.to_string(),
+            api_key: String::new(),
+            default_model: "llama2".to_string(),
+        }
+    }
+}
+
+impl AiProviderSettings {
+    /// Creates the configured provider
+    pub fn build(&self) -> Arc<dyn AiProvider> {
+        match self.kind {
+            ProviderKind::Ollama => Arc::new(OllamaProvider::new(&self.base_url, &self.default_model)),
+            ProviderKind::OpenAiCompatible => {
+                Arc::new(OpenAiCompatibleProvider::new(&self.base_url, &self.default_model).with_api_key(&self.api_key))
+            }
+        }
+    }
+}
+
+/// Progress of a streamed request
+#[derive(Debug, Clone, PartialEq)]
+pub enum AiStreamEvent {
+    /// The next piece of the response
+    Token(String),
+    /// The response is complete
+    Finished(String),
+    /// The request failed or was cancelled
+    Failed(AiError),
+}
+
+/// A request running on a background thread
+///
+/// Dropping the stream cancels the request.
+pub struct AiStream {
+    receiver: Receiver<AiStreamEvent>,
+    cancel: CancellationToken,
+    finished: bool,
+}
+
+impl AiStream {
+    /// Starts running `request` on `provider`
+    pub fn start(provider: Arc<dyn AiProvider>, request: CompletionRequest) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = CancellationToken::new();
+        let token = cancel.clone();
+        let tokens = sender.clone();
+        let worker = sender.clone();
+        let task = async move {
+            let mut on_token = |piece: &str| {
+                let _ = tokens.send(AiStreamEvent::Token(piece.to_string()));
+            };
+            provider.stream(&request, &mut on_token, &token).await
+        };
+        let spawned = spawn_task("ai-stream", task, move |result| {
+            let _ = worker.send(match result {
+                Ok(text) => AiStreamEvent::Finished(text),
+                Err(error) => AiStreamEvent::Failed(error),
+            });
+        });
+        if let Err(error) = spawned {
+            let _ = sender.send(AiStreamEvent::Failed(error));
+        }
+        Self { receiver, cancel, finished: false }
+    }
+
+    /// Returns the events received since the last call, oldest first
+    ///
+    /// Intended to be called once per frame from the UI thread.
+    pub fn poll_events(&mut self) -> Vec<AiStreamEvent> {
+        let events: Vec<_> = self.receiver.try_iter().collect();
+        events.iter().for_each(|event| self.observe(event));
+        events
+    }
+
+    /// Whether the request finished, failed or was cancelled
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Stops the request; a [`AiError::Cancelled`] failure follows
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    fn observe(&mut self, event: &AiStreamEvent) {
+        if !matches!(event, AiStreamEvent::Token(_)) {
+            self.finished = true;
+        }
+    }
+}
+
+impl Drop for AiStream {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+/// A model list request running on a background thread
+pub struct ModelListing {
+    receiver: Receiver<Result<Vec<String>, AiError>>,
+}
+
+impl ModelListing {
+    /// Starts listing the models `provider` can run
+    pub fn start(provider: Arc<dyn AiProvider>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let worker = sender.clone();
+        let task = async move { provider.list_models().await };
+        if let Err(error) = spawn_task("ai-models", task, move |result| {
+            let _ = worker.send(result);
+        }) {
+            let _ = sender.send(Err(error));
+        }
+        Self { receiver }
+    }
+
+    /// Returns the result once the server has answered
+    pub fn poll(&self) -> Option<Result<Vec<String>, AiError>> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+/// Runs `task` on a new thread with its own runtime and passes the result to `done`
+fn spawn_task<T, F>(name: &str, task: F, done: impl FnOnce(Result<T, AiError>) + Send + 'static) -> Result<(), AiError>
+where
+    F: Future<Output = Result<T, AiError>> + Send + 'static,
+{
+    thread::Builder::new()
+        .name(name.to_string())
+        .spawn(move || {
+            let result = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                Ok(runtime) => runtime.block_on(task),
+                Err(e) => Err(AiError::Server(format!("Cannot start AI runtime: {}", e))),
+            };
+            done(result);
+        })
+        .map(|_| ())
+        .map_err(|e| AiError::Server(format!("Cannot start AI worker: {}", e)))
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder().connect_timeout(CONNECT_TIMEOUT).build().unwrap_or_default()
+}
+
+fn model_or<'a>(model: &'a str, default: &'a str) -> &'a str {
+    if model.is_empty() { default } else { model }
+}
+
+fn connection_error(url: &str, error: reqwest::Error) -> AiError {
+    AiError::Connection { url: url.to_string(), message: error.to_string() }
+}
+
+fn parse_json(text: &str) -> Result<Value, AiError> {
+    serde_json::from_str(text).map_err(|e| AiError::Protocol(format!("{} in '{}'", e, text)))
+}
+
+/// Message of an `error` field, either a string or an object with a message
+fn error_text(error: &Value) -> String {
+    error.as_str().or_else(|| error["message"].as_str()).map(str::to_string).unwrap_or_else(|| error.to_string())
+}
+
+/// Values of `field` in each entry of a JSON array
+fn names(entries: &Value, field: &str) -> Vec<String> {
+    entries.as_array().into_iter().flatten().filter_map(|entry| entry[field].as_str()).map(str::to_string).collect()
+}
+
+/// Sends `request`, turning error statuses into [`AiError::Status`]
+async fn send(request: reqwest::RequestBuilder, url: &str, cancel: &CancellationToken) -> Result<reqwest::Response, AiError> {
+    let response = tokio::select! {
+        _ = cancel.cancelled() => return Err(AiError::Cancelled),
+        response = request.send() => response.map_err(|e| connection_error(url, e))?,
+    };
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<Value>(&body)
+        .ok()
+        .filter(|value| !value["error"].is_null())
+        .map(|value| error_text(&value["error"]))
+        .unwrap_or_else(|| body.trim().to_string());
+    Err(AiError::Status { status: status.as_u16(), message })
+}
+
+async fn fetch_json(request: reqwest::RequestBuilder, url: &str) -> Result<Value, AiError> {
+    let response = send(request, url, &CancellationToken::new()).await?;
+    let body = response.text().await.map_err(|e| connection_error(url, e))?;
+    parse_json(&body)
+}
+
+/// Passes each trimmed line of a streamed body to `on_line` until it returns
+/// `false` or the body ends
+async fn read_lines(
+    mut response: reqwest::Response,
+    url: &str,
+    cancel: &CancellationToken,
+    mut on_line: impl FnMut(&str) -> Result<bool, AiError>,
+) -> Result<(), AiError> {
+    let mut pending = Vec::new();
+    loop {
+        let chunk = tokio::select! {
+            _ = cancel.cancelled() => return Err(AiError::Cancelled),
+            chunk = response.chunk() => chunk.map_err(|e| connection_error(url, e))?,
+        };
+        let Some(chunk) = chunk else { break };
+        pending.extend_from_slice(&chunk);
+        // Lines are decoded whole so multi-byte characters split across chunks survive
+        while let Some(end) = pending.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = pending.drain(..=end).collect();
+            if !on_line(String::from_utf8_lossy(&line).trim())? {
+                return Ok(());
+            }
+        }
+    }
+    let rest = String::from_utf8_lossy(&pending);
+    if !rest.trim().is_empty() {
+        on_line(rest.trim())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,43 @@
+//! Collaboration relay for the Rust RAD IDE
+//!
+//! Lets IDE instances on the same network edit documents together. Run it on
+//! one machine and point the editors at its address:
+//!
+//! ```text
+//! collab-relay [--listen <address>]
+//! ```
+
+use ide_rs::editor::collaboration::relay::{RelayServer, DEFAULT_RELAY_PORT};
+
+fn main() {
+    let mut address = format!("0.0.0.0:{}", DEFAULT_RELAY_PORT);
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--listen" | "-l" => match args.next() {
+                Some(value) => address = value,
+                None => exit_with("--listen needs an address"),
+            },
+            "--help" | "-h" => {
+                println!("Usage: collab-relay [--listen <address>]");
+                println!("Default address: {}", address);
+                return;
+            }
+            other => exit_with(&format!("Unknown argument: {}", other)),
+        }
+    }
+
+    let relay = RelayServer::bind(&address).unwrap_or_else(|e| exit_with(&e));
+    match relay.local_addr() {
+        Ok(addr) => println!("Collaboration relay listening on {}", addr),
+        Err(_) => println!("Collaboration relay listening on {}", address),
+    }
+    if let Err(e) = relay.run() {
+        exit_with(&e);
+    }
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("collab-relay: {}", message);
+    std::process::exit(1);
+}
@@ -0,0 +1,506 @@
+//! Headless command-line mode
+//!
+//! `ide-rs <command>` runs IDE operations without opening a window so they
+//! can be used from scripts and CI:
+//!
+//! ```text
+//! ide-rs [--json] codegen <project> [--output <file>]
+//! ide-rs [--json] export --format <format> <form.json> [--output <file>]
+//! ide-rs [--json] validate <project>
+//! ide-rs [--json] new --template <id> [--name <name>] [--path <dir>] [--author <name>]
+//!                     [--description <text>] [--var <name>=<value>]... [--no-git]
+//! ide-rs [--json] package <component-dir> [--output <dir>]
+//! ```
+//!
+//! The process exits with [`EXIT_SUCCESS`] when the command succeeded,
+//! [`EXIT_FAILURE`] when it failed (including projects that do not
+//! validate) and [`EXIT_USAGE`] for invalid arguments. With `--json`,
+//! exactly one JSON object is written to stdout, carrying `command`,
+//! `success` and either the command's results or an `error` message.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+
+use crate::editor::code_generator::CodeGenerator;
+use crate::editor::form_events::{
+    declare_form_module, dispatch_code, ensure_handler, form_source_path, form_type_name, to_pascal_case, to_snake_case,
+    EventBinding, HANDLER_PROPERTY_PREFIX,
+};
+use crate::editor::layout_engine::{egui_placement_code, layout_rects_code, LayoutNode};
+use crate::editor::output_panel::OutputPanel;
+use crate::editor::packaging::{package_component, PackageArchive};
+use crate::editor::project_manager::project::ProjectType;
+use crate::editor::project_manager::{IdeProject, ProjectOperations, ProjectSerializer};
+use crate::editor::project_scaffolding::{ProjectConfiguration, ProjectScaffoldingEngine, TemplateCategory};
+use crate::editor::scene_store::LayoutType;
+use crate::rcl::ui::basic::form::Form;
+use crate::shared::serialization::{ExportFormat, SerializationUtils};
+
+/// Exit code for a successful command
+pub const EXIT_SUCCESS: i32 = 0;
+/// Exit code for a command that ran but failed
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code for invalid command-line arguments
+pub const EXIT_USAGE: i32 = 2;
+
+const USAGE: &str = "\
+Usage: ide-rs [--json] <command> [options]
+
+Commands:
+  codegen <project> [--output <file>]               Generate the form code for a project
+  export --format <format> <form.json> [--output <file>]
+                                                    Export a form (native, react, vue, angular, html, svg, png)
+  validate <project>                                Check a project for errors
+  new --template <id> [--name <name>] [--path <dir>] [--author <name>]
+      [--description <text>] [--var <name>=<value>]... [--no-git]
+                                                    Create a project from a template
+  package <component-dir> [--output <dir>]          Build a component package archive
+
+Run without arguments to start the IDE.";
+
+/// Why a command did not succeed
+#[derive(Debug)]
+enum CliError {
+    /// The arguments were invalid
+    Usage(String),
+    /// The command ran and failed
+    Failed(String),
+}
+
+/// Result of a command that ran to completion
+struct Report {
+    /// Whether the command achieved what was asked
+    success: bool,
+    /// Human readable output, one entry per line
+    lines: Vec<String>,
+    /// Fields for the JSON output
+    data: Map<String, Value>,
+}
+
+impl Report {
+    fn new(success: bool) -> Self {
+        Self { success, lines: Vec::new(), data: Map::new() }
+    }
+
+    fn line(mut self, line: impl Into<String>) -> Self {
+        self.lines.push(line.into());
+        self
+    }
+
+    fn field(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.data.insert(name.to_string(), value.into());
+        self
+    }
+}
+
+/// Run a headless command and return the process exit code
+///
+/// `args` are the command-line arguments without the program name. Results
+/// go to `out` and diagnostics to `err`; with `--json` everything is written
+/// to `out` as a single JSON object. `--json` is a global flag and only
+/// recognized before the command, so it can still be an option's value.
+pub fn run(args: &[String], out: &mut impl Write, err: &mut impl Write) -> i32 {
+    let json_output = args.first().is_some_and(|arg| arg == "--json");
+    let args: Vec<&str> = args.iter().skip(usize::from(json_output)).map(String::as_str).collect();
+
+    let Some((&command, rest)) = args.split_first() else {
+        return finish("", Err(CliError::Usage("No command given".to_string())), json_output, out, err);
+    };
+    let result = match command {
+        "--help" | "-h" | "help" => {
+            let _ = writeln!(out, "{}", USAGE);
+            return EXIT_SUCCESS;
+        }
+        "codegen" => codegen(rest),
+        "export" => export(rest),
+        "validate" => validate(rest),
+        "new" => new_project(rest),
+        "package" => package(rest),
+        other => Err(CliError::Usage(format!("Unknown command '{}'", other))),
+    };
+    finish(command, result, json_output, out, err)
+}
+
+/// Write the outcome of a command and pick the exit code
+fn finish(command: &str, result: Result<Report, CliError>, json_output: bool, out: &mut impl Write, err: &mut impl Write) -> i32 {
+    let (code, report) = match result {
+        Ok(report) => (if report.success { EXIT_SUCCESS } else { EXIT_FAILURE }, Ok(report)),
+        Err(CliError::Usage(message)) => (EXIT_USAGE, Err(message)),
+        Err(CliError::Failed(message)) => (EXIT_FAILURE, Err(message)),
+    };
+
+    if json_output {
+        let mut document = Map::new();
+        document.insert("command".to_string(), Value::from(command));
+        document.insert("success".to_string(), Value::from(code == EXIT_SUCCESS));
+        match report {
+            Ok(report) => document.extend(report.data),
+            Err(message) => {
+                document.insert("error".to_string(), Value::from(message));
+            }
+        }
+        let _ = writeln!(out, "{}", Value::Object(document));
+    } else {
+        match report {
+            Ok(report) => {
+                for line in report.lines {
+                    let _ = writeln!(out, "{}", line);
+                }
+            }
+            Err(message) => {
+                let _ = writeln!(err, "ide-rs: {}", message);
+                if code == EXIT_USAGE {
+                    let _ = writeln!(err, "Run 'ide-rs --help' for usage.");
+                }
+            }
+        }
+    }
+    code
+}
+
+/// Option values given on the command line, keyed by option name
+type Options<'a> = HashMap<String, Vec<&'a str>>;
+
+/// Split arguments into positionals and `--name value` options
+///
+/// `flags` lists the options that take no value.
+fn parse_options<'a>(args: &[&'a str], options: &[&str], flags: &[&str]) -> Result<(Vec<&'a str>, Options<'a>), CliError> {
+    let mut positionals = Vec::new();
+    let mut values = Options::new();
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        if flags.contains(&arg) {
+            values.entry(arg.to_string()).or_default();
+        } else if options.contains(&arg) {
+            let value = args.next().ok_or_else(|| CliError::Usage(format!("{} needs a value", arg)))?;
+            values.entry(arg.to_string()).or_default().push(value);
+        } else if arg.starts_with("--") {
+            return Err(CliError::Usage(format!("Unknown option '{}'", arg)));
+        } else {
+            positionals.push(arg);
+        }
+    }
+    Ok((positionals, values))
+}
+
+/// Get the single positional argument of a command
+fn single_positional<'a>(positionals: &[&'a str], what: &str) -> Result<&'a str, CliError> {
+    match positionals {
+        [value] => Ok(value),
+        [] => Err(CliError::Usage(format!("Missing {}", what))),
+        _ => Err(CliError::Usage(format!("Expected a single {}", what))),
+    }
+}
+
+/// Get the last value given for an option
+fn option<'a>(values: &Options<'a>, name: &str) -> Option<&'a str> {
+    values.get(name).and_then(|values| values.last().copied())
+}
+
+fn load_project(path: &Path) -> Result<IdeProject, CliError> {
+    ProjectSerializer::new()
+        .load_project(path, &mut OutputPanel::new())
+        .map_err(|e| CliError::Failed(format!("Cannot load project {}: {}", path.display(), e)))
+}
+
+/// `codegen <project>`: generate the form code for a project's designer components
+fn codegen(args: &[&str]) -> Result<Report, CliError> {
+    let (positionals, options) = parse_options(args, &["--output"], &[])?;
+    let project_path = PathBuf::from(single_positional(&positionals, "project directory")?);
+    let project = load_project(&project_path)?;
+
+    let output = match option(&options, "--output") {
+        Some(output) => PathBuf::from(output),
+        None => form_source_path(&project_path, &project.metadata.name),
+    };
+
+    let mut components = project.designer_data.components.clone();
+    components.sort_by_key(|component| component.z_order);
+    let mut modules = Vec::new();
+    let mut fields = Vec::new();
+    let mut init = Vec::new();
+    let mut properties = Vec::new();
+    for component in &components {
+        let (module, type_name, constructor) = rcl_component(&component.component_type).ok_or_else(|| CliError::Failed(format!(
+            "Component {} has type '{}', which has no RCL counterpart",
+            component.id, component.component_type
+        )))?;
+        let field = to_snake_case(&component.id);
+        modules.push(format!("{}::{}", module, type_name));
+        fields.push(format!("    pub {}: {},", field, type_name));
+        init.push(format!("            {}: {},", field, constructor));
+        let mut values: Vec<(&String, &String)> = component.properties.iter()
+            .filter(|(name, _)| !name.starts_with(HANDLER_PROPERTY_PREFIX))
+            .collect();
+        values.sort();
+        properties.extend(values.into_iter()
+            .map(|(name, value)| format!("        form.{}.set_property({:?}, {:?});", field, name, value)));
+    }
+    modules.sort();
+    modules.dedup();
+    // Flowing layouts keep the configured padding from the form's edges
+    let layout = LayoutNode::from_designer(&project.designer_data);
+    let padding = if layout.layout == LayoutType::Absolute { 0.0 } else { project.designer_data.layout_config.padding };
+    // Rects are solved for a form of the size the designer canvas shows
+    let form_size = Form::new(String::new()).size();
+    let imports: Vec<String> = std::iter::once("use ide_rs::rcl::ui::component::Component;".to_string())
+        .chain(modules.iter().map(|module| format!("use ide_rs::rcl::ui::basic::{};", module)))
+        .collect();
+    let bindings: Vec<EventBinding> = components.iter()
+        .flat_map(|component| EventBinding::from_properties(&component.id, &component.properties))
+        .collect();
+    let form_name = form_type_name(&project.metadata.name);
+
+    let template = CodeGenerator::create_form_template();
+    let template_id = template.template_id.clone();
+    let mut generator = CodeGenerator::new(output.parent().map(Path::to_path_buf).unwrap_or_default());
+    generator.register_template(template);
+
+    let variables = HashMap::from([
+        ("form_name".to_string(), json!(form_name)),
+        ("component_imports".to_string(), json!(imports.join("\n"))),
+        ("component_fields".to_string(), json!(fields.join("\n"))),
+        ("component_init".to_string(), json!(init.join("\n"))),
+        ("component_properties".to_string(), json!(properties.join("\n"))),
+        ("component_layout".to_string(), json!(layout_rects_code(&layout, form_size, padding, 8))),
+        ("component_render".to_string(), json!(indent(&egui_placement_code(&layout), 8))),
+        ("event_dispatch".to_string(), json!(dispatch_code(&bindings))),
+    ]);
+    generator.load_snapshot(&output)
+        .map_err(|e| CliError::Failed(format!("Cannot read the snapshot of {}: {}", output.display(), e)))?;
+    let regeneration = generator.regenerate(&template_id, variables, output.clone(), None)
+        .map_err(|e| CliError::Failed(e.to_string()))?;
+    generator.commit_regeneration(&regeneration);
+
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| CliError::Failed(format!("Cannot create {}: {}", parent.display(), e)))?;
+    }
+    let save_snapshot = |generator: &CodeGenerator| generator.save_snapshot(&output)
+        .map_err(|e| CliError::Failed(format!("Cannot save the snapshot of {}: {}", output.display(), e)));
+
+    // Conflicting edits are written out with markers for the user to resolve
+    let Some(mut code) = regeneration.merged_code() else {
+        std::fs::write(&output, regeneration.merge.text_with_markers())
+            .map_err(|e| CliError::Failed(format!("Cannot write {}: {}", output.display(), e)))?;
+        save_snapshot(&generator)?;
+        return Err(CliError::Failed(format!(
+            "{} merge conflict(s) between your edits and the designer in {}; resolve the conflict markers and run codegen again",
+            regeneration.merge.conflict_count(),
+            output.display()
+        )));
+    };
+
+    // Handler methods live in a guard the template leaves empty
+    for binding in &bindings {
+        code = ensure_handler(&code, &form_name, &binding.handler)
+            .map_err(|e| CliError::Failed(format!("Cannot add handler for {}.{}: {}", binding.component, binding.event, e)))?
+            .0;
+    }
+
+    std::fs::write(&output, code)
+        .map_err(|e| CliError::Failed(format!("Cannot write {}: {}", output.display(), e)))?;
+    save_snapshot(&generator)?;
+    // Forms in the project's `generated` module are compiled with it
+    if option(&options, "--output").is_none() {
+        declare_form_module(&output)
+            .map_err(|e| CliError::Failed(format!("Cannot declare the module of {}: {}", output.display(), e)))?;
+    }
+
+    Ok(Report::new(true)
+        .line(format!("Generated {} ({} components)", output.display(), components.len()))
+        .field("output", output.display().to_string())
+        .field("components", components.len())
+        .field("events", bindings.len()))
+}
+
+/// Indent every line of `code` by `spaces`
+fn indent(code: &str, spaces: usize) -> String {
+    code.lines().map(|line| format!("{}{}", " ".repeat(spaces), line)).collect::<Vec<_>>().join("\n")
+}
+
+/// Module, type name and constructor of the RCL component for a designer
+/// component type; designer property values are applied afterwards
+fn rcl_component(component_type: &str) -> Option<(&'static str, &'static str, &'static str)> {
+    Some(match to_pascal_case(component_type).to_ascii_lowercase().as_str() {
+        "button" => ("button", "Button", "Button::new(String::new())"),
+        "checkbox" => ("checkbox", "Checkbox", "Checkbox::new(String::new(), false)"),
+        "dropdown" => ("dropdown", "Dropdown", "Dropdown::new(String::new(), Vec::new(), 0)"),
+        "form" => ("form", "Form", "Form::new(String::new())"),
+        "label" => ("label", "Label", "Label::new(String::new())"),
+        "radiobutton" => ("radio_button", "RadioButton", "RadioButton::new(String::new(), false)"),
+        "slider" => ("slider", "Slider", "Slider::new(0.0, 0.0, 100.0)"),
+        "textbox" => ("textbox", "TextBox", "TextBox::new(String::new())"),
+        _ => return None,
+    })
+}
+
+/// Parse an export format name, accepting short aliases
+fn parse_format(name: &str) -> Result<ExportFormat, CliError> {
+    match name.to_ascii_lowercase().as_str() {
+        "native" | "json" => Ok(ExportFormat::Native),
+        "react" | "jsx" | "react_jsx" => Ok(ExportFormat::ReactJsx),
+        "vue" | "vue_template" => Ok(ExportFormat::VueTemplate),
+        "angular" | "angular_template" => Ok(ExportFormat::AngularTemplate),
+        "html" | "html_css" => Ok(ExportFormat::HtmlCss),
+        "svg" => Ok(ExportFormat::Svg),
+        "png" => Ok(ExportFormat::Png),
+        _ => Err(CliError::Usage(format!(
+            "Unsupported export format '{}' (expected native, react, vue, angular, html, svg or png)",
+            name
+        ))),
+    }
+}
+
+/// `export --format <format> <form>`: convert a saved form to another format
+fn export(args: &[&str]) -> Result<Report, CliError> {
+    let (positionals, options) = parse_options(args, &["--format", "--output"], &[])?;
+    let form_path = single_positional(&positionals, "form file")?;
+    let format = parse_format(option(&options, "--format").ok_or_else(|| CliError::Usage("export needs --format".to_string()))?)?;
+    let output = option(&options, "--output").map(PathBuf::from);
+    if format == ExportFormat::Png && output.is_none() {
+        return Err(CliError::Usage("PNG export needs --output".to_string()));
+    }
+
+    let json = std::fs::read_to_string(form_path)
+        .map_err(|e| CliError::Failed(format!("Cannot read {}: {}", form_path, e)))?;
+    let component = SerializationUtils::deserialize_component(&json)
+        .map_err(|e| CliError::Failed(format!("Invalid form {}: {}", form_path, e)))?;
+    let format_name = serde_json::to_value(&format).unwrap_or(Value::Null);
+
+    let Some(output) = output else {
+        let content = SerializationUtils::export_component(&component, &format)
+            .map_err(|e| CliError::Failed(e.to_string()))?;
+        return Ok(Report::new(true).line(content.clone()).field("format", format_name).field("content", content));
+    };
+
+    let bytes = SerializationUtils::export_component_bytes(&component, &format)
+        .map_err(|e| CliError::Failed(e.to_string()))?;
+    std::fs::write(&output, &bytes)
+        .map_err(|e| CliError::Failed(format!("Cannot write {}: {}", output.display(), e)))?;
+    Ok(Report::new(true)
+        .line(format!("Exported {} to {}", form_path, output.display()))
+        .field("format", format_name)
+        .field("output", output.display().to_string())
+        .field("bytes", bytes.len()))
+}
+
+/// `validate <project>`: report project errors and warnings
+fn validate(args: &[&str]) -> Result<Report, CliError> {
+    let (positionals, _) = parse_options(args, &[], &[])?;
+    let project_path = PathBuf::from(single_positional(&positionals, "project directory")?);
+    let project = load_project(&project_path)?;
+    let result = ProjectOperations::new()
+        .validate_project(&project, &mut OutputPanel::new())
+        .map_err(|e| CliError::Failed(e.to_string()))?;
+
+    let valid = result.is_valid();
+    let mut report = Report::new(valid);
+    report.lines.extend(result.errors.iter().map(|error| format!("error: {}", error)));
+    report.lines.extend(result.warnings.iter().map(|warning| format!("warning: {}", warning)));
+    report.lines.push(format!(
+        "{}: {} ({} errors, {} warnings)",
+        project.metadata.name,
+        if valid { "valid" } else { "invalid" },
+        result.errors.len(),
+        result.warnings.len()
+    ));
+    Ok(report
+        .field("project", project.metadata.name.clone())
+        .field("valid", valid)
+        .field("errors", result.errors)
+        .field("warnings", result.warnings)
+        .field("info", result.info))
+}
+
+/// `new --template <id>`: create a project from a scaffolding template
+fn new_project(args: &[&str]) -> Result<Report, CliError> {
+    let (positionals, options) = parse_options(
+        args,
+        &["--template", "--name", "--path", "--author", "--description", "--var"],
+        &["--no-git"],
+    )?;
+    if let Some(extra) = positionals.first() {
+        return Err(CliError::Usage(format!("Unexpected argument '{}'", extra)));
+    }
+
+    let mut engine = ProjectScaffoldingEngine::new();
+    let template_id = option(&options, "--template").ok_or_else(|| CliError::Usage("new needs --template".to_string()))?;
+    let Some(template) = engine.get_template(template_id) else {
+        let mut available: Vec<&String> = engine.templates.keys().collect();
+        available.sort();
+        let available: Vec<&str> = available.into_iter().map(String::as_str).collect();
+        return Err(CliError::Usage(format!("Unknown template '{}' (available: {})", template_id, available.join(", "))));
+    };
+    let category = template.category.clone();
+
+    let mut variables = HashMap::new();
+    for assignment in options.get("--var").into_iter().flatten() {
+        let (name, value) = assignment.split_once('=')
+            .ok_or_else(|| CliError::Usage(format!("--var expects <name>=<value>, got '{}'", assignment)))?;
+        variables.insert(name.to_string(), value.to_string());
+    }
+
+    let defaults = ProjectConfiguration::default();
+    let name = option(&options, "--name").map(str::to_string)
+        .or_else(|| template.variables.iter().find(|variable| variable.name == "project_name").map(|variable| variable.default_value.clone()))
+        .unwrap_or(defaults.name.clone());
+    let git = !options.contains_key("--no-git");
+    let config = ProjectConfiguration {
+        name: name.clone(),
+        path: option(&options, "--path").map(PathBuf::from).unwrap_or(defaults.path.clone()),
+        description: option(&options, "--description").map(str::to_string).unwrap_or(defaults.description.clone()),
+        author: option(&options, "--author").map(str::to_string).unwrap_or(defaults.author.clone()),
+        init_git: git,
+        initial_commit: git,
+        ..defaults
+    };
+
+    let project_path = engine.create_project(template_id, &config, &variables).map_err(CliError::Failed)?;
+
+    // Record the IDE project so the other commands can work on it
+    let mut project = IdeProject::new(name, project_path.clone(), project_type(&category));
+    project.metadata.author = config.author.clone();
+    if !config.description.is_empty() {
+        project.metadata.description = config.description.clone();
+    }
+    ProjectSerializer::new()
+        .save_project(&project, &mut OutputPanel::new())
+        .map_err(|e| CliError::Failed(format!("Cannot save project file: {}", e)))?;
+
+    Ok(Report::new(true)
+        .line(format!("Created {} from template {}", project_path.display(), template_id))
+        .field("template", template_id)
+        .field("path", project_path.display().to_string()))
+}
+
+/// IDE project type for projects created from a template category
+fn project_type(category: &TemplateCategory) -> ProjectType {
+    match category {
+        TemplateCategory::Application | TemplateCategory::CLI | TemplateCategory::SystemProgramming => ProjectType::ConsoleApplication,
+        TemplateCategory::Library => ProjectType::Library,
+        TemplateCategory::WebFramework | TemplateCategory::API => ProjectType::WebApplication,
+        TemplateCategory::GameDevelopment => ProjectType::GameProject,
+        TemplateCategory::Desktop => ProjectType::GuiApplication,
+        other => ProjectType::Custom(format!("{:?}", other)),
+    }
+}
+
+/// `package <component-dir>`: build a component package archive
+fn package(args: &[&str]) -> Result<Report, CliError> {
+    let (positionals, options) = parse_options(args, &["--output"], &[])?;
+    let source = PathBuf::from(single_positional(&positionals, "component directory")?);
+    let output_dir = option(&options, "--output").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("."));
+
+    let archive = package_component(&source, &output_dir).map_err(|e| CliError::Failed(e.to_string()))?;
+    let manifest = PackageArchive::open(&archive).map_err(|e| CliError::Failed(e.to_string()))?.manifest;
+    Ok(Report::new(true)
+        .line(format!("Packaged {} {} into {}", manifest.name, manifest.version, archive.display()))
+        .field("package", manifest.name.clone())
+        .field("version", manifest.version.to_string())
+        .field("archive", archive.display().to_string())
+        .field("files", manifest.files.len()))
+}
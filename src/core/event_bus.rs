@@ -67,6 +67,9 @@ pub enum IdeEvent {
     TerminalClosed { terminal_id: Uuid },
     TerminalOutput { terminal_id: Uuid, line: String, is_error: bool },
     
+    /// Version control events
+    GitCommitted { repository_path: std::path::PathBuf, hash: String, message: String },
+    
     /// Task and build system events
     TaskStarted { task_id: Uuid, name: String, command: String },
     TaskCompleted { task_id: Uuid, name: String, exit_code: i32, duration_ms: u64 },
@@ -146,6 +149,7 @@ pub enum EventType {
     LanguageServer,
     Terminal,
     Task,
+    VersionControl,
     UI,
     Application,
 }
@@ -199,6 +203,8 @@ impl EventType {
             (EventType::Task, IdeEvent::TaskCompleted { .. }) => true,
             (EventType::Task, IdeEvent::TaskFailed { .. }) => true,
             
+            (EventType::VersionControl, IdeEvent::GitCommitted { .. }) => true,
+            
             (EventType::UI, IdeEvent::PanelOpened { .. }) => true,
             (EventType::UI, IdeEvent::PanelClosed { .. }) => true,
             (EventType::UI, IdeEvent::ThemeChanged { .. }) => true,
@@ -80,7 +80,7 @@ macro_rules! log_error {
 /// Span creation helper for tracing operation lifecycle
 #[macro_export]
 macro_rules! trace_span {
-    ($name:expr) => {
+    ($name:expr) => {{
         #[cfg(feature = "logging")]
         {
             tracing::info_span!($name)
@@ -89,8 +89,8 @@ macro_rules! trace_span {
         {
             NoOpSpan
         }
-    };
-    ($name:expr, $($field:tt)*) => {
+    }};
+    ($name:expr, $($field:tt)*) => {{
         #[cfg(feature = "logging")]
         {
             tracing::info_span!($name, $($field)*)
@@ -99,7 +99,7 @@ macro_rules! trace_span {
         {
             NoOpSpan
         }
-    };
+    }};
 }
 
 /// No-op span for when logging is disabled
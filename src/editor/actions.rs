@@ -34,7 +34,11 @@
 //! println!("Recent: {:?}", action_manager.recent_actions);
 //! ```
 
-use crate::editor::{output_panel::OutputPanel, component_registry::ComponentRegistry};
+use std::path::{Path, PathBuf};
+
+use crate::editor::{output_panel::OutputPanel, component_registry::{self, ComponentRegistry}};
+use crate::editor::packaging::{self, PackageManifest, PackageRegistry};
+use crate::rcl::component_registry::ComponentRegistry as RclComponentRegistry;
 
 /// Central action manager for coordinating IDE commands and operations
 /// 
@@ -503,86 +507,109 @@ fn execute_cargo_command(args: &[&str], output_panel: &mut OutputPanel) -> Resul
 // COMPONENT PACKAGING ACTIONS - Component lifecycle management
 // ========================================================================================
 
-/// Package a single component for distribution
+/// Package a component source directory for distribution
 /// 
-/// Creates a distributable package for a specific component, including
-/// its source code, metadata, and dependencies. The package can be
-/// shared with other developers or imported into other projects.
+/// Builds an `.rclpkg` archive from a directory containing an
+/// `rcl-package.json` manifest. The archive bundles the component files
+/// together with their SHA-256 hashes so it can be verified on install.
 /// 
 /// # Arguments
 /// 
-/// * `name` - Name identifier for the component
-/// * `source` - Source directory path containing component files
-/// * `output` - Output directory path for the generated package
-/// * `_registry` - Component registry for tracking packaged components
+/// * `source` - Component source directory containing the manifest
+/// * `output` - Directory the package archive is written to
+/// * `output_panel` - Output panel for progress and error messages
 /// 
-/// # Behavior
+/// # Returns
 /// 
-/// - Validates component source files
-/// - Creates package metadata
-/// - Bundles component resources
-/// - Registers package in component registry
-/// 
-/// **Note**: This is currently a placeholder implementation for demonstration.
-/// A production version would implement actual file packaging logic.
-pub fn package_component(name: &str, source: &str, output: &str, _registry: &mut ComponentRegistry) {
-    // In a real implementation, this would actually package components
-    println!("📦 Packaging component {} from {} to {}", name, source, output);
-    println!("✅ Component packaged successfully");
+/// The path of the created archive, or `None` if packaging failed.
+pub fn package_component(source: &Path, output: &Path, output_panel: &mut OutputPanel) -> Option<PathBuf> {
+    output_panel.log(&format!("📦 Packaging component from {}", source.display()));
+    match packaging::package_component(source, output) {
+        Ok(archive) => {
+            output_panel.log(&format!("✅ Package created: {}", archive.display()));
+            Some(archive)
+        }
+        Err(e) => {
+            output_panel.log(&format!("❌ Packaging failed: {}", e));
+            None
+        }
+    }
 }
 
 /// Install a component package
 /// 
-/// Extracts and installs a component package into the current project,
-/// making it available for use in the visual designer and code generation.
+/// Verifies and installs a package archive into the local package registry,
+/// installing any missing dependencies found next to the archive. The
+/// components of every installed package are registered with the component
+/// library so they appear in the component palette.
 /// 
 /// # Arguments
 /// 
-/// * `package` - Path to the component package file
-/// * `destination` - Installation directory in the current project
-/// * `_registry` - Component registry for tracking installed components
-/// 
-/// # Behavior
-/// 
-/// - Validates package integrity
-/// - Extracts package contents
-/// - Installs component files
-/// - Updates component registry
-/// - Refreshes component palette
-/// 
-/// **Note**: This is currently a placeholder implementation for demonstration.
-/// A production version would implement actual package installation logic.
-pub fn install_component(package: &str, destination: &str, _registry: &mut ComponentRegistry) {
-    // In a real implementation, this would actually install components
-    println!("📥 Installing component from {} to {}", package, destination);
-    println!("✅ Component installed successfully");
+/// * `package` - Path to the `.rclpkg` archive
+/// * `packages` - Local package registry to install into
+/// * `library` - RCL component registry backing the palette
+/// * `registry` - Registry of installed components and libraries
+/// * `output_panel` - Output panel for progress and error messages
+pub fn install_component(
+    package: &Path,
+    packages: &PackageRegistry,
+    library: &mut RclComponentRegistry,
+    registry: &mut ComponentRegistry,
+    output_panel: &mut OutputPanel,
+) {
+    output_panel.log(&format!("📥 Installing package {}", package.display()));
+    match packages.install(package, library) {
+        Ok(installed) => {
+            for manifest in installed {
+                output_panel.log(&format!(
+                    "✅ Installed {} {} ({} components)",
+                    manifest.name, manifest.version, manifest.components.len()
+                ));
+                registry.install(installed_metadata(packages, &manifest));
+            }
+        }
+        Err(e) => output_panel.log(&format!("❌ Install failed: {}", e)),
+    }
 }
 
 /// Uninstall a component package
 /// 
-/// Removes an installed component package from the project, cleaning up
-/// all associated files and removing it from the component registry.
+/// Removes an installed package from the local package registry and its
+/// components from the palette. Packages that other installed packages
+/// still depend on are left in place and an error is reported.
 /// 
 /// # Arguments
 /// 
-/// * `package` - Name of the component package to uninstall
-/// * `location` - Installation location of the component
-/// * `_registry` - Component registry for tracking component removal
-/// 
-/// # Behavior
-/// 
-/// - Validates component can be safely removed
-/// - Removes component files and resources
-/// - Updates component registry
-/// - Refreshes component palette
-/// - Cleans up any orphaned dependencies
-/// 
-/// **Note**: This is currently a placeholder implementation for demonstration.
-/// A production version would implement actual package removal logic.
-pub fn uninstall_component(package: &str, location: &str, _registry: &mut ComponentRegistry) {
-    // In a real implementation, this would actually uninstall components
-    println!("🗑 Uninstalling component {} from {}", package, location);
-    println!("✅ Component uninstalled successfully");
+/// * `name` - Name of the package to uninstall
+/// * `packages` - Local package registry to remove it from
+/// * `library` - RCL component registry backing the palette
+/// * `registry` - Registry of installed components and libraries
+/// * `output_panel` - Output panel for progress and error messages
+pub fn uninstall_component(
+    name: &str,
+    packages: &PackageRegistry,
+    library: &mut RclComponentRegistry,
+    registry: &mut ComponentRegistry,
+    output_panel: &mut OutputPanel,
+) {
+    output_panel.log(&format!("🗑 Uninstalling package {}", name));
+    match packages.uninstall(name, library) {
+        Ok(manifest) => {
+            registry.uninstall(&manifest.name);
+            output_panel.log(&format!("✅ Uninstalled {} {}", manifest.name, manifest.version));
+        }
+        Err(e) => output_panel.log(&format!("❌ Uninstall failed: {}", e)),
+    }
+}
+
+/// Registry entry describing an installed package
+pub fn installed_metadata(packages: &PackageRegistry, manifest: &PackageManifest) -> component_registry::ComponentMetadata {
+    component_registry::ComponentMetadata {
+        name: manifest.name.clone(),
+        version: manifest.version.to_string(),
+        source: packages.files_dir(&manifest.name).display().to_string(),
+        description: manifest.description.clone(),
+    }
 }
 
 // ========================================================================================
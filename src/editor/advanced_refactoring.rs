@@ -11,6 +11,9 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 use regex::Regex;
+use sha2::{Digest, Sha256};
+
+use super::semantic_refactoring::{self, RefactoringWorkspace};
 
 /// Main refactoring engine for code transformations
 pub struct AdvancedRefactoringEngine {
@@ -32,6 +35,8 @@ pub struct AdvancedRefactoringEngine {
     settings: RefactoringSettings,
     /// Performance metrics
     metrics: RefactoringMetrics,
+    /// Workspace the transformations read and rewrite
+    workspace: RefactoringWorkspace,
 }
 
 /// Individual refactoring operation definition
@@ -118,7 +123,7 @@ pub struct RefactoringParameter {
 }
 
 /// Parameter types for refactoring operations
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ParameterType {
     String,
     Boolean,
@@ -179,7 +184,8 @@ pub enum PrerequisiteType {
 }
 
 /// Function signature for transformation logic
-pub type TransformationFunction = fn(&CodeSelection, &HashMap<String, String>) -> Result<Vec<CodeChange>, String>;
+pub type TransformationFunction =
+    fn(&RefactoringWorkspace, &CodeSelection, &HashMap<String, String>) -> Result<Vec<CodeChange>, String>;
 
 /// Code analyzer for understanding code structure
 #[derive(Debug, Clone)]
@@ -328,7 +334,7 @@ pub struct TextPosition {
 }
 
 /// Context information for code selection
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct SelectionContext {
     /// Containing function
     pub function: Option<String>,
@@ -1030,6 +1036,7 @@ pub struct BatchOperation {
     pub name: String,
     pub description: String,
     pub operations: Vec<RefactoringOperation>,
+    pub parameters: HashMap<String, String>,
     pub target_files: HashSet<PathBuf>,
     pub progress: BatchProgress,
     pub results: Vec<BatchResult>,
@@ -1146,6 +1153,8 @@ pub struct RollbackPoint {
 /// File snapshot for rollback
 #[derive(Debug, Clone)]
 pub struct FileSnapshot {
+    /// Whether the file existed; restoring a missing file deletes it
+    pub existed: bool,
     pub content: String,
     pub checksum: String,
     pub last_modified: std::time::SystemTime,
@@ -1279,12 +1288,23 @@ impl AdvancedRefactoringEngine {
             history: RefactoringHistory::new(),
             settings,
             metrics: RefactoringMetrics::default(),
+            workspace: RefactoringWorkspace::new(std::env::current_dir().unwrap_or_default()),
         };
 
         engine.initialize_default_operations();
         engine
     }
 
+    /// Set the workspace root whose files refactorings read and rewrite
+    pub fn set_workspace_root(&mut self, root: impl Into<PathBuf>) {
+        self.workspace = RefactoringWorkspace::new(root);
+    }
+
+    /// Current workspace root
+    pub fn workspace_root(&self) -> &Path {
+        self.workspace.root()
+    }
+
     /// Initialize default refactoring operations
     fn initialize_default_operations(&mut self) {
         // Rename operations
@@ -1295,26 +1315,14 @@ impl AdvancedRefactoringEngine {
             category: RefactoringCategory::Rename,
             applicable_contexts: vec![
                 CodeContext::Function,
+                CodeContext::Method,
                 CodeContext::Variable,
                 CodeContext::Type,
+                CodeContext::Enum,
+                CodeContext::Trait,
             ],
             safety_level: SafetyLevel::Safe,
-            parameters: vec![
-                RefactoringParameter {
-                    name: "new_name".to_string(),
-                    param_type: ParameterType::Identifier,
-                    required: true,
-                    default_value: None,
-                    description: "New name for the symbol".to_string(),
-                    validation: vec![
-                        ValidationRule {
-                            rule_type: ValidationRuleType::ValidIdentifier,
-                            value: "".to_string(),
-                            error_message: "Must be a valid identifier".to_string(),
-                        }
-                    ],
-                }
-            ],
+            parameters: vec![identifier_parameter("new_name", None, "New name for the symbol")],
             prerequisites: vec![
                 RefactoringPrerequisite {
                     prerequisite_type: PrerequisiteType::CompilationSuccess,
@@ -1322,10 +1330,25 @@ impl AdvancedRefactoringEngine {
                     required: true,
                 }
             ],
-            transformer: dummy_transformer,
+            transformer: semantic_refactoring::rename_symbol,
+        });
+
+        // Extract operations
+        self.add_operation(RefactoringOperation {
+            id: "extract_function".to_string(),
+            name: "Extract Function".to_string(),
+            description: "Extract selected code into a new function".to_string(),
+            category: RefactoringCategory::Extract,
+            applicable_contexts: vec![CodeContext::Block, CodeContext::Expression],
+            safety_level: SafetyLevel::MostlySafe,
+            parameters: vec![
+                identifier_parameter("function_name", Some("extracted_function"), "Name for the extracted function"),
+                type_parameter(),
+            ],
+            prerequisites: vec![],
+            transformer: semantic_refactoring::extract_function,
         });
 
-        // Extract method
         self.add_operation(RefactoringOperation {
             id: "extract_method".to_string(),
             name: "Extract Method".to_string(),
@@ -1334,23 +1357,23 @@ impl AdvancedRefactoringEngine {
             applicable_contexts: vec![CodeContext::Block, CodeContext::Expression],
             safety_level: SafetyLevel::MostlySafe,
             parameters: vec![
-                RefactoringParameter {
-                    name: "method_name".to_string(),
-                    param_type: ParameterType::Identifier,
-                    required: true,
-                    default_value: Some("extracted_method".to_string()),
-                    description: "Name for the extracted method".to_string(),
-                    validation: vec![
-                        ValidationRule {
-                            rule_type: ValidationRuleType::ValidIdentifier,
-                            value: "".to_string(),
-                            error_message: "Must be a valid method name".to_string(),
-                        }
-                    ],
-                }
+                identifier_parameter("method_name", Some("extracted_method"), "Name for the extracted method"),
+                type_parameter(),
             ],
             prerequisites: vec![],
-            transformer: dummy_transformer,
+            transformer: semantic_refactoring::extract_method,
+        });
+
+        self.add_operation(RefactoringOperation {
+            id: "extract_variable".to_string(),
+            name: "Extract Variable".to_string(),
+            description: "Introduce a local variable for the selected expression".to_string(),
+            category: RefactoringCategory::Extract,
+            applicable_contexts: vec![CodeContext::Expression],
+            safety_level: SafetyLevel::Safe,
+            parameters: vec![identifier_parameter("variable_name", Some("value"), "Name for the new variable")],
+            prerequisites: vec![],
+            transformer: semantic_refactoring::extract_variable,
         });
 
         // Inline variable
@@ -1369,7 +1392,40 @@ impl AdvancedRefactoringEngine {
                     required: true,
                 }
             ],
-            transformer: dummy_transformer,
+            transformer: semantic_refactoring::inline_variable,
+        });
+
+        // Move item
+        self.add_operation(RefactoringOperation {
+            id: "move_item_to_module".to_string(),
+            name: "Move to Module".to_string(),
+            description: "Move an item into another module and update its paths".to_string(),
+            category: RefactoringCategory::Move,
+            applicable_contexts: vec![
+                CodeContext::Function,
+                CodeContext::Type,
+                CodeContext::Enum,
+                CodeContext::Trait,
+            ],
+            safety_level: SafetyLevel::Moderate,
+            parameters: vec![
+                RefactoringParameter {
+                    name: "target_module".to_string(),
+                    param_type: ParameterType::String,
+                    required: true,
+                    default_value: None,
+                    description: "Destination module path, e.g. crate::utils".to_string(),
+                    validation: vec![
+                        ValidationRule {
+                            rule_type: ValidationRuleType::Regex,
+                            value: r"^(crate::)?[A-Za-z_][A-Za-z0-9_]*(::[A-Za-z_][A-Za-z0-9_]*)*$".to_string(),
+                            error_message: "Must be a module path".to_string(),
+                        }
+                    ],
+                }
+            ],
+            prerequisites: vec![],
+            transformer: semantic_refactoring::move_item_to_module,
         });
     }
 
@@ -1386,6 +1442,21 @@ impl AdvancedRefactoringEngine {
             .collect()
     }
 
+    /// Get a registered operation by id
+    pub fn get_operation(&self, operation_id: &str) -> Option<&RefactoringOperation> {
+        self.operations.get(operation_id)
+    }
+
+    /// Compute the changes a refactoring would make without touching any file
+    pub fn preview_refactoring(
+        &self,
+        operation_id: &str,
+        selection: &CodeSelection,
+        parameters: &HashMap<String, String>,
+    ) -> Result<Vec<CodeChange>, String> {
+        self.plan_refactoring(operation_id, selection, parameters).map(|(changes, _)| changes)
+    }
+
     /// Execute a refactoring operation
     pub fn execute_refactoring(
         &mut self,
@@ -1393,6 +1464,31 @@ impl AdvancedRefactoringEngine {
         selection: &CodeSelection,
         parameters: HashMap<String, String>,
     ) -> Result<RefactoringResult, String> {
+        let started = std::time::Instant::now();
+        let (changes, warnings) = self.plan_refactoring(operation_id, selection, &parameters)?;
+
+        // Apply changes
+        let transaction_id = format!("tx_{}", uuid::Uuid::new_v4());
+        let mut result = self.apply_changes(&transaction_id, operation_id, changes)?;
+        result.warnings = warnings;
+        result.execution_time = started.elapsed();
+
+        // Record in history
+        self.record_transaction(&transaction_id, operation_id, &result);
+
+        // Update metrics
+        self.update_metrics(operation_id, &result);
+
+        Ok(result)
+    }
+
+    /// Run the transformer and checks for an operation, returning changes and safety warnings
+    fn plan_refactoring(
+        &self,
+        operation_id: &str,
+        selection: &CodeSelection,
+        parameters: &HashMap<String, String>,
+    ) -> Result<(Vec<CodeChange>, Vec<String>), String> {
         let operation = self.operations
             .get(operation_id)
             .ok_or_else(|| format!("Unknown operation: {}", operation_id))?;
@@ -1401,22 +1497,18 @@ impl AdvancedRefactoringEngine {
         self.check_prerequisites(operation, selection)?;
 
         // Validate parameters
-        self.validate_parameters(operation, &parameters)?;
+        self.validate_parameters(operation, parameters)?;
+        let mut parameters = parameters.clone();
+        for param in &operation.parameters {
+            if let Some(default) = &param.default_value {
+                parameters.entry(param.name.clone()).or_insert_with(|| default.clone());
+            }
+        }
 
         // Perform safety checks
-        let potential_changes = (operation.transformer)(selection, &parameters)?;
-        self.safety_checker.check_safety(selection, &potential_changes)?;
-
-        // Apply changes
-        let result = self.apply_changes(potential_changes)?;
-
-        // Record in history
-        self.record_transaction(operation_id, &result);
-
-        // Update metrics
-        self.update_metrics(operation_id, &result);
-
-        Ok(result)
+        let potential_changes = (operation.transformer)(&self.workspace, selection, &parameters)?;
+        let warnings = self.safety_checker.check_safety(selection, &potential_changes)?;
+        Ok((potential_changes, warnings))
     }
 
     /// Check operation prerequisites
@@ -1469,6 +1561,12 @@ impl AdvancedRefactoringEngine {
                         return Err(rule.error_message.clone());
                     }
                 },
+                ValidationRuleType::Regex => {
+                    let pattern = Regex::new(&rule.value).map_err(|e| e.to_string())?;
+                    if !pattern.is_match(value) {
+                        return Err(rule.error_message.clone());
+                    }
+                },
                 _ => {
                     // Handle other validation rules
                 }
@@ -1477,44 +1575,43 @@ impl AdvancedRefactoringEngine {
         Ok(())
     }
 
-    /// Apply code changes
-    fn apply_changes(&mut self, changes: Vec<CodeChange>) -> Result<RefactoringResult, String> {
-        let mut applied_changes = Vec::new();
-        let mut errors = Vec::new();
-
-        for change in changes {
-            match self.apply_single_change(&change) {
-                Ok(()) => applied_changes.push(change),
-                Err(e) => errors.push(e),
-            }
+    /// Apply code changes as one unit, restoring every file if any write fails
+    fn apply_changes(&mut self, transaction_id: &str, description: &str, changes: Vec<CodeChange>) -> Result<RefactoringResult, String> {
+        let edits = plan_edits(&changes)?;
+        let mut snapshots = HashMap::new();
+        if let Err(error) = write_edits(edits, &mut snapshots) {
+            restore_snapshots(&snapshots)?;
+            return Err(error);
         }
+        self.batch_manager.rollback_manager.record(transaction_id, description, snapshots);
 
         Ok(RefactoringResult {
-            success: errors.is_empty(),
-            applied_changes,
-            errors,
+            success: true,
+            applied_changes: changes,
+            errors: Vec::new(),
             warnings: Vec::new(),
             execution_time: std::time::Duration::from_millis(0),
         })
     }
 
-    /// Apply a single code change
-    fn apply_single_change(&self, _change: &CodeChange) -> Result<(), String> {
-        // Would apply the actual change to the file
-        Ok(())
-    }
-
     /// Record refactoring transaction in history
-    fn record_transaction(&mut self, operation_id: &str, result: &RefactoringResult) {
+    fn record_transaction(&mut self, transaction_id: &str, operation_id: &str, result: &RefactoringResult) {
+        let mut files: Vec<String> = result.applied_changes
+            .iter()
+            .map(|change| change.file_path.display().to_string())
+            .collect();
+        files.sort();
+        files.dedup();
+
         let transaction = RefactoringTransaction {
-            transaction_id: format!("tx_{}", uuid::Uuid::new_v4().to_string()),
+            transaction_id: transaction_id.to_string(),
             operation_name: operation_id.to_string(),
             timestamp: std::time::Instant::now(),
             changes: result.applied_changes.clone(),
             rollback_info: RollbackInfo {
                 can_rollback: true,
                 rollback_data: Vec::new(),
-                dependencies: Vec::new(),
+                dependencies: files,
             },
             metadata: HashMap::new(),
         };
@@ -1522,10 +1619,48 @@ impl AdvancedRefactoringEngine {
         self.history.add_transaction(transaction);
     }
 
+    /// Undo the most recent refactoring, restoring every file it touched
+    pub fn undo_last_refactoring(&mut self) -> Result<RefactoringTransaction, String> {
+        let position = self.history.current_position;
+        let transaction = position
+            .checked_sub(1)
+            .and_then(|index| self.history.history_stack.get(index))
+            .cloned()
+            .ok_or("Nothing to undo")?;
+
+        let id = &transaction.transaction_id;
+        self.batch_manager.rollback_manager.restore(&after_point_id(id), id)?;
+        self.history.current_position -= 1;
+        Ok(transaction)
+    }
+
+    /// Reapply the most recently undone refactoring
+    pub fn redo_refactoring(&mut self) -> Result<RefactoringTransaction, String> {
+        let transaction = self.history.history_stack
+            .get(self.history.current_position)
+            .cloned()
+            .ok_or("Nothing to redo")?;
+
+        let id = &transaction.transaction_id;
+        self.batch_manager.rollback_manager.restore(id, &after_point_id(id))?;
+        self.history.current_position += 1;
+        Ok(transaction)
+    }
+
+    /// Whether there is a refactoring to undo
+    pub fn can_undo(&self) -> bool {
+        self.history.current_position > 0
+    }
+
+    /// Whether there is an undone refactoring to redo
+    pub fn can_redo(&self) -> bool {
+        self.history.current_position < self.history.history_stack.len()
+    }
+
     /// Update performance metrics
     fn update_metrics(&mut self, operation_id: &str, result: &RefactoringResult) {
         self.metrics.total_refactorings += 1;
-        
+
         if result.success {
             let success_count = (self.metrics.success_rate * (self.metrics.total_refactorings - 1) as f32) + 1.0;
             self.metrics.success_rate = success_count / self.metrics.total_refactorings as f32;
@@ -1562,13 +1697,24 @@ impl AdvancedRefactoringEngine {
 
     /// Check if operation is applicable to selection
     fn is_applicable(&self, operation: &RefactoringOperation, selection: &CodeSelection) -> bool {
-        // Would analyze the selection context
-        operation.applicable_contexts.contains(&CodeContext::Function) // Simplified
+        semantic_refactoring::classify_selection(&self.workspace, selection)
+            .is_some_and(|context| operation.applicable_contexts.contains(&context))
     }
 
     /// Calculate confidence for suggestion
-    fn calculate_confidence(&self, _operation: &RefactoringOperation, _selection: &CodeSelection) -> f32 {
-        0.8 // Simplified
+    fn calculate_confidence(&self, operation: &RefactoringOperation, selection: &CodeSelection) -> f32 {
+        let base: f32 = match operation.safety_level {
+            SafetyLevel::Safe => 0.95,
+            SafetyLevel::MostlySafe => 0.85,
+            SafetyLevel::Moderate => 0.7,
+            SafetyLevel::Risky => 0.5,
+            SafetyLevel::Dangerous => 0.3,
+        };
+        if self.is_applicable(operation, selection) {
+            base
+        } else {
+            base * 0.5
+        }
     }
 
     /// Estimate effort for operation
@@ -1585,9 +1731,106 @@ impl AdvancedRefactoringEngine {
         vec!["Improved readability".to_string(), "Better maintainability".to_string()]
     }
 
+    /// Register a batch template
+    pub fn add_batch_template(&mut self, template: BatchTemplate) {
+        self.batch_manager.batch_templates.retain(|existing| existing.template_id != template.template_id);
+        self.batch_manager.batch_templates.push(template);
+    }
+
     /// Start a batch refactoring operation
     pub fn start_batch_operation(&mut self, template_id: &str, parameters: HashMap<String, String>) -> Result<String, String> {
-        self.batch_manager.start_batch(template_id, parameters)
+        self.batch_manager.start_batch(template_id, parameters, &self.operations)
+    }
+
+    /// Get a batch operation and its progress
+    pub fn get_batch(&self, batch_id: &str) -> Option<&BatchOperation> {
+        self.batch_manager.active_batches.get(batch_id)
+    }
+
+    /// Run every operation of a batch on every selection as a single transaction
+    ///
+    /// Operations run in template order and each sees the files as left by the
+    /// previous one. Selections are visited from the end of each file backwards
+    /// so edits do not shift the offsets of those still pending. If any step
+    /// fails, all files are restored.
+    pub fn run_batch(&mut self, batch_id: &str, selections: &[CodeSelection]) -> Result<RefactoringResult, String> {
+        let started = std::time::Instant::now();
+        let mut selections = selections.to_vec();
+        selections.sort_by(|a, b| a.file_path.cmp(&b.file_path).then(b.start.offset.cmp(&a.start.offset)));
+        let (name, operations, parameters) = {
+            let batch = self.batch_manager.active_batches
+                .get_mut(batch_id)
+                .ok_or_else(|| format!("Unknown batch: {}", batch_id))?;
+            batch.progress.total_operations = batch.operations.len() * selections.len();
+            batch.progress.start_time = started;
+            let operations: Vec<String> = batch.operations.iter().map(|operation| operation.id.clone()).collect();
+            (batch.name.clone(), operations, batch.parameters.clone())
+        };
+        if operations.len() * selections.len() > self.settings.max_batch_size {
+            return Err(format!("Batch exceeds the maximum of {} operations", self.settings.max_batch_size));
+        }
+
+        let mut snapshots = HashMap::new();
+        let mut applied = Vec::new();
+        let mut warnings = Vec::new();
+        for operation_id in &operations {
+            for selection in &selections {
+                let step_started = std::time::Instant::now();
+                let step = self.plan_refactoring(operation_id, selection, &parameters).and_then(|(changes, step_warnings)| {
+                    write_edits(plan_edits(&changes)?, &mut snapshots)?;
+                    Ok((changes, step_warnings))
+                });
+
+                let batch = self.batch_manager.active_batches.get_mut(batch_id).expect("batch exists");
+                batch.progress.current_operation = Some(operation_id.clone());
+                match step {
+                    Ok((changes, step_warnings)) => {
+                        batch.progress.completed_operations += 1;
+                        batch.target_files.extend(changes.iter().map(|change| change.file_path.clone()));
+                        batch.results.push(BatchResult {
+                            operation_id: operation_id.clone(),
+                            success: true,
+                            changes_applied: changes.clone(),
+                            errors: Vec::new(),
+                            warnings: step_warnings.clone(),
+                            execution_time: step_started.elapsed(),
+                        });
+                        applied.extend(changes);
+                        warnings.extend(step_warnings);
+                    }
+                    Err(error) => {
+                        batch.progress.failed_operations += 1;
+                        batch.results.push(BatchResult {
+                            operation_id: operation_id.clone(),
+                            success: false,
+                            changes_applied: Vec::new(),
+                            errors: vec![error.clone()],
+                            warnings: Vec::new(),
+                            execution_time: step_started.elapsed(),
+                        });
+                        restore_snapshots(&snapshots)?;
+                        return Err(format!("{} failed on {}: {}", operation_id, selection.file_path.display(), error));
+                    }
+                }
+            }
+        }
+
+        let transaction_id = format!("tx_{}", uuid::Uuid::new_v4());
+        self.batch_manager.rollback_manager.record(&transaction_id, &name, snapshots);
+        if let Some(batch) = self.batch_manager.active_batches.get_mut(batch_id) {
+            batch.progress.current_operation = None;
+        }
+
+        let result = RefactoringResult {
+            success: true,
+            applied_changes: applied,
+            errors: Vec::new(),
+            warnings,
+            execution_time: started.elapsed(),
+        };
+        self.record_transaction(&transaction_id, batch_id, &result);
+        self.update_metrics(batch_id, &result);
+        Ok(result)
     }
 
     /// Get refactoring metrics
@@ -1644,16 +1887,25 @@ impl CodeTransformer {
 impl RefactoringSafetyChecker {
     fn new() -> Self {
         Self {
-            safety_rules: Vec::new(),
+            safety_rules: default_safety_rules(),
             conflict_patterns: Vec::new(),
             breaking_change_detectors: Vec::new(),
             impact_analyzer: ImpactAnalyzer::new(),
         }
     }
 
-    fn check_safety(&self, _selection: &CodeSelection, _changes: &[CodeChange]) -> Result<(), String> {
-        // Would perform safety checks
-        Ok(())
+    /// Run the safety rules, failing on errors and returning lesser findings as warnings
+    fn check_safety(&self, selection: &CodeSelection, changes: &[CodeChange]) -> Result<Vec<String>, String> {
+        let mut warnings = Vec::new();
+        for rule in &self.safety_rules {
+            if let Err(message) = (rule.checker)(selection, changes) {
+                if rule.severity >= SafetySeverity::Error {
+                    return Err(format!("{}: {}", rule.error_message, message));
+                }
+                warnings.push(format!("{}: {}", rule.rule_name, message));
+            }
+        }
+        Ok(warnings)
     }
 }
 
@@ -1667,9 +1919,44 @@ impl BatchRefactoringManager {
         }
     }
 
-    fn start_batch(&mut self, _template_id: &str, _parameters: HashMap<String, String>) -> Result<String, String> {
-        // Would start a batch operation
-        Ok("batch_001".to_string())
+    fn start_batch(
+        &mut self,
+        template_id: &str,
+        parameters: HashMap<String, String>,
+        operations: &HashMap<String, RefactoringOperation>,
+    ) -> Result<String, String> {
+        let template = self.batch_templates
+            .iter()
+            .find(|template| template.template_id == template_id)
+            .ok_or_else(|| format!("Unknown batch template: {}", template_id))?;
+
+        let batch_operations = template.operations
+            .iter()
+            .map(|id| operations.get(id).cloned().ok_or_else(|| format!("Unknown operation: {}", id)))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(missing) = template.parameters.iter().find(|param| !parameters.contains_key(&param.name)) {
+            return Err(format!("Required parameter '{}' is missing", missing.name));
+        }
+
+        let batch_id = format!("batch_{}", uuid::Uuid::new_v4());
+        self.active_batches.insert(batch_id.clone(), BatchOperation {
+            operation_id: batch_id.clone(),
+            name: template.name.clone(),
+            description: template.description.clone(),
+            progress: BatchProgress {
+                total_operations: batch_operations.len(),
+                completed_operations: 0,
+                failed_operations: 0,
+                current_operation: None,
+                start_time: std::time::Instant::now(),
+                estimated_completion: None,
+            },
+            operations: batch_operations,
+            parameters,
+            target_files: HashSet::new(),
+            results: Vec::new(),
+        });
+        Ok(batch_id)
     }
 }
 
@@ -1684,10 +1971,13 @@ impl RefactoringHistory {
     }
 
     fn add_transaction(&mut self, transaction: RefactoringTransaction) {
+        // A new transaction discards anything that was undone
+        self.history_stack.truncate(self.current_position);
         self.history_stack.push_back(transaction);
         if self.history_stack.len() > self.max_history_size {
             self.history_stack.pop_front();
         }
+        self.current_position = self.history_stack.len();
     }
 }
 
@@ -1770,6 +2060,69 @@ impl RollbackManager {
             rollback_strategy: RollbackStrategy::FileSystem,
         }
     }
+
+    /// Store the state of the files before and after a transaction
+    fn record(&mut self, transaction_id: &str, description: &str, before: HashMap<PathBuf, FileSnapshot>) {
+        let after = before.keys().map(|path| (path.clone(), FileSnapshot::capture(path))).collect();
+        self.add_point(transaction_id.to_string(), description, before);
+        self.add_point(after_point_id(transaction_id), description, after);
+    }
+
+    fn add_point(&mut self, point_id: String, description: &str, affected_files: HashMap<PathBuf, FileSnapshot>) {
+        self.rollback_points.retain(|point| point.point_id != point_id);
+        self.rollback_points.push(RollbackPoint {
+            point_id,
+            timestamp: std::time::Instant::now(),
+            description: description.to_string(),
+            affected_files,
+            metadata: HashMap::new(),
+        });
+        while self.rollback_points.len() > self.max_rollback_points {
+            self.rollback_points.remove(0);
+        }
+    }
+
+    fn point(&self, point_id: &str) -> Result<&RollbackPoint, String> {
+        self.rollback_points
+            .iter()
+            .find(|point| point.point_id == point_id)
+            .ok_or_else(|| format!("Rollback point {} is no longer available", point_id))
+    }
+
+    /// Restore the files of `target_id` if they are still as captured by `expected_id`
+    fn restore(&self, expected_id: &str, target_id: &str) -> Result<(), String> {
+        let expected = self.point(expected_id)?;
+        let target = self.point(target_id)?;
+        for (path, snapshot) in &expected.affected_files {
+            let current = FileSnapshot::capture(path);
+            if current.existed != snapshot.existed || current.checksum != snapshot.checksum {
+                return Err(format!("{} was modified after the refactoring", path.display()));
+            }
+        }
+        restore_snapshots(&target.affected_files)
+    }
+}
+
+impl FileSnapshot {
+    /// Snapshot of file content, or of a missing file
+    fn new(content: Option<&str>) -> Self {
+        Self {
+            existed: content.is_some(),
+            content: content.unwrap_or_default().to_string(),
+            checksum: format!("{:x}", Sha256::digest(content.unwrap_or_default().as_bytes())),
+            last_modified: std::time::SystemTime::now(),
+        }
+    }
+
+    fn capture(path: &Path) -> Self {
+        let content = std::fs::read_to_string(path).ok();
+        Self {
+            last_modified: std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .unwrap_or_else(|_| std::time::SystemTime::now()),
+            ..Self::new(content.as_deref())
+        }
+    }
 }
 
 impl Default for FormattingRules {
@@ -1803,26 +2156,348 @@ impl Default for RefactoringMetrics {
     }
 }
 
+/// What the user chose in the refactoring window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefactoringViewAction {
+    /// Compute the changes of the chosen operation
+    Preview,
+    /// Run the chosen operation
+    Apply,
+    /// Undo the last refactoring
+    Undo,
+    /// Redo the last undone refactoring
+    Redo,
+    /// Close the window
+    Close,
+}
+
+/// Window offering the refactorings that apply to a selection
+pub struct RefactoringView {
+    pub selection: CodeSelection,
+    pub suggestions: Vec<RefactoringSuggestion>,
+    /// Chosen operation
+    pub operation_id: Option<String>,
+    /// Parameter values of the chosen operation
+    pub parameters: HashMap<String, String>,
+    /// Changes of the last preview, or why it failed
+    pub preview: Option<Result<Vec<CodeChange>, String>>,
+}
+
+impl RefactoringView {
+    /// Create a view of the refactorings `engine` suggests for `selection`
+    pub fn new(engine: &AdvancedRefactoringEngine, selection: CodeSelection) -> Self {
+        let suggestions = engine.get_suggestions(&selection);
+        let mut view = Self { selection, suggestions, operation_id: None, parameters: HashMap::new(), preview: None };
+        if let Some(first) = view.suggestions.first().map(|suggestion| suggestion.operation_id.clone()) {
+            view.choose(engine, first);
+        }
+        view
+    }
+
+    /// Choose an operation, resetting its parameters to their defaults
+    pub fn choose(&mut self, engine: &AdvancedRefactoringEngine, operation_id: String) {
+        self.parameters = engine.get_operation(&operation_id)
+            .map(|operation| {
+                operation.parameters.iter()
+                    .map(|param| (param.name.clone(), param.default_value.clone().unwrap_or_default()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        // Renames start from the selected name
+        if let Some(name) = self.parameters.get_mut("new_name").filter(|name| name.is_empty()) {
+            name.clone_from(&self.selection.text);
+        }
+        self.operation_id = Some(operation_id);
+        self.preview = None;
+    }
+
+    /// Parameters to pass to the engine, leaving out optional ones left empty
+    pub fn parameter_values(&self) -> HashMap<String, String> {
+        self.parameters.iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect()
+    }
+
+    /// Show the window, returning the user's decision once made
+    pub fn show(&mut self, ctx: &egui::Context, engine: &AdvancedRefactoringEngine) -> Option<RefactoringViewAction> {
+        let mut action = None;
+        let mut chosen = None;
+        egui::Window::new("🔧 Refactor")
+            .default_size([640.0, 420.0])
+            .collapsible(false)
+            .show(ctx, |ui| {
+                let file = self.selection.file_path.file_name().unwrap_or_default().to_string_lossy();
+                ui.label(format!("{}:{}:{}", file, self.selection.start.line + 1, self.selection.start.column + 1));
+                if self.suggestions.is_empty() {
+                    ui.label("No refactoring applies to the selection");
+                }
+                for suggestion in &self.suggestions {
+                    let selected = self.operation_id.as_deref() == Some(suggestion.operation_id.as_str());
+                    if ui.selectable_label(selected, &suggestion.title).on_hover_text(&suggestion.description).clicked() && !selected {
+                        chosen = Some(suggestion.operation_id.clone());
+                    }
+                }
+
+                if let Some(operation) = self.operation_id.as_deref().and_then(|id| engine.get_operation(id)) {
+                    ui.separator();
+                    for param in &operation.parameters {
+                        ui.horizontal(|ui| {
+                            ui.label(&param.name).on_hover_text(&param.description);
+                            let value = self.parameters.entry(param.name.clone()).or_default();
+                            if ui.text_edit_singleline(value).changed() {
+                                self.preview = None;
+                            }
+                        });
+                    }
+                }
+
+                match &self.preview {
+                    Some(Ok(changes)) => {
+                        ui.separator();
+                        egui::ScrollArea::vertical().max_height(220.0).show(ui, |ui| {
+                            for change in changes {
+                                let file = change.file_path.file_name().unwrap_or_default().to_string_lossy();
+                                ui.monospace(format!(
+                                    "{}:{}: {:?} {:?} → {:?}",
+                                    file,
+                                    change.position.line + 1,
+                                    change.change_type,
+                                    change.old_text.as_deref().unwrap_or_default(),
+                                    change.new_text,
+                                ));
+                            }
+                        });
+                    }
+                    Some(Err(error)) => {
+                        ui.colored_label(egui::Color32::from_rgb(220, 90, 90), error);
+                    }
+                    None => {}
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let chosen = self.operation_id.is_some();
+                    if ui.add_enabled(chosen, egui::Button::new("Preview")).clicked() {
+                        action = Some(RefactoringViewAction::Preview);
+                    }
+                    if ui.add_enabled(chosen, egui::Button::new("✔ Apply")).clicked() {
+                        action = Some(RefactoringViewAction::Apply);
+                    }
+                    if ui.add_enabled(engine.can_undo(), egui::Button::new("↶ Undo")).clicked() {
+                        action = Some(RefactoringViewAction::Undo);
+                    }
+                    if ui.add_enabled(engine.can_redo(), egui::Button::new("↷ Redo")).clicked() {
+                        action = Some(RefactoringViewAction::Redo);
+                    }
+                    if ui.button("Close").clicked() {
+                        action = Some(RefactoringViewAction::Close);
+                    }
+                });
+            });
+        if let Some(operation_id) = chosen {
+            self.choose(engine, operation_id);
+        }
+        action
+    }
+}
+
 // Helper functions
-fn dummy_transformer(_selection: &CodeSelection, _parameters: &HashMap<String, String>) -> Result<Vec<CodeChange>, String> {
-    // Dummy implementation
-    Ok(Vec::new())
+impl CodeSelection {
+    /// Selection of the byte range `range` of `source`, the content of `file_path`
+    pub fn new(file_path: impl Into<PathBuf>, source: &str, range: std::ops::Range<usize>) -> Self {
+        Self {
+            file_path: file_path.into(),
+            start: TextPosition::from_offset(source, range.start),
+            end: TextPosition::from_offset(source, range.end),
+            text: source.get(range).unwrap_or_default().to_string(),
+            context: SelectionContext::default(),
+        }
+    }
 }
 
-fn is_valid_identifier(name: &str) -> bool {
-    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
-}
-
-// UUID module for generating IDs
-mod uuid {
-    pub struct Uuid;
-    impl Uuid {
-        pub fn new_v4() -> Self { Self }
-        pub fn to_string(&self) -> String {
-            format!("id_{}", std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_millis())
+impl TextPosition {
+    /// Line and column of a byte offset in `source`
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let before = source.get(..offset).unwrap_or(source);
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            line: before.matches('\n').count(),
+            column: before[line_start..].chars().count(),
+            offset,
         }
     }
-}
\ No newline at end of file
+}
+
+/// New content of one file after applying a set of changes
+#[derive(Debug, Clone)]
+pub struct FileEdit {
+    pub path: PathBuf,
+    /// Content before the changes; `None` if the file is created
+    pub original: Option<String>,
+    pub updated: String,
+}
+
+/// Apply a single code change to file content
+///
+/// Fails if the text at the change position differs from `old_text`.
+pub fn apply_single_change(content: &mut String, change: &CodeChange) -> Result<(), String> {
+    let start = change.position.offset;
+    let old_text = change.old_text.as_deref().unwrap_or_default();
+    let end = start + old_text.len();
+    if content.get(start..end) != Some(old_text) {
+        return Err(format!(
+            "{}:{}:{}: expected {:?}; the file has changed since the refactoring was planned",
+            change.file_path.display(),
+            change.position.line + 1,
+            change.position.column + 1,
+            old_text
+        ));
+    }
+    content.replace_range(start..end, &change.new_text);
+    Ok(())
+}
+
+/// Compute the new content of every file touched by `changes`
+///
+/// Changes to one file must not overlap; they are applied from the end of
+/// the file backwards so earlier offsets stay valid.
+pub fn plan_edits(changes: &[CodeChange]) -> Result<Vec<FileEdit>, String> {
+    let mut by_file: Vec<(&PathBuf, Vec<(usize, &CodeChange)>)> = Vec::new();
+    for (index, change) in changes.iter().enumerate() {
+        match by_file.iter_mut().find(|(path, _)| **path == change.file_path) {
+            Some((_, file_changes)) => file_changes.push((index, change)),
+            None => by_file.push((&change.file_path, vec![(index, change)])),
+        }
+    }
+
+    let mut edits = Vec::new();
+    for (path, mut file_changes) in by_file {
+        let original = match std::fs::read_to_string(path) {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(format!("Cannot read {}: {}", path.display(), e)),
+        };
+        if original.is_none() && file_changes.iter().any(|(_, change)| change.old_text.is_some() || change.position.offset != 0) {
+            return Err(format!("{} does not exist", path.display()));
+        }
+
+        // Later changes first; for inserts at one offset, keep their listed order
+        file_changes.sort_by(|(a_index, a), (b_index, b)| {
+            b.position.offset.cmp(&a.position.offset).then(b_index.cmp(a_index))
+        });
+        let mut updated = original.clone().unwrap_or_default();
+        let mut limit = usize::MAX;
+        for (_, change) in file_changes {
+            let end = change.position.offset + change.old_text.as_deref().map_or(0, str::len);
+            if end > limit || (end == limit && change.old_text.as_deref().is_some_and(|text| !text.is_empty())) {
+                return Err(format!("Overlapping changes in {} at offset {}", path.display(), change.position.offset));
+            }
+            apply_single_change(&mut updated, change)?;
+            limit = change.position.offset;
+        }
+        edits.push(FileEdit { path: path.clone(), original, updated });
+    }
+    Ok(edits)
+}
+
+/// Write file edits, snapshotting each file the first time it is touched
+fn write_edits(edits: Vec<FileEdit>, snapshots: &mut HashMap<PathBuf, FileSnapshot>) -> Result<(), String> {
+    for edit in edits {
+        snapshots
+            .entry(edit.path.clone())
+            .or_insert_with(|| FileSnapshot::new(edit.original.as_deref()));
+        if let Some(parent) = edit.path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Cannot create {}: {}", parent.display(), e))?;
+        }
+        std::fs::write(&edit.path, &edit.updated).map_err(|e| format!("Cannot write {}: {}", edit.path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Put files back to their snapshot state, deleting files that did not exist
+fn restore_snapshots(snapshots: &HashMap<PathBuf, FileSnapshot>) -> Result<(), String> {
+    for (path, snapshot) in snapshots {
+        let result = if snapshot.existed {
+            std::fs::write(path, &snapshot.content)
+        } else {
+            match std::fs::remove_file(path) {
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                other => other,
+            }
+        };
+        result.map_err(|e| format!("Cannot restore {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+fn after_point_id(transaction_id: &str) -> String {
+    format!("{}:after", transaction_id)
+}
+
+/// Rules every refactoring is checked against before it is applied
+fn default_safety_rules() -> Vec<SafetyRule> {
+    vec![
+        SafetyRule {
+            rule_id: "changes_apply".to_string(),
+            rule_name: "Changes apply cleanly".to_string(),
+            severity: SafetySeverity::Error,
+            checker: |_, changes| plan_edits(changes).map(|_| ()),
+            error_message: "Changes do not match the current files".to_string(),
+        },
+        SafetyRule {
+            rule_id: "syntax_preserved".to_string(),
+            rule_name: "Syntax preserved".to_string(),
+            severity: SafetySeverity::Critical,
+            checker: |_, changes| {
+                for edit in plan_edits(changes)? {
+                    if edit.path.extension().is_some_and(|ext| ext == "rs") {
+                        syn::parse_file(&edit.updated)
+                            .map_err(|e| format!("{} would not parse: {}", edit.path.display(), e))?;
+                    }
+                }
+                Ok(())
+            },
+            error_message: "Refactoring would produce invalid code".to_string(),
+        },
+        SafetyRule {
+            rule_id: "has_changes".to_string(),
+            rule_name: "Has changes".to_string(),
+            severity: SafetySeverity::Warning,
+            checker: |_, changes| if changes.is_empty() { Err("nothing to change".to_string()) } else { Ok(()) },
+            error_message: "Refactoring makes no changes".to_string(),
+        },
+    ]
+}
+
+fn identifier_parameter(name: &str, default_value: Option<&str>, description: &str) -> RefactoringParameter {
+    RefactoringParameter {
+        name: name.to_string(),
+        param_type: ParameterType::Identifier,
+        required: default_value.is_none(),
+        default_value: default_value.map(str::to_string),
+        description: description.to_string(),
+        validation: vec![
+            ValidationRule {
+                rule_type: ValidationRuleType::ValidIdentifier,
+                value: "".to_string(),
+                error_message: "Must be a valid identifier".to_string(),
+            }
+        ],
+    }
+}
+
+fn type_parameter() -> RefactoringParameter {
+    RefactoringParameter {
+        name: "return_type".to_string(),
+        param_type: ParameterType::String,
+        required: false,
+        default_value: None,
+        description: "Return type, when it cannot be inferred".to_string(),
+        validation: vec![],
+    }
+}
+
+fn is_valid_identifier(name: &str) -> bool {
+    syn::parse_str::<syn::Ident>(name).is_ok()
+}
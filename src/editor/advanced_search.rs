@@ -9,10 +9,22 @@
 
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use serde::{Deserialize, Serialize};
 use regex::Regex;
 
+use super::file_watcher::{AdvancedFileWatcher, FileWatchEvent};
+use super::workspace_index::{
+    content_hash, extract_rust_symbols, file_type_of, is_indexable, module_path_for, relocate_symbols, similarity,
+    trigrams, words, workspace_files, FileRecord, FileSymbols, IndexSnapshot, FORMAT_VERSION, MAX_INDEXED_FILE_SIZE,
+};
+
+/// Maximum number of symbol-based suggestions
+const MAX_SYMBOL_SUGGESTIONS: usize = 10;
+
+/// Maximum number of history-based suggestions
+const MAX_HISTORY_SUGGESTIONS: usize = 10;
+
 /// Main search engine for the IDE
 pub struct AdvancedSearchEngine {
     /// Text search index
@@ -31,6 +43,10 @@ pub struct AdvancedSearchEngine {
     settings: SearchSettings,
     /// Performance metrics
     metrics: SearchMetrics,
+    /// Root of the workspace whose index is persisted
+    workspace_root: Option<PathBuf>,
+    /// Whether the index changed since it was saved
+    index_dirty: bool,
 }
 
 /// Text search index for full-text search
@@ -73,6 +89,8 @@ pub struct SymbolIndex {
     hierarchy: SymbolHierarchy,
     /// Cross-reference graph
     xref_graph: CrossReferenceGraph,
+    /// Symbols extracted from each file, used to update the maps above incrementally
+    file_symbols: HashMap<PathBuf, FileSymbols>,
 }
 
 /// Search history management
@@ -162,8 +180,8 @@ pub struct IndexedFile {
     pub path: PathBuf,
     /// File content hash
     pub content_hash: String,
-    /// Last modified time
-    pub last_modified: Instant,
+    /// Last modified time on disk
+    pub last_modified: SystemTime,
     /// File type
     pub file_type: FileType,
     /// Indexed content
@@ -175,7 +193,7 @@ pub struct IndexedFile {
 }
 
 /// File type classification
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum FileType {
     Rust,
     JavaScript,
@@ -214,7 +232,7 @@ pub struct DocumentReference {
 }
 
 /// Text position in a document
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TextPosition {
     /// Line number (0-based)
     pub line: usize,
@@ -285,7 +303,7 @@ pub enum ASTNodeType {
 }
 
 /// Text span in source code
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TextSpan {
     pub start: TextPosition,
     pub end: TextPosition,
@@ -353,7 +371,7 @@ pub struct Parameter {
 }
 
 /// Visibility levels
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Visibility {
     Public,
     Private,
@@ -574,7 +592,7 @@ pub struct PatternExample {
 }
 
 /// Symbol representation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Symbol {
     /// Symbol name
     pub name: String,
@@ -593,7 +611,7 @@ pub struct Symbol {
 }
 
 /// Symbol kinds
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SymbolKind {
     Function,
     Variable,
@@ -611,7 +629,7 @@ pub enum SymbolKind {
 }
 
 /// Symbol location
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolLocation {
     pub file_path: PathBuf,
     pub position: TextPosition,
@@ -619,7 +637,7 @@ pub struct SymbolLocation {
 }
 
 /// Symbol definition
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolDefinition {
     pub symbol: Symbol,
     pub definition_type: DefinitionType,
@@ -628,7 +646,7 @@ pub struct SymbolDefinition {
 }
 
 /// Definition types
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DefinitionType {
     Declaration,
     Implementation,
@@ -637,7 +655,7 @@ pub enum DefinitionType {
 }
 
 /// Symbol reference
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolReference {
     pub symbol_name: String,
     pub location: SymbolLocation,
@@ -646,7 +664,7 @@ pub struct SymbolReference {
 }
 
 /// Reference types
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ReferenceType {
     Read,
     Write,
@@ -708,7 +726,7 @@ pub struct SearchHistoryEntry {
     /// Search type
     pub search_type: SearchType,
     /// Timestamp
-    pub timestamp: Instant,
+    pub timestamp: SystemTime,
     /// Results count
     pub results_count: usize,
     /// Execution time
@@ -747,9 +765,9 @@ pub struct SavedSearch {
     /// Tags
     pub tags: Vec<String>,
     /// Created timestamp
-    pub created_at: Instant,
+    pub created_at: SystemTime,
     /// Last used timestamp
-    pub last_used: Option<Instant>,
+    pub last_used: Option<SystemTime>,
     /// Usage count
     pub usage_count: usize,
 }
@@ -761,7 +779,7 @@ pub struct SearchCollection {
     pub name: String,
     pub description: Option<String>,
     pub searches: Vec<String>,
-    pub created_at: Instant,
+    pub created_at: SystemTime,
     pub tags: Vec<String>,
 }
 
@@ -770,7 +788,7 @@ pub struct SearchCollection {
 pub struct SharedSearch {
     pub search: SavedSearch,
     pub shared_by: String,
-    pub shared_at: Instant,
+    pub shared_at: SystemTime,
     pub permissions: SharePermissions,
     pub comments: Vec<SearchComment>,
 }
@@ -790,7 +808,7 @@ pub struct SearchComment {
     pub id: String,
     pub author: String,
     pub content: String,
-    pub created_at: Instant,
+    pub created_at: SystemTime,
     pub parent_comment: Option<String>,
 }
 
@@ -929,6 +947,8 @@ impl AdvancedSearchEngine {
             filter_manager: FilterManager::new(),
             settings,
             metrics: SearchMetrics::default(),
+            workspace_root: None,
+            index_dirty: false,
         }
     }
 
@@ -1036,11 +1056,8 @@ impl AdvancedSearchEngine {
 
     /// Index a file
     pub async fn index_file(&mut self, file_path: &Path) -> Result<(), String> {
-        // Index for text search
-        self.text_index.index_file(file_path).await?;
-        
-        // Index for symbol search
-        self.symbol_index.index_file(file_path).await?;
+        // Index for text and symbol search
+        self.update_file(file_path)?;
         
         // Index for semantic search
         self.semantic_engine.index_file(file_path).await?;
@@ -1081,6 +1098,299 @@ impl AdvancedSearchEngine {
     pub fn get_metrics(&self) -> &SearchMetrics {
         &self.metrics
     }
+
+    /// Open the workspace at `root`
+    ///
+    /// Restores the index saved under `.ide-rs`, then re-indexes files that
+    /// were added, changed or removed since it was written. A missing or
+    /// unreadable snapshot is rebuilt from scratch.
+    pub fn open_workspace(&mut self, root: &Path) -> Result<IndexUpdate, String> {
+        if !root.is_dir() {
+            return Err(format!("{} is not a directory", root.display()));
+        }
+        self.text_index = TextSearchIndex::new();
+        self.symbol_index = SymbolIndex::new();
+        self.workspace_root = Some(root.to_path_buf());
+        self.index_dirty = false;
+
+        match IndexSnapshot::load(root) {
+            Ok(Some(snapshot)) => self.restore_snapshot(root, snapshot),
+            Ok(None) => self.index_dirty = true,
+            Err(_e) => {
+                crate::log_warn!("Rebuilding search index: {}", _e);
+                self.index_dirty = true;
+            }
+        }
+        self.refresh_index()
+    }
+
+    /// Root of the open workspace
+    pub fn workspace_root(&self) -> Option<&Path> {
+        self.workspace_root.as_deref()
+    }
+
+    /// Number of files in the index
+    pub fn indexed_file_count(&self) -> usize {
+        self.text_index.indexed_files.len()
+    }
+
+    /// Bring the index in line with the workspace on disk and save it
+    pub fn refresh_index(&mut self) -> Result<IndexUpdate, String> {
+        let root = self.workspace_root.clone().ok_or("No workspace is open")?;
+        let files = workspace_files(&root, &root);
+
+        let mut update = IndexUpdate::default();
+        let present: HashSet<&PathBuf> = files.iter().collect();
+        let stale: Vec<PathBuf> = self.text_index.indexed_files.keys().filter(|path| !present.contains(path)).cloned().collect();
+        for path in stale {
+            self.remove_from_index(&path);
+            update.removed += 1;
+        }
+        for path in &files {
+            match self.update_file(path)? {
+                FileStatus::Added => update.added += 1,
+                FileStatus::Updated => update.updated += 1,
+                FileStatus::Unchanged => update.unchanged += 1,
+                FileStatus::Removed => update.removed += 1,
+                FileStatus::Skipped => {}
+            }
+        }
+
+        self.save_index()?;
+        Ok(update)
+    }
+
+    /// Write the index to the workspace's `.ide-rs` directory if it changed
+    pub fn save_index(&mut self) -> Result<(), String> {
+        let Some(root) = &self.workspace_root else { return Ok(()) };
+        if !self.index_dirty {
+            return Ok(());
+        }
+        self.snapshot(root).save(root)?;
+        self.index_dirty = false;
+        Ok(())
+    }
+
+    /// Update the index for a file watcher event; returns whether anything changed
+    pub fn apply_watch_event(&mut self, event: &FileWatchEvent) -> Result<bool, String> {
+        let Some(root) = self.workspace_root.clone() else { return Ok(false) };
+        let changed = match event {
+            FileWatchEvent::Created(path) | FileWatchEvent::Modified(path) => {
+                is_indexable(&root, path) && self.update_file(path)?.is_change()
+            }
+            FileWatchEvent::Deleted(path) => self.remove_from_index(path),
+            FileWatchEvent::Renamed(old_path, new_path) => {
+                let removed = self.remove_from_index(old_path);
+                let added = is_indexable(&root, new_path) && self.update_file(new_path)?.is_change();
+                removed || added
+            }
+            FileWatchEvent::DirectoryCreated(dir) => {
+                let mut changed = false;
+                for path in workspace_files(&root, dir) {
+                    changed |= self.update_file(&path)?.is_change();
+                }
+                changed
+            }
+            FileWatchEvent::DirectoryDeleted(dir) => {
+                let removed: Vec<PathBuf> = self.text_index.indexed_files.keys().filter(|path| path.starts_with(dir)).cloned().collect();
+                for path in &removed {
+                    self.remove_from_index(path);
+                }
+                !removed.is_empty()
+            }
+            FileWatchEvent::Batch(events) => {
+                let mut changed = false;
+                for event in events {
+                    changed |= self.apply_watch_event(event)?;
+                }
+                changed
+            }
+        };
+        Ok(changed)
+    }
+
+    /// Apply all pending events of `watcher` and save the index if it changed
+    ///
+    /// Returns the number of events that changed the index. An event that
+    /// cannot be applied is skipped so the events queued behind it still are.
+    pub fn process_watcher_events(&mut self, watcher: &AdvancedFileWatcher) -> Result<usize, String> {
+        let mut changes = 0;
+        while let Ok(event) = watcher.try_recv_event() {
+            match self.apply_watch_event(&event) {
+                Ok(true) => changes += 1,
+                Ok(false) => {}
+                Err(_e) => {
+                    crate::log_warn!("Skipping file watcher event {:?}: {}", event, _e);
+                }
+            }
+        }
+        self.save_index()?;
+        Ok(changes)
+    }
+
+    /// Definitions of a symbol, by name or qualified name
+    pub fn find_definitions(&self, name: &str) -> Vec<&SymbolDefinition> {
+        let (scope, simple) = match name.rsplit_once("::") {
+            Some((scope, simple)) => (Some(scope), simple),
+            None => (None, name),
+        };
+        self.symbol_index
+            .definitions
+            .get(simple)
+            .into_iter()
+            .flatten()
+            .filter(|definition| scope.is_none_or(|scope| definition.symbol.scope == scope || definition.symbol.scope.ends_with(&format!("::{}", scope))))
+            .collect()
+    }
+
+    /// Every place a symbol name is used, in file order
+    pub fn find_usages(&self, name: &str) -> Vec<&SymbolReference> {
+        let simple = name.rsplit("::").next().unwrap_or(name);
+        let mut usages: Vec<&SymbolReference> = self.symbol_index.references.get(simple).into_iter().flatten().collect();
+        usages.sort_by(|a, b| a.location.file_path.cmp(&b.location.file_path).then(a.location.position.offset.cmp(&b.location.position.offset)));
+        usages
+    }
+
+    /// Symbols matching `query` for go-to-symbol, best first
+    pub fn go_to_symbol(&self, query: &str) -> Vec<SearchResult> {
+        let mut results = self.symbol_index.find(query, None);
+        results.truncate(self.settings.max_results);
+        results
+    }
+
+    /// Qualified names of the functions and methods that call `name`
+    pub fn callers_of(&self, name: &str) -> Vec<String> {
+        let simple = name.rsplit("::").next().unwrap_or(name);
+        let graph = &self.symbol_index.xref_graph;
+        let mut callers: Vec<String> = graph
+            .dependents
+            .get(simple)
+            .into_iter()
+            .flatten()
+            .filter(|caller| graph.call_graph.get(*caller).is_some_and(|callees| callees.iter().any(|callee| callee == simple)))
+            .cloned()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        callers.sort();
+        callers
+    }
+
+    /// Re-index one file if it changed on disk
+    fn update_file(&mut self, path: &Path) -> Result<FileStatus, String> {
+        let metadata = match std::fs::metadata(path) {
+            Ok(metadata) if metadata.is_file() && metadata.len() <= MAX_INDEXED_FILE_SIZE => metadata,
+            _ => return Ok(if self.remove_from_index(path) { FileStatus::Removed } else { FileStatus::Skipped }),
+        };
+        let modified = metadata.modified().unwrap_or(std::time::UNIX_EPOCH);
+        let known = self.text_index.indexed_files.get(path);
+        if known.is_some_and(|file| file.last_modified == modified && file.metadata.size == metadata.len()) {
+            return Ok(FileStatus::Unchanged);
+        }
+        let existed = known.is_some();
+
+        let content = match std::fs::read(path).map(String::from_utf8) {
+            Ok(Ok(content)) => content,
+            // Binary content
+            Ok(Err(_)) => return Ok(if self.remove_from_index(path) { FileStatus::Removed } else { FileStatus::Skipped }),
+            Err(e) => return Err(format!("Cannot read {}: {}", path.display(), e)),
+        };
+        let hash = content_hash(&content);
+        self.index_dirty = true;
+        if let Some(file) = self.text_index.indexed_files.get_mut(path) {
+            if file.content_hash == hash {
+                file.last_modified = modified;
+                return Ok(FileStatus::Unchanged);
+            }
+        }
+
+        self.text_index.index_text(path, &content, hash, modified);
+        if file_type_of(path) == FileType::Rust {
+            let root = self.workspace_root.as_deref().or(path.parent()).unwrap_or(path);
+            // Keep the last good symbols while the file does not parse
+            if let Ok(symbols) = extract_rust_symbols(path, &content, &module_path_for(root, path)) {
+                self.symbol_index.index_symbols(path, symbols);
+            }
+        }
+        Ok(if existed { FileStatus::Updated } else { FileStatus::Added })
+    }
+
+    fn remove_from_index(&mut self, path: &Path) -> bool {
+        let removed_text = self.text_index.remove_file(path);
+        let removed_symbols = self.symbol_index.remove_file(path);
+        let removed = removed_text || removed_symbols;
+        self.index_dirty |= removed;
+        removed
+    }
+
+    fn snapshot(&self, root: &Path) -> IndexSnapshot {
+        let mut paths: Vec<&PathBuf> = self.text_index.indexed_files.keys().collect();
+        paths.sort();
+        let ids: HashMap<&PathBuf, u32> = paths.iter().enumerate().map(|(id, path)| (*path, id as u32)).collect();
+
+        let files = paths
+            .iter()
+            .map(|path| {
+                let file = &self.text_index.indexed_files[*path];
+                let relative = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+                let mut symbols = self.symbol_index.file_symbols.get(*path).cloned().unwrap_or_default();
+                relocate_symbols(&mut symbols, &relative);
+                FileRecord {
+                    path: relative,
+                    content_hash: file.content_hash.clone(),
+                    modified: file.last_modified,
+                    size: file.metadata.size,
+                    file_type: file.file_type.clone(),
+                    symbols,
+                }
+            })
+            .collect();
+        let postings = |index: &HashMap<String, Vec<DocumentReference>>| {
+            index
+                .iter()
+                .map(|(token, references)| {
+                    let mut file_ids: Vec<u32> = references.iter().filter_map(|reference| ids.get(&reference.file_path).copied()).collect();
+                    file_ids.sort_unstable();
+                    (token.clone(), file_ids)
+                })
+                .collect()
+        };
+
+        IndexSnapshot {
+            format_version: FORMAT_VERSION,
+            files,
+            trigrams: postings(&self.text_index.ngram_index),
+            words: postings(&self.text_index.inverted_index),
+        }
+    }
+
+    fn restore_snapshot(&mut self, root: &Path, snapshot: IndexSnapshot) {
+        let paths: Vec<PathBuf> = snapshot.files.iter().map(|record| root.join(&record.path)).collect();
+        let restore_postings = |postings: HashMap<String, Vec<u32>>| -> HashMap<String, Vec<DocumentReference>> {
+            postings
+                .into_iter()
+                .map(|(token, file_ids)| {
+                    let references = file_ids
+                        .into_iter()
+                        .filter_map(|id| paths.get(id as usize))
+                        .map(|path| DocumentReference { file_path: path.clone(), positions: Vec::new(), relevance: 1.0, context: None })
+                        .collect();
+                    (token, references)
+                })
+                .collect()
+        };
+        self.text_index.ngram_index = restore_postings(snapshot.trigrams);
+        self.text_index.inverted_index = restore_postings(snapshot.words);
+
+        for (record, path) in snapshot.files.into_iter().zip(&paths) {
+            let file = IndexedFile::new(path, record.content_hash, record.modified, record.file_type, record.size);
+            self.text_index.indexed_files.insert(path.clone(), file);
+            let mut symbols = record.symbols;
+            relocate_symbols(&mut symbols, path);
+            self.symbol_index.index_symbols(path, symbols);
+        }
+        self.text_index.updated();
+    }
 }
 
 /// Search results container
@@ -1144,7 +1454,32 @@ pub enum SuggestionType {
     ContextBased,
 }
 
-// Implementation stubs for the main components
+/// Files touched while bringing the workspace index up to date
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndexUpdate {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+/// What re-indexing a single file did
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FileStatus {
+    Added,
+    Updated,
+    Unchanged,
+    Removed,
+    Skipped,
+}
+
+impl FileStatus {
+    fn is_change(self) -> bool {
+        matches!(self, FileStatus::Added | FileStatus::Updated | FileStatus::Removed)
+    }
+}
+
+// Implementation of the main components
 impl TextSearchIndex {
     fn new() -> Self {
         Self {
@@ -1163,23 +1498,162 @@ impl TextSearchIndex {
     }
 
     async fn search(&self, query: &str, filtered_files: &[PathBuf], case_sensitive: bool) -> Result<Vec<SearchResult>, String> {
-        // Implementation would perform text search
-        Ok(Vec::new())
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let pattern = format!("{}{}", if case_sensitive { "" } else { "(?i)" }, regex::escape(query));
+        let regex = Regex::new(&pattern).map_err(|e| e.to_string())?;
+        let candidates = self.candidates(&trigrams(query), filtered_files);
+        Ok(self.find_matches(&regex, candidates, |_| 1.0))
     }
 
     async fn regex_search(&self, regex: Regex, filtered_files: &[PathBuf]) -> Result<Vec<SearchResult>, String> {
-        // Implementation would perform regex search
-        Ok(Vec::new())
+        let candidates = self.candidates(&HashSet::new(), filtered_files);
+        Ok(self.find_matches(&regex, candidates, |_| 1.0))
     }
 
     async fn fuzzy_search(&self, query: &str, filtered_files: &[PathBuf], threshold: f32) -> Result<Vec<SearchResult>, String> {
-        // Implementation would perform fuzzy search
-        Ok(Vec::new())
+        // Best similarity of each indexed word to any word of the query
+        let mut scores: HashMap<&str, f32> = HashMap::new();
+        for term in words(query) {
+            for word in self.inverted_index.keys() {
+                let score = similarity(&term, word);
+                if score >= threshold {
+                    let best = scores.entry(word.as_str()).or_insert(0.0);
+                    *best = best.max(score);
+                }
+            }
+        }
+        if scores.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let allowed: HashSet<&PathBuf> = filtered_files.iter().collect();
+        let mut files: Vec<&PathBuf> = scores
+            .keys()
+            .flat_map(|word| &self.inverted_index[*word])
+            .map(|reference| &reference.file_path)
+            .filter(|path| allowed.contains(path))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        files.sort();
+
+        let alternatives: Vec<String> = scores.keys().map(|word| regex::escape(word)).collect();
+        let regex = Regex::new(&format!(r"(?i)\b(?:{})\b", alternatives.join("|"))).map_err(|e| e.to_string())?;
+        Ok(self.find_matches(&regex, files, |found| scores.get(found.to_lowercase().as_str()).copied().unwrap_or(0.0)))
     }
 
-    async fn index_file(&mut self, file_path: &Path) -> Result<(), String> {
-        // Implementation would index file content
-        Ok(())
+    /// Add or replace a file in the index
+    fn index_text(&mut self, path: &Path, content: &str, content_hash: String, last_modified: SystemTime) {
+        self.remove_file(path);
+
+        let reference = || DocumentReference {
+            file_path: path.to_path_buf(),
+            positions: Vec::new(),
+            relevance: 1.0,
+            context: None,
+        };
+        for gram in trigrams(content) {
+            self.ngram_index.entry(gram).or_default().push(reference());
+        }
+        let file_words = words(content);
+        self.statistics.total_tokens += file_words.len();
+        for word in file_words {
+            self.inverted_index.entry(word).or_default().push(reference());
+        }
+
+        let file_type = file_type_of(path);
+        self.indexed_files.insert(path.to_path_buf(), IndexedFile::new(path, content_hash, last_modified, file_type, content.len() as u64));
+        self.updated();
+    }
+
+    /// Drop a file from the index; returns whether it was indexed
+    fn remove_file(&mut self, path: &Path) -> bool {
+        let Some(file) = self.indexed_files.remove(path) else { return false };
+        for postings in [&mut self.ngram_index, &mut self.inverted_index] {
+            postings.retain(|_, references| {
+                references.retain(|reference| reference.file_path != file.path);
+                !references.is_empty()
+            });
+        }
+        self.updated();
+        true
+    }
+
+    fn updated(&mut self) {
+        self.statistics.total_files = self.indexed_files.len();
+        self.statistics.unique_tokens = self.inverted_index.len();
+        self.statistics.index_size_bytes = self.indexed_files.values().map(|file| file.metadata.size).sum();
+        self.statistics.last_update = Instant::now();
+        self.version += 1;
+    }
+
+    /// Indexed files among `filtered_files` that contain all of `grams`
+    fn candidates(&self, grams: &HashSet<String>, filtered_files: &[PathBuf]) -> Vec<&PathBuf> {
+        let allowed: HashSet<&PathBuf> = filtered_files.iter().collect();
+        let mut candidates: Vec<&PathBuf> = self.indexed_files.keys().filter(|path| allowed.contains(path)).collect();
+        // The trigram index is lowercased, so it also narrows case-sensitive queries
+        for gram in grams.iter().map(|gram| gram.to_lowercase()) {
+            let Some(references) = self.ngram_index.get(&gram) else { return Vec::new() };
+            let containing: HashSet<&PathBuf> = references.iter().map(|reference| &reference.file_path).collect();
+            candidates.retain(|path| containing.contains(path));
+        }
+        candidates.sort();
+        candidates
+    }
+
+    /// Every match of `regex` in `files`, read back from disk
+    fn find_matches(&self, regex: &Regex, files: Vec<&PathBuf>, relevance: impl Fn(&str) -> f32) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+        for path in files {
+            let Ok(content) = std::fs::read_to_string(path) else { continue };
+            for found in regex.find_iter(&content).filter(|found| !found.is_empty()) {
+                let line_start = content[..found.start()].rfind('\n').map_or(0, |i| i + 1);
+                let line_end = content[found.end()..].find('\n').map_or(content.len(), |i| found.end() + i);
+                let line = content[line_start..line_end].trim_end_matches('\r');
+                results.push(SearchResult {
+                    file_path: path.clone(),
+                    location: TextPosition {
+                        line: content[..line_start].matches('\n').count(),
+                        column: content[line_start..found.start()].chars().count(),
+                        offset: found.start(),
+                        length: found.len(),
+                    },
+                    context: line.to_string(),
+                    relevance: relevance(found.as_str()),
+                    result_type: SearchResultType::TextMatch,
+                    snippet: line.trim().chars().take(200).collect(),
+                    metadata: HashMap::new(),
+                });
+            }
+        }
+        results
+    }
+}
+
+impl IndexedFile {
+    /// Index entry for a file; the content itself is read from disk when searched
+    fn new(path: &Path, content_hash: String, last_modified: SystemTime, file_type: FileType, size: u64) -> Self {
+        let language = match file_type {
+            FileType::Text | FileType::Binary | FileType::Unknown => None,
+            ref code => Some(format!("{:?}", code).to_lowercase()),
+        };
+        Self {
+            path: path.to_path_buf(),
+            content_hash,
+            last_modified,
+            file_type,
+            content: String::new(),
+            line_boundaries: Vec::new(),
+            metadata: FileMetadata {
+                size,
+                encoding: "utf-8".to_string(),
+                language,
+                tags: Vec::new(),
+                custom_fields: HashMap::new(),
+            },
+        }
     }
 }
 
@@ -1221,8 +1695,35 @@ impl SemanticSearchEngine {
     }
 
     fn get_suggestions(&self, partial_query: &str, context: &SearchContext) -> Vec<SearchSuggestion> {
-        // Implementation would generate semantic suggestions
-        Vec::new()
+        let partial = partial_query.to_lowercase();
+        let mut suggestions = Vec::new();
+
+        // The selection is usually what the user is about to look for
+        if let Some(selected) = context.selected_text.as_deref().map(str::trim) {
+            if !selected.is_empty() && selected.len() <= 100 && !selected.contains('\n') && selected.to_lowercase().contains(&partial) {
+                suggestions.push(SearchSuggestion {
+                    text: selected.to_string(),
+                    suggestion_type: SuggestionType::ContextBased,
+                    relevance: 0.9,
+                    description: Some("Selected text".to_string()),
+                    metadata: HashMap::new(),
+                });
+            }
+        }
+
+        for (rank, file) in context.recent_files.iter().enumerate() {
+            let Some(stem) = file.file_stem().and_then(|stem| stem.to_str()) else { continue };
+            if !partial.is_empty() && stem.to_lowercase().starts_with(&partial) {
+                suggestions.push(SearchSuggestion {
+                    text: stem.to_string(),
+                    suggestion_type: SuggestionType::ContextBased,
+                    relevance: 0.5 / (rank + 1) as f32,
+                    description: Some(format!("Recently opened {}", file.display())),
+                    metadata: HashMap::new(),
+                });
+            }
+        }
+        suggestions
     }
 }
 
@@ -1243,22 +1744,213 @@ impl SymbolIndex {
                 call_graph: HashMap::new(),
                 usage_graph: HashMap::new(),
             },
+            file_symbols: HashMap::new(),
         }
     }
 
     async fn search(&self, query: &str, filtered_files: &[PathBuf]) -> Result<Vec<SearchResult>, String> {
-        // Implementation would search symbols
-        Ok(Vec::new())
+        let allowed: HashSet<&PathBuf> = filtered_files.iter().collect();
+        Ok(self.find(query, Some(&allowed)))
     }
 
-    async fn index_file(&mut self, file_path: &Path) -> Result<(), String> {
-        // Implementation would extract and index symbols
-        Ok(())
+    /// Definitions matching `query`, best first
+    ///
+    /// Exact names rank above prefixes, prefixes above substrings; a query
+    /// containing `::` is matched against qualified names.
+    fn find(&self, query: &str, allowed: Option<&HashSet<&PathBuf>>) -> Vec<SearchResult> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let lowered = query.to_lowercase();
+        let qualified_query = query.contains("::");
+
+        let mut results = Vec::new();
+        for (name, definitions) in &self.definitions {
+            for definition in definitions {
+                let symbol = &definition.symbol;
+                let candidate = if qualified_query { qualified_name(&symbol.scope, name) } else { name.clone() };
+                let lowered_candidate = candidate.to_lowercase();
+                let relevance = if candidate == query || (qualified_query && candidate.ends_with(&format!("::{}", query))) {
+                    1.0
+                } else if lowered_candidate == lowered {
+                    0.95
+                } else if lowered_candidate.starts_with(&lowered) {
+                    0.8
+                } else if lowered_candidate.contains(&lowered) {
+                    0.6
+                } else {
+                    continue;
+                };
+                if allowed.is_some_and(|allowed| !allowed.contains(&symbol.definition.file_path)) {
+                    continue;
+                }
+
+                let mut metadata = HashMap::new();
+                metadata.insert("kind".to_string(), format!("{:?}", symbol.kind));
+                metadata.insert("scope".to_string(), symbol.scope.clone());
+                results.push(SearchResult {
+                    file_path: symbol.definition.file_path.clone(),
+                    location: symbol.definition.position.clone(),
+                    context: qualified_name(&symbol.scope, name),
+                    relevance,
+                    result_type: SearchResultType::SymbolDefinition,
+                    snippet: definition.signature.clone().unwrap_or_else(|| name.clone()),
+                    metadata,
+                });
+            }
+        }
+        results.sort_by(|a, b| {
+            b.relevance
+                .partial_cmp(&a.relevance)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.context.len().cmp(&b.context.len()))
+                .then_with(|| a.context.cmp(&b.context))
+        });
+        results
+    }
+
+    /// Add or replace the symbols of one file
+    fn index_symbols(&mut self, path: &Path, symbols: FileSymbols) {
+        self.remove_file(path);
+
+        for definition in &symbols.definitions {
+            let symbol = &definition.symbol;
+            let qualified = qualified_name(&symbol.scope, &symbol.name);
+            self.symbols.insert(qualified.clone(), symbol.clone());
+            self.definitions.entry(symbol.name.clone()).or_default().push(definition.clone());
+            self.hierarchy.hierarchy.entry(symbol.scope.clone()).or_default().push(qualified.clone());
+            self.hierarchy.containment.insert(qualified.clone(), symbol.scope.clone());
+
+            let scope_type = match symbol.kind {
+                SymbolKind::Module => ScopeType::Module,
+                SymbolKind::Type | SymbolKind::Enum | SymbolKind::Interface | SymbolKind::Class => ScopeType::Class,
+                SymbolKind::Function | SymbolKind::Method => ScopeType::Function,
+                _ => continue,
+            };
+            self.hierarchy.scopes.insert(
+                qualified,
+                ScopeInfo {
+                    name: symbol.name.clone(),
+                    scope_type,
+                    location: symbol.definition.clone(),
+                    parent_scope: (!symbol.scope.is_empty()).then(|| symbol.scope.clone()),
+                },
+            );
+        }
+
+        for reference in &symbols.references {
+            self.references.entry(reference.symbol_name.clone()).or_default().push(reference.clone());
+        }
+
+        let graph = &mut self.xref_graph;
+        for edge in &symbols.edges {
+            graph.dependencies.entry(edge.from.clone()).or_default().push(edge.to.clone());
+            graph.dependents.entry(edge.to.clone()).or_default().push(edge.from.clone());
+            let uses = if edge.reference_type == ReferenceType::Call { &mut graph.call_graph } else { &mut graph.usage_graph };
+            uses.entry(edge.from.clone()).or_default().push(edge.to.clone());
+        }
+
+        self.file_symbols.insert(path.to_path_buf(), symbols);
+    }
+
+    /// Drop the symbols of one file; returns whether it had any indexed
+    fn remove_file(&mut self, path: &Path) -> bool {
+        let Some(symbols) = self.file_symbols.remove(path) else { return false };
+
+        for definition in &symbols.definitions {
+            let symbol = &definition.symbol;
+            let qualified = qualified_name(&symbol.scope, &symbol.name);
+            if self.symbols.get(&qualified).is_some_and(|indexed| indexed.definition.file_path == path) {
+                self.symbols.remove(&qualified);
+                self.hierarchy.scopes.remove(&qualified);
+                self.hierarchy.containment.remove(&qualified);
+            }
+            remove_entry(&mut self.definitions, &symbol.name, |indexed| indexed.symbol.definition.file_path == path);
+            remove_first(&mut self.hierarchy.hierarchy, &symbol.scope, &qualified);
+        }
+
+        for reference in &symbols.references {
+            remove_entry(&mut self.references, &reference.symbol_name, |indexed| indexed.location.file_path == path);
+        }
+
+        // Edges are unique per file, so each one accounts for a single entry
+        let graph = &mut self.xref_graph;
+        for edge in &symbols.edges {
+            remove_first(&mut graph.dependencies, &edge.from, &edge.to);
+            remove_first(&mut graph.dependents, &edge.to, &edge.from);
+            let uses = if edge.reference_type == ReferenceType::Call { &mut graph.call_graph } else { &mut graph.usage_graph };
+            remove_first(uses, &edge.from, &edge.to);
+        }
+        true
     }
 
     fn get_suggestions(&self, partial_query: &str) -> Vec<SearchSuggestion> {
-        // Implementation would generate symbol-based suggestions
-        Vec::new()
+        let partial = partial_query.trim().to_lowercase();
+        if partial.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<(&String, &SymbolDefinition, usize)> = self
+            .definitions
+            .iter()
+            .filter(|(name, _)| name.to_lowercase().starts_with(&partial))
+            .filter_map(|(name, definitions)| {
+                let uses = self.references.get(name).map_or(0, Vec::len);
+                definitions.first().map(|definition| (name, definition, uses))
+            })
+            .collect();
+        // Widely used symbols first
+        matches.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(b.0)));
+
+        let most_used = matches.first().map_or(0, |(_, _, uses)| *uses).max(1) as f32;
+        matches
+            .into_iter()
+            .take(MAX_SYMBOL_SUGGESTIONS)
+            .map(|(name, definition, uses)| {
+                let symbol = &definition.symbol;
+                let mut metadata = HashMap::new();
+                metadata.insert("kind".to_string(), format!("{:?}", symbol.kind));
+                metadata.insert("file".to_string(), symbol.definition.file_path.display().to_string());
+                SearchSuggestion {
+                    text: name.clone(),
+                    suggestion_type: SuggestionType::SymbolBased,
+                    relevance: 0.5 + 0.4 * uses as f32 / most_used,
+                    description: Some(format!("{:?} in {}", symbol.kind, symbol.scope)),
+                    metadata,
+                }
+            })
+            .collect()
+    }
+}
+
+fn qualified_name(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}::{}", scope, name)
+    }
+}
+
+/// Remove the values of `key` matching `predicate`, dropping the key once empty
+fn remove_entry<T>(map: &mut HashMap<String, Vec<T>>, key: &str, predicate: impl Fn(&T) -> bool) {
+    if let Some(values) = map.get_mut(key) {
+        values.retain(|value| !predicate(value));
+        if values.is_empty() {
+            map.remove(key);
+        }
+    }
+}
+
+/// Remove one occurrence of `value` under `key`
+fn remove_first(map: &mut HashMap<String, Vec<String>>, key: &str, value: &str) {
+    if let Some(values) = map.get_mut(key) {
+        if let Some(index) = values.iter().position(|existing| existing == value) {
+            values.remove(index);
+        }
+        if values.is_empty() {
+            map.remove(key);
+        }
     }
 }
 
@@ -1276,7 +1968,7 @@ impl SearchHistoryManager {
         let entry = SearchHistoryEntry {
             query: query.to_string(),
             search_type,
-            timestamp: Instant::now(),
+            timestamp: SystemTime::now(),
             results_count: 0,
             execution_time: Duration::from_millis(0),
             filters: filters.iter().map(|f| f.name.clone()).collect(),
@@ -1292,8 +1984,33 @@ impl SearchHistoryManager {
     }
 
     fn get_suggestions(&self, partial_query: &str) -> Vec<SearchSuggestion> {
-        // Implementation would generate history-based suggestions
-        Vec::new()
+        let partial = partial_query.trim().to_lowercase();
+        let most_frequent = self.search_frequency.values().copied().max().unwrap_or(1) as f32;
+
+        let mut suggestions: Vec<SearchSuggestion> = self
+            .search_frequency
+            .iter()
+            .filter(|(query, _)| {
+                let query = query.to_lowercase();
+                query != partial && query.contains(&partial)
+            })
+            .map(|(query, count)| {
+                // Prefix matches and frequent searches rank higher
+                let prefix_bonus = if query.to_lowercase().starts_with(&partial) { 0.2 } else { 0.0 };
+                SearchSuggestion {
+                    text: query.clone(),
+                    suggestion_type: SuggestionType::HistoryBased,
+                    relevance: 0.3 + prefix_bonus + 0.4 * *count as f32 / most_frequent,
+                    description: Some(format!("Searched {} time{}", count, if *count == 1 { "" } else { "s" })),
+                    metadata: HashMap::new(),
+                }
+            })
+            .collect();
+        suggestions.sort_by(|a, b| {
+            b.relevance.partial_cmp(&a.relevance).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.text.cmp(&b.text))
+        });
+        suggestions.truncate(MAX_HISTORY_SUGGESTIONS);
+        suggestions
     }
 }
 
@@ -1329,11 +2046,77 @@ impl FilterManager {
     }
 
     async fn apply_filters(&self, filters: &[SearchFilter], indexed_files: &HashMap<PathBuf, IndexedFile>) -> Result<Vec<PathBuf>, String> {
-        // Implementation would apply filters to file list
-        Ok(indexed_files.keys().cloned().collect())
+        let active: Vec<&SearchFilter> = filters.iter().filter(|filter| filter.active).collect();
+        let mut files = Vec::new();
+        for (path, file) in indexed_files {
+            let mut keep = true;
+            for filter in &active {
+                if !Self::matches(filter, file)? {
+                    keep = false;
+                    break;
+                }
+            }
+            if keep {
+                files.push(path.clone());
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Whether `file` passes `filter`
+    fn matches(filter: &SearchFilter, file: &IndexedFile) -> Result<bool, String> {
+        let modified = file.last_modified.duration_since(std::time::UNIX_EPOCH).map_or(0, |age| age.as_secs());
+        let value = match &filter.filter_type {
+            FilterType::FileType => {
+                let expected = filter.value.to_lowercase();
+                let type_name = format!("{:?}", file.file_type).to_lowercase();
+                let extension = file.path.extension().and_then(|ext| ext.to_str()).unwrap_or_default().to_lowercase();
+                // Either the type name or the extension may be given
+                let by_name = compare(&filter.operator, &type_name, &expected)?;
+                let by_extension = compare(&filter.operator, &extension, &expected)?;
+                let negated = matches!(filter.operator, FilterOperator::NotEquals | FilterOperator::NotContains | FilterOperator::NotIn);
+                return Ok(if negated { by_name && by_extension } else { by_name || by_extension });
+            }
+            FilterType::FileSize => file.metadata.size.to_string(),
+            FilterType::ModificationDate => modified.to_string(),
+            FilterType::Path => file.path.to_string_lossy().replace('\\', "/"),
+            FilterType::Language => file.metadata.language.clone().unwrap_or_default(),
+            FilterType::ContentType => file.metadata.encoding.clone(),
+            FilterType::Author => file.metadata.custom_fields.get("author").cloned().unwrap_or_default(),
+            FilterType::Custom(field) => file.metadata.custom_fields.get(field).cloned().unwrap_or_default(),
+        };
+        compare(&filter.operator, &value, &filter.value)
     }
 }
 
+/// Compare a file attribute with a filter value
+///
+/// Ordering operators compare numerically; `Between` takes `low..high` and
+/// `In`/`NotIn` take a comma separated list.
+fn compare(operator: &FilterOperator, actual: &str, expected: &str) -> Result<bool, String> {
+    let number = |text: &str| text.trim().parse::<f64>().map_err(|_| format!("Filter value {:?} is not a number", text));
+    let list = || expected.split(',').map(str::trim).any(|item| item == actual);
+    Ok(match operator {
+        FilterOperator::Equals => actual == expected,
+        FilterOperator::NotEquals => actual != expected,
+        FilterOperator::Contains => actual.contains(expected),
+        FilterOperator::NotContains => !actual.contains(expected),
+        FilterOperator::StartsWith => actual.starts_with(expected),
+        FilterOperator::EndsWith => actual.ends_with(expected),
+        FilterOperator::GreaterThan => number(actual)? > number(expected)?,
+        FilterOperator::LessThan => number(actual)? < number(expected)?,
+        FilterOperator::Between => {
+            let (low, high) = expected
+                .split_once("..")
+                .ok_or_else(|| format!("Between filter expects low..high, got {:?}", expected))?;
+            (number(low)?..=number(high)?).contains(&number(actual)?)
+        }
+        FilterOperator::In => list(),
+        FilterOperator::NotIn => !list(),
+    })
+}
+
 impl SearchResults {
     fn new() -> Self {
         Self {
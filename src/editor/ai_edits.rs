@@ -0,0 +1,546 @@
+//! AI Edits as Reviewable Workspace Diffs
+//!
+//! Instead of code in chat text, the AI agent asks the model for structured
+//! edits: a file, a 1-based line range, the text currently on those lines and
+//! its replacement. [`parse_edits`] reads them from the response,
+//! [`EditWorkspace::validate`] checks each one against the current
+//! [`TextBuffer`] contents, and the [`EditReview`] window lets the user accept
+//! or reject every resulting hunk. Accepted hunks are applied as one undo step
+//! per file and are undone together.
+//!
+//! After applying, [`new_errors`] picks out the compiler errors the edits
+//! introduced and [`error_feedback`] turns them into a follow-up request.
+
+use crate::editor::build_system::{CompilerMessage, MessageLevel};
+use crate::editor::text_buffer::{LineEnding, SelectionSet, TextBuffer, TextBufferError, TextRange};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+/// Response format the model is asked to follow
+pub const EDIT_FORMAT: &str = r#"Answer only with a JSON object of this form:
+{"explanation": "what the edits do", "edits": [{"file": "src/lib.rs", "start_line": 3, "end_line": 4, "original": "exact current text of lines 3 to 4", "replacement": "new text for those lines"}]}
+Line numbers are 1-based and inclusive and refer to the files as given. To insert without replacing anything, set end_line to start_line - 1 and original to "". To create a file, use start_line 1, end_line 0 and an empty original. Edits must not overlap."#;
+
+/// An edit as proposed by the model
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ProposedEdit {
+    /// Workspace-relative path
+    pub file: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Text the model believes is on those lines, used to check and relocate the edit
+    #[serde(default)]
+    pub original: Option<String>,
+    pub replacement: String,
+}
+
+/// Edits parsed from a model response
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct EditResponse {
+    #[serde(default)]
+    pub explanation: String,
+    #[serde(default)]
+    pub edits: Vec<ProposedEdit>,
+}
+
+/// Problems with proposed or applied edits
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum AiEditError {
+    #[error("The response does not contain edits: {0}")]
+    Malformed(String),
+    #[error("{0} is outside the workspace")]
+    OutsideWorkspace(String),
+    #[error("Cannot read {file}: {message}")]
+    Unreadable { file: String, message: String },
+    #[error("Lines {start_line}-{end_line} are out of range for {file}, which has {line_count} lines")]
+    LinesOutOfRange { file: String, start_line: usize, end_line: usize, line_count: usize },
+    #[error("The original text does not match lines {start_line}-{end_line} of {file}")]
+    OriginalMismatch { file: String, start_line: usize, end_line: usize },
+    #[error("Edit at line {line} of {file} overlaps an earlier edit")]
+    Overlap { file: String, line: usize },
+    #[error("{0} changed since the edits were proposed")]
+    Stale(String),
+    #[error("Buffer rejected the edit: {0}")]
+    Buffer(String),
+    #[error("No AI edits to undo")]
+    NothingToUndo,
+}
+
+impl From<TextBufferError> for AiEditError {
+    fn from(error: TextBufferError) -> Self {
+        AiEditError::Buffer(error.to_string())
+    }
+}
+
+/// Whether the user wants a hunk applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HunkDecision {
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+/// A validated edit, located in the buffer it applies to
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditHunk {
+    /// Path as named by the model
+    pub file: String,
+    pub path: PathBuf,
+    /// 1-based line the hunk starts at
+    pub start_line: usize,
+    pub range: TextRange,
+    /// Current text of `range`
+    pub original: String,
+    pub replacement: String,
+    pub decision: HunkDecision,
+}
+
+/// One line of a hunk's diff
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    Context(String),
+    Removed(String),
+    Added(String),
+}
+
+impl EditHunk {
+    /// Lines of the hunk, with unchanged leading and trailing lines as context
+    pub fn diff(&self) -> Vec<DiffLine> {
+        let old: Vec<&str> = self.original.lines().collect();
+        let new: Vec<&str> = self.replacement.lines().collect();
+        let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+        let suffix = old[prefix..].iter().rev().zip(new[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+
+        let context = |lines: &[&str]| lines.iter().map(|line| DiffLine::Context(line.to_string())).collect::<Vec<_>>();
+        let mut diff = context(&old[..prefix]);
+        diff.extend(old[prefix..old.len() - suffix].iter().map(|line| DiffLine::Removed(line.to_string())));
+        diff.extend(new[prefix..new.len() - suffix].iter().map(|line| DiffLine::Added(line.to_string())));
+        diff.extend(context(&old[old.len() - suffix..]));
+        diff
+    }
+}
+
+/// Validated edits awaiting review
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EditProposal {
+    pub explanation: String,
+    pub hunks: Vec<EditHunk>,
+    /// Proposed edits that failed validation
+    pub invalid: Vec<(ProposedEdit, AiEditError)>,
+}
+
+impl EditProposal {
+    /// Set every hunk's decision
+    pub fn decide_all(&mut self, decision: HunkDecision) {
+        self.hunks.iter_mut().for_each(|hunk| hunk.decision = decision);
+    }
+
+    /// Hunks the user accepted
+    pub fn accepted(&self) -> impl Iterator<Item = &EditHunk> {
+        self.hunks.iter().filter(|hunk| hunk.decision == HunkDecision::Accepted)
+    }
+}
+
+/// Read the edits from a model response
+///
+/// The JSON object may be wrapped in a code fence or surrounded by prose.
+pub fn parse_edits(response: &str) -> Result<EditResponse, AiEditError> {
+    let fenced = response.split("```").skip(1).step_by(2)
+        .map(|block| block.strip_prefix("json").unwrap_or(block).trim())
+        .find(|block| block.starts_with('{'));
+    let json = match fenced {
+        Some(block) => block,
+        None => {
+            let start = response.find('{').ok_or_else(|| AiEditError::Malformed("no JSON object found".to_string()))?;
+            let end = response.rfind('}').filter(|end| *end > start)
+                .ok_or_else(|| AiEditError::Malformed("unterminated JSON object".to_string()))?;
+            &response[start..=end]
+        }
+    };
+    serde_json::from_str(json).map_err(|e| AiEditError::Malformed(e.to_string()))
+}
+
+/// Build the prompt asking for edits to `files`, given as workspace-relative
+/// path and current text
+///
+/// Lines are numbered so the model can refer to them.
+pub fn edit_prompt(instruction: &str, files: &[(String, String)]) -> String {
+    let mut prompt = format!("{}\n", instruction);
+    for (file, text) in files {
+        prompt.push_str(&format!("\nFile {}:\n", file));
+        for (number, line) in text.lines().enumerate() {
+            prompt.push_str(&format!("{:>4}| {}\n", number + 1, line));
+        }
+    }
+    prompt
+}
+
+/// Errors in `after` that were not already reported in `before`
+///
+/// Diagnostics are matched by file, code and message because the edits move
+/// line numbers around.
+pub fn new_errors(before: &[CompilerMessage], after: &[CompilerMessage]) -> Vec<CompilerMessage> {
+    let key = |message: &CompilerMessage| (message.file.clone(), message.code.clone(), message.message.clone());
+    let mut known: HashMap<_, usize> = HashMap::new();
+    for message in before.iter().filter(|message| message.level == MessageLevel::Error) {
+        *known.entry(key(message)).or_default() += 1;
+    }
+    after.iter()
+        .filter(|message| message.level == MessageLevel::Error)
+        .filter(|message| match known.get_mut(&key(message)) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Instruction asking the model to fix the errors its edits introduced
+pub fn error_feedback(errors: &[CompilerMessage]) -> String {
+    let mut feedback = String::from("Applying your edits introduced these compiler errors. Propose edits that fix them.\n");
+    for error in errors {
+        match &error.rendered {
+            Some(rendered) => feedback.push_str(&format!("\n{}", rendered.trim_end())),
+            None => {
+                let location = match (&error.file, error.line) {
+                    (Some(file), Some(line)) => format!("{}:{}: ", file, line),
+                    _ => String::new(),
+                };
+                feedback.push_str(&format!("\n{}{}", location, error.message));
+            }
+        }
+        feedback.push('\n');
+    }
+    feedback
+}
+
+/// Text buffers of the workspace files AI edits are checked against and applied to
+pub struct EditWorkspace {
+    root: PathBuf,
+    buffers: HashMap<PathBuf, TextBuffer>,
+    /// Files changed by each apply, most recent last
+    applied: Vec<Vec<PathBuf>>,
+}
+
+impl EditWorkspace {
+    /// Create a workspace for files under `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into(), buffers: HashMap::new(), applied: Vec::new() }
+    }
+
+    /// Directory relative paths are resolved against
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Move the workspace to another project, forgetting all buffers
+    pub fn set_root(&mut self, root: impl Into<PathBuf>) {
+        let root = root.into();
+        if root != self.root {
+            *self = Self::new(root);
+        }
+    }
+
+    /// Path of a workspace-relative `file`, `None` if it would leave the workspace
+    pub fn resolve(&self, file: &str) -> Option<PathBuf> {
+        let path = Path::new(file);
+        if path.is_absolute() {
+            return path.starts_with(&self.root).then(|| path.to_path_buf());
+        }
+        path.components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+            .then(|| self.root.join(path.components().filter(|component| *component != Component::CurDir).collect::<PathBuf>()))
+    }
+
+    /// Path of `path` relative to the workspace, as shown to the model
+    pub fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root).unwrap_or(path).to_string_lossy().replace('\\', "/")
+    }
+
+    /// Track `path` with the text the editor currently shows
+    ///
+    /// A buffer whose text differs is replaced, which also drops the AI edits
+    /// on it from the undo history since the user has edited on top of them.
+    pub fn sync(&mut self, path: &Path, text: &str) {
+        if self.buffers.get(path).is_some_and(|buffer| buffer.to_string() == text) {
+            return;
+        }
+        let mut buffer = TextBuffer::from_string(text.to_string());
+        buffer.file_path = Some(path.to_path_buf());
+        self.buffers.insert(path.to_path_buf(), buffer);
+        self.applied.retain(|paths| !paths.iter().any(|applied| applied == path));
+    }
+
+    /// Buffer tracked for `path`
+    pub fn buffer(&self, path: &Path) -> Option<&TextBuffer> {
+        self.buffers.get(path)
+    }
+
+    /// Check `response` against the current buffers, loading untracked files from disk
+    pub fn validate(&mut self, response: EditResponse) -> EditProposal {
+        let mut proposal = EditProposal { explanation: response.explanation, ..Default::default() };
+        for edit in response.edits {
+            match self.locate(&edit) {
+                Ok(hunk) => proposal.hunks.push(hunk),
+                Err(error) => proposal.invalid.push((edit, error)),
+            }
+        }
+
+        // Keep the first of any overlapping hunks, in file order
+        proposal.hunks.sort_by(|a, b| (&a.path, a.range.start.offset, a.range.end.offset).cmp(&(&b.path, b.range.start.offset, b.range.end.offset)));
+        let mut hunks: Vec<EditHunk> = Vec::with_capacity(proposal.hunks.len());
+        for hunk in proposal.hunks.drain(..) {
+            let overlaps = hunks.last().is_some_and(|previous| {
+                previous.path == hunk.path && previous.range.end.offset > hunk.range.start.offset
+            });
+            if overlaps {
+                let error = AiEditError::Overlap { file: hunk.file.clone(), line: hunk.start_line };
+                proposal.invalid.push((proposed(&hunk), error));
+            } else {
+                hunks.push(hunk);
+            }
+        }
+        proposal.hunks = hunks;
+        proposal
+    }
+
+    /// Apply the accepted hunks of `proposal`, returning the files changed
+    ///
+    /// Every hunk is checked before anything changes, so a stale hunk leaves
+    /// all buffers untouched.
+    pub fn apply(&mut self, proposal: &EditProposal) -> Result<Vec<PathBuf>, AiEditError> {
+        let mut by_file: BTreeMap<&Path, Vec<&EditHunk>> = BTreeMap::new();
+        for hunk in proposal.accepted() {
+            by_file.entry(&hunk.path).or_default().push(hunk);
+        }
+        for (path, hunks) in &by_file {
+            let empty = TextBuffer::new();
+            let buffer = self.buffers.get(*path).unwrap_or(&empty);
+            for hunk in hunks {
+                if buffer.slice(hunk.range.clone()).ok().as_deref() != Some(hunk.original.as_str()) {
+                    return Err(AiEditError::Stale(hunk.file.clone()));
+                }
+            }
+        }
+
+        let mut changed = Vec::new();
+        for (path, hunks) in by_file {
+            let buffer = self.buffers.entry(path.to_path_buf()).or_insert_with(|| {
+                let mut buffer = TextBuffer::new();
+                buffer.file_path = Some(path.to_path_buf());
+                buffer
+            });
+            let replacements: Vec<(TextRange, String)> = hunks.iter().map(|hunk| (hunk.range.clone(), hunk.replacement.clone())).collect();
+            buffer.undo_stack.begin_transaction("AI edit".to_string());
+            let result = buffer.replace_ranges(&replacements, SelectionSet::new());
+            buffer.undo_stack.end_transaction();
+            result?;
+            changed.push(path.to_path_buf());
+        }
+        if !changed.is_empty() {
+            self.applied.push(changed.clone());
+        }
+        Ok(changed)
+    }
+
+    /// Whether an applied set of AI edits can be undone
+    pub fn can_undo(&self) -> bool {
+        !self.applied.is_empty()
+    }
+
+    /// Undo the most recently applied edits in every file they touched
+    pub fn undo(&mut self) -> Result<Vec<PathBuf>, AiEditError> {
+        let paths = self.applied.pop().ok_or(AiEditError::NothingToUndo)?;
+        for path in &paths {
+            if let Some(buffer) = self.buffers.get_mut(path) {
+                buffer.undo()?;
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Turn a proposed edit into a hunk of the file's current text
+    fn locate(&mut self, edit: &ProposedEdit) -> Result<EditHunk, AiEditError> {
+        let path = self.resolve(&edit.file).ok_or_else(|| AiEditError::OutsideWorkspace(edit.file.clone()))?;
+        if !self.buffers.contains_key(&path) && path.exists() {
+            let buffer = TextBuffer::from_file(path.clone())
+                .map_err(|e| AiEditError::Unreadable { file: edit.file.clone(), message: e.to_string() })?;
+            self.buffers.insert(path.clone(), buffer);
+        }
+        // Files that do not exist yet are validated as empty
+        let empty = TextBuffer::new();
+        let buffer = self.buffers.get(&path).unwrap_or(&empty);
+        let lines = buffer_lines(buffer);
+
+        let mut start_line = edit.start_line;
+        let length = (edit.end_line + 1).saturating_sub(edit.start_line);
+        let in_range = start_line >= 1 && edit.end_line + 1 >= start_line && edit.end_line <= lines.len();
+        if !in_range {
+            return Err(AiEditError::LinesOutOfRange {
+                file: edit.file.clone(),
+                start_line: edit.start_line,
+                end_line: edit.end_line,
+                line_count: lines.len(),
+            });
+        }
+        if let Some(original) = &edit.original {
+            let expected: Vec<&str> = original.lines().map(str::trim_end).collect();
+            let matches_at = |start: usize| {
+                lines[start - 1..start - 1 + expected.len()].iter().map(|line| line.trim_end()).eq(expected.iter().copied())
+            };
+            if expected.len() != length || !matches_at(start_line) {
+                // Models often miscount lines; accept the text if it occurs exactly once
+                let candidates: Vec<usize> = (1..=(lines.len() + 1).saturating_sub(expected.len()))
+                    .filter(|start| !expected.is_empty() && matches_at(*start))
+                    .collect();
+                match candidates[..] {
+                    [start] => start_line = start,
+                    _ => {
+                        return Err(AiEditError::OriginalMismatch {
+                            file: edit.file.clone(),
+                            start_line: edit.start_line,
+                            end_line: edit.end_line,
+                        })
+                    }
+                }
+            }
+        }
+        let end_line = start_line + edit.original.as_ref().map_or(length, |original| original.lines().count()) - 1;
+
+        let rope = &buffer.rope;
+        let start = rope.line_to_byte(start_line - 1);
+        let end = if end_line < rope.len_lines() { rope.line_to_byte(end_line) } else { rope.len_bytes() };
+        let original = rope.byte_slice(start..end).to_string();
+        let range = TextRange { start: buffer.offset_to_position(start)?, end: buffer.offset_to_position(end)? };
+        Ok(EditHunk {
+            file: edit.file.clone(),
+            path,
+            start_line,
+            replacement: fit_replacement(buffer, &edit.replacement, &original, end),
+            range,
+            original,
+            decision: HunkDecision::Pending,
+        })
+    }
+}
+
+/// Lines of a buffer without their line endings
+fn buffer_lines(buffer: &TextBuffer) -> Vec<String> {
+    let text = buffer.to_string();
+    text.lines().map(str::to_string).collect()
+}
+
+/// Adjust replacement text to the line structure and line endings around it
+fn fit_replacement(buffer: &TextBuffer, replacement: &str, original: &str, end: usize) -> String {
+    let mut text = replacement.replace("\r\n", "\n");
+    let length = buffer.rope.len_bytes();
+    let ends_with_newline = length > 0 && buffer.rope.byte(length - 1) == b'\n';
+    if !text.is_empty() {
+        if end == length && length > 0 && !ends_with_newline && original.is_empty() {
+            // Appending to a file without a final newline
+            text.insert(0, '\n');
+        } else if !text.ends_with('\n') && (original.ends_with('\n') || end < length || ends_with_newline || length == 0) {
+            text.push('\n');
+        }
+    }
+    if buffer.line_ending == LineEnding::Windows {
+        text = text.replace('\n', "\r\n");
+    }
+    text
+}
+
+/// The edit a hunk was made from
+fn proposed(hunk: &EditHunk) -> ProposedEdit {
+    ProposedEdit {
+        file: hunk.file.clone(),
+        start_line: hunk.start_line,
+        end_line: hunk.start_line + hunk.original.lines().count() - 1,
+        original: Some(hunk.original.clone()),
+        replacement: hunk.replacement.clone(),
+    }
+}
+
+/// What the user chose in the review window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditReviewAction {
+    /// Apply the accepted hunks
+    Apply,
+    /// Drop the proposal
+    Discard,
+}
+
+/// Window showing each hunk of a proposal as a diff to accept or reject
+pub struct EditReview {
+    pub proposal: EditProposal,
+}
+
+impl EditReview {
+    /// Create a review of `proposal`
+    pub fn new(proposal: EditProposal) -> Self {
+        Self { proposal }
+    }
+
+    /// Show the window, returning the user's decision once made
+    ///
+    /// `check_after_apply` is the option to run `cargo check` once the hunks are applied.
+    pub fn show(&mut self, ctx: &egui::Context, check_after_apply: &mut bool) -> Option<EditReviewAction> {
+        let mut action = None;
+        egui::Window::new("✏ Review AI edits")
+            .default_size([800.0, 500.0])
+            .collapsible(false)
+            .show(ctx, |ui| {
+                if !self.proposal.explanation.is_empty() {
+                    ui.label(&self.proposal.explanation);
+                }
+                for (edit, error) in &self.proposal.invalid {
+                    ui.colored_label(egui::Color32::from_rgb(220, 160, 60), format!("⚠ Skipped edit to {}: {}", edit.file, error));
+                }
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(380.0).show(ui, |ui| {
+                    for hunk in &mut self.proposal.hunks {
+                        Self::render_hunk(ui, hunk);
+                        ui.separator();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Accept all").clicked() {
+                        self.proposal.decide_all(HunkDecision::Accepted);
+                    }
+                    if ui.button("Reject all").clicked() {
+                        self.proposal.decide_all(HunkDecision::Rejected);
+                    }
+                    ui.checkbox(check_after_apply, "Run cargo check afterwards");
+                });
+                ui.horizontal(|ui| {
+                    let accepted = self.proposal.accepted().count();
+                    if ui.add_enabled(accepted > 0, egui::Button::new(format!("✔ Apply {} accepted", accepted))).clicked() {
+                        action = Some(EditReviewAction::Apply);
+                    }
+                    if ui.button("Discard").clicked() {
+                        action = Some(EditReviewAction::Discard);
+                    }
+                });
+            });
+        action
+    }
+
+    fn render_hunk(ui: &mut egui::Ui, hunk: &mut EditHunk) {
+        ui.horizontal(|ui| {
+            ui.strong(format!("{}:{}", hunk.file, hunk.start_line));
+            ui.selectable_value(&mut hunk.decision, HunkDecision::Accepted, "✔ Accept");
+            ui.selectable_value(&mut hunk.decision, HunkDecision::Rejected, "✖ Reject");
+        });
+        for line in hunk.diff() {
+            let (text, color) = match line {
+                DiffLine::Context(text) => (format!("  {}", text), ui.visuals().text_color()),
+                DiffLine::Removed(text) => (format!("- {}", text), egui::Color32::from_rgb(220, 90, 90)),
+                DiffLine::Added(text) => (format!("+ {}", text), egui::Color32::from_rgb(90, 190, 90)),
+            };
+            ui.add(egui::Label::new(egui::RichText::new(text).monospace().color(color)).wrap(false));
+        }
+    }
+}
@@ -2,14 +2,20 @@
 //!
 //! This module provides comprehensive build and execution capabilities
 //! including compilation, testing, and debugging support.
+//!
+//! Cargo is run with `--message-format=json`, so diagnostics arrive as structured
+//! records with full spans, child notes and suggested replacements.
 
+use std::collections::HashSet;
 use std::process::{Command, Stdio};
 use std::path::{Path, PathBuf};
 use std::io::{BufRead, BufReader};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread;
 use anyhow::{Result, Context};
+use serde::Deserialize;
 use crate::editor::output_panel::OutputPanel;
+use crate::editor::text_buffer::{TextBuffer, TextRange};
 
 /// Build configuration options
 #[derive(Clone, Debug)]
@@ -40,18 +46,98 @@ pub struct BuildResult {
     pub stderr: String,
     pub warnings: Vec<CompilerMessage>,
     pub errors: Vec<CompilerMessage>,
+    pub artifacts: Vec<CompilerArtifact>,
     pub build_time: std::time::Duration,
 }
 
 /// Compiler diagnostic message
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CompilerMessage {
     pub level: MessageLevel,
     pub message: String,
+    /// File of the primary span, as reported by cargo (workspace-relative)
     pub file: Option<String>,
+    /// Line of the primary span (1-based)
     pub line: Option<usize>,
+    /// Column of the primary span (1-based)
     pub column: Option<usize>,
     pub code: Option<String>,
+    /// All spans; the primary ones mark where the diagnostic points
+    pub spans: Vec<DiagnosticSpan>,
+    /// Attached notes, help and suggestions
+    pub children: Vec<CompilerMessage>,
+    /// Diagnostic formatted the way rustc prints it
+    pub rendered: Option<String>,
+}
+
+/// Source region referenced by a diagnostic
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiagnosticSpan {
+    pub file_name: String,
+    /// Byte offsets into the file (end exclusive)
+    pub byte_start: usize,
+    pub byte_end: usize,
+    /// 1-based line and column range (columns count characters)
+    pub line_start: usize,
+    pub line_end: usize,
+    pub column_start: usize,
+    pub column_end: usize,
+    pub is_primary: bool,
+    pub label: Option<String>,
+    /// Replacement text proposed by the compiler for this span
+    pub suggested_replacement: Option<String>,
+    pub suggestion_applicability: Option<Applicability>,
+}
+
+/// How confident the compiler is in a suggested replacement
+#[derive(Debug, Clone, PartialEq)]
+pub enum Applicability {
+    /// Safe to apply automatically
+    MachineApplicable,
+    /// Probably right, but should be reviewed
+    MaybeIncorrect,
+    /// Contains placeholders the user must fill in
+    HasPlaceholders,
+    Unspecified,
+}
+
+/// A compiler suggestion that can be applied as a quick fix
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuggestedFix {
+    /// Help text describing the fix
+    pub message: String,
+    pub applicability: Applicability,
+    pub edits: Vec<FixEdit>,
+}
+
+/// Single text replacement of a quick fix
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixEdit {
+    pub file_name: String,
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub replacement: String,
+}
+
+/// Output file produced for a compiled target
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompilerArtifact {
+    pub package_id: String,
+    pub target_name: String,
+    /// Target kinds such as `lib`, `bin` or `test`
+    pub kind: Vec<String>,
+    pub filenames: Vec<PathBuf>,
+    pub executable: Option<PathBuf>,
+    /// Whether the artifact was already up to date
+    pub fresh: bool,
+}
+
+/// One record of cargo's JSON message stream
+#[derive(Debug, Clone, PartialEq)]
+pub enum CargoMessage {
+    CompilerMessage(CompilerMessage),
+    CompilerArtifact(CompilerArtifact),
+    BuildFinished { success: bool },
 }
 
 /// Message severity levels
@@ -91,6 +177,7 @@ pub enum BuildCommand {
 pub enum BuildOutput {
     Started(BuildCommand),
     Progress(String),
+    Diagnostic(CompilerMessage),
     Finished(BuildResult),
     Error(String),
 }
@@ -372,10 +459,16 @@ impl BuildWorker {
             .ok_or_else(|| anyhow::anyhow!("No project path set"))?;
 
         let start_time = std::time::Instant::now();
+
+        // Subcommands that compile report diagnostics as JSON on stdout
+        let mut args: Vec<&str> = args.to_vec();
+        if matches!(args.first(), Some(&("build" | "run" | "test" | "check" | "clippy" | "doc"))) {
+            args.insert(1, "--message-format=json");
+        }
         
         let mut command = Command::new("cargo");
         command
-            .args(args)
+            .args(&args)
             .current_dir(project_path)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
@@ -387,106 +480,310 @@ impl BuildWorker {
         let stdout = process.stdout.take().unwrap();
         let stderr = process.stderr.take().unwrap();
 
-        let stdout_reader = BufReader::new(stdout);
-        let stderr_reader = BufReader::new(stderr);
+        // Cargo's own progress goes to stderr; drain it alongside stdout
+        let stderr_sender = self.output_sender.clone();
+        let stderr_thread = thread::spawn(move || {
+            let mut lines = Vec::new();
+            for line in BufReader::new(stderr).lines().map_while(|line| line.ok()) {
+                lines.push(line.clone());
+                let _ = stderr_sender.send(BuildOutput::Progress(line));
+            }
+            lines
+        });
 
         let mut stdout_lines = Vec::new();
-        let mut stderr_lines = Vec::new();
-
-        // Read stdout
-        for line in stdout_reader.lines() {
-            let line = line?;
-            stdout_lines.push(line.clone());
-            let _ = self.output_sender.send(BuildOutput::Progress(line));
-        }
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        let mut artifacts = Vec::new();
+        let mut seen = HashSet::new();
 
-        // Read stderr
-        for line in stderr_reader.lines() {
+        for line in BufReader::new(stdout).lines() {
             let line = line?;
-            stderr_lines.push(line.clone());
-            let _ = self.output_sender.send(BuildOutput::Progress(line));
+            match parse_cargo_message(&line) {
+                Some(CargoMessage::CompilerMessage(message)) => {
+                    // The same diagnostic is reported once per target that includes the file
+                    let key = message.rendered.clone().unwrap_or_else(|| format!("{:?}", message));
+                    if message.is_summary() || !seen.insert(key) {
+                        continue;
+                    }
+                    match message.level {
+                        MessageLevel::Error => errors.push(message.clone()),
+                        MessageLevel::Warning => warnings.push(message.clone()),
+                        _ => {}
+                    }
+                    let _ = self.output_sender.send(BuildOutput::Diagnostic(message));
+                }
+                Some(CargoMessage::CompilerArtifact(artifact)) => artifacts.push(artifact),
+                Some(CargoMessage::BuildFinished { .. }) => {}
+                None => {
+                    // Anything else on stdout is output of the program or test harness
+                    stdout_lines.push(line.clone());
+                    let _ = self.output_sender.send(BuildOutput::Progress(line));
+                }
+            }
         }
 
+        let stderr_lines = stderr_thread.join().unwrap_or_default();
         let output = process.wait()?;
         let build_time = start_time.elapsed();
 
-        let stdout_text = stdout_lines.join("\n");
-        let stderr_text = stderr_lines.join("\n");
-
-        // Parse compiler messages
-        let (warnings, errors) = self.parse_compiler_messages(&stderr_text);
-
         Ok(BuildResult {
             success: output.success(),
             exit_code: output.code().unwrap_or(-1),
-            stdout: stdout_text,
-            stderr: stderr_text,
+            stdout: stdout_lines.join("\n"),
+            stderr: stderr_lines.join("\n"),
             warnings,
             errors,
+            artifacts,
             build_time,
         })
     }
+}
 
-    fn parse_compiler_messages(&self, stderr: &str) -> (Vec<CompilerMessage>, Vec<CompilerMessage>) {
-        let mut warnings = Vec::new();
-        let mut errors = Vec::new();
-
-        for line in stderr.lines() {
-            if let Some(message) = self.parse_compiler_line(line) {
-                match message.level {
-                    MessageLevel::Error => errors.push(message),
-                    MessageLevel::Warning => warnings.push(message),
-                    _ => {} // Ignore other levels for now
-                }
-            }
+/// Parse one line of `cargo --message-format=json` output
+///
+/// Returns `None` for lines that are not cargo records (e.g. program output)
+/// and for record kinds the IDE does not use.
+pub fn parse_cargo_message(line: &str) -> Option<CargoMessage> {
+    if !line.starts_with('{') {
+        return None;
+    }
+
+    match serde_json::from_str::<RawCargoMessage>(line).ok()? {
+        RawCargoMessage::CompilerMessage { message } => Some(CargoMessage::CompilerMessage(message.into())),
+        RawCargoMessage::CompilerArtifact { package_id, target, filenames, executable, fresh } => {
+            Some(CargoMessage::CompilerArtifact(CompilerArtifact {
+                package_id,
+                target_name: target.name,
+                kind: target.kind,
+                filenames,
+                executable,
+                fresh,
+            }))
         }
+        RawCargoMessage::BuildFinished { success } => Some(CargoMessage::BuildFinished { success }),
+        RawCargoMessage::Other => None,
+    }
+}
+
+/// Resolve a file name from a diagnostic against the project directory
+///
+/// Cargo reports paths relative to the workspace root, which may be an
+/// ancestor of the project directory.
+pub fn resolve_source_path(project_root: &Path, file_name: &str) -> PathBuf {
+    let path = Path::new(file_name);
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    project_root.ancestors()
+        .map(|dir| dir.join(path))
+        .find(|candidate| candidate.exists())
+        .unwrap_or_else(|| project_root.join(path))
+}
 
-        (warnings, errors)
+impl CompilerMessage {
+    /// The span the diagnostic points at
+    pub fn primary_span(&self) -> Option<&DiagnosticSpan> {
+        self.spans.iter().find(|span| span.is_primary).or_else(|| self.spans.first())
     }
 
-    fn parse_compiler_line(&self, line: &str) -> Option<CompilerMessage> {
-        // Simple parsing - in a real implementation, this would be more sophisticated
-        if line.contains("error:") {
-            Some(CompilerMessage {
-                level: MessageLevel::Error,
-                message: line.to_string(),
-                file: self.extract_file_from_line(line),
-                line: self.extract_line_number_from_line(line),
-                column: None,
-                code: None,
-            })
-        } else if line.contains("warning:") {
-            Some(CompilerMessage {
-                level: MessageLevel::Warning,
-                message: line.to_string(),
-                file: self.extract_file_from_line(line),
-                line: self.extract_line_number_from_line(line),
-                column: None,
-                code: None,
+    /// Quick fixes offered by this diagnostic and its children
+    pub fn suggestions(&self) -> Vec<SuggestedFix> {
+        let mut fixes = Vec::new();
+        self.collect_suggestions(&mut fixes);
+        fixes
+    }
+
+    fn collect_suggestions(&self, fixes: &mut Vec<SuggestedFix>) {
+        let edits: Vec<FixEdit> = self.spans.iter()
+            .filter_map(|span| {
+                span.suggested_replacement.as_ref().map(|replacement| FixEdit {
+                    file_name: span.file_name.clone(),
+                    byte_start: span.byte_start,
+                    byte_end: span.byte_end,
+                    replacement: replacement.clone(),
+                })
             })
-        } else {
-            None
+            .collect();
+
+        if !edits.is_empty() {
+            let applicability = self.spans.iter()
+                .filter_map(|span| span.suggestion_applicability.clone())
+                .next()
+                .unwrap_or(Applicability::Unspecified);
+            fixes.push(SuggestedFix {
+                message: self.message.clone(),
+                applicability,
+                edits,
+            });
         }
+
+        for child in &self.children {
+            child.collect_suggestions(fixes);
+        }
+    }
+
+    /// Whether this is a trailing summary such as "aborting due to 2 previous errors"
+    fn is_summary(&self) -> bool {
+        self.spans.is_empty() && self.code.is_none() && (
+            self.message.starts_with("aborting due to")
+                || self.message.starts_with("For more information about")
+                || self.message.ends_with("warnings emitted")
+                || self.message.ends_with("warning emitted")
+        )
     }
+}
 
-    fn extract_file_from_line(&self, line: &str) -> Option<String> {
-        // Simple extraction - look for src/filename.rs pattern
-        if let Some(start) = line.find("src/") {
-            if let Some(end) = line[start..].find(':') {
-                return Some(line[start..start + end].to_string());
+impl SuggestedFix {
+    /// Files touched by this fix
+    pub fn files(&self) -> Vec<&str> {
+        let mut files: Vec<&str> = self.edits.iter().map(|edit| edit.file_name.as_str()).collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    /// Apply the edits for `file_name` to its source text
+    pub fn apply_to_source(&self, file_name: &str, source: &str) -> Result<String> {
+        let mut edits: Vec<&FixEdit> = self.edits.iter()
+            .filter(|edit| edit.file_name == file_name)
+            .collect();
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.byte_start));
+
+        let mut result = source.to_string();
+        let mut limit = source.len();
+        for edit in edits {
+            if edit.byte_start > edit.byte_end
+                || edit.byte_end > limit
+                || !result.is_char_boundary(edit.byte_start)
+                || !result.is_char_boundary(edit.byte_end)
+            {
+                anyhow::bail!("Suggestion for {} no longer matches the file", file_name);
             }
+            result.replace_range(edit.byte_start..edit.byte_end, &edit.replacement);
+            limit = edit.byte_start;
         }
-        None
+        Ok(result)
+    }
+
+    /// Edits for `file_name` as text buffer ranges, for applying the fix as an undoable edit
+    pub fn text_edits(&self, file_name: &str, buffer: &TextBuffer) -> Result<Vec<(TextRange, String)>> {
+        // Rejects stale or overlapping edits
+        self.apply_to_source(file_name, &buffer.to_string())?;
+        let mut edits = Vec::new();
+        for edit in self.edits.iter().filter(|edit| edit.file_name == file_name) {
+            let range = TextRange {
+                start: buffer.offset_to_position(edit.byte_start)?,
+                end: buffer.offset_to_position(edit.byte_end)?,
+            };
+            edits.push((range, edit.replacement.clone()));
+        }
+        Ok(edits)
     }
+}
 
-    fn extract_line_number_from_line(&self, line: &str) -> Option<usize> {
-        // Look for :number: pattern
-        for part in line.split(':') {
-            if let Ok(num) = part.trim().parse::<usize>() {
-                return Some(num);
-            }
+#[derive(Deserialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+enum RawCargoMessage {
+    CompilerMessage {
+        message: RawDiagnostic,
+    },
+    CompilerArtifact {
+        package_id: String,
+        target: RawTarget,
+        #[serde(default)]
+        filenames: Vec<PathBuf>,
+        executable: Option<PathBuf>,
+        #[serde(default)]
+        fresh: bool,
+    },
+    BuildFinished {
+        success: bool,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+struct RawTarget {
+    name: String,
+    #[serde(default)]
+    kind: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct RawDiagnostic {
+    message: String,
+    code: Option<RawDiagnosticCode>,
+    level: String,
+    #[serde(default)]
+    spans: Vec<RawSpan>,
+    #[serde(default)]
+    children: Vec<RawDiagnostic>,
+    rendered: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawDiagnosticCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct RawSpan {
+    file_name: String,
+    byte_start: usize,
+    byte_end: usize,
+    line_start: usize,
+    line_end: usize,
+    column_start: usize,
+    column_end: usize,
+    is_primary: bool,
+    label: Option<String>,
+    suggested_replacement: Option<String>,
+    suggestion_applicability: Option<String>,
+}
+
+impl From<RawDiagnostic> for CompilerMessage {
+    fn from(raw: RawDiagnostic) -> Self {
+        let level = match raw.level.as_str() {
+            "error" | "error: internal compiler error" => MessageLevel::Error,
+            "warning" => MessageLevel::Warning,
+            "note" | "failure-note" => MessageLevel::Note,
+            "help" => MessageLevel::Help,
+            _ => MessageLevel::Info,
+        };
+
+        let spans: Vec<DiagnosticSpan> = raw.spans.into_iter().map(|span| DiagnosticSpan {
+            file_name: span.file_name,
+            byte_start: span.byte_start,
+            byte_end: span.byte_end,
+            line_start: span.line_start,
+            line_end: span.line_end,
+            column_start: span.column_start,
+            column_end: span.column_end,
+            is_primary: span.is_primary,
+            label: span.label,
+            suggested_replacement: span.suggested_replacement,
+            suggestion_applicability: span.suggestion_applicability.map(|a| match a.as_str() {
+                "MachineApplicable" => Applicability::MachineApplicable,
+                "MaybeIncorrect" => Applicability::MaybeIncorrect,
+                "HasPlaceholders" => Applicability::HasPlaceholders,
+                _ => Applicability::Unspecified,
+            }),
+        }).collect();
+
+        let primary = spans.iter().find(|span| span.is_primary).or_else(|| spans.first());
+        Self {
+            level,
+            message: raw.message,
+            file: primary.map(|span| span.file_name.clone()),
+            line: primary.map(|span| span.line_start),
+            column: primary.map(|span| span.column_start),
+            code: raw.code.map(|code| code.code),
+            children: raw.children.into_iter().map(CompilerMessage::from).collect(),
+            rendered: raw.rendered,
+            spans,
         }
-        None
     }
 }
 
@@ -505,6 +802,9 @@ impl BuildOutputHandler for OutputPanel {
             BuildOutput::Progress(line) => {
                 self.log(&line);
             }
+            BuildOutput::Diagnostic(message) => {
+                self.add_diagnostic(message);
+            }
             BuildOutput::Finished(result) => {
                 self.display_build_result(&result);
             }
@@ -530,7 +830,12 @@ impl BuildOutputHandler for OutputPanel {
         if !result.errors.is_empty() {
             self.log(&format!("❌ {} errors", result.errors.len()));
             for error in &result.errors {
-                self.log(&format!("  {}", error.message));
+                match (&error.file, error.line, error.column) {
+                    (Some(file), Some(line), Some(column)) => {
+                        self.log(&format!("  {}:{}:{}: {}", file, line, column, error.message));
+                    }
+                    _ => self.log(&format!("  {}", error.message)),
+                }
             }
         }
     }
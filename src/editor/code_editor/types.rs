@@ -2,6 +2,8 @@
 
 use std::collections::HashMap;
 use crate::editor::lsp_integration::{CompletionItem, Diagnostic};
+use crate::editor::performance::SyntaxHighlightCache;
+use crate::editor::text_buffer::{SelectionSet, TextBuffer, TextBufferError, TextRange};
 
 #[derive(Clone, Debug)]
 pub struct TextSelection {
@@ -167,35 +169,17 @@ impl EditorTheme {
     }
 }
 
-pub struct EditHistory {
-    pub operations: Vec<EditOperation>,
-    pub current_index: usize,
-    pub max_size: usize,
-}
-
-impl Default for EditHistory {
-    fn default() -> Self {
-        Self {
-            operations: Vec::new(),
-            current_index: 0,
-            max_size: 100,
-        }
-    }
-}
-
-#[derive(Clone)]
-pub struct EditOperation {
-    pub op_type: EditOperationType,
-    pub position: (usize, usize),
-    pub text: String,
-    pub length: usize,
-}
-
-#[derive(Clone)]
-pub enum EditOperationType {
-    Insert,
-    Delete,
-    Replace,
+/// Cursor and selection of another participant of a shared document
+#[derive(Clone, Debug)]
+pub struct RemoteCursor {
+    /// Name shown next to the cursor
+    pub name: String,
+    /// Character index in `code` where the selection started
+    pub anchor: usize,
+    /// Character index in `code` of the cursor
+    pub head: usize,
+    /// Color of the cursor and selection
+    pub color: egui::Color32,
 }
 
 /// Main code editor struct with modern IDE features
@@ -211,8 +195,11 @@ pub struct CodeEditor {
     pub selection: Option<TextSelection>,
     /// Editor settings and preferences
     pub settings: EditorSettings,
-    /// Edit history for undo/redo
-    pub history: EditHistory,
+    /// Text as of the last recorded edit, holding the undo/redo history
+    ///
+    /// Text assigned to `code` directly is recorded as one edit the next time
+    /// the history is used.
+    pub buffer: TextBuffer,
     /// Find and replace state
     pub find_replace: FindReplaceState,
     /// Folded regions
@@ -229,6 +216,15 @@ pub struct CodeEditor {
     pub is_dirty: bool,
     /// Scroll position
     pub scroll_offset: (f32, f32),
+    /// Cursors of the other participants while the file is shared
+    pub remote_cursors: Vec<RemoteCursor>,
+    /// Highlighted lines of `code`, created on first render
+    ///
+    /// Only lines from the first edited one are highlighted again, and large
+    /// files are highlighted on a background thread.
+    highlight_cache: Option<SyntaxHighlightCache>,
+    /// Syntect theme `highlight_cache` highlights with
+    highlight_theme: String,
 }
 
 impl CodeEditor {
@@ -242,6 +238,7 @@ impl CodeEditor {
     pub fn with_content(language: &str, content: String) -> Self {
         let mut editor = Self::default();
         editor.language = language.to_string();
+        editor.buffer = TextBuffer::from_string(content.clone());
         editor.code = content;
         editor
     }
@@ -381,19 +378,53 @@ impl CodeEditor {
             });
     }
 
-    /// Undo the last operation
+    /// Undo the last edit
     pub fn undo(&mut self) {
-        if self.history.current_index > 0 {
-            self.history.current_index -= 1;
-            // Apply reverse operation - placeholder implementation
+        self.sync_buffer();
+        if self.buffer.undo().is_ok() {
+            self.code = self.buffer.to_string();
+            self.mark_dirty();
         }
     }
 
-    /// Redo the last undone operation
+    /// Redo the last undone edit
     pub fn redo(&mut self) {
-        if self.history.current_index < self.history.operations.len() {
-            // Apply operation - placeholder implementation
-            self.history.current_index += 1;
+        self.sync_buffer();
+        if self.buffer.redo().is_ok() {
+            self.code = self.buffer.to_string();
+            self.mark_dirty();
+        }
+    }
+
+    /// Replace several ranges of the code as one undo step
+    ///
+    /// Ranges refer to the current code and must not overlap.
+    pub fn apply_edits(&mut self, description: &str, edits: &[(TextRange, String)]) -> Result<(), TextBufferError> {
+        self.sync_buffer();
+        self.buffer.undo_stack.begin_transaction(description.to_string());
+        let result = self.buffer.replace_ranges(edits, SelectionSet::new());
+        self.buffer.undo_stack.end_transaction();
+        result?;
+        self.code = self.buffer.to_string();
+        self.mark_dirty();
+        Ok(())
+    }
+
+    /// Replace the code with `text` as one undo step, touching only the part that changed
+    pub fn replace_text(&mut self, description: &str, text: &str) -> Result<(), TextBufferError> {
+        self.sync_buffer();
+        match changed_range(&self.buffer, text)? {
+            Some(edit) => self.apply_edits(description, &[edit]),
+            None => Ok(()),
+        }
+    }
+
+    /// Record text assigned to `code` since the last edit as one undo step
+    fn sync_buffer(&mut self) {
+        let recorded = changed_range(&self.buffer, &self.code)
+            .and_then(|edit| edit.map_or(Ok(()), |edit| self.buffer.replace_ranges(&[edit], SelectionSet::new()).map(|_| ())));
+        if recorded.is_err() {
+            self.buffer = TextBuffer::from_string(self.code.clone());
         }
     }
 
@@ -510,6 +541,41 @@ impl CodeEditor {
         }
     }
 
+    /// Byte range of the selection, or the empty range at the cursor
+    pub fn selection_range(&self) -> std::ops::Range<usize> {
+        match &self.selection {
+            Some(selection) => self.line_col_to_offset(selection.start)..self.line_col_to_offset(selection.end),
+            None => {
+                let offset = self.line_col_to_offset(self.cursor_pos);
+                offset..offset
+            }
+        }
+    }
+
+    /// Character indices of the selection anchor and the cursor in the code
+    pub fn selection_chars(&self) -> (usize, usize) {
+        let head = self.line_col_to_offset(self.cursor_pos);
+        let range = self.selection_range();
+        let anchor = if range.start == head { range.end } else { range.start };
+        let to_char = |offset: usize| self.code.get(..offset).map_or(0, |before| before.chars().count());
+        (to_char(anchor), to_char(head))
+    }
+
+    /// Byte offset of a (line, byte column) position, clamped to the code
+    fn line_col_to_offset(&self, (line, col): (usize, usize)) -> usize {
+        let line_start = self.code.split_inclusive('\n').take(line).map(str::len).sum::<usize>();
+        let line_len = self.code[line_start..].find('\n').unwrap_or(self.code.len() - line_start);
+        line_start + col.min(line_len)
+    }
+
+    /// (line, byte column) of a character index into the code
+    fn char_index_to_line_col(&self, index: usize) -> (usize, usize) {
+        let offset = self.code.char_indices().nth(index).map_or(self.code.len(), |(offset, _)| offset);
+        let before = &self.code[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (before.matches('\n').count(), offset - line_start)
+    }
+
     /// Update diagnostics from LSP
     pub fn update_diagnostics(&mut self, diagnostics: Vec<Diagnostic>) {
         self.diagnostics.diagnostics = diagnostics.clone();
@@ -850,37 +916,71 @@ impl CodeEditor {
         text_edit = text_edit.text_color(self.settings.current_theme.text);
         
         // Create the text editor response
-        let response = ui.add(text_edit);
+        let output = text_edit.show(ui);
+        let response = output.response;
         
         // Mark as dirty if text was changed
         if response.changed() {
             self.mark_dirty();
         }
         
-        // Handle cursor position updates (simplified - egui TextEdit handles most cursor logic internally)
-        if response.changed() {
-            // Update cursor position based on text changes
-            let lines_count = self.code.lines().count();
-            if lines_count > 0 {
-                self.cursor_pos.0 = (lines_count - 1).max(0);
-                let last_line = self.code.lines().last().unwrap_or("");
-                self.cursor_pos.1 = last_line.len();
-            }
+        // Track the cursor and selection egui reports, as (line, byte column)
+        if let Some(cursor_range) = output.cursor_range {
+            let primary = self.char_index_to_line_col(cursor_range.primary.ccursor.index);
+            let secondary = self.char_index_to_line_col(cursor_range.secondary.ccursor.index);
+            self.cursor_pos = primary;
+            self.selection = (primary != secondary).then(|| TextSelection {
+                start: primary.min(secondary),
+                end: primary.max(secondary),
+            });
         }
         
         // Overlay syntax highlighting on top of the text editor
         if !self.code.is_empty() {
             self.render_syntax_highlighting_overlay(ui, response.rect);
         }
+        self.render_remote_cursors(ui, &output.galley, output.galley_pos);
         
         // Handle keyboard shortcuts for code editing
         self.handle_editor_shortcuts(ui, &response);
     }
     
+    /// Draw the selections and cursors of the other participants
+    fn render_remote_cursors(&self, ui: &eframe::egui::Ui, galley: &eframe::egui::Galley, galley_pos: eframe::egui::Pos2) {
+        use eframe::egui::text::CCursor;
+
+        let painter = ui.painter();
+        let offset = galley_pos.to_vec2();
+        for cursor in &self.remote_cursors {
+            let (start, end) = (cursor.anchor.min(cursor.head), cursor.anchor.max(cursor.head));
+            if start != end {
+                let mut row_start = 0;
+                for row in &galley.rows {
+                    let row_end = row_start + row.char_count_excluding_newline();
+                    if start <= row_end && end > row_start {
+                        let left = galley.pos_from_ccursor(CCursor::new(start.max(row_start))).min.x;
+                        let right = galley.pos_from_ccursor(CCursor::new(end.min(row_end))).max.x;
+                        let rect = eframe::egui::Rect::from_x_y_ranges(left..=right, row.rect.y_range());
+                        painter.rect_filled(rect.translate(offset), 0.0, cursor.color.gamma_multiply(0.3));
+                    }
+                    row_start += row.char_count_including_newline();
+                }
+            }
+
+            let caret = galley.pos_from_ccursor(CCursor::new(cursor.head)).translate(offset);
+            painter.line_segment([caret.center_top(), caret.center_bottom()], eframe::egui::Stroke::new(2.0, cursor.color));
+            painter.text(
+                caret.left_top(),
+                eframe::egui::Align2::LEFT_BOTTOM,
+                &cursor.name,
+                eframe::egui::FontId::proportional(10.0),
+                cursor.color,
+            );
+        }
+    }
+
     /// Render syntax highlighting overlay on top of the text editor
-    fn render_syntax_highlighting_overlay(&self, ui: &mut eframe::egui::Ui, text_rect: eframe::egui::Rect) {
-        use crate::editor::syntax_highlighter::SyntaxHighlighter;
-        
+    fn render_syntax_highlighting_overlay(&mut self, ui: &mut eframe::egui::Ui, text_rect: eframe::egui::Rect) {
         // Create syntax highlighter based on current theme
         let theme_name = match self.settings.current_theme.name.as_str() {
             "Dark" => "base16-ocean.dark",
@@ -889,7 +989,17 @@ impl CodeEditor {
             _ => "base16-ocean.dark",
         };
         
-        let highlighter = SyntaxHighlighter::new(theme_name);
+        let cache = self.highlight_cache.get_or_insert_with(SyntaxHighlightCache::new);
+        if self.highlight_theme != theme_name {
+            cache.set_theme(theme_name);
+            self.highlight_theme = theme_name.to_string();
+        }
+        // Re-highlights from the first changed line; multi-line comments and strings carry over
+        cache.update_document(&self.code, &self.language);
+        if cache.process_background_results() {
+            ui.ctx().request_repaint();
+        }
+        
         let font_id = eframe::egui::FontId::monospace(self.settings.font_size);
         let line_height = ui.fonts(|fonts| fonts.row_height(&font_id));
         
@@ -921,10 +1031,11 @@ impl CodeEditor {
             }
             
             // Render syntax highlighted text as overlay (transparent background)
-            let highlighted = highlighter.highlight_line(line, &self.language);
+            let highlighted = cache.get_or_highlight_line(line_index, line, &self.language);
             let mut x_offset = 0.0;
             
-            for (text, color) in highlighted {
+            for (text, color) in &highlighted.segments {
+                let color = *color;
                 if !text.trim().is_empty() {
                     let text_pos = line_rect.min + eframe::egui::Vec2::new(x_offset, 0.0);
                     let text_galley = ui.fonts(|fonts| {
@@ -1467,6 +1578,25 @@ impl CodeEditor {
     }
 }
 
+/// The range of `buffer` that differs from `text` and its replacement, `None` if they are equal
+fn changed_range(buffer: &TextBuffer, text: &str) -> Result<Option<(TextRange, String)>, TextBufferError> {
+    let old = buffer.to_string();
+    if old == text {
+        return Ok(None);
+    }
+    let mut prefix = old.bytes().zip(text.bytes()).take_while(|(a, b)| a == b).count();
+    while !old.is_char_boundary(prefix) || !text.is_char_boundary(prefix) {
+        prefix -= 1;
+    }
+    let common = old.len().min(text.len()) - prefix;
+    let mut suffix = old.bytes().rev().zip(text.bytes().rev()).take(common).take_while(|(a, b)| a == b).count();
+    while !old.is_char_boundary(old.len() - suffix) || !text.is_char_boundary(text.len() - suffix) {
+        suffix -= 1;
+    }
+    let range = TextRange { start: buffer.offset_to_position(prefix)?, end: buffer.offset_to_position(old.len() - suffix)? };
+    Ok(Some((range, text[prefix..text.len() - suffix].to_string())))
+}
+
 pub struct CodeFoldingState {
     pub folded_regions: HashMap<usize, usize>,
     pub foldable_regions: Vec<FoldableRegion>,
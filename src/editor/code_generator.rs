@@ -12,12 +12,18 @@
 //! - **Live Preview**: Real-time code preview for visual designer changes
 //! - **Template System**: Extensible code generation templates
 //! - **Diff-based Updates**: Minimal code changes to preserve history
+//! - **Three-Way Merge**: Edits outside guards survive regeneration, with
+//!   conflicting edits surfaced instead of overwritten
 //!
 //! ## Architecture
 //!
 //! The code generator uses a template-based approach with guarded sections that
 //! mark boundaries between generated and user-editable code. This allows the
 //! visual designer to update generated portions while preserving user customizations.
+//!
+//! [`CodeGenerator::regenerate`] goes further: it merges the user's file with the
+//! new output, using the output stored in the last snapshot as the common base,
+//! so edits anywhere in the file are kept.
 
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
@@ -25,6 +31,9 @@ use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::codegen_markers::{CodeLanguage, CodeRewriter};
+use super::three_way_merge::{self, MergeResult};
+
 /// Unique identifier for code generation templates
 pub type TemplateId = String;
 
@@ -190,6 +199,9 @@ pub struct CodeSnapshot {
     pub guard_sections: HashMap<GuardId, GuardedSection>,
     /// Generated code hash for change detection
     pub code_hash: String,
+    /// The generated output, used as the base when merging the next regeneration
+    #[serde(default)]
+    pub generated_code: String,
     /// Metadata for the snapshot
     pub metadata: HashMap<String, String>,
 }
@@ -221,6 +233,7 @@ impl CodeSnapshot {
             template_variables,
             guard_sections,
             code_hash,
+            generated_code: generated_code.to_string(),
             metadata: HashMap::new(),
         }
     }
@@ -312,6 +325,31 @@ impl LivePreviewPanel {
     }
 }
 
+/// Outcome of [`CodeGenerator::regenerate`]
+#[derive(Debug, Clone)]
+pub struct Regeneration {
+    /// File being regenerated
+    pub file_path: PathBuf,
+    /// The new generator output on its own
+    pub generated_code: String,
+    /// Merge of the user's file with the new output
+    pub merge: MergeResult,
+    /// Snapshot to store once the regeneration is accepted
+    snapshot: CodeSnapshot,
+}
+
+impl Regeneration {
+    /// Whether user edits and the new output changed the same lines
+    pub fn has_conflicts(&self) -> bool {
+        self.merge.has_conflicts()
+    }
+
+    /// The merged file, or `None` while conflicts are unresolved
+    pub fn merged_code(&self) -> Option<String> {
+        self.merge.merged_text()
+    }
+}
+
 /// Main code generator with guarded sections support
 pub struct CodeGenerator {
     /// Available code templates
@@ -347,54 +385,114 @@ impl CodeGenerator {
         variables: HashMap<String, serde_json::Value>,
         output_file: PathBuf,
     ) -> CodeGenResult<String> {
-        let template = self.templates.get(template_id)
-            .ok_or_else(|| CodeGenError::TemplateNotFound(template_id.clone()))?;
-
-        // Load existing snapshot if available
-        let existing_snapshot = self.snapshots.get(&output_file);
-        
         // Parse existing guard sections if file exists
-        let existing_guards = if let Some(snapshot) = existing_snapshot {
+        let existing_guards = if let Some(snapshot) = self.snapshots.get(&output_file) {
             snapshot.guard_sections.clone()
         } else {
             self.parse_existing_guards(&output_file)?
         };
 
+        let generated_code = self.render(template_id, &variables, &existing_guards)?;
+        let snapshot = self.snapshot_for(template_id, variables, &output_file, &generated_code)?;
+        self.store_snapshot(snapshot);
+
+        Ok(generated_code)
+    }
+
+    /// Regenerate a file by merging the user's current text with the new output
+    ///
+    /// `current` is the user's version of the file; when `None` it is read from
+    /// `output_file`. The base of the merge is the output stored in the file's
+    /// snapshot, so load the snapshot first. Nothing is stored until the result
+    /// is passed to [`CodeGenerator::commit_regeneration`].
+    pub fn regenerate(
+        &mut self,
+        template_id: &TemplateId,
+        variables: HashMap<String, serde_json::Value>,
+        output_file: PathBuf,
+        current: Option<&str>,
+    ) -> CodeGenResult<Regeneration> {
+        let current = match current {
+            Some(current) => current.to_string(),
+            None if output_file.exists() => std::fs::read_to_string(&output_file)
+                .map_err(|e| CodeGenError::FileError(e.to_string()))?,
+            None => String::new(),
+        };
+
+        let mut generated_code = self.render(template_id, &variables, &self.extract_guard_sections(&current)?)?;
+        if let Some(language) = output_file.extension().and_then(|ext| CodeLanguage::from_extension(&ext.to_string_lossy())) {
+            // Marker guards are copied too; files without them are left as rendered
+            if let Ok(preserved) = CodeRewriter::new(language, generated_code.clone()).preserve_guards(&current) {
+                generated_code = preserved;
+            }
+        }
+
+        // Without a stored output the current file is the best base there is,
+        // which keeps only guard contents like `generate_code`
+        let base = match self.snapshots.get(&output_file) {
+            Some(snapshot) if !snapshot.generated_code.is_empty() => snapshot.generated_code.clone(),
+            _ => current.clone(),
+        };
+        let merge = three_way_merge::merge(&base, &current, &generated_code);
+        let snapshot = self.snapshot_for(template_id, variables, &output_file, &generated_code)?;
+
+        Ok(Regeneration { file_path: output_file, generated_code, merge, snapshot })
+    }
+
+    /// Record a regeneration as the new base for the next merge
+    pub fn commit_regeneration(&mut self, regeneration: &Regeneration) {
+        self.store_snapshot(regeneration.snapshot.clone());
+    }
+
+    /// Fill in a template's variables and guard sections
+    fn render(
+        &self,
+        template_id: &TemplateId,
+        variables: &HashMap<String, serde_json::Value>,
+        existing_guards: &HashMap<GuardId, GuardedSection>,
+    ) -> CodeGenResult<String> {
+        let template = self.templates.get(template_id)
+            .ok_or_else(|| CodeGenError::TemplateNotFound(template_id.clone()))?;
+
         // Generate code with template variables
         let mut generated_code = template.content.clone();
         
         // Replace template variables
-        for (var_name, value) in &variables {
+        for (var_name, value) in variables {
             let placeholder = format!("{{{{{}}}}}", var_name);
             let string_value = self.serialize_template_value(value);
             generated_code = generated_code.replace(&placeholder, &string_value);
         }
 
         // Process guard sections
-        generated_code = self.process_guard_sections(
-            generated_code,
-            &template.default_guards,
-            &existing_guards,
-        )?;
-
-        // Create or update snapshot
-        let guard_sections = self.extract_guard_sections(&generated_code)?;
-        let snapshot = CodeSnapshot::new(
-            output_file.clone(),
+        self.process_guard_sections(generated_code, &template.default_guards, existing_guards)
+    }
+
+    /// Build the snapshot describing freshly generated code
+    fn snapshot_for(
+        &self,
+        template_id: &TemplateId,
+        variables: HashMap<String, serde_json::Value>,
+        output_file: &Path,
+        generated_code: &str,
+    ) -> CodeGenResult<CodeSnapshot> {
+        let guard_sections = self.extract_guard_sections(generated_code)?;
+        Ok(CodeSnapshot::new(
+            output_file.to_path_buf(),
             template_id.clone(),
             variables,
             guard_sections,
-            &generated_code,
-        );
-        
-        self.snapshots.insert(output_file, snapshot);
+            generated_code,
+        ))
+    }
 
+    /// Store a snapshot and refresh the live preview
+    fn store_snapshot(&mut self, snapshot: CodeSnapshot) {
         // Update live preview if enabled
         if self.preview_panel.is_enabled {
-            self.preview_panel.update_preview(generated_code.clone());
+            self.preview_panel.update_preview(snapshot.generated_code.clone());
         }
-
-        Ok(generated_code)
+        self.snapshots.insert(snapshot.file_path.clone(), snapshot);
     }
 
     /// Parse existing guard sections from a file
@@ -420,7 +518,7 @@ impl CodeGenerator {
             
             if line.starts_with("// <guard:") && line.ends_with(":start>") {
                 let guard_id = line[10..line.len()-7].to_string();
-                let mut content = String::new();
+                let mut content = Vec::new();
                 i += 1;
 
                 // Collect content until end marker
@@ -429,12 +527,11 @@ impl CodeGenerator {
                     if current_line == format!("// <guard:{}:end>", guard_id) {
                         break;
                     }
-                    content.push_str(lines[i]);
-                    content.push('\n');
+                    content.push(lines[i]);
                     i += 1;
                 }
 
-                let guard_section = GuardedSection::new(guard_id.clone(), content);
+                let guard_section = GuardedSection::new(guard_id.clone(), content.join("\n"));
                 guard_sections.insert(guard_id, guard_section);
             }
             i += 1;
@@ -464,11 +561,19 @@ impl CodeGenerator {
 
             let guard_section = GuardedSection::new(guard_id.clone(), guard_content);
             let (start_marker, end_marker) = guard_section.generate_markers();
+
+            // Line the end marker up with the placeholder's indentation
+            let indent = code.find(&placeholder)
+                .map(|at| &code[code[..at].rfind('\n').map_or(0, |newline| newline + 1)..at])
+                .filter(|prefix| prefix.trim().is_empty())
+                .unwrap_or("")
+                .to_string();
             
             let full_guard = format!(
-                "{}\n{}\n{}",
+                "{}\n{}\n{}{}",
                 start_marker,
                 guard_section.user_content,
+                indent,
                 end_marker
             );
 
@@ -590,8 +695,6 @@ use crate::rcl::ui::prelude::*;
 #[derive(Debug, Clone)]
 pub struct {{component_name}} {
     {{guard:fields}}
-    // Add custom fields here
-    {{guard:fields}}
     
     /// Component properties
     pub props: {{component_name}}Props,
@@ -603,32 +706,24 @@ pub struct {{component_name}}Props {
     pub name: String,
     pub enabled: bool,
     {{guard:props}}
-    // Add custom properties here
-    {{guard:props}}
 }
 
 impl {{component_name}} {
     /// Create a new {{component_name}}
     pub fn new(props: {{component_name}}Props) -> Self {
         Self {
-            {{guard:constructor}}
-            // Initialize custom fields here
             {{guard:constructor}}
             props,
         }
     }
 
     {{guard:methods}}
-    // Add custom methods here
-    {{guard:methods}}
 }
 
 impl Component for {{component_name}} {
     type Props = {{component_name}}Props;
 
     fn render(&self, ctx: &mut RenderContext) -> RenderResult {
-        {{guard:render}}
-        // Custom render logic here
         {{guard:render}}
         Ok(())
     }
@@ -636,14 +731,10 @@ impl Component for {{component_name}} {
     fn update(&mut self, props: Self::Props) -> UpdateResult {
         self.props = props;
         {{guard:update}}
-        // Custom update logic here
-        {{guard:update}}
         Ok(())
     }
 }
 
-{{guard:impl_blocks}}
-// Add custom implementations here
 {{guard:impl_blocks}}
 "#.to_string(),
         );
@@ -651,7 +742,7 @@ impl Component for {{component_name}} {
         // Add default guard sections
         template.add_guard("fields".to_string(), "    // Custom fields".to_string());
         template.add_guard("props".to_string(), "    // Custom properties".to_string());
-        template.add_guard("constructor".to_string(), "        // Custom initialization".to_string());
+        template.add_guard("constructor".to_string(), "            // Custom initialization".to_string());
         template.add_guard("methods".to_string(), "    // Custom methods".to_string());
         template.add_guard("render".to_string(), "        // Custom render logic".to_string());
         template.add_guard("update".to_string(), "        // Custom update logic".to_string());
@@ -665,6 +756,15 @@ impl Component for {{component_name}} {
     }
 
     /// Create a basic form template
+    ///
+    /// `component_imports` holds the `use` lines for the RCL component traits
+    /// and types, `component_fields` and `component_init` the struct fields and
+    /// their constructors and `component_properties` the designer property
+    /// values, one indented line each. `component_layout` is the expression
+    /// listing each component's rect and `component_render` the indented
+    /// statements placing the components there.
+    /// User sections are [`CodeRewriter`] guard markers, so regeneration keeps
+    /// their contents through [`CodeRewriter::preserve_guards`].
     pub fn create_form_template() -> CodeTemplate {
         let mut template = CodeTemplate::new(
             "form_component".to_string(),
@@ -672,49 +772,89 @@ impl Component for {{component_name}} {
 //!
 //! This form was generated by the visual designer.
 
-use crate::rcl::ui::prelude::*;
+{{component_imports}}
 
 /// {{form_name}} form
-#[derive(Debug)]
 pub struct {{form_name}} {
-    {{guard:form_fields}}
-    // Add form fields here
-    {{guard:form_fields}}
+{{component_fields}}
+    // <codegen:guard:form_fields:start>
+    // Form fields
+    // <codegen:guard:form_fields:end>
 }
 
 impl {{form_name}} {
     pub fn new() -> Self {
-        Self {
-            {{guard:form_init}}
-            // Initialize form fields
-            {{guard:form_init}}
-        }
+        let mut form = Self {
+{{component_init}}
+            // <codegen:guard:form_init:start>
+            // Form initialization
+            // <codegen:guard:form_init:end>
+        };
+{{component_properties}}
+        form
+    }
+
+    // <codegen:guard:form_methods:start>
+    // Form methods
+    // <codegen:guard:form_methods:end>
+
+    /// Rect of each component relative to the form's top-left corner, as laid out in the designer
+    pub fn layout() -> Vec<(&'static str, egui::Rect)> {
+        {{component_layout}}
     }
 
-    {{guard:form_methods}}
-    // Add form methods here
-    {{guard:form_methods}}
+    // <codegen:generated:event_dispatch:start>
+{{event_dispatch}}
+    // <codegen:generated:event_dispatch:end>
+}
+
+impl {{form_name}} {
+    // <codegen:guard:event_handlers:start>
+    // <codegen:guard:event_handlers:end>
+}
+
+impl Default for {{form_name}} {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Component for {{form_name}} {
-    type Props = ();
+    fn name(&self) -> &str {
+        "{{form_name}}"
+    }
 
-    fn render(&self, ctx: &mut RenderContext) -> RenderResult {
-        {{guard:form_render}}
-        // Form rendering logic
-        {{guard:form_render}}
-        Ok(())
+    fn render(&mut self, ui: &mut egui::Ui) {
+{{component_render}}
+        // <codegen:guard:form_render:start>
+        // Form render logic
+        // <codegen:guard:form_render:end>
+        self.dispatch_events();
+    }
+
+    fn get_property(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    fn set_property(&mut self, _name: &str, _value: &str) -> bool {
+        false
+    }
+
+    fn get_property_names(&self) -> Vec<String> {
+        Vec::new()
     }
 }
 "#.to_string(),
         );
 
-        template.add_guard("form_fields".to_string(), "    // Form fields".to_string());
-        template.add_guard("form_init".to_string(), "        // Form initialization".to_string());
-        template.add_guard("form_methods".to_string(), "    // Form methods".to_string());
-        template.add_guard("form_render".to_string(), "        // Form render logic".to_string());
-
         template.add_variable("form_name".to_string(), TemplateVariableType::String);
+        template.add_variable("component_imports".to_string(), TemplateVariableType::String);
+        template.add_variable("component_fields".to_string(), TemplateVariableType::String);
+        template.add_variable("component_init".to_string(), TemplateVariableType::String);
+        template.add_variable("component_properties".to_string(), TemplateVariableType::String);
+        template.add_variable("component_layout".to_string(), TemplateVariableType::String);
+        template.add_variable("component_render".to_string(), TemplateVariableType::String);
+        template.add_variable("event_dispatch".to_string(), TemplateVariableType::String);
 
         template
     }
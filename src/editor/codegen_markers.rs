@@ -528,6 +528,9 @@ impl CodeRewriter {
         
         let mut result = String::new();
         let lines: Vec<&str> = self.original_code.lines().collect();
+        let indent_of = |line: usize| {
+            lines.get(line).map_or("", |text| &text[..text.len() - text.trim_start().len()])
+        };
         let mut current_line = 0;
         
         for marker in &sorted_markers {
@@ -540,13 +543,15 @@ impl CodeRewriter {
                 current_line += 1;
             }
             
-            // Add the marker with its content
+            // Add the marker with its content, keeping the marker lines' indentation
+            result.push_str(indent_of(marker.start_position.line));
             result.push_str(&marker.start_marker(&self.language));
             result.push('\n');
             result.push_str(&marker.content);
-            if !marker.content.ends_with('\n') {
+            if !marker.content.is_empty() && !marker.content.ends_with('\n') {
                 result.push('\n');
             }
+            result.push_str(indent_of(marker.end_position.line));
             result.push_str(&marker.end_marker(&self.language));
             result.push('\n');
             
@@ -565,6 +570,32 @@ impl CodeRewriter {
         Ok(result)
     }
     
+    /// Find a parsed marker by its specification, e.g. `guard:event_handlers`
+    pub fn marker(&self, spec: &str) -> Option<&CodeMarker> {
+        self.markers.iter().find(|marker| self.get_marker_id(marker) == spec)
+    }
+    
+    /// Rewrite the code with each guard's content taken from the guard of the
+    /// same ID in `previous`, so user code survives regeneration
+    pub fn preserve_guards(&mut self, previous: &str) -> Result<String, String> {
+        let mut old = CodeRewriter::new(self.language.clone(), previous.to_string());
+        old.parse_markers()?;
+        if self.markers.is_empty() {
+            self.parse_markers()?;
+        }
+        
+        let mut updated = self.markers.clone();
+        for marker in &mut updated {
+            if !matches!(marker.marker_type, MarkerType::Guard { .. }) {
+                continue;
+            }
+            if let Some(previous_marker) = old.marker(&self.get_marker_id(marker)) {
+                marker.update_content(previous_marker.content.clone());
+            }
+        }
+        self.rewrite_with_markers(updated)
+    }
+    
     /// Get markers by type
     pub fn markers_of_type(&self, marker_type: &str) -> Vec<&CodeMarker> {
         self.markers.iter().filter(|marker| {
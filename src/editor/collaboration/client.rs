@@ -0,0 +1,236 @@
+//! # Collaboration Client
+//!
+//! Editor side of a shared document. At most one edit is in flight to the
+//! server at a time; edits made while waiting for its acknowledgement are
+//! composed into a buffer and sent once it arrives. Remote edits are
+//! transformed past both so they apply to the local text.
+
+use std::collections::HashMap;
+
+use super::ot::TextOp;
+use super::protocol::{ClientId, CollabMessage, PeerInfo, PeerState, SelectionRange};
+
+/// Edits not yet acknowledged by the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SyncState {
+    /// Everything local is on the server
+    Synchronized,
+    /// One edit sent, waiting for its acknowledgement
+    AwaitingAck(TextOp),
+    /// One edit sent and later edits waiting to be sent
+    AwaitingWithBuffer(TextOp, TextOp),
+}
+
+/// Editor state for one shared document
+#[derive(Debug, Clone)]
+pub struct CollabClient {
+    document: String,
+    peer: PeerInfo,
+    client_id: Option<ClientId>,
+    /// Last server revision included in `text`
+    revision: u64,
+    /// Shared text including unacknowledged local edits
+    text: String,
+    state: SyncState,
+    peers: HashMap<ClientId, PeerState>,
+    selections: Vec<SelectionRange>,
+    outbox: Vec<CollabMessage>,
+}
+
+impl CollabClient {
+    /// Create a client for `document`; call [`CollabClient::join`] to connect
+    pub fn new(document: impl Into<String>, peer: PeerInfo) -> Self {
+        Self {
+            document: document.into(),
+            peer,
+            client_id: None,
+            revision: 0,
+            text: String::new(),
+            state: SyncState::Synchronized,
+            peers: HashMap::new(),
+            selections: Vec::new(),
+            outbox: Vec::new(),
+        }
+    }
+
+    /// Ask the server to join the document; `content` is the local text, which
+    /// seeds the document if nobody shares it yet
+    pub fn join(&mut self, content: &str) {
+        self.text = content.to_string();
+        self.client_id = None;
+        self.state = SyncState::Synchronized;
+        self.outbox.push(CollabMessage::Join {
+            document: self.document.clone(),
+            peer: self.peer.clone(),
+            content: content.to_string(),
+        });
+    }
+
+    /// Name of the shared document
+    pub fn document(&self) -> &str {
+        &self.document
+    }
+
+    /// Id assigned by the server, once joined
+    pub fn client_id(&self) -> Option<ClientId> {
+        self.client_id
+    }
+
+    /// Whether the server accepted the join
+    pub fn is_joined(&self) -> bool {
+        self.client_id.is_some()
+    }
+
+    /// Last server revision the local text is based on
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Shared text as the client knows it
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Whether every local edit was acknowledged
+    pub fn is_synchronized(&self) -> bool {
+        self.state == SyncState::Synchronized
+    }
+
+    /// Other participants and their cursors, in local text coordinates
+    pub fn peers(&self) -> Vec<&PeerState> {
+        let mut peers: Vec<&PeerState> = self.peers.values().collect();
+        peers.sort_by_key(|peer| peer.client_id);
+        peers
+    }
+
+    /// Messages to send to the server
+    pub fn take_outgoing(&mut self) -> Vec<CollabMessage> {
+        std::mem::take(&mut self.outbox)
+    }
+
+    /// Record an edit made locally
+    pub fn local_edit(&mut self, op: TextOp) -> Result<(), String> {
+        if !self.is_joined() {
+            return Err("Not connected to a collaboration session".to_string());
+        }
+        if op.is_noop() {
+            return Ok(());
+        }
+        self.text = op.apply(&self.text)?;
+        for peer in self.peers.values_mut() {
+            for selection in &mut peer.selections {
+                *selection = selection.transform(&op);
+            }
+        }
+
+        self.state = match std::mem::replace(&mut self.state, SyncState::Synchronized) {
+            SyncState::Synchronized => {
+                self.outbox.push(CollabMessage::Edit { revision: self.revision, op: op.clone() });
+                SyncState::AwaitingAck(op)
+            }
+            SyncState::AwaitingAck(sent) => SyncState::AwaitingWithBuffer(sent, op),
+            SyncState::AwaitingWithBuffer(sent, buffer) => SyncState::AwaitingWithBuffer(sent, buffer.compose(&op)?),
+        };
+        Ok(())
+    }
+
+    /// Record local edits by comparing the editor's current text with the shared text
+    pub fn sync_text(&mut self, current: &str) -> Result<(), String> {
+        if current == self.text {
+            return Ok(());
+        }
+        self.local_edit(TextOp::diff(&self.text, current))
+    }
+
+    /// Share the local cursors and selections
+    pub fn set_selections(&mut self, selections: Vec<SelectionRange>) {
+        if selections == self.selections {
+            return;
+        }
+        self.selections = selections.clone();
+        if self.is_joined() {
+            self.outbox.push(CollabMessage::Selections { selections });
+        }
+    }
+
+    /// Handle a message from the server, returning an edit to apply to the
+    /// local editor
+    pub fn receive(&mut self, message: CollabMessage) -> Result<Option<TextOp>, String> {
+        match message {
+            CollabMessage::Welcome { client_id, revision, content, peers } => {
+                let op = TextOp::diff(&self.text, &content);
+                self.client_id = Some(client_id);
+                self.revision = revision;
+                self.text = content;
+                self.state = SyncState::Synchronized;
+                self.peers = peers.into_iter().map(|peer| (peer.client_id, peer)).collect();
+                if !self.selections.is_empty() {
+                    self.outbox.push(CollabMessage::Selections { selections: self.selections.clone() });
+                }
+                Ok((!op.is_noop()).then_some(op))
+            }
+            CollabMessage::Ack { revision } => {
+                self.revision = revision;
+                self.state = match std::mem::replace(&mut self.state, SyncState::Synchronized) {
+                    SyncState::Synchronized => return Err("Unexpected acknowledgement from the server".to_string()),
+                    SyncState::AwaitingAck(_) => SyncState::Synchronized,
+                    SyncState::AwaitingWithBuffer(_, buffer) => {
+                        self.outbox.push(CollabMessage::Edit { revision, op: buffer.clone() });
+                        SyncState::AwaitingAck(buffer)
+                    }
+                };
+                Ok(None)
+            }
+            CollabMessage::RemoteEdit { revision, op, .. } => {
+                self.revision = revision;
+                let op = match std::mem::replace(&mut self.state, SyncState::Synchronized) {
+                    SyncState::Synchronized => op,
+                    SyncState::AwaitingAck(sent) => {
+                        let (sent, op) = TextOp::transform(&sent, &op)?;
+                        self.state = SyncState::AwaitingAck(sent);
+                        op
+                    }
+                    SyncState::AwaitingWithBuffer(sent, buffer) => {
+                        let (sent, op) = TextOp::transform(&sent, &op)?;
+                        let (buffer, op) = TextOp::transform(&buffer, &op)?;
+                        self.state = SyncState::AwaitingWithBuffer(sent, buffer);
+                        op
+                    }
+                };
+                self.text = op.apply(&self.text)?;
+                for peer in self.peers.values_mut() {
+                    for selection in &mut peer.selections {
+                        *selection = selection.transform(&op);
+                    }
+                }
+                Ok(Some(op))
+            }
+            CollabMessage::RemoteSelections { client_id, selections } => {
+                // The server's positions do not include edits still in flight
+                let selections = selections.into_iter().map(|selection| self.transform_from_server(selection)).collect();
+                if let Some(peer) = self.peers.get_mut(&client_id) {
+                    peer.selections = selections;
+                }
+                Ok(None)
+            }
+            CollabMessage::PeerJoined { peer } => {
+                self.peers.insert(peer.client_id, peer);
+                Ok(None)
+            }
+            CollabMessage::PeerLeft { client_id } => {
+                self.peers.remove(&client_id);
+                Ok(None)
+            }
+            CollabMessage::Error { message } => Err(message),
+            other => Err(format!("Unexpected message from server: {:?}", other)),
+        }
+    }
+
+    fn transform_from_server(&self, selection: SelectionRange) -> SelectionRange {
+        match &self.state {
+            SyncState::Synchronized => selection,
+            SyncState::AwaitingAck(sent) => selection.transform(sent),
+            SyncState::AwaitingWithBuffer(sent, buffer) => selection.transform(sent).transform(buffer),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! # Real-time Collaborative Editing
+//!
+//! Lets several editors work on the same document at once. Edits are
+//! exchanged as operational transformation operations through a relay that
+//! orders them, so concurrent edits merge without conflicts and every
+//! participant converges on the same text. Cursors and selections are shared
+//! along with the edits.
+//!
+//! - [`ot`]: text operations with compose and transform
+//! - [`protocol`]: messages between editors and the relay
+//! - [`server`] / [`client`]: the transport-independent protocol state machines
+//! - [`relay`]: TCP relay server and connection
+//! - [`session`]: a `TextBuffer` shared through a relay
+
+pub mod ot;
+pub mod protocol;
+pub mod server;
+pub mod client;
+pub mod relay;
+pub mod session;
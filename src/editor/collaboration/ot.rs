@@ -0,0 +1,383 @@
+//! # Operational Transformation
+//!
+//! Text operations in the style of Jupiter/ot.js: an operation walks the whole
+//! document and retains, inserts or deletes characters. Two operations made
+//! concurrently on the same revision can be transformed so that applying them
+//! in either order yields the same text, which is what lets every participant
+//! of a session converge without locking.
+//!
+//! All lengths and positions are counted in `char`s.
+
+use serde::{Deserialize, Serialize};
+
+use crate::editor::text_buffer::{SelectionSet, TextBuffer, TextPosition, TextRange};
+
+/// One step of a text operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpComponent {
+    /// Keep the next characters unchanged
+    Retain(usize),
+    /// Insert text at the current position
+    Insert(String),
+    /// Remove the next characters
+    Delete(usize),
+}
+
+/// Edit of a whole document, from a text of `base_len` characters to one of `target_len`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextOp {
+    components: Vec<OpComponent>,
+    base_len: usize,
+    target_len: usize,
+}
+
+impl TextOp {
+    /// Create an empty operation
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Operation that leaves a document of `len` characters unchanged
+    pub fn identity(len: usize) -> Self {
+        let mut op = Self::new();
+        op.retain(len);
+        op
+    }
+
+    /// Insert `text` at `position` of a document of `len` characters
+    pub fn insert_at(len: usize, position: usize, text: &str) -> Self {
+        let mut op = Self::new();
+        op.retain(position).insert(text).retain(len.saturating_sub(position));
+        op
+    }
+
+    /// Delete `start..end` of a document of `len` characters; the bounds may
+    /// come in either order, as from a selection made backwards
+    pub fn delete_range(len: usize, start: usize, end: usize) -> Self {
+        let (start, end) = (start.min(end), start.max(end));
+        let mut op = Self::new();
+        op.retain(start).delete(end - start).retain(len.saturating_sub(end));
+        op
+    }
+
+    /// Smallest operation turning `old` into `new`: one replaced span between
+    /// their common prefix and suffix
+    pub fn diff(old: &str, new: &str) -> Self {
+        let old: Vec<char> = old.chars().collect();
+        let new: Vec<char> = new.chars().collect();
+        let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+        let suffix = old[prefix..]
+            .iter()
+            .rev()
+            .zip(new[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let mut op = Self::new();
+        let inserted: String = new[prefix..new.len() - suffix].iter().collect();
+        op.retain(prefix)
+            .delete(old.len() - suffix - prefix)
+            .insert(&inserted)
+            .retain(suffix);
+        op
+    }
+
+    /// Components of the operation
+    pub fn components(&self) -> &[OpComponent] {
+        &self.components
+    }
+
+    /// Length of the text the operation applies to
+    pub fn base_len(&self) -> usize {
+        self.base_len
+    }
+
+    /// Length of the text the operation produces
+    pub fn target_len(&self) -> usize {
+        self.target_len
+    }
+
+    /// Whether the operation leaves the text unchanged
+    pub fn is_noop(&self) -> bool {
+        self.components.iter().all(|component| matches!(component, OpComponent::Retain(_)))
+    }
+
+    /// Append a retain of `count` characters
+    pub fn retain(&mut self, count: usize) -> &mut Self {
+        if count == 0 {
+            return self;
+        }
+        self.base_len += count;
+        self.target_len += count;
+        if let Some(OpComponent::Retain(last)) = self.components.last_mut() {
+            *last += count;
+        } else {
+            self.components.push(OpComponent::Retain(count));
+        }
+        self
+    }
+
+    /// Append an insertion; inserts are kept before an adjacent delete so
+    /// equivalent operations compare equal
+    pub fn insert(&mut self, text: &str) -> &mut Self {
+        if text.is_empty() {
+            return self;
+        }
+        self.target_len += text.chars().count();
+        let len = self.components.len();
+        match self.components.as_mut_slice() {
+            [.., OpComponent::Insert(last)] => last.push_str(text),
+            [.., OpComponent::Insert(last), OpComponent::Delete(_)] => last.push_str(text),
+            [.., OpComponent::Delete(_)] => self.components.insert(len - 1, OpComponent::Insert(text.to_string())),
+            _ => self.components.push(OpComponent::Insert(text.to_string())),
+        }
+        self
+    }
+
+    /// Append a deletion of `count` characters
+    pub fn delete(&mut self, count: usize) -> &mut Self {
+        if count == 0 {
+            return self;
+        }
+        self.base_len += count;
+        if let Some(OpComponent::Delete(last)) = self.components.last_mut() {
+            *last += count;
+        } else {
+            self.components.push(OpComponent::Delete(count));
+        }
+        self
+    }
+
+    /// Apply the operation to a string
+    pub fn apply(&self, text: &str) -> Result<String, String> {
+        let len = text.chars().count();
+        if len != self.base_len {
+            return Err(format!("Operation expects a document of {} characters, found {}", self.base_len, len));
+        }
+
+        let mut chars = text.chars();
+        let mut result = String::with_capacity(text.len());
+        for component in &self.components {
+            match component {
+                OpComponent::Retain(count) => result.extend(chars.by_ref().take(*count)),
+                OpComponent::Insert(inserted) => result.push_str(inserted),
+                OpComponent::Delete(count) => {
+                    chars.by_ref().take(*count).for_each(drop);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Apply the operation to a text buffer in place
+    pub fn apply_to_buffer(&self, buffer: &mut TextBuffer) -> Result<(), String> {
+        let len = buffer.rope.len_chars();
+        if len != self.base_len {
+            return Err(format!("Operation expects a document of {} characters, found {}", self.base_len, len));
+        }
+
+        let mut index = 0;
+        for component in &self.components {
+            match component {
+                OpComponent::Retain(count) => index += count,
+                OpComponent::Insert(text) => {
+                    let position = char_position(buffer, index);
+                    buffer.insert(position, text, SelectionSet::new()).map_err(|e| e.to_string())?;
+                    index += text.chars().count();
+                }
+                OpComponent::Delete(count) => {
+                    let range = TextRange {
+                        start: char_position(buffer, index),
+                        end: char_position(buffer, index + count),
+                    };
+                    buffer.delete(range, SelectionSet::new()).map_err(|e| e.to_string())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Combine this operation with one applied after it into a single operation
+    pub fn compose(&self, next: &TextOp) -> Result<TextOp, String> {
+        if self.target_len != next.base_len {
+            return Err("Cannot compose operations: length mismatch".to_string());
+        }
+
+        let mut result = TextOp::new();
+        let mut first_ops = self.components.iter().cloned();
+        let mut second_ops = next.components.iter().cloned();
+        let mut first = first_ops.next();
+        let mut second = second_ops.next();
+
+        loop {
+            match (first.take(), second.take()) {
+                (None, None) => break,
+                (Some(OpComponent::Delete(count)), other) => {
+                    result.delete(count);
+                    first = first_ops.next();
+                    second = other;
+                }
+                (other, Some(OpComponent::Insert(text))) => {
+                    result.insert(&text);
+                    first = other;
+                    second = second_ops.next();
+                }
+                (None, _) | (_, None) => return Err("Cannot compose operations: length mismatch".to_string()),
+                (Some(OpComponent::Retain(a)), Some(OpComponent::Retain(b))) => {
+                    let count = a.min(b);
+                    result.retain(count);
+                    first = remainder(a, count, OpComponent::Retain, &mut first_ops);
+                    second = remainder(b, count, OpComponent::Retain, &mut second_ops);
+                }
+                (Some(OpComponent::Insert(text)), Some(OpComponent::Delete(b))) => {
+                    // Text inserted by the first operation and deleted by the second
+                    let len = text.chars().count();
+                    let count = len.min(b);
+                    first = if len > count { Some(OpComponent::Insert(text.chars().skip(count).collect())) } else { first_ops.next() };
+                    second = remainder(b, count, OpComponent::Delete, &mut second_ops);
+                }
+                (Some(OpComponent::Insert(text)), Some(OpComponent::Retain(b))) => {
+                    let len = text.chars().count();
+                    let count = len.min(b);
+                    let (kept, rest) = split_chars(&text, count);
+                    result.insert(&kept);
+                    first = if rest.is_empty() { first_ops.next() } else { Some(OpComponent::Insert(rest)) };
+                    second = remainder(b, count, OpComponent::Retain, &mut second_ops);
+                }
+                (Some(OpComponent::Retain(a)), Some(OpComponent::Delete(b))) => {
+                    let count = a.min(b);
+                    result.delete(count);
+                    first = remainder(a, count, OpComponent::Retain, &mut first_ops);
+                    second = remainder(b, count, OpComponent::Delete, &mut second_ops);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Transform two operations made concurrently on the same text.
+    ///
+    /// Returns `(a', b')` such that applying `a` then `b'` gives the same text as
+    /// `b` then `a'`. When both insert at the same position, the text of `a`
+    /// ends up first.
+    pub fn transform(a: &TextOp, b: &TextOp) -> Result<(TextOp, TextOp), String> {
+        if a.base_len != b.base_len {
+            return Err("Cannot transform operations on documents of different lengths".to_string());
+        }
+
+        let mut a_prime = TextOp::new();
+        let mut b_prime = TextOp::new();
+        let mut a_ops = a.components.iter().cloned();
+        let mut b_ops = b.components.iter().cloned();
+        let mut first = a_ops.next();
+        let mut second = b_ops.next();
+
+        loop {
+            match (first.take(), second.take()) {
+                (None, None) => break,
+                (Some(OpComponent::Insert(text)), other) => {
+                    b_prime.retain(text.chars().count());
+                    a_prime.insert(&text);
+                    first = a_ops.next();
+                    second = other;
+                }
+                (other, Some(OpComponent::Insert(text))) => {
+                    a_prime.retain(text.chars().count());
+                    b_prime.insert(&text);
+                    first = other;
+                    second = b_ops.next();
+                }
+                (None, _) | (_, None) => return Err("Cannot transform operations: length mismatch".to_string()),
+                (Some(OpComponent::Retain(x)), Some(OpComponent::Retain(y))) => {
+                    let count = x.min(y);
+                    a_prime.retain(count);
+                    b_prime.retain(count);
+                    first = remainder(x, count, OpComponent::Retain, &mut a_ops);
+                    second = remainder(y, count, OpComponent::Retain, &mut b_ops);
+                }
+                (Some(OpComponent::Delete(x)), Some(OpComponent::Delete(y))) => {
+                    // Both removed the same text
+                    let count = x.min(y);
+                    first = remainder(x, count, OpComponent::Delete, &mut a_ops);
+                    second = remainder(y, count, OpComponent::Delete, &mut b_ops);
+                }
+                (Some(OpComponent::Delete(x)), Some(OpComponent::Retain(y))) => {
+                    let count = x.min(y);
+                    a_prime.delete(count);
+                    first = remainder(x, count, OpComponent::Delete, &mut a_ops);
+                    second = remainder(y, count, OpComponent::Retain, &mut b_ops);
+                }
+                (Some(OpComponent::Retain(x)), Some(OpComponent::Delete(y))) => {
+                    let count = x.min(y);
+                    b_prime.delete(count);
+                    first = remainder(x, count, OpComponent::Retain, &mut a_ops);
+                    second = remainder(y, count, OpComponent::Delete, &mut b_ops);
+                }
+            }
+        }
+        Ok((a_prime, b_prime))
+    }
+
+    /// Position of a character index after this operation is applied.
+    ///
+    /// Text inserted exactly at the index pushes it forward, which keeps remote
+    /// cursors behind what was typed in front of them.
+    pub fn transform_index(&self, index: usize) -> usize {
+        let mut remaining = index as i64;
+        let mut new_index = index as i64;
+        for component in &self.components {
+            match component {
+                OpComponent::Retain(count) => remaining -= *count as i64,
+                OpComponent::Insert(text) => new_index += text.chars().count() as i64,
+                OpComponent::Delete(count) => {
+                    new_index -= remaining.min(*count as i64);
+                    remaining -= *count as i64;
+                }
+            }
+            if remaining < 0 {
+                break;
+            }
+        }
+        new_index.max(0) as usize
+    }
+}
+
+/// Transform an operation made on an older revision past the operations that
+/// were applied since, so it applies to the latest text
+pub fn rebase(op: &TextOp, concurrent: &[TextOp]) -> Result<TextOp, String> {
+    let mut op = op.clone();
+    for applied in concurrent {
+        op = TextOp::transform(&op, applied)?.0;
+    }
+    Ok(op)
+}
+
+/// Rest of a component of `total` characters after `used` were consumed
+fn remainder<I: Iterator<Item = OpComponent>>(
+    total: usize,
+    used: usize,
+    make: fn(usize) -> OpComponent,
+    rest: &mut I,
+) -> Option<OpComponent> {
+    if total > used {
+        Some(make(total - used))
+    } else {
+        rest.next()
+    }
+}
+
+fn split_chars(text: &str, count: usize) -> (String, String) {
+    let split = text.char_indices().nth(count).map(|(index, _)| index).unwrap_or(text.len());
+    (text[..split].to_string(), text[split..].to_string())
+}
+
+/// Buffer position of a character index
+pub fn char_position(buffer: &TextBuffer, index: usize) -> TextPosition {
+    let line = buffer.rope.char_to_line(index);
+    TextPosition {
+        line,
+        column: index - buffer.rope.line_to_char(line),
+        offset: buffer.rope.char_to_byte(index),
+    }
+}
@@ -0,0 +1,119 @@
+//! # Collaboration Wire Protocol
+//!
+//! Messages exchanged between editors and the relay. Each message is one line
+//! of JSON, so the same framing works over TCP and is easy to inspect.
+
+use serde::{Deserialize, Serialize};
+
+use super::ot::TextOp;
+use crate::editor::text_buffer::{Cursor, TextBuffer};
+
+/// Identifier the relay assigns to each connected editor
+pub type ClientId = u64;
+
+/// Who is editing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerInfo {
+    /// Stable user identifier
+    pub user_id: String,
+    /// Name shown next to the user's cursor
+    pub display_name: String,
+}
+
+/// A selection as character offsets; an empty selection is a cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelectionRange {
+    /// Where the selection started
+    pub anchor: usize,
+    /// Where the cursor is
+    pub head: usize,
+}
+
+impl SelectionRange {
+    /// Cursor without a selection
+    pub fn caret(position: usize) -> Self {
+        Self { anchor: position, head: position }
+    }
+
+    /// Selection of a text buffer cursor
+    pub fn from_cursor(buffer: &TextBuffer, cursor: &Cursor) -> Self {
+        let to_char = |offset: usize| buffer.rope.byte_to_char(offset.min(buffer.rope.len_bytes()));
+        let head = to_char(cursor.position.offset);
+        let anchor = cursor.anchor.as_ref().map(|anchor| to_char(anchor.offset)).unwrap_or(head);
+        Self { anchor, head }
+    }
+
+    /// Move the selection through an edit made by someone else
+    pub fn transform(&self, op: &TextOp) -> Self {
+        Self {
+            anchor: op.transform_index(self.anchor),
+            head: op.transform_index(self.head),
+        }
+    }
+}
+
+/// A participant of a document as seen by the others
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerState {
+    pub client_id: ClientId,
+    pub peer: PeerInfo,
+    pub selections: Vec<SelectionRange>,
+}
+
+/// Message between an editor and the relay
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CollabMessage {
+    /// Editor → relay: open a shared document; `content` seeds it when nobody
+    /// has opened it yet
+    Join {
+        document: String,
+        peer: PeerInfo,
+        content: String,
+    },
+    /// Relay → editor: the document as of `revision` and who else is editing it
+    Welcome {
+        client_id: ClientId,
+        revision: u64,
+        content: String,
+        peers: Vec<PeerState>,
+    },
+    /// Editor → relay: an edit made on top of `revision`
+    Edit { revision: u64, op: TextOp },
+    /// Relay → author: the edit became `revision`
+    Ack { revision: u64 },
+    /// Relay → other editors: someone's edit, already transformed to apply on
+    /// top of `revision - 1`
+    RemoteEdit {
+        client_id: ClientId,
+        revision: u64,
+        op: TextOp,
+    },
+    /// Editor → relay: the editor's cursors and selections
+    Selections { selections: Vec<SelectionRange> },
+    /// Relay → other editors: a participant's cursors and selections moved
+    RemoteSelections {
+        client_id: ClientId,
+        selections: Vec<SelectionRange>,
+    },
+    /// Relay → editors: someone opened the document
+    PeerJoined { peer: PeerState },
+    /// Relay → editors: someone left the document
+    PeerLeft { client_id: ClientId },
+    /// Relay → editor: a request could not be handled
+    Error { message: String },
+}
+
+impl CollabMessage {
+    /// Encode as one line of JSON, including the newline
+    pub fn to_line(&self) -> Result<String, String> {
+        let mut line = serde_json::to_string(self).map_err(|e| format!("Failed to encode message: {}", e))?;
+        line.push('\n');
+        Ok(line)
+    }
+
+    /// Decode one line of JSON
+    pub fn from_line(line: &str) -> Result<Self, String> {
+        serde_json::from_str(line.trim_end()).map_err(|e| format!("Invalid collaboration message: {}", e))
+    }
+}
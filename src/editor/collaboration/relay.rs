@@ -0,0 +1,167 @@
+//! # Collaboration Relay
+//!
+//! TCP transport for the collaboration protocol. The relay runs a
+//! [`CollabServer`] behind a listening socket so editors on the same network
+//! can share documents; [`RelayConnection`] is the editor side of a socket.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use super::protocol::{ClientId, CollabMessage};
+use super::server::{CollabServer, Outgoing};
+
+/// Default port of the relay
+pub const DEFAULT_RELAY_PORT: u16 = 7878;
+
+type Connections = Arc<Mutex<HashMap<ClientId, TcpStream>>>;
+
+/// Relay server accepting editor connections
+pub struct RelayServer {
+    listener: TcpListener,
+    server: Arc<Mutex<CollabServer>>,
+    connections: Connections,
+}
+
+impl RelayServer {
+    /// Listen on `addr`
+    pub fn bind(addr: impl ToSocketAddrs) -> Result<Self, String> {
+        let listener = TcpListener::bind(addr).map_err(|e| format!("Failed to bind relay: {}", e))?;
+        Ok(Self {
+            listener,
+            server: Arc::new(Mutex::new(CollabServer::new())),
+            connections: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Address the relay listens on
+    pub fn local_addr(&self) -> Result<SocketAddr, String> {
+        self.listener.local_addr().map_err(|e| e.to_string())
+    }
+
+    /// Shared documents, for inspection
+    pub fn server(&self) -> Arc<Mutex<CollabServer>> {
+        Arc::clone(&self.server)
+    }
+
+    /// Accept connections until the listener fails
+    pub fn run(self) -> Result<(), String> {
+        for stream in self.listener.incoming() {
+            match stream {
+                Ok(stream) => self.accept(stream)?,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(format!("Relay stopped accepting connections: {}", e)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the relay on a background thread
+    pub fn spawn(self) -> JoinHandle<Result<(), String>> {
+        std::thread::spawn(move || self.run())
+    }
+
+    fn accept(&self, stream: TcpStream) -> Result<(), String> {
+        let _ = stream.set_nodelay(true);
+        let writer = stream.try_clone().map_err(|e| format!("Failed to accept connection: {}", e))?;
+        let client_id = lock(&self.server).connect();
+        lock(&self.connections).insert(client_id, writer);
+
+        let server = Arc::clone(&self.server);
+        let connections = Arc::clone(&self.connections);
+        std::thread::spawn(move || {
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else { break };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                // Hold the server lock while delivering so every client sees
+                // revisions in order
+                let mut server = lock(&server);
+                let outgoing = match CollabMessage::from_line(&line) {
+                    Ok(message) => server.handle(client_id, message),
+                    Err(message) => vec![(client_id, CollabMessage::Error { message })],
+                };
+                deliver(&connections, outgoing);
+            }
+
+            let mut server = lock(&server);
+            lock(&connections).remove(&client_id);
+            let outgoing = server.disconnect(client_id);
+            deliver(&connections, outgoing);
+        });
+        Ok(())
+    }
+}
+
+/// Write messages to their clients; a failed write is cleaned up by the
+/// client's reader thread
+fn deliver(connections: &Connections, outgoing: Vec<Outgoing>) {
+    let mut connections = lock(connections);
+    for (client_id, message) in outgoing {
+        let (Some(stream), Ok(line)) = (connections.get_mut(&client_id), message.to_line()) else {
+            continue;
+        };
+        let _ = stream.write_all(line.as_bytes());
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Editor connection to a relay
+pub struct RelayConnection {
+    stream: TcpStream,
+    incoming: Receiver<CollabMessage>,
+}
+
+impl RelayConnection {
+    /// Connect to a relay
+    pub fn connect(addr: impl ToSocketAddrs) -> Result<Self, String> {
+        let stream = TcpStream::connect(addr).map_err(|e| format!("Failed to connect to relay: {}", e))?;
+        let _ = stream.set_nodelay(true);
+        let reader = stream.try_clone().map_err(|e| format!("Failed to connect to relay: {}", e))?;
+
+        let (sender, incoming) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else { break };
+                let message = CollabMessage::from_line(&line).unwrap_or_else(|message| CollabMessage::Error { message });
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self { stream, incoming })
+    }
+
+    /// Send a message to the relay
+    pub fn send(&mut self, message: &CollabMessage) -> Result<(), String> {
+        self.stream
+            .write_all(message.to_line()?.as_bytes())
+            .map_err(|e| format!("Failed to send to relay: {}", e))
+    }
+
+    /// Next message from the relay, if one arrived
+    pub fn try_recv(&self) -> Result<Option<CollabMessage>, String> {
+        match self.incoming.try_recv() {
+            Ok(message) => Ok(Some(message)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err("Connection to the relay was closed".to_string()),
+        }
+    }
+
+    /// Wait up to `timeout` for the next message from the relay
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<Option<CollabMessage>, String> {
+        match self.incoming.recv_timeout(timeout) {
+            Ok(message) => Ok(Some(message)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err("Connection to the relay was closed".to_string()),
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! # Collaboration Server
+//!
+//! The authority every editor of a document talks to. It orders concurrent
+//! edits: an edit made on an older revision is transformed past everything
+//! applied since, appended to the history and sent to the other participants.
+//! The server only routes messages; the relay owns the sockets.
+
+use std::collections::HashMap;
+
+use super::ot::{rebase, TextOp};
+use super::protocol::{ClientId, CollabMessage, PeerInfo, PeerState, SelectionRange};
+
+/// A message for one client
+pub type Outgoing = (ClientId, CollabMessage);
+
+/// A document shared by the server
+#[derive(Debug, Clone, Default)]
+pub struct HostedDocument {
+    /// Current text
+    pub content: String,
+    /// Every edit applied, revision `n` being `history[n - 1]`
+    pub history: Vec<TextOp>,
+    /// Participants with their cursors
+    pub participants: HashMap<ClientId, PeerState>,
+}
+
+impl HostedDocument {
+    /// Create a document at revision 0
+    pub fn new(content: String) -> Self {
+        Self { content, ..Self::default() }
+    }
+
+    /// Latest revision
+    pub fn revision(&self) -> u64 {
+        self.history.len() as u64
+    }
+
+    /// Apply an edit made on `revision`, returning it as applied to the latest text
+    pub fn apply(&mut self, revision: u64, op: &TextOp) -> Result<TextOp, String> {
+        let concurrent = self
+            .history
+            .get(revision as usize..)
+            .ok_or_else(|| format!("Unknown revision {} (latest is {})", revision, self.revision()))?;
+        let op = rebase(op, concurrent)?;
+        self.content = op.apply(&self.content)?;
+        for participant in self.participants.values_mut() {
+            for selection in &mut participant.selections {
+                *selection = selection.transform(&op);
+            }
+        }
+        self.history.push(op.clone());
+        Ok(op)
+    }
+}
+
+/// Routes edits and cursors between the participants of shared documents
+#[derive(Debug, Default)]
+pub struct CollabServer {
+    documents: HashMap<String, HostedDocument>,
+    /// Document each joined client edits
+    clients: HashMap<ClientId, String>,
+    next_client_id: ClientId,
+}
+
+impl CollabServer {
+    /// Create a server without documents
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocate an id for a new connection
+    pub fn connect(&mut self) -> ClientId {
+        self.next_client_id += 1;
+        self.next_client_id
+    }
+
+    /// A shared document by name
+    pub fn document(&self, name: &str) -> Option<&HostedDocument> {
+        self.documents.get(name)
+    }
+
+    /// Names of the shared documents
+    pub fn document_names(&self) -> Vec<&str> {
+        self.documents.keys().map(String::as_str).collect()
+    }
+
+    /// Handle a message from a client, returning the messages to deliver
+    pub fn handle(&mut self, client_id: ClientId, message: CollabMessage) -> Vec<Outgoing> {
+        match self.dispatch(client_id, message) {
+            Ok(outgoing) => outgoing,
+            Err(message) => vec![(client_id, CollabMessage::Error { message })],
+        }
+    }
+
+    /// Remove a client, telling the others it left
+    pub fn disconnect(&mut self, client_id: ClientId) -> Vec<Outgoing> {
+        let Some(name) = self.clients.remove(&client_id) else {
+            return Vec::new();
+        };
+        let Some(document) = self.documents.get_mut(&name) else {
+            return Vec::new();
+        };
+        document.participants.remove(&client_id);
+        document
+            .participants
+            .keys()
+            .map(|&other| (other, CollabMessage::PeerLeft { client_id }))
+            .collect()
+    }
+
+    fn dispatch(&mut self, client_id: ClientId, message: CollabMessage) -> Result<Vec<Outgoing>, String> {
+        match message {
+            CollabMessage::Join { document, peer, content } => Ok(self.join(client_id, document, peer, content)),
+            CollabMessage::Edit { revision, op } => {
+                let document = self.joined_document(client_id)?;
+                let op = document.apply(revision, &op)?;
+                let revision = document.revision();
+                let mut outgoing = vec![(client_id, CollabMessage::Ack { revision })];
+                outgoing.extend(Self::broadcast(document, client_id, CollabMessage::RemoteEdit { client_id, revision, op }));
+                Ok(outgoing)
+            }
+            CollabMessage::Selections { selections } => {
+                let document = self.joined_document(client_id)?;
+                let len = document.content.chars().count();
+                let selections: Vec<SelectionRange> = selections
+                    .into_iter()
+                    .map(|selection| SelectionRange { anchor: selection.anchor.min(len), head: selection.head.min(len) })
+                    .collect();
+                if let Some(participant) = document.participants.get_mut(&client_id) {
+                    participant.selections = selections.clone();
+                }
+                Ok(Self::broadcast(document, client_id, CollabMessage::RemoteSelections { client_id, selections }))
+            }
+            other => Err(format!("Unexpected message from client: {:?}", other)),
+        }
+    }
+
+    fn join(&mut self, client_id: ClientId, name: String, peer: PeerInfo, content: String) -> Vec<Outgoing> {
+        // A client edits one document at a time
+        let mut outgoing = self.disconnect(client_id);
+
+        let document = self.documents.entry(name.clone()).or_insert_with(|| HostedDocument::new(content));
+        let state = PeerState { client_id, peer, selections: Vec::new() };
+        let mut peers: Vec<PeerState> = document.participants.values().cloned().collect();
+        peers.sort_by_key(|peer| peer.client_id);
+
+        outgoing.push((
+            client_id,
+            CollabMessage::Welcome {
+                client_id,
+                revision: document.revision(),
+                content: document.content.clone(),
+                peers,
+            },
+        ));
+        outgoing.extend(Self::broadcast(document, client_id, CollabMessage::PeerJoined { peer: state.clone() }));
+        document.participants.insert(client_id, state);
+        self.clients.insert(client_id, name);
+        outgoing
+    }
+
+    fn joined_document(&mut self, client_id: ClientId) -> Result<&mut HostedDocument, String> {
+        self.clients
+            .get(&client_id)
+            .and_then(|name| self.documents.get_mut(name))
+            .ok_or_else(|| "Join a document first".to_string())
+    }
+
+    /// The message for every participant except `sender`
+    fn broadcast(document: &HostedDocument, sender: ClientId, message: CollabMessage) -> Vec<Outgoing> {
+        let mut recipients: Vec<ClientId> = document.participants.keys().copied().filter(|&id| id != sender).collect();
+        recipients.sort_unstable();
+        recipients.into_iter().map(|id| (id, message.clone())).collect()
+    }
+}
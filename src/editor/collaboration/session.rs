@@ -0,0 +1,73 @@
+//! # Collaborative Editing Session
+//!
+//! Binds a [`TextBuffer`] to a shared document on a relay. The editor keeps
+//! editing the buffer as usual and calls [`CollabSession::poll`] every frame:
+//! local changes are picked up by comparing the buffer with the shared text,
+//! and remote edits are applied to the buffer.
+
+use std::net::ToSocketAddrs;
+
+use super::client::CollabClient;
+use super::protocol::{PeerInfo, PeerState, SelectionRange};
+use super::relay::RelayConnection;
+use crate::editor::text_buffer::{SelectionSet, TextBuffer};
+
+/// A text buffer shared through a relay
+pub struct CollabSession {
+    client: CollabClient,
+    connection: RelayConnection,
+}
+
+impl CollabSession {
+    /// Connect to a relay and join `document` with the buffer's content
+    pub fn connect(addr: impl ToSocketAddrs, document: &str, peer: PeerInfo, buffer: &TextBuffer) -> Result<Self, String> {
+        let mut client = CollabClient::new(document, peer);
+        client.join(&buffer.to_string());
+        let mut session = Self { client, connection: RelayConnection::connect(addr)? };
+        session.flush()?;
+        Ok(session)
+    }
+
+    /// Exchange edits with the relay; returns whether the buffer changed
+    pub fn poll(&mut self, buffer: &mut TextBuffer) -> Result<bool, String> {
+        // Local edits first, so remote edits are transformed past them
+        if self.client.is_joined() {
+            self.client.sync_text(&buffer.to_string())?;
+        }
+
+        let mut changed = false;
+        while let Some(message) = self.connection.try_recv()? {
+            if let Some(op) = self.client.receive(message)? {
+                op.apply_to_buffer(buffer)?;
+                changed = true;
+            }
+        }
+
+        self.flush()?;
+        Ok(changed)
+    }
+
+    /// Share the editor's cursors and selections
+    pub fn set_selections(&mut self, buffer: &TextBuffer, selection: &SelectionSet) -> Result<(), String> {
+        let selections = selection.cursors.iter().map(|cursor| SelectionRange::from_cursor(buffer, cursor)).collect();
+        self.client.set_selections(selections);
+        self.flush()
+    }
+
+    /// Other participants and their cursors
+    pub fn peers(&self) -> Vec<&PeerState> {
+        self.client.peers()
+    }
+
+    /// Protocol state of the session
+    pub fn client(&self) -> &CollabClient {
+        &self.client
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        for message in self.client.take_outgoing() {
+            self.connection.send(&message)?;
+        }
+        Ok(())
+    }
+}
@@ -11,6 +11,13 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant, SystemTime};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::collaboration::ot::{rebase, OpComponent, TextOp};
+use super::collaboration::protocol::PeerState;
+
+/// Cursor movements kept per user
+const MAX_CURSOR_MOVEMENTS: usize = 100;
 
 /// Main collaborative development engine
 pub struct CollaborativeDevelopmentEngine {
@@ -179,6 +186,8 @@ pub struct EditingSession {
     pub document_state: DocumentState,
     /// Operation history
     pub operation_history: Vec<Operation>,
+    /// Edits applied to the document; version `n + 1` is the result of `revisions[n - 1]`
+    pub revisions: Vec<TextOp>,
     /// Session metadata
     pub metadata: HashMap<String, String>,
 }
@@ -548,7 +557,7 @@ pub struct AutoResolutionRule {
 // Enums and supporting types
 
 /// Types of operations in operational transformation
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum OperationType {
     Insert,
     Delete,
@@ -1087,7 +1096,7 @@ pub struct WorkflowNotification {
 }
 
 /// Types of notifications
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum NotificationType {
     Email,
     InApp,
@@ -1292,7 +1301,7 @@ pub struct PerformanceTracker {
 }
 
 /// Throughput metrics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ThroughputMetrics {
     pub operations_per_second: f32,
     pub bandwidth_usage: f32,
@@ -1301,7 +1310,7 @@ pub struct ThroughputMetrics {
 }
 
 /// Resource usage tracking
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ResourceUsage {
     pub cpu_usage: f32,
     pub memory_usage: f32,
@@ -1365,11 +1374,49 @@ impl CollaborativeDevelopmentEngine {
         self.workspace_manager.join_workspace(workspace_id, user_id)
     }
 
+    /// Join an existing editing session, returning the document version to edit on
+    pub fn join_editing_session(&mut self, session_id: &str, user_id: String) -> Result<u64, String> {
+        self.realtime_editor.join_session(session_id, user_id)
+    }
+
+    /// Apply an edit a user made on `base_version` of a session's document.
+    ///
+    /// Edits made concurrently on the same version are transformed so they all
+    /// apply; the edit as applied to the latest version is returned.
+    pub fn apply_edit(&mut self, session_id: &str, user_id: &str, base_version: u64, op: &TextOp) -> Result<TextOp, String> {
+        self.realtime_editor.apply_edit(session_id, user_id, base_version, op)
+    }
+
+    /// Current state of a session's document
+    pub fn document_state(&self, session_id: &str) -> Option<&DocumentState> {
+        self.realtime_editor.active_sessions.get(session_id).map(|session| &session.document_state)
+    }
+
+    /// Update where a user's cursor is
+    pub fn update_cursor(&mut self, cursor: CursorPosition) {
+        self.realtime_editor.cursor_tracker.update_cursor(cursor);
+    }
+
+    /// Cursors of everyone in `file`
+    pub fn cursors_in(&self, file: &Path) -> Vec<&CursorPosition> {
+        self.realtime_editor.cursor_tracker.cursors_in(file)
+    }
+
     /// Update user presence
     pub fn update_presence(&mut self, user_id: String, presence: UserPresence) -> Result<(), String> {
         self.presence_system.update_presence(user_id, presence)
     }
 
+    /// Mirror the participants of a shared document in the presence information
+    pub fn sync_presence(&mut self, file: &Path, peers: &[&PeerState]) {
+        self.presence_system.sync_peers(file, peers);
+    }
+
+    /// Users currently online
+    pub fn online_users(&self) -> Vec<&UserPresence> {
+        self.presence_system.online_users()
+    }
+
     /// Get collaboration metrics
     pub fn get_metrics(&self) -> &CollaborationMetrics {
         &self.metrics
@@ -1408,25 +1455,128 @@ impl RealtimeEditingEngine {
         let mut participants = HashSet::new();
         participants.insert(user_id);
 
+        let content = std::fs::read_to_string(&document_path).unwrap_or_default();
         let session = EditingSession {
             session_id: session_id.clone(),
             document_path,
             participants,
             start_time: Instant::now(),
             document_state: DocumentState {
-                content: String::new(),
+                checksum: checksum(&content),
+                content,
                 version: 1,
-                checksum: String::new(),
                 last_modified: SystemTime::now(),
                 locked_regions: Vec::new(),
             },
             operation_history: Vec::new(),
+            revisions: Vec::new(),
             metadata: HashMap::new(),
         };
 
         self.active_sessions.insert(session_id.clone(), session);
         Ok(session_id)
     }
+
+    fn join_session(&mut self, session_id: &str, user_id: String) -> Result<u64, String> {
+        let session = self.active_sessions.get_mut(session_id).ok_or("Editing session not found")?;
+        session.participants.insert(user_id);
+        Ok(session.document_state.version)
+    }
+
+    fn apply_edit(&mut self, session_id: &str, user_id: &str, base_version: u64, op: &TextOp) -> Result<TextOp, String> {
+        let session = self.active_sessions.get_mut(session_id).ok_or("Editing session not found")?;
+        if !session.participants.contains(user_id) {
+            return Err(format!("{} has not joined the editing session", user_id));
+        }
+        let concurrent = base_version
+            .checked_sub(1)
+            .and_then(|applied| session.revisions.get(applied as usize..))
+            .ok_or_else(|| format!("Unknown document version {}", base_version))?;
+
+        let op = self.ot_engine.rebase(user_id, op, concurrent)?;
+        let content = op.apply(&session.document_state.content)?;
+        let previous = std::mem::replace(&mut session.document_state.content, content);
+
+        let state = &mut session.document_state;
+        state.version += 1;
+        state.checksum = checksum(&state.content);
+        state.last_modified = SystemTime::now();
+        session.revisions.push(op.clone());
+        session.operation_history.push(Operation::from_text_op(&op, user_id, self.ot_engine.state_vector.user_states.clone()));
+
+        self.cursor_tracker.transform_cursors(&session.document_path, &previous, &state.content, &op);
+        Ok(op)
+    }
+}
+
+impl Operation {
+    /// Summarize a text operation for the session history
+    fn from_text_op(op: &TextOp, author: &str, state_vector: HashMap<String, u64>) -> Self {
+        let mut index = 0;
+        let mut position = None;
+        let mut content = String::new();
+        let (mut inserts, mut deletes) = (false, false);
+        for component in op.components() {
+            match component {
+                OpComponent::Retain(count) => index += count,
+                OpComponent::Insert(text) => {
+                    position.get_or_insert(index);
+                    content.push_str(text);
+                    inserts = true;
+                }
+                OpComponent::Delete(count) => {
+                    position.get_or_insert(index);
+                    index += count;
+                    deletes = true;
+                }
+            }
+        }
+
+        Self {
+            operation_id: uuid::Uuid::new_v4().to_string(),
+            operation_type: match (inserts, deletes) {
+                (true, false) => OperationType::Insert,
+                (false, true) => OperationType::Delete,
+                _ => OperationType::Replace,
+            },
+            position: position.unwrap_or(0),
+            content,
+            author: author.to_string(),
+            timestamp: SystemTime::now(),
+            state_vector,
+        }
+    }
+}
+
+fn checksum(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Character index of a line and column, clamped to the text
+fn char_index(text: &str, line: usize, column: usize) -> usize {
+    let mut index = 0;
+    for (number, content) in text.split('\n').enumerate() {
+        let len = content.chars().count();
+        if number == line {
+            return index + column.min(len);
+        }
+        index += len + 1;
+    }
+    text.chars().count()
+}
+
+/// Line and column of a character index
+fn line_column(text: &str, index: usize) -> (usize, usize) {
+    let (mut line, mut column) = (0, 0);
+    for ch in text.chars().take(index) {
+        if ch == '\n' {
+            line += 1;
+            column = 0;
+        } else {
+            column += 1;
+        }
+    }
+    (line, column)
 }
 
 impl CodeReviewSystem {
@@ -1537,6 +1687,43 @@ impl PresenceAwarenessSystem {
         self.user_presence.insert(user_id, presence);
         Ok(())
     }
+
+    /// Mark the participants of a shared document online in `file`, and
+    /// everyone who left it offline
+    fn sync_peers(&mut self, file: &Path, peers: &[&PeerState]) {
+        let now = SystemTime::now();
+        for presence in self.user_presence.values_mut() {
+            let in_file = presence.current_file.as_deref() == Some(file);
+            if in_file && !peers.iter().any(|peer| peer.peer.user_id == presence.user_id) {
+                presence.status = PresenceStatus::Offline;
+                presence.current_file = None;
+            }
+        }
+        for peer in peers {
+            let presence = self.user_presence.entry(peer.peer.user_id.clone()).or_insert_with(|| UserPresence {
+                user_id: peer.peer.user_id.clone(),
+                display_name: peer.peer.display_name.clone(),
+                status: PresenceStatus::Online,
+                current_file: None,
+                last_activity: now,
+                custom_status: None,
+                avatar_url: None,
+            });
+            presence.status = PresenceStatus::Online;
+            presence.current_file = Some(file.to_path_buf());
+            presence.last_activity = now;
+        }
+    }
+
+    fn online_users(&self) -> Vec<&UserPresence> {
+        let mut users: Vec<&UserPresence> = self
+            .user_presence
+            .values()
+            .filter(|presence| presence.status != PresenceStatus::Offline)
+            .collect();
+        users.sort_by(|a, b| a.user_id.cmp(&b.user_id));
+        users
+    }
 }
 
 impl ConflictResolutionEngine {
@@ -1564,6 +1751,14 @@ impl OperationalTransformEngine {
             transformation_cache: HashMap::new(),
         }
     }
+
+    /// Transform an edit made on an older version past the edits applied since
+    fn rebase(&mut self, author: &str, op: &TextOp, concurrent: &[TextOp]) -> Result<TextOp, String> {
+        let op = rebase(op, concurrent)?;
+        self.state_vector.sequence_number += 1;
+        *self.state_vector.user_states.entry(author.to_string()).or_insert(0) += 1;
+        Ok(op)
+    }
 }
 
 impl ChangeSynchronizationManager {
@@ -1586,6 +1781,42 @@ impl CursorTracker {
             update_throttle: UpdateThrottle::new(),
         }
     }
+
+    fn update_cursor(&mut self, cursor: CursorPosition) {
+        if let Some(previous) = self.cursor_positions.get(&cursor.user_id) {
+            let history = self.movement_history.entry(cursor.user_id.clone()).or_default();
+            history.push(CursorMovement {
+                from_position: (previous.line, previous.column),
+                to_position: (cursor.line, cursor.column),
+                timestamp: cursor.last_updated,
+                movement_type: if cursor.selection_start.is_some() { MovementType::Selection } else { MovementType::Navigation },
+            });
+            if history.len() > MAX_CURSOR_MOVEMENTS {
+                history.remove(0);
+            }
+        }
+        self.cursor_positions.insert(cursor.user_id.clone(), cursor);
+    }
+
+    fn cursors_in(&self, file: &Path) -> Vec<&CursorPosition> {
+        let mut cursors: Vec<&CursorPosition> = self
+            .cursor_positions
+            .values()
+            .filter(|cursor| cursor.file_path == file)
+            .collect();
+        cursors.sort_by(|a, b| a.user_id.cmp(&b.user_id));
+        cursors
+    }
+
+    /// Move the cursors in `file` through an edit turning `before` into `after`
+    fn transform_cursors(&mut self, file: &Path, before: &str, after: &str, op: &TextOp) {
+        let transform = |(line, column): (usize, usize)| line_column(after, op.transform_index(char_index(before, line, column)));
+        for cursor in self.cursor_positions.values_mut().filter(|cursor| cursor.file_path == file) {
+            (cursor.line, cursor.column) = transform((cursor.line, cursor.column));
+            cursor.selection_start = cursor.selection_start.map(transform);
+            cursor.selection_end = cursor.selection_end.map(transform);
+        }
+    }
 }
 
 impl SelectionManager {
@@ -1850,33 +2081,287 @@ pub struct ChannelSettings {
     pub external_integrations: Vec<String>,
 }
 
-// Additional stub implementations for all remaining types would follow...
-// This provides a comprehensive framework for collaborative development features
+// Supporting types referenced above whose details are not modelled yet
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccessPolicy {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActivityPattern {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActivityVisualizer {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdvanceCondition {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApprovalPolicy {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApprovalRequirement {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApprovalTracker {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchivalPolicy {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AttentionTracker {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditLogger {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AutoResolver {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AwarenessMetrics {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupStrategy {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BandwidthManager {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchOptimization {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BroadcastChannel {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Call {}
 
-// Stub implementations for remaining complex types
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CallProvider {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CallRecord {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CallSettings {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CollaborationZone {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConflictAnalytics {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConflictDetectionAlgorithm {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConflictLearningSystem {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConflictPattern {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConflictPredictionSystem {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConflictRecord {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DelegationRule {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeliveryTracker {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiscordIntegration {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileActivity {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HotspotDetector {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MessageExportManager {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MessageSearchEngine {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MessageStorage {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationChannel {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationTemplate {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Permission {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectStructure {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RateLimiter {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RealtimeConflictMonitor {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResolutionAction {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResolutionAssistant {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResolutionCondition {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResolutionPattern {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResolutionValidator {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RestorationTools {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Role {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SetupScript {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SlackIntegration {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatusPersistence {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncConflict {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncConflictResolver {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncConflictRule {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncStatus {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncStrategy {}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SyncType {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TeamsIntegration {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrafficShaping {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateProtocol {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VersionPolicy {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VisualMergeTool {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Webhook {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceArea {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceVersion {}
+// Constructors for the supporting types without state of their own
 macro_rules! impl_new_for_stub {
     ($($type:ident),*) => {
         $(
             impl $type {
                 fn new() -> Self {
-                    // Default implementation would be provided for each type
-                    // This is a simplified stub
-                    unsafe { std::mem::zeroed() }
+                    Self::default()
                 }
             }
         )*
     };
 }
 
-// Apply the macro to create stub implementations
 impl_new_for_stub!(
-    SyncConflictDetector, BandwidthOptimizer, UpdateThrottle, SelectionHighlighting, SelectionPersistence,
-    ApprovalTracker, BandwidthManager, SyncConflictResolver, AuditLogger, RestorationTools, CallSettings,
-    DeliveryTracker, MessageStorage, MessageSearchEngine, MessageExportManager, HotspotDetector,
-    ActivityVisualizer, AttentionTracker, AwarenessMetrics, RateLimiter, StatusPersistence,
-    RealtimeConflictMonitor, ConflictPredictionSystem, AutoResolver, ResolutionAssistant,
-    ResolutionValidator, ConflictLearningSystem, ConflictAnalytics, PerformanceTracker
+    ApprovalTracker, BandwidthManager, SyncConflictResolver, AuditLogger, RestorationTools,
+    CallSettings, DeliveryTracker, MessageStorage, MessageSearchEngine, MessageExportManager,
+    HotspotDetector, ActivityVisualizer, AttentionTracker, AwarenessMetrics, RateLimiter,
+    StatusPersistence, RealtimeConflictMonitor, ConflictPredictionSystem, AutoResolver,
+    ResolutionAssistant, ResolutionValidator, ConflictLearningSystem, ConflictAnalytics
 );
 
-// Additional stub implementations would be needed for full functionality
-// This framework provides the structure for a comprehensive collaborative development system
\ No newline at end of file
+impl SyncConflictDetector {
+    fn new() -> Self {
+        Self {
+            detection_rules: Vec::new(),
+            conflict_cache: HashMap::new(),
+            resolution_suggestions: HashMap::new(),
+        }
+    }
+}
+
+impl BandwidthOptimizer {
+    fn new() -> Self {
+        Self {
+            compression_enabled: false,
+            delta_sync: true,
+            batch_optimization: BatchOptimization::default(),
+            traffic_shaping: TrafficShaping::default(),
+        }
+    }
+}
+
+impl UpdateThrottle {
+    fn new() -> Self {
+        Self {
+            max_updates_per_second: 30,
+            adaptive_throttling: false,
+            priority_based: false,
+            network_aware: false,
+        }
+    }
+}
+
+impl SelectionHighlighting {
+    fn new() -> Self {
+        Self {
+            highlight_styles: HashMap::new(),
+            fade_duration: Duration::from_millis(300),
+            max_concurrent_highlights: 16,
+            conflict_resolution: HighlightConflictResolution::Overlay,
+        }
+    }
+}
+
+impl SelectionPersistence {
+    fn new() -> Self {
+        Self {
+            persist_across_sessions: false,
+            storage_backend: SelectionStorage::Memory,
+            cleanup_policy: SelectionCleanupPolicy {
+                max_age: Duration::from_secs(3600),
+                max_selections_per_user: 32,
+                cleanup_frequency: Duration::from_secs(60),
+            },
+        }
+    }
+}
+
+impl PerformanceTracker {
+    fn new() -> Self {
+        Self {
+            response_times: HashMap::new(),
+            throughput_metrics: ThroughputMetrics::default(),
+            error_rates: HashMap::new(),
+            resource_usage: ResourceUsage::default(),
+        }
+    }
+}
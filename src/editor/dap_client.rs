@@ -0,0 +1,836 @@
+//! Debug Adapter Protocol Client
+//!
+//! Implements [`DebugAdapter`] on top of the Debug Adapter Protocol (DAP), so the
+//! debugger can drive any DAP-speaking backend: `lldb-dap`, `gdb --interpreter=dap`
+//! over stdio, or `codelldb` over a local TCP port.
+//!
+//! Messages use the same `Content-Length` framing as LSP. Requests are answered
+//! synchronously (with a timeout); events are queued and handed to the debugger
+//! through [`DebugAdapter::poll_events`].
+
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::editor::debugger::*;
+
+/// Default time an adapter has to answer a request
+pub const DEFAULT_DAP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How to reach a debug adapter
+#[derive(Debug, Clone, PartialEq)]
+pub enum DapTransport {
+    /// Spawn the adapter and talk to it over stdin/stdout
+    Stdio { command: String, args: Vec<String> },
+    /// Spawn the adapter with a `--port` argument, then connect to it over TCP
+    TcpLaunch { command: String, args: Vec<String> },
+    /// Connect to an adapter that is already listening
+    Tcp { address: String },
+}
+
+impl DapTransport {
+    /// LLDB's DAP server (formerly `lldb-vscode`)
+    pub fn lldb_dap() -> Self {
+        DapTransport::Stdio { command: "lldb-dap".to_string(), args: Vec::new() }
+    }
+
+    /// CodeLLDB adapter, which listens on a TCP port
+    pub fn codelldb() -> Self {
+        DapTransport::TcpLaunch { command: "codelldb".to_string(), args: Vec::new() }
+    }
+
+    /// GDB 14+ built-in DAP interpreter
+    pub fn gdb() -> Self {
+        DapTransport::Stdio { command: "gdb".to_string(), args: vec!["--interpreter=dap".to_string()] }
+    }
+
+    /// Transport preset for a `DebugConfiguration::adapter_type`
+    pub fn for_adapter_type(adapter_type: &str) -> Option<Self> {
+        match adapter_type {
+            "lldb" | "lldb-dap" => Some(Self::lldb_dap()),
+            "codelldb" => Some(Self::codelldb()),
+            "gdb" => Some(Self::gdb()),
+            _ => None,
+        }
+    }
+}
+
+/// Framed JSON connection to a running debug adapter
+///
+/// The stream ends sit behind mutexes only so the adapter is `Sync`; all access
+/// goes through `&mut self` and `Mutex::get_mut`.
+struct DapConnection {
+    writer: Mutex<Box<dyn Write + Send>>,
+    incoming: Mutex<Receiver<Value>>,
+    child: Option<Child>,
+    next_seq: i64,
+    /// Events received while waiting for something else
+    events: VecDeque<Value>,
+    /// Responses received while waiting for a different request
+    responses: HashMap<i64, Value>,
+    timeout: Duration,
+}
+
+impl DapConnection {
+    fn open(transport: &DapTransport, timeout: Duration) -> Result<Self, DebugError> {
+        match transport {
+            DapTransport::Stdio { command, args } => {
+                let mut child = Command::new(command)
+                    .args(args)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .spawn()
+                    .map_err(|e| DebugError::CommunicationError(format!("{}: {}", command, e)))?;
+
+                let stdin = child.stdin.take()
+                    .ok_or_else(|| DebugError::CommunicationError("adapter stdin unavailable".to_string()))?;
+                let stdout = child.stdout.take()
+                    .ok_or_else(|| DebugError::CommunicationError("adapter stdout unavailable".to_string()))?;
+
+                Ok(Self::from_streams(Box::new(stdin), stdout, Some(child), timeout))
+            }
+            DapTransport::TcpLaunch { command, args } => {
+                // Reserve a free port, then hand it to the adapter
+                let port = std::net::TcpListener::bind("127.0.0.1:0")
+                    .and_then(|listener| listener.local_addr())
+                    .map(|addr| addr.port())?;
+
+                let child = Command::new(command)
+                    .args(args)
+                    .arg("--port")
+                    .arg(port.to_string())
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn()
+                    .map_err(|e| DebugError::CommunicationError(format!("{}: {}", command, e)))?;
+
+                let address = format!("127.0.0.1:{}", port);
+                let deadline = Instant::now() + timeout;
+                let stream = loop {
+                    match TcpStream::connect(&address) {
+                        Ok(stream) => break stream,
+                        Err(_) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(50)),
+                        Err(e) => return Err(DebugError::CommunicationError(format!("{}: {}", address, e))),
+                    }
+                };
+                stream.set_nodelay(true)?;
+                let reader = stream.try_clone()?;
+                Ok(Self::from_streams(Box::new(stream), reader, Some(child), timeout))
+            }
+            DapTransport::Tcp { address } => {
+                let stream = TcpStream::connect(address)
+                    .map_err(|e| DebugError::CommunicationError(format!("{}: {}", address, e)))?;
+                stream.set_nodelay(true)?;
+                let reader = stream.try_clone()?;
+                Ok(Self::from_streams(Box::new(stream), reader, None, timeout))
+            }
+        }
+    }
+
+    fn from_streams<R>(writer: Box<dyn Write + Send>, reader: R, child: Option<Child>, timeout: Duration) -> Self
+    where
+        R: Read + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(reader);
+            while let Ok(Some(message)) = read_message(&mut reader) {
+                if tx.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            writer: Mutex::new(writer),
+            incoming: Mutex::new(rx),
+            child,
+            next_seq: 1,
+            events: VecDeque::new(),
+            responses: HashMap::new(),
+            timeout,
+        }
+    }
+
+    /// Send a request without waiting for its response
+    fn send_request(&mut self, command: &str, arguments: Value) -> Result<i64, DebugError> {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.write(&json!({
+            "seq": seq,
+            "type": "request",
+            "command": command,
+            "arguments": arguments,
+        }))?;
+        Ok(seq)
+    }
+
+    /// Send a request and return the body of a successful response
+    fn request(&mut self, command: &str, arguments: Value) -> Result<Value, DebugError> {
+        let seq = self.send_request(command, arguments)?;
+        self.wait_response(seq)
+    }
+
+    fn wait_response(&mut self, seq: i64) -> Result<Value, DebugError> {
+        let response = match self.responses.remove(&seq) {
+            Some(response) => response,
+            None => self.wait_for(|message| is_response_to(message, seq))?,
+        };
+        response_body(&response)
+    }
+
+    /// Wait for the first message matching `predicate`, queueing everything else
+    fn wait_for<F>(&mut self, predicate: F) -> Result<Value, DebugError>
+    where
+        F: Fn(&Value) -> bool,
+    {
+        if let Some(index) = self.events.iter().position(&predicate) {
+            return Ok(self.events.remove(index).unwrap_or(Value::Null));
+        }
+
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let message = match self.incoming().recv_timeout(remaining) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(DebugError::CommunicationError("debug adapter timed out".to_string()));
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(DebugError::CommunicationError("debug adapter disconnected".to_string()));
+                }
+            };
+
+            if predicate(&message) {
+                return Ok(message);
+            }
+            self.stash(message)?;
+        }
+    }
+
+    /// Move everything that has arrived into the event/response queues
+    fn pump(&mut self) -> Result<(), DebugError> {
+        while let Ok(message) = self.incoming().try_recv() {
+            self.stash(message)?;
+        }
+        Ok(())
+    }
+
+    fn stash(&mut self, message: Value) -> Result<(), DebugError> {
+        match message.get("type").and_then(Value::as_str) {
+            Some("event") => self.events.push_back(message),
+            Some("response") => {
+                if let Some(seq) = message.get("request_seq").and_then(Value::as_i64) {
+                    self.responses.insert(seq, message);
+                }
+            }
+            Some("request") => {
+                // Reverse requests (runInTerminal, startDebugging) are not supported
+                let seq = self.next_seq;
+                self.next_seq += 1;
+                self.write(&json!({
+                    "seq": seq,
+                    "type": "response",
+                    "request_seq": message.get("seq").cloned().unwrap_or(Value::Null),
+                    "command": message.get("command").cloned().unwrap_or(Value::Null),
+                    "success": false,
+                    "message": "not supported by ide-rs",
+                }))?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, message: &Value) -> Result<(), DebugError> {
+        let body = serde_json::to_string(message)
+            .map_err(|e| DebugError::CommunicationError(e.to_string()))?;
+        let writer = self.writer.get_mut().unwrap_or_else(|e| e.into_inner());
+        write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn incoming(&mut self) -> &Receiver<Value> {
+        self.incoming.get_mut().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for DapConnection {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Read one `Content-Length` framed JSON message; `Ok(None)` at end of stream
+fn read_message<R: BufRead>(reader: &mut R) -> std::io::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let mut body = vec![0u8; content_length.unwrap_or(0)];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+fn is_response_to(message: &Value, seq: i64) -> bool {
+    message.get("type").and_then(Value::as_str) == Some("response")
+        && message.get("request_seq").and_then(Value::as_i64) == Some(seq)
+}
+
+fn is_event(message: &Value, name: &str) -> bool {
+    message.get("type").and_then(Value::as_str) == Some("event")
+        && message.get("event").and_then(Value::as_str) == Some(name)
+}
+
+fn response_body(response: &Value) -> Result<Value, DebugError> {
+    if response.get("success").and_then(Value::as_bool).unwrap_or(false) {
+        Ok(response.get("body").cloned().unwrap_or(Value::Null))
+    } else {
+        let message = response.get("message").and_then(Value::as_str)
+            .or_else(|| response.pointer("/body/error/format").and_then(Value::as_str))
+            .unwrap_or("request failed");
+        Err(DebugError::CommunicationError(message.to_string()))
+    }
+}
+
+/// [`DebugAdapter`] implementation that speaks DAP to an external adapter
+pub struct DapDebugAdapter {
+    /// How to reach the adapter
+    transport: DapTransport,
+    /// Live connection, present while a session runs
+    connection: Option<DapConnection>,
+    /// Current session ID
+    session_id: Option<String>,
+    /// Capabilities returned by `initialize`
+    capabilities: Value,
+    /// Breakpoints grouped by source file, as DAP sets them per file
+    breakpoints: HashMap<PathBuf, Vec<Breakpoint>>,
+    /// Adapter breakpoint IDs mapped to IDE breakpoint IDs
+    adapter_breakpoint_ids: HashMap<i64, u64>,
+    /// Whether the adapter accepted the configuration phase
+    configured: bool,
+    /// Thread that last reported a stop
+    stopped_thread: Option<u64>,
+    /// Events converted and waiting for `poll_events`
+    events: Vec<DebugEvent>,
+    /// Request timeout
+    timeout: Duration,
+}
+
+impl DapDebugAdapter {
+    /// Create an adapter client for the given transport
+    pub fn new(transport: DapTransport) -> Self {
+        Self {
+            transport,
+            connection: None,
+            session_id: None,
+            capabilities: Value::Null,
+            breakpoints: HashMap::new(),
+            adapter_breakpoint_ids: HashMap::new(),
+            configured: false,
+            stopped_thread: None,
+            events: Vec::new(),
+            timeout: DEFAULT_DAP_TIMEOUT,
+        }
+    }
+
+    /// Set the time the adapter has to answer a request
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+        if let Some(connection) = &mut self.connection {
+            connection.timeout = timeout;
+        }
+    }
+
+    /// Capabilities reported by the adapter
+    pub fn capabilities(&self) -> &Value {
+        &self.capabilities
+    }
+
+    fn connection(&mut self, session_id: &str) -> Result<&mut DapConnection, DebugError> {
+        if self.session_id.as_deref() != Some(session_id) {
+            return Err(DebugError::SessionNotFound(session_id.to_string()));
+        }
+        self.connection.as_mut()
+            .ok_or_else(|| DebugError::SessionNotFound(session_id.to_string()))
+    }
+
+    /// Thread a `continue` or `pause` applies to: the stopped one, else the first the adapter reports
+    ///
+    /// Adapters number threads their own way, so there is no id to assume.
+    fn target_thread(&mut self, session_id: &str) -> Result<u64, DebugError> {
+        if let Some(thread_id) = self.stopped_thread {
+            return Ok(thread_id);
+        }
+        self.get_threads(session_id)?
+            .first()
+            .map(|thread| thread.id)
+            .ok_or_else(|| DebugError::CommunicationError("Debug adapter reported no threads".to_string()))
+    }
+
+    fn thread_request(&mut self, session_id: &str, command: &str, thread_id: u64) -> Result<(), DebugError> {
+        self.connection(session_id)?.request(command, json!({ "threadId": thread_id }))?;
+        self.events.push(DebugEvent::Continued { thread_id: Some(thread_id) });
+        Ok(())
+    }
+
+    /// Send the full breakpoint list for one file and record verification results
+    fn sync_file_breakpoints(&mut self, file: &Path) -> Result<Vec<(u64, VerificationStatus)>, DebugError> {
+        let enabled: Vec<Breakpoint> = self.breakpoints.get(file)
+            .map(|list| list.iter().filter(|bp| bp.enabled).cloned().collect())
+            .unwrap_or_default();
+
+        let source_breakpoints: Vec<Value> = enabled.iter().map(|bp| {
+            let mut entry = json!({ "line": bp.location.line });
+            if let Some(column) = bp.location.column {
+                entry["column"] = json!(column);
+            }
+            if let Some(condition) = &bp.condition {
+                entry["condition"] = json!(condition);
+            }
+            if let Some(hit) = &bp.hit_condition {
+                entry["hitCondition"] = json!(match hit.condition_type {
+                    HitConditionType::Equals => hit.count.to_string(),
+                    HitConditionType::GreaterThan => format!("> {}", hit.count),
+                    HitConditionType::Multiple => format!("% {}", hit.count),
+                });
+            }
+            if let Some(message) = &bp.log_message {
+                entry["logMessage"] = json!(message);
+            }
+            entry
+        }).collect();
+
+        let connection = self.connection.as_mut()
+            .ok_or_else(|| DebugError::CommunicationError("no active session".to_string()))?;
+        let body = connection.request("setBreakpoints", json!({
+            "source": {
+                "path": file,
+                "name": file.file_name().map(|n| n.to_string_lossy().to_string()),
+            },
+            "breakpoints": source_breakpoints,
+        }))?;
+
+        let results = body.get("breakpoints").and_then(Value::as_array).cloned().unwrap_or_default();
+        let mut statuses = Vec::new();
+        for (index, breakpoint) in enabled.iter().enumerate() {
+            let status = match results.get(index) {
+                Some(result) => {
+                    if let Some(adapter_id) = result.get("id").and_then(Value::as_i64) {
+                        self.adapter_breakpoint_ids.insert(adapter_id, breakpoint.id);
+                    }
+                    verification_status(result)
+                }
+                None => VerificationStatus::Failed { reason: "not acknowledged by adapter".to_string() },
+            };
+            self.events.push(DebugEvent::BreakpointChanged { breakpoint_id: breakpoint.id, status: status.clone() });
+            statuses.push((breakpoint.id, status));
+        }
+        Ok(statuses)
+    }
+
+    /// Convert a raw DAP event into a [`DebugEvent`]
+    fn convert_event(&mut self, message: &Value) -> Option<DebugEvent> {
+        let body = message.get("body").cloned().unwrap_or(Value::Null);
+        let thread_id = body.get("threadId").and_then(Value::as_u64);
+
+        match message.get("event").and_then(Value::as_str)? {
+            "stopped" => {
+                let description = body.get("text").or_else(|| body.get("description"))
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string();
+                let hit = body.get("hitBreakpointIds").and_then(Value::as_array)
+                    .and_then(|ids| ids.first())
+                    .and_then(Value::as_i64)
+                    .and_then(|id| self.adapter_breakpoint_ids.get(&id).copied());
+
+                let reason = match body.get("reason").and_then(Value::as_str).unwrap_or("pause") {
+                    "breakpoint" | "function breakpoint" | "data breakpoint" | "instruction breakpoint" => {
+                        PauseReason::Breakpoint { breakpoint_id: hit.unwrap_or(0) }
+                    }
+                    "step" | "goto" => PauseReason::Step,
+                    "exception" => PauseReason::Exception { message: description },
+                    "entry" => PauseReason::Entry,
+                    _ => PauseReason::Pause,
+                };
+                self.stopped_thread = thread_id.or(self.stopped_thread);
+
+                Some(DebugEvent::Stopped {
+                    thread_id,
+                    reason,
+                    all_threads_stopped: body.get("allThreadsStopped").and_then(Value::as_bool).unwrap_or(false),
+                })
+            }
+            "continued" => Some(DebugEvent::Continued { thread_id }),
+            "exited" => Some(DebugEvent::Exited {
+                exit_code: body.get("exitCode").and_then(Value::as_i64).unwrap_or(0) as i32,
+            }),
+            "terminated" => Some(DebugEvent::Terminated),
+            "output" => {
+                let category = body.get("category").and_then(Value::as_str).unwrap_or("console");
+                if category == "telemetry" {
+                    return None;
+                }
+                Some(DebugEvent::Output {
+                    category: category.to_string(),
+                    text: body.get("output").and_then(Value::as_str).unwrap_or_default().to_string(),
+                })
+            }
+            "breakpoint" => {
+                let breakpoint = body.get("breakpoint")?;
+                let adapter_id = breakpoint.get("id").and_then(Value::as_i64)?;
+                let breakpoint_id = *self.adapter_breakpoint_ids.get(&adapter_id)?;
+                Some(DebugEvent::BreakpointChanged {
+                    breakpoint_id,
+                    status: verification_status(breakpoint),
+                })
+            }
+            "thread" => Some(DebugEvent::Thread {
+                thread_id: thread_id?,
+                started: body.get("reason").and_then(Value::as_str) == Some("started"),
+            }),
+            _ => None,
+        }
+    }
+
+    fn fetch_variables(&mut self, session_id: &str, reference: u64) -> Result<Vec<Variable>, DebugError> {
+        let modifiable = self.capabilities.get("supportsSetVariable").and_then(Value::as_bool).unwrap_or(false);
+        let body = self.connection(session_id)?.request("variables", json!({ "variablesReference": reference }))?;
+
+        Ok(body.get("variables").and_then(Value::as_array).map(|variables| {
+            variables.iter().map(|variable| {
+                let reference = variable.get("variablesReference").and_then(Value::as_u64).unwrap_or(0);
+                let value = variable.get("value").and_then(Value::as_str).unwrap_or_default().to_string();
+                Variable {
+                    name: variable.get("name").and_then(Value::as_str).unwrap_or_default().to_string(),
+                    value: variable_value(value, reference, variable),
+                    var_type: variable.get("type").and_then(Value::as_str).unwrap_or_default().to_string(),
+                    modifiable,
+                    memory_reference: variable.get("memoryReference").and_then(Value::as_str).map(str::to_string),
+                    reference: (reference > 0).then_some(reference),
+                }
+            }).collect()
+        }).unwrap_or_default())
+    }
+}
+
+impl DebugAdapter for DapDebugAdapter {
+    fn start_session(&mut self, config: &DebugConfiguration) -> Result<String, DebugError> {
+        if self.connection.is_some() {
+            return Err(DebugError::CommunicationError("a debug session is already running".to_string()));
+        }
+
+        let mut connection = DapConnection::open(&self.transport, self.timeout)?;
+
+        self.capabilities = connection.request("initialize", json!({
+            "clientID": "ide-rs",
+            "clientName": "Rust RAD IDE",
+            "adapterID": config.adapter_type,
+            "locale": "en-US",
+            "linesStartAt1": true,
+            "columnsStartAt1": true,
+            "pathFormat": "path",
+            "supportsVariableType": true,
+            "supportsRunInTerminalRequest": false,
+        }))?;
+
+        // `request: "attach"` in the additional config switches from launch to attach
+        let request = config.additional_config.get("request")
+            .and_then(Value::as_str)
+            .unwrap_or("launch")
+            .to_string();
+        let mut arguments = json!({
+            "name": config.name,
+            "program": config.program,
+            "args": config.args,
+            "cwd": config.cwd,
+            "env": config.env,
+            "stopOnEntry": config.stop_at_entry,
+        });
+        for (key, value) in &config.additional_config {
+            if key != "request" {
+                arguments[key] = value.clone();
+            }
+        }
+        let launch_seq = connection.send_request(&request, arguments)?;
+
+        // Adapters signal readiness for breakpoints with `initialized`; a failed
+        // launch may be reported first.
+        let mut launch_response = None;
+        loop {
+            let message = connection.wait_for(|m| is_event(m, "initialized") || is_response_to(m, launch_seq))?;
+            if is_event(&message, "initialized") {
+                break;
+            }
+            response_body(&message)?;
+            launch_response = Some(message);
+        }
+
+        self.connection = Some(connection);
+        self.configured = true;
+
+        let files: Vec<PathBuf> = self.breakpoints.keys().cloned().collect();
+        for file in files {
+            self.sync_file_breakpoints(&file)?;
+        }
+
+        let connection = self.connection.as_mut().unwrap();
+        if self.capabilities.get("supportsConfigurationDoneRequest").and_then(Value::as_bool).unwrap_or(false) {
+            connection.request("configurationDone", Value::Null)?;
+        }
+        if launch_response.is_none() {
+            connection.wait_response(launch_seq)?;
+        }
+
+        let session_id = uuid::Uuid::new_v4().to_string();
+        self.session_id = Some(session_id.clone());
+        Ok(session_id)
+    }
+
+    fn stop_session(&mut self, session_id: &str) -> Result<(), DebugError> {
+        let connection = self.connection(session_id)?;
+        let result = connection.request("disconnect", json!({ "terminateDebuggee": true }));
+
+        self.connection = None;
+        self.session_id = None;
+        self.configured = false;
+        self.stopped_thread = None;
+        self.adapter_breakpoint_ids.clear();
+
+        // An adapter that exits right after `disconnect` may drop the response
+        match result {
+            Err(DebugError::CommunicationError(_)) | Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn set_breakpoint(&mut self, breakpoint: &Breakpoint) -> Result<VerificationStatus, DebugError> {
+        let file = breakpoint.location.file.clone();
+        let list = self.breakpoints.entry(file.clone()).or_default();
+        list.retain(|bp| bp.id != breakpoint.id);
+        list.push(breakpoint.clone());
+
+        if !self.configured {
+            return Ok(VerificationStatus::Pending);
+        }
+
+        let statuses = self.sync_file_breakpoints(&file)?;
+        Ok(statuses.into_iter()
+            .find(|(id, _)| *id == breakpoint.id)
+            .map(|(_, status)| status)
+            .unwrap_or(VerificationStatus::Pending))
+    }
+
+    fn remove_breakpoint(&mut self, breakpoint_id: u64) -> Result<(), DebugError> {
+        let file = self.breakpoints.iter()
+            .find(|(_, list)| list.iter().any(|bp| bp.id == breakpoint_id))
+            .map(|(file, _)| file.clone());
+
+        if let Some(file) = file {
+            if let Some(list) = self.breakpoints.get_mut(&file) {
+                list.retain(|bp| bp.id != breakpoint_id);
+            }
+            self.adapter_breakpoint_ids.retain(|_, id| *id != breakpoint_id);
+            if self.configured {
+                self.sync_file_breakpoints(&file)?;
+            }
+            if self.breakpoints.get(&file).is_some_and(|list| list.is_empty()) {
+                self.breakpoints.remove(&file);
+            }
+        }
+        Ok(())
+    }
+
+    fn continue_execution(&mut self, session_id: &str) -> Result<(), DebugError> {
+        let thread_id = self.target_thread(session_id)?;
+        self.thread_request(session_id, "continue", thread_id)
+    }
+
+    fn step_over(&mut self, session_id: &str, thread_id: u64) -> Result<(), DebugError> {
+        self.thread_request(session_id, "next", thread_id)
+    }
+
+    fn step_into(&mut self, session_id: &str, thread_id: u64) -> Result<(), DebugError> {
+        self.thread_request(session_id, "stepIn", thread_id)
+    }
+
+    fn step_out(&mut self, session_id: &str, thread_id: u64) -> Result<(), DebugError> {
+        self.thread_request(session_id, "stepOut", thread_id)
+    }
+
+    fn pause(&mut self, session_id: &str) -> Result<(), DebugError> {
+        let thread_id = self.target_thread(session_id)?;
+        self.connection(session_id)?.request("pause", json!({ "threadId": thread_id }))?;
+        Ok(())
+    }
+
+    fn get_call_stack(&mut self, session_id: &str, thread_id: u64) -> Result<Vec<StackFrame>, DebugError> {
+        let body = self.connection(session_id)?.request("stackTrace", json!({
+            "threadId": thread_id,
+            "startFrame": 0,
+            "levels": 0,
+        }))?;
+
+        Ok(body.get("stackFrames").and_then(Value::as_array).map(|frames| {
+            frames.iter().map(|frame| StackFrame {
+                id: frame.get("id").and_then(Value::as_u64).unwrap_or(0),
+                name: frame.get("name").and_then(Value::as_str).unwrap_or("<unknown>").to_string(),
+                location: source_location(frame),
+                instruction_pointer: frame.get("instructionPointerReference")
+                    .and_then(Value::as_str)
+                    .and_then(|ip| u64::from_str_radix(ip.trim_start_matches("0x"), 16).ok()),
+                module: frame.get("moduleId").map(|id| match id {
+                    Value::String(name) => name.clone(),
+                    other => other.to_string(),
+                }),
+                presentation_hint: frame.get("presentationHint").and_then(Value::as_str).map(|hint| match hint {
+                    "subtle" => FramePresentationHint::Subtle,
+                    "label" => FramePresentationHint::Label,
+                    _ => FramePresentationHint::Normal,
+                }),
+            }).collect()
+        }).unwrap_or_default())
+    }
+
+    fn get_variables(&mut self, session_id: &str, frame_id: u64) -> Result<Vec<VariableScope>, DebugError> {
+        let body = self.connection(session_id)?.request("scopes", json!({ "frameId": frame_id }))?;
+        let scopes = body.get("scopes").and_then(Value::as_array).cloned().unwrap_or_default();
+
+        let mut result = Vec::new();
+        for scope in scopes {
+            let expensive = scope.get("expensive").and_then(Value::as_bool).unwrap_or(false);
+            let reference = scope.get("variablesReference").and_then(Value::as_u64).unwrap_or(0);
+            // Expensive scopes (e.g. globals/registers) are fetched on demand
+            let variables = if expensive || reference == 0 {
+                Vec::new()
+            } else {
+                self.fetch_variables(session_id, reference)?
+            };
+
+            result.push(VariableScope {
+                name: scope.get("name").and_then(Value::as_str).unwrap_or_default().to_string(),
+                variables,
+                expensive,
+            });
+        }
+        Ok(result)
+    }
+
+    fn evaluate_expression(&mut self, session_id: &str, expression: &str, frame_id: Option<u64>) -> Result<VariableValue, DebugError> {
+        let mut arguments = json!({ "expression": expression, "context": "repl" });
+        if let Some(frame_id) = frame_id {
+            arguments["frameId"] = json!(frame_id);
+        }
+
+        let body = self.connection(session_id)?.request("evaluate", arguments)
+            .map_err(|e| DebugError::ExpressionEvaluationFailed(e.to_string()))?;
+        let result = body.get("result").and_then(Value::as_str).unwrap_or_default().to_string();
+        let reference = body.get("variablesReference").and_then(Value::as_u64).unwrap_or(0);
+        Ok(variable_value(result, reference, &body))
+    }
+
+    fn get_child_variables(&mut self, session_id: &str, reference: u64) -> Result<Vec<Variable>, DebugError> {
+        self.fetch_variables(session_id, reference)
+    }
+
+    fn get_threads(&mut self, session_id: &str) -> Result<Vec<ThreadInfo>, DebugError> {
+        let stopped_thread = self.stopped_thread;
+        let body = self.connection(session_id)?.request("threads", Value::Null)?;
+
+        Ok(body.get("threads").and_then(Value::as_array).map(|threads| {
+            threads.iter().filter_map(|thread| {
+                let id = thread.get("id").and_then(Value::as_u64)?;
+                Some(ThreadInfo {
+                    id,
+                    name: thread.get("name").and_then(Value::as_str).unwrap_or_default().to_string(),
+                    location: None,
+                    state: if stopped_thread == Some(id) { ThreadState::Paused } else { ThreadState::Stopped },
+                })
+            }).collect()
+        }).unwrap_or_default())
+    }
+
+    fn poll_events(&mut self) -> Vec<DebugEvent> {
+        let raw_events: Vec<Value> = match &mut self.connection {
+            Some(connection) => {
+                let _ = connection.pump();
+                connection.events.drain(..).collect()
+            }
+            None => Vec::new(),
+        };
+
+        for message in raw_events {
+            if let Some(event) = self.convert_event(&message) {
+                self.events.push(event);
+            }
+        }
+        std::mem::take(&mut self.events)
+    }
+}
+
+/// Map a DAP `Breakpoint` object to a verification status
+fn verification_status(breakpoint: &Value) -> VerificationStatus {
+    if breakpoint.get("verified").and_then(Value::as_bool).unwrap_or(false) {
+        VerificationStatus::Verified
+    } else {
+        match breakpoint.get("message").and_then(Value::as_str) {
+            Some(reason) => VerificationStatus::Failed { reason: reason.to_string() },
+            None => VerificationStatus::Pending,
+        }
+    }
+}
+
+/// Map a DAP value with an optional `variablesReference` to a [`VariableValue`]
+fn variable_value(value: String, reference: u64, source: &Value) -> VariableValue {
+    if reference == 0 {
+        return VariableValue::Simple(value);
+    }
+    let named = source.get("namedVariables").and_then(Value::as_u64);
+    let indexed = source.get("indexedVariables").and_then(Value::as_u64);
+    let child_count = match (named, indexed) {
+        (None, None) => None,
+        (named, indexed) => Some((named.unwrap_or(0) + indexed.unwrap_or(0)) as u32),
+    };
+    VariableValue::Complex { summary: value, children: None, child_count }
+}
+
+/// Source location of a DAP stack frame
+fn source_location(frame: &Value) -> Option<SourceLocation> {
+    let path = frame.pointer("/source/path").and_then(Value::as_str)?;
+    Some(SourceLocation {
+        file: PathBuf::from(path),
+        line: frame.get("line").and_then(Value::as_u64).unwrap_or(0) as u32,
+        column: frame.get("column").and_then(Value::as_u64).map(|c| c as u32),
+    })
+}
@@ -7,6 +7,8 @@ use egui::*;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
+use crate::editor::dap_client::{DapDebugAdapter, DapTransport};
+
 /// Main debugger interface
 pub struct Debugger {
     /// Current debugging session
@@ -213,6 +215,8 @@ pub struct Variable {
     pub modifiable: bool,
     /// Memory reference (for pointers)
     pub memory_reference: Option<String>,
+    /// Adapter handle used to fetch children of structured values
+    pub reference: Option<u64>,
 }
 
 /// Variable value representation
@@ -358,6 +362,58 @@ pub trait DebugAdapter: Send + Sync {
     
     /// Evaluate expression
     fn evaluate_expression(&mut self, session_id: &str, expression: &str, frame_id: Option<u64>) -> Result<VariableValue, DebugError>;
+    
+    /// Get the children of a structured variable
+    fn get_child_variables(&mut self, _session_id: &str, _reference: u64) -> Result<Vec<Variable>, DebugError> {
+        Ok(Vec::new())
+    }
+    
+    /// Get the threads of the debugged program
+    fn get_threads(&mut self, _session_id: &str) -> Result<Vec<ThreadInfo>, DebugError> {
+        Ok(Vec::new())
+    }
+    
+    /// Take events reported by the adapter since the last call
+    fn poll_events(&mut self) -> Vec<DebugEvent> {
+        Vec::new()
+    }
+}
+
+/// Asynchronous notifications from a debug adapter
+#[derive(Debug, Clone, PartialEq)]
+pub enum DebugEvent {
+    /// Execution stopped
+    Stopped {
+        thread_id: Option<u64>,
+        reason: PauseReason,
+        all_threads_stopped: bool,
+    },
+    /// Execution resumed
+    Continued { thread_id: Option<u64> },
+    /// The debuggee exited
+    Exited { exit_code: i32 },
+    /// The debug session ended
+    Terminated,
+    /// Output from the debuggee or the adapter
+    Output { category: String, text: String },
+    /// A breakpoint was verified, moved or rejected
+    BreakpointChanged { breakpoint_id: u64, status: VerificationStatus },
+    /// A thread started or exited
+    Thread { thread_id: u64, started: bool },
+}
+
+/// Live session state the debug console can act on
+pub struct ConsoleSession<'a> {
+    /// Adapter driving the session
+    pub adapter: &'a mut dyn DebugAdapter,
+    /// Session ID
+    pub session_id: &'a str,
+    /// Thread to step
+    pub thread_id: Option<u64>,
+    /// Frame to evaluate expressions in
+    pub frame_id: Option<u64>,
+    /// File of the current frame, for `break <line>`
+    pub current_file: Option<PathBuf>,
 }
 
 /// Debug configuration
@@ -582,8 +638,13 @@ impl DebugConsole {
         }
     }
 
-    /// Execute a debug command
-    pub fn execute_command(&mut self, command: String) -> String {
+    /// Execute a debug command, driving `session` when one is active
+    pub fn execute_command(
+        &mut self,
+        command: String,
+        breakpoints: &mut BreakpointManager,
+        session: Option<ConsoleSession<'_>>,
+    ) -> String {
         // Add to history
         if !command.is_empty() {
             self.history.push(command.clone());
@@ -594,44 +655,148 @@ impl DebugConsole {
         self.add_message(format!("> {}", command), ConsoleMessageType::Input);
 
         // Parse and execute command
-        let result = self.parse_and_execute(&command);
+        let (result, message_type) = match self.parse_and_execute(&command, breakpoints, session) {
+            Ok(result) => (result, ConsoleMessageType::Result),
+            Err(error) => (error, ConsoleMessageType::Error),
+        };
         
         // Add result to output
-        self.add_message(result.clone(), ConsoleMessageType::Result);
+        self.add_message(result.clone(), message_type);
 
         result
     }
 
     /// Parse and execute debug command
-    fn parse_and_execute(&self, command: &str) -> String {
+    fn parse_and_execute(
+        &mut self,
+        command: &str,
+        breakpoints: &mut BreakpointManager,
+        session: Option<ConsoleSession<'_>>,
+    ) -> Result<String, String> {
         let parts: Vec<&str> = command.split_whitespace().collect();
         if parts.is_empty() {
-            return String::new();
+            return Ok(String::new());
         }
 
+        fn require_session(session: Option<ConsoleSession<'_>>) -> Result<ConsoleSession<'_>, String> {
+            session.ok_or_else(|| "No active debug session.".to_string())
+        }
+        let error = |e: DebugError| e.to_string();
+
         match parts[0] {
-            "help" | "h" => self.show_help(),
-            "continue" | "c" => "Continuing execution...".to_string(),
-            "step" | "s" => "Stepping over...".to_string(),
-            "stepi" | "si" => "Stepping into...".to_string(),
-            "stepo" | "so" => "Stepping out...".to_string(),
+            "help" | "h" => Ok(self.show_help()),
+            "continue" | "c" => {
+                let session = require_session(session)?;
+                session.adapter.continue_execution(session.session_id).map_err(error)?;
+                Ok("Continuing execution...".to_string())
+            }
+            "step" | "s" | "stepi" | "si" | "stepo" | "so" => {
+                let session = require_session(session)?;
+                let thread_id = session.thread_id.ok_or_else(|| "Program is not paused.".to_string())?;
+                let (result, message) = match parts[0] {
+                    "step" | "s" => (session.adapter.step_over(session.session_id, thread_id), "Stepping over..."),
+                    "stepi" | "si" => (session.adapter.step_into(session.session_id, thread_id), "Stepping into..."),
+                    _ => (session.adapter.step_out(session.session_id, thread_id), "Stepping out..."),
+                };
+                result.map_err(error)?;
+                Ok(message.to_string())
+            }
+            "pause" => {
+                let session = require_session(session)?;
+                session.adapter.pause(session.session_id).map_err(error)?;
+                Ok("Pausing...".to_string())
+            }
             "print" | "p" => {
-                if parts.len() > 1 {
-                    format!("Evaluating: {}", parts[1..].join(" "))
-                } else {
-                    "Usage: print <expression>".to_string()
+                if parts.len() < 2 {
+                    return Err("Usage: print <expression>".to_string());
+                }
+                let session = require_session(session)?;
+                let expression = parts[1..].join(" ");
+                let value = session.adapter
+                    .evaluate_expression(session.session_id, &expression, session.frame_id)
+                    .map_err(error)?;
+                match value {
+                    VariableValue::Simple(value) => Ok(value),
+                    VariableValue::Complex { summary, .. } => Ok(summary),
+                    VariableValue::Error(message) => Err(message),
                 }
             }
             "break" | "b" => {
-                if parts.len() > 1 {
-                    format!("Setting breakpoint at: {}", parts[1])
-                } else {
-                    "Usage: break <location>".to_string()
+                if parts.len() < 2 {
+                    return Err("Usage: break <file:line | line>".to_string());
+                }
+                let current_file = session.as_ref().and_then(|s| s.current_file.clone());
+                let location = Self::parse_location(parts[1], current_file)
+                    .ok_or_else(|| format!("Invalid breakpoint location: {}", parts[1]))?;
+                let description = format!("{}:{}", location.file.display(), location.line);
+
+                let id = breakpoints.add_breakpoint(location);
+                let status = match session {
+                    Some(session) => {
+                        let breakpoint = breakpoints.breakpoints[&id].clone();
+                        session.adapter.set_breakpoint(&breakpoint).map_err(error)?
+                    }
+                    None => VerificationStatus::Pending,
+                };
+                breakpoints.verification_status.insert(id, status.clone());
+                Ok(format!("Breakpoint {} at {} ({})", id, description, Self::describe_status(&status)))
+            }
+            "delete" | "d" => {
+                let id: u64 = parts.get(1)
+                    .and_then(|id| id.parse().ok())
+                    .ok_or_else(|| "Usage: delete <breakpoint id>".to_string())?;
+                if !breakpoints.remove_breakpoint(id) {
+                    return Err(format!("No breakpoint {}", id));
+                }
+                if let Some(session) = session {
+                    session.adapter.remove_breakpoint(id).map_err(error)?;
                 }
+                Ok(format!("Deleted breakpoint {}", id))
             }
-            "list" | "l" => "Listing breakpoints...".to_string(),
-            "clear" => "Console cleared.".to_string(),
-            _ => format!("Unknown command: {}. Type 'help' for available commands.", parts[0]),
+            "list" | "l" => {
+                if breakpoints.breakpoints.is_empty() {
+                    return Ok("No breakpoints.".to_string());
+                }
+                let mut list: Vec<&Breakpoint> = breakpoints.breakpoints.values().collect();
+                list.sort_by_key(|bp| bp.id);
+                Ok(list.iter().map(|bp| {
+                    let status = breakpoints.verification_status.get(&bp.id)
+                        .map(Self::describe_status)
+                        .unwrap_or_else(|| "pending".to_string());
+                    format!(
+                        "{} {}:{}{} ({}, hit {} times)",
+                        bp.id,
+                        bp.location.file.display(),
+                        bp.location.line,
+                        if bp.enabled { "" } else { " [disabled]" },
+                        status,
+                        bp.hit_count,
+                    )
+                }).collect::<Vec<_>>().join("\n"))
+            }
+            "clear" => {
+                self.output.clear();
+                Ok("Console cleared.".to_string())
+            }
+            _ => Err(format!("Unknown command: {}. Type 'help' for available commands.", parts[0])),
+        }
+    }
+
+    /// Parse `file:line` or a bare line number in the current file
+    fn parse_location(text: &str, current_file: Option<PathBuf>) -> Option<SourceLocation> {
+        let (file, line) = match text.rsplit_once(':') {
+            Some((file, line)) => (PathBuf::from(file), line),
+            None => (current_file?, text),
+        };
+        let line = line.parse::<u32>().ok().filter(|line| *line > 0)?;
+        Some(SourceLocation { file, line, column: None })
+    }
+
+    fn describe_status(status: &VerificationStatus) -> String {
+        match status {
+            VerificationStatus::Verified => "verified".to_string(),
+            VerificationStatus::Pending => "pending".to_string(),
+            VerificationStatus::Failed { reason } => format!("failed: {}", reason),
         }
     }
 
@@ -643,16 +808,25 @@ impl DebugConsole {
   step, s              - Step over next line
   stepi, si           - Step into function
   stepo, so           - Step out of function
+  pause               - Pause execution
   print, p <expr>     - Print expression value
-  break, b <location> - Set breakpoint
+  break, b <location> - Set breakpoint (file:line or line)
+  delete, d <id>      - Delete breakpoint
   list, l             - List breakpoints
   clear               - Clear console"#.to_string()
     }
 }
 
 impl Debugger {
-    /// Create a new debugger instance
+    /// Create a new debugger instance with the built-in DAP adapters registered
     pub fn new() -> Self {
+        let mut adapters: HashMap<String, Box<dyn DebugAdapter>> = HashMap::new();
+        for adapter_type in ["lldb-dap", "codelldb", "gdb"] {
+            if let Some(transport) = DapTransport::for_adapter_type(adapter_type) {
+                adapters.insert(adapter_type.to_string(), Box::new(DapDebugAdapter::new(transport)));
+            }
+        }
+
         Self {
             session: None,
             breakpoints: BreakpointManager::new(),
@@ -660,7 +834,7 @@ impl Debugger {
             call_stack: CallStackViewer::new(),
             console: DebugConsole::new(),
             settings: DebuggerSettings::default(),
-            adapters: HashMap::new(),
+            adapters,
         }
     }
 
@@ -671,9 +845,19 @@ impl Debugger {
 
     /// Start a debugging session
     pub fn start_debug_session(&mut self, config: DebugConfiguration) -> Result<(), DebugError> {
+        if self.session.is_some() {
+            self.stop_debug_session()?;
+        }
+
         let adapter = self.adapters.get_mut(&config.adapter_type)
             .ok_or_else(|| DebugError::AdapterNotFound(config.adapter_type.clone()))?;
 
+        // Breakpoints set before the session are sent during the configuration phase
+        for breakpoint in self.breakpoints.breakpoints.values() {
+            let status = adapter.set_breakpoint(breakpoint)?;
+            self.breakpoints.verification_status.insert(breakpoint.id, status);
+        }
+
         let session_id = adapter.start_session(&config)?;
 
         let session = DebugSession {
@@ -682,7 +866,7 @@ impl Debugger {
             args: config.args.clone(),
             env: config.env.clone(),
             working_dir: config.cwd.clone(),
-            state: ExecutionState::Stopped,
+            state: ExecutionState::Running,
             threads: Vec::new(),
             active_thread: None,
             current_frame: None,
@@ -691,6 +875,7 @@ impl Debugger {
 
         self.session = Some(session);
         self.console.add_message("Debug session started.".to_string(), ConsoleMessageType::Output);
+        self.update();
 
         Ok(())
     }
@@ -701,34 +886,351 @@ impl Debugger {
             if let Some(adapter) = self.adapters.get_mut(&session.language) {
                 adapter.stop_session(&session.id)?;
             }
-            self.session = None;
-            self.console.add_message("Debug session stopped.".to_string(), ConsoleMessageType::Output);
+            self.end_session("Debug session stopped.");
         }
         Ok(())
     }
 
-    /// Render debugger UI
-    pub fn render_debugger_ui(&mut self, ui: &mut Ui) {
-        ui.horizontal(|ui| {
-            // Debug controls
-            if self.session.is_some() {
-                if ui.button("⏸ Pause").clicked() {
-                    // TODO: Implement pause
+    /// Continue execution of the paused program
+    pub fn continue_execution(&mut self) -> Result<(), DebugError> {
+        let (session, adapter) = Self::active(&mut self.session, &mut self.adapters)?;
+        adapter.continue_execution(&session.id)?;
+        self.update();
+        Ok(())
+    }
+
+    /// Step over the next statement on the active thread
+    pub fn step_over(&mut self) -> Result<(), DebugError> {
+        let (session, adapter) = Self::active(&mut self.session, &mut self.adapters)?;
+        adapter.step_over(&session.id, Self::paused_thread(session)?)?;
+        self.update();
+        Ok(())
+    }
+
+    /// Step into the call on the active thread
+    pub fn step_into(&mut self) -> Result<(), DebugError> {
+        let (session, adapter) = Self::active(&mut self.session, &mut self.adapters)?;
+        adapter.step_into(&session.id, Self::paused_thread(session)?)?;
+        self.update();
+        Ok(())
+    }
+
+    /// Step out of the current function on the active thread
+    pub fn step_out(&mut self) -> Result<(), DebugError> {
+        let (session, adapter) = Self::active(&mut self.session, &mut self.adapters)?;
+        adapter.step_out(&session.id, Self::paused_thread(session)?)?;
+        self.update();
+        Ok(())
+    }
+
+    /// Pause the running program
+    pub fn pause(&mut self) -> Result<(), DebugError> {
+        let (session, adapter) = Self::active(&mut self.session, &mut self.adapters)?;
+        adapter.pause(&session.id)?;
+        self.update();
+        Ok(())
+    }
+
+    /// Add a breakpoint, sending it to the adapter when a session is active
+    pub fn add_breakpoint(&mut self, location: SourceLocation) -> u64 {
+        let id = self.breakpoints.add_breakpoint(location);
+        self.sync_breakpoint(id);
+        id
+    }
+
+    /// Remove a breakpoint from the manager and the active session
+    pub fn remove_breakpoint(&mut self, id: u64) -> bool {
+        if !self.breakpoints.remove_breakpoint(id) {
+            return false;
+        }
+        if let Ok((_, adapter)) = Self::active(&mut self.session, &mut self.adapters) {
+            if let Err(e) = adapter.remove_breakpoint(id) {
+                self.console.add_message(e.to_string(), ConsoleMessageType::Error);
+            }
+        }
+        true
+    }
+
+    /// Toggle a breakpoint, updating the active session
+    pub fn toggle_breakpoint(&mut self, id: u64) -> bool {
+        let enabled = self.breakpoints.toggle_breakpoint(id);
+        self.sync_breakpoint(id);
+        enabled
+    }
+
+    /// Select a call stack frame and load its variables
+    pub fn select_frame(&mut self, index: usize) -> Result<(), DebugError> {
+        let frame = self.call_stack.select_frame(index).cloned();
+        if let Some(session) = &mut self.session {
+            session.current_frame = frame;
+        }
+        self.refresh_frame_state()
+    }
+
+    /// Evaluate an expression in the selected frame
+    pub fn evaluate(&mut self, expression: &str) -> Result<VariableValue, DebugError> {
+        let frame_id = self.selected_frame_id();
+        let (session, adapter) = Self::active(&mut self.session, &mut self.adapters)?;
+        adapter.evaluate_expression(&session.id, expression, frame_id)
+    }
+
+    /// Fetch the children of a structured variable and attach them to it
+    pub fn load_variable_children(&mut self, reference: u64) -> Result<(), DebugError> {
+        let (session, adapter) = Self::active(&mut self.session, &mut self.adapters)?;
+        let children = adapter.get_child_variables(&session.id, reference)?;
+
+        for scope in &mut self.variable_inspector.scopes {
+            if Self::attach_children(&mut scope.variables, reference, &children) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Execute a debug console command against the active session
+    pub fn execute_console_command(&mut self, command: String) -> String {
+        let frame_id = self.selected_frame_id();
+        let session = match Self::active(&mut self.session, &mut self.adapters) {
+            Ok((session, adapter)) => Some(ConsoleSession {
+                adapter: adapter.as_mut(),
+                session_id: &session.id,
+                thread_id: session.active_thread,
+                frame_id,
+                current_file: session.current_frame.as_ref()
+                    .and_then(|frame| frame.location.as_ref())
+                    .map(|location| location.file.clone()),
+            }),
+            Err(_) => None,
+        };
+
+        let result = self.console.execute_command(command, &mut self.breakpoints, session);
+        self.update();
+        result
+    }
+
+    /// Process adapter events; call this regularly while a session is active
+    pub fn update(&mut self) {
+        let events = match Self::active(&mut self.session, &mut self.adapters) {
+            Ok((_, adapter)) => adapter.poll_events(),
+            Err(_) => return,
+        };
+
+        for event in events {
+            self.handle_event(event);
+        }
+    }
+
+    fn handle_event(&mut self, event: DebugEvent) {
+        match event {
+            DebugEvent::Stopped { thread_id, reason, .. } => {
+                if let PauseReason::Breakpoint { breakpoint_id } = &reason {
+                    if let Some(breakpoint) = self.breakpoints.breakpoints.get_mut(breakpoint_id) {
+                        breakpoint.hit_count += 1;
+                    }
                 }
-                if ui.button("▶ Continue").clicked() {
-                    // TODO: Implement continue
+                if let Some(session) = &mut self.session {
+                    session.active_thread = thread_id.or(session.active_thread);
+                    session.state = ExecutionState::Paused { reason, location: None };
                 }
-                if ui.button("⏭ Step Over").clicked() {
-                    // TODO: Implement step over
+                if let Err(e) = self.refresh_paused_state() {
+                    self.console.add_message(e.to_string(), ConsoleMessageType::Error);
                 }
-                if ui.button("⏬ Step Into").clicked() {
-                    // TODO: Implement step into
+            }
+            DebugEvent::Continued { .. } => {
+                if let Some(session) = &mut self.session {
+                    session.state = ExecutionState::Running;
+                    session.current_frame = None;
+                }
+                self.clear_paused_state();
+            }
+            DebugEvent::Exited { exit_code } => {
+                if let Some(session) = &mut self.session {
+                    session.state = ExecutionState::Exited { exit_code };
+                }
+                self.console.add_message(
+                    format!("Program exited with code {}.", exit_code),
+                    ConsoleMessageType::Output,
+                );
+            }
+            DebugEvent::Terminated => {
+                if let Ok((session, adapter)) = Self::active(&mut self.session, &mut self.adapters) {
+                    let _ = adapter.stop_session(&session.id);
+                }
+                self.end_session("Debug session ended.");
+            }
+            DebugEvent::Output { category, text } => {
+                let text = text.trim_end_matches('\n');
+                if !text.is_empty() {
+                    let message_type = if category == "stderr" {
+                        ConsoleMessageType::Error
+                    } else {
+                        ConsoleMessageType::Output
+                    };
+                    self.console.add_message(text.to_string(), message_type);
+                }
+            }
+            DebugEvent::BreakpointChanged { breakpoint_id, status } => {
+                self.breakpoints.verification_status.insert(breakpoint_id, status);
+            }
+            DebugEvent::Thread { thread_id, started } => {
+                if let Some(session) = &mut self.session {
+                    session.threads.retain(|thread| thread.id != thread_id);
+                    if started {
+                        session.threads.push(ThreadInfo {
+                            id: thread_id,
+                            name: format!("Thread {}", thread_id),
+                            location: None,
+                            state: ThreadState::Running,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reload threads, call stack, variables and watches after a stop
+    fn refresh_paused_state(&mut self) -> Result<(), DebugError> {
+        let (session, adapter) = Self::active(&mut self.session, &mut self.adapters)?;
+
+        session.threads = adapter.get_threads(&session.id)?;
+        if session.active_thread.is_none() {
+            session.active_thread = session.threads.first().map(|thread| thread.id);
+        }
+        let frames = match session.active_thread {
+            Some(thread_id) => adapter.get_call_stack(&session.id, thread_id)?,
+            None => Vec::new(),
+        };
+
+        let top = frames.first().cloned();
+        if let ExecutionState::Paused { location, .. } = &mut session.state {
+            *location = top.as_ref().and_then(|frame| frame.location.clone());
+        }
+        if let Some(thread) = session.threads.iter_mut().find(|thread| Some(thread.id) == session.active_thread) {
+            thread.location = top.as_ref().and_then(|frame| frame.location.clone());
+        }
+        session.current_frame = top;
+
+        self.call_stack.selected_frame = if frames.is_empty() { None } else { Some(0) };
+        self.call_stack.frames = frames;
+        self.refresh_frame_state()
+    }
+
+    /// Reload variables and watches for the selected frame
+    fn refresh_frame_state(&mut self) -> Result<(), DebugError> {
+        let frame_id = self.selected_frame_id();
+        let (session, adapter) = Self::active(&mut self.session, &mut self.adapters)?;
+
+        self.variable_inspector.scopes = match frame_id {
+            Some(frame_id) => adapter.get_variables(&session.id, frame_id)?,
+            None => Vec::new(),
+        };
+
+        self.variable_inspector.evaluation_cache.clear();
+        for watch in &mut self.variable_inspector.watches {
+            match adapter.evaluate_expression(&session.id, &watch.expression, frame_id) {
+                Ok(value) => {
+                    watch.value = Some(value.clone());
+                    watch.valid = true;
+                    watch.error = None;
+                    self.variable_inspector.evaluation_cache.insert(watch.expression.clone(), value);
+                }
+                Err(e) => {
+                    watch.value = None;
+                    watch.valid = false;
+                    watch.error = Some(e.to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn clear_paused_state(&mut self) {
+        self.call_stack.frames.clear();
+        self.call_stack.selected_frame = None;
+        self.variable_inspector.scopes.clear();
+        self.variable_inspector.evaluation_cache.clear();
+    }
+
+    fn end_session(&mut self, message: &str) {
+        self.session = None;
+        self.clear_paused_state();
+        self.console.add_message(message.to_string(), ConsoleMessageType::Output);
+    }
+
+    /// Send the current state of one breakpoint to the active session
+    fn sync_breakpoint(&mut self, id: u64) {
+        let Some(breakpoint) = self.breakpoints.breakpoints.get(&id) else { return };
+        let Ok((_, adapter)) = Self::active(&mut self.session, &mut self.adapters) else { return };
+
+        let status = adapter.set_breakpoint(breakpoint)
+            .unwrap_or_else(|e| VerificationStatus::Failed { reason: e.to_string() });
+        self.breakpoints.verification_status.insert(id, status);
+    }
+
+    fn selected_frame_id(&self) -> Option<u64> {
+        self.call_stack.selected_frame
+            .and_then(|index| self.call_stack.frames.get(index))
+            .map(|frame| frame.id)
+    }
+
+    fn attach_children(variables: &mut [Variable], reference: u64, children: &[Variable]) -> bool {
+        for variable in variables {
+            if let VariableValue::Complex { children: slot, .. } = &mut variable.value {
+                if variable.reference == Some(reference) {
+                    *slot = Some(children.to_vec());
+                    return true;
                 }
-                if ui.button("⏫ Step Out").clicked() {
-                    // TODO: Implement step out
+                if let Some(nested) = slot {
+                    if Self::attach_children(nested, reference, children) {
+                        return true;
+                    }
                 }
-                if ui.button("⏹ Stop").clicked() {
-                    let _ = self.stop_debug_session();
+            }
+        }
+        false
+    }
+
+    /// Split borrow of the active session and the adapter driving it
+    fn active<'a>(
+        session: &'a mut Option<DebugSession>,
+        adapters: &'a mut HashMap<String, Box<dyn DebugAdapter>>,
+    ) -> Result<(&'a mut DebugSession, &'a mut Box<dyn DebugAdapter>), DebugError> {
+        let session = session.as_mut()
+            .ok_or_else(|| DebugError::SessionNotFound("no active session".to_string()))?;
+        let adapter = adapters.get_mut(&session.language)
+            .ok_or_else(|| DebugError::AdapterNotFound(session.language.clone()))?;
+        Ok((session, adapter))
+    }
+
+    fn paused_thread(session: &DebugSession) -> Result<u64, DebugError> {
+        session.active_thread
+            .ok_or_else(|| DebugError::CommunicationError("program is not paused".to_string()))
+    }
+
+    /// Render debugger UI
+    pub fn render_debugger_ui(&mut self, ui: &mut Ui) {
+        self.update();
+
+        ui.horizontal(|ui| {
+            // Debug controls
+            if self.session.is_some() {
+                let result = if ui.button("⏸ Pause").clicked() {
+                    self.pause()
+                } else if ui.button("▶ Continue").clicked() {
+                    self.continue_execution()
+                } else if ui.button("⏭ Step Over").clicked() {
+                    self.step_over()
+                } else if ui.button("⏬ Step Into").clicked() {
+                    self.step_into()
+                } else if ui.button("⏫ Step Out").clicked() {
+                    self.step_out()
+                } else if ui.button("⏹ Stop").clicked() {
+                    self.stop_debug_session()
+                } else {
+                    Ok(())
+                };
+                if let Err(e) = result {
+                    self.console.add_message(e.to_string(), ConsoleMessageType::Error);
                 }
             } else {
                 if ui.button("▶ Start Debug").clicked() {
@@ -739,6 +1241,9 @@ impl Debugger {
 
         ui.separator();
 
+        let mut expand_reference = None;
+        let mut select_frame = None;
+
         // Debug panels
         ui.horizontal(|ui| {
             // Variables panel
@@ -749,21 +1254,7 @@ impl Debugger {
                         for scope in &self.variable_inspector.scopes {
                             ui.collapsing(&scope.name, |ui| {
                                 for var in &scope.variables {
-                                    ui.horizontal(|ui| {
-                                        ui.label(&var.name);
-                                        ui.label(":");
-                                        match &var.value {
-                                            VariableValue::Simple(value) => {
-                                                ui.label(value);
-                                            }
-                                            VariableValue::Complex { summary, .. } => {
-                                                ui.label(summary);
-                                            }
-                                            VariableValue::Error(error) => {
-                                                ui.colored_label(Color32::RED, error);
-                                            }
-                                        }
-                                    });
+                                    Self::render_variable(ui, var, &mut expand_reference);
                                 }
                             });
                         }
@@ -776,13 +1267,19 @@ impl Debugger {
                 ui.vertical(|ui| {
                     ui.heading("Call Stack");
                     ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
-                        let frames_len = self.call_stack.frames.len();
-                        for i in 0..frames_len {
-                            if let Some(frame) = self.call_stack.frames.get(i) {
-                                let is_selected = self.call_stack.selected_frame == Some(i);
-                                if ui.selectable_label(is_selected, &frame.name).clicked() {
-                                    self.call_stack.select_frame(i);
-                                }
+                        for (i, frame) in self.call_stack.frames.iter().enumerate() {
+                            let is_selected = self.call_stack.selected_frame == Some(i);
+                            let label = match &frame.location {
+                                Some(location) => format!(
+                                    "{} ({}:{})",
+                                    frame.name,
+                                    location.file.file_name().unwrap_or_default().to_string_lossy(),
+                                    location.line,
+                                ),
+                                None => frame.name.clone(),
+                            };
+                            if ui.selectable_label(is_selected, label).clicked() {
+                                select_frame = Some(i);
                             }
                         }
                     });
@@ -790,6 +1287,17 @@ impl Debugger {
             });
         });
 
+        let result = if let Some(reference) = expand_reference {
+            self.load_variable_children(reference)
+        } else if let Some(index) = select_frame {
+            self.select_frame(index)
+        } else {
+            Ok(())
+        };
+        if let Err(e) = result {
+            self.console.add_message(e.to_string(), ConsoleMessageType::Error);
+        }
+
         ui.separator();
 
         // Debug console
@@ -821,12 +1329,45 @@ impl Debugger {
                     if response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
                         let command = self.console.input.clone();
                         self.console.input.clear();
-                        self.console.execute_command(command);
+                        self.execute_console_command(command);
                     }
                 });
             });
         });
     }
+
+    /// Render one variable, reporting a click on an unloaded structured value
+    fn render_variable(ui: &mut Ui, var: &Variable, expand_reference: &mut Option<u64>) {
+        match &var.value {
+            VariableValue::Complex { summary, children, .. } => {
+                let header = format!("{}: {}", var.name, summary);
+                let response = CollapsingHeader::new(header)
+                    .id_source((&var.name, var.reference))
+                    .show(ui, |ui| {
+                        for child in children.iter().flatten() {
+                            Self::render_variable(ui, child, expand_reference);
+                        }
+                    });
+                if response.header_response.clicked() && children.is_none() {
+                    *expand_reference = var.reference;
+                }
+            }
+            VariableValue::Simple(value) => {
+                ui.horizontal(|ui| {
+                    ui.label(&var.name);
+                    ui.label(":");
+                    ui.label(value);
+                });
+            }
+            VariableValue::Error(error) => {
+                ui.horizontal(|ui| {
+                    ui.label(&var.name);
+                    ui.label(":");
+                    ui.colored_label(Color32::RED, error);
+                });
+            }
+        }
+    }
 }
 
 impl Default for Debugger {
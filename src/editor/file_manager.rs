@@ -502,6 +502,19 @@ impl FileManager {
         }
     }
     
+    /// Replace a tab's content with the file on disk, e.g. after it was rewritten by a refactoring
+    pub fn reload_tab(&mut self, path: &PathBuf) -> Result<(), FileManagerError> {
+        let tab = self.open_tabs.get_mut(path).ok_or_else(|| FileManagerError::TabNotFound(path.clone()))?;
+        let content = std::fs::read_to_string(path)?;
+        if let Some(editor) = tab.code_editor.as_mut() {
+            editor.code = content.clone();
+        }
+        tab.content = content;
+        tab.mark_clean();
+        tab.last_modified = std::fs::metadata(path)?.modified().ok();
+        Ok(())
+    }
+    
     /// Add file to recent files list
     fn add_to_recent(&mut self, path: &PathBuf) {
         // Remove if already in list
@@ -640,6 +653,11 @@ impl FileManager {
                 std::fs::write(path, &tab.content)?;
                 tab.mark_clean();
                 tab.last_modified = std::fs::metadata(path)?.modified().ok();
+                
+                global_event_bus().publish(IdeEvent::FileSaved {
+                    path: path.clone(),
+                    buffer_id: None,
+                });
             }
             Ok(())
         } else {
@@ -137,6 +137,7 @@ impl Default for FileWatcherConfig {
                 "*.swp".to_string(),
                 ".vscode".to_string(),
                 ".idea".to_string(),
+                ".ide-rs".to_string(),
             ],
             watch_extensions: None, // Watch all files by default
             recursive: true,
@@ -0,0 +1,265 @@
+//! Event Handler Stubs and Dispatch for Designer Forms
+//!
+//! The Events tab of the object inspector binds a component event to a handler
+//! method on the form, stored in the component's `on_<event>` property. Handler
+//! methods live in the `event_handlers` guard of the form source, which
+//! [`CodeRewriter`] keeps intact when the form is regenerated, and the generated
+//! `dispatch_events` method routes the events components raise at run time to
+//! the bound handlers.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::editor::codegen_markers::{CodeLanguage, CodeRewriter};
+
+/// ID of the guard section holding the user's handler methods
+pub const HANDLERS_GUARD: &str = "event_handlers";
+
+/// ID of the generated section holding `dispatch_events`
+pub const DISPATCH_SECTION: &str = "event_dispatch";
+
+/// Prefix of the properties that bind events to handlers
+pub const HANDLER_PROPERTY_PREFIX: &str = "on_";
+
+/// A component event bound to a handler method
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventBinding {
+    /// Designer ID of the component raising the event
+    pub component: String,
+    /// Event name, e.g. `click`
+    pub event: String,
+    /// Name of the form method handling the event
+    pub handler: String,
+}
+
+impl EventBinding {
+    /// Bindings for every `on_<event>` property with a handler set, sorted by event
+    pub fn from_properties(component: &str, properties: &HashMap<String, String>) -> Vec<Self> {
+        let mut bindings: Vec<Self> = properties
+            .iter()
+            .filter_map(|(name, handler)| {
+                let event = name.strip_prefix(HANDLER_PROPERTY_PREFIX)?;
+                let handler = handler.trim();
+                (!event.is_empty() && !handler.is_empty()).then(|| Self {
+                    component: component.to_string(),
+                    event: event.to_string(),
+                    handler: handler.to_string(),
+                })
+            })
+            .collect();
+        bindings.sort_by(|a, b| a.event.cmp(&b.event));
+        bindings
+    }
+}
+
+/// Where a handler method ended up after [`ensure_handler`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandlerLocation {
+    /// Zero-based line of the method signature
+    pub line: usize,
+    /// Whether a new stub was written
+    pub created: bool,
+}
+
+/// Property that stores the handler bound to `event`
+pub fn handler_property(event: &str) -> String {
+    format!("{}{}", HANDLER_PROPERTY_PREFIX, event)
+}
+
+/// Handler name suggested for a new binding, e.g. `button_0_click`
+pub fn default_handler_name(component: &str, event: &str) -> String {
+    format!("{}_{}", to_snake_case(component), to_snake_case(event))
+}
+
+/// Designer ID of the component at `index`, as stored in saved projects
+pub fn designer_component_id(component_type: &str, index: usize) -> String {
+    format!("{}_{}", component_type.to_lowercase(), index)
+}
+
+/// Whether `name` can be used as a handler method name
+pub fn is_valid_handler_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|first| first == '_' || first.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+        && name != "_"
+}
+
+/// Line of the `fn <handler>` declaration in `source`, if any
+pub fn find_handler(source: &str, handler: &str) -> Option<usize> {
+    let pattern = format!("fn {}", handler);
+    source.lines().position(|line| {
+        line.match_indices(&pattern).any(|(at, _)| {
+            let starts_word = !line[..at].ends_with(|c: char| c.is_alphanumeric() || c == '_');
+            let rest = line[at + pattern.len()..].trim_start();
+            starts_word && (rest.starts_with('(') || rest.starts_with('<'))
+        })
+    })
+}
+
+/// Make sure `source` has a method called `handler`, adding an empty stub to the
+/// handlers guard when it has none
+///
+/// An `impl <form_type>` block holding the guard is appended if the source has
+/// no handlers guard yet. Returns the updated source and the handler's location.
+pub fn ensure_handler(source: &str, form_type: &str, handler: &str) -> Result<(String, HandlerLocation), String> {
+    if !is_valid_handler_name(handler) {
+        return Err(format!("'{}' is not a valid method name", handler));
+    }
+    if let Some(line) = find_handler(source, handler) {
+        return Ok((source.to_string(), HandlerLocation { line, created: false }));
+    }
+
+    let source = with_handlers_guard(source, form_type)?;
+    let mut rewriter = CodeRewriter::new(CodeLanguage::Rust, source.clone());
+    rewriter.parse_markers()?;
+    let mut guard = rewriter
+        .marker(&format!("guard:{}", HANDLERS_GUARD))
+        .cloned()
+        .ok_or_else(|| format!("Missing {} guard", HANDLERS_GUARD))?;
+
+    let start_line = source.lines().nth(guard.start_position.line).unwrap_or_default();
+    let indent = &start_line[..start_line.len() - start_line.trim_start().len()];
+    let stub = format!("{indent}pub fn {handler}(&mut self) {{\n{indent}}}");
+    let existing = guard.content.trim_end();
+    let content = if existing.trim().is_empty() { stub } else { format!("{}\n\n{}", existing, stub) };
+    guard.update_content(content);
+
+    let updated = rewriter.rewrite_with_markers(vec![guard])?;
+    let line = find_handler(&updated, handler).ok_or_else(|| format!("Could not place handler {}", handler))?;
+    Ok((updated, HandlerLocation { line, created: true }))
+}
+
+/// Body of the generated section: a `dispatch_events` method that drains each
+/// bound component's events and calls the matching handlers
+pub fn dispatch_code(bindings: &[EventBinding]) -> String {
+    let mut components: Vec<(&str, Vec<&EventBinding>)> = Vec::new();
+    for binding in bindings {
+        match components.iter_mut().find(|(component, _)| *component == binding.component) {
+            Some((_, bound)) => bound.push(binding),
+            None => components.push((&binding.component, vec![binding])),
+        }
+    }
+
+    let mut code = String::from("    /// Route the events raised by components to their handlers\n");
+    if components.is_empty() {
+        code.push_str("    pub fn dispatch_events(&mut self) {}");
+        return code;
+    }
+    code.push_str("    pub fn dispatch_events(&mut self) {\n");
+    for (component, bound) in components {
+        code.push_str(&format!("        for event in self.{}.take_events() {{\n", to_snake_case(component)));
+        code.push_str("            match event.as_str() {\n");
+        for binding in bound {
+            code.push_str(&format!("                {:?} => self.{}(),\n", binding.event, binding.handler));
+        }
+        code.push_str("                _ => {}\n            }\n        }\n");
+    }
+    code.push_str("    }");
+    code
+}
+
+/// Replace the generated dispatch section of `source` with code for `bindings`
+pub fn update_dispatch(source: &str, bindings: &[EventBinding]) -> Result<String, String> {
+    let mut rewriter = CodeRewriter::new(CodeLanguage::Rust, source.to_string());
+    rewriter.parse_markers()?;
+    let mut section = rewriter
+        .marker(&format!("generated:{}", DISPATCH_SECTION))
+        .cloned()
+        .ok_or_else(|| format!("Missing {} section", DISPATCH_SECTION))?;
+    section.update_content(dispatch_code(bindings));
+    rewriter.rewrite_with_markers(vec![section])
+}
+
+/// Type name of the form generated for a project
+pub fn form_type_name(project_name: &str) -> String {
+    format!("{}Form", to_pascal_case(project_name))
+}
+
+/// Default path of the form source generated for a project
+pub fn form_source_path(project_root: &Path, project_name: &str) -> PathBuf {
+    project_root.join("src").join("generated").join(format!("{}_form.rs", to_snake_case(project_name)))
+}
+
+/// Declare a generated form's module in the `mod.rs` next to it
+///
+/// Returns whether `mod.rs` was changed; it is created if missing.
+pub fn declare_form_module(form_path: &Path) -> std::io::Result<bool> {
+    let (Some(dir), Some(module)) = (form_path.parent(), form_path.file_stem().and_then(|stem| stem.to_str())) else {
+        return Ok(false);
+    };
+    let mod_rs = dir.join("mod.rs");
+    let mut source = if mod_rs.exists() { std::fs::read_to_string(&mod_rs)? } else { String::new() };
+    let declaration = format!("pub mod {};", module);
+    if source.lines().any(|line| line.trim() == declaration) {
+        return Ok(false);
+    }
+    if !source.is_empty() && !source.ends_with('\n') {
+        source.push('\n');
+    }
+    source.push_str(&declaration);
+    source.push('\n');
+    std::fs::write(&mod_rs, source)?;
+    Ok(true)
+}
+
+/// Convert a name to a snake_case Rust identifier
+pub fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            if c.is_ascii_uppercase() && previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit()) {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else if !snake.is_empty() && !snake.ends_with('_') {
+            snake.push('_');
+        }
+        previous = Some(c);
+    }
+    let snake = snake.trim_end_matches('_').to_string();
+    match snake.chars().next() {
+        None => "component".to_string(),
+        Some(first) if first.is_ascii_digit() => format!("_{}", snake),
+        Some(_) => snake,
+    }
+}
+
+/// Convert a name to a PascalCase Rust type name
+pub fn to_pascal_case(name: &str) -> String {
+    let pascal: String = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|first| first.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+        })
+        .collect();
+    match pascal.chars().next() {
+        None => "Component".to_string(),
+        Some(first) if first.is_ascii_digit() => format!("_{}", pascal),
+        Some(_) => pascal,
+    }
+}
+
+/// `source` with a handlers guard, appending one in an `impl` block if missing
+fn with_handlers_guard(source: &str, form_type: &str) -> Result<String, String> {
+    let mut rewriter = CodeRewriter::new(CodeLanguage::Rust, source.to_string());
+    rewriter.parse_markers()?;
+    if rewriter.marker(&format!("guard:{}", HANDLERS_GUARD)).is_some() {
+        return Ok(source.to_string());
+    }
+
+    let mut updated = source.to_string();
+    if !updated.is_empty() && !updated.ends_with('\n') {
+        updated.push('\n');
+    }
+    if !updated.is_empty() {
+        updated.push('\n');
+    }
+    updated.push_str(&format!(
+        "impl {form_type} {{\n    // <codegen:guard:{guard}:start>\n    // <codegen:guard:{guard}:end>\n}}\n",
+        guard = HANDLERS_GUARD
+    ));
+    Ok(updated)
+}
@@ -7,6 +7,7 @@ use egui::*;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use crate::core::event_bus::{global_event_bus, IdeEvent};
 
 /// Git integration manager
 pub struct GitIntegration {
@@ -423,6 +424,19 @@ impl GitIntegration {
         }
 
         let commit_output = String::from_utf8_lossy(&output.stdout);
+
+        let hash = Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .current_dir(repo_path)
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .unwrap_or_default();
+        global_event_bus().publish(IdeEvent::GitCommitted {
+            repository_path: repo_path.clone(),
+            hash,
+            message: message.to_string(),
+        });
+
         Ok(commit_output.to_string())
     }
 
@@ -14,6 +14,7 @@ use uuid::Uuid;
 
 use crate::core::event_bus::{IdeEvent, global_event_bus};
 use super::lsp_integration::{Diagnostic, Range, Position, LspError};
+use super::lsp_service::{LanguageServerSettings, LspService};
 
 /// Core trait for language services
 #[async_trait::async_trait]
@@ -28,7 +29,7 @@ pub trait LanguageService: Send + Sync {
     fn supports_file(&self, file_path: &Path) -> bool;
     
     /// Get supported file extensions
-    fn supported_extensions(&self) -> Vec<&'static str>;
+    fn supported_extensions(&self) -> Vec<String>;
     
     /// Open/synchronize a document with the language service
     async fn open_document(&mut self, file_path: &Path, content: &str) -> Result<(), LanguageServiceError>;
@@ -419,10 +420,40 @@ impl LanguageServiceManager {
         
         // Emit event
         global_event_bus().publish(IdeEvent::LanguageServiceRegistered {
-            language: "rust".to_string(), // TODO: determine language from service
+            language: name.clone(),
             service_name: name,
         });
     }
+
+    /// Register an [`LspService`] for every server in `settings`
+    ///
+    /// Servers are registered under their language name; when two servers
+    /// claim an extension, the one whose language sorts last wins.
+    pub fn register_servers(&mut self, settings: &LanguageServerSettings) {
+        for config in settings.servers() {
+            let extensions = config.extensions.clone();
+            self.register_service(config.language.clone(), LspService::new(config.clone()), extensions);
+        }
+    }
+
+    /// Register the servers configured in the workspace's `.ide-rs/language_servers.json`
+    pub fn register_workspace_servers(&mut self, workspace_root: &Path) -> Result<usize, LanguageServiceError> {
+        let settings = LanguageServerSettings::load(workspace_root).map_err(LanguageServiceError::Other)?;
+        self.register_servers(&settings);
+        Ok(settings.languages.len())
+    }
+
+    /// Get the language service handling a file
+    pub fn service_for_file(&mut self, file_path: &Path) -> Result<&mut dyn LanguageService, LanguageServiceError> {
+        let service_name = self.get_service_for_file(file_path)
+            .ok_or_else(|| LanguageServiceError::FileNotSupported(file_path.to_path_buf()))?
+            .to_string();
+
+        match self.services.get_mut(&service_name) {
+            Some(service) => Ok(service.as_mut()),
+            None => Err(LanguageServiceError::Other(format!("Service {} not found", service_name))),
+        }
+    }
     
     /// Get language service for a file
     pub fn get_service_for_file(&self, file_path: &Path) -> Option<&str> {
@@ -536,8 +567,8 @@ mod tests {
             true
         }
         
-        fn supported_extensions(&self) -> Vec<&'static str> {
-            vec!["rs", "toml"]
+        fn supported_extensions(&self) -> Vec<String> {
+            vec!["rs".to_string(), "toml".to_string()]
         }
         
         async fn open_document(&mut self, _file_path: &Path, _content: &str) -> Result<(), LanguageServiceError> {
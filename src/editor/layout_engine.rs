@@ -0,0 +1,768 @@
+//! Flex and Grid Layout Engine
+//!
+//! Resolves the boxes of a tree of components laid out with the scene store's
+//! [`LayoutType`]s:
+//!
+//! - **Flex** containers follow CSS flexbox. Items start at their preferred
+//!   size, grow or shrink along the main axis within their min/max
+//!   constraints, wrap onto lines and are aligned with `justify_content` and
+//!   `align_items`.
+//! - **Grid** containers place items by explicit cell or auto flow and size
+//!   `px`, `fr`, `auto`, `min-content`, `max-content` and `minmax` tracks from
+//!   the items in them.
+//! - Other containers position children from their offsets and anchors.
+//!
+//! Children laid out as `Fixed` are positioned against the viewport, and
+//! `Relative` children of flex and grid containers are shifted by their
+//! offset from where the flow puts them. The designer lays forms out with
+//! [`solve`]; for generated forms [`layout_rects_code`] emits the rects it
+//! solves and [`egui_placement_code`] renders each component at its rect, so
+//! a generated form shows every component where the canvas does without
+//! depending on the engine.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Range;
+
+use egui::{vec2, Pos2, Rect, Vec2};
+
+use crate::editor::form_events::to_snake_case;
+use crate::editor::project_manager::project::{DesignerData, HorizontalAlign, VerticalAlign};
+use crate::editor::project_manager::project::LayoutType as DesignerLayout;
+use crate::editor::scene_store::{
+    AlignItems, ComponentId, FlexDirection, FlexLayout, FlexWrap, GridAutoFlow, GridLayout, GridTrack, JustifyContent,
+    LayoutConstraints, LayoutType, SceneStore,
+};
+use crate::rcl::component_registry::PropertyValue;
+
+/// Where an item goes in a grid; unset lines are filled by auto placement
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridPlacement {
+    /// First row, 0-based
+    pub row: Option<usize>,
+    /// First column, 0-based
+    pub column: Option<usize>,
+    /// Number of rows spanned
+    pub row_span: usize,
+    /// Number of columns spanned
+    pub column_span: usize,
+}
+
+impl Default for GridPlacement {
+    fn default() -> Self {
+        Self { row: None, column: None, row_span: 1, column_span: 1 }
+    }
+}
+
+/// A box to lay out together with its children
+#[derive(Clone, Debug)]
+pub struct LayoutNode<K> {
+    /// Key the solved rect is reported under
+    pub key: K,
+    /// How the node arranges its children, or how it is positioned itself
+    pub layout: LayoutType,
+    /// Offset in the parent for positioned layouts
+    pub position: Vec2,
+    /// Preferred size, used as flex basis and grid content size
+    pub size: Vec2,
+    /// Min/max size, aspect ratio and anchors
+    pub constraints: LayoutConstraints,
+    /// Share of free space taken in a flex line
+    pub flex_grow: f32,
+    /// Share of overflow given up in a flex line, weighted by size
+    pub flex_shrink: f32,
+    /// Cell in a grid container
+    pub grid: GridPlacement,
+    /// Child boxes in document order
+    pub children: Vec<LayoutNode<K>>,
+}
+
+impl<K> LayoutNode<K> {
+    /// Create an absolutely positioned node without children
+    pub fn new(key: K, size: Vec2) -> Self {
+        Self {
+            key,
+            layout: LayoutType::Absolute,
+            position: Vec2::ZERO,
+            size,
+            constraints: LayoutConstraints::default(),
+            flex_grow: 0.0,
+            flex_shrink: 1.0,
+            grid: GridPlacement::default(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Set how the node lays out its children
+    pub fn with_layout(mut self, layout: LayoutType) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Set the offset in the parent
+    pub fn with_position(mut self, position: Vec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Set the flex grow and shrink factors
+    pub fn with_flex(mut self, grow: f32, shrink: f32) -> Self {
+        self.flex_grow = grow;
+        self.flex_shrink = shrink;
+        self
+    }
+
+    /// Set the cell in a grid container
+    pub fn with_grid(mut self, grid: GridPlacement) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    /// Set the min/max size, aspect ratio and anchors
+    pub fn with_constraints(mut self, constraints: LayoutConstraints) -> Self {
+        self.constraints = constraints;
+        self
+    }
+
+    /// Append a child
+    pub fn with_child(mut self, child: LayoutNode<K>) -> Self {
+        self.children.push(child);
+        self
+    }
+}
+
+impl LayoutNode<ComponentId> {
+    /// Layout tree of a scene component and its visible descendants
+    ///
+    /// Flex factors and grid cells come from the `flex_grow`, `flex_shrink`,
+    /// `grid_row`, `grid_column`, `grid_row_span` and `grid_column_span`
+    /// properties.
+    pub fn from_scene(store: &SceneStore, id: ComponentId) -> Option<Self> {
+        let component = store.get_component(id).filter(|component| component.visible)?;
+        let meta = &component.layout_meta;
+        let number = |name: &str| match component.properties.get(name) {
+            Some(PropertyValue::Float(value)) => Some(*value),
+            Some(PropertyValue::Integer(value)) => Some(*value as f64),
+            _ => None,
+        };
+        let line = |name: &str| number(name).filter(|value| *value >= 0.0).map(|value| value as usize);
+
+        Some(Self {
+            key: id,
+            layout: meta.layout_type.clone(),
+            position: vec2(meta.position.x, meta.position.y),
+            size: vec2(meta.size.width, meta.size.height),
+            constraints: meta.constraints.clone(),
+            flex_grow: number("flex_grow").unwrap_or(0.0) as f32,
+            flex_shrink: number("flex_shrink").unwrap_or(1.0) as f32,
+            grid: GridPlacement {
+                row: line("grid_row"),
+                column: line("grid_column"),
+                row_span: line("grid_row_span").unwrap_or(1).max(1),
+                column_span: line("grid_column_span").unwrap_or(1).max(1),
+            },
+            children: store.get_children(id).into_iter().filter_map(|child| Self::from_scene(store, child)).collect(),
+        })
+    }
+}
+
+impl LayoutNode<String> {
+    /// Layout tree of a project's designer form, keyed by component field name
+    ///
+    /// The root has an empty key. Vertical and horizontal layouts become a
+    /// flex column or row spaced by the configured spacing, with components in
+    /// the order they appear along it; other layouts keep the designer
+    /// positions. Flex factors and grid cells come from the same properties as
+    /// in [`LayoutNode::from_scene`].
+    pub fn from_designer(data: &DesignerData) -> Self {
+        let config = &data.layout_config;
+        let cross = |start: bool, center: bool| if start { AlignItems::FlexStart } else if center { AlignItems::Center } else { AlignItems::FlexEnd };
+        let horizontal = cross(
+            matches!(config.alignment.horizontal, HorizontalAlign::Left),
+            matches!(config.alignment.horizontal, HorizontalAlign::Center),
+        );
+        let vertical = cross(
+            matches!(config.alignment.vertical, VerticalAlign::Top),
+            matches!(config.alignment.vertical, VerticalAlign::Center),
+        );
+        let justify = |align: AlignItems| match align {
+            AlignItems::Center => JustifyContent::Center,
+            AlignItems::FlexEnd => JustifyContent::FlexEnd,
+            _ => JustifyContent::FlexStart,
+        };
+        let flex = |direction: FlexDirection, main: AlignItems, cross: AlignItems| LayoutType::Flex(FlexLayout {
+            direction,
+            justify_content: justify(main),
+            align_items: cross,
+            wrap: FlexWrap::NoWrap,
+            gap: config.spacing,
+        });
+        let layout = match config.layout_type {
+            DesignerLayout::Vertical => flex(FlexDirection::Column, vertical, horizontal),
+            DesignerLayout::Horizontal => flex(FlexDirection::Row, horizontal, vertical),
+            DesignerLayout::Grid | DesignerLayout::Free | DesignerLayout::Custom(_) => LayoutType::Absolute,
+        };
+
+        let mut components: Vec<_> = data.components.iter().collect();
+        match config.layout_type {
+            DesignerLayout::Vertical => components.sort_by(|a, b| a.position.1.total_cmp(&b.position.1).then(a.z_order.cmp(&b.z_order))),
+            DesignerLayout::Horizontal => components.sort_by(|a, b| a.position.0.total_cmp(&b.position.0).then(a.z_order.cmp(&b.z_order))),
+            _ => components.sort_by_key(|component| component.z_order),
+        }
+        let in_flow = layout != LayoutType::Absolute;
+        let root = LayoutNode::new(String::new(), Vec2::ZERO).with_layout(layout);
+        components.into_iter().fold(root, |root, component| {
+            let number = |name: &str| component.properties.get(name).and_then(|value| value.trim().parse::<f32>().ok());
+            let line = |name: &str| number(name).filter(|value| *value >= 0.0).map(|value| value as usize);
+            let mut child = LayoutNode::new(to_snake_case(&component.id), vec2(component.size.0, component.size.1))
+                .with_flex(number("flex_grow").unwrap_or(0.0), number("flex_shrink").unwrap_or(1.0))
+                .with_grid(GridPlacement {
+                    row: line("grid_row"),
+                    column: line("grid_column"),
+                    row_span: line("grid_row_span").unwrap_or(1).max(1),
+                    column_span: line("grid_column_span").unwrap_or(1).max(1),
+                });
+            if !in_flow {
+                child = child.with_position(vec2(component.position.0, component.position.1));
+            }
+            root.with_child(child)
+        })
+    }
+}
+
+/// Solve the rect of every node, with the root filling `viewport`
+pub fn solve<K: Clone + Eq + Hash>(root: &LayoutNode<K>, viewport: Rect) -> HashMap<K, Rect> {
+    let mut rects = HashMap::new();
+    place(root, viewport, viewport, &mut rects);
+    rects
+}
+
+/// Solve the rects of a scene's components, with the root filling `viewport`
+pub fn solve_scene(store: &SceneStore, viewport: Rect) -> HashMap<ComponentId, Rect> {
+    store.root_component
+        .and_then(|root| LayoutNode::from_scene(store, root))
+        .map(|root| solve(&root, viewport))
+        .unwrap_or_default()
+}
+
+/// Rust expression listing the rect [`solve`] gives every component of a form
+///
+/// The tree is solved once, against a form of `form_size` shrunk by
+/// `padding`, and each node below the root becomes a `(key, rect)` pair in
+/// tree order, relative to the form's top-left corner. Generated forms embed
+/// the result so they need nothing but egui to place their components.
+/// Continuation lines are indented by `indent` spaces.
+pub fn layout_rects_code(root: &LayoutNode<String>, form_size: Vec2, padding: f32, indent: usize) -> String {
+    let nodes = descendants(root);
+    if nodes.is_empty() {
+        return "Vec::new()".to_string();
+    }
+    let rects = solve(root, Rect::from_min_size(Pos2::ZERO, form_size).shrink(padding));
+    let mut code = "vec![\n".to_string();
+    for node in nodes {
+        let rect = rects[&node.key];
+        code.push_str(&format!(
+            "{}({:?}, egui::Rect::from_min_size(egui::pos2({:?}, {:?}), egui::vec2({:?}, {:?}))),\n",
+            " ".repeat(indent + 4), node.key, rect.min.x, rect.min.y, rect.width(), rect.height()
+        ));
+    }
+    code.push_str(&" ".repeat(indent));
+    code.push(']');
+    code
+}
+
+/// Code placing each component of a form at its rect from the form's `layout()`
+///
+/// The rects are moved to the form's `ui` on every frame and each component
+/// below the root is rendered in its rect with `ui.allocate_ui_at_rect`, the
+/// same call the designer canvas uses. Fields are named after the node keys.
+pub fn egui_placement_code(root: &LayoutNode<String>) -> String {
+    let nodes = descendants(root);
+    if nodes.is_empty() {
+        return String::new();
+    }
+    let mut code = "let origin = ui.max_rect().min.to_vec2();\n\
+                    for (key, rect) in Self::layout() {\n\
+                    \x20   let rect = rect.translate(origin);\n\
+                    \x20   match key {\n".to_string();
+    for node in nodes {
+        code.push_str(&format!("        {:?} => ui.allocate_ui_at_rect(rect, |ui| self.{}.render(ui)),\n", node.key, node.key));
+    }
+    code.push_str("        _ => continue,\n    };\n}\n");
+    code
+}
+
+/// Nodes below `root`, in tree order
+fn descendants<K>(root: &LayoutNode<K>) -> Vec<&LayoutNode<K>> {
+    let mut nodes = Vec::new();
+    let mut pending: Vec<&LayoutNode<K>> = root.children.iter().rev().collect();
+    while let Some(node) = pending.pop() {
+        nodes.push(node);
+        pending.extend(node.children.iter().rev());
+    }
+    nodes
+}
+
+/// Record a node's rect and lay out its children inside it
+fn place<K: Clone + Eq + Hash>(node: &LayoutNode<K>, rect: Rect, viewport: Rect, rects: &mut HashMap<K, Rect>) {
+    rects.insert(node.key.clone(), rect);
+
+    let (fixed, in_flow): (Vec<&LayoutNode<K>>, Vec<&LayoutNode<K>>) =
+        node.children.iter().partition(|child| child.layout == LayoutType::Fixed);
+    let (flow_rects, container) = match &node.layout {
+        LayoutType::Flex(flex) => (flex_layout(flex, &in_flow, rect), true),
+        LayoutType::Grid(grid) => (grid_layout(grid, &in_flow, rect), true),
+        _ => (in_flow.iter().map(|child| anchored_rect(child, rect)).collect(), false),
+    };
+
+    for (child, child_rect) in in_flow.into_iter().zip(flow_rects) {
+        let child_rect = if container && child.layout == LayoutType::Relative {
+            child_rect.translate(child.position)
+        } else {
+            child_rect
+        };
+        place(child, child_rect, viewport, rects);
+    }
+    for child in fixed {
+        place(child, anchored_rect(child, viewport), viewport, rects);
+    }
+}
+
+/// Clamp one axis of a size to the node's min/max constraints, min winning
+fn clamp_axis<K>(node: &LayoutNode<K>, value: f32, horizontal: bool) -> f32 {
+    let pick = |size: &crate::editor::scene_store::Size| if horizontal { size.width } else { size.height };
+    let min = node.constraints.min_size.as_ref().map_or(0.0, pick);
+    let max = node.constraints.max_size.as_ref().map_or(f32::INFINITY, pick);
+    value.min(max).max(min)
+}
+
+fn clamp_size<K>(node: &LayoutNode<K>, size: Vec2) -> Vec2 {
+    vec2(clamp_axis(node, size.x, true), clamp_axis(node, size.y, false))
+}
+
+/// Preferred size after the aspect ratio and min/max constraints
+fn preferred_size<K>(node: &LayoutNode<K>) -> Vec2 {
+    let mut size = node.size;
+    if let Some(ratio) = node.constraints.aspect_ratio.filter(|ratio| *ratio > 0.0) {
+        size.y = size.x / ratio;
+    }
+    clamp_size(node, size)
+}
+
+/// Rect of a positioned node from its offset and anchors
+///
+/// Anchoring both sides of an axis stretches the node between them; one
+/// anchor pins that side at the given distance from the parent's edge.
+fn anchored_rect<K>(node: &LayoutNode<K>, parent: Rect) -> Rect {
+    let size = preferred_size(node);
+    let anchors = &node.constraints.anchors;
+    let axis = |start: Option<f32>, end: Option<f32>, offset: f32, size: f32, available: f32, horizontal: bool| match (start, end) {
+        (Some(start), Some(end)) => (start, clamp_axis(node, available - start - end, horizontal)),
+        (Some(start), None) => (start, size),
+        (None, Some(end)) => (available - end - size, size),
+        (None, None) => (offset, size),
+    };
+    let (x, width) = axis(anchors.left, anchors.right, node.position.x, size.x, parent.width(), true);
+    let (y, height) = axis(anchors.top, anchors.bottom, node.position.y, size.y, parent.height(), false);
+    Rect::from_min_size(parent.min + vec2(x, y), vec2(width, height))
+}
+
+/// Lay out flex items inside `rect`
+fn flex_layout<K>(flex: &FlexLayout, items: &[&LayoutNode<K>], rect: Rect) -> Vec<Rect> {
+    let row = matches!(flex.direction, FlexDirection::Row | FlexDirection::RowReverse);
+    let reverse = matches!(flex.direction, FlexDirection::RowReverse | FlexDirection::ColumnReverse);
+    let split = |size: Vec2| if row { (size.x, size.y) } else { (size.y, size.x) };
+    let (main_size, cross_size) = split(rect.size());
+    let preferred: Vec<(f32, f32)> = items.iter().map(|item| split(preferred_size(item))).collect();
+
+    // Break items into lines at the container's main size
+    let mut lines: Vec<Range<usize>> = Vec::new();
+    let (mut start, mut used) = (0, 0.0);
+    for (index, (basis, _)) in preferred.iter().enumerate() {
+        let needed = if index == start { *basis } else { used + flex.gap + basis };
+        if flex.wrap != FlexWrap::NoWrap && index > start && needed > main_size {
+            lines.push(start..index);
+            start = index;
+            used = *basis;
+        } else {
+            used = needed;
+        }
+    }
+    if start < items.len() {
+        lines.push(start..items.len());
+    }
+
+    // Lines share the cross size; a single unwrapped line takes all of it
+    let mut line_cross: Vec<f32> = lines.iter()
+        .map(|line| preferred[line.clone()].iter().map(|(_, cross)| *cross).fold(0.0, f32::max))
+        .collect();
+    if flex.wrap == FlexWrap::NoWrap {
+        line_cross.iter_mut().for_each(|cross| *cross = cross_size);
+    } else {
+        let used: f32 = line_cross.iter().sum::<f32>() + flex.gap * lines.len().saturating_sub(1) as f32;
+        if used < cross_size && !lines.is_empty() {
+            let extra = (cross_size - used) / lines.len() as f32;
+            line_cross.iter_mut().for_each(|cross| *cross += extra);
+        }
+    }
+
+    let mut rects = vec![Rect::NOTHING; items.len()];
+    let mut line_start = 0.0;
+    for (line, cross) in lines.into_iter().zip(line_cross) {
+        let sizes = resolve_main_sizes(flex, &items[line.clone()], &preferred[line.clone()], main_size, row);
+        let count = sizes.len() as f32;
+        let leftover = main_size - sizes.iter().sum::<f32>() - flex.gap * (count - 1.0);
+        let (mut offset, spacing) = match flex.justify_content {
+            JustifyContent::FlexStart => (0.0, 0.0),
+            JustifyContent::FlexEnd => (leftover, 0.0),
+            JustifyContent::Center => (leftover / 2.0, 0.0),
+            JustifyContent::SpaceBetween if leftover > 0.0 && count > 1.0 => (0.0, leftover / (count - 1.0)),
+            JustifyContent::SpaceBetween => (0.0, 0.0),
+            JustifyContent::SpaceAround if leftover > 0.0 => (leftover / count / 2.0, leftover / count),
+            JustifyContent::SpaceEvenly if leftover > 0.0 => (leftover / (count + 1.0), leftover / (count + 1.0)),
+            JustifyContent::SpaceAround | JustifyContent::SpaceEvenly => (leftover / 2.0, 0.0),
+        };
+
+        let line_position = if flex.wrap == FlexWrap::WrapReverse { cross_size - line_start - cross } else { line_start };
+        for ((index, main), (_, preferred_cross)) in line.clone().zip(sizes).zip(&preferred[line]) {
+            let item = items[index];
+            let item_cross = match flex.align_items {
+                AlignItems::Stretch => clamp_axis(item, cross, !row),
+                _ => *preferred_cross,
+            };
+            let mut cross_offset = match flex.align_items {
+                AlignItems::FlexEnd => cross - item_cross,
+                AlignItems::Center => (cross - item_cross) / 2.0,
+                AlignItems::FlexStart | AlignItems::Baseline | AlignItems::Stretch => 0.0,
+            };
+            if flex.wrap == FlexWrap::WrapReverse {
+                cross_offset = cross - cross_offset - item_cross;
+            }
+
+            let main_position = if reverse { main_size - offset - main } else { offset };
+            let (position, size) = if row {
+                (vec2(main_position, line_position + cross_offset), vec2(main, item_cross))
+            } else {
+                (vec2(line_position + cross_offset, main_position), vec2(item_cross, main))
+            };
+            rects[index] = Rect::from_min_size(rect.min + position, size);
+            offset += main + flex.gap + spacing;
+        }
+        line_start += cross + flex.gap;
+    }
+    rects
+}
+
+/// Grow or shrink the items of a flex line to fill its main size
+///
+/// Items that would cross their min or max size are frozen there and the
+/// remaining space is shared again among the others.
+fn resolve_main_sizes<K>(flex: &FlexLayout, items: &[&LayoutNode<K>], preferred: &[(f32, f32)], main_size: f32, row: bool) -> Vec<f32> {
+    let bases: Vec<f32> = preferred.iter().map(|(basis, _)| *basis).collect();
+    let gaps = flex.gap * bases.len().saturating_sub(1) as f32;
+    let growing = bases.iter().sum::<f32>() + gaps < main_size;
+    let factor = |index: usize| if growing { items[index].flex_grow } else { items[index].flex_shrink * bases[index] };
+
+    let mut sizes = bases.clone();
+    let mut frozen: Vec<bool> = (0..items.len()).map(|index| factor(index) <= 0.0).collect();
+    while frozen.iter().any(|frozen| !frozen) {
+        let occupied: f32 = (0..items.len()).map(|index| if frozen[index] { sizes[index] } else { bases[index] }).sum();
+        let free = main_size - gaps - occupied;
+        let total: f32 = (0..items.len()).filter(|index| !frozen[*index]).map(factor).sum();
+
+        let mut violation = 0.0;
+        let mut clamped = vec![0.0; items.len()];
+        for index in (0..items.len()).filter(|index| !frozen[*index]) {
+            let target = bases[index] + free * factor(index) / total;
+            sizes[index] = clamp_axis(items[index], target, row).max(0.0);
+            clamped[index] = sizes[index] - target;
+            violation += clamped[index];
+        }
+        if violation.abs() < 0.01 {
+            break;
+        }
+        // Freeze the items that hit the limit the total leans towards
+        for (frozen, clamped) in frozen.iter_mut().zip(clamped) {
+            if (violation > 0.0 && clamped > 0.0) || (violation < 0.0 && clamped < 0.0) {
+                *frozen = true;
+            }
+        }
+    }
+    sizes
+}
+
+/// Lay out grid items inside `rect`
+fn grid_layout<K>(grid: &GridLayout, items: &[&LayoutNode<K>], rect: Rect) -> Vec<Rect> {
+    let cells = place_grid_items(grid, items);
+    let column_count = cells.iter().map(|cell| cell.column + cell.column_span).max().unwrap_or(0).max(grid.columns.len());
+    let row_count = cells.iter().map(|cell| cell.row + cell.row_span).max().unwrap_or(0).max(grid.rows.len());
+
+    let contributions = |horizontal: bool| -> Vec<TrackItem> {
+        items.iter().zip(&cells).map(|(item, cell)| {
+            let preferred = preferred_size(item);
+            TrackItem {
+                start: if horizontal { cell.column } else { cell.row },
+                span: if horizontal { cell.column_span } else { cell.row_span },
+                min_content: clamp_axis(*item, 0.0, horizontal),
+                max_content: if horizontal { preferred.x } else { preferred.y },
+            }
+        }).collect()
+    };
+    let columns = size_tracks(&tracks(&grid.columns, column_count), rect.width(), grid.gap.column, &contributions(true));
+    let rows = size_tracks(&tracks(&grid.rows, row_count), rect.height(), grid.gap.row, &contributions(false));
+    let starts = |sizes: &[f32], gap: f32| -> Vec<f32> {
+        sizes.iter().scan(0.0, |at, size| {
+            let start = *at;
+            *at += size + gap;
+            Some(start)
+        }).collect()
+    };
+    let (column_starts, row_starts) = (starts(&columns, grid.gap.column), starts(&rows, grid.gap.row));
+    let span = |sizes: &[f32], start: usize, span: usize, gap: f32| sizes[start..start + span].iter().sum::<f32>() + gap * (span - 1) as f32;
+
+    // Items stretch to fill their cell within their own constraints
+    items.iter().zip(&cells).map(|(item, cell)| {
+        let cell_size = vec2(
+            span(&columns, cell.column, cell.column_span, grid.gap.column),
+            span(&rows, cell.row, cell.row_span, grid.gap.row),
+        );
+        Rect::from_min_size(rect.min + vec2(column_starts[cell.column], row_starts[cell.row]), clamp_size(*item, cell_size))
+    }).collect()
+}
+
+/// Explicit tracks followed by `auto` tracks up to `count`
+fn tracks(explicit: &[GridTrack], count: usize) -> Vec<GridTrack> {
+    explicit.iter().cloned().chain(std::iter::repeat(GridTrack::Auto)).take(count).collect()
+}
+
+/// Cell of an item after placement
+#[derive(Clone, Debug, PartialEq)]
+struct GridCell {
+    row: usize,
+    column: usize,
+    row_span: usize,
+    column_span: usize,
+}
+
+/// Place items in a grid: explicit cells first, then the rest in auto-flow order
+///
+/// Row flow fills rows across the explicit columns and adds rows as needed;
+/// column flow does the same down the explicit rows. Dense flow restarts the
+/// search at the first cell for every item to fill earlier holes.
+fn place_grid_items<K>(grid: &GridLayout, items: &[&LayoutNode<K>]) -> Vec<GridCell> {
+    let by_rows = matches!(grid.auto_flow, GridAutoFlow::Row | GridAutoFlow::RowDense);
+    let dense = matches!(grid.auto_flow, GridAutoFlow::RowDense | GridAutoFlow::ColumnDense);
+    // Work in (major, minor) lines: rows and columns for row flow
+    let to_flow = |placement: &GridPlacement| if by_rows {
+        (placement.row, placement.column, placement.row_span, placement.column_span)
+    } else {
+        (placement.column, placement.row, placement.column_span, placement.row_span)
+    };
+    let explicit_minor = if by_rows { grid.columns.len() } else { grid.rows.len() };
+    let minor_count = items.iter()
+        .map(|item| {
+            let (_, minor, _, minor_span) = to_flow(&item.grid);
+            minor.unwrap_or(0) + minor_span
+        })
+        .max()
+        .unwrap_or(0)
+        .max(explicit_minor)
+        .max(1);
+
+    let mut occupied: Vec<Vec<bool>> = Vec::new();
+    let fits = |occupied: &mut Vec<Vec<bool>>, major: usize, minor: usize, major_span: usize, minor_span: usize, claim: bool| {
+        if minor + minor_span > minor_count {
+            return false;
+        }
+        while occupied.len() < major + major_span {
+            occupied.push(vec![false; minor_count]);
+        }
+        let free = (major..major + major_span).all(|line| occupied[line][minor..minor + minor_span].iter().all(|taken| !taken));
+        if free && claim {
+            for line in &mut occupied[major..major + major_span] {
+                line[minor..minor + minor_span].iter_mut().for_each(|taken| *taken = true);
+            }
+        }
+        free
+    };
+
+    let mut cells: Vec<Option<(usize, usize, usize, usize)>> = vec![None; items.len()];
+    // Fully explicit items claim their cells first, overlapping or not
+    for (index, item) in items.iter().enumerate() {
+        if let (Some(major), Some(minor), major_span, minor_span) = to_flow(&item.grid) {
+            fits(&mut occupied, major, minor, major_span, minor_span, true);
+            cells[index] = Some((major, minor, major_span, minor_span));
+        }
+    }
+
+    let mut cursor = (0, 0);
+    for (index, item) in items.iter().enumerate() {
+        if cells[index].is_some() {
+            continue;
+        }
+        let (major, minor, major_span, minor_span) = to_flow(&item.grid);
+        let minor_span = minor_span.min(minor_count);
+        let mut at = match (major, minor) {
+            (Some(major), _) => (major, 0),
+            _ if dense => (0, 0),
+            _ => cursor,
+        };
+        loop {
+            let candidate_minor = minor.unwrap_or(at.1);
+            if (minor.is_none() || at.1 <= candidate_minor) && fits(&mut occupied, at.0, candidate_minor, major_span, minor_span, true) {
+                cells[index] = Some((at.0, candidate_minor, major_span, minor_span));
+                if major.is_none() {
+                    cursor = (at.0, candidate_minor + minor_span);
+                }
+                break;
+            }
+            at = if minor.is_none() && at.1 + 1 + minor_span <= minor_count { (at.0, at.1 + 1) } else { (at.0 + 1, 0) };
+        }
+    }
+
+    cells.into_iter().map(|cell| {
+        let (major, minor, major_span, minor_span) = cell.unwrap_or((0, 0, 1, 1));
+        if by_rows {
+            GridCell { row: major, column: minor, row_span: major_span, column_span: minor_span }
+        } else {
+            GridCell { row: minor, column: major, row_span: minor_span, column_span: major_span }
+        }
+    }).collect()
+}
+
+/// What an item needs from the tracks it spans on one axis
+struct TrackItem {
+    start: usize,
+    span: usize,
+    /// Smallest size, from the item's min size
+    min_content: f32,
+    /// Size it would like, its preferred size
+    max_content: f32,
+}
+
+/// Sizing function of one side of a track
+#[derive(Clone, Copy, PartialEq)]
+enum TrackBound {
+    Fixed(f32),
+    MinContent,
+    MaxContent,
+    Flex(f32),
+}
+
+fn min_bound(track: &GridTrack) -> TrackBound {
+    match track {
+        GridTrack::Px(size) => TrackBound::Fixed(*size),
+        GridTrack::MaxContent => TrackBound::MaxContent,
+        GridTrack::Minmax(min, _) => min_bound(min),
+        GridTrack::Fr(_) | GridTrack::Auto | GridTrack::MinContent => TrackBound::MinContent,
+    }
+}
+
+fn max_bound(track: &GridTrack) -> TrackBound {
+    match track {
+        GridTrack::Px(size) => TrackBound::Fixed(*size),
+        GridTrack::Fr(factor) => TrackBound::Flex(*factor),
+        GridTrack::MinContent => TrackBound::MinContent,
+        GridTrack::Auto | GridTrack::MaxContent => TrackBound::MaxContent,
+        GridTrack::Minmax(_, max) => max_bound(max),
+    }
+}
+
+/// Whether a track's maximum is `auto`, which takes leftover space
+fn auto_max(track: &GridTrack) -> bool {
+    match track {
+        GridTrack::Auto => true,
+        GridTrack::Minmax(_, max) => auto_max(max),
+        _ => false,
+    }
+}
+
+/// Size the tracks of one axis to fit `available`
+///
+/// Tracks start at their minimum, grow towards their maximum while space
+/// remains, then `fr` tracks share what is left by factor without going
+/// below their minimum. Without `fr` tracks, leftover space stretches the
+/// `auto` tracks.
+fn size_tracks(tracks: &[GridTrack], available: f32, gap: f32, items: &[TrackItem]) -> Vec<f32> {
+    let count = tracks.len();
+    let space = available - gap * count.saturating_sub(1) as f32;
+
+    let (mut min_content, mut max_content) = (vec![0.0f32; count], vec![0.0f32; count]);
+    for item in items.iter().filter(|item| item.span == 1 && item.start < count) {
+        min_content[item.start] = min_content[item.start].max(item.min_content);
+        max_content[item.start] = max_content[item.start].max(item.max_content);
+    }
+    let resolve = |bound: TrackBound, index: usize| match bound {
+        TrackBound::Fixed(size) => size,
+        TrackBound::MinContent => min_content[index],
+        TrackBound::MaxContent => max_content[index],
+        TrackBound::Flex(_) => f32::INFINITY,
+    };
+    let mut base: Vec<f32> = (0..count).map(|index| resolve(min_bound(&tracks[index]), index)).collect();
+    let mut limit: Vec<f32> = (0..count).map(|index| resolve(max_bound(&tracks[index]), index).max(base[index])).collect();
+
+    // Items spanning several tracks spread what they need over the intrinsic ones
+    let mut spanning: Vec<&TrackItem> = items.iter().filter(|item| item.span > 1 && item.start + item.span <= count).collect();
+    spanning.sort_by_key(|item| item.span);
+    for item in spanning {
+        let spanned = item.start..item.start + item.span;
+        let inner_gaps = gap * (item.span - 1) as f32;
+        let intrinsic: Vec<usize> = spanned.clone().filter(|index| !matches!(min_bound(&tracks[*index]), TrackBound::Fixed(_))).collect();
+        let extra = item.min_content - inner_gaps - spanned.clone().map(|index| base[index]).sum::<f32>();
+        if extra > 0.0 && !intrinsic.is_empty() {
+            intrinsic.iter().for_each(|index| base[*index] += extra / intrinsic.len() as f32);
+        }
+        let growable: Vec<usize> = spanned.clone().filter(|index| matches!(max_bound(&tracks[*index]), TrackBound::MinContent | TrackBound::MaxContent)).collect();
+        let extra = item.max_content - inner_gaps - spanned.clone().map(|index| limit[index].min(f32::MAX)).sum::<f32>();
+        if extra > 0.0 && !growable.is_empty() {
+            growable.iter().for_each(|index| limit[*index] += extra / growable.len() as f32);
+        }
+        spanned.for_each(|index| limit[index] = limit[index].max(base[index]));
+    }
+
+    // Grow tracks towards their limits, sharing the free space evenly
+    let mut free = space - base.iter().sum::<f32>();
+    loop {
+        let growable: Vec<usize> = (0..count).filter(|index| limit[*index].is_finite() && base[*index] < limit[*index]).collect();
+        if free <= 0.01 || growable.is_empty() {
+            break;
+        }
+        let share = free / growable.len() as f32;
+        for index in growable {
+            let grow = share.min(limit[index] - base[index]);
+            base[index] += grow;
+            free -= grow;
+        }
+    }
+
+    let flexible: Vec<(usize, f32)> = (0..count)
+        .filter_map(|index| match max_bound(&tracks[index]) {
+            TrackBound::Flex(factor) if factor > 0.0 => Some((index, factor)),
+            _ => None,
+        })
+        .collect();
+    if !flexible.is_empty() {
+        // Tracks whose minimum exceeds their share keep it and drop out
+        let inflexible: f32 = (0..count).filter(|index| flexible.iter().all(|(flex, _)| flex != index)).map(|index| base[index]).sum();
+        let mut active = flexible.clone();
+        let unit = loop {
+            let dropped: f32 = flexible.iter().filter(|track| !active.contains(track)).map(|(index, _)| base[*index]).sum();
+            let factors = active.iter().map(|(_, factor)| factor).sum::<f32>().max(1.0);
+            let unit = (space - inflexible - dropped).max(0.0) / factors;
+            let before = active.len();
+            active.retain(|(index, factor)| base[*index] <= factor * unit);
+            if active.len() == before {
+                break unit;
+            }
+        };
+        for (index, factor) in active {
+            base[index] = base[index].max(factor * unit);
+        }
+    } else if free > 0.01 {
+        let auto: Vec<usize> = (0..count).filter(|index| auto_max(&tracks[*index])).collect();
+        if !auto.is_empty() {
+            auto.iter().for_each(|index| base[*index] += free / auto.len() as f32);
+        }
+    }
+    base
+}
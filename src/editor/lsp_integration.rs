@@ -260,7 +260,13 @@ impl LspClient {
 
     /// Start rust-analyzer language server
     pub fn start_rust_analyzer(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let mut child = Command::new("rust-analyzer")
+        self.start_server("rust-analyzer", &[])
+    }
+
+    /// Start any stdio language server
+    pub fn start_server(&mut self, command: &str, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut child = Command::new(command)
+            .args(args)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -570,7 +576,7 @@ impl LspClient {
     }
 
     /// Parse completion items from LSP response
-    fn parse_completion_items(response: &Value) -> Vec<CompletionItem> {
+    pub(crate) fn parse_completion_items(response: &Value) -> Vec<CompletionItem> {
         let mut items = Vec::new();
         
         if let Some(items_array) = response.get("items").and_then(|v| v.as_array()) {
@@ -747,7 +753,7 @@ impl LspClient {
     }
 
     /// Parse LSP range
-    fn parse_range(range_obj: &Value) -> Range {
+    pub(crate) fn parse_range(range_obj: &Value) -> Range {
         let start = if let Some(start_obj) = range_obj.get("start") {
             Position {
                 line: start_obj.get("line").and_then(|v| v.as_u64()).unwrap_or(0),
@@ -770,7 +776,7 @@ impl LspClient {
     }
 
     /// Parse diagnostic severity
-    fn parse_diagnostic_severity(severity: Option<&Value>) -> Option<DiagnosticSeverity> {
+    pub(crate) fn parse_diagnostic_severity(severity: Option<&Value>) -> Option<DiagnosticSeverity> {
         severity.and_then(|v| v.as_u64()).and_then(|s| {
             match s {
                 1 => Some(DiagnosticSeverity::Error),
@@ -843,4 +849,39 @@ pub struct TextDocumentContentChangeEvent {
     pub range: Option<Range>,
     pub range_length: Option<u64>,
     pub text: String,
-}
\ No newline at end of file
+}
+/// Read one `Content-Length` framed message; `None` at end of stream
+pub fn read_message(reader: &mut impl BufRead) -> std::io::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length.unwrap_or(0)];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// Write a message with `Content-Length` framing
+pub fn write_message(writer: &mut impl Write, message: &Value) -> std::io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
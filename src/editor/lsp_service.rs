@@ -0,0 +1,1512 @@
+//! Generic Language Server Service
+//!
+//! This module provides a LanguageService implementation for any language
+//! server speaking LSP over stdio. Each server is described by an
+//! [`LspServerConfig`] (command, arguments, file extensions, initialization
+//! options and root markers), and a workspace lists its servers in
+//! `.ide-rs/language_servers.json`:
+//!
+//! ```json
+//! {
+//!   "languages": {
+//!     "python": {
+//!       "command": "pylsp",
+//!       "extensions": ["py", "pyi"],
+//!       "rootMarkers": ["pyproject.toml", "setup.py"],
+//!       "settings": { "pylsp": { "plugins": { "ruff": { "enabled": true } } } }
+//!     },
+//!     "wgsl": { "command": "wgsl-analyzer", "extensions": ["wgsl"] }
+//!   }
+//! }
+//! ```
+//!
+//! The server runs as a child process speaking JSON-RPC over stdio. A writer
+//! thread sends framed messages to its stdin and a reader thread dispatches
+//! everything coming back: responses are matched to their pending request,
+//! `textDocument/publishDiagnostics` and `$/progress` notifications are
+//! recorded, and server-to-client requests such as `workspace/applyEdit`
+//! are answered.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use std::process::{Child, Command, Stdio};
+use std::io::{BufRead, BufReader};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use uuid::Uuid;
+use serde_json::{json, Value};
+
+use crate::core::event_bus::{global_event_bus, IdeEvent};
+use super::language_service::{
+    LanguageService, LanguageServiceError, CompletionItem, CompletionKind,
+    HoverInfo, Location, CodeAction, CodeActionKind, TextEdit, DocumentSymbol,
+    WorkspaceSymbol, SymbolKind, SignatureHelp, SignatureInformation,
+    ParameterInformation, SemanticToken, FoldingRange, FoldingRangeKind,
+    WorkspaceEdit, Command as LspCommand,
+};
+use super::lsp_integration::{
+    self, LspClient, Diagnostic, DiagnosticRelatedInformation, Range, Position,
+};
+
+/// JSON-RPC error code for requests the client does not handle
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Handler deciding whether a server-initiated `workspace/applyEdit` succeeds
+pub type ApplyEditHandler = Box<dyn FnMut(&WorkspaceEdit) -> Result<(), String> + Send>;
+
+/// Settings file listing the language servers of a workspace
+pub const LANGUAGE_SERVERS_FILE: &str = ".ide-rs/language_servers.json";
+
+/// How to launch and talk to one language server
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LspServerConfig {
+    /// Language name, used as the service name and the default `languageId`
+    pub language: String,
+    /// Server executable
+    pub command: String,
+    /// Arguments passed to the server executable
+    pub args: Vec<String>,
+    /// File extensions handled by the server, without the dot
+    pub extensions: Vec<String>,
+    /// `languageId` per extension, for servers handling several languages
+    pub language_ids: HashMap<String, String>,
+    /// Sent as `initializationOptions` in the `initialize` request
+    pub initialization_options: Option<Value>,
+    /// Files or directories marking the project root, searched upwards from the workspace
+    pub root_markers: Vec<String>,
+    /// Answers to `workspace/configuration`, looked up by section
+    pub settings: Value,
+}
+
+impl LspServerConfig {
+    /// Create a configuration launching `command`
+    pub fn new(language: impl Into<String>, command: impl Into<String>) -> Self {
+        Self {
+            language: language.into(),
+            command: command.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Configuration for rust-analyzer
+    pub fn rust_analyzer() -> Self {
+        Self {
+            extensions: vec!["rs".to_string(), "toml".to_string()],
+            language_ids: HashMap::from([("toml".to_string(), "toml".to_string())]),
+            root_markers: vec!["Cargo.toml".to_string()],
+            ..Self::new("rust", "rust-analyzer")
+        }
+    }
+
+    /// Set the arguments passed to the server
+    pub fn with_args(mut self, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the file extensions handled by the server
+    pub fn with_extensions(mut self, extensions: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.extensions = extensions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Whether the server handles `file_path`, judged by its extension
+    pub fn handles(&self, file_path: &Path) -> bool {
+        file_path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| self.extensions.iter().any(|handled| handled == ext))
+    }
+
+    /// `languageId` sent when opening `file_path`
+    pub fn language_id(&self, file_path: &Path) -> &str {
+        file_path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| self.language_ids.get(ext))
+            .unwrap_or(&self.language)
+    }
+
+    /// Root the server is started in
+    ///
+    /// The nearest ancestor of `workspace_root` (itself included) containing
+    /// one of the root markers, or `workspace_root` when none does.
+    pub fn project_root(&self, workspace_root: &Path) -> PathBuf {
+        if self.root_markers.is_empty() {
+            return workspace_root.to_path_buf();
+        }
+
+        workspace_root.ancestors()
+            .find(|dir| self.root_markers.iter().any(|marker| dir.join(marker).exists()))
+            .unwrap_or(workspace_root)
+            .to_path_buf()
+    }
+}
+
+/// Language servers configured for a workspace
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LanguageServerSettings {
+    /// Server configuration by language name
+    pub languages: HashMap<String, LspServerConfig>,
+}
+
+impl LanguageServerSettings {
+    /// Parse settings from JSON
+    ///
+    /// A configuration without an explicit `language` takes its key.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let mut settings: Self = serde_json::from_str(json)
+            .map_err(|e| format!("Invalid language server settings: {}", e))?;
+
+        for (language, config) in &mut settings.languages {
+            if config.language.is_empty() {
+                config.language = language.clone();
+            }
+            if config.command.is_empty() {
+                return Err(format!("Language server for {} has no command", language));
+            }
+        }
+        Ok(settings)
+    }
+
+    /// Load `.ide-rs/language_servers.json` from a workspace
+    ///
+    /// Without a settings file only rust-analyzer is configured.
+    pub fn load(workspace_root: &Path) -> Result<Self, String> {
+        let path = workspace_root.join(LANGUAGE_SERVERS_FILE);
+        if !path.exists() {
+            return Ok(Self::default_servers());
+        }
+
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::from_json(&json)
+    }
+
+    /// Settings used when a workspace has none
+    pub fn default_servers() -> Self {
+        Self {
+            languages: HashMap::from([("rust".to_string(), LspServerConfig::rust_analyzer())]),
+        }
+    }
+
+    /// Configurations sorted by language name
+    pub fn servers(&self) -> Vec<&LspServerConfig> {
+        let mut servers: Vec<&LspServerConfig> = self.languages.values().collect();
+        servers.sort_by(|a, b| a.language.cmp(&b.language));
+        servers
+    }
+}
+
+/// Language service backed by a stdio language server
+pub struct LspService {
+    /// How to launch the server
+    config: LspServerConfig,
+    /// Language server process
+    process: Option<Child>,
+    /// Workspace root path
+    workspace_root: Option<PathBuf>,
+    /// Document versions for sync
+    document_versions: HashMap<PathBuf, u64>,
+    /// Initialization state
+    initialized: bool,
+    /// Request counter
+    request_counter: u64,
+    /// How long to wait for a response before cancelling the request
+    request_timeout: Duration,
+    /// Pending requests, shared with the reader thread
+    pending_requests: Arc<Mutex<HashMap<u64, PendingLspRequest>>>,
+    /// State fed by server notifications and requests
+    server_state: Arc<Mutex<ServerState>>,
+    /// Outgoing messages, written to the server by the writer thread
+    message_tx: Option<mpsc::UnboundedSender<Value>>,
+    /// Capabilities returned by `initialize`
+    server_capabilities: Value,
+    /// Identifier used in language server events
+    server_id: String,
+}
+
+/// Pending request for the language server
+struct PendingLspRequest {
+    method: String,
+    sender: oneshot::Sender<Result<Value, LanguageServiceError>>,
+}
+
+/// State updated by the reader thread
+#[derive(Default)]
+struct ServerState {
+    running: bool,
+    settings: Value,
+    diagnostics: HashMap<PathBuf, Vec<Diagnostic>>,
+    progress: HashMap<String, WorkDoneProgress>,
+    workspace_edits: Vec<WorkspaceEdit>,
+    apply_edit_handler: Option<ApplyEditHandler>,
+}
+
+/// Work done progress reported by the server through `$/progress`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkDoneProgress {
+    pub token: String,
+    pub title: String,
+    pub message: Option<String>,
+    pub percentage: Option<u32>,
+    pub done: bool,
+}
+
+impl LspService {
+    /// Create a service for the configured server
+    pub fn new(config: LspServerConfig) -> Self {
+        let server_state = ServerState {
+            settings: config.settings.clone(),
+            ..Default::default()
+        };
+
+        Self {
+            config,
+            process: None,
+            workspace_root: None,
+            document_versions: HashMap::new(),
+            initialized: false,
+            request_counter: 0,
+            request_timeout: Duration::from_secs(30),
+            pending_requests: Arc::new(Mutex::new(HashMap::new())),
+            server_state: Arc::new(Mutex::new(server_state)),
+            message_tx: None,
+            server_capabilities: Value::Null,
+            server_id: Uuid::new_v4().to_string(),
+        }
+    }
+
+    /// Server configuration
+    pub fn config(&self) -> &LspServerConfig {
+        &self.config
+    }
+
+    /// Root the server was started in, once initialized
+    pub fn project_root(&self) -> Option<&Path> {
+        self.workspace_root.as_deref()
+    }
+
+    /// Set how long requests wait for a response
+    pub fn set_request_timeout(&mut self, timeout: Duration) {
+        self.request_timeout = timeout;
+    }
+
+    /// Whether `initialize` has completed
+    pub fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+
+    /// Capabilities announced by the server, `Null` before initialization
+    pub fn server_capabilities(&self) -> &Value {
+        &self.server_capabilities
+    }
+
+    /// Token types and modifiers used to interpret semantic tokens
+    pub fn semantic_token_legend(&self) -> (Vec<String>, Vec<String>) {
+        let legend = &self.server_capabilities["semanticTokensProvider"]["legend"];
+        let strings = |key: &str| {
+            legend[key]
+                .as_array()
+                .map(|values| values.iter().filter_map(Value::as_str).map(str::to_string).collect())
+                .unwrap_or_default()
+        };
+        (strings("tokenTypes"), strings("tokenModifiers"))
+    }
+
+    /// Progress reported by the server, including finished work
+    pub fn progress(&self) -> Vec<WorkDoneProgress> {
+        let state = self.server_state.lock().unwrap();
+        let mut progress: Vec<WorkDoneProgress> = state.progress.values().cloned().collect();
+        progress.sort_by(|a, b| a.token.cmp(&b.token));
+        progress
+    }
+
+    /// Take the `workspace/applyEdit` edits received without a handler
+    pub fn take_workspace_edits(&mut self) -> Vec<WorkspaceEdit> {
+        std::mem::take(&mut self.server_state.lock().unwrap().workspace_edits)
+    }
+
+    /// Apply server-initiated workspace edits through `handler` instead of queueing them
+    pub fn set_apply_edit_handler(&mut self, handler: impl FnMut(&WorkspaceEdit) -> Result<(), String> + Send + 'static) {
+        self.server_state.lock().unwrap().apply_edit_handler = Some(Box::new(handler));
+    }
+
+    /// Execute a command, usually one attached to a code action
+    ///
+    /// Edits the server sends back while running the command arrive as
+    /// `workspace/applyEdit` requests.
+    pub async fn execute_command(&mut self, command: &LspCommand) -> Result<Value, LanguageServiceError> {
+        let params = json!({
+            "command": command.command,
+            "arguments": command.arguments
+        });
+
+        self.send_request("workspace/executeCommand", params).await
+    }
+
+    /// Start the language server process
+    async fn start_server(&mut self, workspace_root: &Path) -> Result<(), LanguageServiceError> {
+        let mut process = Command::new(&self.config.command)
+            .args(&self.config.args)
+            .current_dir(workspace_root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| LanguageServiceError::Other(format!("Failed to start {}: {}", self.config.command, e)))?;
+
+        let stdin = process.stdin.take().unwrap();
+        let stdout = process.stdout.take().unwrap();
+        let stderr = process.stderr.take().unwrap();
+        self.start_message_handler(stdin, stdout, stderr);
+
+        self.process = Some(process);
+        Ok(())
+    }
+
+    /// Start the writer, reader and stderr threads
+    fn start_message_handler(&mut self, mut stdin: std::process::ChildStdin, stdout: std::process::ChildStdout, stderr: std::process::ChildStderr) {
+        let (tx, mut rx) = mpsc::unbounded_channel::<Value>();
+        self.message_tx = Some(tx.clone());
+        self.server_state.lock().unwrap().running = true;
+
+        std::thread::spawn(move || {
+            while let Some(message) = rx.blocking_recv() {
+                if lsp_integration::write_message(&mut stdin, &message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let language = self.config.language.clone();
+        let pending_requests = self.pending_requests.clone();
+        let server_state = self.server_state.clone();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            while let Ok(Some(message)) = lsp_integration::read_message(&mut reader) {
+                dispatch_message(&language, message, &pending_requests, &server_state, &tx);
+            }
+
+            // The server is gone; nothing will answer the requests still waiting
+            server_state.lock().unwrap().running = false;
+            for (_, pending) in pending_requests.lock().unwrap().drain() {
+                let _ = pending.sender.send(Err(LanguageServiceError::ServerUnresponsive));
+            }
+        });
+
+        let _language = self.config.language.clone();
+        std::thread::spawn(move || {
+            // Drain stderr so the server never blocks on a full pipe
+            for _line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                crate::log_debug!("{} language server: {}", _language, _line);
+            }
+        });
+    }
+
+    /// Send LSP request
+    async fn send_request(&mut self, method: &str, params: Value) -> Result<Value, LanguageServiceError> {
+        if !self.initialized {
+            return Err(LanguageServiceError::NotInitialized);
+        }
+
+        self.request(method, params).await
+    }
+
+    /// Send a request and wait for its response, without checking initialization
+    async fn request(&mut self, method: &str, params: Value) -> Result<Value, LanguageServiceError> {
+        let msg_tx = self.message_tx.clone().ok_or(LanguageServiceError::NotInitialized)?;
+
+        let request_id = self.request_counter;
+        self.request_counter += 1;
+
+        let (tx, rx) = oneshot::channel();
+        self.pending_requests.lock().unwrap().insert(request_id, PendingLspRequest {
+            method: method.to_string(),
+            sender: tx,
+        });
+
+        // The reader clears `running` before failing pending requests, so a
+        // request registered after that would otherwise wait for the timeout
+        if !self.server_state.lock().unwrap().running {
+            self.pending_requests.lock().unwrap().remove(&request_id);
+            return Err(LanguageServiceError::ServerUnresponsive);
+        }
+
+        let mut message = json!({
+            "jsonrpc": "2.0",
+            "id": request_id,
+            "method": method
+        });
+        if !params.is_null() {
+            message["params"] = params;
+        }
+
+        if msg_tx.send(message).is_err() {
+            self.pending_requests.lock().unwrap().remove(&request_id);
+            return Err(LanguageServiceError::Communication("Failed to send message".to_string()));
+        }
+
+        match tokio::time::timeout(self.request_timeout, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(LanguageServiceError::Communication("Request cancelled".to_string())),
+            Err(_) => {
+                self.pending_requests.lock().unwrap().remove(&request_id);
+                let _ = self.send_notification("$/cancelRequest", json!({ "id": request_id })).await;
+                Err(LanguageServiceError::Timeout)
+            }
+        }
+    }
+
+    /// Send LSP notification
+    async fn send_notification(&mut self, method: &str, params: Value) -> Result<(), LanguageServiceError> {
+        let msg_tx = self.message_tx.as_ref().ok_or(LanguageServiceError::NotInitialized)?;
+
+        let mut message = json!({
+            "jsonrpc": "2.0",
+            "method": method
+        });
+        if !params.is_null() {
+            message["params"] = params;
+        }
+
+        msg_tx.send(message)
+            .map_err(|_| LanguageServiceError::Communication("Failed to send notification".to_string()))
+    }
+
+    /// Wait briefly for the process to exit after `exit`, then kill it
+    async fn stop_process(&mut self) {
+        self.message_tx = None;
+
+        if let Some(mut process) = self.process.take() {
+            for _ in 0..50 {
+                if let Ok(Some(_)) = process.try_wait() {
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            let _ = process.kill();
+            let _ = process.wait();
+        }
+    }
+
+    /// Convert position to LSP format
+    fn position_to_lsp(&self, position: &Position) -> Value {
+        json!({
+            "line": position.line,
+            "character": position.character
+        })
+    }
+
+    /// Convert range to LSP format
+    fn range_to_lsp(&self, range: &Range) -> Value {
+        json!({
+            "start": self.position_to_lsp(&range.start),
+            "end": self.position_to_lsp(&range.end)
+        })
+    }
+
+    /// Convert file path to URI
+    fn path_to_uri(&self, path: &Path) -> String {
+        path_to_uri(path)
+    }
+
+    /// Parameters naming a document and a position in it
+    fn text_document_position(&self, file_path: &Path, position: &Position) -> Value {
+        json!({
+            "textDocument": {
+                "uri": self.path_to_uri(file_path)
+            },
+            "position": self.position_to_lsp(position)
+        })
+    }
+}
+
+impl Drop for LspService {
+    fn drop(&mut self) {
+        if let Some(mut process) = self.process.take() {
+            let _ = process.kill();
+            let _ = process.wait();
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LanguageService for LspService {
+    async fn initialize(&mut self, workspace_root: &Path) -> Result<(), LanguageServiceError> {
+        if self.initialized {
+            return Ok(());
+        }
+
+        let project_root = self.config.project_root(workspace_root);
+        let workspace_root = project_root.as_path();
+        self.workspace_root = Some(workspace_root.to_path_buf());
+
+        // Start language server process
+        if let Err(error) = self.start_server(workspace_root).await {
+            global_event_bus().publish(IdeEvent::LanguageServerError {
+                language: self.config.language.clone(),
+                error: error.to_string(),
+            });
+            return Err(error);
+        }
+
+        // Send initialize request
+        let mut init_params = json!({
+            "processId": std::process::id(),
+            "rootUri": self.path_to_uri(workspace_root),
+            "capabilities": {
+                "textDocument": {
+                    "completion": {
+                        "completionItem": {
+                            "snippetSupport": true,
+                            "resolveSupport": {
+                                "properties": ["documentation", "detail"]
+                            }
+                        }
+                    },
+                    "hover": {
+                        "contentFormat": ["markdown", "plaintext"]
+                    },
+                    "signatureHelp": {
+                        "signatureInformation": {
+                            "documentationFormat": ["markdown", "plaintext"],
+                            "parameterInformation": {
+                                "labelOffsetSupport": true
+                            }
+                        }
+                    },
+                    "publishDiagnostics": {
+                        "relatedInformation": true
+                    },
+                    "definition": {
+                        "linkSupport": true
+                    },
+                    "references": {},
+                    "documentSymbol": {
+                        "hierarchicalDocumentSymbolSupport": true
+                    },
+                    "codeAction": {
+                        "codeActionLiteralSupport": {
+                            "codeActionKind": {
+                                "valueSet": ["quickfix", "refactor", "refactor.extract", "refactor.inline", "refactor.rewrite"]
+                            }
+                        }
+                    },
+                    "formatting": {},
+                    "rangeFormatting": {},
+                    "rename": {},
+                    "semanticTokens": {
+                        "requests": {
+                            "full": true,
+                            "range": true
+                        }
+                    },
+                    "foldingRange": {}
+                },
+                "workspace": {
+                    "symbol": {},
+                    "workspaceFolders": true,
+                    "applyEdit": true,
+                    "executeCommand": {},
+                    "workspaceEdit": {
+                        "documentChanges": true
+                    }
+                },
+                "window": {
+                    "workDoneProgress": true
+                }
+            },
+            "workspaceFolders": [{
+                "uri": self.path_to_uri(workspace_root),
+                "name": workspace_root.file_name()
+                    .unwrap_or_else(|| std::ffi::OsStr::new("workspace"))
+                    .to_string_lossy()
+            }]
+        });
+
+        if let Some(options) = &self.config.initialization_options {
+            init_params["initializationOptions"] = options.clone();
+        }
+
+        let response = match self.request("initialize", init_params).await {
+            Ok(response) => response,
+            Err(error) => {
+                self.stop_process().await;
+                global_event_bus().publish(IdeEvent::LanguageServerError {
+                    language: self.config.language.clone(),
+                    error: error.to_string(),
+                });
+                return Err(error);
+            }
+        };
+        self.server_capabilities = response.get("capabilities").cloned().unwrap_or(Value::Null);
+
+        // Send initialized notification
+        self.send_notification("initialized", json!({})).await?;
+
+        self.initialized = true;
+        global_event_bus().publish(IdeEvent::LanguageServerStarted {
+            language: self.config.language.clone(),
+            server_id: self.server_id.clone(),
+        });
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> Result<(), LanguageServiceError> {
+        if !self.initialized {
+            return Ok(());
+        }
+
+        // Send shutdown request, then exit regardless of the answer
+        let result = self.request("shutdown", Value::Null).await;
+        let _ = self.send_notification("exit", Value::Null).await;
+        self.stop_process().await;
+
+        self.initialized = false;
+        self.workspace_root = None;
+        self.document_versions.clear();
+        self.server_capabilities = Value::Null;
+        global_event_bus().publish(IdeEvent::LanguageServerStopped {
+            language: self.config.language.clone(),
+            server_id: self.server_id.clone(),
+        });
+
+        result.map(|_| ())
+    }
+
+    fn supports_file(&self, file_path: &Path) -> bool {
+        self.config.handles(file_path)
+    }
+
+    fn supported_extensions(&self) -> Vec<String> {
+        self.config.extensions.clone()
+    }
+
+    async fn open_document(&mut self, file_path: &Path, content: &str) -> Result<(), LanguageServiceError> {
+        let params = json!({
+            "textDocument": {
+                "uri": self.path_to_uri(file_path),
+                "languageId": self.config.language_id(file_path),
+                "version": 1,
+                "text": content
+            }
+        });
+
+        self.send_notification("textDocument/didOpen", params).await?;
+        self.document_versions.insert(file_path.to_path_buf(), 1);
+
+        Ok(())
+    }
+
+    async fn update_document(&mut self, file_path: &Path, content: &str, version: u64) -> Result<(), LanguageServiceError> {
+        let params = json!({
+            "textDocument": {
+                "uri": self.path_to_uri(file_path),
+                "version": version
+            },
+            "contentChanges": [{
+                "text": content
+            }]
+        });
+
+        self.send_notification("textDocument/didChange", params).await?;
+        self.document_versions.insert(file_path.to_path_buf(), version);
+
+        Ok(())
+    }
+
+    async fn close_document(&mut self, file_path: &Path) -> Result<(), LanguageServiceError> {
+        let params = json!({
+            "textDocument": {
+                "uri": self.path_to_uri(file_path)
+            }
+        });
+
+        self.send_notification("textDocument/didClose", params).await?;
+        self.document_versions.remove(file_path);
+
+        Ok(())
+    }
+
+    async fn completion(&mut self, file_path: &Path, position: Position) -> Result<Vec<CompletionItem>, LanguageServiceError> {
+        let params = self.text_document_position(file_path, &position);
+        let response = self.send_request("textDocument/completion", params).await?;
+        Ok(parse_completion_items(&response))
+    }
+
+    async fn hover(&mut self, file_path: &Path, position: Position) -> Result<Option<HoverInfo>, LanguageServiceError> {
+        let params = self.text_document_position(file_path, &position);
+        let response = self.send_request("textDocument/hover", params).await?;
+        Ok(parse_hover(&response))
+    }
+
+    async fn goto_definition(&mut self, file_path: &Path, position: Position) -> Result<Vec<Location>, LanguageServiceError> {
+        let params = self.text_document_position(file_path, &position);
+        let response = self.send_request("textDocument/definition", params).await?;
+        Ok(parse_locations(&response))
+    }
+
+    async fn find_references(&mut self, file_path: &Path, position: Position) -> Result<Vec<Location>, LanguageServiceError> {
+        let mut params = self.text_document_position(file_path, &position);
+        params["context"] = json!({
+            "includeDeclaration": true
+        });
+
+        let response = self.send_request("textDocument/references", params).await?;
+        Ok(parse_locations(&response))
+    }
+
+    async fn diagnostics(&mut self, file_path: &Path) -> Result<Vec<Diagnostic>, LanguageServiceError> {
+        // Servers push diagnostics with textDocument/publishDiagnostics
+        // notifications; return the latest set received for the file
+        let state = self.server_state.lock().unwrap();
+        Ok(state.diagnostics.get(file_path).cloned().unwrap_or_default())
+    }
+
+    async fn code_actions(&mut self, file_path: &Path, range: Range) -> Result<Vec<CodeAction>, LanguageServiceError> {
+        let diagnostics: Vec<Value> = {
+            let state = self.server_state.lock().unwrap();
+            state.diagnostics.get(file_path)
+                .map(|diagnostics| {
+                    diagnostics.iter()
+                        .filter(|diagnostic| ranges_overlap(&diagnostic.range, &range))
+                        .map(diagnostic_to_lsp)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let params = json!({
+            "textDocument": {
+                "uri": self.path_to_uri(file_path)
+            },
+            "range": self.range_to_lsp(&range),
+            "context": {
+                "diagnostics": diagnostics
+            }
+        });
+
+        let response = self.send_request("textDocument/codeAction", params).await?;
+        Ok(parse_code_actions(&response))
+    }
+
+    async fn format_document(&mut self, file_path: &Path) -> Result<Vec<TextEdit>, LanguageServiceError> {
+        let params = json!({
+            "textDocument": {
+                "uri": self.path_to_uri(file_path)
+            },
+            "options": {
+                "tabSize": 4,
+                "insertSpaces": true
+            }
+        });
+
+        let response = self.send_request("textDocument/formatting", params).await?;
+        Ok(parse_text_edits(&response))
+    }
+
+    async fn format_range(&mut self, file_path: &Path, range: Range) -> Result<Vec<TextEdit>, LanguageServiceError> {
+        let params = json!({
+            "textDocument": {
+                "uri": self.path_to_uri(file_path)
+            },
+            "range": self.range_to_lsp(&range),
+            "options": {
+                "tabSize": 4,
+                "insertSpaces": true
+            }
+        });
+
+        let response = self.send_request("textDocument/rangeFormatting", params).await?;
+        Ok(parse_text_edits(&response))
+    }
+
+    async fn rename(&mut self, file_path: &Path, position: Position, new_name: &str) -> Result<HashMap<PathBuf, Vec<TextEdit>>, LanguageServiceError> {
+        let mut params = self.text_document_position(file_path, &position);
+        params["newName"] = json!(new_name);
+
+        let response = self.send_request("textDocument/rename", params).await?;
+        Ok(parse_workspace_edit(&response).changes)
+    }
+
+    async fn document_symbols(&mut self, file_path: &Path) -> Result<Vec<DocumentSymbol>, LanguageServiceError> {
+        let params = json!({
+            "textDocument": {
+                "uri": self.path_to_uri(file_path)
+            }
+        });
+
+        let response = self.send_request("textDocument/documentSymbol", params).await?;
+        Ok(parse_document_symbols(&response))
+    }
+
+    async fn workspace_symbols(&mut self, query: &str) -> Result<Vec<WorkspaceSymbol>, LanguageServiceError> {
+        let params = json!({
+            "query": query
+        });
+
+        let response = self.send_request("workspace/symbol", params).await?;
+        Ok(parse_workspace_symbols(&response))
+    }
+
+    async fn signature_help(&mut self, file_path: &Path, position: Position) -> Result<Option<SignatureHelp>, LanguageServiceError> {
+        let params = self.text_document_position(file_path, &position);
+        let response = self.send_request("textDocument/signatureHelp", params).await?;
+        Ok(parse_signature_help(&response))
+    }
+
+    async fn semantic_tokens(&mut self, file_path: &Path) -> Result<Vec<SemanticToken>, LanguageServiceError> {
+        let params = json!({
+            "textDocument": {
+                "uri": self.path_to_uri(file_path)
+            }
+        });
+
+        let response = self.send_request("textDocument/semanticTokens/full", params).await?;
+        Ok(parse_semantic_tokens(&response))
+    }
+
+    async fn folding_ranges(&mut self, file_path: &Path) -> Result<Vec<FoldingRange>, LanguageServiceError> {
+        let params = json!({
+            "textDocument": {
+                "uri": self.path_to_uri(file_path)
+            }
+        });
+
+        let response = self.send_request("textDocument/foldingRange", params).await?;
+        Ok(parse_folding_ranges(&response))
+    }
+}
+
+/// Route one message read from the server
+fn dispatch_message(
+    language: &str,
+    message: Value,
+    pending_requests: &Mutex<HashMap<u64, PendingLspRequest>>,
+    server_state: &Mutex<ServerState>,
+    tx: &mpsc::UnboundedSender<Value>,
+) {
+    let method = message.get("method").and_then(Value::as_str);
+    let id = message.get("id").cloned();
+
+    match (method, id) {
+        (Some(method), Some(id)) => {
+            let reply = match handle_server_request(method, &message["params"], server_state) {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err((code, error)) => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": code, "message": error }
+                }),
+            };
+            let _ = tx.send(reply);
+        }
+        (Some(method), None) => handle_notification(language, method, &message["params"], server_state),
+        (None, Some(id)) => {
+            let Some(pending) = id.as_u64().and_then(|id| pending_requests.lock().unwrap().remove(&id)) else {
+                return;
+            };
+            let result = match message.get("error") {
+                Some(error) => Err(response_error(&pending.method, error)),
+                None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+            };
+            let _ = pending.sender.send(result);
+        }
+        (None, None) => {}
+    }
+}
+
+/// Map a JSON-RPC error response to a language service error
+fn response_error(method: &str, error: &Value) -> LanguageServiceError {
+    let code = error.get("code").and_then(Value::as_i64).unwrap_or(0);
+    let message = error.get("message").and_then(Value::as_str).unwrap_or("unknown error");
+
+    match code {
+        // InvalidRequest, InvalidParams
+        -32600 | -32602 => LanguageServiceError::InvalidRequest(format!("{}: {}", method, message)),
+        _ => LanguageServiceError::Other(format!("{} failed: {}", method, message)),
+    }
+}
+
+/// Answer a request sent by the server
+fn handle_server_request(method: &str, params: &Value, server_state: &Mutex<ServerState>) -> Result<Value, (i64, String)> {
+    match method {
+        "workspace/applyEdit" => {
+            let edit = parse_workspace_edit(&params["edit"]);
+            let mut state = server_state.lock().unwrap();
+            let outcome = match state.apply_edit_handler.as_mut() {
+                Some(handler) => handler(&edit),
+                None => {
+                    state.workspace_edits.push(edit);
+                    Ok(())
+                }
+            };
+            Ok(match outcome {
+                Ok(()) => json!({ "applied": true }),
+                Err(reason) => json!({ "applied": false, "failureReason": reason }),
+            })
+        }
+        "window/workDoneProgress/create" | "client/registerCapability" | "client/unregisterCapability" => Ok(Value::Null),
+        "workspace/configuration" => {
+            let state = server_state.lock().unwrap();
+            let items = params["items"].as_array().map(Vec::as_slice).unwrap_or_default();
+            Ok(items.iter().map(|item| settings_section(&state.settings, item["section"].as_str())).collect())
+        }
+        _ => Err((METHOD_NOT_FOUND, format!("Unhandled method {}", method))),
+    }
+}
+
+/// Record a notification sent by the server
+fn handle_notification(_language: &str, method: &str, params: &Value, server_state: &Mutex<ServerState>) {
+    match method {
+        "textDocument/publishDiagnostics" => {
+            let Some(uri) = params["uri"].as_str() else {
+                return;
+            };
+            let path = uri_to_path(uri);
+            let diagnostics: Vec<Diagnostic> = params["diagnostics"]
+                .as_array()
+                .map(|items| items.iter().map(parse_diagnostic).collect())
+                .unwrap_or_default();
+            let diagnostics_count = diagnostics.len();
+            server_state.lock().unwrap().diagnostics.insert(path.clone(), diagnostics);
+
+            global_event_bus().publish(IdeEvent::DiagnosticsUpdated { path, diagnostics_count });
+        }
+        "$/progress" => {
+            let token = match &params["token"] {
+                Value::String(token) => token.clone(),
+                token => token.to_string(),
+            };
+            let value = &params["value"];
+            let mut state = server_state.lock().unwrap();
+            let progress = state.progress.entry(token.clone()).or_insert_with(|| WorkDoneProgress {
+                token,
+                ..Default::default()
+            });
+
+            match value["kind"].as_str() {
+                Some("begin") => {
+                    progress.title = value["title"].as_str().unwrap_or_default().to_string();
+                    progress.done = false;
+                }
+                Some("end") => progress.done = true,
+                _ => {}
+            }
+            if let Some(message) = value["message"].as_str() {
+                progress.message = Some(message.to_string());
+            }
+            if let Some(percentage) = value["percentage"].as_u64() {
+                progress.percentage = Some(percentage as u32);
+            }
+        }
+        "window/logMessage" | "window/showMessage" => {
+            crate::log_debug!("{} language server: {}", _language, params["message"].as_str().unwrap_or_default());
+        }
+        _ => {}
+    }
+}
+
+/// Settings under a dotted `section`, `null` when absent
+fn settings_section(settings: &Value, section: Option<&str>) -> Value {
+    let Some(section) = section else {
+        return settings.clone();
+    };
+
+    section.split('.')
+        .try_fold(settings, |value, key| value.get(key))
+        .cloned()
+        .unwrap_or(Value::Null)
+}
+
+/// Convert a file path to a `file://` URI
+pub fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' | b':' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+/// Convert a `file://` URI back to a file path
+pub fn uri_to_path(uri: &str) -> PathBuf {
+    let encoded = uri.strip_prefix("file://").unwrap_or(uri);
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| encoded.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    let path = String::from_utf8_lossy(&decoded).into_owned();
+    // `/C:/src` is a Windows drive path
+    let path = match path.as_bytes() {
+        [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => path[1..].to_string(),
+        _ => path,
+    };
+    PathBuf::from(path)
+}
+
+fn parse_range(value: &Value) -> Range {
+    LspClient::parse_range(value)
+}
+
+fn ranges_overlap(a: &Range, b: &Range) -> bool {
+    let start = |range: &Range| (range.start.line, range.start.character);
+    let end = |range: &Range| (range.end.line, range.end.character);
+    start(a) <= end(b) && start(b) <= end(a)
+}
+
+/// Text of a `string | MarkupContent | MarkedString` value
+fn markup_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Object(object) => {
+            let text = object.get("value")?.as_str()?;
+            Some(match object.get("language").and_then(Value::as_str) {
+                Some(language) => format!("```{}\n{}\n```", language, text),
+                None => text.to_string(),
+            })
+        }
+        _ => None,
+    }
+}
+
+fn optional_string(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn parse_diagnostic(value: &Value) -> Diagnostic {
+    let code = match value.get("code") {
+        Some(Value::String(code)) => Some(code.clone()),
+        Some(Value::Number(code)) => Some(code.to_string()),
+        _ => None,
+    };
+    let related_information = value.get("relatedInformation").and_then(Value::as_array).map(|items| {
+        items.iter()
+            .map(|item| DiagnosticRelatedInformation {
+                location: lsp_integration::Location {
+                    uri: item["location"]["uri"].as_str().unwrap_or_default().to_string(),
+                    range: parse_range(&item["location"]["range"]),
+                },
+                message: item["message"].as_str().unwrap_or_default().to_string(),
+            })
+            .collect()
+    });
+
+    Diagnostic {
+        range: parse_range(&value["range"]),
+        severity: LspClient::parse_diagnostic_severity(value.get("severity")),
+        code,
+        source: optional_string(value, "source"),
+        message: value["message"].as_str().unwrap_or_default().to_string(),
+        related_information,
+    }
+}
+
+fn diagnostic_to_lsp(diagnostic: &Diagnostic) -> Value {
+    let mut value = json!({
+        "range": diagnostic.range,
+        "message": diagnostic.message
+    });
+    if let Some(severity) = &diagnostic.severity {
+        value["severity"] = json!(severity.clone() as u8);
+    }
+    if let Some(code) = &diagnostic.code {
+        value["code"] = json!(code);
+    }
+    if let Some(source) = &diagnostic.source {
+        value["source"] = json!(source);
+    }
+    value
+}
+
+fn parse_completion_items(response: &Value) -> Vec<CompletionItem> {
+    let items = match response {
+        Value::Array(items) => items,
+        _ => match response["items"].as_array() {
+            Some(items) => items,
+            None => return Vec::new(),
+        },
+    };
+
+    items.iter()
+        .filter_map(|item| {
+            Some(CompletionItem {
+                label: item["label"].as_str()?.to_string(),
+                kind: completion_kind(item["kind"].as_u64().unwrap_or(1)),
+                detail: optional_string(item, "detail"),
+                documentation: item.get("documentation").and_then(markup_text),
+                insert_text: optional_string(item, "insertText")
+                    .or_else(|| optional_string(&item["textEdit"], "newText")),
+                filter_text: optional_string(item, "filterText"),
+                sort_text: optional_string(item, "sortText"),
+                additional_text_edits: parse_text_edits(&item["additionalTextEdits"]),
+            })
+        })
+        .collect()
+}
+
+fn completion_kind(kind: u64) -> CompletionKind {
+    match kind {
+        2 => CompletionKind::Method,
+        3 => CompletionKind::Function,
+        4 => CompletionKind::Constructor,
+        5 => CompletionKind::Field,
+        6 => CompletionKind::Variable,
+        7 => CompletionKind::Class,
+        8 => CompletionKind::Interface,
+        9 => CompletionKind::Module,
+        10 => CompletionKind::Property,
+        11 => CompletionKind::Unit,
+        12 => CompletionKind::Value,
+        13 => CompletionKind::Enum,
+        14 => CompletionKind::Keyword,
+        15 => CompletionKind::Snippet,
+        16 => CompletionKind::Color,
+        17 => CompletionKind::File,
+        18 => CompletionKind::Reference,
+        19 => CompletionKind::Folder,
+        20 => CompletionKind::EnumMember,
+        21 => CompletionKind::Constant,
+        22 => CompletionKind::Struct,
+        23 => CompletionKind::Event,
+        24 => CompletionKind::Operator,
+        25 => CompletionKind::TypeParameter,
+        _ => CompletionKind::Text,
+    }
+}
+
+fn parse_hover(response: &Value) -> Option<HoverInfo> {
+    let contents = match response.get("contents")? {
+        Value::Array(items) => items.iter().filter_map(markup_text).collect::<Vec<_>>().join("\n\n"),
+        contents => markup_text(contents)?,
+    };
+
+    Some(HoverInfo {
+        contents,
+        range: response.get("range").map(parse_range),
+    })
+}
+
+/// Parse `Location | Location[] | LocationLink[]`
+fn parse_locations(response: &Value) -> Vec<Location> {
+    let items = match response {
+        Value::Array(items) => items.iter().collect(),
+        Value::Null => Vec::new(),
+        location => vec![location],
+    };
+
+    items.into_iter().filter_map(parse_location).collect()
+}
+
+fn parse_location(value: &Value) -> Option<Location> {
+    if let Some(uri) = value.get("targetUri").and_then(Value::as_str) {
+        let range = value.get("targetSelectionRange").or_else(|| value.get("targetRange"))?;
+        return Some(Location {
+            uri: uri_to_path(uri),
+            range: parse_range(range),
+        });
+    }
+
+    Some(Location {
+        uri: uri_to_path(value.get("uri")?.as_str()?),
+        range: parse_range(value.get("range")?),
+    })
+}
+
+fn parse_text_edits(response: &Value) -> Vec<TextEdit> {
+    response.as_array()
+        .map(|edits| {
+            edits.iter()
+                .filter_map(|edit| {
+                    Some(TextEdit {
+                        range: parse_range(edit.get("range")?),
+                        new_text: edit.get("newText")?.as_str()?.to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse a workspace edit from either `changes` or `documentChanges`
+///
+/// Resource operations (create, rename and delete) are not represented in
+/// [`WorkspaceEdit`] and are skipped.
+fn parse_workspace_edit(response: &Value) -> WorkspaceEdit {
+    let mut changes: HashMap<PathBuf, Vec<TextEdit>> = HashMap::new();
+
+    if let Some(document_changes) = response.get("documentChanges").and_then(Value::as_array) {
+        for change in document_changes {
+            if let Some(uri) = change["textDocument"]["uri"].as_str() {
+                changes.entry(uri_to_path(uri)).or_default().extend(parse_text_edits(&change["edits"]));
+            }
+        }
+    } else if let Some(uri_changes) = response.get("changes").and_then(Value::as_object) {
+        for (uri, edits) in uri_changes {
+            changes.entry(uri_to_path(uri)).or_default().extend(parse_text_edits(edits));
+        }
+    }
+
+    WorkspaceEdit { changes }
+}
+
+fn parse_command(value: &Value) -> Option<LspCommand> {
+    Some(LspCommand {
+        title: value["title"].as_str().unwrap_or_default().to_string(),
+        command: value.get("command")?.as_str()?.to_string(),
+        arguments: value["arguments"].as_array().cloned().unwrap_or_default(),
+    })
+}
+
+fn code_action_kind(kind: &str) -> CodeActionKind {
+    match kind {
+        kind if kind.starts_with("quickfix") => CodeActionKind::QuickFix,
+        kind if kind.starts_with("refactor.extract") => CodeActionKind::RefactorExtract,
+        kind if kind.starts_with("refactor.inline") => CodeActionKind::RefactorInline,
+        kind if kind.starts_with("refactor.rewrite") => CodeActionKind::RefactorRewrite,
+        kind if kind.starts_with("source.organizeImports") => CodeActionKind::SourceOrganizeImports,
+        kind if kind.starts_with("source.fixAll") => CodeActionKind::SourceFixAll,
+        kind if kind.starts_with("source") => CodeActionKind::Source,
+        _ => CodeActionKind::Refactor,
+    }
+}
+
+/// Parse `(Command | CodeAction)[]`
+fn parse_code_actions(response: &Value) -> Vec<CodeAction> {
+    let Some(items) = response.as_array() else {
+        return Vec::new();
+    };
+
+    items.iter()
+        .filter_map(|item| {
+            let title = item["title"].as_str()?.to_string();
+
+            // A bare Command has a string `command`; a CodeAction nests it
+            if item["command"].is_string() {
+                return Some(CodeAction {
+                    title,
+                    kind: CodeActionKind::Refactor,
+                    diagnostics: Vec::new(),
+                    edit: None,
+                    command: parse_command(item),
+                });
+            }
+
+            Some(CodeAction {
+                title,
+                kind: code_action_kind(item["kind"].as_str().unwrap_or_default()),
+                diagnostics: item["diagnostics"]
+                    .as_array()
+                    .map(|diagnostics| diagnostics.iter().map(parse_diagnostic).collect())
+                    .unwrap_or_default(),
+                edit: item.get("edit").map(parse_workspace_edit),
+                command: item.get("command").and_then(parse_command),
+            })
+        })
+        .collect()
+}
+
+fn symbol_kind(kind: u64) -> SymbolKind {
+    match kind {
+        2 => SymbolKind::Module,
+        3 => SymbolKind::Namespace,
+        4 => SymbolKind::Package,
+        5 => SymbolKind::Class,
+        6 => SymbolKind::Method,
+        7 => SymbolKind::Property,
+        8 => SymbolKind::Field,
+        9 => SymbolKind::Constructor,
+        10 => SymbolKind::Enum,
+        11 => SymbolKind::Interface,
+        12 => SymbolKind::Function,
+        13 => SymbolKind::Variable,
+        14 => SymbolKind::Constant,
+        15 => SymbolKind::String,
+        16 => SymbolKind::Number,
+        17 => SymbolKind::Boolean,
+        18 => SymbolKind::Array,
+        19 => SymbolKind::Object,
+        20 => SymbolKind::Key,
+        21 => SymbolKind::Null,
+        22 => SymbolKind::EnumMember,
+        23 => SymbolKind::Struct,
+        24 => SymbolKind::Event,
+        25 => SymbolKind::Operator,
+        26 => SymbolKind::TypeParameter,
+        _ => SymbolKind::File,
+    }
+}
+
+/// Parse `DocumentSymbol[]`, or flat `SymbolInformation[]` from older servers
+fn parse_document_symbols(response: &Value) -> Vec<DocumentSymbol> {
+    response.as_array()
+        .map(|symbols| symbols.iter().filter_map(parse_document_symbol).collect())
+        .unwrap_or_default()
+}
+
+fn parse_document_symbol(value: &Value) -> Option<DocumentSymbol> {
+    let name = value["name"].as_str()?.to_string();
+    let kind = symbol_kind(value["kind"].as_u64().unwrap_or(0));
+
+    if let Some(location) = value.get("location") {
+        let range = parse_range(&location["range"]);
+        return Some(DocumentSymbol {
+            name,
+            detail: optional_string(value, "containerName"),
+            kind,
+            range: range.clone(),
+            selection_range: range,
+            children: Vec::new(),
+        });
+    }
+
+    Some(DocumentSymbol {
+        name,
+        detail: optional_string(value, "detail"),
+        kind,
+        range: parse_range(&value["range"]),
+        selection_range: parse_range(&value["selectionRange"]),
+        children: parse_document_symbols(&value["children"]),
+    })
+}
+
+fn parse_workspace_symbols(response: &Value) -> Vec<WorkspaceSymbol> {
+    let Some(symbols) = response.as_array() else {
+        return Vec::new();
+    };
+
+    symbols.iter()
+        .filter_map(|symbol| {
+            let location = &symbol["location"];
+            Some(WorkspaceSymbol {
+                name: symbol["name"].as_str()?.to_string(),
+                kind: symbol_kind(symbol["kind"].as_u64().unwrap_or(0)),
+                location: Location {
+                    uri: uri_to_path(location["uri"].as_str()?),
+                    // `WorkspaceSymbol.location` may omit the range
+                    range: parse_range(&location["range"]),
+                },
+                container_name: optional_string(symbol, "containerName"),
+            })
+        })
+        .collect()
+}
+
+fn parse_signature_help(response: &Value) -> Option<SignatureHelp> {
+    let signatures: Vec<SignatureInformation> = response.get("signatures")?
+        .as_array()?
+        .iter()
+        .filter_map(|signature| {
+            let label = signature["label"].as_str()?.to_string();
+            let parameters = signature["parameters"]
+                .as_array()
+                .map(|parameters| {
+                    parameters.iter()
+                        .map(|parameter| ParameterInformation {
+                            label: parameter_label(&label, &parameter["label"]),
+                            documentation: parameter.get("documentation").and_then(markup_text),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            Some(SignatureInformation {
+                documentation: signature.get("documentation").and_then(markup_text),
+                label,
+                parameters,
+            })
+        })
+        .collect();
+
+    Some(SignatureHelp {
+        signatures,
+        active_signature: response["activeSignature"].as_u64().map(|index| index as usize),
+        active_parameter: response["activeParameter"].as_u64().map(|index| index as usize),
+    })
+}
+
+/// A parameter label is either a string or UTF-16 offsets into the signature label
+fn parameter_label(signature: &str, label: &Value) -> String {
+    if let Some(label) = label.as_str() {
+        return label.to_string();
+    }
+
+    let offset = |index: usize| label[index].as_u64().unwrap_or(0) as usize;
+    let utf16: Vec<u16> = signature.encode_utf16().collect();
+    let (start, end) = (offset(0).min(utf16.len()), offset(1).min(utf16.len()));
+    String::from_utf16_lossy(&utf16[start..end.max(start)])
+}
+
+/// Decode the relative `data` array of a semantic tokens response
+fn parse_semantic_tokens(response: &Value) -> Vec<SemanticToken> {
+    let Some(data) = response["data"].as_array() else {
+        return Vec::new();
+    };
+    let data: Vec<u32> = data.iter().map(|value| value.as_u64().unwrap_or(0) as u32).collect();
+
+    let mut tokens = Vec::with_capacity(data.len() / 5);
+    let (mut line, mut start_char) = (0, 0);
+    for chunk in data.chunks_exact(5) {
+        if chunk[0] > 0 {
+            line += chunk[0];
+            start_char = 0;
+        }
+        start_char += chunk[1];
+        tokens.push(SemanticToken {
+            line,
+            start_char,
+            length: chunk[2],
+            token_type: chunk[3],
+            token_modifiers: chunk[4],
+        });
+    }
+    tokens
+}
+
+fn parse_folding_ranges(response: &Value) -> Vec<FoldingRange> {
+    let Some(ranges) = response.as_array() else {
+        return Vec::new();
+    };
+
+    ranges.iter()
+        .filter_map(|range| {
+            let number = |key: &str| range.get(key).and_then(Value::as_u64).map(|n| n as u32);
+            Some(FoldingRange {
+                start_line: number("startLine")?,
+                start_character: number("startCharacter"),
+                end_line: number("endLine")?,
+                end_character: number("endCharacter"),
+                kind: match range["kind"].as_str() {
+                    Some("comment") => Some(FoldingRangeKind::Comment),
+                    Some("imports") => Some(FoldingRangeKind::Imports),
+                    Some("region") => Some(FoldingRangeKind::Region),
+                    _ => None,
+                },
+            })
+        })
+        .collect()
+}
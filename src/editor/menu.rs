@@ -7,6 +7,8 @@
 use egui::*;
 use crate::editor::{toolbar::IdeToolbar, output_panel::OutputPanel, ai_panel::AiPanel, component_registry::ComponentRegistry};
 use crate::editor::actions;
+use crate::editor::packaging::{PackageRegistry, PACKAGE_EXTENSION};
+use crate::rcl::component_registry::ComponentRegistry as RclComponentRegistry;
 
 /// Main IDE menu system containing all major IDE functionality
 /// 
@@ -28,6 +30,10 @@ pub struct IdeMenu {
     pub ai_panel: AiPanel,
     /// Registry for managing installed and available components
     pub registry: ComponentRegistry,
+    /// Local directory of installed component packages
+    pub packages: PackageRegistry,
+    /// Component metadata contributed by installed packages, shown in the palette
+    pub component_library: RclComponentRegistry,
     /// Show settings panel flag
     pub show_settings: bool,
 }
@@ -35,11 +41,30 @@ pub struct IdeMenu {
 impl IdeMenu {
     /// Creates a new IDE menu with all subsystems initialized
     pub fn new() -> Self {
+        let mut output_panel = OutputPanel::new();
+        let mut registry = ComponentRegistry::new();
+        let packages = PackageRegistry::new(PackageRegistry::default_location());
+        let mut component_library = RclComponentRegistry::new();
+
+        match packages.installed() {
+            Ok(installed) => {
+                for manifest in installed {
+                    registry.install(actions::installed_metadata(&packages, &manifest));
+                }
+                if let Err(e) = packages.register_installed(&mut component_library) {
+                    output_panel.log(&format!("❌ Failed to load component packages: {}", e));
+                }
+            }
+            Err(e) => output_panel.log(&format!("❌ Failed to load component packages: {}", e)),
+        }
+
         Self {
             toolbar: IdeToolbar::new(),
-            output_panel: OutputPanel::new(),
+            output_panel,
             ai_panel: AiPanel::new(),
-            registry: ComponentRegistry::new(),
+            registry,
+            packages,
+            component_library,
             show_settings: false,
         }
     }
@@ -84,14 +109,48 @@ impl IdeMenu {
             // Component Management Submenu
             ui.menu_button("📦 Components", |ui| {
                 if ui.button("Package Components   Ctrl+P").clicked() {
-                    actions::package_component("MyComponent", "src/rcl/ui/basic/button.rs", "packages/MyComponent.pkg", &mut self.registry);
+                    if let Some(source) = rfd::FileDialog::new()
+                        .set_title("Select component source folder")
+                        .pick_folder()
+                    {
+                        actions::package_component(&source, &source.join("packages"), &mut self.output_panel);
+                    }
+                    ui.close_menu();
                 }
                 if ui.button("Install Component   Ctrl+I").clicked() {
-                    actions::install_component("packages/MyComponent.pkg", "installed_components", &mut self.registry);
-                }
-                if ui.button("Uninstall Component   Ctrl+U").clicked() {
-                    actions::uninstall_component("MyComponent.pkg", "installed_components", &mut self.registry);
+                    if let Some(package) = rfd::FileDialog::new()
+                        .add_filter("RCL package", &[PACKAGE_EXTENSION])
+                        .pick_file()
+                    {
+                        actions::install_component(
+                            &package,
+                            &self.packages,
+                            &mut self.component_library,
+                            &mut self.registry,
+                            &mut self.output_panel,
+                        );
+                    }
+                    ui.close_menu();
                 }
+                ui.menu_button("Uninstall Component   Ctrl+U", |ui| {
+                    let mut installed = self.registry.list();
+                    installed.sort_by(|a, b| a.name.cmp(&b.name));
+                    if installed.is_empty() {
+                        ui.label("No packages installed");
+                    }
+                    for package in installed {
+                        if ui.button(format!("{} {}", package.name, package.version)).clicked() {
+                            actions::uninstall_component(
+                                &package.name,
+                                &self.packages,
+                                &mut self.component_library,
+                                &mut self.registry,
+                                &mut self.output_panel,
+                            );
+                            ui.close_menu();
+                        }
+                    }
+                });
             });
 
             // Project and Tools Submenu
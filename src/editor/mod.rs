@@ -29,6 +29,7 @@
 //! - [`code_editor`] - Advanced code editing with syntax highlighting
 //! - [`rust_analyzer`] - Rust language server integration
 //! - [`lsp_integration`] - Language Server Protocol support
+//! - [`lsp_service`] - Configurable stdio language servers
 //! - [`smart_editing`] - Intelligent code completion and refactoring
 //!
 //! ### AI Integration
@@ -169,6 +170,12 @@ pub mod code_completion;
 /// variable watching, and interactive debugging sessions.
 pub mod debugger;
 
+/// Debug Adapter Protocol client
+/// 
+/// Drives lldb-dap, codelldb and gdb through the Debug Adapter Protocol
+/// as the backend for the integrated debugger.
+pub mod dap_client;
+
 /// Code folding system for hierarchical code navigation
 /// 
 /// Provides collapsible code regions based on language syntax
@@ -195,6 +202,13 @@ pub mod git_integration;
 pub mod terminal;
 pub mod text_buffer;
 pub mod scene_store;
+
+/// Flex and grid layout solver for scene components
+/// 
+/// Resolves nested flex and grid containers, track sizing, min/max
+/// constraints, gaps, wrapping and anchors into component rects shared by
+/// the designer canvas and generated egui code.
+pub mod layout_engine;
 pub mod terminal_ansi;
 pub mod buffer_manager;
 
@@ -204,12 +218,30 @@ pub mod buffer_manager;
 /// that preserve user modifications while allowing automated code updates.
 pub mod code_generator;
 
+/// Three-way merge of regenerated code with user edits
+///
+/// Line-based diff3 between the last generated output, the user's file and the
+/// new output, plus a window for resolving conflicts.
+pub mod three_way_merge;
+
+/// AI edits as reviewable workspace diffs
+///
+/// Structured edits requested from the model, validated against the open
+/// buffers and applied per hunk after review.
+pub mod ai_edits;
+
 /// Enhanced code generation markers and rewrite system
 /// 
 /// Advanced marker-based code generation with typed markers, conditional
 /// generation, and intelligent merging capabilities.
 pub mod codegen_markers;
 
+/// Event handler stubs and runtime dispatch for designer forms
+///
+/// Creates handler methods inside a guarded section of the form source and
+/// generates the code that routes component events to them.
+pub mod form_events;
+
 /// Enhanced code generation integration
 /// 
 /// Integrates enhanced markers with the existing code generator to provide
@@ -229,6 +261,12 @@ pub mod codegen_example;
 /// custom components, language support, and integrations.
 pub mod plugin_system;
 
+/// Out-of-process plugin host
+/// 
+/// Runs plugin executables in their own processes and talks to them
+/// over a line-delimited JSON-RPC protocol on stdio.
+pub mod plugin_host;
+
 /// Component discovery and registration system
 /// 
 /// Manages available components, their metadata, and integration
@@ -283,6 +321,12 @@ pub mod lsp_integration;
 /// over different implementations (rust-analyzer, LSP clients, etc.)
 pub mod language_service;
 
+/// Configurable stdio language server service
+/// 
+/// Launches any LSP server described in `.ide-rs/language_servers.json`
+/// and exposes it through the unified language service interface.
+pub mod lsp_service;
+
 /// Enhanced LSP client with advanced IDE features
 /// 
 /// Professional LSP client with VS Code-style capabilities including
@@ -387,12 +431,11 @@ pub mod realtime_sync;
 // TODO: Fix compilation errors before enabling
 // pub mod intelligent_completion;
 
-// Advanced project templates and scaffolding
-// 
-// Project template system with wizard-based creation, pre-built templates,
-// and comprehensive scaffolding tools for rapid application development.
-// TODO: Fix compilation errors before enabling
-// pub mod project_scaffolding;
+/// Advanced project templates and scaffolding
+/// 
+/// Project template system with wizard-based creation, pre-built templates,
+/// and comprehensive scaffolding tools for rapid application development.
+pub mod project_scaffolding;
 
 // Visual debugging and inspection tools
 // 
@@ -408,26 +451,41 @@ pub mod realtime_sync;
 // TODO: Fix compilation errors before enabling
 // pub mod live_reload;
 
-// Advanced search and navigation features
-// 
-// Comprehensive search capabilities with full-text search, semantic code search,
-// symbol navigation, cross-references, and intelligent search suggestions.
-// TODO: Fix compilation errors before enabling
-// pub mod advanced_search;
+/// Advanced search and navigation features
+///
+/// Comprehensive search capabilities with full-text search, semantic code search,
+/// symbol navigation, cross-references, and intelligent search suggestions.
+pub mod advanced_search;
 
-// Advanced refactoring and code transformation tools
-// 
-// Comprehensive refactoring capabilities with automated code transformations,
-// semantic-aware refactoring, batch operations, and safety checks.
-// TODO: Fix compilation errors before enabling
-// pub mod advanced_refactoring;
+/// On-disk workspace index backing advanced search
+///
+/// Trigram and word tokenization, syn-based symbol extraction, and the
+/// compressed snapshot kept in the project's `.ide-rs` directory.
+pub mod workspace_index;
 
-// Collaborative development and team integration tools
-// 
-// Real-time collaboration features with shared editing, version control integration,
-// team communication, and synchronized development workflows.
-// TODO: Fix compilation errors before enabling
-// pub mod collaborative_development;
+/// Advanced refactoring and code transformation tools
+///
+/// Comprehensive refactoring capabilities with automated code transformations,
+/// semantic-aware refactoring, batch operations, and safety checks.
+pub mod advanced_refactoring;
+
+/// Syntax-aware rename, extract, inline and move transformations
+///
+/// Implements the operations registered by the advanced refactoring engine
+/// on top of `syn`, producing exact edits across the workspace.
+pub mod semantic_refactoring;
+
+/// Collaborative development and team integration tools
+/// 
+/// Real-time collaboration features with shared editing, version control integration,
+/// team communication, and synchronized development workflows.
+pub mod collaborative_development;
+
+/// Real-time collaborative editing protocol
+///
+/// Operational transformation of concurrent edits, the relay server that orders
+/// them and the client that keeps a `TextBuffer` in sync with a shared document.
+pub mod collaboration;
 
 /// Performance optimization and monitoring system
 ///
@@ -33,6 +33,17 @@ use egui::*;
 use std::fs::File;
 use std::io::Write;
 
+use crate::editor::build_system::{Applicability, CompilerMessage, MessageLevel, SuggestedFix};
+
+/// Action requested by clicking a diagnostic in the output panel
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiagnosticAction {
+    /// Open the file at the given 1-based line and column
+    Open { file: String, line: usize, column: usize },
+    /// Apply a compiler-suggested replacement
+    ApplyFix(SuggestedFix),
+}
+
 /// Output panel for displaying build results, logs, and diagnostic information
 /// 
 /// The OutputPanel provides a comprehensive interface for viewing and managing
@@ -71,6 +82,13 @@ pub struct OutputPanel {
     /// Maintains a persistent record of all logged messages for debugging
     /// and historical analysis. Each entry represents a single log operation.
     pub log_history: Vec<String>,
+    
+    /// Structured diagnostics from the most recent build
+    /// 
+    /// Populated from cargo's JSON output; each entry keeps its spans,
+    /// child notes and suggested replacements so it can be navigated to
+    /// and fixed from the panel.
+    pub diagnostics: Vec<CompilerMessage>,
 }
 
 impl OutputPanel {
@@ -88,6 +106,7 @@ impl OutputPanel {
             search: String::new(),
             filtered: String::new(),
             log_history: vec![],
+            diagnostics: vec![],
         }
     }
 
@@ -193,6 +212,90 @@ impl OutputPanel {
         self.apply_filter();
     }
 
+    /// Record a structured compiler diagnostic
+    /// 
+    /// Logs the diagnostic as rustc renders it and keeps the structured
+    /// form for the clickable problems list.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `message` - Diagnostic parsed from cargo's JSON output
+    pub fn add_diagnostic(&mut self, message: CompilerMessage) {
+        match &message.rendered {
+            Some(rendered) => self.log(rendered.trim_end()),
+            None => self.log(&message.message),
+        }
+        self.diagnostics.push(message);
+    }
+
+    /// Remove diagnostics of the previous build
+    pub fn clear_diagnostics(&mut self) {
+        self.diagnostics.clear();
+    }
+
+    /// Render the clickable problems list
+    /// 
+    /// Shows one row per diagnostic with its location. Clicking a row asks
+    /// the caller to open the primary span; diagnostics with compiler
+    /// suggestions get a quick-fix button per suggestion.
+    /// 
+    /// # Returns
+    /// 
+    /// The action the user requested this frame, if any
+    pub fn render_diagnostics(&mut self, ui: &mut Ui) -> Option<DiagnosticAction> {
+        if self.diagnostics.is_empty() {
+            return None;
+        }
+
+        let mut action = None;
+        let errors = self.diagnostics.iter().filter(|d| d.level == MessageLevel::Error).count();
+        let warnings = self.diagnostics.iter().filter(|d| d.level == MessageLevel::Warning).count();
+
+        CollapsingHeader::new(format!("Problems ({} errors, {} warnings)", errors, warnings))
+            .default_open(true)
+            .show(ui, |ui| {
+                ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+                    for diagnostic in &self.diagnostics {
+                        ui.horizontal(|ui| {
+                            let (icon, color) = match diagnostic.level {
+                                MessageLevel::Error => ("❌", Color32::RED),
+                                MessageLevel::Warning => ("⚠️", Color32::YELLOW),
+                                _ => ("ℹ️", Color32::LIGHT_BLUE),
+                            };
+                            ui.colored_label(color, icon);
+
+                            let location = match (&diagnostic.file, diagnostic.line, diagnostic.column) {
+                                (Some(file), Some(line), Some(column)) => format!("{}:{}:{}", file, line, column),
+                                _ => String::new(),
+                            };
+                            let code = diagnostic.code.as_ref()
+                                .map(|code| format!("[{}] ", code))
+                                .unwrap_or_default();
+                            let label = ui.selectable_label(false, format!("{} {}{}", location, code, diagnostic.message));
+                            let label = match &diagnostic.rendered {
+                                Some(rendered) => label.on_hover_text(RichText::new(rendered).monospace()),
+                                None => label,
+                            };
+                            if label.clicked() {
+                                if let (Some(file), Some(line), Some(column)) = (&diagnostic.file, diagnostic.line, diagnostic.column) {
+                                    action = Some(DiagnosticAction::Open { file: file.clone(), line, column });
+                                }
+                            }
+
+                            for fix in diagnostic.suggestions() {
+                                let icon = if fix.applicability == Applicability::MachineApplicable { "🔧" } else { "💡" };
+                                if ui.small_button(icon).on_hover_text(&fix.message).clicked() {
+                                    action = Some(DiagnosticAction::ApplyFix(fix));
+                                }
+                            }
+                        });
+                    }
+                });
+            });
+
+        action
+    }
+
     /// Display parsed errors in a dedicated panel section
     /// 
     /// Creates a dedicated error display section that shows parsed
@@ -256,6 +359,7 @@ impl OutputPanel {
         if ui.button("Clear Output").clicked() {
             self.output.clear();
             self.filtered.clear();
+            self.diagnostics.clear();
         }
         if ui.button("Export Output").clicked() {
             self.export("output.log");
@@ -1,27 +1,632 @@
 //! Packaging logic for components and libraries
+//!
+//! An RCL component package is a gzip-compressed tar archive (`.rclpkg`)
+//! containing an `rcl-package.json` manifest and the package files under
+//! `files/`. The manifest records the package name, its semver version,
+//! the RCL API version it targets, its dependencies on other packages,
+//! the component metadata and property schemas it contributes, and a
+//! SHA-256 hash for every file so archives can be verified before they
+//! are installed.
+//!
+//! Installed packages live in a local registry directory, one
+//! sub-directory per package. [`PackageRegistry`] resolves dependencies
+//! when installing, refuses to uninstall packages other packages still
+//! depend on, and keeps an RCL [`ComponentRegistry`] in sync so packaged
+//! components show up in the component palette.
+
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::io::{Read, Write};
+use std::path::{Component as PathComponent, Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::rcl::component_registry::{ComponentMetadata, ComponentRegistry};
+
+/// Version of the RCL component API implemented by this IDE
+pub const RCL_API_VERSION: &str = "0.1.0";
+
+/// Name of the manifest file inside package sources, archives and installs
+pub const MANIFEST_FILE_NAME: &str = "rcl-package.json";
+
+/// File extension used for package archives
+pub const PACKAGE_EXTENSION: &str = "rclpkg";
+
+/// Directory inside archives and installs that holds the package files
+const FILES_DIR: &str = "files";
+
+/// Errors produced while building, reading, installing or removing packages
+#[derive(Debug, thiserror::Error)]
+pub enum PackageError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid package manifest: {0}")]
+    InvalidManifest(String),
+    #[error("Invalid package archive {path}: {reason}")]
+    InvalidArchive { path: PathBuf, reason: String },
+    #[error("Integrity check failed for '{file}' in package '{package}'")]
+    IntegrityMismatch { package: String, file: String },
+    #[error("Package '{package}' requires RCL API {required}, but this IDE provides {provided}")]
+    IncompatibleApi { package: String, required: VersionReq, provided: Version },
+    #[error("Dependency '{name} {requirement}' of '{required_by}' could not be found")]
+    DependencyNotFound { name: String, requirement: VersionReq, required_by: String },
+    #[error("Package '{required_by}' requires '{name} {requirement}', but version {found} is installed")]
+    DependencyConflict { name: String, requirement: VersionReq, found: Version, required_by: String },
+    #[error("Dependency cycle detected: {0}")]
+    DependencyCycle(String),
+    #[error("Package '{0}' is not installed")]
+    NotInstalled(String),
+    #[error("Package '{name}' is required by: {}", dependents.join(", "))]
+    HasDependents { name: String, dependents: Vec<String> },
+}
+
+/// Package manifest stored as `rcl-package.json`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PackageManifest {
+    /// Package name, unique within a registry
+    pub name: String,
+    /// Package version
+    pub version: Version,
+    /// RCL API versions the package works with
+    pub rcl_api_version: VersionReq,
+    /// Human readable description
+    #[serde(default)]
+    pub description: String,
+    /// Package authors
+    #[serde(default)]
+    pub authors: Vec<String>,
+    /// Other packages this package depends on
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, VersionReq>,
+    /// Components contributed to the palette
+    #[serde(default)]
+    pub components: Vec<ComponentMetadata>,
+    /// SHA-256 hashes of the package files, keyed by relative path
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+}
+
+impl PackageManifest {
+    /// Create a manifest for an empty package targeting the current RCL API
+    pub fn new(name: impl Into<String>, version: Version) -> Self {
+        Self {
+            name: name.into(),
+            version,
+            rcl_api_version: VersionReq::parse(&format!("^{}", RCL_API_VERSION))
+                .expect("RCL_API_VERSION is a valid version"),
+            description: String::new(),
+            authors: Vec::new(),
+            dependencies: BTreeMap::new(),
+            components: Vec::new(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Load a manifest from a JSON file
+    pub fn load(path: &Path) -> Result<Self, PackageError> {
+        let content = fs::read_to_string(path)?;
+        Self::from_json(&content)
+    }
+
+    /// Parse and validate a manifest from JSON
+    pub fn from_json(json: &str) -> Result<Self, PackageError> {
+        let manifest: Self = serde_json::from_str(json)
+            .map_err(|e| PackageError::InvalidManifest(e.to_string()))?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Serialize the manifest to pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("manifest serialization cannot fail")
+    }
+
+    /// Check the manifest for structural problems
+    pub fn validate(&self) -> Result<(), PackageError> {
+        let valid_name = !self.name.is_empty()
+            && self.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_name {
+            return Err(PackageError::InvalidManifest(format!("invalid package name '{}'", self.name)));
+        }
+        if self.dependencies.contains_key(&self.name) {
+            return Err(PackageError::InvalidManifest(format!("package '{}' depends on itself", self.name)));
+        }
+        let mut component_types = HashSet::new();
+        for component in &self.components {
+            if component.component_type.is_empty() {
+                return Err(PackageError::InvalidManifest("component with empty type".to_string()));
+            }
+            if !component_types.insert(component.component_type.as_str()) {
+                return Err(PackageError::InvalidManifest(format!(
+                    "component '{}' is declared twice", component.component_type
+                )));
+            }
+        }
+        for path in self.files.keys() {
+            if !is_safe_relative_path(path) {
+                return Err(PackageError::InvalidManifest(format!("unsafe file path '{}'", path)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the package can be used with this IDE's RCL API
+    pub fn is_api_compatible(&self) -> bool {
+        self.rcl_api_version.matches(&rcl_api_version())
+    }
+
+    /// Default archive file name, `<name>-<version>.rclpkg`
+    pub fn archive_file_name(&self) -> String {
+        format!("{}-{}.{}", self.name, self.version, PACKAGE_EXTENSION)
+    }
+
+    fn check_api(&self) -> Result<(), PackageError> {
+        if self.is_api_compatible() {
+            Ok(())
+        } else {
+            Err(PackageError::IncompatibleApi {
+                package: self.name.clone(),
+                required: self.rcl_api_version.clone(),
+                provided: rcl_api_version(),
+            })
+        }
+    }
+}
+
+/// The RCL API version as a parsed semver version
+pub fn rcl_api_version() -> Version {
+    Version::parse(RCL_API_VERSION).expect("RCL_API_VERSION is a valid version")
+}
+
+/// Hex-encoded SHA-256 digest of `data`
+pub fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// A package archive read into memory with all file hashes verified
+#[derive(Clone, Debug)]
+pub struct PackageArchive {
+    /// Package manifest
+    pub manifest: PackageManifest,
+    /// File contents keyed by relative path
+    pub files: BTreeMap<String, Vec<u8>>,
+}
+
+impl PackageArchive {
+    /// Read an archive and verify it against its manifest
+    pub fn open(path: &Path) -> Result<Self, PackageError> {
+        let invalid = |reason: String| PackageError::InvalidArchive { path: path.to_path_buf(), reason };
+
+        let mut archive = tar::Archive::new(GzDecoder::new(fs::File::open(path)?));
+        let mut manifest = None;
+        let mut files = BTreeMap::new();
+
+        for entry in archive.entries().map_err(|e| invalid(e.to_string()))? {
+            let mut entry = entry.map_err(|e| invalid(e.to_string()))?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let entry_path = entry.path().map_err(|e| invalid(e.to_string()))?.to_string_lossy().replace('\\', "/");
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+
+            if entry_path == MANIFEST_FILE_NAME {
+                let json = String::from_utf8(data).map_err(|e| invalid(e.to_string()))?;
+                manifest = Some(PackageManifest::from_json(&json)?);
+            } else if let Some(relative) = entry_path.strip_prefix("files/") {
+                if !is_safe_relative_path(relative) {
+                    return Err(invalid(format!("unsafe file path '{}'", entry_path)));
+                }
+                files.insert(relative.to_string(), data);
+            } else {
+                return Err(invalid(format!("unexpected entry '{}'", entry_path)));
+            }
+        }
+
+        let manifest = manifest.ok_or_else(|| invalid(format!("missing {}", MANIFEST_FILE_NAME)))?;
+        verify_files(&manifest, &files)?;
+        Ok(Self { manifest, files })
+    }
+
+    /// Write the archive to `path`
+    pub fn write(&self, path: &Path) -> Result<(), PackageError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let encoder = GzEncoder::new(fs::File::create(path)?, Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+
+        append_entry(&mut builder, MANIFEST_FILE_NAME, self.manifest.to_json().as_bytes())?;
+        for (relative, data) in &self.files {
+            append_entry(&mut builder, &format!("{}/{}", FILES_DIR, relative), data)?;
+        }
+
+        builder.into_inner()?.finish()?.flush()?;
+        Ok(())
+    }
+}
+
+/// Build a package archive from a component source directory
+///
+/// The directory must contain an `rcl-package.json` manifest. Every other
+/// file, except hidden files and `target` directories, is added to the
+/// package and hashed into the manifest. The archive is written to
+/// `output_dir` as `<name>-<version>.rclpkg` and its path is returned.
+pub fn package_component(source_dir: &Path, output_dir: &Path) -> Result<PathBuf, PackageError> {
+    let mut manifest = PackageManifest::load(&source_dir.join(MANIFEST_FILE_NAME))?;
+
+    let mut files = BTreeMap::new();
+    collect_files(source_dir, source_dir, &mut files)?;
+    manifest.files = files.iter().map(|(path, data)| (path.clone(), sha256_hex(data))).collect();
+
+    let output = output_dir.join(manifest.archive_file_name());
+    PackageArchive { manifest, files }.write(&output)?;
+    Ok(output)
+}
+
+/// Local directory of installed packages
+pub struct PackageRegistry {
+    root: PathBuf,
+    search_paths: Vec<PathBuf>,
+}
+
+impl PackageRegistry {
+    /// Open a registry rooted at `root`
+    ///
+    /// The directory is created on first install.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into(), search_paths: Vec::new() }
+    }
 
-#[allow(dead_code)]
-pub fn package_component(src: &str, dest: &str) -> Result<(), anyhow::Error> {
-    // Placeholder: copy src to dest as a simple package
-    fs::copy(src, dest)?;
+    /// Default per-user registry location
+    pub fn default_location() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("ide-rs")
+            .join("packages")
+    }
+
+    /// Registry root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Add a directory searched for dependency archives during install
+    ///
+    /// The directory containing the package being installed is always searched.
+    pub fn add_search_path(&mut self, path: impl Into<PathBuf>) {
+        self.search_paths.push(path.into());
+    }
+
+    /// Manifests of all installed packages, sorted by name
+    pub fn installed(&self) -> Result<Vec<PackageManifest>, PackageError> {
+        let mut manifests = Vec::new();
+        if !self.root.is_dir() {
+            return Ok(manifests);
+        }
+        for entry in fs::read_dir(&self.root)? {
+            let manifest_path = entry?.path().join(MANIFEST_FILE_NAME);
+            if manifest_path.is_file() {
+                manifests.push(PackageManifest::load(&manifest_path)?);
+            }
+        }
+        manifests.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(manifests)
+    }
+
+    /// Manifest of an installed package
+    pub fn get(&self, name: &str) -> Option<PackageManifest> {
+        PackageManifest::load(&self.package_dir(name).join(MANIFEST_FILE_NAME)).ok()
+    }
+
+    /// Whether a package is installed
+    pub fn is_installed(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Directory holding the files of an installed package
+    pub fn files_dir(&self, name: &str) -> PathBuf {
+        self.package_dir(name).join(FILES_DIR)
+    }
+
+    /// Names of installed packages that depend on `name`
+    pub fn dependents(&self, name: &str) -> Result<Vec<String>, PackageError> {
+        Ok(self
+            .installed()?
+            .into_iter()
+            .filter(|manifest| manifest.dependencies.contains_key(name))
+            .map(|manifest| manifest.name)
+            .collect())
+    }
+
+    /// Install a package archive together with any missing dependencies
+    ///
+    /// Dependencies are satisfied by installed packages first, then by the
+    /// highest matching archive in the package's directory or the registry
+    /// search paths. Nothing is installed unless the whole dependency graph
+    /// resolves. Components of every installed package are registered with
+    /// `components`. Returns the manifests installed, dependencies first.
+    pub fn install(
+        &self,
+        package_path: &Path,
+        components: &mut ComponentRegistry,
+    ) -> Result<Vec<PackageManifest>, PackageError> {
+        let archive = PackageArchive::open(package_path)?;
+
+        let mut search_paths = Vec::new();
+        if let Some(parent) = package_path.parent() {
+            search_paths.push(if parent.as_os_str().is_empty() { PathBuf::from(".") } else { parent.to_path_buf() });
+        }
+        search_paths.extend(self.search_paths.iter().cloned());
+
+        // Replacing an installed version must keep its dependents satisfied
+        for dependent in self.installed()? {
+            if let Some(requirement) = dependent.dependencies.get(&archive.manifest.name) {
+                if !requirement.matches(&archive.manifest.version) {
+                    return Err(PackageError::DependencyConflict {
+                        name: archive.manifest.name.clone(),
+                        requirement: requirement.clone(),
+                        found: archive.manifest.version.clone(),
+                        required_by: dependent.name,
+                    });
+                }
+            }
+        }
+
+        let mut plan = Vec::new();
+        self.resolve(archive, &search_paths, &mut plan, &mut Vec::new())?;
+
+        let mut installed = Vec::new();
+        for archive in plan {
+            if let Some(previous) = self.get(&archive.manifest.name) {
+                unregister_components(&previous, components);
+            }
+            self.extract(&archive)?;
+            register_components(&archive.manifest, components);
+            installed.push(archive.manifest);
+        }
+        Ok(installed)
+    }
+
+    /// Remove an installed package
+    ///
+    /// Fails with [`PackageError::HasDependents`] if another installed
+    /// package depends on it. Its components are removed from `components`.
+    pub fn uninstall(&self, name: &str, components: &mut ComponentRegistry) -> Result<PackageManifest, PackageError> {
+        let manifest = self.get(name).ok_or_else(|| PackageError::NotInstalled(name.to_string()))?;
+
+        let dependents = self.dependents(name)?;
+        if !dependents.is_empty() {
+            return Err(PackageError::HasDependents { name: name.to_string(), dependents });
+        }
+
+        fs::remove_dir_all(self.package_dir(name))?;
+        unregister_components(&manifest, components);
+        Ok(manifest)
+    }
+
+    /// Register the components of every installed package
+    ///
+    /// Returns the number of components registered.
+    pub fn register_installed(&self, components: &mut ComponentRegistry) -> Result<usize, PackageError> {
+        let mut count = 0;
+        for manifest in self.installed()? {
+            register_components(&manifest, components);
+            count += manifest.components.len();
+        }
+        Ok(count)
+    }
+
+    /// Re-hash the files of an installed package against its manifest
+    pub fn verify(&self, name: &str) -> Result<(), PackageError> {
+        let manifest = self.get(name).ok_or_else(|| PackageError::NotInstalled(name.to_string()))?;
+        let files_dir = self.files_dir(name);
+        let mut files = BTreeMap::new();
+        if files_dir.is_dir() {
+            collect_files(&files_dir, &files_dir, &mut files)?;
+        }
+        verify_files(&manifest, &files)
+    }
+
+    fn package_dir(&self, name: &str) -> PathBuf {
+        self.root.join(name)
+    }
+
+    /// Depth-first resolution, pushing archives onto `plan` in install order
+    fn resolve(
+        &self,
+        archive: PackageArchive,
+        search_paths: &[PathBuf],
+        plan: &mut Vec<PackageArchive>,
+        stack: &mut Vec<String>,
+    ) -> Result<(), PackageError> {
+        let manifest = &archive.manifest;
+        manifest.check_api()?;
+
+        if stack.contains(&manifest.name) {
+            let mut cycle = stack.clone();
+            cycle.push(manifest.name.clone());
+            return Err(PackageError::DependencyCycle(cycle.join(" -> ")));
+        }
+        stack.push(manifest.name.clone());
+
+        for (name, requirement) in &manifest.dependencies {
+            let conflict = |found: &Version| PackageError::DependencyConflict {
+                name: name.clone(),
+                requirement: requirement.clone(),
+                found: found.clone(),
+                required_by: manifest.name.clone(),
+            };
+
+            if let Some(planned) = plan.iter().find(|planned| &planned.manifest.name == name) {
+                if !requirement.matches(&planned.manifest.version) {
+                    return Err(conflict(&planned.manifest.version));
+                }
+                continue;
+            }
+            if let Some(installed) = self.get(name) {
+                if !requirement.matches(&installed.version) {
+                    return Err(conflict(&installed.version));
+                }
+                continue;
+            }
+
+            let candidate = find_candidate(name, requirement, search_paths)?.ok_or_else(|| {
+                PackageError::DependencyNotFound {
+                    name: name.clone(),
+                    requirement: requirement.clone(),
+                    required_by: manifest.name.clone(),
+                }
+            })?;
+            self.resolve(PackageArchive::open(&candidate)?, search_paths, plan, stack)?;
+        }
+
+        stack.pop();
+        plan.push(archive);
+        Ok(())
+    }
+
+    /// Extract an archive into the registry, replacing any previous install
+    fn extract(&self, archive: &PackageArchive) -> Result<(), PackageError> {
+        let name = &archive.manifest.name;
+        fs::create_dir_all(&self.root)?;
+        let staging = self.root.join(format!(".{}.partial", name));
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+
+        for (relative, data) in &archive.files {
+            let path = staging.join(FILES_DIR).join(relative);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, data)?;
+        }
+        fs::create_dir_all(&staging)?;
+        fs::write(staging.join(MANIFEST_FILE_NAME), archive.manifest.to_json())?;
+
+        let target = self.package_dir(name);
+        if target.exists() {
+            fs::remove_dir_all(&target)?;
+        }
+        fs::rename(staging, target)?;
+        Ok(())
+    }
+}
+
+/// Find the highest version archive named `name` matching `requirement`
+fn find_candidate(name: &str, requirement: &VersionReq, search_paths: &[PathBuf]) -> Result<Option<PathBuf>, PackageError> {
+    let mut best: Option<(Version, PathBuf)> = None;
+    for dir in search_paths {
+        let Ok(entries) = fs::read_dir(dir) else { continue };
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(PACKAGE_EXTENSION) {
+                continue;
+            }
+            // Unreadable archives are skipped here; the chosen one is fully verified later
+            let Ok(manifest) = read_manifest(&path) else { continue };
+            if manifest.name == name
+                && requirement.matches(&manifest.version)
+                && best.as_ref().is_none_or(|(version, _)| manifest.version > *version)
+            {
+                best = Some((manifest.version, path));
+            }
+        }
+    }
+    Ok(best.map(|(_, path)| path))
+}
+
+/// Read only the manifest from an archive, without verifying file hashes
+pub fn read_manifest(path: &Path) -> Result<PackageManifest, PackageError> {
+    let mut archive = tar::Archive::new(GzDecoder::new(fs::File::open(path)?));
+    let entries = archive.entries().map_err(|e| PackageError::InvalidArchive {
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    })?;
+    for entry in entries {
+        let mut entry = entry?;
+        if entry.path()?.to_string_lossy() == MANIFEST_FILE_NAME {
+            let mut json = String::new();
+            entry.read_to_string(&mut json)?;
+            return PackageManifest::from_json(&json);
+        }
+    }
+    Err(PackageError::InvalidArchive { path: path.to_path_buf(), reason: format!("missing {}", MANIFEST_FILE_NAME) })
+}
+
+fn register_components(manifest: &PackageManifest, components: &mut ComponentRegistry) {
+    for metadata in &manifest.components {
+        components.register_component_metadata(metadata.clone());
+    }
+}
+
+fn unregister_components(manifest: &PackageManifest, components: &mut ComponentRegistry) {
+    for metadata in &manifest.components {
+        components.unregister_component(&metadata.component_type);
+    }
+}
+
+/// Check that `files` contains exactly the manifest's files with matching hashes
+fn verify_files(manifest: &PackageManifest, files: &BTreeMap<String, Vec<u8>>) -> Result<(), PackageError> {
+    let mismatch = |file: &str| PackageError::IntegrityMismatch { package: manifest.name.clone(), file: file.to_string() };
+
+    for (path, hash) in &manifest.files {
+        match files.get(path) {
+            Some(data) if sha256_hex(data).eq_ignore_ascii_case(hash) => {}
+            _ => return Err(mismatch(path)),
+        }
+    }
+    if let Some(extra) = files.keys().find(|path| !manifest.files.contains_key(*path)) {
+        return Err(mismatch(extra));
+    }
     Ok(())
 }
 
-#[allow(dead_code)]
-pub fn install_package(package_path: &str, install_dir: &str) -> Result<(), anyhow::Error> {
-    // Placeholder: copy package to install_dir
-    let file_name = Path::new(package_path).file_name().unwrap();
-    let dest = Path::new(install_dir).join(file_name);
-    fs::copy(package_path, dest)?;
+/// Recursively read package files below `dir`, keyed by `/`-separated path relative to `root`
+fn collect_files(root: &Path, dir: &Path, files: &mut BTreeMap<String, Vec<u8>>) -> Result<(), PackageError> {
+    let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if file_name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            if file_name != "target" {
+                collect_files(root, &path, files)?;
+            }
+        } else if !(dir == root && file_name == MANIFEST_FILE_NAME) {
+            let relative = path
+                .strip_prefix(root)
+                .expect("collected path is below root")
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.insert(relative, fs::read(&path)?);
+        }
+    }
     Ok(())
 }
 
-#[allow(dead_code)]
-pub fn uninstall_package(name: &str, install_dir: &str) -> Result<(), anyhow::Error> {
-    // Placeholder: remove file from install_dir
-    let path = Path::new(install_dir).join(name);
-    fs::remove_file(path)?;
+fn is_safe_relative_path(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path).components().all(|part| matches!(part, PathComponent::Normal(_)))
+}
+
+fn append_entry<W: Write>(builder: &mut tar::Builder<W>, path: &str, data: &[u8]) -> Result<(), PackageError> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, path, data)?;
     Ok(())
 }
@@ -1,33 +1,56 @@
 //! # Syntax Highlight Cache
 //!
-//! Provides intelligent caching and background highlighting for syntax highlighting.
-//! Uses LRU cache with smart invalidation and async highlighting for performance.
+//! Keeps the highlighted lines of the open document together with the syntect
+//! state each line was highlighted from, so edits only re-highlight from the
+//! first changed line until the state converges. Documents above a size
+//! threshold are highlighted on a background thread.
 
 use egui::Color32;
-use std::collections::{HashMap, BTreeSet};
-use std::sync::{Arc, Mutex};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
 use std::thread::{self, JoinHandle};
-use std::sync::mpsc::{channel, Sender, Receiver};
-use std::time::{Duration, Instant};
+use std::time::Instant;
+use syntect::highlighting::Theme;
+use syntect::parsing::SyntaxSet;
 
-use crate::editor::syntax_highlighter::SyntaxHighlighter;
+use crate::editor::syntax_highlighter::{
+    changed_line_range, plain_segments, DocumentHighlighter, HighlightedSegments, SyntaxHighlighter,
+};
 
-/// Cache for syntax highlighted lines with LRU eviction
+/// Documents with more lines than this are highlighted in the background
+const DEFAULT_BACKGROUND_THRESHOLD: usize = 5_000;
+
+/// Lines highlighted per call to `process_background_results` for small documents
+const DEFAULT_LINES_PER_FRAME: usize = 200;
+
+/// Lines the background thread highlights before releasing the document
+const BACKGROUND_CHUNK_LINES: usize = 200;
+
+/// Cache of highlighted lines for one document
 pub struct SyntaxHighlightCache {
-    /// Cached highlighted lines (line_number -> highlighted_line)
-    cache: lru::LruCache<usize, HighlightedLine>,
+    /// Highlighter used to create documents
+    highlighter: SyntaxHighlighter,
+    /// Highlighted document, created on first use
+    document: Option<HighlightDocument>,
     /// Cache hit rate tracking
     hit_rate: f32,
     /// Total cache requests
     total_requests: u64,
     /// Cache hits
     cache_hits: u64,
-    /// Lines marked as dirty (need re-highlighting)
-    dirty_lines: BTreeSet<usize>,
-    /// Background highlighter
-    background_highlighter: Option<BackgroundHighlighter>,
-    /// Maximum cache size
-    max_cache_size: usize,
+    /// Line count above which highlighting moves to a background thread
+    background_threshold: usize,
+    /// Lines highlighted per frame when highlighting in the foreground
+    lines_per_frame: usize,
+}
+
+/// Where the document is highlighted
+enum HighlightDocument {
+    /// On the calling thread
+    Local(Box<DocumentHighlighter>),
+    /// On a background thread
+    Background(BackgroundHighlighter),
 }
 
 /// A syntax highlighted line with color segments
@@ -37,147 +60,226 @@ pub struct HighlightedLine {
     pub segments: Vec<(String, Color32)>,
     /// Line number
     pub line_number: usize,
-    /// Timestamp when highlighted
+    /// Timestamp when the line was read from the cache
     pub highlighted_at: Instant,
     /// Language used for highlighting
     pub language: String,
 }
 
-/// Background syntax highlighter for async processing
+/// Highlights a document on a background thread
+///
+/// syntect's parse state cannot leave the thread that created it, so the
+/// background thread owns the [`DocumentHighlighter`] and this side keeps a
+/// copy of the lines and their latest colors.
 pub struct BackgroundHighlighter {
-    /// Sender for highlight requests
-    request_sender: Sender<HighlightRequest>,
+    /// Sender for highlight requests, dropped to stop the thread
+    request_sender: Option<Sender<HighlightRequest>>,
     /// Receiver for highlight results
     result_receiver: Receiver<HighlightResult>,
     /// Background thread handle
     thread_handle: Option<JoinHandle<()>>,
-    /// Whether the highlighter is running
-    is_running: Arc<Mutex<bool>>,
+    theme: Arc<Theme>,
+    language: String,
+    /// Lines as last sent to the background thread
+    lines: Vec<MirrorLine>,
+    /// Number of requests sent so far
+    requests_sent: u64,
+    /// Number of requests the background thread had applied in its latest result
+    requests_acknowledged: u64,
+    /// Edits the background thread may not have applied yet, by request number
+    unapplied_edits: VecDeque<(u64, usize, usize, usize)>,
+    /// Lines still waiting to be highlighted, as last reported
+    pending_lines: usize,
+    /// Lines highlighted by the background thread, as last reported
+    lines_highlighted: u64,
+}
+
+struct MirrorLine {
+    text: String,
+    segments: Option<HighlightedSegments>,
+    dirty: bool,
 }
 
 /// Request for background highlighting
 #[derive(Debug, Clone)]
-pub struct HighlightRequest {
-    /// Line number to highlight
-    pub line_number: usize,
-    /// Line content
-    pub content: String,
-    /// Programming language
-    pub language: String,
-    /// Request timestamp
-    pub timestamp: Instant,
+pub enum HighlightRequest {
+    /// Replace lines `start..end` with `lines`
+    Edit { start: usize, end: usize, lines: Vec<String> },
+    /// Highlight lines `start..end` again
+    Invalidate { start: usize, end: usize },
 }
 
 /// Result from background highlighting
 #[derive(Debug, Clone)]
 pub struct HighlightResult {
-    /// Line number
-    pub line_number: usize,
-    /// Highlighted line
-    pub highlighted_line: HighlightedLine,
-    /// Processing time
-    pub processing_time: Duration,
+    /// Number of requests applied before these lines were highlighted
+    pub requests_applied: u64,
+    /// Highlighted lines, numbered as of `requests_applied`
+    pub lines: Vec<(usize, HighlightedSegments)>,
+    /// Lines still waiting to be highlighted
+    pub pending_lines: usize,
+    /// Total number of lines highlighted by the background thread
+    pub lines_highlighted: u64,
 }
 
-impl SyntaxHighlightCache {
-    /// Create a new syntax highlight cache
-    pub fn new() -> Self {
-        Self::with_capacity(1000)
+impl HighlightDocument {
+    fn language(&self) -> &str {
+        match self {
+            HighlightDocument::Local(document) => document.language(),
+            HighlightDocument::Background(background) => &background.language,
+        }
     }
 
-    /// Create cache with specific capacity
-    pub fn with_capacity(capacity: usize) -> Self {
-        Self {
-            cache: lru::LruCache::new(std::num::NonZeroUsize::new(capacity).unwrap()),
-            hit_rate: 0.0,
-            total_requests: 0,
-            cache_hits: 0,
-            dirty_lines: BTreeSet::new(),
-            background_highlighter: Some(BackgroundHighlighter::new()),
-            max_cache_size: capacity,
+    fn line_count(&self) -> usize {
+        match self {
+            HighlightDocument::Local(document) => document.line_count(),
+            HighlightDocument::Background(background) => background.lines.len(),
         }
     }
 
-    /// Get or highlight a line (sync)
-    pub fn get_or_highlight_line(
-        &mut self,
-        line_number: usize,
-        content: &str,
-        language: &str,
-    ) -> HighlightedLine {
-        self.total_requests += 1;
-
-        // Check if line is dirty
-        if self.dirty_lines.contains(&line_number) {
-            self.cache.pop(&line_number);
-            self.dirty_lines.remove(&line_number);
+    fn line_text(&self, index: usize) -> Option<&str> {
+        match self {
+            HighlightDocument::Local(document) => document.line_text(index),
+            HighlightDocument::Background(background) => background.line_text(index),
         }
+    }
 
-        // Try to get from cache first
-        if let Some(cached_line) = self.cache.get(&line_number) {
-            // Verify language matches
-            if cached_line.language == language {
-                self.cache_hits += 1;
-                self.update_hit_rate();
-                return cached_line.clone();
-            }
+    /// Highlighted segments of a line if it is up to date
+    fn fresh_line(&self, index: usize) -> Option<&[(String, Color32)]> {
+        match self {
+            HighlightDocument::Local(document) => document.line(index).filter(|_| !document.is_dirty(index)),
+            HighlightDocument::Background(background) => background.line(index).filter(|_| !background.is_dirty(index)),
         }
+    }
 
-        // Not in cache or language mismatch, highlight now
-        let highlighted_line = self.highlight_line_immediate(line_number, content, language);
-        self.cache.put(line_number, highlighted_line.clone());
+    fn line_or_plain(&self, index: usize) -> HighlightedSegments {
+        match self {
+            HighlightDocument::Local(document) => document.line_or_plain(index),
+            HighlightDocument::Background(background) => background.line_or_plain(index),
+        }
+    }
 
-        self.update_hit_rate();
-        highlighted_line
+    fn edit_lines(&mut self, start: usize, end: usize, new_lines: &[&str]) {
+        match self {
+            HighlightDocument::Local(document) => document.edit_lines(start, end, new_lines),
+            HighlightDocument::Background(background) => background.edit_lines(start, end, new_lines),
+        }
     }
 
-    /// Prefetch line highlighting (async)
-    pub fn prefetch_line(&mut self, line_number: usize, content: &str, language: &str) {
-        // Skip if already cached and not dirty
-        if !self.dirty_lines.contains(&line_number) && self.cache.contains(&line_number) {
-            return;
+    fn update(&mut self, content: &str) {
+        match self {
+            HighlightDocument::Local(document) => {
+                document.update(content);
+            }
+            HighlightDocument::Background(background) => background.update(content),
         }
+    }
 
-        // Send to background highlighter
-        if let Some(ref mut highlighter) = self.background_highlighter {
-            let request = HighlightRequest {
-                line_number,
-                content: content.to_string(),
-                language: language.to_string(),
-                timestamp: Instant::now(),
-            };
+    fn invalidate_lines(&mut self, start: usize, end: usize) {
+        match self {
+            HighlightDocument::Local(document) => document.invalidate_lines(start, end),
+            HighlightDocument::Background(background) => background.invalidate_lines(start, end),
+        }
+    }
 
-            if highlighter.send_request(request).is_err() {
-                // Fallback to immediate highlighting if background failed
-                let highlighted = self.highlight_line_immediate(line_number, content, language);
-                self.cache.put(line_number, highlighted);
+    /// Highlighted line count, pending line count and total lines highlighted
+    fn counts(&self) -> (usize, usize, u64) {
+        match self {
+            HighlightDocument::Local(document) => {
+                (document.highlighted_line_count(), document.dirty_line_count(), document.lines_highlighted())
             }
+            HighlightDocument::Background(background) => (
+                background.lines.iter().filter(|line| !line.dirty && line.segments.is_some()).count(),
+                background.pending_lines,
+                background.lines_highlighted,
+            ),
         }
     }
+}
 
-    /// Process results from background highlighter
-    pub fn process_background_results(&mut self) {
-        if let Some(ref mut highlighter) = self.background_highlighter {
-            let results = highlighter.collect_results();
+impl SyntaxHighlightCache {
+    /// Create a new syntax highlight cache
+    pub fn new() -> Self {
+        Self::with_background_threshold(DEFAULT_BACKGROUND_THRESHOLD)
+    }
 
-            for result in results {
-                // Only cache if line isn't dirty (hasn't been modified since request)
-                if !self.dirty_lines.contains(&result.line_number) {
-                    self.cache.put(result.line_number, result.highlighted_line);
-                }
-            }
+    /// Create a cache that highlights documents longer than `lines` in the background
+    pub fn with_background_threshold(lines: usize) -> Self {
+        Self {
+            highlighter: SyntaxHighlighter::new("base16-ocean.dark"),
+            document: None,
+            hit_rate: 0.0,
+            total_requests: 0,
+            cache_hits: 0,
+            background_threshold: lines,
+            lines_per_frame: DEFAULT_LINES_PER_FRAME,
         }
     }
 
-    /// Highlight line immediately (sync)
-    fn highlight_line_immediate(
-        &self,
+    /// Set how many lines `process_background_results` highlights for small documents
+    pub fn set_lines_per_frame(&mut self, lines: usize) {
+        self.lines_per_frame = lines.max(1);
+    }
+
+    /// Highlight with the syntect theme `theme_name` from now on
+    ///
+    /// The document is highlighted again from scratch.
+    pub fn set_theme(&mut self, theme_name: &str) {
+        self.highlighter.set_theme(theme_name);
+        self.document = None;
+    }
+
+    /// Whether the document is highlighted on a background thread
+    pub fn is_background(&self) -> bool {
+        matches!(self.document, Some(HighlightDocument::Background(_)))
+    }
+
+    /// Bring the cached document in line with `content`
+    ///
+    /// Only lines that differ from the previous content are re-highlighted.
+    pub fn update_document(&mut self, content: &str, language: &str) {
+        self.collect_background_results();
+        self.document_for(language).update(content);
+        self.move_to_background_if_large();
+    }
+
+    /// Get or highlight a line (sync)
+    ///
+    /// In the background mode a line that is not highlighted yet is returned
+    /// with its previous colors, or uncolored, instead of blocking.
+    pub fn get_or_highlight_line(
+        &mut self,
         line_number: usize,
         content: &str,
         language: &str,
     ) -> HighlightedLine {
-        let highlighter = SyntaxHighlighter::new("base16-ocean.dark");
-        let segments = highlighter.highlight_line(content, language);
+        self.total_requests += 1;
+
+        let document = self.document_for(language);
+        if document.line_text(line_number) != Some(content) {
+            let count = document.line_count();
+            if line_number < count {
+                document.edit_lines(line_number, line_number + 1, &[content]);
+            } else {
+                let mut lines = vec![""; line_number - count];
+                lines.push(content);
+                document.edit_lines(count, count, &lines);
+            }
+        }
+        let (hit, segments) = match document.fresh_line(line_number) {
+            Some(segments) => (true, segments.to_vec()),
+            None => {
+                if let HighlightDocument::Local(document) = document {
+                    document.highlight_through(line_number);
+                }
+                (false, document.line_or_plain(line_number))
+            }
+        };
+
+        if hit {
+            self.cache_hits += 1;
+        }
+        self.update_hit_rate();
 
         HighlightedLine {
             segments,
@@ -187,24 +289,38 @@ impl SyntaxHighlightCache {
         }
     }
 
+    /// Continue highlighting pending lines
+    ///
+    /// Small documents are highlighted a few lines per call; large ones are
+    /// handled by the background thread. Returns `true` while lines are
+    /// still waiting to be highlighted.
+    pub fn process_background_results(&mut self) -> bool {
+        let lines_per_frame = self.lines_per_frame;
+        match &mut self.document {
+            Some(HighlightDocument::Local(document)) => !document.highlight_pending(lines_per_frame),
+            Some(HighlightDocument::Background(background)) => {
+                background.collect_results();
+                !background.is_idle()
+            }
+            None => false,
+        }
+    }
+
     /// Mark line range as dirty (needs re-highlighting)
     pub fn invalidate_range(&mut self, start_line: usize, end_line: usize) {
-        for line in start_line..=end_line {
-            self.dirty_lines.insert(line);
-            self.cache.pop(&line);
+        if let Some(document) = &mut self.document {
+            document.invalidate_lines(start_line, end_line.saturating_add(1));
         }
     }
 
     /// Mark single line as dirty
     pub fn invalidate_line(&mut self, line_number: usize) {
-        self.dirty_lines.insert(line_number);
-        self.cache.pop(&line_number);
+        self.invalidate_range(line_number, line_number);
     }
 
     /// Clear entire cache
     pub fn clear_cache(&mut self) {
-        self.cache.clear();
-        self.dirty_lines.clear();
+        self.document = None;
         self.total_requests = 0;
         self.cache_hits = 0;
         self.hit_rate = 0.0;
@@ -219,107 +335,222 @@ impl SyntaxHighlightCache {
 
     /// Get cache statistics
     pub fn get_stats(&self) -> CacheStats {
+        let (cache_size, dirty_lines_count, lines_highlighted) = match &self.document {
+            Some(document) => document.counts(),
+            None => (0, 0, 0),
+        };
         CacheStats {
             hit_rate: self.hit_rate,
             total_requests: self.total_requests,
             cache_hits: self.cache_hits,
-            cache_size: self.cache.len(),
-            max_cache_size: self.max_cache_size,
-            dirty_lines_count: self.dirty_lines.len(),
+            cache_size,
+            dirty_lines_count,
+            lines_highlighted,
         }
     }
 
-    /// Resize cache
-    pub fn resize_cache(&mut self, new_size: usize) {
-        if let Ok(new_capacity) = std::num::NonZeroUsize::try_from(new_size) {
-            self.cache.resize(new_capacity);
-            self.max_cache_size = new_size;
+    fn collect_background_results(&mut self) {
+        if let Some(HighlightDocument::Background(background)) = &mut self.document {
+            background.collect_results();
+        }
+    }
+
+    /// The document for `language`, replacing the current one if its language differs
+    fn document_for(&mut self, language: &str) -> &mut HighlightDocument {
+        if self.document.as_ref().is_some_and(|document| document.language() != language) {
+            self.document = None;
+        }
+        let highlighter = &self.highlighter;
+        self.document.get_or_insert_with(|| HighlightDocument::Local(Box::new(highlighter.document(language))))
+    }
+
+    fn move_to_background_if_large(&mut self) {
+        let large = matches!(
+            &self.document,
+            Some(HighlightDocument::Local(document)) if document.line_count() > self.background_threshold
+        );
+        if large {
+            if let Some(HighlightDocument::Local(document)) = self.document.take() {
+                let background = BackgroundHighlighter::new(
+                    &document,
+                    self.highlighter.syntax_set.clone(),
+                    self.highlighter.theme.clone(),
+                );
+                self.document = Some(HighlightDocument::Background(background));
+            }
         }
     }
 }
 
 impl BackgroundHighlighter {
-    /// Create a new background highlighter
-    pub fn new() -> Self {
+    /// Start highlighting `document` on a background thread
+    ///
+    /// Colors already in `document` are shown until the background thread
+    /// has highlighted the lines again.
+    pub fn new(document: &DocumentHighlighter, syntax_set: Arc<SyntaxSet>, theme: Arc<Theme>) -> Self {
+        let language = document.language().to_string();
+        let lines: Vec<MirrorLine> = (0..document.line_count())
+            .map(|index| MirrorLine {
+                text: document.line_text(index).unwrap_or_default().to_string(),
+                segments: document.line(index).map(<[_]>::to_vec),
+                dirty: true,
+            })
+            .collect();
+        let text: Vec<String> = lines.iter().map(|line| line.text.clone()).collect();
+
         let (request_sender, request_receiver) = channel::<HighlightRequest>();
         let (result_sender, result_receiver) = channel::<HighlightResult>();
-        let is_running = Arc::new(Mutex::new(true));
-        let is_running_clone = is_running.clone();
+        let worker_theme = theme.clone();
+        let worker_language = language.clone();
 
         // Spawn background highlighting thread
         let thread_handle = thread::spawn(move || {
-            let mut highlighter = SyntaxHighlighter::new("base16-ocean.dark");
-
-            while *is_running_clone.lock().unwrap() {
-                // Process requests with timeout to allow checking shutdown
-                match request_receiver.recv_timeout(Duration::from_millis(100)) {
-                    Ok(request) => {
-                        let start_time = Instant::now();
-
-                        // Highlight the line
-                        let segments = highlighter.highlight_line(&request.content, &request.language);
-
-                        let highlighted_line = HighlightedLine {
-                            segments,
-                            line_number: request.line_number,
-                            highlighted_at: Instant::now(),
-                            language: request.language,
-                        };
-
-                        let result = HighlightResult {
-                            line_number: request.line_number,
-                            highlighted_line,
-                            processing_time: start_time.elapsed(),
-                        };
-
-                        // Send result back (ignore if receiver is dropped)
-                        let _ = result_sender.send(result);
-                    }
-                    Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
-                        // Continue loop to check shutdown condition
-                        continue;
-                    }
-                    Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
-                        // Channel disconnected, exit thread
-                        break;
-                    }
-                }
-            }
+            let mut document = DocumentHighlighter::new(syntax_set, worker_theme, &worker_language);
+            let text: Vec<&str> = text.iter().map(String::as_str).collect();
+            document.edit_lines(0, 0, &text);
+            run_background_highlighter(document, request_receiver, result_sender);
         });
 
         Self {
-            request_sender,
+            request_sender: Some(request_sender),
             result_receiver,
             thread_handle: Some(thread_handle),
-            is_running,
+            theme,
+            language,
+            pending_lines: lines.len(),
+            lines,
+            requests_sent: 0,
+            requests_acknowledged: 0,
+            unapplied_edits: VecDeque::new(),
+            lines_highlighted: 0,
         }
     }
 
-    /// Send a highlight request to background thread
-    pub fn send_request(&self, request: HighlightRequest) -> Result<(), String> {
-        self.request_sender
-            .send(request)
-            .map_err(|e| format!("Failed to send highlight request: {}", e))
+    /// Text of a line
+    pub fn line_text(&self, index: usize) -> Option<&str> {
+        self.lines.get(index).map(|line| line.text.as_str())
     }
 
-    /// Collect all available results
-    pub fn collect_results(&self) -> Vec<HighlightResult> {
-        let mut results = Vec::new();
+    /// Latest colors of a line, possibly from before the last edit
+    pub fn line(&self, index: usize) -> Option<&[(String, Color32)]> {
+        self.lines.get(index)?.segments.as_deref()
+    }
+
+    /// Segments of a line, falling back to uncolored text if it was never highlighted
+    pub fn line_or_plain(&self, index: usize) -> HighlightedSegments {
+        match self.lines.get(index) {
+            Some(MirrorLine { segments: Some(segments), .. }) => segments.clone(),
+            Some(line) => plain_segments(&self.theme, &line.text),
+            None => Vec::new(),
+        }
+    }
+
+    /// Whether a line is waiting to be highlighted
+    pub fn is_dirty(&self, index: usize) -> bool {
+        self.lines.get(index).is_some_and(|line| line.dirty)
+    }
+
+    /// Whether every line has been highlighted
+    pub fn is_idle(&self) -> bool {
+        self.requests_acknowledged == self.requests_sent && self.pending_lines == 0
+    }
+
+    /// Bring the document in line with `content`
+    pub fn update(&mut self, content: &str) {
+        let new_lines: Vec<&str> = content.lines().collect();
+        let old_lines: Vec<&str> = self.lines.iter().map(|line| line.text.as_str()).collect();
+        if let Some((start, old_end, new_end)) = changed_line_range(&old_lines, &new_lines) {
+            self.edit_lines(start, old_end, &new_lines[start..new_end]);
+        }
+    }
+
+    /// Replace the lines `start..end` with `new_lines`
+    pub fn edit_lines(&mut self, start: usize, end: usize, new_lines: &[&str]) {
+        let end = end.min(self.lines.len());
+        let start = start.min(end);
+        self.lines.splice(start..end, new_lines.iter().map(|text| MirrorLine {
+            text: text.to_string(),
+            segments: None,
+            dirty: true,
+        }));
+        if new_lines.is_empty() {
+            // The line after a deletion now follows a different line
+            if let Some(next) = self.lines.get_mut(start) {
+                next.dirty = true;
+            }
+        }
+
+        self.send_request(HighlightRequest::Edit {
+            start,
+            end,
+            lines: new_lines.iter().map(|text| text.to_string()).collect(),
+        });
+        self.unapplied_edits.push_back((self.requests_sent, start, end, new_lines.len()));
+    }
+
+    /// Force the lines `start..end` to be highlighted again
+    pub fn invalidate_lines(&mut self, start: usize, end: usize) {
+        let end = end.min(self.lines.len());
+        let start = start.min(end);
+        for line in &mut self.lines[start..end] {
+            line.dirty = true;
+        }
+        self.send_request(HighlightRequest::Invalidate { start, end });
+    }
+
+    fn send_request(&mut self, request: HighlightRequest) {
+        self.requests_sent += 1;
+        if let Some(sender) = &self.request_sender {
+            // A stopped thread leaves the lines dirty, which shows them uncolored
+            let _ = sender.send(request);
+        }
+    }
+
+    /// Apply all available results
+    ///
+    /// Returns whether any line changed.
+    pub fn collect_results(&mut self) -> bool {
+        let mut changed = false;
 
         // Collect all available results without blocking
         while let Ok(result) = self.result_receiver.try_recv() {
-            results.push(result);
+            self.apply_result(result);
+            changed = true;
         }
 
-        results
+        changed
+    }
+
+    fn apply_result(&mut self, result: HighlightResult) {
+        for (line_number, segments) in result.lines {
+            // Follow the line through edits made after it was highlighted
+            let mapped = self.unapplied_edits.iter()
+                .filter(|(request, ..)| *request > result.requests_applied)
+                .try_fold(line_number, |index, &(_, start, end, inserted)| {
+                    if index < start {
+                        Some(index)
+                    } else if index >= end {
+                        Some(index - (end - start) + inserted)
+                    } else {
+                        None
+                    }
+                });
+            if let Some(line) = mapped.and_then(|index| self.lines.get_mut(index)) {
+                line.segments = Some(segments);
+                line.dirty = false;
+            }
+        }
+
+        self.unapplied_edits.retain(|(request, ..)| *request > result.requests_applied);
+        self.requests_acknowledged = result.requests_applied;
+        self.pending_lines = result.pending_lines;
+        self.lines_highlighted = result.lines_highlighted;
     }
 
     /// Shutdown background highlighter
     pub fn shutdown(&mut self) {
-        // Signal shutdown
-        if let Ok(mut running) = self.is_running.lock() {
-            *running = false;
-        }
+        // Closing the channel stops the thread
+        self.request_sender = None;
 
         // Wait for thread to finish
         if let Some(handle) = self.thread_handle.take() {
@@ -334,6 +565,59 @@ impl Drop for BackgroundHighlighter {
     }
 }
 
+/// Body of the background thread
+///
+/// Applies requests as they arrive, highlights pending lines in chunks and
+/// reports each chunk. Blocks while everything is highlighted.
+fn run_background_highlighter(
+    mut document: DocumentHighlighter,
+    requests: Receiver<HighlightRequest>,
+    results: Sender<HighlightResult>,
+) {
+    let mut requests_applied = 0;
+    let apply = |document: &mut DocumentHighlighter, request: HighlightRequest| match request {
+        HighlightRequest::Edit { start, end, lines } => {
+            let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+            document.edit_lines(start, end, &lines);
+        }
+        HighlightRequest::Invalidate { start, end } => document.invalidate_lines(start, end),
+    };
+
+    loop {
+        if document.is_complete() {
+            match requests.recv() {
+                Ok(request) => {
+                    apply(&mut document, request);
+                    requests_applied += 1;
+                }
+                Err(_) => return,
+            }
+        }
+        loop {
+            match requests.try_recv() {
+                Ok(request) => {
+                    apply(&mut document, request);
+                    requests_applied += 1;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return,
+            }
+        }
+
+        let mut lines = Vec::new();
+        document.highlight_pending_with(BACKGROUND_CHUNK_LINES, |index, segments| lines.push((index, segments.to_vec())));
+        let result = HighlightResult {
+            requests_applied,
+            lines,
+            pending_lines: document.dirty_line_count(),
+            lines_highlighted: document.lines_highlighted(),
+        };
+        if results.send(result).is_err() {
+            return;
+        }
+    }
+}
+
 /// Cache statistics
 #[derive(Debug, Clone)]
 pub struct CacheStats {
@@ -343,12 +627,12 @@ pub struct CacheStats {
     pub total_requests: u64,
     /// Number of cache hits
     pub cache_hits: u64,
-    /// Current cache size
+    /// Number of highlighted, up to date lines
     pub cache_size: usize,
-    /// Maximum cache size
-    pub max_cache_size: usize,
-    /// Number of dirty lines
+    /// Number of lines waiting to be highlighted
     pub dirty_lines_count: usize,
+    /// Total number of lines highlighted, including re-highlights
+    pub lines_highlighted: u64,
 }
 
 impl Default for SyntaxHighlightCache {
@@ -410,4 +694,4 @@ mod tests {
         let stats = cache.get_stats();
         assert_eq!(stats.cache_hits, 0);
     }
-}
\ No newline at end of file
+}
@@ -132,7 +132,7 @@ impl VirtualCodeEditor {
 
     /// Update text metrics for accurate calculations
     fn update_text_metrics(&mut self, ui: &Ui) {
-        let font_id = FontId::new(self.settings.font_size, self.settings.font_family);
+        let font_id = FontId::new(self.settings.font_size, self.settings.font_family.clone());
 
         // Calculate line height
         self.line_height = ui.fonts(|fonts| fonts.row_height(&font_id));
@@ -187,6 +187,9 @@ impl VirtualCodeEditor {
             ui.add_space(spacer_height);
         }
 
+        // Sync the highlighted document; only changed lines are re-highlighted
+        self.highlight_cache.update_document(content, language);
+
         // Get lines in render range
         let lines: Vec<&str> = content.lines().collect();
         let render_lines = &lines[render_start..render_end.min(lines.len())];
@@ -323,7 +326,7 @@ impl VirtualCodeEditor {
         highlighted_line: &HighlightedLine,
     ) {
         let mut x_offset = 0.0;
-        let font_id = FontId::new(self.settings.font_size, self.settings.font_family);
+        let font_id = FontId::new(self.settings.font_size, self.settings.font_family.clone());
 
         for (text, color) in &highlighted_line.segments {
             if !text.is_empty() {
@@ -403,18 +406,12 @@ impl VirtualCodeEditor {
         self.highlight_cache.invalidate_range(start_line, end_line);
     }
 
-    /// Prefetch highlighting for upcoming lines
+    /// Continue highlighting lines that are not up to date yet
     pub fn prefetch_highlighting(&mut self, content: &str, language: &str) {
         if self.settings.background_highlighting {
-            let prefetch_start = self.viewport.last_visible_line;
-            let prefetch_end = (prefetch_start + 50).min(self.viewport.total_lines);
-
-            let lines: Vec<&str> = content.lines().collect();
-            for line_idx in prefetch_start..prefetch_end {
-                if let Some(line) = lines.get(line_idx) {
-                    self.highlight_cache.prefetch_line(line_idx, line, language);
-                }
-            }
+            self.highlight_cache.set_lines_per_frame(self.settings.max_highlight_per_frame);
+            self.highlight_cache.update_document(content, language);
+            self.highlight_cache.process_background_results();
         }
     }
 }
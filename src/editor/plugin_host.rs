@@ -0,0 +1,639 @@
+//! Out-of-Process Plugin Host
+//!
+//! Runs plugins as separate executables that talk to the IDE over stdio using
+//! JSON-RPC 2.0, one JSON message per line. A plugin that crashes or hangs only
+//! takes down its own process; the IDE reports the failure and carries on.
+//!
+//! ## Protocol
+//!
+//! Requests sent by the IDE (the plugin must answer each one):
+//! - `initialize` `{pluginId, ideVersion, settings}` → `{commands, events, extensionPoints}`
+//! - `activate`, `deactivate`, `shutdown`
+//! - `executeCommand` `{command, context: {activeFile, selection, cursor}}`
+//! - `getSettingsSchema`, `updateSettings` `{settings}`
+//! - `extension/request` `{extensionPoint, kind, ...}` for contributed extension points
+//!
+//! Notifications sent by the IDE for subscribed events: `event/fileOpened`,
+//! `event/fileSaved`, `event/fileClosed`, `event/fileChanged`, `event/selectionChanged`,
+//! `event/buildStarted`, `event/buildCompleted` and `event/gitCommit`.
+//!
+//! Notifications a plugin may send: `window/log` `{level, message}` and
+//! `window/showMessage` `{type, message}` (LSP message types 1-4).
+
+use egui::Ui;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::editor::lsp_integration::{Diagnostic, LspClient, Range};
+use crate::editor::plugin_system::*;
+
+/// Default time a plugin has to answer a request
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Running plugin process and its JSON-RPC connection
+pub struct PluginProcess {
+    /// Plugin ID, used to tag messages
+    plugin_id: String,
+    /// Child process
+    child: Child,
+    /// Child stdin used to send messages
+    stdin: ChildStdin,
+    /// Parsed messages from the child's stdout and stderr
+    incoming: Receiver<Value>,
+    /// Next request ID
+    next_id: u64,
+    /// Notifications received while waiting for responses
+    messages: Vec<PluginMessage>,
+    /// Time a plugin has to answer a request
+    timeout: Duration,
+}
+
+/// Plugin process shared between the API object, command handlers, hooks and extensions
+pub type SharedPluginProcess = Arc<Mutex<PluginProcess>>;
+
+impl PluginProcess {
+    /// Start a plugin executable with piped stdio
+    pub fn spawn(plugin_id: &str, program: &Path, args: &[String], working_dir: &Path) -> Result<Self, PluginError> {
+        let mut child = Command::new(program)
+            .args(args)
+            .current_dir(working_dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| PluginError::InitializationFailed(format!("{}: {}", program.display(), e)))?;
+
+        let stdin = child.stdin.take()
+            .ok_or_else(|| PluginError::InitializationFailed("plugin stdin unavailable".to_string()))?;
+        let (tx, rx) = mpsc::channel();
+
+        if let Some(stdout) = child.stdout.take() {
+            let tx = tx.clone();
+            std::thread::spawn(move || {
+                let reader = BufReader::new(stdout);
+                for line in reader.lines() {
+                    let Ok(line) = line else { break };
+                    // Anything that is not a JSON message is treated as plain log output
+                    let message = serde_json::from_str::<Value>(line.trim())
+                        .ok()
+                        .filter(|value| value.is_object())
+                        .unwrap_or_else(|| Self::log_notification("info", &line));
+                    if tx.send(message).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        if let Some(stderr) = child.stderr.take() {
+            std::thread::spawn(move || {
+                let reader = BufReader::new(stderr);
+                for line in reader.lines() {
+                    let Ok(line) = line else { break };
+                    if tx.send(Self::log_notification("warning", &line)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        Ok(Self {
+            plugin_id: plugin_id.to_string(),
+            child,
+            stdin,
+            incoming: rx,
+            next_id: 0,
+            messages: Vec::new(),
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+        })
+    }
+
+    /// Set the time a plugin has to answer a request
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// OS process ID of the plugin
+    pub fn pid(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Whether the plugin process is still running
+    pub fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// Send a request and wait for its response
+    pub fn request(&mut self, method: &str, params: Value) -> Result<Value, PluginError> {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.write_message(&json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        }))?;
+
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let message = match self.incoming.recv_timeout(remaining) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(PluginError::Timeout(format!("{} did not answer {}", self.plugin_id, method)));
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(PluginError::ExecutionError(format!("{} exited", self.plugin_id)));
+                }
+            };
+
+            let is_response = message.get("method").is_none();
+            if is_response && message.get("id").and_then(Value::as_u64) == Some(id) {
+                if let Some(error) = message.get("error") {
+                    let text = error.get("message").and_then(Value::as_str).unwrap_or("unknown error");
+                    return Err(PluginError::ExecutionError(format!("{}: {}", method, text)));
+                }
+                return Ok(message.get("result").cloned().unwrap_or(Value::Null));
+            }
+
+            self.handle_incoming(message)?;
+        }
+    }
+
+    /// Send a notification; no response is expected
+    pub fn notify(&mut self, method: &str, params: Value) -> Result<(), PluginError> {
+        self.write_message(&json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+        }))
+    }
+
+    /// Take messages received from the plugin since the last call
+    pub fn drain_messages(&mut self) -> Vec<PluginMessage> {
+        while let Ok(message) = self.incoming.try_recv() {
+            let _ = self.handle_incoming(message);
+        }
+        std::mem::take(&mut self.messages)
+    }
+
+    /// Ask the plugin to exit, then make sure it does
+    pub fn shutdown(&mut self) {
+        if self.is_running() {
+            self.timeout = Duration::from_millis(500);
+            let _ = self.request("shutdown", Value::Null);
+            let _ = self.notify("exit", Value::Null);
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+
+    /// Handle a notification or request that arrived from the plugin
+    fn handle_incoming(&mut self, message: Value) -> Result<(), PluginError> {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // Stale response to a request that already timed out
+            return Ok(());
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        if let Some(id) = message.get("id").cloned() {
+            // The IDE does not serve requests from plugins yet
+            return self.write_message(&json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": -32601, "message": format!("Method not found: {}", method) },
+            }));
+        }
+
+        let text = params.get("message").and_then(Value::as_str).unwrap_or_default().to_string();
+        let level = match method {
+            "window/log" => match params.get("level").and_then(Value::as_str) {
+                Some("error") => PluginMessageLevel::Error,
+                Some("warning") | Some("warn") => PluginMessageLevel::Warning,
+                _ => PluginMessageLevel::Info,
+            },
+            "window/showMessage" => match params.get("type").and_then(Value::as_u64) {
+                Some(1) => PluginMessageLevel::Error,
+                Some(2) => PluginMessageLevel::Warning,
+                _ => PluginMessageLevel::Info,
+            },
+            _ => return Ok(()),
+        };
+
+        self.messages.push(PluginMessage {
+            plugin_id: self.plugin_id.clone(),
+            level,
+            text,
+        });
+        Ok(())
+    }
+
+    fn write_message(&mut self, message: &Value) -> Result<(), PluginError> {
+        let mut line = serde_json::to_string(message)
+            .map_err(|e| PluginError::SerializationError(e.to_string()))?;
+        line.push('\n');
+        self.stdin.write_all(line.as_bytes())
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| PluginError::ExecutionError(format!("{}: {}", self.plugin_id, e)))
+    }
+
+    fn log_notification(level: &str, line: &str) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "method": "window/log",
+            "params": { "level": level, "message": line },
+        })
+    }
+}
+
+impl Drop for PluginProcess {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// [`PluginAPI`] implementation backed by a plugin process
+pub struct ProcessPluginAPI {
+    /// Plugin metadata
+    metadata: PluginMetadata,
+    /// Connection to the plugin process
+    process: SharedPluginProcess,
+    /// Result of the `initialize` request
+    capabilities: Value,
+}
+
+impl ProcessPluginAPI {
+    /// Start the plugin's entry point from its install directory
+    pub fn spawn(metadata: PluginMetadata, plugin_dir: &Path) -> Result<Self, PluginError> {
+        let entry_point = PathBuf::from(&metadata.entry_point);
+        let program = if entry_point.is_absolute() {
+            entry_point
+        } else {
+            plugin_dir.join(entry_point)
+        };
+
+        let process = PluginProcess::spawn(&metadata.id, &program, &metadata.args, plugin_dir)?;
+        Ok(Self {
+            metadata,
+            process: Arc::new(Mutex::new(process)),
+            capabilities: Value::Null,
+        })
+    }
+
+    /// Shared handle to the plugin process
+    pub fn process(&self) -> SharedPluginProcess {
+        self.process.clone()
+    }
+
+    fn request(&self, method: &str, params: Value) -> Result<Value, PluginError> {
+        self.process.lock().unwrap().request(method, params)
+    }
+
+    fn event_handler(&self, event: &str) -> ProcessEventHandler {
+        ProcessEventHandler {
+            plugin_id: self.metadata.id.clone(),
+            process: self.process.clone(),
+            method: format!("event/{}", event),
+        }
+    }
+}
+
+impl PluginAPI for ProcessPluginAPI {
+    fn get_metadata(&self) -> &PluginMetadata {
+        &self.metadata
+    }
+
+    fn initialize(&mut self, context: ExtensionContext) -> Result<(), PluginError> {
+        let settings: serde_json::Map<String, Value> = context.config.settings.iter()
+            .map(|(key, value)| (key.clone(), value.to_json()))
+            .collect();
+
+        self.capabilities = self.request("initialize", json!({
+            "pluginId": context.plugin_id,
+            "ideVersion": context.ide_version,
+            "settings": settings,
+        }))?;
+        Ok(())
+    }
+
+    fn activate(&mut self) -> Result<(), PluginError> {
+        self.request("activate", Value::Null).map(|_| ())
+    }
+
+    fn deactivate(&mut self) -> Result<(), PluginError> {
+        self.request("deactivate", Value::Null).map(|_| ())
+    }
+
+    fn handle_request(&mut self, request: Value) -> Result<Value, PluginError> {
+        // `{method, params}` objects are forwarded as-is; anything else goes to `handleRequest`
+        match request.get("method").and_then(Value::as_str) {
+            Some(method) => {
+                let params = request.get("params").cloned().unwrap_or(Value::Null);
+                self.request(method, params)
+            }
+            None => self.request("handleRequest", request),
+        }
+    }
+
+    fn render_ui(&mut self, ui: &mut Ui) -> Result<(), PluginError> {
+        let mut process = self.process.lock().unwrap();
+        let status = if process.is_running() { "running" } else { "exited" };
+        ui.label(format!("Process {} ({})", process.pid(), status));
+        Ok(())
+    }
+
+    fn get_settings_schema(&self) -> Result<Value, PluginError> {
+        self.request("getSettingsSchema", Value::Null)
+    }
+
+    fn update_settings(&mut self, settings: BTreeMap<String, PluginSettingValue>) -> Result<(), PluginError> {
+        let settings: serde_json::Map<String, Value> = settings.iter()
+            .map(|(key, value)| (key.clone(), value.to_json()))
+            .collect();
+        self.request("updateSettings", json!({ "settings": settings })).map(|_| ())
+    }
+
+    fn contributions(&mut self) -> PluginContributions {
+        let mut contributions = PluginContributions::default();
+
+        if let Some(commands) = self.capabilities.get("commands").and_then(Value::as_array) {
+            for command in commands {
+                let Some(id) = command.get("id").and_then(Value::as_str) else { continue };
+                let text = |key: &str| command.get(key).and_then(Value::as_str).map(str::to_string);
+
+                contributions.commands.push(PluginCommand {
+                    id: id.to_string(),
+                    name: text("name").unwrap_or_else(|| id.to_string()),
+                    description: text("description").unwrap_or_default(),
+                    shortcut: text("shortcut"),
+                    handler: Box::new(ProcessCommandHandler {
+                        process: self.process.clone(),
+                        command_id: id.to_string(),
+                    }),
+                });
+            }
+        }
+
+        if let Some(events) = self.capabilities.get("events").and_then(Value::as_array) {
+            let hooks = &mut contributions.hooks;
+            for event in events.iter().filter_map(Value::as_str) {
+                match event {
+                    "fileOpened" => hooks.file_opened.push(Box::new(self.event_handler(event))),
+                    "fileSaved" => hooks.file_saved.push(Box::new(self.event_handler(event))),
+                    "fileClosed" => hooks.file_closed.push(Box::new(self.event_handler(event))),
+                    "fileChanged" => hooks.file_changed.push(Box::new(self.event_handler(event))),
+                    "selectionChanged" => hooks.selection_changed.push(Box::new(self.event_handler(event))),
+                    "buildStarted" => hooks.build_started.push(Box::new(self.event_handler(event))),
+                    "buildCompleted" => hooks.build_completed.push(Box::new(self.event_handler(event))),
+                    "gitCommit" => hooks.git_commit.push(Box::new(self.event_handler(event))),
+                    _ => {}
+                }
+            }
+        }
+
+        if let Some(points) = self.capabilities.get("extensionPoints").and_then(Value::as_array) {
+            for point in points {
+                let id = point.get("id").and_then(Value::as_str);
+                let point_type = point.get("type").and_then(Value::as_str).and_then(ExtensionPointType::from_name);
+                if let (Some(id), Some(point_type)) = (id, point_type) {
+                    contributions.extension_points.push(ExtensionPoint {
+                        id: id.to_string(),
+                        point_type,
+                        implementation: Box::new(ProcessExtension {
+                            process: self.process.clone(),
+                            extension_id: id.to_string(),
+                        }),
+                    });
+                }
+            }
+        }
+
+        contributions
+    }
+
+    fn drain_messages(&mut self) -> Vec<PluginMessage> {
+        self.process.lock().unwrap().drain_messages()
+    }
+}
+
+/// Runs a plugin command by sending `executeCommand` to the plugin process
+pub struct ProcessCommandHandler {
+    process: SharedPluginProcess,
+    command_id: String,
+}
+
+impl CommandHandler for ProcessCommandHandler {
+    fn execute(&mut self, context: &CommandContext) -> Result<(), PluginError> {
+        let cursor = context.cursor_position.as_ref()
+            .map(|p| json!({ "line": p.line, "character": p.character }));
+
+        self.process.lock().unwrap().request("executeCommand", json!({
+            "command": self.command_id,
+            "context": {
+                "activeFile": context.active_file,
+                "selection": context.selection,
+                "cursor": cursor,
+            },
+        })).map(|_| ())
+    }
+
+    fn can_execute(&self, _context: &CommandContext) -> bool {
+        self.process.lock().unwrap().is_running()
+    }
+}
+
+/// Forwards IDE events to the plugin process as notifications
+pub struct ProcessEventHandler {
+    plugin_id: String,
+    process: SharedPluginProcess,
+    method: String,
+}
+
+impl ProcessEventHandler {
+    fn send(&self, params: Value) -> Result<(), PluginError> {
+        self.process.lock().unwrap().notify(&self.method, params)
+    }
+}
+
+impl FileEventHandler for ProcessEventHandler {
+    fn handle_file_event(&mut self, event: FileEvent) -> Result<(), PluginError> {
+        let (kind, old_path) = match &event.event_type {
+            FileEventType::Opened => ("opened", None),
+            FileEventType::Saved => ("saved", None),
+            FileEventType::Closed => ("closed", None),
+            FileEventType::Modified => ("modified", None),
+            FileEventType::Renamed { old_path } => ("renamed", Some(old_path.clone())),
+            FileEventType::Deleted => ("deleted", None),
+        };
+        self.send(json!({ "path": event.file_path, "kind": kind, "oldPath": old_path }))
+    }
+
+    fn plugin_id(&self) -> Option<&str> {
+        Some(&self.plugin_id)
+    }
+}
+
+impl SelectionEventHandler for ProcessEventHandler {
+    fn handle_selection_event(&mut self, event: SelectionEvent) -> Result<(), PluginError> {
+        let range = event.range.as_ref().map(|r| json!({
+            "start": { "line": r.start.line, "character": r.start.character },
+            "end": { "line": r.end.line, "character": r.end.character },
+        }));
+        self.send(json!({ "path": event.file_path, "selection": event.selection, "range": range }))
+    }
+
+    fn plugin_id(&self) -> Option<&str> {
+        Some(&self.plugin_id)
+    }
+}
+
+impl BuildEventHandler for ProcessEventHandler {
+    fn handle_build_event(&mut self, event: BuildEvent) -> Result<(), PluginError> {
+        let (success, error) = match &event.event_type {
+            BuildEventType::Started => (None, None),
+            BuildEventType::Completed { success } => (Some(*success), None),
+            BuildEventType::Failed { error } => (Some(false), Some(error.clone())),
+        };
+        self.send(json!({
+            "projectPath": event.project_path,
+            "success": success,
+            "error": error,
+            "details": event.details,
+        }))
+    }
+
+    fn plugin_id(&self) -> Option<&str> {
+        Some(&self.plugin_id)
+    }
+}
+
+impl GitEventHandler for ProcessEventHandler {
+    fn handle_git_event(&mut self, event: GitEvent) -> Result<(), PluginError> {
+        let kind = match &event.event_type {
+            GitEventType::Commit { hash } => json!({ "type": "commit", "hash": hash }),
+            GitEventType::Branch { name } => json!({ "type": "branch", "name": name }),
+            GitEventType::Push => json!({ "type": "push" }),
+            GitEventType::Pull => json!({ "type": "pull" }),
+            GitEventType::Merge { branch } => json!({ "type": "merge", "branch": branch }),
+        };
+        self.send(json!({
+            "repositoryPath": event.repository_path,
+            "event": kind,
+            "details": event.details,
+        }))
+    }
+
+    fn plugin_id(&self) -> Option<&str> {
+        Some(&self.plugin_id)
+    }
+}
+
+/// Extension point served by the plugin process through `extension/request`
+pub struct ProcessExtension {
+    process: SharedPluginProcess,
+    extension_id: String,
+}
+
+impl Extension for ProcessExtension {
+    fn initialize(&mut self, _context: &ExtensionContext) -> Result<(), PluginError> {
+        // The plugin process was initialized when it was loaded
+        Ok(())
+    }
+
+    fn activate(&mut self) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    fn deactivate(&mut self) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    fn handle_request(&mut self, request: ExtensionRequest) -> Result<ExtensionResponse, PluginError> {
+        let params = match &request {
+            ExtensionRequest::GetCompletions { file_path, position, context } => json!({
+                "kind": "getCompletions",
+                "path": file_path,
+                "position": { "line": position.line, "character": position.character },
+                "context": context,
+            }),
+            ExtensionRequest::FormatCode { file_path, content, options } => json!({
+                "kind": "formatCode",
+                "path": file_path,
+                "content": content,
+                "options": {
+                    "tabSize": options.tab_size,
+                    "insertSpaces": options.insert_spaces,
+                    "trimTrailingWhitespace": options.trim_trailing_whitespace,
+                },
+            }),
+            ExtensionRequest::GetDiagnostics { file_path, content } => json!({
+                "kind": "getDiagnostics",
+                "path": file_path,
+                "content": content,
+            }),
+            ExtensionRequest::ExecuteCommand { command, args } => json!({
+                "kind": "executeCommand",
+                "command": command,
+                "args": args,
+            }),
+            ExtensionRequest::Custom { request_type, data } => json!({
+                "kind": "custom",
+                "requestType": request_type,
+                "data": data,
+            }),
+        };
+
+        let mut params = params;
+        params["extensionPoint"] = Value::from(self.extension_id.clone());
+        let result = self.process.lock().unwrap().request("extension/request", params)?;
+
+        // Responses use LSP shapes so existing parsers can be reused
+        Ok(match request {
+            ExtensionRequest::GetCompletions { .. } => {
+                let list = if result.is_array() { json!({ "items": result }) } else { result };
+                ExtensionResponse::Completions(LspClient::parse_completion_items(&list))
+            }
+            ExtensionRequest::FormatCode { content, .. } => {
+                ExtensionResponse::FormattedCode(result.as_str().map(str::to_string).unwrap_or(content))
+            }
+            ExtensionRequest::GetDiagnostics { .. } => {
+                ExtensionResponse::Diagnostics(parse_diagnostics(&result))
+            }
+            ExtensionRequest::ExecuteCommand { .. } => {
+                let text = match &result {
+                    Value::String(text) => text.clone(),
+                    Value::Null => String::new(),
+                    other => other.to_string(),
+                };
+                ExtensionResponse::CommandResult(text)
+            }
+            ExtensionRequest::Custom { .. } => ExtensionResponse::Custom(result),
+        })
+    }
+}
+
+/// Parse an array of LSP diagnostics
+fn parse_diagnostics(value: &Value) -> Vec<Diagnostic> {
+    value.as_array().map(|items| {
+        items.iter().filter_map(|item| {
+            let message = item.get("message").and_then(Value::as_str)?;
+            Some(Diagnostic {
+                range: item.get("range").map(LspClient::parse_range).unwrap_or_else(|| Range {
+                    start: crate::editor::lsp_integration::Position { line: 0, character: 0 },
+                    end: crate::editor::lsp_integration::Position { line: 0, character: 0 },
+                }),
+                severity: LspClient::parse_diagnostic_severity(item.get("severity")),
+                code: item.get("code").and_then(Value::as_str).map(str::to_string),
+                source: item.get("source").and_then(Value::as_str).map(str::to_string),
+                message: message.to_string(),
+                related_information: None,
+            })
+        }).collect()
+    }).unwrap_or_default()
+}
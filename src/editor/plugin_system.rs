@@ -7,6 +7,8 @@ use egui::*;
 use std::collections::{HashMap, BTreeMap};
 use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use crate::core::event_bus::{global_event_bus, EventHandler, IdeEvent, SubscriberId};
 
 /// Plugin system manager
 pub struct PluginManager {
@@ -20,6 +22,12 @@ pub struct PluginManager {
     pub settings: PluginSettings,
     /// Plugin marketplace connection
     pub marketplace: PluginMarketplace,
+    /// IDE events received from the global event bus, waiting to be dispatched to hooks
+    event_queue: Arc<Mutex<Vec<IdeEvent>>>,
+    /// Event bus subscription feeding `event_queue`
+    event_subscription: Option<SubscriberId>,
+    /// Messages produced by plugins or by the manager on their behalf
+    messages: Vec<PluginMessage>,
 }
 
 /// Individual plugin instance
@@ -61,8 +69,12 @@ pub struct PluginMetadata {
     pub min_ide_version: String,
     /// Plugin dependencies
     pub dependencies: Vec<PluginDependency>,
-    /// Plugin entry point
+    /// Plugin entry point: executable path relative to the plugin directory.
+    /// Plugins without an entry point are declarative and run no code.
     pub entry_point: String,
+    /// Arguments passed to the entry point executable
+    #[serde(default)]
+    pub args: Vec<String>,
     /// Plugin icon
     pub icon: Option<String>,
     /// Plugin category
@@ -152,6 +164,45 @@ pub enum PluginSettingValue {
     Object(BTreeMap<String, PluginSettingValue>),
 }
 
+impl PluginSettingValue {
+    /// Convert to a plain JSON value as seen by plugin processes
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            PluginSettingValue::String(value) => serde_json::Value::from(value.clone()),
+            PluginSettingValue::Integer(value) => serde_json::Value::from(*value),
+            PluginSettingValue::Float(value) => serde_json::Value::from(*value),
+            PluginSettingValue::Boolean(value) => serde_json::Value::from(*value),
+            PluginSettingValue::Array(values) => {
+                serde_json::Value::Array(values.iter().map(|v| v.to_json()).collect())
+            }
+            PluginSettingValue::Object(map) => serde_json::Value::Object(
+                map.iter().map(|(k, v)| (k.clone(), v.to_json())).collect()
+            ),
+        }
+    }
+
+    /// Convert from a plain JSON value; `null` has no setting equivalent
+    pub fn from_json(value: &serde_json::Value) -> Option<Self> {
+        match value {
+            serde_json::Value::Null => None,
+            serde_json::Value::Bool(b) => Some(PluginSettingValue::Boolean(*b)),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => Some(PluginSettingValue::Integer(i)),
+                None => n.as_f64().map(PluginSettingValue::Float),
+            },
+            serde_json::Value::String(s) => Some(PluginSettingValue::String(s.clone())),
+            serde_json::Value::Array(values) => Some(PluginSettingValue::Array(
+                values.iter().filter_map(Self::from_json).collect()
+            )),
+            serde_json::Value::Object(map) => Some(PluginSettingValue::Object(
+                map.iter()
+                    .filter_map(|(k, v)| Self::from_json(v).map(|v| (k.clone(), v)))
+                    .collect()
+            )),
+        }
+    }
+}
+
 /// Extension point for plugin functionality
 pub struct ExtensionPoint {
     /// Extension point ID
@@ -193,6 +244,28 @@ pub enum ExtensionPointType {
     SettingsProvider,
 }
 
+impl ExtensionPointType {
+    /// Parse the extension point name used in plugin manifests and the plugin protocol
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "languageProvider" => Some(ExtensionPointType::LanguageProvider),
+            "completionProvider" => Some(ExtensionPointType::CompletionProvider),
+            "diagnosticsProvider" => Some(ExtensionPointType::DiagnosticsProvider),
+            "formatterProvider" => Some(ExtensionPointType::FormatterProvider),
+            "codeActionProvider" => Some(ExtensionPointType::CodeActionProvider),
+            "themeProvider" => Some(ExtensionPointType::ThemeProvider),
+            "commandProvider" => Some(ExtensionPointType::CommandProvider),
+            "fileSystemProvider" => Some(ExtensionPointType::FileSystemProvider),
+            "buildTaskProvider" => Some(ExtensionPointType::BuildTaskProvider),
+            "debugAdapter" => Some(ExtensionPointType::DebugAdapter),
+            "gitIntegration" => Some(ExtensionPointType::GitIntegration),
+            "widgetProvider" => Some(ExtensionPointType::WidgetProvider),
+            "settingsProvider" => Some(ExtensionPointType::SettingsProvider),
+            _ => None,
+        }
+    }
+}
+
 /// Extension trait for plugin functionality
 pub trait Extension: Send + Sync {
     /// Initialize the extension
@@ -308,6 +381,8 @@ pub struct PluginRegistry {
     pub installed: HashMap<String, PluginMetadata>,
     /// Plugin directory
     pub plugin_directory: PathBuf,
+    /// Directory each installed plugin was discovered in
+    pub install_paths: HashMap<String, PathBuf>,
     /// Registry cache
     pub cache: RegistryCache,
 }
@@ -331,6 +406,8 @@ pub struct PluginHooks {
     pub file_saved: Vec<Box<dyn FileEventHandler>>,
     /// File close hooks
     pub file_closed: Vec<Box<dyn FileEventHandler>>,
+    /// File modified/renamed/deleted hooks
+    pub file_changed: Vec<Box<dyn FileEventHandler>>,
     /// Editor selection changed hooks
     pub selection_changed: Vec<Box<dyn SelectionEventHandler>>,
     /// Build started hooks
@@ -344,21 +421,41 @@ pub struct PluginHooks {
 /// File event handler trait
 pub trait FileEventHandler: Send + Sync {
     fn handle_file_event(&mut self, event: FileEvent) -> Result<(), PluginError>;
+
+    /// ID of the plugin that registered this handler
+    fn plugin_id(&self) -> Option<&str> {
+        None
+    }
 }
 
 /// Selection event handler trait
 pub trait SelectionEventHandler: Send + Sync {
     fn handle_selection_event(&mut self, event: SelectionEvent) -> Result<(), PluginError>;
+
+    /// ID of the plugin that registered this handler
+    fn plugin_id(&self) -> Option<&str> {
+        None
+    }
 }
 
 /// Build event handler trait
 pub trait BuildEventHandler: Send + Sync {
     fn handle_build_event(&mut self, event: BuildEvent) -> Result<(), PluginError>;
+
+    /// ID of the plugin that registered this handler
+    fn plugin_id(&self) -> Option<&str> {
+        None
+    }
 }
 
 /// Git event handler trait
 pub trait GitEventHandler: Send + Sync {
     fn handle_git_event(&mut self, event: GitEvent) -> Result<(), PluginError>;
+
+    /// ID of the plugin that registered this handler
+    fn plugin_id(&self) -> Option<&str> {
+        None
+    }
 }
 
 /// File events
@@ -487,6 +584,47 @@ pub trait PluginAPI: Send + Sync {
     
     /// Update plugin settings
     fn update_settings(&mut self, settings: BTreeMap<String, PluginSettingValue>) -> Result<(), PluginError>;
+
+    /// Commands, extension points and hooks the plugin contributes.
+    /// Called once after a successful `initialize`.
+    fn contributions(&mut self) -> PluginContributions {
+        PluginContributions::default()
+    }
+
+    /// Take messages (logs, notifications) the plugin produced since the last call
+    fn drain_messages(&mut self) -> Vec<PluginMessage> {
+        Vec::new()
+    }
+}
+
+/// Everything a plugin registers with the IDE when it is loaded
+#[derive(Default)]
+pub struct PluginContributions {
+    /// Commands exposed in the IDE
+    pub commands: Vec<PluginCommand>,
+    /// Extension points implemented by the plugin
+    pub extension_points: Vec<ExtensionPoint>,
+    /// Event hooks the plugin subscribes to
+    pub hooks: PluginHooks,
+}
+
+/// Message emitted by a plugin for display in the IDE
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginMessage {
+    /// Plugin that produced the message
+    pub plugin_id: String,
+    /// Severity of the message
+    pub level: PluginMessageLevel,
+    /// Message text
+    pub text: String,
+}
+
+/// Severity of a plugin message
+#[derive(Debug, Clone, PartialEq)]
+pub enum PluginMessageLevel {
+    Info,
+    Warning,
+    Error,
 }
 
 // Helper types from other modules
@@ -514,11 +652,17 @@ impl PluginManager {
             .unwrap_or_else(|| PathBuf::from("."))
             .join("ide-plugins");
 
+        Self::with_plugin_directory(plugin_dir)
+    }
+
+    /// Create a plugin manager that discovers plugins in a specific directory
+    pub fn with_plugin_directory(plugin_dir: PathBuf) -> Self {
         Self {
             plugins: HashMap::new(),
             registry: PluginRegistry {
                 installed: HashMap::new(),
                 plugin_directory: plugin_dir,
+                install_paths: HashMap::new(),
                 cache: RegistryCache {
                     last_scan: None,
                     cached_plugins: Vec::new(),
@@ -532,9 +676,25 @@ impl PluginManager {
                 available_plugins: Vec::new(),
                 update_cache: HashMap::new(),
             },
+            event_queue: Arc::new(Mutex::new(Vec::new())),
+            event_subscription: None,
+            messages: Vec::new(),
         }
     }
 
+    /// Subscribe to the global event bus so plugin hooks see file and git events.
+    ///
+    /// Events are queued and delivered by [`PluginManager::process_ide_events`],
+    /// which the IDE calls once per frame.
+    pub fn connect_event_bus(&mut self) {
+        if self.event_subscription.is_some() {
+            return;
+        }
+
+        let bridge = PluginEventBridge { queue: self.event_queue.clone() };
+        self.event_subscription = Some(global_event_bus().subscribe(bridge));
+    }
+
     /// Scan for installed plugins
     pub fn scan_plugins(&mut self) -> Result<(), PluginError> {
         if !self.registry.plugin_directory.exists() {
@@ -543,6 +703,7 @@ impl PluginManager {
         }
 
         self.registry.installed.clear();
+        self.registry.install_paths.clear();
 
         for entry in std::fs::read_dir(&self.registry.plugin_directory)
             .map_err(|e| PluginError::FileSystemError(e.to_string()))? 
@@ -552,6 +713,7 @@ impl PluginManager {
 
             if path.is_dir() {
                 if let Ok(metadata) = self.load_plugin_metadata(&path) {
+                    self.registry.install_paths.insert(metadata.id.clone(), path.clone());
                     self.registry.installed.insert(metadata.id.clone(), metadata);
                 }
             }
@@ -573,7 +735,7 @@ impl PluginManager {
         Ok(metadata)
     }
 
-    /// Load a plugin
+    /// Load a plugin: start its code, register its contributions and activate it
     pub fn load_plugin(&mut self, plugin_id: &str) -> Result<(), PluginError> {
         if self.plugins.contains_key(plugin_id) {
             return Err(PluginError::PluginAlreadyLoaded(plugin_id.to_string()));
@@ -587,33 +749,77 @@ impl PluginManager {
         self.check_dependencies(&metadata)?;
 
         // Create plugin instance
-        let plugin = self.create_plugin_instance(metadata)?;
-        
-        // Initialize plugin
-        // Note: This would typically involve loading dynamic libraries or WASM modules
-        // For now, we'll use a placeholder implementation
+        let mut plugin = self.create_plugin_instance(metadata)?;
+        plugin.state = PluginState::Loading;
+
+        let context = ExtensionContext {
+            plugin_id: plugin_id.to_string(),
+            ide_version: env!("CARGO_PKG_VERSION").to_string(),
+            config: plugin.config.clone(),
+            apis: HashMap::new(),
+        };
+        plugin.api.initialize(context)
+            .map_err(|e| PluginError::InitializationFailed(format!("{}: {}", plugin_id, e)))?;
+
+        let contributions = plugin.api.contributions();
+        plugin.commands = contributions.commands;
+        plugin.extension_points = contributions.extension_points;
+        self.hooks.merge(contributions.hooks);
+
+        if plugin.config.enabled {
+            match plugin.api.activate() {
+                Ok(()) => plugin.state = PluginState::Active,
+                Err(e) => plugin.state = PluginState::Error(e.to_string()),
+            }
+        } else {
+            plugin.state = PluginState::Paused;
+        }
 
         self.plugins.insert(plugin_id.to_string(), plugin);
         Ok(())
     }
 
-    /// Create plugin instance (placeholder implementation)
+    /// Load every plugin listed in the auto-load settings, recording failures as messages
+    pub fn load_auto_load_plugins(&mut self) {
+        let plugin_ids = self.settings.auto_load_plugins.clone();
+        for plugin_id in plugin_ids {
+            if let Err(e) = self.load_plugin(&plugin_id) {
+                self.push_message(&plugin_id, PluginMessageLevel::Error, e.to_string());
+            }
+        }
+    }
+
+    /// Create plugin instance.
+    ///
+    /// Plugins with an entry point run out of process (see [`crate::editor::plugin_host`]);
+    /// declarative plugins without one get a no-op API.
     fn create_plugin_instance(&self, metadata: PluginMetadata) -> Result<Plugin, PluginError> {
-        // This would typically load the plugin from a dynamic library or WASM module
-        // For now, create a basic placeholder
-        
+        let mut settings = self.settings.global_settings.clone();
+        if let Some(plugin_settings) = self.settings.plugin_settings.get(&metadata.id) {
+            settings.extend(plugin_settings.clone());
+        }
+
+        let api: Box<dyn PluginAPI> = if metadata.entry_point.is_empty() {
+            Box::new(PlaceholderPluginAPI { metadata: metadata.clone() })
+        } else {
+            let plugin_dir = self.registry.install_paths.get(&metadata.id)
+                .cloned()
+                .unwrap_or_else(|| self.registry.plugin_directory.join(&metadata.id));
+            Box::new(crate::editor::plugin_host::ProcessPluginAPI::spawn(metadata.clone(), &plugin_dir)?)
+        };
+
         Ok(Plugin {
-            metadata: metadata.clone(),
+            metadata,
             state: PluginState::Unloaded,
             config: PluginConfig {
-                settings: BTreeMap::new(),
+                settings,
                 enabled: true,
                 auto_load: false,
                 priority: 0,
             },
             extension_points: Vec::new(),
             commands: Vec::new(),
-            api: Box::new(PlaceholderPluginAPI { metadata }),
+            api,
         })
     }
 
@@ -635,7 +841,10 @@ impl PluginManager {
     pub fn unload_plugin(&mut self, plugin_id: &str) -> Result<(), PluginError> {
         if let Some(mut plugin) = self.plugins.remove(plugin_id) {
             plugin.state = PluginState::Unloading;
+            self.hooks.remove_plugin(plugin_id);
             let _ = plugin.api.deactivate();
+            let messages = plugin.api.drain_messages();
+            self.messages.extend(messages);
             Ok(())
         } else {
             Err(PluginError::PluginNotFound(plugin_id.to_string()))
@@ -666,16 +875,38 @@ impl PluginManager {
 
     /// Execute plugin command
     pub fn execute_plugin_command(&mut self, command_id: &str) -> Result<(), PluginError> {
+        self.execute_plugin_command_with_context(command_id, None, None, None)
+    }
+
+    /// Execute plugin command with the current editor context
+    pub fn execute_plugin_command_with_context(
+        &mut self,
+        command_id: &str,
+        active_file: Option<PathBuf>,
+        selection: Option<String>,
+        cursor_position: Option<Position>,
+    ) -> Result<(), PluginError> {
         // Find plugin that owns this command
         for plugin in self.plugins.values_mut() {
             for command in &mut plugin.commands {
                 if command.id == command_id {
+                    if plugin.state != PluginState::Active {
+                        return Err(PluginError::ExecutionError(format!(
+                            "Plugin {} is not active", plugin.metadata.id
+                        )));
+                    }
+
                     let context = CommandContext {
-                        active_file: None,
-                        selection: None,
-                        cursor_position: None,
+                        active_file,
+                        selection,
+                        cursor_position,
                         config: plugin.config.clone(),
                     };
+                    if !command.handler.can_execute(&context) {
+                        return Err(PluginError::ExecutionError(format!(
+                            "Command {} cannot run in the current context", command_id
+                        )));
+                    }
                     return command.handler.execute(&context);
                 }
             }
@@ -683,6 +914,141 @@ impl PluginManager {
         Err(PluginError::CommandNotFound(command_id.to_string()))
     }
 
+    /// Commands registered by loaded plugins, as (plugin id, command) pairs
+    pub fn list_commands(&self) -> Vec<(&str, &PluginCommand)> {
+        let mut commands: Vec<_> = self.plugins.values()
+            .flat_map(|plugin| plugin.commands.iter().map(move |c| (plugin.metadata.id.as_str(), c)))
+            .collect();
+        commands.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+        commands
+    }
+
+    /// Deliver IDE events queued from the event bus to plugin hooks
+    pub fn process_ide_events(&mut self) {
+        let events: Vec<IdeEvent> = std::mem::take(&mut *self.event_queue.lock().unwrap());
+
+        for event in events {
+            let (file_path, event_type) = match event {
+                IdeEvent::FileOpened { path, .. } => (path, FileEventType::Opened),
+                IdeEvent::FileSaved { path, .. } => (path, FileEventType::Saved),
+                IdeEvent::FileClosed { path, .. } => (path, FileEventType::Closed),
+                IdeEvent::FileModified { path, .. } => (path, FileEventType::Modified),
+                IdeEvent::FileDeleted { path } => (path, FileEventType::Deleted),
+                IdeEvent::FileRenamed { old_path, new_path } => {
+                    (new_path, FileEventType::Renamed { old_path })
+                }
+                IdeEvent::GitCommitted { repository_path, hash, message } => {
+                    self.dispatch_git_event(GitEvent {
+                        repository_path,
+                        event_type: GitEventType::Commit { hash },
+                        details: message,
+                    });
+                    continue;
+                }
+                _ => continue,
+            };
+
+            self.dispatch_file_event(FileEvent {
+                file_path,
+                event_type,
+                timestamp: std::time::SystemTime::now(),
+            });
+        }
+    }
+
+    /// Deliver a file event to the hooks of active plugins
+    pub fn dispatch_file_event(&mut self, event: FileEvent) {
+        let handlers = match event.event_type {
+            FileEventType::Opened => &mut self.hooks.file_opened,
+            FileEventType::Saved => &mut self.hooks.file_saved,
+            FileEventType::Closed => &mut self.hooks.file_closed,
+            _ => &mut self.hooks.file_changed,
+        };
+
+        for handler in handlers.iter_mut() {
+            if !Self::is_handler_active(&self.plugins, handler.plugin_id()) {
+                continue;
+            }
+            if let Err(e) = handler.handle_file_event(event.clone()) {
+                Self::record_hook_error(&mut self.messages, handler.plugin_id(), e);
+            }
+        }
+    }
+
+    /// Deliver a selection event to the hooks of active plugins
+    pub fn dispatch_selection_event(&mut self, event: SelectionEvent) {
+        for handler in self.hooks.selection_changed.iter_mut() {
+            if !Self::is_handler_active(&self.plugins, handler.plugin_id()) {
+                continue;
+            }
+            if let Err(e) = handler.handle_selection_event(event.clone()) {
+                Self::record_hook_error(&mut self.messages, handler.plugin_id(), e);
+            }
+        }
+    }
+
+    /// Deliver a build event to the hooks of active plugins
+    pub fn dispatch_build_event(&mut self, event: BuildEvent) {
+        let handlers = match event.event_type {
+            BuildEventType::Started => &mut self.hooks.build_started,
+            _ => &mut self.hooks.build_completed,
+        };
+
+        for handler in handlers.iter_mut() {
+            if !Self::is_handler_active(&self.plugins, handler.plugin_id()) {
+                continue;
+            }
+            if let Err(e) = handler.handle_build_event(event.clone()) {
+                Self::record_hook_error(&mut self.messages, handler.plugin_id(), e);
+            }
+        }
+    }
+
+    /// Deliver a git event to the hooks of active plugins
+    pub fn dispatch_git_event(&mut self, event: GitEvent) {
+        for handler in self.hooks.git_commit.iter_mut() {
+            if !Self::is_handler_active(&self.plugins, handler.plugin_id()) {
+                continue;
+            }
+            if let Err(e) = handler.handle_git_event(event.clone()) {
+                Self::record_hook_error(&mut self.messages, handler.plugin_id(), e);
+            }
+        }
+    }
+
+    /// Take all pending plugin messages
+    pub fn drain_messages(&mut self) -> Vec<PluginMessage> {
+        for plugin in self.plugins.values_mut() {
+            let messages = plugin.api.drain_messages();
+            self.messages.extend(messages);
+        }
+        std::mem::take(&mut self.messages)
+    }
+
+    /// Handlers without an owner always run; plugin handlers run only while the plugin is active
+    fn is_handler_active(plugins: &HashMap<String, Plugin>, plugin_id: Option<&str>) -> bool {
+        match plugin_id {
+            Some(id) => plugins.get(id).is_some_and(|p| p.state == PluginState::Active),
+            None => true,
+        }
+    }
+
+    fn record_hook_error(messages: &mut Vec<PluginMessage>, plugin_id: Option<&str>, error: PluginError) {
+        messages.push(PluginMessage {
+            plugin_id: plugin_id.unwrap_or("unknown").to_string(),
+            level: PluginMessageLevel::Error,
+            text: error.to_string(),
+        });
+    }
+
+    fn push_message(&mut self, plugin_id: &str, level: PluginMessageLevel, text: String) {
+        self.messages.push(PluginMessage {
+            plugin_id: plugin_id.to_string(),
+            level,
+            text,
+        });
+    }
+
     /// Render plugin management UI
     pub fn render_plugin_manager_ui(&mut self, ui: &mut Ui) {
         ui.heading("Plugin Manager");
@@ -755,11 +1121,66 @@ impl PluginManager {
                     ui.label(&description);
                     
                     if ui.small_button("Load").clicked() {
-                        let _ = self.load_plugin(&id);
+                        if let Err(e) = self.load_plugin(&id) {
+                            self.push_message(&id, PluginMessageLevel::Error, e.to_string());
+                        }
                     }
                 });
             }
         });
+
+        // Commands contributed by plugins
+        ui.collapsing("Plugin Commands", |ui| {
+            let commands: Vec<_> = self.list_commands().into_iter()
+                .map(|(_, command)| (command.id.clone(), command.name.clone(), command.description.clone()))
+                .collect();
+
+            if commands.is_empty() {
+                ui.label("No plugin commands registered");
+            }
+
+            for (id, name, description) in commands {
+                if ui.button(&name).on_hover_text(&description).clicked() {
+                    if let Err(e) = self.execute_plugin_command(&id) {
+                        self.push_message(&id, PluginMessageLevel::Error, e.to_string());
+                    }
+                }
+            }
+        });
+    }
+}
+
+impl Drop for PluginManager {
+    fn drop(&mut self) {
+        if let Some(subscriber_id) = self.event_subscription.take() {
+            global_event_bus().unsubscribe(subscriber_id);
+        }
+    }
+}
+
+/// Event bus handler that queues IDE events for the plugin manager
+struct PluginEventBridge {
+    queue: Arc<Mutex<Vec<IdeEvent>>>,
+}
+
+impl EventHandler for PluginEventBridge {
+    fn handle_event(&self, event: &IdeEvent) {
+        match event {
+            IdeEvent::FileOpened { .. }
+            | IdeEvent::FileSaved { .. }
+            | IdeEvent::FileClosed { .. }
+            | IdeEvent::FileModified { .. }
+            | IdeEvent::FileDeleted { .. }
+            | IdeEvent::FileRenamed { .. }
+            | IdeEvent::GitCommitted { .. } => {
+                self.queue.lock().unwrap().push(event.clone());
+            }
+            _ => {}
+        }
+    }
+
+    fn handler_name(&self) -> &'static str {
+        "PluginEventBridge"
     }
 }
 
@@ -769,6 +1190,7 @@ impl Default for PluginHooks {
             file_opened: Vec::new(),
             file_saved: Vec::new(),
             file_closed: Vec::new(),
+            file_changed: Vec::new(),
             selection_changed: Vec::new(),
             build_started: Vec::new(),
             build_completed: Vec::new(),
@@ -777,6 +1199,45 @@ impl Default for PluginHooks {
     }
 }
 
+impl PluginHooks {
+    /// Append all handlers from another hook set
+    pub fn merge(&mut self, other: PluginHooks) {
+        self.file_opened.extend(other.file_opened);
+        self.file_saved.extend(other.file_saved);
+        self.file_closed.extend(other.file_closed);
+        self.file_changed.extend(other.file_changed);
+        self.selection_changed.extend(other.selection_changed);
+        self.build_started.extend(other.build_started);
+        self.build_completed.extend(other.build_completed);
+        self.git_commit.extend(other.git_commit);
+    }
+
+    /// Remove every handler registered by a plugin
+    pub fn remove_plugin(&mut self, plugin_id: &str) {
+        let keep = |owner: Option<&str>| owner != Some(plugin_id);
+        self.file_opened.retain(|h| keep(h.plugin_id()));
+        self.file_saved.retain(|h| keep(h.plugin_id()));
+        self.file_closed.retain(|h| keep(h.plugin_id()));
+        self.file_changed.retain(|h| keep(h.plugin_id()));
+        self.selection_changed.retain(|h| keep(h.plugin_id()));
+        self.build_started.retain(|h| keep(h.plugin_id()));
+        self.build_completed.retain(|h| keep(h.plugin_id()));
+        self.git_commit.retain(|h| keep(h.plugin_id()));
+    }
+
+    /// Total number of registered handlers
+    pub fn handler_count(&self) -> usize {
+        self.file_opened.len()
+            + self.file_saved.len()
+            + self.file_closed.len()
+            + self.file_changed.len()
+            + self.selection_changed.len()
+            + self.build_started.len()
+            + self.build_completed.len()
+            + self.git_commit.len()
+    }
+}
+
 /// Placeholder plugin API implementation
 pub struct PlaceholderPluginAPI {
     metadata: PluginMetadata,
@@ -835,6 +1296,8 @@ pub enum PluginError {
     InitializationFailed(String),
     #[error("Plugin execution error: {0}")]
     ExecutionError(String),
+    #[error("Plugin timed out: {0}")]
+    Timeout(String),
 }
 
 impl Default for PluginManager {
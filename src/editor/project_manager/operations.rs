@@ -156,12 +156,12 @@ impl ProjectOperations {
         for (i, component) in components.iter().enumerate() {
             let component_data = ComponentData {
                 component_type: component.name().to_string(),
-                properties: self.extract_component_properties(component),
+                properties: self.extract_component_properties(component.as_ref()),
                 position: (i as f32 * 50.0, i as f32 * 50.0), // Default positioning
                 size: (100.0, 30.0), // Default size
                 z_order: i as i32,
                 locked: false,
-                id: format!("{}_{}", component.name().to_lowercase(), i),
+                id: crate::editor::form_events::designer_component_id(component.name(), i),
             };
             
             project.designer_data.components.push(component_data);
@@ -618,10 +618,11 @@ impl AppUi {{
     }
 
     /// Extract properties from a component
-    fn extract_component_properties(&self, _component: &Box<dyn Component>) -> HashMap<String, String> {
-        // Placeholder implementation
-        // In a real implementation, this would extract actual component properties
-        HashMap::new()
+    fn extract_component_properties(&self, component: &dyn Component) -> HashMap<String, String> {
+        component.get_property_names()
+            .into_iter()
+            .filter_map(|name| component.get_property(&name).map(|value| (name, value)))
+            .collect()
     }
 
     /// Set executable permissions on Unix systems
@@ -401,7 +401,7 @@ impl ProjectScaffoldingEngine {
                     choices: Vec::new(),
                 },
             ],
-            dependencies: vec![],
+            dependencies: rcl_dependencies(),
             dev_dependencies: vec![],
             build_config: BuildConfiguration::default(),
             post_scripts: vec![],
@@ -590,6 +590,23 @@ impl ProjectScaffoldingEngine {
                             permissions: None,
                             is_template: true,
                         },
+                        FileTreeNode {
+                            name: "generated".to_string(),
+                            node_type: FileNodeType::Directory,
+                            content: None,
+                            children: vec![
+                                FileTreeNode {
+                                    name: "mod.rs".to_string(),
+                                    node_type: FileNodeType::File,
+                                    content: Some("//! Forms generated by the visual designer\n".to_string()),
+                                    children: vec![],
+                                    permissions: None,
+                                    is_template: false,
+                                },
+                            ],
+                            permissions: None,
+                            is_template: false,
+                        },
                     ],
                     permissions: None,
                     is_template: false,
@@ -734,7 +751,9 @@ readme = "README.md"
 
     /// Get basic main.rs template
     fn get_basic_main_rs(&self) -> String {
-        r#"fn main() {
+        r#"mod generated;
+
+fn main() {
     println!("Hello from {{project_name}}!");
     
     // Your application logic here
@@ -1039,6 +1058,7 @@ async fn health_check() -> Json<HealthResponse> {
 
         // Template grid
         let filtered_templates = self.get_filtered_templates();
+        let mut selected = None;
         
         egui::ScrollArea::vertical().show(ui, |ui| {
             egui::Grid::new("template_grid")
@@ -1070,8 +1090,7 @@ async fn health_check() -> Json<HealthResponse> {
                                 });
 
                                 if ui.button("Select Template").clicked() {
-                                    self.wizard.selected_template = Some(template.id.clone());
-                                    self.wizard.current_step = WizardStep::ProjectConfiguration;
+                                    selected = Some(template.id.clone());
                                 }
                             });
                         });
@@ -1079,6 +1098,11 @@ async fn health_check() -> Json<HealthResponse> {
                 });
         });
 
+        if selected.is_some() {
+            self.wizard.selected_template = selected;
+            self.wizard.current_step = WizardStep::ProjectConfiguration;
+        }
+
         None
     }
 
@@ -1314,29 +1338,182 @@ async fn health_check() -> Json<HealthResponse> {
     }
 
     /// Create a new project from template
-    pub fn create_project(&mut self, template_id: &str, config: &ProjectConfiguration, variables: &HashMap<String, String>) -> Result<(), String> {
+    ///
+    /// The template's file tree is written below `config.path`, with
+    /// `{{variable}}` placeholders replaced in names and template files.
+    /// Variables start from the template defaults, then the project
+    /// configuration (`project_name`, `author_name`, `description`,
+    /// `license`), then `variables`. Template dependencies missing from the
+    /// generated Cargo.toml are added, required post-creation scripts for
+    /// this platform are run and git is initialized if requested.
+    ///
+    /// Returns the root directory of the new project.
+    pub fn create_project(&mut self, template_id: &str, config: &ProjectConfiguration, variables: &HashMap<String, String>) -> Result<PathBuf, String> {
         let template = self.templates.get(template_id)
             .ok_or_else(|| "Template not found".to_string())?;
 
-        // TODO: Implement actual project creation
-        // This would:
-        // 1. Create directory structure
-        // 2. Process template files
-        // 3. Replace variables
-        // 4. Create Cargo.toml with dependencies
-        // 5. Run post-creation scripts
-        // 6. Initialize git if requested
+        let values = Self::resolve_variables(template, config, variables)?;
+        let project_path = config.path.join(substitute_variables(&template.file_tree.name, &values));
+        let occupied = std::fs::read_dir(&project_path).map(|mut entries| entries.next().is_some()).unwrap_or(false);
+        if occupied {
+            return Err(format!("Directory {} already exists and is not empty", project_path.display()));
+        }
+
+        Self::write_node(&template.file_tree, &config.path, &values)?;
+        Self::add_dependencies(&project_path.join("Cargo.toml"), "dependencies", &template.dependencies)?;
+        Self::add_dependencies(&project_path.join("Cargo.toml"), "dev-dependencies", &template.dev_dependencies)?;
+
+        for script in &template.post_scripts {
+            if script.required && script.platforms.iter().any(Platform::is_current) {
+                let dir = match &script.working_dir {
+                    Some(dir) => project_path.join(dir),
+                    None => project_path.clone(),
+                };
+                run_command(shell_command(&script.command).current_dir(dir), &script.name)?;
+            }
+        }
+
+        if config.init_git {
+            run_command(std::process::Command::new("git").arg("init").current_dir(&project_path), "git init")?;
+            if config.initial_commit {
+                run_command(std::process::Command::new("git").args(["add", "-A"]).current_dir(&project_path), "git add")?;
+                run_command(std::process::Command::new("git").args(["commit", "-m", "Initial commit"]).current_dir(&project_path), "git commit")?;
+            }
+        }
 
         // Update usage count
         self.templates.get_mut(template_id).unwrap().usage_count += 1;
-        
+
         // Add to recent templates
         self.recent_templates.insert(0, template_id.to_string());
         self.recent_templates.truncate(10);
 
+        Ok(project_path)
+    }
+
+    /// Collect variable values for a template and check them against its definitions
+    fn resolve_variables(template: &ProjectTemplate, config: &ProjectConfiguration, variables: &HashMap<String, String>) -> Result<HashMap<String, String>, String> {
+        let mut values: HashMap<String, String> = template.variables.iter()
+            .map(|variable| (variable.name.clone(), variable.default_value.clone()))
+            .collect();
+        values.insert("project_name".to_string(), config.name.clone());
+        values.insert("author_name".to_string(), config.author.clone());
+        values.insert("description".to_string(), config.description.clone());
+        values.insert("license".to_string(), config.license.clone());
+        values.extend(variables.iter().map(|(name, value)| (name.clone(), value.clone())));
+
+        for variable in &template.variables {
+            let value = values.get(&variable.name).map(String::as_str).unwrap_or("");
+            if value.is_empty() {
+                if variable.required {
+                    return Err(format!("Missing value for required variable '{}'", variable.name));
+                }
+                continue;
+            }
+            if let Some(pattern) = &variable.validation {
+                let regex = regex::Regex::new(pattern)
+                    .map_err(|e| format!("Invalid validation pattern for '{}': {}", variable.name, e))?;
+                if !regex.is_match(value) {
+                    return Err(format!("Invalid value '{}' for variable '{}'", value, variable.name));
+                }
+            }
+            if variable.var_type == VariableType::Choice && !variable.choices.is_empty() && !variable.choices.iter().any(|choice| choice == value) {
+                return Err(format!("Invalid value '{}' for variable '{}' (expected one of: {})", value, variable.name, variable.choices.join(", ")));
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Write a file tree node and its children below `parent`
+    fn write_node(node: &FileTreeNode, parent: &Path, values: &HashMap<String, String>) -> Result<(), String> {
+        let name = substitute_variables(&node.name, values);
+        if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+            return Err(format!("Invalid file name '{}' in template", name));
+        }
+        let path = parent.join(&name);
+
+        match &node.node_type {
+            FileNodeType::Directory => {
+                std::fs::create_dir_all(&path)
+                    .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+                for child in &node.children {
+                    Self::write_node(child, &path, values)?;
+                }
+            }
+            FileNodeType::File => {
+                let content = node.content.as_deref().unwrap_or("");
+                let content = if node.is_template {
+                    substitute_variables(content, values)
+                } else {
+                    content.to_string()
+                };
+                std::fs::write(&path, content)
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            }
+            FileNodeType::SymbolicLink(target) => {
+                #[cfg(unix)]
+                std::os::unix::fs::symlink(substitute_variables(target, values), &path)
+                    .map_err(|e| format!("Failed to create link {}: {}", path.display(), e))?;
+                #[cfg(not(unix))]
+                return Err(format!("Cannot create link {}: symbolic links are not supported on this platform", path.display()));
+            }
+        }
+
+        #[cfg(unix)]
+        if let Some(mode) = node.permissions {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode))
+                .map_err(|e| format!("Failed to set permissions on {}: {}", path.display(), e))?;
+        }
+
         Ok(())
     }
 
+    /// Add dependencies that the manifest does not declare yet to a Cargo.toml section
+    fn add_dependencies(manifest: &Path, section: &str, dependencies: &[Dependency]) -> Result<(), String> {
+        if dependencies.is_empty() || !manifest.exists() {
+            return Ok(());
+        }
+        let content = std::fs::read_to_string(manifest)
+            .map_err(|e| format!("Failed to read {}: {}", manifest.display(), e))?;
+
+        let header = format!("[{}]", section);
+        let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+        let start = match lines.iter().position(|line| line.trim() == header) {
+            Some(index) => index + 1,
+            None => {
+                if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                    lines.push(String::new());
+                }
+                lines.push(header);
+                lines.len()
+            }
+        };
+        let end = lines[start..].iter()
+            .position(|line| line.trim_start().starts_with('['))
+            .map_or(lines.len(), |offset| start + offset);
+
+        let declared: Vec<String> = lines[start..end].iter()
+            .filter_map(|line| line.split_once('='))
+            .map(|(name, _)| name.trim().to_string())
+            .collect();
+        // Keep a blank line that separates the section from the next one
+        let mut insert_at = end;
+        while insert_at > start && lines[insert_at - 1].trim().is_empty() {
+            insert_at -= 1;
+        }
+        for dependency in dependencies.iter().filter(|dependency| !declared.contains(&dependency.name)) {
+            lines.insert(insert_at, dependency.to_toml());
+            insert_at += 1;
+        }
+
+        let mut updated = lines.join("\n");
+        updated.push('\n');
+        std::fs::write(manifest, updated)
+            .map_err(|e| format!("Failed to write {}: {}", manifest.display(), e))
+    }
+
     /// Get template by ID
     pub fn get_template(&self, id: &str) -> Option<&ProjectTemplate> {
         self.templates.get(id)
@@ -1432,4 +1609,111 @@ impl Default for BuildConfiguration {
             profiles: HashMap::new(),
         }
     }
-}
\ No newline at end of file
+}
+impl Dependency {
+    /// Render the dependency as a Cargo.toml entry
+    pub fn to_toml(&self) -> String {
+        let simple = self.features.is_empty() && !self.optional && self.default_features
+            && self.git.is_none() && self.path.is_none();
+        if simple {
+            return format!("{} = \"{}\"", self.name, self.version);
+        }
+
+        let mut fields = Vec::new();
+        if !self.version.is_empty() {
+            fields.push(format!("version = \"{}\"", self.version));
+        }
+        if let Some(git) = &self.git {
+            fields.push(format!("git = \"{}\"", git));
+            if let Some(git_ref) = &self.git_ref {
+                fields.push(format!("rev = \"{}\"", git_ref));
+            }
+        }
+        if let Some(path) = &self.path {
+            fields.push(format!("path = \"{}\"", path));
+        }
+        if !self.features.is_empty() {
+            let features: Vec<String> = self.features.iter().map(|feature| format!("\"{}\"", feature)).collect();
+            fields.push(format!("features = [{}]", features.join(", ")));
+        }
+        if !self.default_features {
+            fields.push("default-features = false".to_string());
+        }
+        if self.optional {
+            fields.push("optional = true".to_string());
+        }
+        format!("{} = {{ {} }}", self.name, fields.join(", "))
+    }
+}
+
+impl Platform {
+    /// Whether the platform matches the one the IDE is running on
+    pub fn is_current(&self) -> bool {
+        match self {
+            Platform::All => true,
+            Platform::Windows => cfg!(windows),
+            Platform::MacOS => cfg!(target_os = "macos"),
+            Platform::Linux => cfg!(target_os = "linux"),
+            Platform::Unix => cfg!(unix),
+        }
+    }
+}
+
+/// Dependencies of the forms generated by the visual designer: egui and the
+/// RCL components, taken from the IDE sources this binary was built from
+fn rcl_dependencies() -> Vec<Dependency> {
+    vec![
+        Dependency {
+            name: "egui".to_string(),
+            version: "0.27".to_string(),
+            features: vec![],
+            optional: false,
+            default_features: true,
+            git: None,
+            git_ref: None,
+            path: None,
+        },
+        Dependency {
+            name: "ide-rs".to_string(),
+            version: String::new(),
+            features: vec![],
+            optional: false,
+            default_features: true,
+            git: None,
+            git_ref: None,
+            path: Some(env!("CARGO_MANIFEST_DIR").replace('\\', "/")),
+        },
+    ]
+}
+
+/// Replace `{{name}}` placeholders with variable values
+fn substitute_variables(text: &str, values: &HashMap<String, String>) -> String {
+    values.iter().fold(text.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{{{}}}}}", name), value)
+    })
+}
+
+/// Build a command that runs `command` through the platform shell
+fn shell_command(command: &str) -> std::process::Command {
+    let mut shell = if cfg!(windows) {
+        let mut shell = std::process::Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = std::process::Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    shell.arg(command);
+    shell
+}
+
+/// Run a command to completion, turning a failure into an error message
+fn run_command(command: &mut std::process::Command, name: &str) -> Result<(), String> {
+    let output = command.output().map_err(|e| format!("{} failed to start: {}", name, e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} failed: {}", name, String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
@@ -2,11 +2,15 @@
 //!
 //! Provides bidirectional synchronization between the visual designer and generated code,
 //! ensuring changes are reflected immediately in both views.
+//!
+//! Regenerated code is merged with the user's edits against the last generated
+//! output; conflicting edits are held back and shown in a merge view.
 
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 use crate::editor::visual_designer::VisualDesigner;
 use crate::editor::code_editor::CodeEditor;
+use crate::editor::three_way_merge::{self, MergeView, MergeViewAction};
 use crate::editor::modern_ide_integration_modules::code_generation::{
     CodeGenerator, GenerationContext, ComponentGenerationData
 };
@@ -31,6 +35,18 @@ pub struct RealtimeSync {
     pub sync_direction: SyncDirection,
     /// Component change listeners
     pub change_listeners: Vec<Box<dyn ComponentChangeListener>>,
+    /// Code produced by the last sync, the base for merging the next one
+    pub last_generated: Option<String>,
+    /// Regenerated code waiting for its conflicts to be resolved
+    pending_merge: Option<PendingMerge>,
+}
+
+/// A regeneration whose merge with the user's code has conflicts
+struct PendingMerge {
+    /// Conflict resolution window
+    view: MergeView,
+    /// The regenerated code, the base once the merge is settled
+    generated: String,
 }
 
 /// Synchronization direction
@@ -116,6 +132,8 @@ impl RealtimeSync {
             sync_enabled: true,
             sync_direction: SyncDirection::DesignerToCode,
             change_listeners: Vec::new(),
+            last_generated: None,
+            pending_merge: None,
         }
     }
     
@@ -152,7 +170,7 @@ impl RealtimeSync {
         components: &[Box<dyn Component>],
         code_editor: &mut CodeEditor,
     ) -> Option<SyncResult> {
-        if !self.sync_enabled {
+        if !self.sync_enabled || self.has_pending_merge() {
             return None;
         }
         
@@ -236,15 +254,34 @@ impl RealtimeSync {
         
         match self.code_generator.generate_code(&template_id, &context) {
             Ok(generated_code) => {
-                // Update code editor with generated code
-                code_editor.code = generated_code.content.clone();
+                // Merge with edits made since the last sync instead of overwriting them
+                let base = self.last_generated.as_deref().unwrap_or(&code_editor.code);
+                let merge = three_way_merge::merge(base, &code_editor.code, &generated_code.content);
+                let Some(merged) = merge.merged_text() else {
+                    let error = format!("{} merge conflicts with your edits", merge.conflict_count());
+                    self.pending_merge = Some(PendingMerge {
+                        view: MergeView::new("designer code".to_string(), merge),
+                        generated: generated_code.content,
+                    });
+                    return Some(SyncResult {
+                        code: String::new(),
+                        language: generated_code.language,
+                        success: false,
+                        error: Some(error),
+                        timestamp: Instant::now(),
+                    });
+                };
+
+                // Update code editor with the merged code
+                code_editor.code = merged.clone();
                 code_editor.mark_dirty();
+                self.last_generated = Some(generated_code.content);
                 
                 // Update our hash to prevent immediate re-sync
                 self.last_code_hash = self.calculate_code_hash(code_editor);
                 
                 Some(SyncResult {
-                    code: generated_code.content,
+                    code: merged,
                     language: generated_code.language,
                     success: true,
                     error: None,
@@ -263,6 +300,38 @@ impl RealtimeSync {
         }
     }
     
+    /// Whether a regeneration is waiting for its conflicts to be resolved
+    pub fn has_pending_merge(&self) -> bool {
+        self.pending_merge.is_some()
+    }
+
+    /// Show the merge view for a pending regeneration
+    ///
+    /// Returns true when `code_editor` was updated with the merged code.
+    /// Keeping the user's version still advances the merge base, so the
+    /// same conflicts are not raised again by the next sync.
+    pub fn render_merge_view(&mut self, ctx: &egui::Context, code_editor: &mut CodeEditor) -> bool {
+        let Some(pending) = &mut self.pending_merge else {
+            return false;
+        };
+        let Some(action) = pending.view.show(ctx) else {
+            return false;
+        };
+
+        let generated = std::mem::take(&mut pending.generated);
+        self.pending_merge = None;
+        self.last_generated = Some(generated);
+        match action {
+            MergeViewAction::Apply(merged) => {
+                code_editor.code = merged;
+                code_editor.mark_dirty();
+                self.last_code_hash = self.calculate_code_hash(code_editor);
+                true
+            }
+            MergeViewAction::KeepMine => false,
+        }
+    }
+    
     /// Sync code changes to visual designer (not fully implemented - complex parsing required)
     fn sync_code_to_designer(
         &mut self,
@@ -1,49 +1,27 @@
 //! Rust Analyzer Language Service Implementation
 //!
 //! This module provides a concrete implementation of the LanguageService trait
-//! for rust-analyzer, enabling rich IDE features for Rust code.
+//! for rust-analyzer, enabling rich IDE features for Rust code. It is the
+//! generic [`LspService`] preconfigured with [`LspServerConfig::rust_analyzer`].
 
 use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
 use std::path::{Path, PathBuf};
-use std::process::{Child, Command, Stdio};
-use std::io::{BufRead, BufReader, Write};
-use std::sync::Arc;
-use tokio::sync::{Mutex, mpsc};
-use uuid::Uuid;
-use serde_json::{json, Value};
 
 use super::language_service::{
-    LanguageService, LanguageServiceError, CompletionItem, CompletionKind,
-    HoverInfo, Location, CodeAction, TextEdit, DocumentSymbol,
-    WorkspaceSymbol, SignatureHelp, SemanticToken, FoldingRange
+    LanguageService, LanguageServiceError, CompletionItem, HoverInfo, Location,
+    CodeAction, TextEdit, DocumentSymbol, WorkspaceSymbol, SignatureHelp,
+    SemanticToken, FoldingRange,
 };
-use super::lsp_integration::{LspClient, LspMessage, Diagnostic, Range, Position};
+use super::lsp_integration::{Diagnostic, Range, Position};
+use super::lsp_service::{LspService, LspServerConfig};
 
 /// Rust Analyzer language service implementation
+///
+/// Dereferences to [`LspService`] for server state such as progress and
+/// workspace edits.
 pub struct RustAnalyzerService {
-    /// LSP client for communication
-    lsp_client: Option<LspClient>,
-    /// Rust Analyzer process
-    process: Option<Child>,
-    /// Workspace root path
-    workspace_root: Option<PathBuf>,
-    /// Document versions for sync
-    document_versions: HashMap<PathBuf, u64>,
-    /// Initialization state
-    initialized: bool,
-    /// Request counter
-    request_counter: u64,
-    /// Pending requests
-    pending_requests: HashMap<u64, PendingRustAnalyzerRequest>,
-    /// Message channels
-    message_tx: Option<mpsc::UnboundedSender<LspMessage>>,
-    message_rx: Option<mpsc::UnboundedReceiver<LspMessage>>,
-}
-
-/// Pending request for rust-analyzer
-struct PendingRustAnalyzerRequest {
-    method: String,
-    sender: tokio::sync::oneshot::Sender<Result<Value, LanguageServiceError>>,
+    service: LspService,
 }
 
 impl Default for RustAnalyzerService {
@@ -56,514 +34,120 @@ impl RustAnalyzerService {
     /// Create a new Rust Analyzer service
     pub fn new() -> Self {
         Self {
-            lsp_client: None,
-            process: None,
-            workspace_root: None,
-            document_versions: HashMap::new(),
-            initialized: false,
-            request_counter: 0,
-            pending_requests: HashMap::new(),
-            message_tx: None,
-            message_rx: None,
+            service: LspService::new(LspServerConfig::rust_analyzer()),
         }
     }
-    
-    /// Start rust-analyzer process
-    async fn start_rust_analyzer(&mut self, workspace_root: &Path) -> Result<(), LanguageServiceError> {
-        // Try to start rust-analyzer
-        let mut process = Command::new("rust-analyzer")
-            .current_dir(workspace_root)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| LanguageServiceError::Other(format!("Failed to start rust-analyzer: {}", e)))?;
-        
-        // Set up message channels
-        let (tx, rx) = mpsc::unbounded_channel();
-        self.message_tx = Some(tx);
-        self.message_rx = Some(rx);
-        
-        // Start message handling task
-        let stdin = process.stdin.take().unwrap();
-        let stdout = process.stdout.take().unwrap();
-        
-        self.start_message_handler(stdin, stdout).await?;
-        
-        self.process = Some(process);
-        Ok(())
-    }
-    
-    /// Start message handling task
-    async fn start_message_handler(&mut self, _stdin: std::process::ChildStdin, _stdout: std::process::ChildStdout) -> Result<(), LanguageServiceError> {
-        // TODO: Implement LSP message handling
-        // This would involve:
-        // 1. Reading messages from stdout in a separate task
-        // 2. Writing messages to stdin
-        // 3. Handling request/response correlation
-        // 4. Processing notifications
-        
-        Ok(())
-    }
-    
-    /// Send LSP request
-    async fn send_request(&mut self, method: &str, params: Value) -> Result<Value, LanguageServiceError> {
-        if !self.initialized {
-            return Err(LanguageServiceError::NotInitialized);
-        }
-        
-        let request_id = self.request_counter;
-        self.request_counter += 1;
-        
-        let (tx, rx) = tokio::sync::oneshot::channel();
-        self.pending_requests.insert(request_id, PendingRustAnalyzerRequest {
-            method: method.to_string(),
-            sender: tx,
-        });
-        
-        let message = LspMessage::Request {
-            id: request_id,
-            method: method.to_string(),
-            params,
-        };
-        
-        if let Some(msg_tx) = &self.message_tx {
-            msg_tx.send(message)
-                .map_err(|_| LanguageServiceError::Communication("Failed to send message".to_string()))?;
-        }
-        
-        // Wait for response
-        match tokio::time::timeout(std::time::Duration::from_secs(30), rx).await {
-            Ok(Ok(result)) => result,
-            Ok(Err(_)) => Err(LanguageServiceError::Communication("Request cancelled".to_string())),
-            Err(_) => Err(LanguageServiceError::Timeout),
-        }
-    }
-    
-    /// Send LSP notification
-    async fn send_notification(&mut self, method: &str, params: Value) -> Result<(), LanguageServiceError> {
-        let message = LspMessage::Notification {
-            method: method.to_string(),
-            params,
+
+    /// Create a service that launches a different server executable
+    pub fn with_command(program: impl Into<String>, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let config = LspServerConfig {
+            command: program.into(),
+            ..LspServerConfig::rust_analyzer()
         };
-        
-        if let Some(msg_tx) = &self.message_tx {
-            msg_tx.send(message)
-                .map_err(|_| LanguageServiceError::Communication("Failed to send notification".to_string()))?;
+
+        Self {
+            service: LspService::new(config.with_args(args)),
         }
-        
-        Ok(())
-    }
-    
-    /// Convert position to LSP format
-    fn position_to_lsp(&self, position: &Position) -> Value {
-        json!({
-            "line": position.line,
-            "character": position.character
-        })
     }
-    
-    /// Convert range to LSP format
-    fn range_to_lsp(&self, range: &Range) -> Value {
-        json!({
-            "start": self.position_to_lsp(&range.start),
-            "end": self.position_to_lsp(&range.end)
-        })
+}
+
+impl Deref for RustAnalyzerService {
+    type Target = LspService;
+
+    fn deref(&self) -> &LspService {
+        &self.service
     }
-    
-    /// Convert file path to URI
-    fn path_to_uri(&self, path: &Path) -> String {
-        format!("file://{}", path.to_string_lossy())
+}
+
+impl DerefMut for RustAnalyzerService {
+    fn deref_mut(&mut self) -> &mut LspService {
+        &mut self.service
     }
 }
 
 #[async_trait::async_trait]
 impl LanguageService for RustAnalyzerService {
     async fn initialize(&mut self, workspace_root: &Path) -> Result<(), LanguageServiceError> {
-        if self.initialized {
-            return Ok(());
-        }
-        
-        self.workspace_root = Some(workspace_root.to_path_buf());
-        
-        // Start rust-analyzer process
-        self.start_rust_analyzer(workspace_root).await?;
-        
-        // Send initialize request
-        let init_params = json!({
-            "processId": std::process::id(),
-            "rootUri": self.path_to_uri(workspace_root),
-            "capabilities": {
-                "textDocument": {
-                    "completion": {
-                        "completionItem": {
-                            "snippetSupport": true,
-                            "resolveSupport": {
-                                "properties": ["documentation", "detail"]
-                            }
-                        }
-                    },
-                    "hover": {
-                        "contentFormat": ["markdown", "plaintext"]
-                    },
-                    "signatureHelp": {
-                        "signatureInformation": {
-                            "documentationFormat": ["markdown", "plaintext"]
-                        }
-                    },
-                    "publishDiagnostics": {
-                        "relatedInformation": true
-                    },
-                    "definition": {
-                        "linkSupport": true
-                    },
-                    "references": {},
-                    "documentSymbol": {
-                        "hierarchicalDocumentSymbolSupport": true
-                    },
-                    "codeAction": {
-                        "codeActionLiteralSupport": {
-                            "codeActionKind": {
-                                "valueSet": ["quickfix", "refactor", "refactor.extract", "refactor.inline", "refactor.rewrite"]
-                            }
-                        }
-                    },
-                    "formatting": {},
-                    "rangeFormatting": {},
-                    "rename": {},
-                    "semanticTokens": {
-                        "requests": {
-                            "full": true,
-                            "range": true
-                        }
-                    },
-                    "foldingRange": {}
-                },
-                "workspace": {
-                    "symbol": {},
-                    "workspaceFolders": true
-                }
-            },
-            "workspaceFolders": [{
-                "uri": self.path_to_uri(workspace_root),
-                "name": workspace_root.file_name()
-                    .unwrap_or_else(|| std::ffi::OsStr::new("workspace"))
-                    .to_string_lossy()
-            }]
-        });
-        
-        let _response = self.send_request("initialize", init_params).await?;
-        
-        // Send initialized notification
-        self.send_notification("initialized", json!({})).await?;
-        
-        self.initialized = true;
-        Ok(())
+        self.service.initialize(workspace_root).await
     }
-    
+
     async fn shutdown(&mut self) -> Result<(), LanguageServiceError> {
-        if !self.initialized {
-            return Ok(());
-        }
-        
-        // Send shutdown request
-        let _response = self.send_request("shutdown", json!({})).await?;
-        
-        // Send exit notification
-        self.send_notification("exit", json!({})).await?;
-        
-        // Kill process if still running
-        if let Some(mut process) = self.process.take() {
-            let _ = process.kill();
-            let _ = process.wait();
-        }
-        
-        self.initialized = false;
-        self.workspace_root = None;
-        self.document_versions.clear();
-        self.pending_requests.clear();
-        
-        Ok(())
+        self.service.shutdown().await
     }
-    
+
     fn supports_file(&self, file_path: &Path) -> bool {
-        matches!(file_path.extension().and_then(|s| s.to_str()), Some("rs") | Some("toml"))
+        self.service.supports_file(file_path)
     }
-    
-    fn supported_extensions(&self) -> Vec<&'static str> {
-        vec!["rs", "toml"]
+
+    fn supported_extensions(&self) -> Vec<String> {
+        self.service.supported_extensions()
     }
-    
+
     async fn open_document(&mut self, file_path: &Path, content: &str) -> Result<(), LanguageServiceError> {
-        let params = json!({
-            "textDocument": {
-                "uri": self.path_to_uri(file_path),
-                "languageId": "rust",
-                "version": 1,
-                "text": content
-            }
-        });
-        
-        self.send_notification("textDocument/didOpen", params).await?;
-        self.document_versions.insert(file_path.to_path_buf(), 1);
-        
-        Ok(())
+        self.service.open_document(file_path, content).await
     }
-    
+
     async fn update_document(&mut self, file_path: &Path, content: &str, version: u64) -> Result<(), LanguageServiceError> {
-        let params = json!({
-            "textDocument": {
-                "uri": self.path_to_uri(file_path),
-                "version": version
-            },
-            "contentChanges": [{
-                "text": content
-            }]
-        });
-        
-        self.send_notification("textDocument/didChange", params).await?;
-        self.document_versions.insert(file_path.to_path_buf(), version);
-        
-        Ok(())
+        self.service.update_document(file_path, content, version).await
     }
-    
+
     async fn close_document(&mut self, file_path: &Path) -> Result<(), LanguageServiceError> {
-        let params = json!({
-            "textDocument": {
-                "uri": self.path_to_uri(file_path)
-            }
-        });
-        
-        self.send_notification("textDocument/didClose", params).await?;
-        self.document_versions.remove(file_path);
-        
-        Ok(())
+        self.service.close_document(file_path).await
     }
-    
+
     async fn completion(&mut self, file_path: &Path, position: Position) -> Result<Vec<CompletionItem>, LanguageServiceError> {
-        let params = json!({
-            "textDocument": {
-                "uri": self.path_to_uri(file_path)
-            },
-            "position": self.position_to_lsp(&position)
-        });
-        
-        let response = self.send_request("textDocument/completion", params).await?;
-        
-        // Parse completion response - this is simplified
-        // In reality, you'd need to parse the LSP response format properly
-        let empty_array = json!([]);
-        let items = response.get("items").unwrap_or(&empty_array);
-        let mut completions = Vec::new();
-        
-        if let Some(items_array) = items.as_array() {
-            for item in items_array {
-                if let Some(label) = item.get("label").and_then(|l| l.as_str()) {
-                    completions.push(CompletionItem {
-                        label: label.to_string(),
-                        kind: CompletionKind::Text, // Would need proper mapping
-                        detail: item.get("detail").and_then(|d| d.as_str()).map(|s| s.to_string()),
-                        documentation: None,
-                        insert_text: item.get("insertText").and_then(|t| t.as_str()).map(|s| s.to_string()),
-                        filter_text: None,
-                        sort_text: None,
-                        additional_text_edits: Vec::new(),
-                    });
-                }
-            }
-        }
-        
-        Ok(completions)
+        self.service.completion(file_path, position).await
     }
-    
+
     async fn hover(&mut self, file_path: &Path, position: Position) -> Result<Option<HoverInfo>, LanguageServiceError> {
-        let params = json!({
-            "textDocument": {
-                "uri": self.path_to_uri(file_path)
-            },
-            "position": self.position_to_lsp(&position)
-        });
-        
-        let response = self.send_request("textDocument/hover", params).await?;
-        
-        // Parse hover response
-        if let Some(contents) = response.get("contents") {
-            if let Some(content_str) = contents.as_str() {
-                return Ok(Some(HoverInfo {
-                    contents: content_str.to_string(),
-                    range: None, // Would need to parse range from response
-                }));
-            }
-        }
-        
-        Ok(None)
+        self.service.hover(file_path, position).await
     }
-    
+
     async fn goto_definition(&mut self, file_path: &Path, position: Position) -> Result<Vec<Location>, LanguageServiceError> {
-        let params = json!({
-            "textDocument": {
-                "uri": self.path_to_uri(file_path)
-            },
-            "position": self.position_to_lsp(&position)
-        });
-        
-        let _response = self.send_request("textDocument/definition", params).await?;
-        
-        // TODO: Parse definition response and convert to Location objects
-        Ok(Vec::new())
+        self.service.goto_definition(file_path, position).await
     }
-    
+
     async fn find_references(&mut self, file_path: &Path, position: Position) -> Result<Vec<Location>, LanguageServiceError> {
-        let params = json!({
-            "textDocument": {
-                "uri": self.path_to_uri(file_path)
-            },
-            "position": self.position_to_lsp(&position),
-            "context": {
-                "includeDeclaration": true
-            }
-        });
-        
-        let _response = self.send_request("textDocument/references", params).await?;
-        
-        // TODO: Parse references response and convert to Location objects
-        Ok(Vec::new())
+        self.service.find_references(file_path, position).await
     }
-    
-    async fn diagnostics(&mut self, _file_path: &Path) -> Result<Vec<Diagnostic>, LanguageServiceError> {
-        // Rust-analyzer sends diagnostics as notifications, not responses to requests
-        // We would typically cache diagnostics from textDocument/publishDiagnostics notifications
-        Ok(Vec::new())
+
+    async fn diagnostics(&mut self, file_path: &Path) -> Result<Vec<Diagnostic>, LanguageServiceError> {
+        self.service.diagnostics(file_path).await
     }
-    
+
     async fn code_actions(&mut self, file_path: &Path, range: Range) -> Result<Vec<CodeAction>, LanguageServiceError> {
-        let params = json!({
-            "textDocument": {
-                "uri": self.path_to_uri(file_path)
-            },
-            "range": self.range_to_lsp(&range),
-            "context": {
-                "diagnostics": []
-            }
-        });
-        
-        let _response = self.send_request("textDocument/codeAction", params).await?;
-        
-        // TODO: Parse code actions response
-        Ok(Vec::new())
+        self.service.code_actions(file_path, range).await
     }
-    
+
     async fn format_document(&mut self, file_path: &Path) -> Result<Vec<TextEdit>, LanguageServiceError> {
-        let params = json!({
-            "textDocument": {
-                "uri": self.path_to_uri(file_path)
-            },
-            "options": {
-                "tabSize": 4,
-                "insertSpaces": true
-            }
-        });
-        
-        let _response = self.send_request("textDocument/formatting", params).await?;
-        
-        // TODO: Parse formatting response and convert to TextEdit objects
-        Ok(Vec::new())
+        self.service.format_document(file_path).await
     }
-    
+
     async fn format_range(&mut self, file_path: &Path, range: Range) -> Result<Vec<TextEdit>, LanguageServiceError> {
-        let params = json!({
-            "textDocument": {
-                "uri": self.path_to_uri(file_path)
-            },
-            "range": self.range_to_lsp(&range),
-            "options": {
-                "tabSize": 4,
-                "insertSpaces": true
-            }
-        });
-        
-        let _response = self.send_request("textDocument/rangeFormatting", params).await?;
-        
-        // TODO: Parse range formatting response
-        Ok(Vec::new())
+        self.service.format_range(file_path, range).await
     }
-    
+
     async fn rename(&mut self, file_path: &Path, position: Position, new_name: &str) -> Result<HashMap<PathBuf, Vec<TextEdit>>, LanguageServiceError> {
-        let params = json!({
-            "textDocument": {
-                "uri": self.path_to_uri(file_path)
-            },
-            "position": self.position_to_lsp(&position),
-            "newName": new_name
-        });
-        
-        let _response = self.send_request("textDocument/rename", params).await?;
-        
-        // TODO: Parse rename response and convert to HashMap of TextEdits
-        Ok(HashMap::new())
+        self.service.rename(file_path, position, new_name).await
     }
-    
+
     async fn document_symbols(&mut self, file_path: &Path) -> Result<Vec<DocumentSymbol>, LanguageServiceError> {
-        let params = json!({
-            "textDocument": {
-                "uri": self.path_to_uri(file_path)
-            }
-        });
-        
-        let _response = self.send_request("textDocument/documentSymbol", params).await?;
-        
-        // TODO: Parse document symbols response
-        Ok(Vec::new())
+        self.service.document_symbols(file_path).await
     }
-    
+
     async fn workspace_symbols(&mut self, query: &str) -> Result<Vec<WorkspaceSymbol>, LanguageServiceError> {
-        let params = json!({
-            "query": query
-        });
-        
-        let _response = self.send_request("workspace/symbol", params).await?;
-        
-        // TODO: Parse workspace symbols response
-        Ok(Vec::new())
+        self.service.workspace_symbols(query).await
     }
-    
+
     async fn signature_help(&mut self, file_path: &Path, position: Position) -> Result<Option<SignatureHelp>, LanguageServiceError> {
-        let params = json!({
-            "textDocument": {
-                "uri": self.path_to_uri(file_path)
-            },
-            "position": self.position_to_lsp(&position)
-        });
-        
-        let _response = self.send_request("textDocument/signatureHelp", params).await?;
-        
-        // TODO: Parse signature help response
-        Ok(None)
+        self.service.signature_help(file_path, position).await
     }
-    
+
     async fn semantic_tokens(&mut self, file_path: &Path) -> Result<Vec<SemanticToken>, LanguageServiceError> {
-        let params = json!({
-            "textDocument": {
-                "uri": self.path_to_uri(file_path)
-            }
-        });
-        
-        let _response = self.send_request("textDocument/semanticTokens/full", params).await?;
-        
-        // TODO: Parse semantic tokens response
-        Ok(Vec::new())
+        self.service.semantic_tokens(file_path).await
     }
-    
+
     async fn folding_ranges(&mut self, file_path: &Path) -> Result<Vec<FoldingRange>, LanguageServiceError> {
-        let params = json!({
-            "textDocument": {
-                "uri": self.path_to_uri(file_path)
-            }
-        });
-        
-        let _response = self.send_request("textDocument/foldingRange", params).await?;
-        
-        // TODO: Parse folding ranges response
-        Ok(Vec::new())
+        self.service.folding_ranges(file_path).await
     }
-}
\ No newline at end of file
+}
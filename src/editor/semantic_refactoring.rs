@@ -0,0 +1,1985 @@
+//! Semantic Refactoring Transformations
+//!
+//! Syntax-aware implementations of the refactorings registered by
+//! [`AdvancedRefactoringEngine`](super::advanced_refactoring::AdvancedRefactoringEngine).
+//! Files are parsed with `syn`, and span locations map syntax nodes back to
+//! byte offsets so each transformation yields exact [`CodeChange`]s that can be
+//! previewed before anything is written.
+//!
+//! Resolution is name based: a name bound by a pattern inside a function is
+//! treated as a local of that function, any other name as an item that may be
+//! referenced from every file in the workspace.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::{Ident, LineColumn, Span, TokenStream, TokenTree};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+use super::advanced_refactoring::{ChangeType, CodeChange, CodeContext, CodeSelection, TextPosition};
+
+/// Root directory whose Rust sources a refactoring may read and rewrite
+#[derive(Debug, Clone)]
+pub struct RefactoringWorkspace {
+    root: PathBuf,
+}
+
+impl RefactoringWorkspace {
+    /// Create a workspace rooted at `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Workspace root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Rust source files under the root, skipping `target` and hidden directories
+    pub fn rust_files(&self) -> Vec<PathBuf> {
+        fn walk(dir: &Path, files: &mut Vec<PathBuf>) {
+            let Ok(entries) = std::fs::read_dir(dir) else { return };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                if path.is_dir() {
+                    if name != "target" && !name.starts_with('.') {
+                        walk(&path, files);
+                    }
+                } else if path.extension().is_some_and(|ext| ext == "rs") {
+                    files.push(path);
+                }
+            }
+        }
+
+        let mut files = Vec::new();
+        walk(&self.root, &mut files);
+        files.sort();
+        files
+    }
+
+    /// Resolve `path` against the workspace root
+    fn resolve(&self, path: &Path) -> PathBuf {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.root.join(path)
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Operations
+// ---------------------------------------------------------------------------
+
+/// Rename the symbol under the cursor
+///
+/// Locals are renamed within their function; items, fields and methods are
+/// renamed in every workspace file. Parameters: `new_name`.
+pub fn rename_symbol(
+    workspace: &RefactoringWorkspace,
+    selection: &CodeSelection,
+    parameters: &HashMap<String, String>,
+) -> Result<Vec<CodeChange>, String> {
+    let new_name = required(parameters, "new_name")?;
+    let file = SourceFile::read(&workspace.resolve(&selection.file_path))?;
+    let cursor = selection_range(&file, selection).start;
+    let target = ident_at(&file, cursor).ok_or("No symbol at the cursor")?;
+    let old_name = target.to_string();
+    if old_name == new_name {
+        return Err(format!("Symbol is already named `{}`", new_name));
+    }
+
+    let description = format!("Rename `{}` to `{}`", old_name, new_name);
+    let target_range = file.range(target.span());
+
+    if let Some(scope) = file.enclosing_fn(&target_range) {
+        let refs = local_refs(scope, &old_name);
+        if refs.iter().any(|reference| file.range(reference.span) == target_range) {
+            if scope_bindings(scope).iter().any(|ident| ident == new_name) {
+                return Err(format!("`{}` is already bound in this function", new_name));
+            }
+            let changes = refs
+                .into_iter()
+                .map(|reference| {
+                    let text = match reference.kind {
+                        LocalRefKind::Binding | LocalRefKind::Use => new_name.to_string(),
+                        LocalRefKind::Shorthand | LocalRefKind::ShorthandPat => format!("{}: {}", old_name, new_name),
+                    };
+                    file.replace(file.range(reference.span), text, ChangeType::Rename, &description)
+                })
+                .collect();
+            return Ok(changes);
+        }
+    }
+
+    if file.ast.items.iter().any(|item| matches!(item, syn::Item::Mod(m) if m.ident == old_name && m.content.is_none())) {
+        return Err(format!("`{}` is a file module; rename its file instead", old_name));
+    }
+
+    // Fields live apart from items: renaming one never touches the other
+    let renaming_field = member_positions(&file).contains(&target_range.start);
+
+    let mut defined = false;
+    let mut changes = Vec::new();
+    for path in workspace.rust_files() {
+        let source = if path == file.path { None } else { Some(SourceFile::read(&path)?) };
+        let source = source.as_ref().unwrap_or(&file);
+        let names = defined_names(&source.ast);
+        defined |= names.contains(&old_name);
+
+        let mut occurrences: Vec<(Range<usize>, String)> = Vec::new();
+        let locals = local_occurrences(source, &old_name);
+        let members = member_positions(source);
+        for ident in collect_idents(&source.ast, Some(&old_name)) {
+            let range = source.range(ident.span());
+            let text = match (members.contains(&range.start), locals.get(&range.start)) {
+                (true, Some(LocalRefKind::Shorthand | LocalRefKind::ShorthandPat)) if renaming_field => {
+                    format!("{}: {}", new_name, old_name)
+                }
+                (true, _) if renaming_field => new_name.to_string(),
+                (false, None) if !renaming_field => new_name.to_string(),
+                _ => continue,
+            };
+            occurrences.push((range, text));
+        }
+        occurrences.sort_by_key(|(range, _)| range.start);
+        occurrences.dedup_by_key(|(range, _)| range.start);
+
+        if !occurrences.is_empty() && names.contains(new_name) {
+            return Err(format!("`{}` is already defined in {}", new_name, source.path.display()));
+        }
+        changes.extend(
+            occurrences
+                .into_iter()
+                .map(|(range, text)| source.replace(range, text, ChangeType::Rename, &description)),
+        );
+    }
+
+    if !defined {
+        return Err(format!("`{}` is not defined in the workspace", old_name));
+    }
+    Ok(changes)
+}
+
+/// Extract the selected expression into a local variable
+///
+/// Parameters: `variable_name`.
+pub fn extract_variable(
+    workspace: &RefactoringWorkspace,
+    selection: &CodeSelection,
+    parameters: &HashMap<String, String>,
+) -> Result<Vec<CodeChange>, String> {
+    let name = required(parameters, "variable_name")?;
+    let file = SourceFile::read(&workspace.resolve(&selection.file_path))?;
+    let range = selection_range(&file, selection);
+    let scope = file.enclosing_fn(&range).ok_or("Selection is not inside a function")?;
+    let expr = expr_at(scope, &file, &range).ok_or("Selection is not a complete expression")?;
+    let stmt = innermost_stmt(scope, &file, &range).ok_or("Selection is not inside a statement")?;
+
+    // Names bound inside the statement (closure parameters, match arms, ...) are
+    // not visible where the new `let` is inserted
+    let mut scan = BodyScan::default();
+    scan.visit_expr(&expr);
+    let inner_bindings: HashSet<String> = stmt_bindings(&stmt.1).into_iter().map(|ident| ident.to_string()).collect();
+    if let Some(used) = scan.uses.iter().find(|ident| inner_bindings.contains(&ident.to_string())) {
+        return Err(format!("Expression uses `{}`, which is bound inside the statement", used));
+    }
+    if scope_bindings(scope).iter().any(|ident| ident == name) {
+        return Err(format!("`{}` is already bound in this function", name));
+    }
+
+    let description = format!("Extract variable `{}`", name);
+    let stmt_start = stmt.0.start;
+    let line_start = file.line_start(stmt_start);
+    let declaration = if file.text[line_start..stmt_start].trim().is_empty() {
+        let indent = &file.text[line_start..stmt_start];
+        file.insert(line_start, format!("{}let {} = {};\n", indent, name, &file.text[range.clone()]), &description)
+    } else {
+        file.insert(stmt_start, format!("let {} = {}; ", name, &file.text[range.clone()]), &description)
+    };
+    Ok(vec![declaration, file.replace(range, name, ChangeType::Replace, &description)])
+}
+
+/// Inline the local variable under the cursor into its uses and remove it
+pub fn inline_variable(
+    workspace: &RefactoringWorkspace,
+    selection: &CodeSelection,
+    _parameters: &HashMap<String, String>,
+) -> Result<Vec<CodeChange>, String> {
+    let file = SourceFile::read(&workspace.resolve(&selection.file_path))?;
+    let cursor = selection_range(&file, selection).start;
+    let target = ident_at(&file, cursor).ok_or("No variable at the cursor")?;
+    let name = target.to_string();
+    let target_range = file.range(target.span());
+    let scope = file.enclosing_fn(&target_range).ok_or("Variable is not inside a function")?;
+
+    let mut finder = LocalFinder { file: &file, name: &name, before: target_range.end, found: None };
+    finder.visit_block(&scope.block);
+    let (local_range, local, block_end) = finder.found.ok_or_else(|| format!("No `let {} = ...` found", name))?;
+    let init = match (&local.pat, &local.init) {
+        (syn::Pat::Ident(pat), Some(init)) if pat.by_ref.is_none() && pat.mutability.is_none() && init.diverge.is_none() => init,
+        (syn::Pat::Ident(pat), _) if pat.mutability.is_some() => return Err(format!("`{}` is mutable", name)),
+        _ => return Err(format!("`{}` is not a simple initialized binding", name)),
+    };
+
+    // Uses run from the declaration to the end of its block or the next rebinding
+    let refs = local_refs(scope, &name);
+    let scope_end = refs
+        .iter()
+        .map(|r| (file.range(r.span).start, r.kind))
+        .filter(|(start, kind)| matches!(kind, LocalRefKind::Binding | LocalRefKind::ShorthandPat) && *start > local_range.end && *start < block_end)
+        .map(|(start, _)| start)
+        .min()
+        .unwrap_or(block_end);
+
+    let init_range = file.node_range(&*init.expr);
+    let value = &file.text[init_range];
+    let value = if is_atomic(&init.expr) { value.to_string() } else { format!("({})", value) };
+
+    let description = format!("Inline variable `{}`", name);
+    let mut changes = vec![file.delete(file.full_lines(local_range.clone()), &description)];
+    for reference in refs {
+        let range = file.range(reference.span);
+        if range.start <= local_range.end || range.start >= scope_end {
+            continue;
+        }
+        let text = match reference.kind {
+            LocalRefKind::Use => value.clone(),
+            LocalRefKind::Shorthand => format!("{}: {}", name, value),
+            LocalRefKind::Binding | LocalRefKind::ShorthandPat => continue,
+        };
+        changes.push(file.replace(range, text, ChangeType::Replace, &description));
+    }
+    Ok(changes)
+}
+
+/// Extract the selected statements or expression into a free function
+///
+/// Parameters: `function_name`, optional `return_type` when it cannot be inferred.
+pub fn extract_function(
+    workspace: &RefactoringWorkspace,
+    selection: &CodeSelection,
+    parameters: &HashMap<String, String>,
+) -> Result<Vec<CodeChange>, String> {
+    extract_callable(workspace, selection, parameters, false)
+}
+
+/// Extract the selected statements or expression into a method of the enclosing impl
+///
+/// Parameters: `method_name`, optional `return_type` when it cannot be inferred.
+pub fn extract_method(
+    workspace: &RefactoringWorkspace,
+    selection: &CodeSelection,
+    parameters: &HashMap<String, String>,
+) -> Result<Vec<CodeChange>, String> {
+    extract_callable(workspace, selection, parameters, true)
+}
+
+/// Move the top-level item under the cursor into another module of the crate
+///
+/// Parameters: `target_module`, a path such as `crate::ui::widgets`. A missing
+/// module file is created and declared in its parent.
+pub fn move_item_to_module(
+    workspace: &RefactoringWorkspace,
+    selection: &CodeSelection,
+    parameters: &HashMap<String, String>,
+) -> Result<Vec<CodeChange>, String> {
+    let target_module = required(parameters, "target_module")?;
+    let file = SourceFile::read(&workspace.resolve(&selection.file_path))?;
+    let cursor = selection_range(&file, selection).start;
+    let layout = CrateLayout::of(&file.path)?;
+    let source_segments = layout.module_path(&file.path)?;
+    let target_segments: Vec<String> = target_module
+        .trim_start_matches("crate::")
+        .split("::")
+        .filter(|segment| !segment.is_empty() && *segment != "crate")
+        .map(str::to_string)
+        .collect();
+    if target_segments.is_empty() || target_segments.iter().any(|s| syn::parse_str::<Ident>(s).is_err()) {
+        return Err(format!("Invalid module path `{}`", target_module));
+    }
+    if target_segments == source_segments {
+        return Err("Item is already in that module".to_string());
+    }
+
+    let item = file
+        .ast
+        .items
+        .iter()
+        .find(|item| contains(&file.node_range(*item), &(cursor..cursor)))
+        .ok_or("No top-level item at the cursor")?;
+    let (name, vis) = movable_item(item).ok_or("Only functions, types, traits, constants and statics can be moved")?;
+    let name = name.to_string();
+    let description = format!("Move `{}` to {}", name, module_display(&target_segments));
+    let item_range = file.node_range(item);
+
+    // Moved text, widened to crate visibility so existing users keep compiling
+    let mut item_text = file.text[item_range.clone()].to_string();
+    if matches!(vis, syn::Visibility::Inherited) {
+        let keyword = item_keyword_offset(&file, item) - item_range.start;
+        item_text.insert_str(keyword, "pub(crate) ");
+    }
+
+    // Imports the item needs in its new module
+    let item_names: HashSet<String> = collect_idents_in(item, None).into_iter().map(|ident| ident.to_string()).collect();
+    let mut imports = Vec::new();
+    for use_item in file.ast.items.iter().filter_map(|item| match item { syn::Item::Use(u) => Some(u), _ => None }) {
+        if is_relative_use(&use_item.tree) {
+            continue;
+        }
+        if use_leaf_names(&use_item.tree).iter().any(|leaf| item_names.contains(leaf)) {
+            imports.push(file.text[file.node_range(use_item)].to_string());
+        }
+    }
+    let mut siblings: Vec<String> = file
+        .ast
+        .items
+        .iter()
+        .filter_map(|other| movable_item(other).map(|(ident, _)| ident.to_string()))
+        .filter(|other| *other != name && item_names.contains(other))
+        .collect();
+    siblings.sort();
+    siblings.dedup();
+    if !siblings.is_empty() {
+        imports.push(use_statement(&source_segments, &siblings));
+    }
+
+    let mut changes = vec![file.delete(file.full_lines_with_blank(item_range.clone()), &description)];
+
+    // Keep the source module compiling if it still refers to the item
+    let still_used = collect_idents(&file.ast, Some(&name))
+        .iter()
+        .any(|ident| !contains(&item_range, &file.range(ident.span())));
+    if still_used {
+        changes.push(file.insert(
+            file.import_offset(),
+            format!("{}\n", use_statement(&target_segments, std::slice::from_ref(&name))),
+            &description,
+        ));
+    }
+
+    // Destination module, created and declared when missing
+    match layout.module_file(&target_segments) {
+        Some(target_path) => {
+            let target = SourceFile::read(&target_path)?;
+            if defined_names(&target.ast).contains(&name) {
+                return Err(format!("`{}` is already defined in {}", name, target.path.display()));
+            }
+            let existing: HashSet<String> = target
+                .ast
+                .items
+                .iter()
+                .filter(|item| matches!(item, syn::Item::Use(_)))
+                .map(|item| target.text[target.node_range(item)].to_string())
+                .collect();
+            let imports: Vec<String> = imports.into_iter().filter(|import| !existing.contains(import)).collect();
+            if !imports.is_empty() {
+                changes.push(target.insert(target.import_offset(), format!("{}\n", imports.join("\n")), &description));
+            }
+            let separator = if target.text.ends_with('\n') || target.text.is_empty() { "" } else { "\n" };
+            changes.push(CodeChange {
+                change_type: ChangeType::Move,
+                ..target.insert(target.text.len(), format!("{}\n{}\n", separator, item_text), &description)
+            });
+        }
+        None => {
+            let (parent, module_name) = target_segments.split_at(target_segments.len() - 1);
+            let parent_path = layout
+                .module_file(parent)
+                .ok_or_else(|| format!("Parent module {} does not exist", module_display(parent)))?;
+            let new_path = match parent_path.file_name().and_then(|name| name.to_str()) {
+                Some("lib.rs") | Some("main.rs") | Some("mod.rs") => parent_path.with_file_name(format!("{}.rs", module_name[0])),
+                _ => parent_path.with_extension("").join(format!("{}.rs", module_name[0])),
+            };
+
+            let parent_file = if parent_path == file.path { None } else { Some(SourceFile::read(&parent_path)?) };
+            let parent_file = parent_file.as_ref().unwrap_or(&file);
+            let (offset, declaration) = parent_file.module_declaration_insert(&module_name[0]);
+            changes.push(parent_file.insert(offset, declaration, &description));
+
+            let mut content = String::new();
+            if !imports.is_empty() {
+                content.push_str(&imports.join("\n"));
+                content.push_str("\n\n");
+            }
+            content.push_str(&item_text);
+            content.push('\n');
+            changes.push(CodeChange {
+                file_path: new_path,
+                change_type: ChangeType::Move,
+                position: TextPosition { line: 0, column: 0, offset: 0 },
+                old_text: None,
+                new_text: content,
+                description: description.clone(),
+            });
+        }
+    }
+
+    // Absolute paths to the item elsewhere in the crate
+    let old_prefix: Vec<String> = std::iter::once("crate".to_string()).chain(source_segments.iter().cloned()).collect();
+    let new_prefix = module_display(&target_segments);
+    for path in workspace.rust_files() {
+        if path == file.path || !path.starts_with(&layout.src_dir) {
+            continue;
+        }
+        let other = SourceFile::read(&path)?;
+        let mut rewriter = PathRewriter { file: &other, name: &name, old_prefix: &old_prefix, new_prefix: &new_prefix, description: &description, changes: Vec::new() };
+        rewriter.visit_file(&other.ast);
+        changes.extend(rewriter.changes);
+    }
+
+    Ok(changes)
+}
+
+/// Syntactic context of a selection, used to offer applicable refactorings
+pub fn classify_selection(workspace: &RefactoringWorkspace, selection: &CodeSelection) -> Option<CodeContext> {
+    let file = SourceFile::read(&workspace.resolve(&selection.file_path)).ok()?;
+    let range = selection_range(&file, selection);
+    let scope = file.enclosing_fn(&range);
+
+    if range.is_empty() {
+        let ident = ident_at(&file, range.start)?;
+        if scope.is_some_and(|scope| scope_bindings(scope).iter().any(|binding| binding == &ident)) {
+            return Some(CodeContext::Variable);
+        }
+        let mut finder = DefinitionKind { name: ident.to_string(), kind: None };
+        finder.visit_file(&file.ast);
+        return finder.kind.or(Some(CodeContext::Expression));
+    }
+
+    let scope = scope?;
+    if expr_at(scope, &file, &range).is_some() {
+        Some(CodeContext::Expression)
+    } else if selected_stmts(scope, &file, &range).is_some() {
+        Some(CodeContext::Block)
+    } else {
+        None
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Extract function / method
+// ---------------------------------------------------------------------------
+
+fn extract_callable(
+    workspace: &RefactoringWorkspace,
+    selection: &CodeSelection,
+    parameters: &HashMap<String, String>,
+    as_method: bool,
+) -> Result<Vec<CodeChange>, String> {
+    let name = required(parameters, if as_method { "method_name" } else { "function_name" })?;
+    let file = SourceFile::read(&workspace.resolve(&selection.file_path))?;
+    let range = selection_range(&file, selection);
+    let scope = file.enclosing_fn(&range).ok_or("Selection is not inside a function")?;
+    if as_method && !scope.in_impl {
+        return Err("Selection is not inside an impl method".to_string());
+    }
+
+    // What is being extracted: whole statements, or a single expression
+    let (stmts, tail): (Vec<syn::Stmt>, Option<syn::Expr>) = match selected_stmts(scope, &file, &range) {
+        Some((stmts, is_block_tail)) => {
+            let tail = match stmts.last() {
+                Some(syn::Stmt::Expr(expr, None)) if is_block_tail => Some(expr.clone()),
+                _ => None,
+            };
+            (stmts, tail)
+        }
+        None => {
+            let expr = expr_at(scope, &file, &range).ok_or("Selection must be complete statements or an expression")?;
+            (Vec::new(), Some(expr))
+        }
+    };
+    let expression_only = stmts.is_empty();
+
+    let mut scan = BodyScan::default();
+    for stmt in &stmts {
+        scan.visit_stmt(stmt);
+    }
+    if expression_only {
+        scan.visit_expr(tail.as_ref().expect("expression selection"));
+    }
+    if scan.returns || scan.tries || scan.escapes_loop {
+        return Err("Selection contains `return`, `?`, `break` or `continue` that leaves it".to_string());
+    }
+    if scan.awaits && scope.sig.asyncness.is_none() {
+        return Err("Selection awaits outside an async function".to_string());
+    }
+
+    // Locals visible before the selection, with their types where known
+    let outer = outer_locals(scope, &file, range.start);
+    let declared_at: HashMap<String, usize> = scan
+        .declared
+        .iter()
+        .map(|ident| (ident.to_string(), file.range(ident.span()).start))
+        .rev()
+        .collect();
+
+    let mut params: Vec<(String, String)> = Vec::new();
+    let mut uses_self = false;
+    for ident in &scan.uses {
+        let used = ident.to_string();
+        if used == "self" {
+            uses_self = true;
+            continue;
+        }
+        let used_at = file.range(ident.span()).start;
+        let bound_inside = declared_at.get(&used).is_some_and(|&at| at < used_at);
+        if bound_inside || params.iter().any(|(param, _)| *param == used) {
+            continue;
+        }
+        if let Some(ty) = outer.get(&used) {
+            let ty = ty.clone().ok_or_else(|| format!("Cannot determine the type of `{}`; add a type annotation", used))?;
+            params.push((used, ty));
+        }
+    }
+    if uses_self && !as_method {
+        return Err("Selection uses `self`; extract a method instead".to_string());
+    }
+    if let Some(assigned) = params.iter().find(|(param, _)| scan.assigned.contains(param)) {
+        return Err(format!("Selection mutates `{}`, which is declared outside it", assigned.0));
+    }
+
+    // Variables declared by the selection and still used after it become return values
+    let mut known: HashMap<String, Option<String>> = outer.clone();
+    let mut outputs: Vec<(String, bool, String)> = Vec::new();
+    let after = local_uses_after(scope, &file, range.end);
+    for stmt in &stmts {
+        let syn::Stmt::Local(local) = stmt else { continue };
+        for (ident, mutable, ty) in local_bindings_with_types(local, &file, &known) {
+            known.insert(ident.to_string(), ty.clone());
+            if after.contains(&ident.to_string()) {
+                let ty = ty.ok_or_else(|| format!("Cannot determine the type of `{}`; add a type annotation", ident))?;
+                outputs.retain(|(name, _, _)| ident != name);
+                outputs.push((ident.to_string(), mutable, ty));
+            }
+        }
+    }
+    if tail.is_some() && !outputs.is_empty() {
+        return Err("Selection both produces a value and declares variables used later".to_string());
+    }
+
+    let return_type = match (&tail, parameters.get("return_type")) {
+        (_, Some(ty)) => Some(ty.trim().to_string()),
+        (Some(expr), None) => Some(
+            infer_type(expr, &file, &known)
+                .ok_or("Cannot infer the return type; pass the `return_type` parameter")?,
+        ),
+        (None, None) => match outputs.as_slice() {
+            [] => None,
+            [(_, _, ty)] => Some(ty.clone()),
+            many => Some(format!("({})", many.iter().map(|(_, _, ty)| ty.as_str()).collect::<Vec<_>>().join(", "))),
+        },
+    }
+    .filter(|ty| ty != "()");
+
+    // Signature
+    let receiver = match (as_method && uses_self, scan.mutates_self) {
+        (false, _) => None,
+        (true, false) => Some("&self"),
+        (true, true) => Some("&mut self"),
+    };
+    let mut signature_params: Vec<String> = receiver.map(str::to_string).into_iter().collect();
+    let mut args = Vec::new();
+    for (param, ty) in &params {
+        if is_copy_like(ty) {
+            signature_params.push(format!("{}: {}", param, ty));
+            args.push(param.clone());
+        } else {
+            signature_params.push(format!("{}: &{}", param, ty));
+            args.push(format!("&{}", param));
+        }
+    }
+    let generics = scope_generics(scope, &file, params.iter().map(|(_, ty)| ty.as_str()).chain(return_type.as_deref()));
+    let asyncness = if scan.awaits { "async " } else { "" };
+    let await_suffix = if scan.awaits { ".await" } else { "" };
+
+    // Body
+    let container_range = if as_method { scope.range.clone() } else { file.enclosing_module_item(&range).ok_or("Selection is not inside an item")? };
+    let indent = file.indent_at(container_range.start).to_string();
+    let body_indent = format!("{}    ", indent);
+    let mut body = reindent(&file, range.clone(), &body_indent);
+    match outputs.as_slice() {
+        [] => {}
+        [(name, _, _)] => body.push_str(&format!("\n{}{}", body_indent, name)),
+        many => body.push_str(&format!(
+            "\n{}({})",
+            body_indent,
+            many.iter().map(|(name, _, _)| name.as_str()).collect::<Vec<_>>().join(", ")
+        )),
+    }
+    let function = format!(
+        "\n\n{indent}{asyncness}fn {name}{generics}({params}){ret}{where_clause} {{\n{body}\n{indent}}}",
+        indent = indent,
+        asyncness = asyncness,
+        name = name,
+        generics = generics.0,
+        params = signature_params.join(", "),
+        ret = return_type.as_ref().map(|ty| format!(" -> {}", ty)).unwrap_or_default(),
+        where_clause = generics.1,
+        body = body,
+    );
+
+    // Call site
+    let callee = match (as_method, receiver) {
+        (false, _) => name.to_string(),
+        (true, Some(_)) => format!("self.{}", name),
+        (true, None) => format!("Self::{}", name),
+    };
+    let call = format!("{}({}){}", callee, args.join(", "), await_suffix);
+    let call = match (outputs.as_slice(), &tail) {
+        (_, Some(_)) => call,
+        ([], None) => format!("{};", call),
+        ([(name, mutable, _)], None) => format!("let {}{} = {};", if *mutable { "mut " } else { "" }, name, call),
+        (many, None) => format!(
+            "let ({}) = {};",
+            many.iter()
+                .map(|(name, mutable, _)| format!("{}{}", if *mutable { "mut " } else { "" }, name))
+                .collect::<Vec<_>>()
+                .join(", "),
+            call
+        ),
+    };
+
+    let description = format!("Extract `{}`", name);
+    let mut defined = defined_names(&file.ast);
+    if !as_method {
+        defined.extend(scope_bindings(scope).iter().map(|ident| ident.to_string()));
+    }
+    if defined.contains(name) {
+        return Err(format!("`{}` is already defined", name));
+    }
+    Ok(vec![
+        file.replace(range, call, ChangeType::Replace, &description),
+        file.insert(container_range.end, function, &description),
+    ])
+}
+
+/// Locals bound before `before` in `scope`, with their declared or inferred types
+fn outer_locals(scope: &FnScope, file: &SourceFile, before: usize) -> HashMap<String, Option<String>> {
+    let mut locals = HashMap::new();
+    for input in &scope.sig.inputs {
+        match input {
+            syn::FnArg::Typed(typed) => {
+                for ident in pattern_bindings(&typed.pat) {
+                    let ty = matches!(&*typed.pat, syn::Pat::Ident(_)).then(|| file.text[file.node_range(&*typed.ty)].to_string());
+                    locals.insert(ident.to_string(), ty);
+                }
+            }
+            syn::FnArg::Receiver(_) => {}
+        }
+    }
+
+    struct Before<'a> {
+        file: &'a SourceFile,
+        before: usize,
+        locals: HashMap<String, Option<String>>,
+    }
+    impl<'ast> Visit<'ast> for Before<'_> {
+        fn visit_local(&mut self, local: &'ast syn::Local) {
+            if self.file.node_range(local).end <= self.before {
+                for (ident, _, ty) in local_bindings_with_types(local, self.file, &self.locals) {
+                    self.locals.insert(ident.to_string(), ty);
+                }
+            }
+            visit::visit_local(self, local);
+        }
+        fn visit_pat_ident(&mut self, pat: &'ast syn::PatIdent) {
+            if self.file.range(pat.ident.span()).end <= self.before {
+                self.locals.entry(pat.ident.to_string()).or_insert(None);
+            }
+            visit::visit_pat_ident(self, pat);
+        }
+        fn visit_item(&mut self, _: &'ast syn::Item) {}
+    }
+
+    let mut visitor = Before { file, before, locals };
+    visitor.visit_block(&scope.block);
+    visitor.locals
+}
+
+/// Bindings of a `let`, with `mut` and the annotated or inferred type
+fn local_bindings_with_types(
+    local: &syn::Local,
+    file: &SourceFile,
+    known: &HashMap<String, Option<String>>,
+) -> Vec<(Ident, bool, Option<String>)> {
+    match &local.pat {
+        syn::Pat::Type(typed) => match &*typed.pat {
+            syn::Pat::Ident(pat) => vec![(pat.ident.clone(), pat.mutability.is_some(), Some(file.text[file.node_range(&*typed.ty)].to_string()))],
+            other => pattern_bindings(other).into_iter().map(|ident| (ident, false, None)).collect(),
+        },
+        syn::Pat::Ident(pat) => {
+            let ty = local.init.as_ref().and_then(|init| infer_type(&init.expr, file, known));
+            vec![(pat.ident.clone(), pat.mutability.is_some(), ty)]
+        }
+        other => pattern_bindings(other).into_iter().map(|ident| (ident, false, None)).collect(),
+    }
+}
+
+/// Names of locals read at or after `offset` in `scope`
+fn local_uses_after(scope: &FnScope, file: &SourceFile, offset: usize) -> HashSet<String> {
+    let mut scan = BodyScan::default();
+    scan.visit_block(&scope.block);
+    scan.uses
+        .iter()
+        .filter(|ident| file.range(ident.span()).start >= offset)
+        .map(|ident| ident.to_string())
+        .collect()
+}
+
+/// Generics and where clause of `scope`, if any of `types` mention its type parameters
+fn scope_generics<'a>(scope: &FnScope, file: &SourceFile, mut types: impl Iterator<Item = &'a str>) -> (String, String) {
+    let generics = &scope.sig.generics;
+    if generics.params.is_empty() {
+        return (String::new(), String::new());
+    }
+    let names: Vec<String> = generics
+        .params
+        .iter()
+        .map(|param| match param {
+            syn::GenericParam::Type(ty) => ty.ident.to_string(),
+            syn::GenericParam::Lifetime(lt) => lt.lifetime.to_string(),
+            syn::GenericParam::Const(c) => c.ident.to_string(),
+        })
+        .collect();
+    let mentions = |ty: &str| {
+        ty.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '\''))
+            .any(|word| names.iter().any(|name| name == word))
+    };
+    if !types.any(mentions) {
+        return (String::new(), String::new());
+    }
+    let params = file.text[file.node_range(generics)].to_string();
+    let params = params.split(" where").next().unwrap_or_default().to_string();
+    let where_clause = generics
+        .where_clause
+        .as_ref()
+        .map(|clause| format!(" {}", &file.text[file.node_range(clause)]))
+        .unwrap_or_default();
+    (params, where_clause)
+}
+
+/// Selected text, re-indented to `indent`
+fn reindent(file: &SourceFile, range: Range<usize>, indent: &str) -> String {
+    let first_column = range.start - file.line_start(range.start);
+    let text = &file.text[range];
+    let lines: Vec<&str> = text.lines().collect();
+    let common = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .chain(std::iter::once(first_column))
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            if line.trim().is_empty() {
+                String::new()
+            } else if i == 0 {
+                format!("{}{}{}", indent, " ".repeat(first_column - common), line)
+            } else {
+                format!("{}{}", indent, &line[common.min(line.len() - line.trim_start().len())..])
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Best-effort type of `expr` from literals, known locals and common methods
+fn infer_type(expr: &syn::Expr, file: &SourceFile, known: &HashMap<String, Option<String>>) -> Option<String> {
+    use syn::{BinOp, Expr, Lit, UnOp};
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(int) => Some(if int.suffix().is_empty() { "i32" } else { int.suffix() }.to_string()),
+            Lit::Float(float) => Some(if float.suffix().is_empty() { "f64" } else { float.suffix() }.to_string()),
+            Lit::Bool(_) => Some("bool".to_string()),
+            Lit::Str(_) => Some("&str".to_string()),
+            Lit::Char(_) => Some("char".to_string()),
+            _ => None,
+        },
+        Expr::Path(path) => path.path.get_ident().and_then(|ident| known.get(&ident.to_string()).cloned().flatten()),
+        Expr::Paren(paren) => infer_type(&paren.expr, file, known),
+        Expr::Group(group) => infer_type(&group.expr, file, known),
+        Expr::Cast(cast) => Some(file.text[file.node_range(&*cast.ty)].to_string()),
+        Expr::Binary(binary) => match binary.op {
+            BinOp::Eq(_) | BinOp::Ne(_) | BinOp::Lt(_) | BinOp::Le(_) | BinOp::Gt(_) | BinOp::Ge(_) | BinOp::And(_) | BinOp::Or(_) => {
+                Some("bool".to_string())
+            }
+            BinOp::Add(_) | BinOp::Sub(_) | BinOp::Mul(_) | BinOp::Div(_) | BinOp::Rem(_) | BinOp::BitAnd(_) | BinOp::BitOr(_)
+            | BinOp::BitXor(_) | BinOp::Shl(_) | BinOp::Shr(_) => infer_type(&binary.left, file, known)
+                .filter(|ty| is_copy_like(ty) && !ty.starts_with('&'))
+                .or_else(|| infer_type(&binary.right, file, known).filter(|ty| is_copy_like(ty) && !ty.starts_with('&'))),
+            _ => Some("()".to_string()),
+        },
+        Expr::Assign(_) => Some("()".to_string()),
+        Expr::Unary(unary) => match unary.op {
+            UnOp::Deref(_) => infer_type(&unary.expr, file, known).and_then(|ty| ty.strip_prefix('&').map(|ty| ty.trim_start_matches("mut ").to_string())),
+            _ => infer_type(&unary.expr, file, known),
+        },
+        Expr::Reference(reference) => infer_type(&reference.expr, file, known)
+            .map(|ty| format!("&{}{}", if reference.mutability.is_some() { "mut " } else { "" }, ty)),
+        Expr::Tuple(tuple) => {
+            let types: Option<Vec<String>> = tuple.elems.iter().map(|elem| infer_type(elem, file, known)).collect();
+            types.map(|types| format!("({})", types.join(", ")))
+        }
+        Expr::MethodCall(call) => match call.method.to_string().as_str() {
+            "len" | "count" => Some("usize".to_string()),
+            "to_string" | "to_uppercase" | "to_lowercase" | "repeat" => Some("String".to_string()),
+            "is_empty" | "contains" | "starts_with" | "ends_with" | "is_some" | "is_none" | "is_ok" | "is_err" => Some("bool".to_string()),
+            "clone" | "abs" | "min" | "max" | "pow" | "sqrt" => {
+                infer_type(&call.receiver, file, known).map(|ty| ty.trim_start_matches('&').to_string())
+            }
+            _ => None,
+        },
+        Expr::Macro(mac) => match mac.mac.path.get_ident().map(|ident| ident.to_string()).as_deref() {
+            Some("format") => Some("String".to_string()),
+            Some("println" | "print" | "eprintln" | "eprint" | "assert" | "assert_eq" | "assert_ne" | "debug_assert") => Some("()".to_string()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Types passed by value to an extracted function; everything else is borrowed
+fn is_copy_like(ty: &str) -> bool {
+    ty.starts_with('&')
+        || matches!(
+            ty,
+            "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "f32" | "f64" | "bool" | "char"
+        )
+}
+
+// ---------------------------------------------------------------------------
+// Move item
+// ---------------------------------------------------------------------------
+
+/// Source layout of the crate containing a file
+struct CrateLayout {
+    src_dir: PathBuf,
+    root_file: PathBuf,
+}
+
+impl CrateLayout {
+    fn of(file: &Path) -> Result<Self, String> {
+        let manifest_dir = file
+            .ancestors()
+            .skip(1)
+            .find(|dir| dir.join("Cargo.toml").is_file())
+            .ok_or_else(|| format!("{} is not inside a Cargo package", file.display()))?;
+        let src_dir = manifest_dir.join("src");
+        let root_file = ["lib.rs", "main.rs"]
+            .iter()
+            .map(|name| src_dir.join(name))
+            .find(|path| path.is_file())
+            .ok_or_else(|| format!("No src/lib.rs or src/main.rs in {}", manifest_dir.display()))?;
+        Ok(Self { src_dir, root_file })
+    }
+
+    /// Module path of `file`, e.g. `["ui", "widgets"]`; empty for the crate root
+    fn module_path(&self, file: &Path) -> Result<Vec<String>, String> {
+        if file == self.root_file {
+            return Ok(Vec::new());
+        }
+        let relative = file
+            .strip_prefix(&self.src_dir)
+            .map_err(|_| format!("{} is not under {}", file.display(), self.src_dir.display()))?;
+        let mut segments: Vec<String> = relative
+            .with_extension("")
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        if segments.last().is_some_and(|last| last == "mod") {
+            segments.pop();
+        }
+        Ok(segments)
+    }
+
+    /// Existing file that defines the module at `segments`
+    fn module_file(&self, segments: &[String]) -> Option<PathBuf> {
+        if segments.is_empty() {
+            return Some(self.root_file.clone());
+        }
+        let base = segments.iter().fold(self.src_dir.clone(), |path, segment| path.join(segment));
+        [base.with_extension("rs"), base.join("mod.rs")].into_iter().find(|path| path.is_file())
+    }
+}
+
+fn module_display(segments: &[String]) -> String {
+    std::iter::once("crate").chain(segments.iter().map(String::as_str)).collect::<Vec<_>>().join("::")
+}
+
+fn use_statement(module: &[String], names: &[String]) -> String {
+    match names {
+        [single] => format!("use {}::{};", module_display(module), single),
+        many => format!("use {}::{{{}}};", module_display(module), many.join(", ")),
+    }
+}
+
+/// Name and visibility of an item that can be moved between modules
+fn movable_item(item: &syn::Item) -> Option<(&Ident, &syn::Visibility)> {
+    match item {
+        syn::Item::Fn(f) => Some((&f.sig.ident, &f.vis)),
+        syn::Item::Struct(s) => Some((&s.ident, &s.vis)),
+        syn::Item::Enum(e) => Some((&e.ident, &e.vis)),
+        syn::Item::Union(u) => Some((&u.ident, &u.vis)),
+        syn::Item::Trait(t) => Some((&t.ident, &t.vis)),
+        syn::Item::Type(t) => Some((&t.ident, &t.vis)),
+        syn::Item::Const(c) => Some((&c.ident, &c.vis)),
+        syn::Item::Static(s) => Some((&s.ident, &s.vis)),
+        _ => None,
+    }
+}
+
+/// Offset of the first token after an item's attributes
+fn item_keyword_offset(file: &SourceFile, item: &syn::Item) -> usize {
+    let attrs: &[syn::Attribute] = match item {
+        syn::Item::Fn(f) => &f.attrs,
+        syn::Item::Struct(s) => &s.attrs,
+        syn::Item::Enum(e) => &e.attrs,
+        syn::Item::Union(u) => &u.attrs,
+        syn::Item::Trait(t) => &t.attrs,
+        syn::Item::Type(t) => &t.attrs,
+        syn::Item::Const(c) => &c.attrs,
+        syn::Item::Static(s) => &s.attrs,
+        _ => &[],
+    };
+    let after_attrs = attrs
+        .iter()
+        .map(|attr| file.node_range(attr).end)
+        .max()
+        .unwrap_or_else(|| file.node_range(item).start);
+    after_attrs + (file.text[after_attrs..].len() - file.text[after_attrs..].trim_start().len())
+}
+
+fn is_relative_use(tree: &syn::UseTree) -> bool {
+    match tree {
+        syn::UseTree::Path(path) => path.ident == "self" || path.ident == "super",
+        syn::UseTree::Name(name) => name.ident == "self" || name.ident == "super",
+        _ => false,
+    }
+}
+
+fn use_leaf_names(tree: &syn::UseTree) -> Vec<String> {
+    match tree {
+        syn::UseTree::Path(path) => use_leaf_names(&path.tree),
+        syn::UseTree::Name(name) => vec![name.ident.to_string()],
+        syn::UseTree::Rename(rename) => vec![rename.rename.to_string()],
+        syn::UseTree::Glob(_) => Vec::new(),
+        syn::UseTree::Group(group) => group.items.iter().flat_map(use_leaf_names).collect(),
+    }
+}
+
+/// Rewrites `crate::old::Name` paths and imports to the item's new module
+struct PathRewriter<'a> {
+    file: &'a SourceFile,
+    name: &'a str,
+    old_prefix: &'a [String],
+    new_prefix: &'a str,
+    description: &'a str,
+    changes: Vec<CodeChange>,
+}
+
+impl PathRewriter<'_> {
+    fn prefix_matches(&self, segments: &[&Ident]) -> bool {
+        segments.len() == self.old_prefix.len() && segments.iter().zip(self.old_prefix).all(|(ident, expected)| *ident == expected)
+    }
+
+    fn replace_prefix(&mut self, first: &Ident, last: &Ident) {
+        let range = self.file.range(first.span()).start..self.file.range(last.span()).end;
+        self.changes.push(self.file.replace(range, self.new_prefix, ChangeType::ModifyImport, self.description));
+    }
+
+    fn visit_use_tree_with_prefix(&mut self, tree: &syn::UseTree, prefix: &mut Vec<Ident>, item: &syn::ItemUse) {
+        match tree {
+            syn::UseTree::Path(path) => {
+                prefix.push(path.ident.clone());
+                self.visit_use_tree_with_prefix(&path.tree, prefix, item);
+                prefix.pop();
+            }
+            syn::UseTree::Group(group) => {
+                let refs: Vec<&Ident> = prefix.iter().collect();
+                let matches = self.prefix_matches(&refs);
+                let pairs: Vec<_> = group.items.pairs().collect();
+                for (index, pair) in pairs.iter().enumerate() {
+                    let leaf = match pair.value() {
+                        syn::UseTree::Name(name) => Some(&name.ident),
+                        syn::UseTree::Rename(rename) => Some(&rename.ident),
+                        _ => None,
+                    };
+                    if !(matches && leaf.is_some_and(|ident| ident == self.name)) {
+                        self.visit_use_tree_with_prefix(pair.value(), prefix, item);
+                        continue;
+                    }
+                    if pairs.len() == 1 {
+                        let (first, last) = (prefix.first().cloned(), prefix.last().cloned());
+                        if let (Some(first), Some(last)) = (first, last) {
+                            self.replace_prefix(&first, &last);
+                        }
+                        continue;
+                    }
+                    // Drop the entry from the group and import it from its new module
+                    let value = self.file.node_range(pair.value());
+                    let range = match pair.punct() {
+                        Some(comma) => {
+                            let end = self.file.node_range(*comma).end;
+                            let rest = &self.file.text[end..];
+                            value.start..end + (rest.len() - rest.trim_start_matches(' ').len())
+                        }
+                        None => {
+                            let previous = pairs[index - 1].punct().map(|comma| self.file.node_range(*comma).start);
+                            previous.unwrap_or(value.start)..value.end
+                        }
+                    };
+                    self.changes.push(self.file.delete(range, self.description));
+                    let text = &self.file.text[value];
+                    self.changes.push(self.file.insert(
+                        self.file.node_range(item).end,
+                        format!("\n{}use {}::{};", self.file.indent_at(self.file.node_range(item).start), self.new_prefix, text),
+                        self.description,
+                    ));
+                }
+            }
+            syn::UseTree::Name(syn::UseName { ident }) | syn::UseTree::Rename(syn::UseRename { ident, .. }) => {
+                let refs: Vec<&Ident> = prefix.iter().collect();
+                if ident == self.name && self.prefix_matches(&refs) {
+                    let (first, last) = (prefix[0].clone(), prefix[prefix.len() - 1].clone());
+                    self.replace_prefix(&first, &last);
+                }
+            }
+            syn::UseTree::Glob(_) => {}
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for PathRewriter<'_> {
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        let segments: Vec<&Ident> = path.segments.iter().map(|segment| &segment.ident).collect();
+        let k = self.old_prefix.len();
+        if path.leading_colon.is_none() && segments.len() > k && segments[k] == self.name && self.prefix_matches(&segments[..k]) {
+            let (first, last) = (segments[0].clone(), segments[k - 1].clone());
+            self.replace_prefix(&first, &last);
+        }
+        visit::visit_path(self, path);
+    }
+
+    fn visit_item_use(&mut self, item: &'ast syn::ItemUse) {
+        if item.leading_colon.is_none() {
+            self.visit_use_tree_with_prefix(&item.tree, &mut Vec::new(), item);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Source files and spans
+// ---------------------------------------------------------------------------
+
+/// Parsed source file with byte offset lookup for spans
+struct SourceFile {
+    path: PathBuf,
+    text: String,
+    /// Length of a stripped byte order mark; spans start after it
+    bom: usize,
+    line_starts: Vec<usize>,
+    ast: syn::File,
+    functions: Vec<FnScope>,
+}
+
+/// A function body and its signature
+struct FnScope {
+    range: Range<usize>,
+    sig: syn::Signature,
+    block: syn::Block,
+    in_impl: bool,
+}
+
+impl SourceFile {
+    fn read(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        let bom = if text.starts_with('\u{feff}') { '\u{feff}'.len_utf8() } else { 0 };
+        let ast = syn::parse_file(&text[bom..]).map_err(|e| {
+            let at = e.span().start();
+            format!("Cannot parse {}:{}:{}: {}", path.display(), at.line, at.column + 1, e)
+        })?;
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+
+        let mut file = Self { path: path.to_path_buf(), text, bom, line_starts, ast, functions: Vec::new() };
+        let mut collector = FnCollector { file: &file, functions: Vec::new() };
+        collector.visit_file(&file.ast);
+        file.functions = collector.functions;
+        Ok(file)
+    }
+
+    fn offset(&self, at: LineColumn) -> usize {
+        let start = match at.line {
+            0 | 1 => self.bom,
+            line => match self.line_starts.get(line - 1) {
+                Some(start) => *start,
+                None => return self.text.len(),
+            },
+        };
+        self.text[start..].char_indices().nth(at.column).map_or(self.text.len(), |(i, _)| start + i)
+    }
+
+    fn range(&self, span: Span) -> Range<usize> {
+        self.offset(span.start())..self.offset(span.end())
+    }
+
+    fn node_range(&self, node: &impl Spanned) -> Range<usize> {
+        self.range(node.span())
+    }
+
+    fn position(&self, offset: usize) -> TextPosition {
+        let line = self.line_starts.partition_point(|&start| start <= offset).saturating_sub(1);
+        let column = self.text[self.line_starts[line]..offset].chars().count();
+        TextPosition { line, column, offset }
+    }
+
+    fn line_start(&self, offset: usize) -> usize {
+        self.text[..offset].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    fn indent_at(&self, offset: usize) -> &str {
+        let start = self.line_start(offset);
+        let line = &self.text[start..];
+        &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+    }
+
+    /// Widen `range` to whole lines when nothing else shares them
+    fn full_lines(&self, range: Range<usize>) -> Range<usize> {
+        let start = self.line_start(range.start);
+        if !self.text[start..range.start].trim().is_empty() {
+            return range;
+        }
+        let rest = &self.text[range.end..];
+        match rest.find('\n') {
+            Some(newline) if rest[..newline].trim().is_empty() => start..range.end + newline + 1,
+            None if rest.trim().is_empty() => start..self.text.len(),
+            _ => range,
+        }
+    }
+
+    /// Like [`Self::full_lines`], also taking one blank line that follows
+    fn full_lines_with_blank(&self, range: Range<usize>) -> Range<usize> {
+        let lines = self.full_lines(range);
+        let rest = &self.text[lines.end..];
+        match rest.find('\n') {
+            Some(newline) if rest[..newline].trim().is_empty() => lines.start..lines.end + newline + 1,
+            _ => lines,
+        }
+    }
+
+    /// Where new `use` declarations go: after the last one, or before the first item
+    fn import_offset(&self) -> usize {
+        match self.ast.items.iter().rfind(|item| matches!(item, syn::Item::Use(_))) {
+            Some(last) => self.full_lines(self.node_range(last)).end,
+            None => self.ast.items.first().map_or(self.text.len(), |item| self.line_start(self.node_range(item).start)),
+        }
+    }
+
+    /// Insertion point and text for declaring child module `name`
+    fn module_declaration_insert(&self, name: &str) -> (usize, String) {
+        let declaration = format!("pub mod {};\n", name);
+        let last_mod = self.ast.items.iter().rfind(|item| matches!(item, syn::Item::Mod(_)));
+        match last_mod {
+            Some(item) => (self.full_lines(self.node_range(item)).end, declaration),
+            None => {
+                let offset = self.import_offset();
+                let separator = if offset < self.text.len() { "\n" } else { "" };
+                (offset, format!("{}{}", declaration, separator))
+            }
+        }
+    }
+
+    /// Innermost function whose body contains `range`
+    fn enclosing_fn(&self, range: &Range<usize>) -> Option<&FnScope> {
+        self.functions
+            .iter()
+            .filter(|scope| contains(&scope.range, range))
+            .min_by_key(|scope| scope.range.len())
+    }
+
+    /// Innermost module-level item containing `range`
+    fn enclosing_module_item(&self, range: &Range<usize>) -> Option<Range<usize>> {
+        fn find(file: &SourceFile, items: &[syn::Item], range: &Range<usize>) -> Option<Range<usize>> {
+            let item = items.iter().find(|item| contains(&file.node_range(*item), range))?;
+            if let syn::Item::Mod(syn::ItemMod { content: Some((_, inner)), .. }) = item {
+                if let Some(inner_range) = find(file, inner, range) {
+                    return Some(inner_range);
+                }
+            }
+            Some(file.node_range(item))
+        }
+        find(self, &self.ast.items, range)
+    }
+
+    fn replace(&self, range: Range<usize>, new_text: impl Into<String>, change_type: ChangeType, description: &str) -> CodeChange {
+        CodeChange {
+            file_path: self.path.clone(),
+            change_type,
+            position: self.position(range.start),
+            old_text: Some(self.text[range].to_string()),
+            new_text: new_text.into(),
+            description: description.to_string(),
+        }
+    }
+
+    fn insert(&self, offset: usize, new_text: impl Into<String>, description: &str) -> CodeChange {
+        CodeChange {
+            file_path: self.path.clone(),
+            change_type: ChangeType::Insert,
+            position: self.position(offset),
+            old_text: None,
+            new_text: new_text.into(),
+            description: description.to_string(),
+        }
+    }
+
+    fn delete(&self, range: Range<usize>, description: &str) -> CodeChange {
+        self.replace(range, String::new(), ChangeType::Delete, description)
+    }
+}
+
+struct FnCollector<'a> {
+    file: &'a SourceFile,
+    functions: Vec<FnScope>,
+}
+
+impl<'ast> Visit<'ast> for FnCollector<'_> {
+    fn visit_item_fn(&mut self, f: &'ast syn::ItemFn) {
+        self.functions.push(FnScope { range: self.file.node_range(f), sig: f.sig.clone(), block: (*f.block).clone(), in_impl: false });
+        visit::visit_item_fn(self, f);
+    }
+
+    fn visit_impl_item_fn(&mut self, f: &'ast syn::ImplItemFn) {
+        self.functions.push(FnScope { range: self.file.node_range(f), sig: f.sig.clone(), block: f.block.clone(), in_impl: true });
+        visit::visit_impl_item_fn(self, f);
+    }
+
+    fn visit_trait_item_fn(&mut self, f: &'ast syn::TraitItemFn) {
+        if let Some(block) = &f.default {
+            self.functions.push(FnScope { range: self.file.node_range(f), sig: f.sig.clone(), block: block.clone(), in_impl: false });
+        }
+        visit::visit_trait_item_fn(self, f);
+    }
+}
+
+/// Byte range of the selection with surrounding whitespace trimmed
+fn selection_range(file: &SourceFile, selection: &CodeSelection) -> Range<usize> {
+    let clamp = |offset: usize| {
+        let mut offset = offset.min(file.text.len());
+        while !file.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    };
+    let (start, end) = (clamp(selection.start.offset), clamp(selection.end.offset.max(selection.start.offset)));
+    let text = &file.text[start..end];
+    let start = start + (text.len() - text.trim_start().len());
+    let end = end - (text.len() - text.trim_end().len());
+    start..end.max(start)
+}
+
+fn contains(outer: &Range<usize>, inner: &Range<usize>) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+fn required<'a>(parameters: &'a HashMap<String, String>, name: &str) -> Result<&'a str, String> {
+    let value = parameters
+        .get(name)
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| format!("Required parameter '{}' is missing", name))?;
+    if name.ends_with("_name") && syn::parse_str::<Ident>(value).is_err() {
+        return Err(format!("`{}` is not a valid identifier", value));
+    }
+    Ok(value)
+}
+
+// ---------------------------------------------------------------------------
+// Syntax queries
+// ---------------------------------------------------------------------------
+
+/// Identifier token touching `offset`
+fn ident_at(file: &SourceFile, offset: usize) -> Option<Ident> {
+    collect_idents(&file.ast, None)
+        .into_iter()
+        .filter(|ident| {
+            let range = file.range(ident.span());
+            range.start <= offset && offset <= range.end
+        })
+        .min_by_key(|ident| file.range(ident.span()).len())
+}
+
+/// Every identifier in `file`, optionally only those named `name`, including macro input
+fn collect_idents(file: &syn::File, name: Option<&str>) -> Vec<Ident> {
+    let mut collector = IdentCollector { name, found: Vec::new() };
+    collector.visit_file(file);
+    collector.found
+}
+
+fn collect_idents_in(item: &syn::Item, name: Option<&str>) -> Vec<Ident> {
+    let mut collector = IdentCollector { name, found: Vec::new() };
+    collector.visit_item(item);
+    collector.found
+}
+
+struct IdentCollector<'a> {
+    name: Option<&'a str>,
+    found: Vec<Ident>,
+}
+
+impl IdentCollector<'_> {
+    fn push(&mut self, ident: &Ident) {
+        if self.name.is_none_or(|name| ident == name) {
+            self.found.push(ident.clone());
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for IdentCollector<'_> {
+    fn visit_ident(&mut self, ident: &'ast Ident) {
+        self.push(ident);
+    }
+
+    fn visit_lifetime(&mut self, _: &'ast syn::Lifetime) {}
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        visit::visit_macro(self, mac);
+        for_each_token_ident(mac.tokens.clone(), &mut |ident| self.push(&ident));
+    }
+}
+
+fn for_each_token_ident(tokens: TokenStream, visit: &mut dyn FnMut(Ident)) {
+    // Lifetimes arrive as an apostrophe followed by an identifier
+    let mut after_apostrophe = false;
+    for token in tokens {
+        match &token {
+            TokenTree::Ident(ident) if !after_apostrophe => visit(ident.clone()),
+            TokenTree::Group(group) => for_each_token_ident(group.stream(), visit),
+            _ => {}
+        }
+        after_apostrophe = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == '\'');
+    }
+}
+
+/// Names of items, fields, variants and associated items defined in `file`
+fn defined_names(file: &syn::File) -> HashSet<String> {
+    struct Definitions(HashSet<String>);
+    impl<'ast> Visit<'ast> for Definitions {
+        fn visit_item(&mut self, item: &'ast syn::Item) {
+            let ident = match item {
+                syn::Item::Fn(f) => Some(&f.sig.ident),
+                syn::Item::Struct(s) => Some(&s.ident),
+                syn::Item::Enum(e) => Some(&e.ident),
+                syn::Item::Union(u) => Some(&u.ident),
+                syn::Item::Trait(t) => Some(&t.ident),
+                syn::Item::Type(t) => Some(&t.ident),
+                syn::Item::Const(c) => Some(&c.ident),
+                syn::Item::Static(s) => Some(&s.ident),
+                syn::Item::Mod(m) => Some(&m.ident),
+                syn::Item::Macro(m) => m.ident.as_ref(),
+                _ => None,
+            };
+            if let Some(ident) = ident {
+                self.0.insert(ident.to_string());
+            }
+            visit::visit_item(self, item);
+        }
+        fn visit_field(&mut self, field: &'ast syn::Field) {
+            if let Some(ident) = &field.ident {
+                self.0.insert(ident.to_string());
+            }
+            visit::visit_field(self, field);
+        }
+        fn visit_variant(&mut self, variant: &'ast syn::Variant) {
+            self.0.insert(variant.ident.to_string());
+            visit::visit_variant(self, variant);
+        }
+        fn visit_impl_item_fn(&mut self, f: &'ast syn::ImplItemFn) {
+            self.0.insert(f.sig.ident.to_string());
+            visit::visit_impl_item_fn(self, f);
+        }
+        fn visit_impl_item_const(&mut self, c: &'ast syn::ImplItemConst) {
+            self.0.insert(c.ident.to_string());
+            visit::visit_impl_item_const(self, c);
+        }
+        fn visit_impl_item_type(&mut self, t: &'ast syn::ImplItemType) {
+            self.0.insert(t.ident.to_string());
+            visit::visit_impl_item_type(self, t);
+        }
+        fn visit_trait_item_fn(&mut self, f: &'ast syn::TraitItemFn) {
+            self.0.insert(f.sig.ident.to_string());
+            visit::visit_trait_item_fn(self, f);
+        }
+    }
+
+    let mut definitions = Definitions(HashSet::new());
+    definitions.visit_file(file);
+    definitions.0
+}
+
+/// Kind of definition named `name`, for classifying a cursor position
+struct DefinitionKind {
+    name: String,
+    kind: Option<CodeContext>,
+}
+
+impl<'ast> Visit<'ast> for DefinitionKind {
+    fn visit_item(&mut self, item: &'ast syn::Item) {
+        let kind = match item {
+            syn::Item::Fn(f) if f.sig.ident == self.name => Some(CodeContext::Function),
+            syn::Item::Struct(s) if s.ident == self.name => Some(CodeContext::Type),
+            syn::Item::Union(u) if u.ident == self.name => Some(CodeContext::Type),
+            syn::Item::Type(t) if t.ident == self.name => Some(CodeContext::Type),
+            syn::Item::Enum(e) if e.ident == self.name => Some(CodeContext::Enum),
+            syn::Item::Trait(t) if t.ident == self.name => Some(CodeContext::Trait),
+            syn::Item::Mod(m) if m.ident == self.name => Some(CodeContext::Module),
+            syn::Item::Const(c) if c.ident == self.name => Some(CodeContext::Variable),
+            syn::Item::Static(s) if s.ident == self.name => Some(CodeContext::Variable),
+            _ => None,
+        };
+        self.kind = self.kind.take().or(kind);
+        visit::visit_item(self, item);
+    }
+
+    fn visit_impl_item_fn(&mut self, f: &'ast syn::ImplItemFn) {
+        if f.sig.ident == self.name && self.kind.is_none() {
+            self.kind = Some(CodeContext::Method);
+        }
+        visit::visit_impl_item_fn(self, f);
+    }
+}
+
+/// Everything a pattern binds
+fn pattern_bindings(pat: &syn::Pat) -> Vec<Ident> {
+    let mut bindings = Bindings(Vec::new());
+    bindings.visit_pat(pat);
+    bindings.0
+}
+
+/// Names bound anywhere in a function, parameters included
+fn scope_bindings(scope: &FnScope) -> Vec<Ident> {
+    let mut bindings = Bindings(Vec::new());
+    for input in &scope.sig.inputs {
+        bindings.visit_fn_arg(input);
+    }
+    bindings.visit_block(&scope.block);
+    bindings.0
+}
+
+fn stmt_bindings(stmt: &syn::Stmt) -> Vec<Ident> {
+    let mut bindings = Bindings(Vec::new());
+    bindings.visit_stmt(stmt);
+    bindings.0
+}
+
+struct Bindings(Vec<Ident>);
+
+impl<'ast> Visit<'ast> for Bindings {
+    fn visit_pat_ident(&mut self, pat: &'ast syn::PatIdent) {
+        self.0.push(pat.ident.clone());
+        visit::visit_pat_ident(self, pat);
+    }
+
+    fn visit_item(&mut self, _: &'ast syn::Item) {}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LocalRefKind {
+    /// Pattern that binds the name
+    Binding,
+    /// Expression reading the name
+    Use,
+    /// Struct field shorthand in an expression, `Point { x }`
+    Shorthand,
+    /// Struct field shorthand in a pattern, which binds the name
+    ShorthandPat,
+}
+
+struct LocalRef {
+    span: Span,
+    kind: LocalRefKind,
+}
+
+/// Bindings and uses of local `name` within a function
+fn local_refs(scope: &FnScope, name: &str) -> Vec<LocalRef> {
+    let mut finder = LocalRefs { name, refs: Vec::new(), scopes: vec![false] };
+    for input in &scope.sig.inputs {
+        finder.visit_fn_arg(input);
+    }
+    finder.visit_block(&scope.block);
+    finder.refs
+}
+
+/// Start offsets of local references to `name` in functions that bind it
+fn local_occurrences(file: &SourceFile, name: &str) -> HashMap<usize, LocalRefKind> {
+    file.functions
+        .iter()
+        .filter(|scope| scope_bindings(scope).iter().any(|ident| ident == name))
+        .flat_map(|scope| local_refs(scope, name))
+        .map(|reference| (file.range(reference.span).start, reference.kind))
+        .collect()
+}
+
+/// Start offsets of field names: definitions, accesses and struct literal or
+/// pattern members
+fn member_positions(file: &SourceFile) -> HashSet<usize> {
+    struct Members<'a> {
+        file: &'a SourceFile,
+        found: HashSet<usize>,
+    }
+    impl Members<'_> {
+        fn member(&mut self, member: &syn::Member) {
+            if let syn::Member::Named(ident) = member {
+                self.found.insert(self.file.range(ident.span()).start);
+            }
+        }
+    }
+    impl<'ast> Visit<'ast> for Members<'_> {
+        fn visit_field(&mut self, field: &'ast syn::Field) {
+            if let Some(ident) = &field.ident {
+                self.found.insert(self.file.range(ident.span()).start);
+            }
+            visit::visit_field(self, field);
+        }
+        fn visit_expr_field(&mut self, expr: &'ast syn::ExprField) {
+            self.member(&expr.member);
+            visit::visit_expr_field(self, expr);
+        }
+        fn visit_field_value(&mut self, field: &'ast syn::FieldValue) {
+            self.member(&field.member);
+            visit::visit_field_value(self, field);
+        }
+        fn visit_field_pat(&mut self, field: &'ast syn::FieldPat) {
+            self.member(&field.member);
+            visit::visit_field_pat(self, field);
+        }
+    }
+
+    let mut members = Members { file, found: HashSet::new() };
+    members.visit_file(&file.ast);
+    members.found
+}
+
+/// Scope-aware search for a local; uses only count once a binding is in scope
+struct LocalRefs<'a> {
+    name: &'a str,
+    refs: Vec<LocalRef>,
+    /// Whether the name is bound in each enclosing scope
+    scopes: Vec<bool>,
+}
+
+impl LocalRefs<'_> {
+    fn in_scope(&self) -> bool {
+        self.scopes.iter().any(|bound| *bound)
+    }
+
+    fn bind(&mut self, ident: &Ident, kind: LocalRefKind) {
+        self.refs.push(LocalRef { span: ident.span(), kind });
+        if let Some(top) = self.scopes.last_mut() {
+            *top = true;
+        }
+    }
+
+    fn use_of(&mut self, ident: &Ident, kind: LocalRefKind) {
+        if ident == self.name && self.in_scope() {
+            self.refs.push(LocalRef { span: ident.span(), kind });
+        }
+    }
+
+    fn scoped(&mut self, visit: impl FnOnce(&mut Self)) {
+        self.scopes.push(false);
+        visit(self);
+        self.scopes.pop();
+    }
+}
+
+impl<'ast> Visit<'ast> for LocalRefs<'_> {
+    fn visit_block(&mut self, block: &'ast syn::Block) {
+        self.scoped(|refs| visit::visit_block(refs, block));
+    }
+
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        // The initializer cannot see the bindings it initializes
+        if let Some(init) = &local.init {
+            self.visit_expr(&init.expr);
+            if let Some((_, diverge)) = &init.diverge {
+                self.visit_expr(diverge);
+            }
+        }
+        self.visit_pat(&local.pat);
+    }
+
+    fn visit_expr_let(&mut self, expr: &'ast syn::ExprLet) {
+        self.visit_expr(&expr.expr);
+        self.visit_pat(&expr.pat);
+    }
+
+    fn visit_expr_if(&mut self, expr: &'ast syn::ExprIf) {
+        self.scoped(|refs| {
+            refs.visit_expr(&expr.cond);
+            refs.visit_block(&expr.then_branch);
+        });
+        if let Some((_, else_branch)) = &expr.else_branch {
+            self.visit_expr(else_branch);
+        }
+    }
+
+    fn visit_expr_while(&mut self, expr: &'ast syn::ExprWhile) {
+        self.scoped(|refs| {
+            refs.visit_expr(&expr.cond);
+            refs.visit_block(&expr.body);
+        });
+    }
+
+    fn visit_expr_for_loop(&mut self, expr: &'ast syn::ExprForLoop) {
+        self.visit_expr(&expr.expr);
+        self.scoped(|refs| {
+            refs.visit_pat(&expr.pat);
+            refs.visit_block(&expr.body);
+        });
+    }
+
+    fn visit_arm(&mut self, arm: &'ast syn::Arm) {
+        self.scoped(|refs| visit::visit_arm(refs, arm));
+    }
+
+    fn visit_expr_closure(&mut self, expr: &'ast syn::ExprClosure) {
+        self.scoped(|refs| visit::visit_expr_closure(refs, expr));
+    }
+
+    fn visit_pat_ident(&mut self, pat: &'ast syn::PatIdent) {
+        if let Some((_, subpat)) = &pat.subpat {
+            self.visit_pat(subpat);
+        }
+        if pat.ident == self.name {
+            self.bind(&pat.ident, LocalRefKind::Binding);
+        }
+    }
+
+    fn visit_expr_path(&mut self, expr: &'ast syn::ExprPath) {
+        if expr.qself.is_none() {
+            if let Some(ident) = expr.path.get_ident() {
+                self.use_of(ident, LocalRefKind::Use);
+            }
+        }
+        visit::visit_expr_path(self, expr);
+    }
+
+    fn visit_field_value(&mut self, field: &'ast syn::FieldValue) {
+        match &field.member {
+            syn::Member::Named(ident) if field.colon_token.is_none() => self.use_of(ident, LocalRefKind::Shorthand),
+            _ => visit::visit_field_value(self, field),
+        }
+    }
+
+    fn visit_field_pat(&mut self, field: &'ast syn::FieldPat) {
+        match &field.member {
+            syn::Member::Named(ident) if field.colon_token.is_none() => {
+                if ident == self.name {
+                    self.bind(ident, LocalRefKind::ShorthandPat);
+                }
+            }
+            _ => visit::visit_field_pat(self, field),
+        }
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        for_each_token_ident(mac.tokens.clone(), &mut |ident| self.use_of(&ident, LocalRefKind::Use));
+    }
+
+    fn visit_item(&mut self, _: &'ast syn::Item) {}
+}
+
+/// Finds the last simple `let name = ...` declared before an offset
+struct LocalFinder<'a> {
+    file: &'a SourceFile,
+    name: &'a str,
+    before: usize,
+    /// Statement range, the statement, and the end of its block
+    found: Option<(Range<usize>, syn::Local, usize)>,
+}
+
+impl<'ast> Visit<'ast> for LocalFinder<'_> {
+    fn visit_block(&mut self, block: &'ast syn::Block) {
+        let block_end = self.file.node_range(block).end.saturating_sub(1);
+        for stmt in &block.stmts {
+            if let syn::Stmt::Local(local) = stmt {
+                let range = self.file.node_range(local);
+                let binds = pattern_bindings(&local.pat).iter().any(|ident| ident == self.name);
+                if binds && range.start < self.before {
+                    self.found = Some((range, local.clone(), block_end));
+                }
+            }
+        }
+        visit::visit_block(self, block);
+    }
+
+    fn visit_item(&mut self, _: &'ast syn::Item) {}
+}
+
+/// Expression in `scope` spanning exactly `range`
+fn expr_at(scope: &FnScope, file: &SourceFile, range: &Range<usize>) -> Option<syn::Expr> {
+    struct ExprAt<'a> {
+        file: &'a SourceFile,
+        range: &'a Range<usize>,
+        found: Option<syn::Expr>,
+    }
+    impl<'ast> Visit<'ast> for ExprAt<'_> {
+        fn visit_expr(&mut self, expr: &'ast syn::Expr) {
+            if self.found.is_some() {
+                return;
+            }
+            if self.file.node_range(expr) == *self.range {
+                self.found = Some(expr.clone());
+                return;
+            }
+            visit::visit_expr(self, expr);
+        }
+        fn visit_item(&mut self, _: &'ast syn::Item) {}
+    }
+
+    let mut finder = ExprAt { file, range, found: None };
+    finder.visit_block(&scope.block);
+    finder.found
+}
+
+/// Innermost statement containing `range`, with its byte range
+fn innermost_stmt(scope: &FnScope, file: &SourceFile, range: &Range<usize>) -> Option<(Range<usize>, syn::Stmt)> {
+    struct StmtAt<'a> {
+        file: &'a SourceFile,
+        range: &'a Range<usize>,
+        found: Option<(Range<usize>, syn::Stmt)>,
+    }
+    impl<'ast> Visit<'ast> for StmtAt<'_> {
+        fn visit_stmt(&mut self, stmt: &'ast syn::Stmt) {
+            let stmt_range = self.file.node_range(stmt);
+            if contains(&stmt_range, self.range) {
+                self.found = Some((stmt_range, stmt.clone()));
+                visit::visit_stmt(self, stmt);
+            }
+        }
+        fn visit_item(&mut self, _: &'ast syn::Item) {}
+    }
+
+    let mut finder = StmtAt { file, range, found: None };
+    finder.visit_block(&scope.block);
+    finder.found
+}
+
+/// Consecutive statements of one block spanning exactly `range`, and whether
+/// the last of them is the block's tail
+fn selected_stmts(scope: &FnScope, file: &SourceFile, range: &Range<usize>) -> Option<(Vec<syn::Stmt>, bool)> {
+    struct Stmts<'a> {
+        file: &'a SourceFile,
+        range: &'a Range<usize>,
+        found: Option<(Vec<syn::Stmt>, bool)>,
+    }
+    impl<'ast> Visit<'ast> for Stmts<'_> {
+        fn visit_block(&mut self, block: &'ast syn::Block) {
+            if self.found.is_some() || !contains(&self.file.node_range(block), self.range) {
+                return;
+            }
+            let ranges: Vec<Range<usize>> = block.stmts.iter().map(|stmt| self.file.node_range(stmt)).collect();
+            let first = ranges.iter().position(|r| r.start == self.range.start);
+            let last = ranges.iter().position(|r| r.end == self.range.end);
+            if let (Some(first), Some(last)) = (first, last) {
+                if first <= last {
+                    self.found = Some((block.stmts[first..=last].to_vec(), last + 1 == block.stmts.len()));
+                    return;
+                }
+            }
+            visit::visit_block(self, block);
+        }
+        fn visit_item(&mut self, _: &'ast syn::Item) {}
+    }
+
+    let mut finder = Stmts { file, range, found: None };
+    finder.visit_block(&scope.block);
+    finder.found
+}
+
+/// Expressions that need no parentheses when substituted for a variable
+fn is_atomic(expr: &syn::Expr) -> bool {
+    matches!(
+        expr,
+        syn::Expr::Lit(_)
+            | syn::Expr::Path(_)
+            | syn::Expr::Call(_)
+            | syn::Expr::MethodCall(_)
+            | syn::Expr::Field(_)
+            | syn::Expr::Paren(_)
+            | syn::Expr::Macro(_)
+            | syn::Expr::Index(_)
+            | syn::Expr::Tuple(_)
+            | syn::Expr::Array(_)
+            | syn::Expr::Struct(_)
+    )
+}
+
+/// Variable use and control flow within statements being extracted
+#[derive(Default)]
+struct BodyScan {
+    /// Single-segment paths read, in source order, `self` included
+    uses: Vec<Ident>,
+    /// Names bound by patterns
+    declared: Vec<Ident>,
+    /// Locals assigned to or mutably borrowed
+    assigned: HashSet<String>,
+    mutates_self: bool,
+    returns: bool,
+    tries: bool,
+    awaits: bool,
+    escapes_loop: bool,
+    loop_depth: usize,
+}
+
+impl BodyScan {
+    fn mark_assigned(&mut self, target: &syn::Expr) {
+        let mut expr = target;
+        loop {
+            expr = match expr {
+                syn::Expr::Field(field) => &field.base,
+                syn::Expr::Index(index) => &index.expr,
+                syn::Expr::Paren(paren) => &paren.expr,
+                syn::Expr::Unary(unary) => &unary.expr,
+                syn::Expr::Path(path) => {
+                    if let Some(ident) = path.path.get_ident() {
+                        if ident == "self" {
+                            self.mutates_self = true;
+                        } else {
+                            self.assigned.insert(ident.to_string());
+                        }
+                    }
+                    return;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn visit_nested_body(&mut self, visit: impl FnOnce(&mut Self)) {
+        let saved = (self.returns, self.tries, self.escapes_loop, self.loop_depth);
+        self.loop_depth = 0;
+        visit(self);
+        (self.returns, self.tries, self.escapes_loop, self.loop_depth) = saved;
+    }
+
+    fn visit_loop_body(&mut self, visit: impl FnOnce(&mut Self)) {
+        self.loop_depth += 1;
+        visit(self);
+        self.loop_depth -= 1;
+    }
+}
+
+impl<'ast> Visit<'ast> for BodyScan {
+    fn visit_expr_path(&mut self, expr: &'ast syn::ExprPath) {
+        if expr.qself.is_none() {
+            if let Some(ident) = expr.path.get_ident() {
+                self.uses.push(ident.clone());
+            }
+        }
+        visit::visit_expr_path(self, expr);
+    }
+
+    fn visit_field_value(&mut self, field: &'ast syn::FieldValue) {
+        match &field.member {
+            syn::Member::Named(ident) if field.colon_token.is_none() => self.uses.push(ident.clone()),
+            _ => visit::visit_field_value(self, field),
+        }
+    }
+
+    fn visit_pat_ident(&mut self, pat: &'ast syn::PatIdent) {
+        self.declared.push(pat.ident.clone());
+        visit::visit_pat_ident(self, pat);
+    }
+
+    fn visit_expr_assign(&mut self, expr: &'ast syn::ExprAssign) {
+        self.mark_assigned(&expr.left);
+        visit::visit_expr_assign(self, expr);
+    }
+
+    fn visit_expr_binary(&mut self, expr: &'ast syn::ExprBinary) {
+        use syn::BinOp::*;
+        if matches!(
+            expr.op,
+            AddAssign(_) | SubAssign(_) | MulAssign(_) | DivAssign(_) | RemAssign(_) | BitXorAssign(_) | BitAndAssign(_) | BitOrAssign(_) | ShlAssign(_) | ShrAssign(_)
+        ) {
+            self.mark_assigned(&expr.left);
+        }
+        visit::visit_expr_binary(self, expr);
+    }
+
+    fn visit_expr_reference(&mut self, expr: &'ast syn::ExprReference) {
+        if expr.mutability.is_some() {
+            self.mark_assigned(&expr.expr);
+        }
+        visit::visit_expr_reference(self, expr);
+    }
+
+    fn visit_expr_return(&mut self, expr: &'ast syn::ExprReturn) {
+        self.returns = true;
+        visit::visit_expr_return(self, expr);
+    }
+
+    fn visit_expr_try(&mut self, expr: &'ast syn::ExprTry) {
+        self.tries = true;
+        visit::visit_expr_try(self, expr);
+    }
+
+    fn visit_expr_await(&mut self, expr: &'ast syn::ExprAwait) {
+        self.awaits = true;
+        visit::visit_expr_await(self, expr);
+    }
+
+    fn visit_expr_break(&mut self, expr: &'ast syn::ExprBreak) {
+        if self.loop_depth == 0 || expr.label.is_some() {
+            self.escapes_loop = true;
+        }
+        visit::visit_expr_break(self, expr);
+    }
+
+    fn visit_expr_continue(&mut self, expr: &'ast syn::ExprContinue) {
+        if self.loop_depth == 0 || expr.label.is_some() {
+            self.escapes_loop = true;
+        }
+        visit::visit_expr_continue(self, expr);
+    }
+
+    fn visit_expr_loop(&mut self, expr: &'ast syn::ExprLoop) {
+        self.visit_loop_body(|scan| visit::visit_expr_loop(scan, expr));
+    }
+
+    fn visit_expr_while(&mut self, expr: &'ast syn::ExprWhile) {
+        self.visit_loop_body(|scan| visit::visit_expr_while(scan, expr));
+    }
+
+    fn visit_expr_for_loop(&mut self, expr: &'ast syn::ExprForLoop) {
+        self.visit_loop_body(|scan| visit::visit_expr_for_loop(scan, expr));
+    }
+
+    fn visit_expr_closure(&mut self, expr: &'ast syn::ExprClosure) {
+        self.visit_nested_body(|scan| visit::visit_expr_closure(scan, expr));
+    }
+
+    fn visit_expr_async(&mut self, expr: &'ast syn::ExprAsync) {
+        let awaits = self.awaits;
+        self.visit_nested_body(|scan| visit::visit_expr_async(scan, expr));
+        self.awaits = awaits;
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        for_each_token_ident(mac.tokens.clone(), &mut |ident| self.uses.push(ident));
+    }
+
+    fn visit_item(&mut self, _: &'ast syn::Item) {}
+}
@@ -1,25 +1,44 @@
 // Token-based syntax highlighting and theme support for CodeEditor
 // This module provides a wrapper around syntect for use in the code editor.
+//
+// `SyntaxHighlighter::highlight_line` colors a line on its own, without the
+// lines around it. Whole documents go through `DocumentHighlighter`, which
+// saves the syntect parse and highlight state at every line boundary so
+// block comments, raw strings and other multi-line constructs are colored
+// correctly. After an edit it re-highlights from the first changed line
+// until the state at a line boundary matches the saved one again.
 
-use syntect::parsing::SyntaxSet;
-use syntect::highlighting::{ThemeSet, Theme, Style as SyntectStyle};
+use std::sync::{Arc, OnceLock};
+
+use syntect::parsing::{ParseState, ScopeStack, SyntaxSet};
+use syntect::highlighting::{ThemeSet, Theme, Style as SyntectStyle, Highlighter, HighlightIterator, HighlightState};
 use syntect::easy::HighlightLines;
 use egui::Color32;
 
+/// Colored text segments making up one line
+pub type HighlightedSegments = Vec<(String, Color32)>;
+
+/// Syntax definitions shared by every highlighter, loaded on first use
+fn default_syntax_set() -> Arc<SyntaxSet> {
+    static SYNTAX_SET: OnceLock<Arc<SyntaxSet>> = OnceLock::new();
+    SYNTAX_SET.get_or_init(|| Arc::new(SyntaxSet::load_defaults_newlines())).clone()
+}
+
 pub struct SyntaxHighlighter {
-    pub syntax_set: SyntaxSet,
+    pub syntax_set: Arc<SyntaxSet>,
     pub theme_set: ThemeSet,
-    pub theme: Theme,
+    pub theme: Arc<Theme>,
 }
 
 impl SyntaxHighlighter {
     pub fn new(theme_name: &str) -> Self {
-        let syntax_set = SyntaxSet::load_defaults_newlines();
+        let syntax_set = default_syntax_set();
         let theme_set = ThemeSet::load_defaults();
         let theme = theme_set.themes.get(theme_name).cloned().unwrap_or_else(|| theme_set.themes["InspiredGitHub"].clone());
-        Self { syntax_set, theme_set, theme }
+        Self { syntax_set, theme_set, theme: Arc::new(theme) }
     }
 
+    /// Highlight a single line without any surrounding context
     pub fn highlight_line(&self, line: &str, language: &str) -> Vec<(String, Color32)> {
         let syntax = self.syntax_set.find_syntax_by_token(language).unwrap_or_else(|| self.syntax_set.find_syntax_plain_text());
         let mut h = HighlightLines::new(syntax, &self.theme);
@@ -30,6 +49,21 @@ impl SyntaxHighlighter {
         result
     }
 
+    /// Highlight every line of `text`, carrying state from line to line
+    pub fn highlight_text(&self, text: &str, language: &str) -> Vec<HighlightedSegments> {
+        let mut document = self.document(language);
+        document.set_text(text);
+        document.highlight_all();
+        (0..document.line_count())
+            .map(|index| document.line(index).map(<[_]>::to_vec).unwrap_or_default())
+            .collect()
+    }
+
+    /// Create an incremental highlighter for a document in `language`
+    pub fn document(&self, language: &str) -> DocumentHighlighter {
+        DocumentHighlighter::new(self.syntax_set.clone(), self.theme.clone(), language)
+    }
+
     fn syntect_to_egui_color(style: SyntectStyle) -> Color32 {
         Color32::from_rgba_unmultiplied(style.foreground.r, style.foreground.g, style.foreground.b, style.foreground.a)
     }
@@ -40,7 +74,331 @@ impl SyntaxHighlighter {
 
     pub fn set_theme(&mut self, theme_name: &str) {
         if let Some(theme) = self.theme_set.themes.get(theme_name) {
-            self.theme = theme.clone();
+            self.theme = Arc::new(theme.clone());
+        }
+    }
+}
+
+/// Syntect parse and highlight state at a line boundary
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LineState {
+    parse: ParseState,
+    highlight: HighlightState,
+}
+
+/// A document line with the state it was highlighted from
+struct DocumentLine {
+    text: String,
+    /// State at the start of the line
+    start_state: LineState,
+    /// Whether the line must be highlighted again
+    dirty: bool,
+    /// Highlight result, `None` until the line has been highlighted once
+    segments: Option<HighlightedSegments>,
+}
+
+/// Incremental, stateful highlighter for a whole document
+///
+/// Edits mark lines dirty; [`Self::highlight_pending`] then re-highlights
+/// from the first dirty line and stops as soon as the state at the start
+/// of an unchanged line equals the one saved for it, because everything
+/// after that line would come out the same.
+pub struct DocumentHighlighter {
+    syntax_set: Arc<SyntaxSet>,
+    theme: Arc<Theme>,
+    language: String,
+    /// State at the start of the document
+    initial_state: LineState,
+    /// State after the last line
+    end_state: LineState,
+    lines: Vec<DocumentLine>,
+    /// No line before this index is dirty
+    first_dirty: Option<usize>,
+    /// Number of lines highlighted so far
+    lines_highlighted: u64,
+}
+
+impl DocumentHighlighter {
+    /// Create a highlighter for an empty document
+    ///
+    /// Unknown languages are highlighted as plain text.
+    pub fn new(syntax_set: Arc<SyntaxSet>, theme: Arc<Theme>, language: &str) -> Self {
+        let initial_state = Self::initial_state(&syntax_set, &theme, language);
+        Self {
+            syntax_set,
+            theme,
+            language: language.to_string(),
+            end_state: initial_state.clone(),
+            initial_state,
+            lines: Vec::new(),
+            first_dirty: None,
+            lines_highlighted: 0,
+        }
+    }
+
+    fn initial_state(syntax_set: &SyntaxSet, theme: &Theme, language: &str) -> LineState {
+        let syntax = syntax_set.find_syntax_by_token(language).unwrap_or_else(|| syntax_set.find_syntax_plain_text());
+        LineState {
+            parse: ParseState::new(syntax),
+            highlight: HighlightState::new(&Highlighter::new(theme), ScopeStack::new()),
+        }
+    }
+
+    /// Language the document is highlighted as
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Number of lines in the document
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Text of a line
+    pub fn line_text(&self, index: usize) -> Option<&str> {
+        self.lines.get(index).map(|line| line.text.as_str())
+    }
+
+    /// Highlighted segments of a line
+    ///
+    /// Returns `None` for lines that have not been highlighted yet. Lines
+    /// after a pending edit keep their previous colors until they are
+    /// highlighted again.
+    pub fn line(&self, index: usize) -> Option<&[(String, Color32)]> {
+        self.lines.get(index)?.segments.as_deref()
+    }
+
+    /// Segments of a line, falling back to uncolored text if it was never highlighted
+    pub fn line_or_plain(&self, index: usize) -> HighlightedSegments {
+        match self.lines.get(index) {
+            Some(DocumentLine { segments: Some(segments), .. }) => segments.clone(),
+            Some(line) => self.plain_segments(&line.text),
+            None => Vec::new(),
+        }
+    }
+
+    /// `text` as a single segment in the theme's foreground color
+    pub fn plain_segments(&self, text: &str) -> HighlightedSegments {
+        plain_segments(&self.theme, text)
+    }
+
+    /// Whether a line is waiting to be highlighted
+    pub fn is_dirty(&self, index: usize) -> bool {
+        self.lines.get(index).is_some_and(|line| line.dirty)
+    }
+
+    /// Total number of lines highlighted since the highlighter was created
+    pub fn lines_highlighted(&self) -> u64 {
+        self.lines_highlighted
+    }
+
+    /// Number of lines that have been highlighted and are not dirty
+    pub fn highlighted_line_count(&self) -> usize {
+        self.lines.iter().filter(|line| !line.dirty && line.segments.is_some()).count()
+    }
+
+    /// Number of lines waiting to be highlighted
+    pub fn dirty_line_count(&self) -> usize {
+        match self.first_dirty {
+            Some(first) => self.lines[first.min(self.lines.len())..].iter().filter(|line| line.dirty).count(),
+            None => 0,
         }
     }
+
+    /// Whether every line is highlighted
+    pub fn is_complete(&mut self) -> bool {
+        self.next_dirty(0).is_none()
+    }
+
+    /// Replace the whole document
+    pub fn set_text(&mut self, text: &str) {
+        let lines: Vec<&str> = text.lines().collect();
+        self.edit_lines(0, self.lines.len(), &lines);
+    }
+
+    /// Bring the document in line with `text`, editing only the lines that changed
+    ///
+    /// Returns whether anything changed.
+    pub fn update(&mut self, text: &str) -> bool {
+        let new_lines: Vec<&str> = text.lines().collect();
+        let old_lines: Vec<&str> = self.lines.iter().map(|line| line.text.as_str()).collect();
+        match changed_line_range(&old_lines, &new_lines) {
+            Some((start, old_end, new_end)) => {
+                self.edit_lines(start, old_end, &new_lines[start..new_end]);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replace the lines `start..end` with `new_lines`
+    pub fn edit_lines(&mut self, start: usize, end: usize, new_lines: &[&str]) {
+        let end = end.min(self.lines.len());
+        let start = start.min(end);
+        // State before the edit; still valid unless an earlier line is dirty,
+        // in which case it is recomputed before these lines are reached
+        let state = self.lines.get(start).map_or_else(|| self.end_state.clone(), |line| line.start_state.clone());
+
+        let replacement: Vec<DocumentLine> = new_lines.iter()
+            .map(|text| DocumentLine {
+                text: text.to_string(),
+                start_state: state.clone(),
+                dirty: true,
+                segments: None,
+            })
+            .collect();
+        self.lines.splice(start..end, replacement);
+
+        if new_lines.is_empty() {
+            // The line after a deletion now follows a different line
+            match self.lines.get_mut(start) {
+                Some(line) => {
+                    line.start_state = state;
+                    line.dirty = true;
+                }
+                None => self.end_state = state,
+            }
+        }
+        self.mark_dirty_from(start);
+    }
+
+    /// Force the lines `start..end` to be highlighted again
+    pub fn invalidate_lines(&mut self, start: usize, end: usize) {
+        let end = end.min(self.lines.len());
+        for line in &mut self.lines[start.min(end)..end] {
+            line.dirty = true;
+        }
+        self.mark_dirty_from(start);
+    }
+
+    /// Switch to another theme, re-highlighting the whole document
+    pub fn set_theme(&mut self, theme: Arc<Theme>) {
+        self.theme = theme;
+        self.initial_state = Self::initial_state(&self.syntax_set, &self.theme, &self.language);
+        self.end_state = self.initial_state.clone();
+        if let Some(first) = self.lines.first_mut() {
+            first.start_state = self.initial_state.clone();
+        }
+        let count = self.lines.len();
+        self.invalidate_lines(0, count);
+    }
+
+    /// Highlight up to `max_lines` dirty lines
+    ///
+    /// Returns `true` once the whole document is highlighted.
+    pub fn highlight_pending(&mut self, max_lines: usize) -> bool {
+        self.highlight_pending_with(max_lines, |_, _| {})
+    }
+
+    /// Like [`Self::highlight_pending`], calling `on_line` for every line highlighted
+    pub fn highlight_pending_with(&mut self, max_lines: usize, mut on_line: impl FnMut(usize, &[(String, Color32)])) -> bool {
+        let theme = self.theme.clone();
+        let highlighter = Highlighter::new(&theme);
+        let mut budget = max_lines;
+
+        while let Some(mut index) = self.next_dirty(0) {
+            if budget == 0 {
+                return false;
+            }
+            let mut state = self.lines[index].start_state.clone();
+            loop {
+                let line = &mut self.lines[index];
+                let (segments, next_state) = highlight_with_state(&self.syntax_set, &highlighter, &line.text, &state);
+                on_line(index, &segments);
+                line.segments = Some(segments);
+                line.dirty = false;
+                self.lines_highlighted += 1;
+                budget -= 1;
+                index += 1;
+
+                let Some(next) = self.lines.get_mut(index) else {
+                    self.end_state = next_state;
+                    break;
+                };
+                if !next.dirty && next.start_state == next_state {
+                    // Converged: the rest of this region is unchanged
+                    break;
+                }
+                next.start_state = next_state.clone();
+                next.dirty = true;
+                if budget == 0 {
+                    self.mark_dirty_from(index);
+                    return false;
+                }
+                state = next_state;
+            }
+            self.first_dirty = Some(index);
+        }
+        true
+    }
+
+    /// Highlight every dirty line
+    pub fn highlight_all(&mut self) {
+        self.highlight_pending(usize::MAX);
+    }
+
+    /// Highlight dirty lines until `index` is up to date
+    pub fn highlight_through(&mut self, index: usize) {
+        while self.next_dirty(0).is_some_and(|dirty| dirty <= index) {
+            self.highlight_pending(index + 1);
+        }
+    }
+
+    fn mark_dirty_from(&mut self, index: usize) {
+        self.first_dirty = Some(self.first_dirty.map_or(index, |first| first.min(index)));
+    }
+
+    /// Find the first dirty line at or after `from`, updating the search hint
+    fn next_dirty(&mut self, from: usize) -> Option<usize> {
+        let start = self.first_dirty?.max(from);
+        match self.lines.iter().skip(start).position(|line| line.dirty) {
+            Some(offset) => {
+                self.first_dirty = Some(start + offset);
+                self.first_dirty
+            }
+            None => {
+                self.first_dirty = None;
+                None
+            }
+        }
+    }
+}
+
+/// `text` as a single segment in the foreground color of `theme`
+pub fn plain_segments(theme: &Theme, text: &str) -> HighlightedSegments {
+    let color = theme.settings.foreground
+        .map(|c| Color32::from_rgba_unmultiplied(c.r, c.g, c.b, c.a))
+        .unwrap_or(Color32::LIGHT_GRAY);
+    if text.is_empty() { Vec::new() } else { vec![(text.to_string(), color)] }
+}
+
+/// Find the lines that differ between `old` and `new`
+///
+/// Returns `(start, old_end, new_end)` such that replacing `old[start..old_end]`
+/// with `new[start..new_end]` turns `old` into `new`, or `None` if they are equal.
+pub fn changed_line_range(old: &[&str], new: &[&str]) -> Option<(usize, usize, usize)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    if prefix == old.len() && old.len() == new.len() {
+        return None;
+    }
+    let suffix = old[prefix..].iter().rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    Some((prefix, old.len() - suffix, new.len() - suffix))
+}
+
+/// Highlight one line starting from `state`, returning its segments and the state after it
+fn highlight_with_state(syntax_set: &SyntaxSet, highlighter: &Highlighter, text: &str, state: &LineState) -> (HighlightedSegments, LineState) {
+    let mut next = state.clone();
+    // The default syntaxes expect lines to end with a newline
+    let line = format!("{}\n", text);
+    let ops = next.parse.parse_line(&line, syntax_set).unwrap_or_default();
+    let segments = HighlightIterator::new(&mut next.highlight, &ops, &line, highlighter)
+        .filter_map(|(style, piece)| {
+            let piece = piece.strip_suffix('\n').unwrap_or(piece);
+            (!piece.is_empty()).then(|| (piece.to_string(), SyntaxHighlighter::syntect_to_egui_color(style)))
+        })
+        .collect();
+    (segments, next)
 }
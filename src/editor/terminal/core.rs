@@ -7,12 +7,15 @@
 use egui::*;
 use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
-use std::process::{Child, Command, Stdio};
+use std::io::Read;
 use std::sync::mpsc::{Receiver, Sender};
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 use serde::{Serialize, Deserialize};
 
+use super::pty::{PtyConfig, PtyFactory, PtySession};
+use super::vt::VtScreen;
+
 // Serializable color wrapper
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SerializableColor {
@@ -82,20 +85,18 @@ pub struct Terminal {
     pub last_activity: Instant,
     /// Environment variables
     pub environment: HashMap<String, String>,
+    /// Emulated screen the process draws on
+    pub screen: VtScreen,
 }
 
 /// Terminal process wrapper for better management
 pub struct TerminalProcess {
-    /// Child process handle
-    pub child: Child,
+    /// Pseudo-terminal session running the shell
+    pub session: Box<dyn PtySession>,
     /// Process ID
     pub pid: u32,
-    /// Output receiver channel
-    pub output_receiver: Receiver<String>,
-    /// Error output receiver
-    pub error_receiver: Receiver<String>,
-    /// Input sender channel
-    pub input_sender: Sender<String>,
+    /// Raw output read from the pseudo-terminal
+    pub output_receiver: Receiver<Vec<u8>>,
     /// Process start time
     pub started_at: Instant,
 }
@@ -463,44 +464,35 @@ impl Terminal {
             created_at: Instant::now(),
             last_activity: Instant::now(),
             environment: settings.environment_variables.clone(),
+            screen: VtScreen::new(80, 24),
         }
     }
     
-    /// Start the terminal process
+    /// Start the terminal process on a pseudo-terminal
     pub fn start_process(&mut self, settings: &TerminalSettings) -> Result<(), String> {
         let (shell_cmd, shell_args) = self.get_shell_command();
-        
-        let mut command = Command::new(shell_cmd);
-        command
-            .args(shell_args)
-            .current_dir(&self.working_dir)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
-        
-        // Set environment variables
-        for (key, value) in &self.environment {
-            command.env(key, value);
-        }
-        
-        match command.spawn() {
-            Ok(mut child) => {
-                let pid = child.id();
-                
-                // Create channels for process communication
+
+        let config = PtyConfig {
+            initial_size: self.screen.size(),
+            working_dir: Some(self.working_dir.clone()),
+            environment: self.environment.clone(),
+            ..PtyConfig::default()
+        };
+        let mut pty = PtyFactory::create_with_config(config);
+        
+        let spawned = pty
+            .spawn(shell_cmd, &shell_args, Some(&self.working_dir))
+            .and_then(|mut session| session.take_reader().map(|reader| (session, reader)));
+        match spawned {
+            Ok((session, reader)) => {
+                let pid = session.pid().unwrap_or(0);
                 let (output_sender, output_receiver) = std::sync::mpsc::channel();
-                let (error_sender, error_receiver) = std::sync::mpsc::channel();
-                let (input_sender, input_receiver) = std::sync::mpsc::channel();
-                
-                // Spawn threads for process I/O
-                self.spawn_io_threads(&mut child, output_sender, error_sender, input_receiver);
+                Self::spawn_reader_thread(reader, output_sender);
                 
                 self.process = Some(TerminalProcess {
-                    child,
+                    session,
                     pid,
                     output_receiver,
-                    error_receiver,
-                    input_sender,
                     started_at: Instant::now(),
                 });
                 
@@ -535,124 +527,71 @@ impl Terminal {
         }
     }
     
-    /// Spawn I/O threads for process communication
-    fn spawn_io_threads(
-        &self,
-        child: &mut std::process::Child,
-        output_sender: Sender<String>,
-        error_sender: Sender<String>,
-        input_receiver: Receiver<String>,
-    ) {
-        use std::io::{BufRead, BufReader, Write};
-        
-        // Stdout reading thread
-        if let Some(stdout) = child.stdout.take() {
-            std::thread::spawn(move || {
-                let reader = BufReader::new(stdout);
-                for line in reader.lines() {
-                    match line {
-                        Ok(content) => {
-                            if output_sender.send(content).is_err() {
-                                break;
-                            }
+    /// Forward raw pseudo-terminal output until the process closes it
+    fn spawn_reader_thread(mut reader: Box<dyn Read + Send>, output_sender: Sender<Vec<u8>>) {
+        std::thread::spawn(move || {
+            let mut buffer = [0u8; 8192];
+            loop {
+                match reader.read(&mut buffer) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        if output_sender.send(buffer[..n].to_vec()).is_err() {
+                            break;
                         }
-                        Err(_) => break,
                     }
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(_) => break,
                 }
-            });
-        }
-        
-        // Stderr reading thread
-        if let Some(stderr) = child.stderr.take() {
-            std::thread::spawn(move || {
-                let reader = BufReader::new(stderr);
-                for line in reader.lines() {
-                    match line {
-                        Ok(content) => {
-                            if error_sender.send(content).is_err() {
-                                break;
-                            }
-                        }
-                        Err(_) => break,
-                    }
-                }
-            });
-        }
-        
-        // Stdin writing thread
-        if let Some(mut stdin) = child.stdin.take() {
-            std::thread::spawn(move || {
-                while let Ok(input) = input_receiver.recv() {
-                    if stdin.write_all(input.as_bytes()).is_err() {
-                        break;
-                    }
-                    if stdin.flush().is_err() {
-                        break;
-                    }
-                }
-            });
+            }
+        });
+    }
+    
+    /// Resize the screen and the pseudo-terminal; the shell receives SIGWINCH
+    pub fn resize(&mut self, cols: u16, rows: u16) -> Result<(), String> {
+        self.screen.resize(cols, rows);
+        self.flush_scrollback(&None);
+        if let Some(process) = &mut self.process {
+            let (cols, rows) = self.screen.size();
+            process.session.resize(cols, rows)
+                .map_err(|e| format!("Failed to resize terminal: {}", e))?;
         }
+        Ok(())
     }
     
     /// Update terminal (read output, check process status)
     pub fn update(&mut self, event_sender: &Option<Sender<TerminalEvent>>) {
-        // Collect outputs first to avoid borrowing conflicts
-        let mut outputs = Vec::new();
-        let mut errors = Vec::new();
         let mut process_exit_status = None;
 
         if let Some(process) = &mut self.process {
-            // Read stdout
+            // Run the output through the screen; stdout and stderr share the tty
+            let mut received = false;
             while let Ok(output) = process.output_receiver.try_recv() {
-                outputs.push(output);
+                self.screen.process(&output);
+                received = true;
             }
 
-            // Read stderr
-            while let Ok(error) = process.error_receiver.try_recv() {
-                errors.push(error);
+            // Answer cursor position reports and other queries
+            let responses = self.screen.take_responses();
+            if !responses.is_empty() {
+                let _ = process.session.write_input(&responses);
             }
 
-            // Check process status
-            match process.child.try_wait() {
-                Ok(Some(exit_status)) => {
-                    process_exit_status = Some(exit_status.code().unwrap_or(-1));
-                }
-                Ok(None) => {
-                    // Process is still running
-                }
-                Err(_) => {
-                    // Error checking process status
-                }
+            if received {
+                self.last_activity = Instant::now();
             }
-        }
-
-        // Process outputs
-        for output in outputs {
-            self.add_output_line(output.clone(), LineType::Normal);
-            self.last_activity = Instant::now();
 
-            if let Some(sender) = event_sender {
-                let _ = sender.send(TerminalEvent::OutputReceived {
-                    terminal_id: self.id,
-                    content: output,
-                    line_type: LineType::Normal,
-                });
+            // Check process status
+            if let Ok(Some(exit_code)) = process.session.wait_with_timeout(Duration::ZERO) {
+                // Drain what the process wrote before exiting
+                while let Ok(output) = process.output_receiver.try_recv() {
+                    self.screen.process(&output);
+                }
+                process_exit_status = Some(exit_code);
             }
         }
 
-        // Process errors
-        for error in errors {
-            self.add_output_line(error.clone(), LineType::Error);
-            self.last_activity = Instant::now();
-
-            if let Some(sender) = event_sender {
-                let _ = sender.send(TerminalEvent::OutputReceived {
-                    terminal_id: self.id,
-                    content: error,
-                    line_type: LineType::Error,
-                });
-            }
-        }
+        // Lines scrolled off the screen move to the scrollback buffer
+        self.flush_scrollback(event_sender);
 
         // Handle process exit status
         if let Some(exit_code) = process_exit_status {
@@ -682,18 +621,16 @@ impl Terminal {
     
     /// Send input to terminal
     pub fn send_input(&mut self, input: &str) -> Result<(), String> {
-        // Handle command history and display first
+        // Record command history; the tty echoes the input itself
         if input.ends_with('\n') {
             let command = input.trim_end().to_string();
             if !command.is_empty() {
-                self.input_history.add_command(command.clone());
-                self.add_input_line(command);
+                self.input_history.add_command(command);
             }
         }
 
-        // Then send to process
         if let Some(process) = &mut self.process {
-            process.input_sender.send(input.to_string())
+            process.session.write_input(input.as_bytes())
                 .map_err(|e| format!("Failed to send input: {}", e))?;
 
             self.last_activity = Instant::now();
@@ -716,9 +653,18 @@ impl Terminal {
         self.output_buffer.add_line(line);
     }
     
-    /// Add input line to buffer
-    fn add_input_line(&mut self, content: String) {
-        self.add_output_line(format!("$ {}", content), LineType::Input);
+    /// Move lines scrolled off the screen into the output buffer
+    fn flush_scrollback(&mut self, event_sender: &Option<Sender<TerminalEvent>>) {
+        for line in self.screen.take_scrollback() {
+            if let Some(sender) = event_sender {
+                let _ = sender.send(TerminalEvent::OutputReceived {
+                    terminal_id: self.id,
+                    content: line.content.clone(),
+                    line_type: line.line_type,
+                });
+            }
+            self.output_buffer.add_line(line);
+        }
     }
     
     /// Add system message to buffer
@@ -734,8 +680,8 @@ impl Terminal {
     /// Clean up terminal resources
     pub fn cleanup(&mut self) {
         if let Some(mut process) = self.process.take() {
-            let _ = process.child.kill();
-            let _ = process.child.wait();
+            let _ = process.session.kill();
+            let _ = process.session.wait_with_timeout(Duration::from_millis(500));
         }
     }
 }
@@ -14,6 +14,7 @@ pub mod features;
 pub mod themes;
 pub mod session;
 pub mod pty;
+pub mod vt;
 
 // Re-export core types
 pub use core::{
@@ -51,6 +51,9 @@ pub trait PtySession: Send {
     /// Write input to the PTY
     fn write_input(&mut self, data: &[u8]) -> Result<usize, PtyError>;
     
+    /// Take a reader for the output that can be moved to a reader thread
+    fn take_reader(&mut self) -> Result<Box<dyn Read + Send>, PtyError>;
+    
     /// Resize the PTY
     fn resize(&mut self, cols: u16, rows: u16) -> Result<(), PtyError>;
     
@@ -437,6 +440,12 @@ mod windows_pty {
             }
         }
         
+        fn take_reader(&mut self) -> Result<Box<dyn Read + Send>, PtyError> {
+            let process = self.process.as_mut().ok_or(PtyError::ProcessNotFound)?;
+            let stdout = process.stdout.take().ok_or_else(|| PtyError::Other("output reader already taken".to_string()))?;
+            Ok(Box::new(stdout))
+        }
+        
         fn resize(&mut self, cols: u16, rows: u16) -> Result<(), PtyError> {
             if cols == 0 || rows == 0 {
                 return Err(PtyError::InvalidSize { cols, rows });
@@ -518,8 +527,9 @@ mod windows_pty {
 #[cfg(unix)]
 mod unix_pty {
     use super::*;
-    use std::os::unix::io::{AsRawFd, RawFd};
-    use std::os::unix::process::CommandExt;
+    use std::fs::File;
+    use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+    use std::os::unix::process::{CommandExt, ExitStatusExt};
     
     /// Unix PTY implementation using libc pty functions
     pub struct UnixPty {
@@ -528,12 +538,15 @@ mod unix_pty {
     }
     
     /// Unix PTY session
+    ///
+    /// The child runs as a session leader with the slave side of the
+    /// pseudo-terminal as its controlling terminal; the IDE keeps the master.
     pub struct UnixPtySession {
         id: Uuid,
         process: Option<Child>,
         working_dir: PathBuf,
-        master_fd: Option<RawFd>,
-        slave_fd: Option<RawFd>,
+        master: File,
+        read_timeout: Option<Duration>,
         exit_status: Option<i32>,
     }
     
@@ -604,14 +617,21 @@ mod unix_pty {
         }
         
         fn create_pty_session(&self, command: &str, args: &[&str], working_dir: Option<&Path>) -> Result<UnixPtySession, PtyError> {
-            // This is a simplified implementation
-            // In a real implementation, you would use the libc pty functions
+            let (cols, rows) = self.default_size;
+            if cols == 0 || rows == 0 {
+                return Err(PtyError::InvalidSize { cols, rows });
+            }
+            let (master, slave) = open_pty(cols, rows)?;
+            let working_dir = match working_dir {
+                Some(dir) => dir.to_path_buf(),
+                None => std::env::current_dir().map_err(|e| PtyError::IoError(e.to_string()))?,
+            };
             
             let mut cmd = Command::new(command);
-            cmd.args(args);
-            
-            if let Some(dir) = working_dir {
-                cmd.current_dir(dir);
+            cmd.args(args).current_dir(&working_dir);
+            cmd.env("TERM", "xterm-256color");
+            if self.config.utf8_mode && std::env::var_os("LANG").is_none() {
+                cmd.env("LANG", "C.UTF-8");
             }
             
             // Set up environment
@@ -619,24 +639,88 @@ mod unix_pty {
                 cmd.env(key, value);
             }
             
-            cmd.stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped());
+            let stdio = || slave.try_clone().map(Stdio::from).map_err(|e| PtyError::SpawnFailed(e.to_string()));
+            cmd.stdin(stdio()?).stdout(stdio()?).stderr(stdio()?);
+            
+            // Same as forkpty: a new session whose controlling terminal is the slave
+            unsafe {
+                cmd.pre_exec(|| {
+                    if libc::setsid() == -1 {
+                        return Err(io::Error::last_os_error());
+                    }
+                    if libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                        return Err(io::Error::last_os_error());
+                    }
+                    Ok(())
+                });
+            }
             
             let process = cmd.spawn()
-                .map_err(|e| PtyError::SpawnFailed(e.to_string()))?;
+                .map_err(|e| PtyError::SpawnFailed(format!("{}: {}", command, e)))?;
+            // Only the child may hold the slave, so reads see EOF once it exits
+            drop(cmd);
+            drop(slave);
             
             Ok(UnixPtySession {
                 id: Uuid::new_v4(),
                 process: Some(process),
-                working_dir: working_dir.unwrap_or(&std::env::current_dir().unwrap()).to_path_buf(),
-                master_fd: None, // Would be initialized with real PTY master
-                slave_fd: None,  // Would be initialized with real PTY slave
+                working_dir,
+                master: File::from(master),
+                read_timeout: self.config.read_timeout,
                 exit_status: None,
             })
         }
     }
     
+    /// Open a pseudo-terminal pair of the given size
+    fn open_pty(cols: u16, rows: u16) -> Result<(OwnedFd, OwnedFd), PtyError> {
+        let mut master: RawFd = -1;
+        let mut slave: RawFd = -1;
+        let size = window_size(cols, rows);
+        let result = unsafe {
+            libc::openpty(&mut master, &mut slave, std::ptr::null_mut(), std::ptr::null_mut(), &size)
+        };
+        if result != 0 {
+            return Err(PtyError::SpawnFailed(format!("openpty failed: {}", io::Error::last_os_error())));
+        }
+        let (master, slave) = unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+        
+        // Keep both ends out of other children; stdio duplicates clear the flag
+        for fd in [&master, &slave] {
+            if unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+                return Err(PtyError::IoError(io::Error::last_os_error().to_string()));
+            }
+        }
+        Ok((master, slave))
+    }
+    
+    fn window_size(cols: u16, rows: u16) -> libc::winsize {
+        libc::winsize {
+            ws_row: rows,
+            ws_col: cols,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        }
+    }
+    
+    impl UnixPtySession {
+        fn signal(&self, signal: libc::c_int) -> Result<(), PtyError> {
+            let pid = self.process.as_ref().ok_or(PtyError::ProcessNotFound)?.id() as libc::pid_t;
+            if self.exit_status.is_some() {
+                return Err(PtyError::ProcessNotFound);
+            }
+            if unsafe { libc::kill(pid, signal) } == -1 {
+                let error = io::Error::last_os_error();
+                return Err(match error.raw_os_error() {
+                    Some(libc::ESRCH) => PtyError::ProcessNotFound,
+                    Some(libc::EPERM) => PtyError::PermissionDenied,
+                    _ => PtyError::Other(error.to_string()),
+                });
+            }
+            Ok(())
+        }
+    }
+    
     impl PtyInterface for UnixPty {
         fn spawn(&mut self, command: &str, args: &[&str], working_dir: Option<&Path>) -> Result<Box<dyn PtySession>, PtyError> {
             let session = self.create_pty_session(command, args, working_dir)?;
@@ -687,52 +771,56 @@ mod unix_pty {
         }
         
         fn read_output(&mut self, buffer: &mut [u8]) -> Result<usize, PtyError> {
-            // Would read from master PTY in real implementation
-            if let Some(ref mut process) = self.process {
-                if let Some(ref mut stdout) = process.stdout {
-                    stdout.read(buffer).map_err(|e| PtyError::IoError(e.to_string()))
-                } else {
-                    Ok(0)
+            if let Some(timeout) = self.read_timeout {
+                let mut poll_fd = libc::pollfd { fd: self.master.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+                let millis = timeout.as_millis().min(libc::c_int::MAX as u128) as libc::c_int;
+                match unsafe { libc::poll(&mut poll_fd, 1, millis) } {
+                    -1 => return Err(PtyError::IoError(io::Error::last_os_error().to_string())),
+                    0 => return Err(PtyError::WouldBlock),
+                    _ => {}
                 }
-            } else {
-                Err(PtyError::ProcessNotFound)
+            }
+            match self.master.read(buffer) {
+                Ok(count) => Ok(count),
+                // Linux reports EIO on the master once the slave is closed
+                Err(e) if e.raw_os_error() == Some(libc::EIO) => Ok(0),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => Err(PtyError::WouldBlock),
+                Err(e) => Err(PtyError::IoError(e.to_string())),
             }
         }
         
         fn write_input(&mut self, data: &[u8]) -> Result<usize, PtyError> {
-            // Would write to master PTY in real implementation
-            if let Some(ref mut process) = self.process {
-                if let Some(ref mut stdin) = process.stdin {
-                    stdin.write(data).map_err(|e| PtyError::IoError(e.to_string()))
-                } else {
-                    Ok(0)
-                }
-            } else {
-                Err(PtyError::ProcessNotFound)
-            }
+            self.master.write_all(data).map_err(|e| PtyError::IoError(e.to_string()))?;
+            Ok(data.len())
+        }
+        
+        fn take_reader(&mut self) -> Result<Box<dyn Read + Send>, PtyError> {
+            let reader = self.master.try_clone().map_err(|e| PtyError::IoError(e.to_string()))?;
+            Ok(Box::new(PtyReader(reader)))
         }
         
         fn resize(&mut self, cols: u16, rows: u16) -> Result<(), PtyError> {
             if cols == 0 || rows == 0 {
                 return Err(PtyError::InvalidSize { cols, rows });
             }
-            // Would use ioctl TIOCSWINSZ in real implementation
+            // The kernel delivers SIGWINCH to the foreground process group
+            let size = window_size(cols, rows);
+            if unsafe { libc::ioctl(self.master.as_raw_fd(), libc::TIOCSWINSZ, &size) } == -1 {
+                return Err(PtyError::IoError(io::Error::last_os_error().to_string()));
+            }
             Ok(())
         }
         
         fn get_size(&self) -> Result<(u16, u16), PtyError> {
-            // Would query actual PTY size in real implementation
-            Ok((80, 24))
+            let mut size = window_size(0, 0);
+            if unsafe { libc::ioctl(self.master.as_raw_fd(), libc::TIOCGWINSZ, &mut size) } == -1 {
+                return Err(PtyError::IoError(io::Error::last_os_error().to_string()));
+            }
+            Ok((size.ws_col, size.ws_row))
         }
         
         fn is_alive(&self) -> bool {
-            if let Some(ref process) = self.process {
-                // Since we can't call try_wait() in an immutable context,
-                // we check if we still have a process reference and no exit status
-                self.exit_status.is_none()
-            } else {
-                false
-            }
+            self.process.is_some() && self.exit_status.is_none()
         }
         
         fn pid(&self) -> Option<u32> {
@@ -740,39 +828,32 @@ mod unix_pty {
         }
         
         fn terminate(&mut self) -> Result<(), PtyError> {
-            if let Some(ref mut process) = self.process {
-                // Send SIGTERM
-                process.kill().map_err(|e| PtyError::Other(e.to_string()))?;
-                Ok(())
-            } else {
-                Err(PtyError::ProcessNotFound)
-            }
+            // Interactive shells ignore SIGTERM; hang up as a closed terminal would
+            self.signal(libc::SIGHUP)
         }
         
         fn kill(&mut self) -> Result<(), PtyError> {
-            if let Some(ref mut process) = self.process {
-                // Send SIGKILL - more forceful than terminate
-                process.kill().map_err(|e| PtyError::Other(e.to_string()))?;
-                Ok(())
-            } else {
-                Err(PtyError::ProcessNotFound)
-            }
+            self.signal(libc::SIGKILL)
         }
         
         fn wait_with_timeout(&mut self, timeout: Duration) -> Result<Option<i32>, PtyError> {
-            if let Some(ref mut process) = self.process {
-                // Simplified implementation
+            if let Some(code) = self.exit_status {
+                return Ok(Some(code));
+            }
+            let process = self.process.as_mut().ok_or(PtyError::ProcessNotFound)?;
+            let deadline = Instant::now() + timeout;
+            loop {
                 match process.try_wait() {
                     Ok(Some(exit_status)) => {
-                        let code = exit_status.code().unwrap_or(-1);
+                        // Shell convention for signals: 128 + signal number
+                        let code = exit_status.code().or_else(|| exit_status.signal().map(|signal| 128 + signal)).unwrap_or(-1);
                         self.exit_status = Some(code);
-                        Ok(Some(code))
+                        return Ok(Some(code));
                     }
-                    Ok(None) => Ok(None),
-                    Err(e) => Err(PtyError::Other(e.to_string())),
+                    Ok(None) if Instant::now() >= deadline => return Ok(None),
+                    Ok(None) => std::thread::sleep(Duration::from_millis(10).min(timeout)),
+                    Err(e) => return Err(PtyError::Other(e.to_string())),
                 }
-            } else {
-                Err(PtyError::ProcessNotFound)
             }
         }
         
@@ -780,9 +861,8 @@ mod unix_pty {
             self.exit_status
         }
         
-        fn set_environment(&mut self, env: HashMap<String, String>) -> Result<(), PtyError> {
-            // Environment variables are typically set during process creation
-            // On Unix, we could potentially use the environ manipulation
+        fn set_environment(&mut self, _env: HashMap<String, String>) -> Result<(), PtyError> {
+            // The environment of a running process cannot be changed from outside
             Err(PtyError::NotImplemented)
         }
         
@@ -795,6 +875,32 @@ mod unix_pty {
             Ok(())
         }
     }
+    
+    impl Drop for UnixPtySession {
+        fn drop(&mut self) {
+            if self.is_alive() {
+                let _ = self.terminate();
+                if !matches!(self.wait_with_timeout(Duration::from_millis(100)), Ok(Some(_))) {
+                    let _ = self.kill();
+                    if let Some(process) = self.process.as_mut() {
+                        let _ = process.wait();
+                    }
+                }
+            }
+        }
+    }
+    
+    /// Reader for the master side that reports the end of the session as EOF
+    struct PtyReader(File);
+    
+    impl Read for PtyReader {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            match self.0.read(buffer) {
+                Err(e) if e.raw_os_error() == Some(libc::EIO) => Ok(0),
+                other => other,
+            }
+        }
+    }
 }
 
 // Re-export platform-specific implementations
@@ -0,0 +1,999 @@
+//! # VT Terminal Emulation
+//!
+//! A VT100/xterm state machine that turns the byte stream of a pseudo-terminal
+//! into a screen of styled cells. It handles cursor addressing, erasing,
+//! scroll regions, the alternate screen and the replies programs expect from
+//! a terminal. Lines scrolled off the top of the primary screen are collected
+//! as scrollback for the terminal's `TerminalBuffer`.
+
+use std::time::Instant;
+
+use super::core::{AnsiMetadata, LineType, SerializableColor, TerminalLine};
+
+/// Maximum number of numeric parameters kept for one control sequence
+const MAX_PARAMS: usize = 32;
+
+/// Maximum length of an operating system command string
+const MAX_OSC_LEN: usize = 4096;
+
+/// Color of a cell, as sent by the program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VtColor {
+    /// One of the 256 palette colors
+    Indexed(u8),
+    /// 24-bit color
+    Rgb(u8, u8, u8),
+}
+
+impl VtColor {
+    /// RGB value using the xterm palette
+    pub fn to_rgb(self) -> (u8, u8, u8) {
+        const BASE: [(u8, u8, u8); 16] = [
+            (0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0),
+            (0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+            (127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0),
+            (92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255),
+        ];
+        match self {
+            VtColor::Rgb(r, g, b) => (r, g, b),
+            VtColor::Indexed(index @ 0..=15) => BASE[index as usize],
+            VtColor::Indexed(index @ 16..=231) => {
+                let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+                let index = index - 16;
+                (level(index / 36), level(index / 6 % 6), level(index % 6))
+            }
+            VtColor::Indexed(index) => {
+                let gray = 8 + (index - 232) * 10;
+                (gray, gray, gray)
+            }
+        }
+    }
+}
+
+/// Rendition of a cell
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CellStyle {
+    /// Foreground color; `None` is the terminal default
+    pub fg: Option<VtColor>,
+    /// Background color; `None` is the terminal default
+    pub bg: Option<VtColor>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub blink: bool,
+    pub inverse: bool,
+    pub hidden: bool,
+    pub strikethrough: bool,
+}
+
+impl CellStyle {
+    fn to_metadata(self) -> AnsiMetadata {
+        let color = |color: VtColor| {
+            let (r, g, b) = color.to_rgb();
+            SerializableColor { r, g, b, a: 255 }
+        };
+        let (fg, bg) = if self.inverse { (self.bg, self.fg) } else { (self.fg, self.bg) };
+        AnsiMetadata {
+            fg_color: fg.map(color),
+            bg_color: bg.map(color),
+            bold: self.bold,
+            italic: self.italic,
+            underline: self.underline,
+            strikethrough: self.strikethrough,
+        }
+    }
+}
+
+/// One character cell of the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub style: CellStyle,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self { ch: ' ', style: CellStyle::default() }
+    }
+}
+
+/// Cursor position and the state saved with it by DECSC
+#[derive(Debug, Clone, Copy, Default)]
+struct Cursor {
+    row: usize,
+    col: usize,
+    style: CellStyle,
+    /// The last column was written; the next printable character wraps first
+    pending_wrap: bool,
+    origin_mode: bool,
+}
+
+/// Escape sequence parser state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Ground,
+    Escape,
+    EscapeIntermediate,
+    Csi,
+    CsiIgnore,
+    Osc,
+    /// String of a DCS, SOS, PM or APC sequence, which are ignored
+    IgnoredString,
+}
+
+/// Screen of a VT100/xterm compatible terminal
+pub struct VtScreen {
+    cols: usize,
+    rows: usize,
+    primary: Vec<Vec<Cell>>,
+    alternate: Vec<Vec<Cell>>,
+    alternate_active: bool,
+    cursor: Cursor,
+    saved_primary: Cursor,
+    saved_alternate: Cursor,
+    /// First and last row of the scroll region, inclusive
+    scroll_top: usize,
+    scroll_bottom: usize,
+    tab_stops: Vec<bool>,
+    autowrap: bool,
+    insert_mode: bool,
+    cursor_visible: bool,
+    application_cursor_keys: bool,
+    bracketed_paste: bool,
+    title: String,
+    bell: bool,
+    /// Lines scrolled off the primary screen, not yet taken
+    scrollback: Vec<Vec<Cell>>,
+    /// Replies to send back to the program, such as cursor position reports
+    responses: Vec<u8>,
+
+    state: State,
+    params: Vec<u16>,
+    current_param: Option<u16>,
+    private_marker: Option<u8>,
+    intermediates: Vec<u8>,
+    osc: Vec<u8>,
+    /// Set after ESC inside a string, where `ESC \` terminates it
+    string_escape: bool,
+    utf8: Vec<u8>,
+    utf8_len: usize,
+    last_printed: Option<char>,
+}
+
+impl VtScreen {
+    /// Create a blank screen of the given size
+    pub fn new(cols: u16, rows: u16) -> Self {
+        let cols = usize::from(cols.max(1));
+        let rows = usize::from(rows.max(1));
+        Self {
+            cols,
+            rows,
+            primary: blank_grid(cols, rows),
+            alternate: blank_grid(cols, rows),
+            alternate_active: false,
+            cursor: Cursor::default(),
+            saved_primary: Cursor::default(),
+            saved_alternate: Cursor::default(),
+            scroll_top: 0,
+            scroll_bottom: rows - 1,
+            tab_stops: default_tab_stops(cols),
+            autowrap: true,
+            insert_mode: false,
+            cursor_visible: true,
+            application_cursor_keys: false,
+            bracketed_paste: false,
+            title: String::new(),
+            bell: false,
+            scrollback: Vec::new(),
+            responses: Vec::new(),
+            state: State::Ground,
+            params: Vec::new(),
+            current_param: None,
+            private_marker: None,
+            intermediates: Vec::new(),
+            osc: Vec::new(),
+            string_escape: false,
+            utf8: Vec::new(),
+            utf8_len: 0,
+            last_printed: None,
+        }
+    }
+
+    /// Screen size as (columns, rows)
+    pub fn size(&self) -> (u16, u16) {
+        (self.cols as u16, self.rows as u16)
+    }
+
+    /// Cursor position as (row, column), both 0-based
+    pub fn cursor_position(&self) -> (usize, usize) {
+        (self.cursor.row, self.cursor.col)
+    }
+
+    /// Whether the program asked for the cursor to be shown
+    pub fn cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    /// Whether a full-screen program switched to the alternate screen
+    pub fn is_alternate_screen(&self) -> bool {
+        self.alternate_active
+    }
+
+    /// Whether cursor keys should send application sequences (`ESC O A`)
+    pub fn application_cursor_keys(&self) -> bool {
+        self.application_cursor_keys
+    }
+
+    /// Whether pasted text should be wrapped in bracketed paste markers
+    pub fn bracketed_paste(&self) -> bool {
+        self.bracketed_paste
+    }
+
+    /// Window title set by the program
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Whether the bell rang since the last call
+    pub fn take_bell(&mut self) -> bool {
+        std::mem::take(&mut self.bell)
+    }
+
+    /// Replies to write back to the program
+    pub fn take_responses(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.responses)
+    }
+
+    /// Cells of a visible row
+    pub fn row(&self, row: usize) -> &[Cell] {
+        &self.grid()[row]
+    }
+
+    /// Text of a visible row without trailing blanks
+    pub fn row_text(&self, row: usize) -> String {
+        row_text(&self.grid()[row])
+    }
+
+    /// Visible screen as terminal lines
+    pub fn visible_lines(&self) -> Vec<TerminalLine> {
+        self.grid().iter().map(|row| to_terminal_line(row)).collect()
+    }
+
+    /// Lines scrolled off the primary screen since the last call
+    pub fn take_scrollback(&mut self) -> Vec<TerminalLine> {
+        self.scrollback.drain(..).map(|row| to_terminal_line(&row)).collect()
+    }
+
+    /// Change the screen size, keeping the cursor line visible
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        let cols = usize::from(cols.max(1));
+        let rows = usize::from(rows.max(1));
+        if cols == self.cols && rows == self.rows {
+            return;
+        }
+
+        for alternate in [false, true] {
+            let is_active = alternate == self.alternate_active;
+            let mut grid = std::mem::take(if alternate { &mut self.alternate } else { &mut self.primary });
+            if rows < grid.len() {
+                // Drop lines above the cursor first, then blank lines below it
+                let cursor_row = if is_active { self.cursor.row } else { grid.len() - 1 };
+                let from_top = (cursor_row + 1).saturating_sub(rows).min(grid.len() - rows);
+                let removed: Vec<Vec<Cell>> = grid.drain(..from_top).collect();
+                if !alternate {
+                    self.scrollback.extend(removed);
+                }
+                if is_active {
+                    self.cursor.row -= from_top;
+                }
+                grid.truncate(rows);
+            }
+            grid.resize_with(rows, || vec![Cell::default(); cols]);
+            for row in &mut grid {
+                row.resize(cols, Cell::default());
+            }
+            *(if alternate { &mut self.alternate } else { &mut self.primary }) = grid;
+        }
+
+        self.cols = cols;
+        self.rows = rows;
+        self.scroll_top = 0;
+        self.scroll_bottom = rows - 1;
+        self.tab_stops.resize(cols, false);
+        for col in (8..cols).step_by(8) {
+            self.tab_stops[col] = true;
+        }
+        for cursor in [&mut self.cursor, &mut self.saved_primary, &mut self.saved_alternate] {
+            cursor.row = cursor.row.min(rows - 1);
+            cursor.col = cursor.col.min(cols - 1);
+            cursor.pending_wrap = false;
+        }
+    }
+
+    /// Feed output of the program
+    pub fn process(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.advance(byte);
+        }
+    }
+
+    fn advance(&mut self, byte: u8) {
+        // Continue a multi-byte character
+        if self.utf8_len > 0 {
+            if byte & 0xC0 == 0x80 {
+                self.utf8.push(byte);
+                if self.utf8.len() == self.utf8_len {
+                    let ch = std::str::from_utf8(&self.utf8).ok().and_then(|text| text.chars().next()).unwrap_or('\u{FFFD}');
+                    self.utf8.clear();
+                    self.utf8_len = 0;
+                    self.character(ch);
+                }
+                return;
+            }
+            self.utf8.clear();
+            self.utf8_len = 0;
+            self.character('\u{FFFD}');
+        }
+
+        match byte {
+            0x00..=0x7F => self.ascii(byte),
+            0xC2..=0xDF => self.start_utf8(byte, 2),
+            0xE0..=0xEF => self.start_utf8(byte, 3),
+            0xF0..=0xF4 => self.start_utf8(byte, 4),
+            _ => self.character('\u{FFFD}'),
+        }
+    }
+
+    fn start_utf8(&mut self, byte: u8, len: usize) {
+        self.utf8.push(byte);
+        self.utf8_len = len;
+    }
+
+    /// A decoded non-ASCII character
+    fn character(&mut self, ch: char) {
+        match self.state {
+            State::Ground => self.print(ch),
+            State::Osc if self.osc.len() < MAX_OSC_LEN => {
+                let mut encoded = [0; 4];
+                self.osc.extend_from_slice(ch.encode_utf8(&mut encoded).as_bytes());
+            }
+            _ => {}
+        }
+    }
+
+    fn ascii(&mut self, byte: u8) {
+        // Strings end with BEL or ST, and swallow everything else
+        if matches!(self.state, State::Osc | State::IgnoredString) {
+            match byte {
+                0x07 => self.end_string(),
+                0x1B => self.string_escape = true,
+                b'\\' if self.string_escape => self.end_string(),
+                _ => {
+                    self.string_escape = false;
+                    if self.state == State::Osc && self.osc.len() < MAX_OSC_LEN {
+                        self.osc.push(byte);
+                    }
+                }
+            }
+            return;
+        }
+
+        match byte {
+            0x1B => {
+                self.clear_sequence();
+                self.state = State::Escape;
+            }
+            // Cancel a sequence in progress
+            0x18 | 0x1A => self.state = State::Ground,
+            0x00..=0x1F => self.execute(byte),
+            0x7F => {}
+            _ => match self.state {
+                State::Ground => self.print(byte as char),
+                State::Escape => self.escape(byte),
+                State::EscapeIntermediate => {
+                    if (0x20..=0x2F).contains(&byte) {
+                        self.intermediates.push(byte);
+                    } else {
+                        self.escape_dispatch(byte);
+                    }
+                }
+                State::Csi => self.csi(byte),
+                State::CsiIgnore => {
+                    if (0x40..=0x7E).contains(&byte) {
+                        self.state = State::Ground;
+                    }
+                }
+                State::Osc | State::IgnoredString => unreachable!("handled above"),
+            },
+        }
+    }
+
+    fn clear_sequence(&mut self) {
+        self.params.clear();
+        self.current_param = None;
+        self.private_marker = None;
+        self.intermediates.clear();
+        self.osc.clear();
+        self.string_escape = false;
+    }
+
+    fn end_string(&mut self) {
+        if self.state == State::Osc {
+            self.osc_dispatch();
+        }
+        self.string_escape = false;
+        self.state = State::Ground;
+    }
+
+    fn escape(&mut self, byte: u8) {
+        match byte {
+            b'[' => self.state = State::Csi,
+            b']' => self.state = State::Osc,
+            b'P' | b'X' | b'^' | b'_' => self.state = State::IgnoredString,
+            0x20..=0x2F => {
+                self.intermediates.push(byte);
+                self.state = State::EscapeIntermediate;
+            }
+            _ => self.escape_dispatch(byte),
+        }
+    }
+
+    fn escape_dispatch(&mut self, byte: u8) {
+        self.state = State::Ground;
+        match (self.intermediates.first(), byte) {
+            (None, b'7') => self.save_cursor(),
+            (None, b'8') => self.restore_cursor(),
+            (None, b'D') => self.index(),
+            (None, b'E') => {
+                self.cursor.col = 0;
+                self.index();
+            }
+            (None, b'M') => self.reverse_index(),
+            (None, b'H') => self.tab_stops[self.cursor.col] = true,
+            (None, b'c') => self.reset(),
+            // Screen alignment test
+            (Some(b'#'), b'8') => {
+                for row in self.grid_mut() {
+                    row.fill(Cell { ch: 'E', style: CellStyle::default() });
+                }
+                self.set_position(0, 0);
+            }
+            // Keypad modes and character set designations have no visible effect here
+            _ => {}
+        }
+    }
+
+    fn csi(&mut self, byte: u8) {
+        match byte {
+            b'0'..=b'9' => {
+                let digit = u16::from(byte - b'0');
+                self.current_param = Some(self.current_param.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+            }
+            b';' | b':' => {
+                if self.params.len() < MAX_PARAMS {
+                    self.params.push(self.current_param.take().unwrap_or(0));
+                }
+            }
+            b'<'..=b'?' => {
+                if self.params.is_empty() && self.current_param.is_none() && self.private_marker.is_none() {
+                    self.private_marker = Some(byte);
+                } else {
+                    self.state = State::CsiIgnore;
+                }
+            }
+            0x20..=0x2F => self.intermediates.push(byte),
+            0x40..=0x7E => {
+                if let Some(param) = self.current_param.take() {
+                    if self.params.len() < MAX_PARAMS {
+                        self.params.push(param);
+                    }
+                }
+                self.state = State::Ground;
+                self.csi_dispatch(byte);
+            }
+            _ => self.state = State::CsiIgnore,
+        }
+    }
+
+    /// Parameter `index`, with 0 or missing meaning `default`
+    fn param(&self, index: usize, default: usize) -> usize {
+        match self.params.get(index) {
+            Some(&value) if value > 0 => usize::from(value),
+            _ => default,
+        }
+    }
+
+    fn csi_dispatch(&mut self, action: u8) {
+        let private = self.private_marker;
+        if !self.intermediates.is_empty() {
+            // DECSCUSR and similar: nothing to emulate
+            return;
+        }
+        match (private, action) {
+            (None, b'@') => self.insert_chars(self.param(0, 1)),
+            (None, b'A') => self.move_up(self.param(0, 1)),
+            (None, b'B') | (None, b'e') => self.move_down(self.param(0, 1)),
+            (None, b'C') | (None, b'a') => self.set_col(self.cursor.col.saturating_add(self.param(0, 1))),
+            (None, b'D') => self.set_col(self.cursor.col.saturating_sub(self.param(0, 1))),
+            (None, b'E') => {
+                self.move_down(self.param(0, 1));
+                self.cursor.col = 0;
+            }
+            (None, b'F') => {
+                self.move_up(self.param(0, 1));
+                self.cursor.col = 0;
+            }
+            (None, b'G') | (None, b'`') => self.set_col(self.param(0, 1) - 1),
+            (None, b'H') | (None, b'f') => self.set_position(self.param(0, 1) - 1, self.param(1, 1) - 1),
+            (None, b'I') => {
+                for _ in 0..self.param(0, 1) {
+                    self.tab();
+                }
+            }
+            (None, b'J') | (Some(b'?'), b'J') => self.erase_display(self.param(0, 0)),
+            (None, b'K') | (Some(b'?'), b'K') => self.erase_line(self.param(0, 0)),
+            (None, b'L') => self.insert_lines(self.param(0, 1)),
+            (None, b'M') => self.delete_lines(self.param(0, 1)),
+            (None, b'P') => self.delete_chars(self.param(0, 1)),
+            (None, b'S') => self.scroll_up(self.param(0, 1)),
+            (None, b'T') => self.scroll_down(self.param(0, 1)),
+            (None, b'X') => {
+                let (row, col) = (self.cursor.row, self.cursor.col);
+                let end = (col + self.param(0, 1)).min(self.cols);
+                let blank = self.blank();
+                self.grid_mut()[row][col..end].fill(blank);
+                self.cursor.pending_wrap = false;
+            }
+            (None, b'Z') => {
+                for _ in 0..self.param(0, 1) {
+                    let col = (0..self.cursor.col).rev().find(|&col| self.tab_stops[col]).unwrap_or(0);
+                    self.cursor.col = col;
+                }
+                self.cursor.pending_wrap = false;
+            }
+            (None, b'b') => {
+                if let Some(ch) = self.last_printed {
+                    for _ in 0..self.param(0, 1).min(self.cols * self.rows) {
+                        self.print(ch);
+                    }
+                }
+            }
+            (None, b'c') => self.responses.extend_from_slice(b"\x1b[?62;22c"),
+            (Some(b'>'), b'c') => self.responses.extend_from_slice(b"\x1b[>0;10;0c"),
+            (None, b'd') => {
+                let row = self.param(0, 1) - 1;
+                let col = self.cursor.col;
+                self.set_position(row, col);
+            }
+            (None, b'g') => match self.param(0, 0) {
+                0 => self.tab_stops[self.cursor.col] = false,
+                3 => self.tab_stops.fill(false),
+                _ => {}
+            },
+            (None, b'h') => self.set_ansi_modes(true),
+            (None, b'l') => self.set_ansi_modes(false),
+            (Some(b'?'), b'h') => self.set_private_modes(true),
+            (Some(b'?'), b'l') => self.set_private_modes(false),
+            (None, b'm') => self.select_graphic_rendition(),
+            (None, b'n') => match self.param(0, 0) {
+                5 => self.responses.extend_from_slice(b"\x1b[0n"),
+                6 => {
+                    let row = if self.cursor.origin_mode { self.cursor.row - self.scroll_top } else { self.cursor.row };
+                    let report = format!("\x1b[{};{}R", row + 1, self.cursor.col + 1);
+                    self.responses.extend_from_slice(report.as_bytes());
+                }
+                _ => {}
+            },
+            (None, b'r') => {
+                let top = self.param(0, 1) - 1;
+                let bottom = self.param(1, self.rows).min(self.rows) - 1;
+                if top < bottom {
+                    self.scroll_top = top;
+                    self.scroll_bottom = bottom;
+                    self.set_position(0, 0);
+                }
+            }
+            (None, b's') => self.save_cursor(),
+            (None, b'u') => self.restore_cursor(),
+            // Window manipulation, mouse reporting and other requests are not emulated
+            _ => {}
+        }
+    }
+
+    fn set_ansi_modes(&mut self, enabled: bool) {
+        for index in 0..self.params.len() {
+            if self.params[index] == 4 {
+                self.insert_mode = enabled;
+            }
+        }
+    }
+
+    fn set_private_modes(&mut self, enabled: bool) {
+        for index in 0..self.params.len() {
+            match self.params[index] {
+                1 => self.application_cursor_keys = enabled,
+                6 => {
+                    self.cursor.origin_mode = enabled;
+                    self.set_position(0, 0);
+                }
+                7 => self.autowrap = enabled,
+                25 => self.cursor_visible = enabled,
+                47 | 1047 => self.switch_screen(enabled, false),
+                1048 => {
+                    if enabled {
+                        self.save_cursor();
+                    } else {
+                        self.restore_cursor();
+                    }
+                }
+                1049 => self.switch_screen(enabled, true),
+                2004 => self.bracketed_paste = enabled,
+                _ => {}
+            }
+        }
+    }
+
+    fn switch_screen(&mut self, alternate: bool, save_cursor: bool) {
+        if alternate == self.alternate_active {
+            return;
+        }
+        if alternate {
+            if save_cursor {
+                self.saved_primary = self.cursor;
+            }
+            self.alternate_active = true;
+            self.alternate = blank_grid(self.cols, self.rows);
+        } else {
+            self.alternate_active = false;
+            if save_cursor {
+                self.cursor = self.saved_primary;
+            }
+        }
+        self.cursor.pending_wrap = false;
+    }
+
+    fn select_graphic_rendition(&mut self) {
+        if self.params.is_empty() {
+            self.cursor.style = CellStyle::default();
+            return;
+        }
+        let mut index = 0;
+        while index < self.params.len() {
+            let style = &mut self.cursor.style;
+            match self.params[index] {
+                0 => *style = CellStyle::default(),
+                1 => style.bold = true,
+                2 => style.dim = true,
+                3 => style.italic = true,
+                4 => style.underline = true,
+                5 | 6 => style.blink = true,
+                7 => style.inverse = true,
+                8 => style.hidden = true,
+                9 => style.strikethrough = true,
+                21 => style.underline = true,
+                22 => {
+                    style.bold = false;
+                    style.dim = false;
+                }
+                23 => style.italic = false,
+                24 => style.underline = false,
+                25 => style.blink = false,
+                27 => style.inverse = false,
+                28 => style.hidden = false,
+                29 => style.strikethrough = false,
+                code @ 30..=37 => style.fg = Some(VtColor::Indexed(code as u8 - 30)),
+                code @ 40..=47 => style.bg = Some(VtColor::Indexed(code as u8 - 40)),
+                code @ 90..=97 => style.fg = Some(VtColor::Indexed(code as u8 - 90 + 8)),
+                code @ 100..=107 => style.bg = Some(VtColor::Indexed(code as u8 - 100 + 8)),
+                39 => style.fg = None,
+                49 => style.bg = None,
+                code @ (38 | 48) => {
+                    let (color, used) = extended_color(&self.params[index + 1..]);
+                    index += used;
+                    if let Some(color) = color {
+                        let style = &mut self.cursor.style;
+                        if code == 38 {
+                            style.fg = Some(color);
+                        } else {
+                            style.bg = Some(color);
+                        }
+                    }
+                }
+                _ => {}
+            }
+            index += 1;
+        }
+    }
+
+    fn osc_dispatch(&mut self) {
+        let text = String::from_utf8_lossy(&self.osc);
+        if let Some((command, value)) = text.split_once(';') {
+            if command == "0" || command == "2" {
+                self.title = value.chars().filter(|ch| !ch.is_control()).collect();
+            }
+        }
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            0x07 => self.bell = true,
+            0x08 => {
+                self.cursor.col = self.cursor.col.saturating_sub(1);
+                self.cursor.pending_wrap = false;
+            }
+            0x09 => self.tab(),
+            0x0A..=0x0C => self.index(),
+            0x0D => {
+                self.cursor.col = 0;
+                self.cursor.pending_wrap = false;
+            }
+            _ => {}
+        }
+    }
+
+    fn print(&mut self, ch: char) {
+        if self.cursor.pending_wrap && self.autowrap {
+            self.cursor.col = 0;
+            self.index();
+        }
+        self.cursor.pending_wrap = false;
+        if self.insert_mode {
+            self.insert_chars(1);
+        }
+
+        let (row, col, style) = (self.cursor.row, self.cursor.col, self.cursor.style);
+        self.grid_mut()[row][col] = Cell { ch, style };
+        self.last_printed = Some(ch);
+        if col + 1 < self.cols {
+            self.cursor.col += 1;
+        } else {
+            self.cursor.pending_wrap = self.autowrap;
+        }
+    }
+
+    fn tab(&mut self) {
+        let next = (self.cursor.col + 1..self.cols).find(|&col| self.tab_stops[col]).unwrap_or(self.cols - 1);
+        self.cursor.col = next;
+        self.cursor.pending_wrap = false;
+    }
+
+    /// Move down a line, scrolling at the bottom of the scroll region
+    fn index(&mut self) {
+        self.cursor.pending_wrap = false;
+        if self.cursor.row == self.scroll_bottom {
+            self.scroll_up(1);
+        } else if self.cursor.row + 1 < self.rows {
+            self.cursor.row += 1;
+        }
+    }
+
+    /// Move up a line, scrolling at the top of the scroll region
+    fn reverse_index(&mut self) {
+        self.cursor.pending_wrap = false;
+        if self.cursor.row == self.scroll_top {
+            self.scroll_down(1);
+        } else if self.cursor.row > 0 {
+            self.cursor.row -= 1;
+        }
+    }
+
+    /// Scroll the scroll region up, keeping lines leaving the primary screen
+    fn scroll_up(&mut self, count: usize) {
+        let (top, bottom) = (self.scroll_top, self.scroll_bottom);
+        let count = count.min(bottom - top + 1);
+        let blank = vec![self.blank(); self.cols];
+        let keep = !self.alternate_active && top == 0;
+        let grid = if self.alternate_active { &mut self.alternate } else { &mut self.primary };
+        let removed: Vec<Vec<Cell>> = grid.drain(top..top + count).collect();
+        for _ in 0..count {
+            grid.insert(bottom + 1 - count, blank.clone());
+        }
+        if keep {
+            self.scrollback.extend(removed);
+        }
+    }
+
+    fn scroll_down(&mut self, count: usize) {
+        let (top, bottom) = (self.scroll_top, self.scroll_bottom);
+        let count = count.min(bottom - top + 1);
+        let blank = vec![self.blank(); self.cols];
+        let grid = self.grid_mut();
+        grid.drain(bottom + 1 - count..=bottom);
+        for _ in 0..count {
+            grid.insert(top, blank.clone());
+        }
+    }
+
+    fn insert_lines(&mut self, count: usize) {
+        if (self.scroll_top..=self.scroll_bottom).contains(&self.cursor.row) {
+            let top = std::mem::replace(&mut self.scroll_top, self.cursor.row);
+            self.scroll_down(count);
+            self.scroll_top = top;
+            self.cursor.col = 0;
+            self.cursor.pending_wrap = false;
+        }
+    }
+
+    fn delete_lines(&mut self, count: usize) {
+        if (self.scroll_top..=self.scroll_bottom).contains(&self.cursor.row) {
+            let top = std::mem::replace(&mut self.scroll_top, self.cursor.row);
+            // Deleted lines never go to scrollback
+            let alternate = std::mem::replace(&mut self.alternate_active, true);
+            let primary_active = !alternate;
+            if primary_active {
+                std::mem::swap(&mut self.primary, &mut self.alternate);
+            }
+            self.scroll_up(count);
+            if primary_active {
+                std::mem::swap(&mut self.primary, &mut self.alternate);
+            }
+            self.alternate_active = alternate;
+            self.scroll_top = top;
+            self.cursor.col = 0;
+            self.cursor.pending_wrap = false;
+        }
+    }
+
+    fn insert_chars(&mut self, count: usize) {
+        let (row, col, cols) = (self.cursor.row, self.cursor.col, self.cols);
+        let count = count.min(cols - col);
+        let blank = self.blank();
+        let line = &mut self.grid_mut()[row];
+        line[col..].rotate_right(count);
+        line[col..col + count].fill(blank);
+        self.cursor.pending_wrap = false;
+    }
+
+    fn delete_chars(&mut self, count: usize) {
+        let (row, col, cols) = (self.cursor.row, self.cursor.col, self.cols);
+        let count = count.min(cols - col);
+        let blank = self.blank();
+        let line = &mut self.grid_mut()[row];
+        line[col..].rotate_left(count);
+        line[cols - count..].fill(blank);
+        self.cursor.pending_wrap = false;
+    }
+
+    fn erase_display(&mut self, mode: usize) {
+        let (row, col) = (self.cursor.row, self.cursor.col);
+        let blank = self.blank();
+        match mode {
+            0 => {
+                self.grid_mut()[row][col..].fill(blank);
+                for line in &mut self.grid_mut()[row + 1..] {
+                    line.fill(blank);
+                }
+            }
+            1 => {
+                for line in &mut self.grid_mut()[..row] {
+                    line.fill(blank);
+                }
+                self.grid_mut()[row][..=col].fill(blank);
+            }
+            2 => {
+                for line in self.grid_mut() {
+                    line.fill(blank);
+                }
+            }
+            3 => self.scrollback.clear(),
+            _ => {}
+        }
+        self.cursor.pending_wrap = false;
+    }
+
+    fn erase_line(&mut self, mode: usize) {
+        let (row, col) = (self.cursor.row, self.cursor.col);
+        let blank = self.blank();
+        let line = &mut self.grid_mut()[row];
+        match mode {
+            0 => line[col..].fill(blank),
+            1 => line[..=col].fill(blank),
+            2 => line.fill(blank),
+            _ => {}
+        }
+        self.cursor.pending_wrap = false;
+    }
+
+    fn move_up(&mut self, count: usize) {
+        let limit = if self.cursor.row >= self.scroll_top { self.scroll_top } else { 0 };
+        self.cursor.row = self.cursor.row.saturating_sub(count).max(limit);
+        self.cursor.pending_wrap = false;
+    }
+
+    fn move_down(&mut self, count: usize) {
+        let limit = if self.cursor.row <= self.scroll_bottom { self.scroll_bottom } else { self.rows - 1 };
+        self.cursor.row = self.cursor.row.saturating_add(count).min(limit);
+        self.cursor.pending_wrap = false;
+    }
+
+    fn set_col(&mut self, col: usize) {
+        self.cursor.col = col.min(self.cols - 1);
+        self.cursor.pending_wrap = false;
+    }
+
+    /// Move to a row and column, relative to the scroll region in origin mode
+    fn set_position(&mut self, row: usize, col: usize) {
+        self.cursor.row = if self.cursor.origin_mode {
+            (self.scroll_top + row).min(self.scroll_bottom)
+        } else {
+            row.min(self.rows - 1)
+        };
+        self.set_col(col);
+    }
+
+    fn save_cursor(&mut self) {
+        if self.alternate_active {
+            self.saved_alternate = self.cursor;
+        } else {
+            self.saved_primary = self.cursor;
+        }
+    }
+
+    fn restore_cursor(&mut self) {
+        self.cursor = if self.alternate_active { self.saved_alternate } else { self.saved_primary };
+        self.cursor.row = self.cursor.row.min(self.rows - 1);
+        self.cursor.col = self.cursor.col.min(self.cols - 1);
+    }
+
+    /// Full reset (RIS), keeping the size and the scrollback
+    fn reset(&mut self) {
+        let scrollback = std::mem::take(&mut self.scrollback);
+        *self = Self::new(self.cols as u16, self.rows as u16);
+        self.scrollback = scrollback;
+    }
+
+    /// Erased cell, which keeps the current background color
+    fn blank(&self) -> Cell {
+        Cell {
+            ch: ' ',
+            style: CellStyle { bg: self.cursor.style.bg, ..CellStyle::default() },
+        }
+    }
+
+    fn grid(&self) -> &Vec<Vec<Cell>> {
+        if self.alternate_active { &self.alternate } else { &self.primary }
+    }
+
+    fn grid_mut(&mut self) -> &mut Vec<Vec<Cell>> {
+        if self.alternate_active { &mut self.alternate } else { &mut self.primary }
+    }
+}
+
+/// Color of an SGR 38/48 sequence and the number of parameters it used
+fn extended_color(params: &[u16]) -> (Option<VtColor>, usize) {
+    match params {
+        [5, index, ..] => (Some(VtColor::Indexed((*index).min(255) as u8)), 2),
+        [2, r, g, b, ..] => (Some(VtColor::Rgb((*r).min(255) as u8, (*g).min(255) as u8, (*b).min(255) as u8)), 4),
+        [5] => (None, 1),
+        [2, rest @ ..] => (None, 1 + rest.len().min(3)),
+        _ => (None, 0),
+    }
+}
+
+fn blank_grid(cols: usize, rows: usize) -> Vec<Vec<Cell>> {
+    vec![vec![Cell::default(); cols]; rows]
+}
+
+fn default_tab_stops(cols: usize) -> Vec<bool> {
+    (0..cols).map(|col| col % 8 == 0 && col > 0).collect()
+}
+
+fn row_text(row: &[Cell]) -> String {
+    let text: String = row.iter().map(|cell| if cell.style.hidden { ' ' } else { cell.ch }).collect();
+    text.trim_end().to_string()
+}
+
+/// Terminal line for a screen row; styled when all of its text shares one style
+fn to_terminal_line(row: &[Cell]) -> TerminalLine {
+    let mut styles = row.iter().filter(|cell| cell.ch != ' ').map(|cell| cell.style);
+    let first = styles.next();
+    let uniform = first.filter(|style| *style != CellStyle::default() && styles.all(|other| other == *style));
+    TerminalLine {
+        content: row_text(row),
+        line_type: LineType::Normal,
+        timestamp: Instant::now(),
+        ends_with_newline: true,
+        ansi_metadata: uniform.map(CellStyle::to_metadata),
+    }
+}
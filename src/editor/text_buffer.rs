@@ -2,6 +2,9 @@
 //!
 //! Provides rope-based text storage with diff-based LSP changes, structured cursor/selection model,
 //! and operation undo stack as specified in the improvement plan.
+//!
+//! Edits can target every cursor of a [`SelectionSet`] at once; each such edit
+//! is a single [`TransactionGroup`] on the undo stack.
 
 use std::collections::VecDeque;
 use std::ops::Range;
@@ -50,6 +53,11 @@ impl Cursor {
             affinity: CursorAffinity::Downstream,
         }
     }
+
+    /// Whether the cursor selects any text
+    pub fn has_selection(&self) -> bool {
+        self.anchor.as_ref().is_some_and(|anchor| *anchor != self.position)
+    }
 }
 
 /// Text position with line and column
@@ -81,6 +89,15 @@ pub struct SelectionSet {
     pub primary: usize,
 }
 
+/// Which side of a caret a delete removes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeleteDirection {
+    /// The character before the caret (Backspace)
+    Backward,
+    /// The character after the caret (Delete)
+    Forward,
+}
+
 /// Line ending styles
 #[derive(Clone, Debug, PartialEq)]
 pub enum LineEnding {
@@ -103,11 +120,11 @@ pub enum TextEncoding {
 
 /// Undo/redo stack with operation history
 pub struct UndoStack {
-    /// Undo operations stack
-    pub undo_stack: VecDeque<TextOperation>,
-    /// Redo operations stack
-    pub redo_stack: VecDeque<TextOperation>,
-    /// Maximum undo history size
+    /// Undo stack; each entry is undone as a whole
+    pub undo_stack: VecDeque<TransactionGroup>,
+    /// Redo stack
+    pub redo_stack: VecDeque<TransactionGroup>,
+    /// Maximum undo history size, in transactions
     pub max_history: usize,
     /// Current transaction group
     pub current_transaction: Option<TransactionGroup>,
@@ -120,8 +137,11 @@ pub struct TextOperation {
     pub operation: OperationType,
     /// Position where operation occurred
     pub position: TextPosition,
-    /// Text involved in operation
+    /// Text involved in operation: the inserted text for inserts, the
+    /// removed text otherwise
     pub text: String,
+    /// Text that replaced `text` (replace operations only)
+    pub new_text: String,
     /// Selection state before operation
     pub old_selection: SelectionSet,
     /// Selection state after operation
@@ -142,6 +162,9 @@ pub enum OperationType {
 }
 
 /// Transaction group for atomic undo/redo
+///
+/// An edit at several cursors is one group, so undoing it restores the text
+/// and the whole cursor set together.
 #[derive(Clone, Debug)]
 pub struct TransactionGroup {
     /// Operations in this transaction
@@ -274,43 +297,8 @@ impl TextBuffer {
             return Err(TextBufferError::InvalidPosition(position));
         }
 
-        // Convert position to byte offset
         let offset = self.position_to_offset(&position)?;
-        
-        // Perform insertion
-        self.rope.insert(offset, text);
-        
-        // Update version and metadata
-        self.version += 1;
-        self.is_dirty = true;
-        self.update_metadata();
-        
-        // Create operation for undo stack
-        let operation = TextOperation {
-            operation: OperationType::Insert,
-            position: position.clone(),
-            text: text.to_string(),
-            old_selection: selection.clone(),
-            new_selection: self.adjust_selection_after_insert(&selection, &position, text.len()),
-            timestamp: std::time::Instant::now(),
-        };
-        
-        // Record change for LSP
-        let change = TextChange {
-            range: TextRange {
-                start: position.clone(),
-                end: position.clone(),
-            },
-            text: text.to_string(),
-            range_length: 0,
-            version: self.version,
-        };
-        
-        // Update stacks
-        self.undo_stack.push_operation(operation);
-        self.change_tracker.record_change(change);
-        
-        Ok(self.adjust_selection_after_insert(&selection, &position, text.len()))
+        self.apply_edits(vec![Edit { range: offset..offset, text: text.to_string() }], selection, None)
     }
 
     /// Delete text range
@@ -320,44 +308,8 @@ impl TextBuffer {
             return Err(TextBufferError::InvalidRange(range));
         }
 
-        // Convert range to byte offsets
-        let start_offset = self.position_to_offset(&range.start)?;
-        let end_offset = self.position_to_offset(&range.end)?;
-        
-        // Get text being deleted for undo
-        let deleted_text = self.rope.slice(start_offset..end_offset).to_string();
-        
-        // Perform deletion
-        self.rope.remove(start_offset..end_offset);
-        
-        // Update version and metadata
-        self.version += 1;
-        self.is_dirty = true;
-        self.update_metadata();
-        
-        // Create operation for undo stack
-        let operation = TextOperation {
-            operation: OperationType::Delete,
-            position: range.start.clone(),
-            text: deleted_text,
-            old_selection: selection.clone(),
-            new_selection: self.adjust_selection_after_delete(&selection, &range),
-            timestamp: std::time::Instant::now(),
-        };
-        
-        // Record change for LSP
-        let change = TextChange {
-            range: range.clone(),
-            text: String::new(),
-            range_length: end_offset - start_offset,
-            version: self.version,
-        };
-        
-        // Update stacks
-        self.undo_stack.push_operation(operation);
-        self.change_tracker.record_change(change);
-        
-        Ok(self.adjust_selection_after_delete(&selection, &range))
+        let range = self.position_to_offset(&range.start)?..self.position_to_offset(&range.end)?;
+        self.apply_edits(vec![Edit { range, text: String::new() }], selection, None)
     }
 
     /// Replace text range
@@ -367,141 +319,169 @@ impl TextBuffer {
             return Err(TextBufferError::InvalidRange(range));
         }
 
-        // Convert range to byte offsets
-        let start_offset = self.position_to_offset(&range.start)?;
-        let end_offset = self.position_to_offset(&range.end)?;
-        
-        // Get text being replaced for undo
-        let old_text = self.rope.slice(start_offset..end_offset).to_string();
-        
-        // Perform replacement
-        self.rope.remove(start_offset..end_offset);
-        self.rope.insert(start_offset, text);
-        
-        // Update version and metadata
-        self.version += 1;
-        self.is_dirty = true;
-        self.update_metadata();
-        
-        // Create operation for undo stack
-        let operation = TextOperation {
-            operation: OperationType::Replace,
-            position: range.start.clone(),
-            text: old_text,
-            old_selection: selection.clone(),
-            new_selection: self.adjust_selection_after_replace(&selection, &range, text.len()),
-            timestamp: std::time::Instant::now(),
-        };
-        
-        // Record change for LSP
-        let change = TextChange {
-            range: range.clone(),
-            text: text.to_string(),
-            range_length: end_offset - start_offset,
-            version: self.version,
-        };
-        
-        // Update stacks
-        self.undo_stack.push_operation(operation);
-        self.change_tracker.record_change(change);
-        
-        Ok(self.adjust_selection_after_replace(&selection, &range, text.len()))
+        let range = self.position_to_offset(&range.start)?..self.position_to_offset(&range.end)?;
+        self.apply_edits(vec![Edit { range, text: text.to_string() }], selection, None)
+    }
+
+    /// Type text at every cursor, replacing each cursor's selection
+    ///
+    /// All insertions form one undo step and every cursor ends up as a caret
+    /// after its inserted text.
+    pub fn insert_at_cursors(&mut self, text: &str, selection: SelectionSet) -> Result<SelectionSet, TextBufferError> {
+        let ranges = self.cursor_ranges(&selection)?;
+        let edits = ranges.iter().map(|(range, _)| Edit { range: range.clone(), text: text.to_string() }).collect();
+        let carets = ranges.iter().map(|(range, primary)| (range.end, *primary)).collect();
+        self.apply_edits(edits, selection, Some(carets))
+    }
+
+    /// Delete at every cursor: the selection if there is one, otherwise the
+    /// character before or after the caret
+    pub fn delete_at_cursors(&mut self, direction: DeleteDirection, selection: SelectionSet) -> Result<SelectionSet, TextBufferError> {
+        let ranges = self.cursor_ranges(&selection)?;
+        let mut edits = Vec::new();
+        for (range, _) in &ranges {
+            let range = if !range.is_empty() {
+                range.clone()
+            } else {
+                let char_index = self.rope.byte_to_char(range.start);
+                match direction {
+                    DeleteDirection::Backward if char_index > 0 => self.rope.char_to_byte(char_index - 1)..range.start,
+                    DeleteDirection::Forward if char_index < self.rope.len_chars() => range.start..self.rope.char_to_byte(char_index + 1),
+                    _ => continue,
+                }
+            };
+            // Carets next to each other would delete the same character twice
+            if edits.last().is_some_and(|last: &Edit| last.range.end > range.start) {
+                continue;
+            }
+            edits.push(Edit { range, text: String::new() });
+        }
+        let carets = ranges.iter().map(|(range, primary)| match direction {
+            DeleteDirection::Forward => (range.start, *primary),
+            DeleteDirection::Backward => (range.end, *primary),
+        }).collect();
+        self.apply_edits(edits, selection, Some(carets))
+    }
+
+    /// Replace each cursor's selection with its own text, as when pasting one
+    /// line per cursor
+    ///
+    /// `texts` pairs up with the cursors in document order.
+    pub fn replace_at_cursors(&mut self, texts: &[String], selection: SelectionSet) -> Result<SelectionSet, TextBufferError> {
+        let ranges = self.cursor_ranges(&selection)?;
+        if ranges.len() != texts.len() {
+            return Err(TextBufferError::CursorCountMismatch { cursors: ranges.len(), texts: texts.len() });
+        }
+        let edits = ranges.iter().zip(texts).map(|((range, _), text)| Edit { range: range.clone(), text: text.clone() }).collect();
+        let carets = ranges.iter().map(|(range, primary)| (range.end, *primary)).collect();
+        self.apply_edits(edits, selection, Some(carets))
     }
 
-    /// Undo last operation
+    /// Undo the last transaction, restoring the cursors it started with
     pub fn undo(&mut self) -> Result<SelectionSet, TextBufferError> {
-        if let Some(operation) = self.undo_stack.pop_undo() {
-            let old_selection = operation.old_selection.clone();
-            let operation_clone = operation.clone(); // Clone before partial moves
+        let group = self.undo_stack.pop_undo().ok_or(TextBufferError::NothingToUndo)?;
+        for operation in group.operations.iter().rev() {
+            let start = self.position_to_offset(&operation.position)?;
             match operation.operation {
-                OperationType::Insert => {
-                    // Undo insert by deleting the inserted text
-                    let end_pos = self.offset_to_position(
-                        self.position_to_offset(&operation.position)? + operation.text.len()
-                    )?;
-                    let range = TextRange {
-                        start: operation.position,
-                        end: end_pos,
-                    };
-                    self.rope.remove(self.position_to_offset(&range.start)?..self.position_to_offset(&range.end)?);
-                }
-                OperationType::Delete => {
-                    // Undo delete by inserting the deleted text
-                    let offset = self.position_to_offset(&operation.position)?;
-                    self.rope.insert(offset, &operation.text);
-                }
-                OperationType::Replace => {
-                    // Undo replace by replacing with original text
-                    let end_pos = self.offset_to_position(
-                        self.position_to_offset(&operation.position)? + operation.text.len()
-                    )?;
-                    let range = TextRange {
-                        start: operation.position,
-                        end: end_pos,
-                    };
-                    self.rope.remove(self.position_to_offset(&range.start)?..self.position_to_offset(&range.end)?);
-                    self.rope.insert(self.position_to_offset(&range.start)?, &operation.text);
-                }
-            }
-            
-            self.version += 1;
-            self.is_dirty = true;
-            self.update_metadata();
-            
-            // Move operation to redo stack
-            self.undo_stack.push_redo(operation_clone);
-            
-            Ok(old_selection)
-        } else {
-            Err(TextBufferError::NothingToUndo)
+                OperationType::Insert => self.splice(start..start + operation.text.len(), "")?,
+                OperationType::Delete => self.splice(start..start, &operation.text)?,
+                OperationType::Replace => self.splice(start..start + operation.new_text.len(), &operation.text)?,
+            };
         }
+        self.is_dirty = true;
+
+        let selection = group.selection_before().cloned().unwrap_or_else(SelectionSet::new);
+        self.undo_stack.push_redo(group);
+        Ok(selection)
     }
 
-    /// Redo last undone operation
+    /// Redo the last undone transaction
     pub fn redo(&mut self) -> Result<SelectionSet, TextBufferError> {
-        if let Some(operation) = self.undo_stack.pop_redo() {
-            let operation_clone = operation.clone(); // Clone before partial moves
-            let new_selection = operation.new_selection.clone(); // Extract before partial move
+        let group = self.undo_stack.pop_redo().ok_or(TextBufferError::NothingToRedo)?;
+        for operation in &group.operations {
+            let start = self.position_to_offset(&operation.position)?;
             match operation.operation {
-                OperationType::Insert => {
-                    let offset = self.position_to_offset(&operation.position)?;
-                    self.rope.insert(offset, &operation.text);
-                }
-                OperationType::Delete => {
-                    let end_pos = self.offset_to_position(
-                        self.position_to_offset(&operation.position)? + operation.text.len()
-                    )?;
-                    let range = TextRange {
-                        start: operation.position,
-                        end: end_pos,
-                    };
-                    self.rope.remove(self.position_to_offset(&range.start)?..self.position_to_offset(&range.end)?);
-                }
-                OperationType::Replace => {
-                    let end_pos = self.offset_to_position(
-                        self.position_to_offset(&operation.position)? + operation.text.len()
-                    )?;
-                    let range = TextRange {
-                        start: operation.position,
-                        end: end_pos,
-                    };
-                    self.rope.remove(self.position_to_offset(&range.start)?..self.position_to_offset(&range.end)?);
-                    // Insert new text (stored in the operation's text field for replace operations)
-                    // Note: For proper redo, we'd need to store both old and new text
-                }
-            }
-            
-            self.version += 1;
-            self.is_dirty = true;
-            self.update_metadata();
-            
-            // Move operation back to undo stack
-            self.undo_stack.push_operation(operation_clone);
+                OperationType::Insert => self.splice(start..start, &operation.text)?,
+                OperationType::Delete => self.splice(start..start + operation.text.len(), "")?,
+                OperationType::Replace => self.splice(start..start + operation.text.len(), &operation.new_text)?,
+            };
+        }
+        self.is_dirty = true;
+
+        let selection = group.selection_after().cloned().unwrap_or_else(SelectionSet::new);
+        self.undo_stack.push_transaction(group);
+        Ok(selection)
+    }
 
-            Ok(new_selection)
+    /// Select the next occurrence of the primary selection as a new cursor
+    ///
+    /// With a caret and no selection, selects the word under the caret
+    /// first. The search wraps around the end of the buffer and the new
+    /// cursor becomes the primary one.
+    pub fn add_next_occurrence(&self, selection: &SelectionSet) -> SelectionSet {
+        let mut result = selection.clone();
+        let Some((needle, range, whole_word)) = self.occurrence_target(selection) else {
+            return result;
+        };
+        if !whole_word || selection.primary_cursor().has_selection() {
+            let selected: Vec<Range<usize>> = self.cursor_ranges(selection).unwrap_or_default().into_iter().map(|(range, _)| range).collect();
+            let matches = self.occurrences(&needle, whole_word);
+            let next = matches.iter()
+                .filter(|candidate| candidate.start >= range.end)
+                .chain(matches.iter().filter(|candidate| candidate.start < range.end))
+                .find(|candidate| !selected.iter().any(|taken| taken.start < candidate.end && candidate.start < taken.end));
+            if let Some(next) = next {
+                result.add_cursor(self.cursor_for(next.clone()));
+                result.primary = result.cursors.len() - 1;
+            }
         } else {
-            Err(TextBufferError::NothingToRedo)
+            // The first press only selects the word under the caret
+            *result.primary_cursor_mut() = self.cursor_for(range);
+        }
+        result
+    }
+
+    /// Select every occurrence of the primary selection, or of the word under
+    /// the caret, with one cursor each
+    pub fn select_all_occurrences(&self, selection: &SelectionSet) -> SelectionSet {
+        let Some((needle, range, whole_word)) = self.occurrence_target(selection) else {
+            return selection.clone();
+        };
+        let matches = self.occurrences(&needle, whole_word);
+        SelectionSet {
+            primary: matches.iter().position(|candidate| *candidate == range).unwrap_or(0),
+            cursors: matches.into_iter().map(|candidate| self.cursor_for(candidate)).collect(),
+        }
+    }
+
+    /// Box selection from `anchor` to `head`, as made by an Alt-drag
+    ///
+    /// Every line between the two gets a cursor spanning the same columns,
+    /// clamped to the line. Lines ending left of the box are skipped, except
+    /// the head's line so the set is never empty; its cursor is the primary.
+    pub fn column_selection(&self, anchor: &TextPosition, head: &TextPosition) -> SelectionSet {
+        let last_line = self.metadata.line_count.saturating_sub(1);
+        let (head_line, anchor_line) = (head.line.min(last_line), anchor.line.min(last_line));
+        let left = anchor.column.min(head.column);
+
+        let mut selection = SelectionSet::new();
+        for line in anchor_line.min(head_line)..=anchor_line.max(head_line) {
+            let length = self.line_length(line);
+            if length < left && line != head_line {
+                continue;
+            }
+            if line == head_line {
+                selection.primary = selection.cursors.len();
+            }
+            let start = self.position_in_line(line, anchor.column.min(length));
+            let end = self.position_in_line(line, head.column.min(length));
+            selection.add_cursor(Cursor {
+                anchor: (start != end).then_some(start),
+                position: end,
+                affinity: CursorAffinity::Downstream,
+            });
         }
+        selection
     }
 
     /// Get text content as string
@@ -522,7 +502,7 @@ impl TextBuffer {
     pub fn slice(&self, range: TextRange) -> Result<String, TextBufferError> {
         let start_offset = self.position_to_offset(&range.start)?;
         let end_offset = self.position_to_offset(&range.end)?;
-        Ok(self.rope.slice(start_offset..end_offset).to_string())
+        Ok(self.rope.byte_slice(start_offset..end_offset).to_string())
     }
 
     /// Convert position to byte offset
@@ -612,17 +592,29 @@ impl TextBuffer {
         matches
     }
 
-    /// Replace all occurrences of a pattern
+    /// Replace all occurrences of a pattern as one undo step
     pub fn replace_all(&mut self, pattern: &str, replacement: &str, selection: SelectionSet) -> Result<usize, TextBufferError> {
-        let matches = self.find_all(pattern);
-        let count = matches.len();
+        let edits: Vec<Edit> = self.occurrences(pattern, false).into_iter()
+            .map(|range| Edit { range, text: replacement.to_string() })
+            .collect();
+        let count = edits.len();
+        self.apply_edits(edits, selection, None)?;
+        Ok(count)
+    }
 
-        // Process matches in reverse order to maintain position validity
-        for range in matches.into_iter().rev() {
-            self.replace(range, replacement, selection.clone())?;
+    /// Replace several ranges as one undo step
+    ///
+    /// Ranges refer to the text before any replacement and must not overlap.
+    pub fn replace_ranges(&mut self, replacements: &[(TextRange, String)], selection: SelectionSet) -> Result<SelectionSet, TextBufferError> {
+        let mut edits = Vec::with_capacity(replacements.len());
+        for (range, text) in replacements {
+            if !self.is_valid_range(range) {
+                return Err(TextBufferError::InvalidRange(range.clone()));
+            }
+            let range = self.position_to_offset(&range.start)?..self.position_to_offset(&range.end)?;
+            edits.push(Edit { range, text: text.clone() });
         }
-
-        Ok(count)
+        self.apply_edits(edits, selection, None)
     }
 
     /// Get text in a specific range
@@ -673,96 +665,222 @@ impl TextBuffer {
         }
     }
 
-    /// Adjust selection after insert operation
-    fn adjust_selection_after_insert(&self, selection: &SelectionSet, position: &TextPosition, insert_len: usize) -> SelectionSet {
-        let mut new_selection = selection.clone();
-        for cursor in &mut new_selection.cursors {
-            if cursor.position >= *position {
-                cursor.position.offset += insert_len;
-                // Update line/column based on new offset
-                if let Ok(new_pos) = self.offset_to_position(cursor.position.offset) {
-                    cursor.position = new_pos;
-                }
-            }
-            if let Some(ref mut anchor) = cursor.anchor {
-                if *anchor >= *position {
-                    anchor.offset += insert_len;
-                    // Update line/column based on new offset
-                    if let Ok(new_pos) = self.offset_to_position(anchor.offset) {
-                        *anchor = new_pos;
-                    }
-                }
-            }
+    /// Apply byte-range edits of the current text as one undo step
+    ///
+    /// `edits` must not overlap. With `carets`, the result is a caret per
+    /// `(offset, primary)` entry, mapped through the edits; otherwise every
+    /// cursor of `selection` is moved along with the text around it.
+    fn apply_edits(&mut self, mut edits: Vec<Edit>, selection: SelectionSet, carets: Option<Vec<(usize, bool)>>) -> Result<SelectionSet, TextBufferError> {
+        edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+        if let Some(pair) = edits.windows(2).find(|pair| pair[0].range.end > pair[1].range.start) {
+            let range = TextRange { start: self.offset_to_position(pair[1].range.start)?, end: self.offset_to_position(pair[0].range.end)? };
+            return Err(TextBufferError::InvalidRange(range));
         }
-        new_selection
+
+        let new_offsets: Vec<(Option<usize>, usize)> = match &carets {
+            Some(carets) => carets.iter().map(|(offset, _)| (None, map_offset(*offset, &edits))).collect(),
+            None => selection.cursors.iter().map(|cursor| {
+                let anchor = cursor.anchor.as_ref().map(|anchor| map_offset(self.resolve_offset(anchor), &edits));
+                (anchor, map_offset(self.resolve_offset(&cursor.position), &edits))
+            }).collect(),
+        };
+
+        // Apply from the end so the offsets of earlier edits stay valid
+        let mut operations = Vec::with_capacity(edits.len());
+        for edit in edits.iter().rev() {
+            let position = self.offset_to_position(edit.range.start)?;
+            let removed = self.rope.byte_slice(edit.range.clone()).to_string();
+            self.splice(edit.range.clone(), &edit.text)?;
+            let operation = match (removed.is_empty(), edit.text.is_empty()) {
+                (true, _) => OperationType::Insert,
+                (false, true) => OperationType::Delete,
+                (false, false) => OperationType::Replace,
+            };
+            let (text, new_text) = match operation {
+                OperationType::Insert => (edit.text.clone(), String::new()),
+                _ => (removed, edit.text.clone()),
+            };
+            operations.push((operation, position, text, new_text));
+        }
+        if !edits.is_empty() {
+            self.is_dirty = true;
+        }
+
+        let mut new_selection = SelectionSet::new();
+        for (anchor, position) in new_offsets {
+            new_selection.add_cursor(Cursor {
+                position: self.offset_to_position(position)?,
+                anchor: anchor.map(|anchor| self.offset_to_position(anchor)).transpose()?,
+                affinity: CursorAffinity::Downstream,
+            });
+        }
+        new_selection.primary = match &carets {
+            Some(carets) => carets.iter().position(|(_, primary)| *primary).unwrap_or(0),
+            None => selection.primary.min(new_selection.cursors.len().saturating_sub(1)),
+        };
+
+        let owns_transaction = self.undo_stack.current_transaction.is_none();
+        if owns_transaction {
+            self.undo_stack.begin_transaction("edit".to_string());
+        }
+        for (operation, position, text, new_text) in operations {
+            self.undo_stack.push_operation(TextOperation {
+                operation,
+                position,
+                text,
+                new_text,
+                old_selection: selection.clone(),
+                new_selection: new_selection.clone(),
+                timestamp: std::time::Instant::now(),
+            });
+        }
+        if owns_transaction {
+            self.undo_stack.end_transaction();
+        }
+
+        Ok(new_selection)
     }
 
-    /// Adjust selection after delete operation
-    fn adjust_selection_after_delete(&self, selection: &SelectionSet, range: &TextRange) -> SelectionSet {
-        let mut new_selection = selection.clone();
-        let delete_len = range.end.offset - range.start.offset;
-        
-        for cursor in &mut new_selection.cursors {
-            if cursor.position > range.end {
-                cursor.position.offset -= delete_len;
-                if let Ok(new_pos) = self.offset_to_position(cursor.position.offset) {
-                    cursor.position = new_pos;
-                }
-            } else if cursor.position > range.start {
-                cursor.position = range.start.clone();
-            }
-            
-            if let Some(ref mut anchor) = cursor.anchor {
-                if *anchor > range.end {
-                    anchor.offset -= delete_len;
-                    if let Ok(new_pos) = self.offset_to_position(anchor.offset) {
-                        *anchor = new_pos;
-                    }
-                } else if *anchor > range.start {
-                    *anchor = range.start.clone();
+    /// Replace a byte range of the rope and record the change for LSP
+    fn splice(&mut self, range: Range<usize>, text: &str) -> Result<(), TextBufferError> {
+        let change_range = TextRange {
+            start: self.offset_to_position(range.start)?,
+            end: self.offset_to_position(range.end)?,
+        };
+        let (start, end) = (self.rope.byte_to_char(range.start), self.rope.byte_to_char(range.end));
+        self.rope.remove(start..end);
+        self.rope.insert(start, text);
+        self.version += 1;
+        self.update_metadata();
+
+        self.change_tracker.record_change(TextChange {
+            range: change_range,
+            text: text.to_string(),
+            range_length: range.len(),
+            version: self.version,
+        });
+        Ok(())
+    }
+
+    /// Byte offset of a position, trusting its line and column over its offset
+    fn resolve_offset(&self, position: &TextPosition) -> usize {
+        self.position_to_offset(position).unwrap_or_else(|_| position.offset.min(self.rope.len_bytes()))
+    }
+
+    /// Byte ranges of the cursors in document order, overlapping ones merged,
+    /// each flagged if it holds the primary cursor
+    fn cursor_ranges(&self, selection: &SelectionSet) -> Result<Vec<(Range<usize>, bool)>, TextBufferError> {
+        let mut ranges = Vec::with_capacity(selection.cursors.len());
+        for (index, cursor) in selection.cursors.iter().enumerate() {
+            let position = self.position_to_offset(&cursor.position)?;
+            let anchor = match &cursor.anchor {
+                Some(anchor) => self.position_to_offset(anchor)?,
+                None => position,
+            };
+            ranges.push((anchor.min(position)..anchor.max(position), index == selection.primary));
+        }
+        ranges.sort_by_key(|(range, _)| (range.start, range.end));
+
+        let mut merged: Vec<(Range<usize>, bool)> = Vec::with_capacity(ranges.len());
+        for (range, primary) in ranges {
+            match merged.last_mut() {
+                Some((last, last_primary)) if range.start < last.end || range == *last => {
+                    last.end = last.end.max(range.end);
+                    *last_primary |= primary;
                 }
+                _ => merged.push((range, primary)),
             }
         }
-        new_selection
+        Ok(merged)
+    }
+
+    /// Text to look for when adding occurrences: the primary selection, or
+    /// the word under the caret as a whole word
+    fn occurrence_target(&self, selection: &SelectionSet) -> Option<(String, Range<usize>, bool)> {
+        let cursor = selection.cursors.get(selection.primary)?;
+        let position = self.position_to_offset(&cursor.position).ok()?;
+        if cursor.has_selection() {
+            let anchor = self.position_to_offset(cursor.anchor.as_ref()?).ok()?;
+            let range = anchor.min(position)..anchor.max(position);
+            return Some((self.rope.byte_slice(range.clone()).to_string(), range, false));
+        }
+
+        let text = self.to_string();
+        let start = text[..position].char_indices().rev()
+            .take_while(|(_, c)| is_word_char(*c))
+            .last()
+            .map_or(position, |(index, _)| index);
+        let end = text[position..].char_indices()
+            .find(|(_, c)| !is_word_char(*c))
+            .map_or(text.len(), |(index, _)| position + index);
+        (start < end).then(|| (text[start..end].to_string(), start..end, true))
+    }
+
+    /// Byte ranges of the non-overlapping occurrences of `needle`
+    fn occurrences(&self, needle: &str, whole_word: bool) -> Vec<Range<usize>> {
+        if needle.is_empty() {
+            return Vec::new();
+        }
+        let text = self.to_string();
+        text.match_indices(needle)
+            .map(|(start, _)| start..start + needle.len())
+            .filter(|range| !whole_word || (
+                !text[..range.start].ends_with(is_word_char) && !text[range.end..].starts_with(is_word_char)
+            ))
+            .collect()
     }
 
-    /// Adjust selection after replace operation
-    fn adjust_selection_after_replace(&self, selection: &SelectionSet, range: &TextRange, new_len: usize) -> SelectionSet {
-        let mut new_selection = selection.clone();
-        let old_len = range.end.offset - range.start.offset;
-        let len_diff = new_len as i64 - old_len as i64;
-        
-        for cursor in &mut new_selection.cursors {
-            if cursor.position > range.end {
-                cursor.position.offset = (cursor.position.offset as i64 + len_diff) as usize;
-                if let Ok(new_pos) = self.offset_to_position(cursor.position.offset) {
-                    cursor.position = new_pos;
-                }
-            } else if cursor.position > range.start {
-                cursor.position = range.start.clone();
-                cursor.position.offset += new_len;
-                if let Ok(new_pos) = self.offset_to_position(cursor.position.offset) {
-                    cursor.position = new_pos;
-                }
-            }
-            
-            if let Some(ref mut anchor) = cursor.anchor {
-                if *anchor > range.end {
-                    anchor.offset = (anchor.offset as i64 + len_diff) as usize;
-                    if let Ok(new_pos) = self.offset_to_position(anchor.offset) {
-                        *anchor = new_pos;
-                    }
-                } else if *anchor > range.start {
-                    *anchor = range.start.clone();
-                    anchor.offset += new_len;
-                    if let Ok(new_pos) = self.offset_to_position(anchor.offset) {
-                        *anchor = new_pos;
-                    }
-                }
-            }
+    /// Cursor selecting a byte range, with the caret at its end
+    fn cursor_for(&self, range: Range<usize>) -> Cursor {
+        let (Ok(start), Ok(end)) = (self.offset_to_position(range.start), self.offset_to_position(range.end)) else {
+            return Cursor::new(TextPosition { line: 0, column: 0, offset: 0 });
+        };
+        Cursor { anchor: Some(start), position: end, affinity: CursorAffinity::Downstream }
+    }
+
+    /// Number of characters on a line, not counting its line ending
+    fn line_length(&self, line: usize) -> usize {
+        let slice = self.rope.line(line);
+        let mut length = slice.len_chars();
+        while length > 0 && matches!(slice.char(length - 1), '\n' | '\r') {
+            length -= 1;
+        }
+        length
+    }
+
+    /// Position of a column within a line known to be long enough
+    fn position_in_line(&self, line: usize, column: usize) -> TextPosition {
+        let offset = self.rope.line_to_byte(line) + self.rope.line(line).slice(..column).len_bytes();
+        TextPosition { line, column, offset }
+    }
+}
+
+/// A replacement of a byte range of the text before any edit of its batch
+struct Edit {
+    range: Range<usize>,
+    text: String,
+}
+
+/// Where a byte offset ends up after a sorted batch of edits
+///
+/// Offsets after an edit shift with it, as do carets at an insertion point;
+/// offsets inside a replaced range move to the end of the new text.
+fn map_offset(offset: usize, edits: &[Edit]) -> usize {
+    let mut shift = 0isize;
+    for edit in edits {
+        if offset < edit.range.start || (offset == edit.range.start && !edit.range.is_empty()) {
+            break;
+        }
+        if offset < edit.range.end {
+            return (edit.range.start as isize + shift) as usize + edit.text.len();
         }
-        new_selection
+        shift += edit.text.len() as isize - edit.range.len() as isize;
     }
+    (offset as isize + shift) as usize
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
 }
 
 impl UndoStack {
@@ -776,13 +894,24 @@ impl UndoStack {
         }
     }
 
-    /// Push operation to undo stack
+    /// Record a new operation, in the open transaction if there is one
     pub fn push_operation(&mut self, operation: TextOperation) {
         // Clear redo stack when new operation is added
         self.redo_stack.clear();
         
-        // Add to undo stack
-        self.undo_stack.push_back(operation);
+        match &mut self.current_transaction {
+            Some(transaction) => transaction.operations.push(operation),
+            None => self.push_transaction(TransactionGroup {
+                description: format!("{:?}", operation.operation),
+                start_time: operation.timestamp,
+                operations: vec![operation],
+            }),
+        }
+    }
+
+    /// Push a whole transaction to the undo stack
+    pub fn push_transaction(&mut self, transaction: TransactionGroup) {
+        self.undo_stack.push_back(transaction);
         
         // Limit stack size
         if self.undo_stack.len() > self.max_history {
@@ -790,14 +919,14 @@ impl UndoStack {
         }
     }
 
-    /// Pop operation from undo stack
-    pub fn pop_undo(&mut self) -> Option<TextOperation> {
+    /// Pop transaction from undo stack
+    pub fn pop_undo(&mut self) -> Option<TransactionGroup> {
         self.undo_stack.pop_back()
     }
 
-    /// Push operation to redo stack
-    pub fn push_redo(&mut self, operation: TextOperation) {
-        self.redo_stack.push_back(operation);
+    /// Push transaction to redo stack
+    pub fn push_redo(&mut self, transaction: TransactionGroup) {
+        self.redo_stack.push_back(transaction);
         
         // Limit stack size
         if self.redo_stack.len() > self.max_history {
@@ -805,13 +934,19 @@ impl UndoStack {
         }
     }
 
-    /// Pop operation from redo stack
-    pub fn pop_redo(&mut self) -> Option<TextOperation> {
+    /// Pop transaction from redo stack
+    pub fn pop_redo(&mut self) -> Option<TransactionGroup> {
         self.redo_stack.pop_back()
     }
 
     /// Start transaction group
+    ///
+    /// Operations pushed until [`UndoStack::end_transaction`] are undone
+    /// together. An already open transaction keeps collecting instead.
     pub fn begin_transaction(&mut self, description: String) {
+        if self.current_transaction.is_some() {
+            return;
+        }
         self.current_transaction = Some(TransactionGroup {
             operations: Vec::new(),
             description,
@@ -819,17 +954,28 @@ impl UndoStack {
         });
     }
 
-    /// End transaction group
+    /// End transaction group, pushing it unless it is empty
     pub fn end_transaction(&mut self) {
         if let Some(transaction) = self.current_transaction.take() {
             if !transaction.operations.is_empty() {
-                // Convert transaction to single compound operation
-                // This would need more sophisticated implementation
+                self.push_transaction(transaction);
             }
         }
     }
 }
 
+impl TransactionGroup {
+    /// Cursors before the first operation
+    pub fn selection_before(&self) -> Option<&SelectionSet> {
+        self.operations.first().map(|operation| &operation.old_selection)
+    }
+
+    /// Cursors after the last operation
+    pub fn selection_after(&self) -> Option<&SelectionSet> {
+        self.operations.last().map(|operation| &operation.new_selection)
+    }
+}
+
 impl ChangeTracker {
     /// Create new change tracker
     pub fn new() -> Self {
@@ -926,6 +1072,8 @@ pub enum TextBufferError {
     NothingToUndo,
     #[error("Nothing to redo")]
     NothingToRedo,
+    #[error("{texts} texts given for {cursors} cursors")]
+    CursorCountMismatch { cursors: usize, texts: usize },
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 }
@@ -0,0 +1,392 @@
+//! Three-Way Merge of Generated Code with User Edits
+//!
+//! Regenerating a designer file merges three versions line by line: the code
+//! generated last time (the base), the file as the user left it (ours) and the
+//! newly generated code (theirs). Regions changed on only one side take that
+//! side's lines, identical changes on both sides are taken once, and regions
+//! changed differently on both sides become conflicts. Conflicts are resolved
+//! in the [`MergeView`] or written out with conflict markers.
+
+/// Label of the user's side in conflict markers and the merge view
+pub const OURS_LABEL: &str = "your edits";
+
+/// Label of the base in conflict markers and the merge view
+pub const BASE_LABEL: &str = "last generated";
+
+/// Label of the regenerated side in conflict markers and the merge view
+pub const THEIRS_LABEL: &str = "designer";
+
+/// A region both sides changed in different ways
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    /// Lines of the base, each with its line ending
+    pub base: Vec<String>,
+    /// The user's lines
+    pub ours: Vec<String>,
+    /// The regenerated lines
+    pub theirs: Vec<String>,
+    /// Zero-based line in the user's file where the region starts
+    pub ours_line: usize,
+    /// How the conflict was resolved, if it was
+    pub resolution: Option<ConflictResolution>,
+}
+
+/// Ways to resolve a conflict
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConflictResolution {
+    /// Keep the user's lines
+    Ours,
+    /// Take the regenerated lines
+    Theirs,
+    /// The user's lines followed by the regenerated ones
+    Both,
+    /// Replace the region with the given text
+    Custom(String),
+}
+
+impl MergeConflict {
+    /// Text of the region after applying `resolution`
+    pub fn resolved_text(&self, resolution: &ConflictResolution) -> String {
+        match resolution {
+            ConflictResolution::Ours => self.ours.concat(),
+            ConflictResolution::Theirs => self.theirs.concat(),
+            ConflictResolution::Both => with_final_newline(&self.ours.concat()) + &self.theirs.concat(),
+            ConflictResolution::Custom(text) => text.clone(),
+        }
+    }
+}
+
+/// A run of merged output
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeChunk {
+    /// Lines all sides agree on after merging
+    Resolved(Vec<String>),
+    /// Lines that need a decision
+    Conflict(MergeConflict),
+}
+
+/// The merged file as resolved runs and conflicts
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MergeResult {
+    /// Chunks in file order
+    pub chunks: Vec<MergeChunk>,
+}
+
+impl MergeResult {
+    /// Whether the merge produced conflicts, resolved or not
+    pub fn has_conflicts(&self) -> bool {
+        self.conflicts().next().is_some()
+    }
+
+    /// Number of conflicts
+    pub fn conflict_count(&self) -> usize {
+        self.conflicts().count()
+    }
+
+    /// Number of conflicts still waiting for a resolution
+    pub fn unresolved_count(&self) -> usize {
+        self.conflicts().filter(|conflict| conflict.resolution.is_none()).count()
+    }
+
+    /// Conflicts in file order
+    pub fn conflicts(&self) -> impl Iterator<Item = &MergeConflict> {
+        self.chunks.iter().filter_map(|chunk| match chunk {
+            MergeChunk::Conflict(conflict) => Some(conflict),
+            MergeChunk::Resolved(_) => None,
+        })
+    }
+
+    /// Resolve the conflict at `index` (in file order), returning false if there is none
+    pub fn resolve(&mut self, index: usize, resolution: ConflictResolution) -> bool {
+        let conflict = self.chunks.iter_mut().filter_map(|chunk| match chunk {
+            MergeChunk::Conflict(conflict) => Some(conflict),
+            MergeChunk::Resolved(_) => None,
+        }).nth(index);
+        match conflict {
+            Some(conflict) => {
+                conflict.resolution = Some(resolution);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The merged text, or `None` while conflicts are unresolved
+    pub fn merged_text(&self) -> Option<String> {
+        let mut text = String::new();
+        for chunk in &self.chunks {
+            match chunk {
+                MergeChunk::Resolved(lines) => text.push_str(&lines.concat()),
+                MergeChunk::Conflict(conflict) => text.push_str(&conflict.resolved_text(conflict.resolution.as_ref()?)),
+            }
+        }
+        Some(text)
+    }
+
+    /// The merged text with unresolved conflicts written as conflict markers
+    pub fn text_with_markers(&self) -> String {
+        let mut text = String::new();
+        for chunk in &self.chunks {
+            match chunk {
+                MergeChunk::Resolved(lines) => text.push_str(&lines.concat()),
+                MergeChunk::Conflict(conflict) => match &conflict.resolution {
+                    Some(resolution) => text.push_str(&conflict.resolved_text(resolution)),
+                    None => {
+                        text = with_final_newline(&text);
+                        text.push_str(&format!("<<<<<<< {}\n", OURS_LABEL));
+                        text.push_str(&with_final_newline(&conflict.ours.concat()));
+                        text.push_str(&format!("||||||| {}\n", BASE_LABEL));
+                        text.push_str(&with_final_newline(&conflict.base.concat()));
+                        text.push_str("=======\n");
+                        text.push_str(&with_final_newline(&conflict.theirs.concat()));
+                        text.push_str(&format!(">>>>>>> {}\n", THEIRS_LABEL));
+                    }
+                },
+            }
+        }
+        text
+    }
+}
+
+/// Merge the changes from `base` to `ours` with those from `base` to `theirs`
+pub fn merge(base: &str, ours: &str, theirs: &str) -> MergeResult {
+    let base: Vec<&str> = base.split_inclusive('\n').collect();
+    let ours: Vec<&str> = ours.split_inclusive('\n').collect();
+    let theirs: Vec<&str> = theirs.split_inclusive('\n').collect();
+    let ours_match = matches_in(&base, &ours);
+    let theirs_match = matches_in(&base, &theirs);
+
+    let mut result = MergeResult::default();
+    let (mut b, mut o, mut t) = (0, 0, 0);
+    loop {
+        // Lines unchanged on both sides
+        let stable_start = b;
+        while b < base.len() && ours_match[b] == Some(o) && theirs_match[b] == Some(t) {
+            b += 1;
+            o += 1;
+            t += 1;
+        }
+        push_resolved(&mut result, &base[stable_start..b]);
+        if b == base.len() && o == ours.len() && t == theirs.len() {
+            break;
+        }
+
+        // The changed region runs up to the next base line both sides kept
+        let (next_b, next_o, next_t) = (b..base.len())
+            .find_map(|i| Some((i, ours_match[i]?, theirs_match[i]?)))
+            .unwrap_or((base.len(), ours.len(), theirs.len()));
+        let (base_lines, our_lines, their_lines) = (&base[b..next_b], &ours[o..next_o], &theirs[t..next_t]);
+        if our_lines == base_lines || our_lines == their_lines {
+            push_resolved(&mut result, their_lines);
+        } else if their_lines == base_lines {
+            push_resolved(&mut result, our_lines);
+        } else {
+            result.chunks.push(MergeChunk::Conflict(MergeConflict {
+                base: base_lines.iter().map(|line| line.to_string()).collect(),
+                ours: our_lines.iter().map(|line| line.to_string()).collect(),
+                theirs: their_lines.iter().map(|line| line.to_string()).collect(),
+                ours_line: o,
+                resolution: None,
+            }));
+        }
+        (b, o, t) = (next_b, next_o, next_t);
+    }
+    result
+}
+
+/// Append lines to the result, extending the previous resolved chunk
+fn push_resolved(result: &mut MergeResult, lines: &[&str]) {
+    if lines.is_empty() {
+        return;
+    }
+    let lines = lines.iter().map(|line| line.to_string());
+    match result.chunks.last_mut() {
+        Some(MergeChunk::Resolved(resolved)) => resolved.extend(lines),
+        _ => result.chunks.push(MergeChunk::Resolved(lines.collect())),
+    }
+}
+
+fn with_final_newline(text: &str) -> String {
+    if text.is_empty() || text.ends_with('\n') {
+        text.to_string()
+    } else {
+        format!("{}\n", text)
+    }
+}
+
+/// For each line of `base`, the line of `other` it is kept as, if any
+fn matches_in(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    let mut matches = vec![None; base.len()];
+    for (base_line, other_line) in common_lines(base, other) {
+        matches[base_line] = Some(other_line);
+    }
+    matches
+}
+
+/// Line pairs of a longest common subsequence of `a` and `b`
+fn common_lines(a: &[&str], b: &[&str]) -> Vec<(usize, usize)> {
+    // Edits are usually local, so only the middle needs the full diff
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(x, y)| x == y).count();
+
+    let mut pairs: Vec<(usize, usize)> = (0..prefix).map(|i| (i, i)).collect();
+    let middle = myers(&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+    pairs.extend(middle.into_iter().map(|(i, j)| (i + prefix, j + prefix)));
+    pairs.extend((0..suffix).map(|k| (a.len() - suffix + k, b.len() - suffix + k)));
+    pairs
+}
+
+/// Myers' O(ND) diff, returning the matched line pairs
+fn myers(a: &[&str], b: &[&str]) -> Vec<(usize, usize)> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = (n + m) as usize;
+    if max == 0 {
+        return Vec::new();
+    }
+
+    // `v[offset + k]` is the furthest x reached on diagonal k; `trace[d]`
+    // keeps diagonals -d-1..=d+1 as they were before step d
+    let offset = max as isize + 1;
+    let mut v = vec![0isize; 2 * max + 3];
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    'search: for d in 0..=max as isize {
+        trace.push(v[(offset - d - 1) as usize..=(offset + d + 1) as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let at = (offset + k) as usize;
+            let mut x = if k == -d || (k != d && v[at - 1] < v[at + 1]) { v[at + 1] } else { v[at - 1] + 1 };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[at] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, saved) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let furthest = |k: isize| saved[(k + d + 1) as usize];
+        let k = x - y;
+        let previous_k = if k == -d || (k != d && furthest(k - 1) < furthest(k + 1)) { k + 1 } else { k - 1 };
+        let previous_x = furthest(previous_k);
+        let previous_y = previous_x - previous_k;
+        while x > previous_x && y > previous_y {
+            x -= 1;
+            y -= 1;
+            pairs.push((x as usize, y as usize));
+        }
+        x = previous_x;
+        y = previous_y;
+    }
+    pairs.reverse();
+    pairs
+}
+
+/// What the user chose in the merge view
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeViewAction {
+    /// Every conflict is resolved; the merged text is ready
+    Apply(String),
+    /// Keep the user's file as it is
+    KeepMine,
+}
+
+/// Window listing each conflict side by side with ways to resolve it
+pub struct MergeView {
+    /// Window title, usually the file being merged
+    pub title: String,
+    /// The merge being resolved
+    pub result: MergeResult,
+    /// Text being edited for a custom resolution, per conflict
+    custom_text: Vec<Option<String>>,
+}
+
+impl MergeView {
+    /// Create a view for `result`
+    pub fn new(title: String, result: MergeResult) -> Self {
+        let custom_text = vec![None; result.conflict_count()];
+        Self { title, result, custom_text }
+    }
+
+    /// Show the window, returning the user's decision once made
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<MergeViewAction> {
+        let mut action = None;
+        egui::Window::new(format!("⚠ Merge conflicts: {}", self.title))
+            .default_size([900.0, 500.0])
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Your edits and the designer changed the same code. {} of {} conflicts left.",
+                    self.result.unresolved_count(),
+                    self.result.conflict_count()
+                ));
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    let conflicts: Vec<MergeConflict> = self.result.conflicts().cloned().collect();
+                    for (index, conflict) in conflicts.iter().enumerate() {
+                        if let Some(resolution) = self.render_conflict(ui, index, conflict) {
+                            self.result.resolve(index, resolution);
+                        }
+                        ui.separator();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let merged = self.result.merged_text();
+                    if ui.add_enabled(merged.is_some(), egui::Button::new("✔ Apply merge")).clicked() {
+                        action = merged.map(MergeViewAction::Apply);
+                    }
+                    if ui.button("Keep my version").clicked() {
+                        action = Some(MergeViewAction::KeepMine);
+                    }
+                });
+            });
+        action
+    }
+
+    /// Render one conflict, returning a resolution the user picked
+    fn render_conflict(&mut self, ui: &mut egui::Ui, index: usize, conflict: &MergeConflict) -> Option<ConflictResolution> {
+        let mut picked = None;
+        ui.horizontal(|ui| {
+            ui.strong(format!("Conflict {} at line {}", index + 1, conflict.ours_line + 1));
+            if let Some(resolution) = &conflict.resolution {
+                ui.label(format!("✔ {:?}", resolution));
+            }
+        });
+        ui.columns(3, |columns| {
+            for (column, (label, lines)) in columns.iter_mut().zip([
+                (OURS_LABEL, &conflict.ours),
+                (BASE_LABEL, &conflict.base),
+                (THEIRS_LABEL, &conflict.theirs),
+            ]) {
+                column.label(label);
+                column.add(egui::Label::new(egui::RichText::new(lines.concat()).monospace()).wrap(false));
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Use mine").clicked() {
+                picked = Some(ConflictResolution::Ours);
+            }
+            if ui.button("Use designer").clicked() {
+                picked = Some(ConflictResolution::Theirs);
+            }
+            if ui.button("Use both").clicked() {
+                picked = Some(ConflictResolution::Both);
+            }
+            if self.custom_text[index].is_none() && ui.button("Edit…").clicked() {
+                self.custom_text[index] = Some(conflict.ours.concat());
+            }
+        });
+        if let Some(text) = &mut self.custom_text[index] {
+            ui.add(egui::TextEdit::multiline(text).code_editor().desired_width(f32::INFINITY));
+            if ui.button("Use edited text").clicked() {
+                picked = Some(ConflictResolution::Custom(text.clone()));
+            }
+        }
+        picked
+    }
+}
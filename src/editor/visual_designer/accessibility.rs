@@ -1,32 +1,520 @@
-/// Accessibility validation and reporting
-///
-/// Implements WCAG checks and accessibility reports.
+//! Accessibility audit for designed forms
+//!
+//! Checks the designer's components against WCAG success criteria: text
+//! contrast, touch target size, accessible names and roles, focus order and
+//! keyboard reachability. Thresholds come from the shared
+//! [`validation::AccessibilityValidator`]. Every issue points at the component
+//! it was found on and carries a fix when one can be applied in one click.
 
-#[derive(Default)]
-pub struct AccessibilityValidator {
-    // ...fields...
+use egui::{pos2, Color32, Pos2, Rect, Vec2, Visuals};
+
+use crate::editor::form_events::designer_component_id;
+use crate::rcl::ui::basic::form::Form;
+use crate::rcl::ui::component::Component;
+use crate::rcl::ui::properties::{PropertyType, PropertyValue};
+use crate::shared::color_utils::AccessibilityChecker;
+use crate::shared::validation::{self, ValidationResult, ValidationSeverity};
+
+use super::layout::LayoutManager;
+
+/// Font size from which text counts as large for contrast purposes
+const LARGE_TEXT_SIZE: f32 = 18.0;
+/// How far from a control a label may sit and still be taken as its caption
+const LABEL_DISTANCE: f32 = 150.0;
+
+/// WCAG check an issue failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IssueKind {
+    /// Text does not stand out enough from its background
+    Contrast,
+    /// Control is too small to hit reliably
+    TouchTarget,
+    /// Control has no accessible name
+    MissingLabel,
+    /// Control does not expose its role
+    MissingRole,
+    /// Tab order differs from the reading order
+    FocusOrder,
+    /// Control cannot be reached or seen with the keyboard
+    KeyboardAccess,
 }
 
-impl AccessibilityValidator {
-    pub fn new() -> Self {
-        Self {
-            // ...
+impl IssueKind {
+    /// WCAG success criterion the check implements
+    pub fn criterion(&self) -> &'static str {
+        match self {
+            IssueKind::Contrast => "1.4.3 Contrast (Minimum)",
+            IssueKind::TouchTarget => "2.5.5 Target Size",
+            IssueKind::MissingLabel | IssueKind::MissingRole => "4.1.2 Name, Role, Value",
+            IssueKind::FocusOrder => "2.4.3 Focus Order",
+            IssueKind::KeyboardAccess => "2.1.1 Keyboard",
         }
     }
+}
+
+/// Change that resolves an issue
+#[derive(Clone, Debug, PartialEq)]
+pub enum AccessibilityFix {
+    /// Set a component property
+    SetProperty { component: usize, name: String, value: String },
+    /// Resize a component on the canvas
+    Resize { component: usize, size: Vec2 },
+    /// Move a component on the canvas
+    Move { component: usize, position: Pos2 },
+    /// Number the components' `tab_index` in this order, starting at 1
+    TabOrder(Vec<usize>),
+}
 
-    pub fn validate(&self) -> AccessibilityReport {
-        // Perform WCAG and accessibility checks
-        AccessibilityReport::default()
+impl AccessibilityFix {
+    /// Short description for the fix button
+    pub fn description(&self) -> String {
+        match self {
+            AccessibilityFix::SetProperty { name, value, .. } => format!("Set {} to \"{}\"", name, value),
+            AccessibilityFix::Resize { size, .. } => format!("Resize to {}×{}", size.x, size.y),
+            AccessibilityFix::Move { position, .. } => format!("Move to ({}, {})", position.x, position.y),
+            AccessibilityFix::TabOrder(order) => format!("Number the tab order of {} controls", order.len()),
+        }
     }
 
-    pub fn report(&self) -> AccessibilityReport {
-        // Generate accessibility report
-        AccessibilityReport::default()
+    /// Apply the fix, returning whether every change was accepted
+    pub fn apply(&self, components: &mut [Box<dyn Component>], layout: &mut LayoutManager) -> bool {
+        match self {
+            AccessibilityFix::SetProperty { component, name, value } => {
+                components.get_mut(*component).is_some_and(|target| target.set_property(name, value))
+            }
+            AccessibilityFix::Resize { component, size } => {
+                layout.sizes.insert(*component, *size);
+                true
+            }
+            AccessibilityFix::Move { component, position } => {
+                layout.positions.insert(*component, *position);
+                true
+            }
+            AccessibilityFix::TabOrder(order) => order.iter().enumerate().fold(true, |applied, (position, index)| {
+                let set = components.get_mut(*index).is_some_and(|target| target.set_property("tab_index", &(position + 1).to_string()));
+                applied && set
+            }),
+        }
     }
 }
 
+/// A failed check on one component
+#[derive(Clone, Debug)]
+pub struct AccessibilityIssue {
+    /// Index of the offending component in the designer
+    pub component: usize,
+    /// Check that failed
+    pub kind: IssueKind,
+    /// How serious the failure is
+    pub severity: ValidationSeverity,
+    /// What is wrong, naming the component
+    pub message: String,
+    /// One-click fix, when one exists
+    pub fix: Option<AccessibilityFix>,
+}
+
+/// Outcome of an audit
 #[derive(Default, Clone, Debug)]
 pub struct AccessibilityReport {
-    pub issues: Vec<String>,
+    /// Failed checks, in component order
+    pub issues: Vec<AccessibilityIssue>,
+    /// Percentage of checks that passed
     pub score: f32,
+    /// Number of checks performed
+    pub checks: usize,
+}
+
+impl AccessibilityReport {
+    /// Issues found on one component
+    pub fn issues_for(&self, component: usize) -> impl Iterator<Item = &AccessibilityIssue> {
+        self.issues.iter().filter(move |issue| issue.component == component)
+    }
+}
+
+/// What the user asked for in the report panel
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditAction {
+    /// Select the component at this index
+    Select(usize),
+    /// Apply the fix of the issue at this index
+    Fix(usize),
+}
+
+/// A component as seen by the audit
+struct Audited<'a> {
+    index: usize,
+    component: &'a dyn Component,
+    id: String,
+    rect: Rect,
+    interactive: bool,
+}
+
+impl Audited<'_> {
+    fn property(&self, name: &str) -> Option<String> {
+        self.component.get_property(name).filter(|value| !value.trim().is_empty())
+    }
+
+    fn supports(&self, name: &str) -> bool {
+        self.component.get_property_names().iter().any(|property| property == name)
+    }
+
+    fn color(&self, name: &str) -> Option<Color32> {
+        match PropertyValue::from_string(&self.property(name)?, &PropertyType::Color) {
+            Ok(PropertyValue::Color(color)) if color.a() > 0 => Some(color),
+            _ => None,
+        }
+    }
+
+    /// Text the component shows on screen
+    fn visible_text(&self) -> Option<String> {
+        ["label", "text"].iter().find_map(|name| self.property(name))
+    }
+
+    fn tab_index(&self) -> Option<i64> {
+        self.property("tab_index")?.trim().parse().ok()
+    }
+
+    fn enabled(&self) -> bool {
+        self.property("enabled").is_none_or(|enabled| enabled != "false")
+    }
+}
+
+/// Audits designed forms against WCAG
+pub struct AccessibilityValidator {
+    /// Contrast and touch target thresholds
+    pub rules: validation::AccessibilityValidator,
+    report: AccessibilityReport,
+}
+
+impl Default for AccessibilityValidator {
+    fn default() -> Self {
+        Self {
+            rules: validation::AccessibilityValidator::default(),
+            report: AccessibilityReport { score: 100.0, ..Default::default() },
+        }
+    }
+}
+
+impl AccessibilityValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Audit the components of `form` as the designer lays them out
+    ///
+    /// Text without its own color is checked in the `visuals` text color.
+    /// The report is kept for [`Self::report`] and the report panel.
+    pub fn validate(
+        &mut self,
+        components: &[Box<dyn Component>],
+        layout: &LayoutManager,
+        form: &Form,
+        visuals: &Visuals,
+    ) -> &AccessibilityReport {
+        let audited: Vec<Audited> = components.iter().enumerate()
+            .filter(|(_, component)| component.get_property("visible").is_none_or(|visible| visible != "false"))
+            .map(|(index, component)| Audited {
+                index,
+                component: component.as_ref(),
+                id: designer_component_id(component.name(), index),
+                rect: Rect::from_min_size(layout.position_of(index), layout.size_of(index, component.name())),
+                interactive: touch_target_type(component.name()).is_some(),
+            })
+            .collect();
+
+        let mut audit = Audit { issues: Vec::new(), checks: 0 };
+        for item in &audited {
+            self.check_contrast(&mut audit, item, &audited, form.background_color, visuals.text_color());
+            self.check_touch_target(&mut audit, item);
+            if item.interactive {
+                check_name_and_role(&mut audit, item, &audited);
+                check_keyboard_access(&mut audit, item, &audited, Rect::from_min_size(Pos2::ZERO, form.size));
+            }
+        }
+        check_focus_order(&mut audit, &audited);
+
+        audit.issues.sort_by_key(|issue| issue.component);
+        let passed = audit.checks.saturating_sub(audit.issues.len());
+        self.report = AccessibilityReport {
+            score: if audit.checks == 0 { 100.0 } else { passed as f32 * 100.0 / audit.checks as f32 },
+            checks: audit.checks,
+            issues: audit.issues,
+        };
+        &self.report
+    }
+
+    /// Report of the last audit
+    pub fn report(&self) -> &AccessibilityReport {
+        &self.report
+    }
+
+    /// Apply the fix of an issue from the last report
+    ///
+    /// Returns false when the issue has no fix or the component rejected it.
+    /// Run the audit again afterwards to refresh the report.
+    pub fn apply_fix(&mut self, issue: usize, components: &mut [Box<dyn Component>], layout: &mut LayoutManager) -> bool {
+        self.report.issues.get(issue)
+            .and_then(|issue| issue.fix.as_ref())
+            .is_some_and(|fix| fix.apply(components, layout))
+    }
+
+    /// Render the last report with links to components and fix buttons
+    pub fn render_report(&self, ui: &mut egui::Ui) -> Option<AuditAction> {
+        let mut action = None;
+        ui.label(format!("Score: {:.0}% of {} checks passed", self.report.score, self.report.checks));
+        if self.report.issues.is_empty() {
+            ui.label("✅ No accessibility issues found");
+            return None;
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (index, issue) in self.report.issues.iter().enumerate() {
+                ui.group(|ui| {
+                    let icon = match issue.severity {
+                        ValidationSeverity::Critical | ValidationSeverity::Error => "❌",
+                        ValidationSeverity::Warning => "⚠️",
+                        ValidationSeverity::Info => "ℹ️",
+                    };
+                    ui.horizontal(|ui| {
+                        ui.label(icon);
+                        ui.strong(issue.kind.criterion());
+                    });
+                    ui.label(&issue.message);
+                    ui.horizontal(|ui| {
+                        if ui.link("Select component").clicked() {
+                            action = Some(AuditAction::Select(issue.component));
+                        }
+                        if let Some(fix) = &issue.fix {
+                            if ui.button("🔧 Fix").on_hover_text(fix.description()).clicked() {
+                                action = Some(AuditAction::Fix(index));
+                            }
+                        }
+                    });
+                });
+            }
+        });
+        action
+    }
+
+    fn check_contrast(&self, audit: &mut Audit, item: &Audited, audited: &[Audited], form_background: Color32, text_color: Color32) {
+        if item.visible_text().is_none() && item.property("value").is_none() {
+            return;
+        }
+        let foreground = item.color("color").unwrap_or(text_color);
+        let background = background_behind(item, audited).unwrap_or(form_background);
+        let ratio = AccessibilityChecker::default().contrast_ratio(foreground, background);
+        let large = item.property("font_size").and_then(|size| size.parse::<f32>().ok()).is_some_and(|size| size >= LARGE_TEXT_SIZE);
+        let text_type = if large { "large_text" } else { "normal_text" };
+
+        let fix = item.supports("color").then(|| {
+            let checker = AccessibilityChecker::default();
+            let black = checker.contrast_ratio(Color32::BLACK, background);
+            let white = checker.contrast_ratio(Color32::WHITE, background);
+            let value = if black >= white { "#000000" } else { "#ffffff" };
+            AccessibilityFix::SetProperty { component: item.index, name: "color".to_string(), value: value.to_string() }
+        });
+        audit.check(item, IssueKind::Contrast, self.rules.validate_contrast(ratio, text_type), fix);
+    }
+
+    fn check_touch_target(&self, audit: &mut Audit, item: &Audited) {
+        let Some(target) = touch_target_type(item.component.name()) else {
+            return;
+        };
+        let size = item.rect.size();
+        let fix = self.rules.touch_target_sizes.get(target).map(|min| AccessibilityFix::Resize {
+            component: item.index,
+            size: size.max(Vec2::splat(*min)),
+        });
+        audit.check(item, IssueKind::TouchTarget, self.rules.validate_touch_target(target, size.x, size.y), fix);
+    }
+}
+
+/// Issues collected during one audit
+struct Audit {
+    issues: Vec<AccessibilityIssue>,
+    checks: usize,
+}
+
+impl Audit {
+    /// Count a check and record an issue when it failed
+    fn check(&mut self, item: &Audited, kind: IssueKind, result: ValidationResult, fix: Option<AccessibilityFix>) {
+        self.checks += 1;
+        if let Some(error) = result.errors.into_iter().next() {
+            self.fail(item.index, kind, error.severity, format!("{}: {}", item.id, error.message), fix);
+        }
+    }
+
+    fn pass(&mut self) {
+        self.checks += 1;
+    }
+
+    fn fail(&mut self, component: usize, kind: IssueKind, severity: ValidationSeverity, message: String, fix: Option<AccessibilityFix>) {
+        self.issues.push(AccessibilityIssue { component, kind, severity, message, fix });
+    }
+}
+
+/// Key of a component type in the touch target rules
+fn touch_target_type(component_type: &str) -> Option<&'static str> {
+    match component_type {
+        "Button" => Some("button"),
+        "Link" | "Hyperlink" => Some("link"),
+        "TextBox" | "Checkbox" | "RadioButton" | "Slider" | "Dropdown" => Some("input"),
+        _ => None,
+    }
+}
+
+/// ARIA role a component type should expose
+fn expected_role(component_type: &str) -> Option<&'static str> {
+    match component_type {
+        "Button" => Some("button"),
+        "TextBox" => Some("textbox"),
+        "Checkbox" => Some("checkbox"),
+        "Slider" => Some("slider"),
+        _ => None,
+    }
+}
+
+/// Background drawn behind a component: its own, or that of the topmost
+/// component beneath it that covers it
+fn background_behind(item: &Audited, audited: &[Audited]) -> Option<Color32> {
+    item.color("background_color").or_else(|| {
+        audited.iter()
+            .rev()
+            .filter(|other| other.index < item.index && other.rect.contains_rect(item.rect))
+            .find_map(|other| other.color("background_color"))
+    })
+}
+
+/// Text of the label closest to the left of or above a control
+fn nearby_label(item: &Audited, audited: &[Audited]) -> Option<String> {
+    audited.iter()
+        .filter(|other| other.component.name() == "Label")
+        .filter_map(|label| {
+            let rect = label.rect;
+            let beside = rect.max.x <= item.rect.min.x + 1.0
+                && (rect.center().y - item.rect.center().y).abs() <= item.rect.height() / 2.0;
+            let above = rect.max.y <= item.rect.min.y + 1.0 && rect.min.x < item.rect.max.x && item.rect.min.x < rect.max.x;
+            let distance = if beside {
+                item.rect.min.x - rect.max.x
+            } else if above {
+                item.rect.min.y - rect.max.y
+            } else {
+                return None;
+            };
+            (distance <= LABEL_DISTANCE).then_some((distance, label.visible_text()?))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, text)| text)
+}
+
+/// Controls need an accessible name and, where they expose one, a role
+fn check_name_and_role(audit: &mut Audit, item: &Audited, audited: &[Audited]) {
+    audit.pass();
+    if item.property("aria_label").is_none() && item.visible_text().is_none() {
+        let fix = item.supports("aria_label").then(|| AccessibilityFix::SetProperty {
+            component: item.index,
+            name: "aria_label".to_string(),
+            value: nearby_label(item, audited).unwrap_or_else(|| item.component.name().to_string()),
+        });
+        audit.fail(
+            item.index,
+            IssueKind::MissingLabel,
+            ValidationSeverity::Error,
+            format!("{} has no accessible name; screen readers cannot announce it", item.id),
+            fix,
+        );
+    }
+
+    if let Some(role) = expected_role(item.component.name()).filter(|_| item.supports("role")) {
+        audit.pass();
+        if item.property("role").is_none() {
+            audit.fail(
+                item.index,
+                IssueKind::MissingRole,
+                ValidationSeverity::Warning,
+                format!("{} does not expose its role", item.id),
+                Some(AccessibilityFix::SetProperty { component: item.index, name: "role".to_string(), value: role.to_string() }),
+            );
+        }
+    }
+}
+
+/// Controls must be in the tab order and visible when focused
+fn check_keyboard_access(audit: &mut Audit, item: &Audited, audited: &[Audited], form_rect: Rect) {
+    if !item.enabled() {
+        return;
+    }
+    audit.pass();
+    let problem = if item.tab_index().is_some_and(|index| index < 0) {
+        Some(("is removed from the tab order", Some(AccessibilityFix::SetProperty {
+            component: item.index,
+            name: "tab_index".to_string(),
+            value: "0".to_string(),
+        })))
+    } else if !form_rect.intersects(item.rect) {
+        let max = (form_rect.max - item.rect.size()).max(Pos2::ZERO);
+        let position = pos2(item.rect.min.x.clamp(0.0, max.x), item.rect.min.y.clamp(0.0, max.y));
+        Some(("lies outside the form, so it cannot be seen when focused", Some(AccessibilityFix::Move { component: item.index, position })))
+    } else if audited.iter().any(|other| other.index > item.index && other.rect.contains_rect(item.rect)) {
+        Some(("is hidden behind another component, so focus on it cannot be seen", None))
+    } else {
+        None
+    };
+    if let Some((problem, fix)) = problem {
+        audit.fail(item.index, IssueKind::KeyboardAccess, ValidationSeverity::Error, format!("{} {}", item.id, problem), fix);
+    }
+}
+
+/// Tab order should follow the reading order, rows top to bottom and left to
+/// right within a row
+fn check_focus_order(audit: &mut Audit, audited: &[Audited]) {
+    let focusable: Vec<&Audited> = audited.iter()
+        .filter(|item| item.interactive && item.enabled() && item.tab_index().is_none_or(|index| index >= 0))
+        .collect();
+    if focusable.len() < 2 {
+        return;
+    }
+    audit.pass();
+
+    // Positive tab indexes come first, then the rest in render order
+    let mut focus_order = focusable.clone();
+    focus_order.sort_by_key(|item| match item.tab_index() {
+        Some(index) if index > 0 => (0, index, item.index),
+        _ => (1, 0, item.index),
+    });
+
+    let mut by_top = focusable.clone();
+    by_top.sort_by(|a, b| a.rect.min.y.total_cmp(&b.rect.min.y));
+    let mut rows: Vec<Vec<&Audited>> = Vec::new();
+    for item in by_top {
+        match rows.last_mut() {
+            Some(row) if same_row(row[0].rect, item.rect) => row.push(item),
+            _ => rows.push(vec![item]),
+        }
+    }
+    let reading_order: Vec<&Audited> = rows.into_iter()
+        .flat_map(|mut row| {
+            row.sort_by(|a, b| a.rect.min.x.total_cmp(&b.rect.min.x));
+            row
+        })
+        .collect();
+
+    let mismatch = focus_order.iter().zip(&reading_order).find(|(focused, read)| focused.index != read.index);
+    if let Some((focused, read)) = mismatch {
+        let fix = focusable.iter().all(|item| item.supports("tab_index"))
+            .then(|| AccessibilityFix::TabOrder(reading_order.iter().map(|item| item.index).collect()));
+        audit.fail(
+            read.index,
+            IssueKind::FocusOrder,
+            ValidationSeverity::Warning,
+            format!("Tab moves to {} before {}, which comes first on screen", focused.id, read.id),
+            fix,
+        );
+    }
+}
+
+/// Whether two controls overlap vertically by at least half the smaller height
+fn same_row(a: Rect, b: Rect) -> bool {
+    let overlap = a.max.y.min(b.max.y) - a.min.y.max(b.min.y);
+    overlap >= a.height().min(b.height()) / 2.0
 }
@@ -6,6 +6,9 @@
 use egui::*;
 use std::collections::HashMap;
 
+use crate::editor::layout_engine::{self, GridPlacement, LayoutNode};
+use crate::editor::scene_store;
+
 /// Advanced layout types supported by the designer
 #[derive(Debug, Clone, PartialEq)]
 pub enum LayoutType {
@@ -17,6 +20,7 @@ pub enum LayoutType {
         justify_content: JustifyContent,
         align_items: AlignItems,
         wrap: FlexWrap,
+        gap: f32,
     },
     /// Grid layout with rows and columns
     Grid {
@@ -261,8 +265,8 @@ impl AdvancedLayoutManager {
                 LayoutType::Absolute => {
                     // No automatic layout calculation needed
                 }
-                LayoutType::Flexbox { direction, justify_content, align_items, .. } => {
-                    self.calculate_flexbox_layout(container_id, direction, justify_content, align_items);
+                LayoutType::Flexbox { direction, justify_content, align_items, wrap, gap } => {
+                    self.calculate_flexbox_layout(container_id, direction, justify_content, align_items, wrap, *gap);
                 }
                 LayoutType::Grid { columns, rows, column_gap, row_gap } => {
                     self.calculate_grid_layout(container_id, *columns, *rows, *column_gap, *row_gap);
@@ -280,115 +284,91 @@ impl AdvancedLayoutManager {
         container_id: usize,
         direction: &FlexDirection,
         justify_content: &JustifyContent,
-        _align_items: &AlignItems,
+        align_items: &AlignItems,
+        wrap: &FlexWrap,
+        gap: f32,
     ) {
-        let container = match self.containers.get(&container_id) {
-            Some(c) => c.clone(),
-            None => return,
+        let flex = scene_store::FlexLayout {
+            direction: match direction {
+                FlexDirection::Row => scene_store::FlexDirection::Row,
+                FlexDirection::RowReverse => scene_store::FlexDirection::RowReverse,
+                FlexDirection::Column => scene_store::FlexDirection::Column,
+                FlexDirection::ColumnReverse => scene_store::FlexDirection::ColumnReverse,
+            },
+            justify_content: match justify_content {
+                JustifyContent::FlexStart => scene_store::JustifyContent::FlexStart,
+                JustifyContent::FlexEnd => scene_store::JustifyContent::FlexEnd,
+                JustifyContent::Center => scene_store::JustifyContent::Center,
+                JustifyContent::SpaceBetween => scene_store::JustifyContent::SpaceBetween,
+                JustifyContent::SpaceAround => scene_store::JustifyContent::SpaceAround,
+                JustifyContent::SpaceEvenly => scene_store::JustifyContent::SpaceEvenly,
+            },
+            align_items: match align_items {
+                AlignItems::FlexStart => scene_store::AlignItems::FlexStart,
+                AlignItems::FlexEnd => scene_store::AlignItems::FlexEnd,
+                AlignItems::Center => scene_store::AlignItems::Center,
+                AlignItems::Baseline => scene_store::AlignItems::Baseline,
+                AlignItems::Stretch => scene_store::AlignItems::Stretch,
+            },
+            wrap: match wrap {
+                FlexWrap::NoWrap => scene_store::FlexWrap::NoWrap,
+                FlexWrap::Wrap => scene_store::FlexWrap::Wrap,
+                FlexWrap::WrapReverse => scene_store::FlexWrap::WrapReverse,
+            },
+            gap,
         };
-
         let is_row = matches!(direction, FlexDirection::Row | FlexDirection::RowReverse);
-        let content_area = container.bounds;
-        
-        // Calculate available space
-        let available_size = if is_row {
-            content_area.width()
-        } else {
-            content_area.height()
-        };
-
-        // Determine component sizes based on flex properties
-        let mut total_flex_grow = 0.0;
-        let mut fixed_size = 0.0;
-
-        for &child_id in &container.children {
-            if let Some(constraints) = self.constraints.get(&child_id) {
-                total_flex_grow += constraints.flex_grow;
-                if let Some(basis) = constraints.flex_basis {
-                    fixed_size += basis;
-                } else if let Some(preferred) = constraints.preferred_size {
-                    fixed_size += if is_row { preferred.x } else { preferred.y };
-                } else {
-                    fixed_size += if is_row { 100.0 } else { 30.0 }; // Default size
-                }
-            } else {
-                fixed_size += if is_row { 100.0 } else { 30.0 }; // Default size
-            }
-        }
-
-        // Calculate positions based on justify_content
-        let remaining_space = available_size - fixed_size;
-        let flex_unit = if total_flex_grow > 0.0 {
-            remaining_space / total_flex_grow
-        } else {
-            0.0
-        };
+        self.solve_container(container_id, scene_store::LayoutType::Flex(flex), is_row);
+    }
 
-        let mut current_pos = match justify_content {
-            JustifyContent::FlexStart => 0.0,
-            JustifyContent::FlexEnd => remaining_space.max(0.0),
-            JustifyContent::Center => (remaining_space / 2.0).max(0.0),
-            _ => 0.0,
+    /// Calculate grid layout with equal `fr` tracks
+    fn calculate_grid_layout(&mut self, container_id: usize, columns: usize, rows: usize, column_gap: f32, row_gap: f32) {
+        let grid = scene_store::GridLayout {
+            columns: vec![scene_store::GridTrack::Fr(1.0); columns],
+            rows: vec![scene_store::GridTrack::Fr(1.0); rows],
+            gap: scene_store::GridGap { row: row_gap, column: column_gap },
+            auto_flow: scene_store::GridAutoFlow::Row,
         };
-
-        // Position children
-        for &child_id in &container.children {
-            let constraints = self.constraints.get(&child_id).cloned()
-                .unwrap_or_default();
-                
-            let child_size = if let Some(basis) = constraints.flex_basis {
-                basis + (constraints.flex_grow * flex_unit)
-            } else if let Some(preferred) = constraints.preferred_size {
-                let base_size = if is_row { preferred.x } else { preferred.y };
-                base_size + (constraints.flex_grow * flex_unit)
-            } else {
-                let default_size = if is_row { 100.0 } else { 30.0 };
-                default_size + (constraints.flex_grow * flex_unit)
-            };
-
-            let child_pos = if is_row {
-                Pos2::new(content_area.min.x + current_pos, content_area.min.y)
-            } else {
-                Pos2::new(content_area.min.x, content_area.min.y + current_pos)
-            };
-
-            let child_full_size = if is_row {
-                Vec2::new(child_size, content_area.height())
-            } else {
-                Vec2::new(content_area.width(), child_size)
-            };
-
-            self.calculated_positions.insert(child_id, child_pos);
-            self.calculated_sizes.insert(child_id, child_full_size);
-
-            current_pos += child_size;
-        }
+        self.solve_container(container_id, scene_store::LayoutType::Grid(grid), true);
     }
 
-    /// Calculate grid layout
-    fn calculate_grid_layout(&mut self, container_id: usize, columns: usize, rows: usize, column_gap: f32, row_gap: f32) {
+    /// Solve a container's children with the layout engine and store the results
+    fn solve_container(&mut self, container_id: usize, layout: scene_store::LayoutType, is_row: bool) {
         let container = match self.containers.get(&container_id) {
             Some(c) => c.clone(),
             None => return,
         };
 
-        let content_area = container.bounds;
-        let cell_width = (content_area.width() - (column_gap * (columns - 1) as f32)) / columns as f32;
-        let cell_height = (content_area.height() - (row_gap * (rows - 1) as f32)) / rows as f32;
-
-        for (index, &child_id) in container.children.iter().enumerate() {
-            let row = index / columns;
-            let col = index % columns;
-
-            if row >= rows {
-                break; // Component doesn't fit in grid
+        let to_size = |size: Vec2| scene_store::Size { width: size.x, height: size.y };
+        let mut root = LayoutNode::new(None, container.bounds.size()).with_layout(layout);
+        for &child_id in &container.children {
+            let constraints = self.constraints.get(&child_id).cloned().unwrap_or_default();
+            let mut size = constraints.preferred_size.unwrap_or(Vec2::new(100.0, 30.0));
+            if let Some(basis) = constraints.flex_basis {
+                if is_row { size.x = basis } else { size.y = basis }
             }
 
-            let x = content_area.min.x + (col as f32 * (cell_width + column_gap));
-            let y = content_area.min.y + (row as f32 * (cell_height + row_gap));
+            let mut child = LayoutNode::new(Some(child_id), size);
+            child.constraints.min_size = constraints.min_size.map(to_size);
+            child.constraints.max_size = constraints.max_size.map(to_size);
+            child.flex_grow = constraints.flex_grow;
+            child.flex_shrink = constraints.flex_shrink;
+            if let Some(area) = &constraints.grid_area {
+                child.grid = GridPlacement {
+                    row: Some(area.start_row),
+                    column: Some(area.start_column),
+                    row_span: area.end_row.saturating_sub(area.start_row).max(1),
+                    column_span: area.end_column.saturating_sub(area.start_column).max(1),
+                };
+            }
+            root = root.with_child(child);
+        }
 
-            self.calculated_positions.insert(child_id, Pos2::new(x, y));
-            self.calculated_sizes.insert(child_id, Vec2::new(cell_width, cell_height));
+        for (key, rect) in layout_engine::solve(&root, container.bounds) {
+            if let Some(child_id) = key {
+                self.calculated_positions.insert(child_id, rect.min);
+                self.calculated_sizes.insert(child_id, rect.size());
+            }
         }
     }
 
@@ -515,6 +495,7 @@ impl AdvancedLayoutManager {
                 justify_content: JustifyContent::FlexStart,
                 align_items: AlignItems::FlexStart,
                 wrap: FlexWrap::NoWrap,
+                gap: 8.0,
             },
             bounds,
         )
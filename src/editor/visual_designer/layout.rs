@@ -16,6 +16,19 @@ pub struct LayoutManager {
 
 impl LayoutManager {
     pub fn get_or_init_position(&mut self, idx: usize) -> egui::Pos2 {
+        let position = self.position_of(idx);
+        self.positions.insert(idx, position);
+        position
+    }
+
+    pub fn get_or_init_size(&mut self, idx: usize, component_name: &str) -> egui::Vec2 {
+        let size = self.size_of(idx, component_name);
+        self.sizes.insert(idx, size);
+        size
+    }
+
+    /// Position of a component, or where it would be placed by default
+    pub fn position_of(&self, idx: usize) -> egui::Pos2 {
         self.positions.get(&idx).copied().unwrap_or_else(|| {
             let columns = 3;
             let col = idx % columns;
@@ -24,28 +37,23 @@ impl LayoutManager {
             let spacing_y = 60.0;
             let start_x = 50.0;
             let start_y = 50.0;
-            let default_pos = egui::pos2(
+            egui::pos2(
                 start_x + (col as f32 * spacing_x),
                 start_y + (row as f32 * spacing_y)
-            );
-            self.positions.insert(idx, default_pos);
-            default_pos
+            )
         })
     }
 
-    pub fn get_or_init_size(&mut self, idx: usize, component_name: &str) -> egui::Vec2 {
-        self.sizes.get(&idx).copied().unwrap_or_else(|| {
-            let default_size = match component_name {
-                "Button" => egui::vec2(100.0, 32.0),
-                "Label" => egui::vec2(80.0, 24.0),
-                "TextBox" => egui::vec2(140.0, 28.0),
-                "Checkbox" => egui::vec2(120.0, 24.0),
-                "Slider" => egui::vec2(140.0, 24.0),
-                "Dropdown" => egui::vec2(120.0, 28.0),
-                _ => egui::vec2(100.0, 32.0),
-            };
-            self.sizes.insert(idx, default_size);
-            default_size
+    /// Size of a component, or its default size for the component type
+    pub fn size_of(&self, idx: usize, component_name: &str) -> egui::Vec2 {
+        self.sizes.get(&idx).copied().unwrap_or(match component_name {
+            "Button" => egui::vec2(100.0, 32.0),
+            "Label" => egui::vec2(80.0, 24.0),
+            "TextBox" => egui::vec2(140.0, 28.0),
+            "Checkbox" => egui::vec2(120.0, 24.0),
+            "Slider" => egui::vec2(140.0, 24.0),
+            "Dropdown" => egui::vec2(120.0, 28.0),
+            _ => egui::vec2(100.0, 32.0),
         })
     }
     
@@ -1048,6 +1048,9 @@ impl VisualDesigner {
             ComponentType::Slider => {
                 Box::new(crate::rcl::ui::basic::slider::Slider::new(0.0, 0.0, 100.0))
             }
+            ComponentType::Custom(12) => {
+                Box::new(crate::rcl::network::websocket::WebSocketComponent::default())
+            }
             _ => {
                 // Default to button for unsupported types
                 Box::new(crate::rcl::ui::basic::button::Button::new("New Component".to_string()))
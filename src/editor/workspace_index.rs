@@ -0,0 +1,727 @@
+//! Persistent Workspace Index
+//!
+//! Building blocks for the [`AdvancedSearchEngine`](super::advanced_search::AdvancedSearchEngine)
+//! index: trigram and word tokenization for text search, syn-based symbol and
+//! reference extraction for Rust sources, and a compressed snapshot stored in
+//! the project's `.ide-rs` directory so the index survives restarts.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use proc_macro2::{LineColumn, Span, TokenStream, TokenTree};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+use super::advanced_search::{
+    DefinitionType, FileType, ReferenceType, Symbol, SymbolDefinition, SymbolKind, SymbolLocation,
+    SymbolReference, TextPosition, TextSpan, Visibility,
+};
+
+/// Per-project directory for IDE state
+pub const INDEX_DIR: &str = ".ide-rs";
+
+/// Snapshot file name inside [`INDEX_DIR`]
+const INDEX_FILE: &str = "search-index.json.gz";
+
+/// Bumped whenever the snapshot layout changes; older snapshots are discarded
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Files larger than this are not indexed
+pub const MAX_INDEXED_FILE_SIZE: u64 = 2 * 1024 * 1024;
+
+/// Directories never indexed, in addition to hidden ones
+const IGNORED_DIRS: &[&str] = &["target", "node_modules"];
+
+// ---------------------------------------------------------------------------
+// Tokenization
+// ---------------------------------------------------------------------------
+
+/// Lowercased three-character windows of `text`, skipping pure whitespace
+pub fn trigrams(text: &str) -> HashSet<String> {
+    let chars: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    chars
+        .windows(3)
+        .filter(|window| !window.iter().all(|c| c.is_whitespace()))
+        .map(|window| window.iter().collect())
+        .collect()
+}
+
+/// Lowercased identifier-like words of `text`
+pub fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| word.chars().count() >= 2)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Similarity of two strings from 0 to 1, based on edit distance
+pub fn similarity(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    1.0 - previous[b.len()] as f32 / longest as f32
+}
+
+// ---------------------------------------------------------------------------
+// Workspace files
+// ---------------------------------------------------------------------------
+
+/// Classify a file by extension
+pub fn file_type_of(path: &Path) -> FileType {
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default().to_ascii_lowercase();
+    match extension.as_str() {
+        "rs" => FileType::Rust,
+        "js" | "mjs" | "cjs" | "jsx" => FileType::JavaScript,
+        "ts" | "tsx" => FileType::TypeScript,
+        "html" | "htm" => FileType::HTML,
+        "css" => FileType::CSS,
+        "json" => FileType::JSON,
+        "toml" => FileType::TOML,
+        "md" | "markdown" => FileType::Markdown,
+        "txt" | "yml" | "yaml" | "rcl" | "sh" | "py" | "c" | "h" | "cpp" | "hpp" | "lock" => FileType::Text,
+        "png" | "jpg" | "jpeg" | "gif" | "ico" | "woff" | "woff2" | "ttf" | "zip" | "gz" | "exe" | "so" | "dll" => {
+            FileType::Binary
+        }
+        _ => FileType::Unknown,
+    }
+}
+
+/// Whether `path` belongs in the index of the workspace at `root`
+pub fn is_indexable(root: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else { return false };
+    let mut components = relative.components().peekable();
+    while let Some(component) = components.next() {
+        let name = component.as_os_str().to_string_lossy();
+        let is_dir = components.peek().is_some();
+        if is_dir && (name.starts_with('.') || IGNORED_DIRS.contains(&name.as_ref())) {
+            return false;
+        }
+    }
+    !matches!(file_type_of(path), FileType::Binary | FileType::Unknown)
+}
+
+/// Indexable files under `dir`, sorted
+pub fn workspace_files(root: &Path, dir: &Path) -> Vec<PathBuf> {
+    fn walk(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else { return };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            match entry.file_type() {
+                Ok(kind) if kind.is_dir() => {
+                    let name = entry.file_name();
+                    let name = name.to_string_lossy();
+                    if !name.starts_with('.') && !IGNORED_DIRS.contains(&name.as_ref()) {
+                        walk(root, &path, files);
+                    }
+                }
+                Ok(kind) if kind.is_file() && is_indexable(root, &path) => files.push(path),
+                _ => {}
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    walk(root, dir, &mut files);
+    files.sort();
+    files
+}
+
+/// Rust module path of a source file, e.g. `crate::editor::search`
+pub fn module_path_for(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path).with_extension("");
+    let mut segments: Vec<String> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    match segments.iter().position(|segment| segment == "src") {
+        Some(src) => {
+            segments.drain(..=src);
+            if matches!(segments.last().map(String::as_str), Some("mod" | "lib" | "main")) {
+                segments.pop();
+            }
+            std::iter::once("crate".to_string()).chain(segments).collect::<Vec<_>>().join("::")
+        }
+        // Tests, examples and benches are crates of their own
+        None => segments.last().cloned().unwrap_or_default(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Rust symbols
+// ---------------------------------------------------------------------------
+
+/// Symbols defined and referenced in one Rust file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileSymbols {
+    pub definitions: Vec<SymbolDefinition>,
+    pub references: Vec<SymbolReference>,
+    /// Which named items each function or type refers to
+    pub edges: Vec<SymbolEdge>,
+}
+
+/// Reference from the item containing it to a symbol name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolEdge {
+    /// Qualified name of the containing item
+    pub from: String,
+    /// Referenced symbol name
+    pub to: String,
+    pub reference_type: ReferenceType,
+}
+
+/// Parse a Rust file and collect its definitions, references and reference edges
+pub fn extract_rust_symbols(path: &Path, text: &str, module: &str) -> Result<FileSymbols, String> {
+    let bom = if text.starts_with('\u{feff}') { '\u{feff}'.len_utf8() } else { 0 };
+    let file = syn::parse_file(&text[bom..]).map_err(|e| {
+        let at = e.span().start();
+        format!("{}:{}:{}: {}", path.display(), at.line, at.column + 1, e)
+    })?;
+
+    let mut line_starts = vec![0];
+    line_starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+    let mut collector = SymbolCollector {
+        path,
+        text,
+        bom,
+        line_starts,
+        scopes: module.split("::").filter(|s| !s.is_empty()).map(str::to_string).collect(),
+        containers: Vec::new(),
+        symbols: FileSymbols::default(),
+    };
+    collector.visit_file(&file);
+
+    let mut seen = HashSet::new();
+    collector.symbols.edges.retain(|edge| {
+        edge.from != edge.to && seen.insert((edge.from.clone(), edge.to.clone(), format!("{:?}", edge.reference_type)))
+    });
+    Ok(collector.symbols)
+}
+
+struct SymbolCollector<'a> {
+    path: &'a Path,
+    text: &'a str,
+    bom: usize,
+    line_starts: Vec<usize>,
+    /// Enclosing modules, types and traits
+    scopes: Vec<String>,
+    /// Qualified names of enclosing items that own references
+    containers: Vec<String>,
+    symbols: FileSymbols,
+}
+
+impl SymbolCollector<'_> {
+    fn offset(&self, at: LineColumn) -> usize {
+        let start = match at.line {
+            0 | 1 => self.bom,
+            line => match self.line_starts.get(line - 1) {
+                Some(start) => *start,
+                None => return self.text.len(),
+            },
+        };
+        self.text[start..].char_indices().nth(at.column).map_or(self.text.len(), |(i, _)| start + i)
+    }
+
+    fn position(&self, at: LineColumn, length: usize) -> TextPosition {
+        TextPosition {
+            line: at.line.saturating_sub(1),
+            column: at.column,
+            offset: self.offset(at),
+            length,
+        }
+    }
+
+    fn location(&self, ident: Span, item: Span) -> SymbolLocation {
+        let start = self.offset(ident.start());
+        let end = self.offset(ident.end());
+        SymbolLocation {
+            file_path: self.path.to_path_buf(),
+            position: self.position(ident.start(), end - start),
+            span: TextSpan {
+                start: self.position(item.start(), 0),
+                end: self.position(item.end(), 0),
+            },
+        }
+    }
+
+    fn line_text(&self, offset: usize) -> String {
+        let start = self.text[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let end = self.text[offset..].find('\n').map_or(self.text.len(), |i| offset + i);
+        self.text[start..end].trim().to_string()
+    }
+
+    fn scope(&self) -> String {
+        self.scopes.join("::")
+    }
+
+    fn qualified(&self, name: &str) -> String {
+        if self.scopes.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}::{}", self.scope(), name)
+        }
+    }
+
+    fn define(
+        &mut self,
+        ident: &syn::Ident,
+        kind: SymbolKind,
+        node: &impl Spanned,
+        vis: Option<&syn::Visibility>,
+        attrs: &[syn::Attribute],
+    ) {
+        let symbol = Symbol {
+            name: ident.to_string(),
+            kind,
+            scope: self.scope(),
+            definition: self.location(ident.span(), node.span()),
+            visibility: match vis {
+                Some(syn::Visibility::Public(_)) => Visibility::Public,
+                Some(syn::Visibility::Restricted(_)) => Visibility::Internal,
+                Some(syn::Visibility::Inherited) => Visibility::Private,
+                // Enum variants and trait items share their parent's visibility
+                None => Visibility::Public,
+            },
+            documentation: documentation(attrs),
+            attributes: HashMap::new(),
+        };
+        self.symbols.definitions.push(SymbolDefinition {
+            symbol,
+            definition_type: DefinitionType::Declaration,
+            signature: None,
+            body: None,
+        });
+    }
+
+    /// Define a function or method, recording its signature
+    fn define_fn(
+        &mut self,
+        sig: &syn::Signature,
+        kind: SymbolKind,
+        node: &impl Spanned,
+        vis: Option<&syn::Visibility>,
+        attrs: &[syn::Attribute],
+        definition_type: DefinitionType,
+    ) {
+        self.define(&sig.ident, kind, node, vis, attrs);
+        let text = &self.text[self.offset(sig.span().start())..self.offset(sig.span().end())];
+        let signature = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if let Some(definition) = self.symbols.definitions.last_mut() {
+            definition.definition_type = definition_type;
+            definition.signature = Some(signature);
+        }
+    }
+
+    fn reference(&mut self, ident: &proc_macro2::Ident, reference_type: ReferenceType) {
+        let name = ident.to_string();
+        if name == "self" || name == "Self" || name == "crate" || name == "super" {
+            return;
+        }
+        let start = self.offset(ident.span().start());
+        let end = self.offset(ident.span().end());
+        let location = SymbolLocation {
+            file_path: self.path.to_path_buf(),
+            position: self.position(ident.span().start(), end - start),
+            span: TextSpan {
+                start: self.position(ident.span().start(), 0),
+                end: self.position(ident.span().end(), 0),
+            },
+        };
+        if let Some(container) = self.containers.last() {
+            self.symbols.edges.push(SymbolEdge { from: container.clone(), to: name.clone(), reference_type: reference_type.clone() });
+        }
+        self.symbols.references.push(SymbolReference {
+            symbol_name: name,
+            location,
+            reference_type,
+            context: self.line_text(start),
+        });
+    }
+
+    fn path_reference(&mut self, path: &syn::Path, reference_type: ReferenceType) {
+        for (index, segment) in path.segments.iter().enumerate() {
+            let last = index + 1 == path.segments.len();
+            // Leading segments name modules or types
+            let kind = if last { reference_type.clone() } else { ReferenceType::Type };
+            self.reference(&segment.ident, kind);
+            if let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments {
+                self.visit_angle_bracketed_generic_arguments(arguments);
+            }
+        }
+    }
+
+    fn tokens(&mut self, tokens: TokenStream) {
+        let mut after_apostrophe = false;
+        for token in tokens {
+            match &token {
+                TokenTree::Ident(ident) if !after_apostrophe => self.reference(ident, ReferenceType::Read),
+                TokenTree::Group(group) => self.tokens(group.stream()),
+                _ => {}
+            }
+            after_apostrophe = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == '\'');
+        }
+    }
+
+    fn within(&mut self, scope: Option<String>, container: Option<String>, visit: impl FnOnce(&mut Self)) {
+        let scoped = scope.map(|scope| self.scopes.push(scope)).is_some();
+        let contained = container.map(|container| self.containers.push(container)).is_some();
+        visit(self);
+        if contained {
+            self.containers.pop();
+        }
+        if scoped {
+            self.scopes.pop();
+        }
+    }
+}
+
+fn documentation(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue { value: syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(text), .. }), .. }) => {
+                Some(text.value().trim().to_string())
+            }
+            _ => None,
+        })
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Name of the type an impl block is for
+fn impl_self_name(item: &syn::ItemImpl) -> String {
+    match &*item.self_ty {
+        syn::Type::Path(path) => path.path.segments.last().map(|segment| segment.ident.to_string()).unwrap_or_default(),
+        other => quote::ToTokens::to_token_stream(other).to_string(),
+    }
+}
+
+impl<'ast> Visit<'ast> for SymbolCollector<'_> {
+    fn visit_item_fn(&mut self, item: &'ast syn::ItemFn) {
+        self.define_fn(&item.sig, SymbolKind::Function, item, Some(&item.vis), &item.attrs, DefinitionType::Implementation);
+        let container = self.qualified(&item.sig.ident.to_string());
+        self.within(None, Some(container), |collector| visit::visit_item_fn(collector, item));
+    }
+
+    fn visit_item_struct(&mut self, item: &'ast syn::ItemStruct) {
+        self.define(&item.ident, SymbolKind::Type, item, Some(&item.vis), &item.attrs);
+        let name = item.ident.to_string();
+        let container = self.qualified(&name);
+        self.within(Some(name), Some(container), |collector| visit::visit_item_struct(collector, item));
+    }
+
+    fn visit_item_enum(&mut self, item: &'ast syn::ItemEnum) {
+        self.define(&item.ident, SymbolKind::Enum, item, Some(&item.vis), &item.attrs);
+        let name = item.ident.to_string();
+        let container = self.qualified(&name);
+        self.within(Some(name), Some(container), |collector| visit::visit_item_enum(collector, item));
+    }
+
+    fn visit_item_union(&mut self, item: &'ast syn::ItemUnion) {
+        self.define(&item.ident, SymbolKind::Type, item, Some(&item.vis), &item.attrs);
+        let name = item.ident.to_string();
+        let container = self.qualified(&name);
+        self.within(Some(name), Some(container), |collector| visit::visit_item_union(collector, item));
+    }
+
+    fn visit_item_trait(&mut self, item: &'ast syn::ItemTrait) {
+        self.define(&item.ident, SymbolKind::Interface, item, Some(&item.vis), &item.attrs);
+        let name = item.ident.to_string();
+        let container = self.qualified(&name);
+        self.within(Some(name), Some(container), |collector| visit::visit_item_trait(collector, item));
+    }
+
+    fn visit_item_type(&mut self, item: &'ast syn::ItemType) {
+        self.define(&item.ident, SymbolKind::Type, item, Some(&item.vis), &item.attrs);
+        let container = self.qualified(&item.ident.to_string());
+        self.within(None, Some(container), |collector| visit::visit_item_type(collector, item));
+    }
+
+    fn visit_item_const(&mut self, item: &'ast syn::ItemConst) {
+        self.define(&item.ident, SymbolKind::Constant, item, Some(&item.vis), &item.attrs);
+        let container = self.qualified(&item.ident.to_string());
+        self.within(None, Some(container), |collector| visit::visit_item_const(collector, item));
+    }
+
+    fn visit_item_static(&mut self, item: &'ast syn::ItemStatic) {
+        self.define(&item.ident, SymbolKind::Variable, item, Some(&item.vis), &item.attrs);
+        let container = self.qualified(&item.ident.to_string());
+        self.within(None, Some(container), |collector| visit::visit_item_static(collector, item));
+    }
+
+    fn visit_item_mod(&mut self, item: &'ast syn::ItemMod) {
+        self.define(&item.ident, SymbolKind::Module, item, Some(&item.vis), &item.attrs);
+        self.within(Some(item.ident.to_string()), None, |collector| visit::visit_item_mod(collector, item));
+    }
+
+    fn visit_item_macro(&mut self, item: &'ast syn::ItemMacro) {
+        match &item.ident {
+            Some(ident) => {
+                self.define(ident, SymbolKind::Macro, item, None, &item.attrs);
+            }
+            None => visit::visit_item_macro(self, item),
+        }
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast syn::ItemImpl) {
+        let name = impl_self_name(item);
+        if let Some((_, trait_path, _)) = &item.trait_ {
+            self.path_reference(trait_path, ReferenceType::Type);
+        }
+        self.visit_type(&item.self_ty);
+        self.visit_generics(&item.generics);
+        let is_trait_impl = item.trait_.is_some();
+        self.within(Some(name), None, |collector| {
+            for impl_item in &item.items {
+                match impl_item {
+                    syn::ImplItem::Fn(method) => {
+                        let definition_type = if is_trait_impl { DefinitionType::Override } else { DefinitionType::Implementation };
+                        collector.define_fn(&method.sig, SymbolKind::Method, method, Some(&method.vis), &method.attrs, definition_type);
+                        let container = collector.qualified(&method.sig.ident.to_string());
+                        collector.within(None, Some(container), |collector| visit::visit_impl_item_fn(collector, method));
+                    }
+                    syn::ImplItem::Const(constant) => {
+                        collector.define(&constant.ident, SymbolKind::Constant, constant, Some(&constant.vis), &constant.attrs);
+                        visit::visit_impl_item_const(collector, constant);
+                    }
+                    syn::ImplItem::Type(ty) => {
+                        collector.define(&ty.ident, SymbolKind::Type, ty, Some(&ty.vis), &ty.attrs);
+                        visit::visit_impl_item_type(collector, ty);
+                    }
+                    other => visit::visit_impl_item(collector, other),
+                }
+            }
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, item: &'ast syn::TraitItemFn) {
+        let definition_type = if item.default.is_some() { DefinitionType::Implementation } else { DefinitionType::Declaration };
+        self.define_fn(&item.sig, SymbolKind::Method, item, None, &item.attrs, definition_type);
+        let container = self.qualified(&item.sig.ident.to_string());
+        self.within(None, Some(container), |collector| visit::visit_trait_item_fn(collector, item));
+    }
+
+    fn visit_field(&mut self, field: &'ast syn::Field) {
+        if let Some(ident) = &field.ident {
+            self.define(ident, SymbolKind::Field, field, Some(&field.vis), &field.attrs);
+        }
+        self.visit_type(&field.ty);
+    }
+
+    fn visit_variant(&mut self, variant: &'ast syn::Variant) {
+        self.define(&variant.ident, SymbolKind::Constant, variant, None, &variant.attrs);
+        self.visit_fields(&variant.fields);
+        if let Some((_, discriminant)) = &variant.discriminant {
+            self.visit_expr(discriminant);
+        }
+    }
+
+    fn visit_use_tree(&mut self, tree: &'ast syn::UseTree) {
+        match tree {
+            syn::UseTree::Path(path) => {
+                self.reference(&path.ident, ReferenceType::Import);
+                self.visit_use_tree(&path.tree);
+            }
+            syn::UseTree::Name(name) => self.reference(&name.ident, ReferenceType::Import),
+            syn::UseTree::Rename(rename) => self.reference(&rename.ident, ReferenceType::Import),
+            syn::UseTree::Group(group) => group.items.iter().for_each(|item| self.visit_use_tree(item)),
+            syn::UseTree::Glob(_) => {}
+        }
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        match &*call.func {
+            syn::Expr::Path(path) if path.qself.is_none() => self.path_reference(&path.path, ReferenceType::Call),
+            other => self.visit_expr(other),
+        }
+        call.args.iter().for_each(|arg| self.visit_expr(arg));
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        self.visit_expr(&call.receiver);
+        self.reference(&call.method, ReferenceType::Call);
+        if let Some(turbofish) = &call.turbofish {
+            self.visit_angle_bracketed_generic_arguments(turbofish);
+        }
+        call.args.iter().for_each(|arg| self.visit_expr(arg));
+    }
+
+    fn visit_expr_assign(&mut self, assign: &'ast syn::ExprAssign) {
+        match &*assign.left {
+            syn::Expr::Path(path) if path.qself.is_none() => self.path_reference(&path.path, ReferenceType::Write),
+            syn::Expr::Field(field) => {
+                self.visit_expr(&field.base);
+                if let syn::Member::Named(ident) = &field.member {
+                    self.reference(ident, ReferenceType::Write);
+                }
+            }
+            other => self.visit_expr(other),
+        }
+        self.visit_expr(&assign.right);
+    }
+
+    fn visit_expr_path(&mut self, expr: &'ast syn::ExprPath) {
+        if let Some(qself) = &expr.qself {
+            self.visit_type(&qself.ty);
+        }
+        self.path_reference(&expr.path, ReferenceType::Read);
+    }
+
+    fn visit_expr_field(&mut self, expr: &'ast syn::ExprField) {
+        self.visit_expr(&expr.base);
+        if let syn::Member::Named(ident) = &expr.member {
+            self.reference(ident, ReferenceType::Read);
+        }
+    }
+
+    fn visit_expr_struct(&mut self, expr: &'ast syn::ExprStruct) {
+        self.path_reference(&expr.path, ReferenceType::Type);
+        for field in &expr.fields {
+            if let syn::Member::Named(ident) = &field.member {
+                self.reference(ident, ReferenceType::Write);
+            }
+            // Shorthand fields reuse the member token as the value
+            if field.colon_token.is_some() {
+                self.visit_expr(&field.expr);
+            }
+        }
+        if let Some(rest) = &expr.rest {
+            self.visit_expr(rest);
+        }
+    }
+
+    fn visit_type_path(&mut self, ty: &'ast syn::TypePath) {
+        if let Some(qself) = &ty.qself {
+            self.visit_type(&qself.ty);
+        }
+        self.path_reference(&ty.path, ReferenceType::Type);
+    }
+
+    fn visit_trait_bound(&mut self, bound: &'ast syn::TraitBound) {
+        self.path_reference(&bound.path, ReferenceType::Type);
+    }
+
+    fn visit_pat_ident(&mut self, pat: &'ast syn::PatIdent) {
+        if let Some((_, subpat)) = &pat.subpat {
+            self.visit_pat(subpat);
+        }
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        self.path_reference(&mac.path, ReferenceType::Call);
+        self.tokens(mac.tokens.clone());
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Snapshot
+// ---------------------------------------------------------------------------
+
+/// Serialized form of the workspace index
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndexSnapshot {
+    pub format_version: u32,
+    /// Indexed files; their position is the id used by the posting lists
+    pub files: Vec<FileRecord>,
+    /// Trigram to ids of files containing it
+    pub trigrams: HashMap<String, Vec<u32>>,
+    /// Word to ids of files containing it
+    pub words: HashMap<String, Vec<u32>>,
+}
+
+/// One indexed file in a snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileRecord {
+    /// Path relative to the workspace root
+    pub path: PathBuf,
+    pub content_hash: String,
+    pub modified: SystemTime,
+    pub size: u64,
+    pub file_type: FileType,
+    pub symbols: FileSymbols,
+}
+
+impl IndexSnapshot {
+    /// Location of the snapshot for the workspace at `root`
+    pub fn location(root: &Path) -> PathBuf {
+        root.join(INDEX_DIR).join(INDEX_FILE)
+    }
+
+    /// Load the snapshot of `root`; `None` if there is none or it has an older format
+    pub fn load(root: &Path) -> Result<Option<Self>, String> {
+        let path = Self::location(root);
+        let file = match std::fs::File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Cannot open {}: {}", path.display(), e)),
+        };
+        let mut json = String::new();
+        GzDecoder::new(file)
+            .read_to_string(&mut json)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+
+        #[derive(Deserialize)]
+        struct Header {
+            format_version: u32,
+        }
+        let header: Header = serde_json::from_str(&json).map_err(|e| format!("Corrupt index {}: {}", path.display(), e))?;
+        if header.format_version != FORMAT_VERSION {
+            return Ok(None);
+        }
+        serde_json::from_str(&json).map(Some).map_err(|e| format!("Corrupt index {}: {}", path.display(), e))
+    }
+
+    /// Write the snapshot atomically under `root`
+    pub fn save(&self, root: &Path) -> Result<(), String> {
+        let path = Self::location(root);
+        let dir = path.parent().expect("index file has a parent");
+        std::fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+
+        let json = serde_json::to_vec(self).map_err(|e| e.to_string())?;
+        let temp = path.with_extension("tmp");
+        let write = || -> std::io::Result<()> {
+            let mut encoder = GzEncoder::new(std::fs::File::create(&temp)?, Compression::fast());
+            encoder.write_all(&json)?;
+            encoder.finish()?.sync_all()?;
+            std::fs::rename(&temp, &path)
+        };
+        write().map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+    }
+}
+
+/// Hex SHA-256 of file content
+pub fn content_hash(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// Point every location in `symbols` at `path`
+///
+/// Snapshots store paths relative to the workspace so a moved project keeps its index.
+pub fn relocate_symbols(symbols: &mut FileSymbols, path: &Path) {
+    for definition in &mut symbols.definitions {
+        definition.symbol.definition.file_path = path.to_path_buf();
+    }
+    for reference in &mut symbols.references {
+        reference.location.file_path = path.to_path_buf();
+    }
+}
@@ -14,12 +14,16 @@ pub struct ComponentClipboardData {
     pub position: egui::Pos2,
     pub size: egui::Vec2,
 }
-use crate::ai_agent::AiAgent;
+use crate::ai_agent::{AiAgent, AiExchange, AiTaskType};
+use crate::ai_provider::{AiProviderSettings, ModelListing};
+use crate::editor::ai_edits::{EditReview, EditWorkspace};
+use crate::editor::build_system::CompilerMessage;
 use crate::editor::menu::IdeMenu;
 use crate::editor::visual_designer::VisualDesigner;
 use crate::editor::smart_ai_assistant::SmartAiAssistant;
 use crate::editor::lsp_integration::LspClient;
 use crate::editor::code_editor::CodeEditor;
+use crate::editor::advanced_refactoring::{AdvancedRefactoringEngine, RefactoringSettings, RefactoringView};
 use crate::editor::advanced_code_editor::AdvancedCodeEditor;
 use crate::editor::enhanced_lsp_client::EnhancedLspClient;
 use crate::editor::project_manager::ProjectManager;
@@ -31,8 +35,15 @@ use crate::editor::modern_ide_integration::ModernIdeIntegration;
 use crate::editor::multi_device_preview::MultiDevicePreview;
 use crate::editor::template_system_simple::ComponentTemplate;
 use crate::editor::file_manager::FileManager;
+use crate::editor::advanced_search::{AdvancedSearchEngine, SearchSettings};
 use crate::editor::realtime_sync::RealtimeSync;
 use crate::editor::build_system::BuildSystem;
+use crate::editor::plugin_system::PluginManager;
+use crate::editor::code_editor::RemoteCursor;
+use crate::editor::collaboration::protocol::PeerInfo;
+use crate::editor::collaboration::relay::DEFAULT_RELAY_PORT;
+use crate::editor::collaboration::session::CollabSession;
+use crate::editor::text_buffer::{SelectionSet, TextBuffer};
 
 /// # Main IDE Application State
 /// 
@@ -100,11 +111,48 @@ pub struct IdeAppState {
     /// UI feedback (loading indicators, disabled controls).
     pub ai_pending: bool,
     
-    /// Async task handle for AI operations.
+    /// Streamed AI request currently in progress.
     /// 
-    /// Maintains a reference to the current AI task to enable cancellation
-    /// and proper cleanup. The complex type reflects the async nature of AI operations.
-    pub ai_task: Option<std::pin::Pin<Box<dyn std::future::Future<Output = anyhow::Result<String>> + Send>>>,
+    /// Polled every frame to append tokens to `ai_response`; cancelling it
+    /// stops the request on the provider.
+    pub ai_task: Option<AiExchange>,
+    
+    /// Task type the next AI prompt is sent as.
+    /// 
+    /// Selects the specialized system prompt and the model the request runs on.
+    pub ai_task_type: AiTaskType,
+    
+    /// Model backend configuration edited in the AI panel.
+    /// 
+    /// Applied to the agent on demand so half-typed URLs are never used.
+    pub ai_settings: AiProviderSettings,
+    
+    /// Models the configured server reported, offered in the model pickers.
+    pub ai_models: Vec<String>,
+    
+    /// Model list request in flight, polled every frame until it answers.
+    pub ai_model_listing: Option<ModelListing>,
+    
+    /// Text buffers AI edits are validated against and applied to.
+    /// 
+    /// Synced from the open tabs before each use; keeps the undo history of
+    /// applied AI edits.
+    pub ai_edits: EditWorkspace,
+    
+    /// AI edit proposal under review.
+    /// 
+    /// Shown as a window of per-hunk diffs until applied or discarded.
+    pub ai_review: Option<EditReview>,
+    
+    /// Whether applied AI edits are saved and checked with `cargo check`.
+    /// 
+    /// Errors the edits introduce are sent back to the model for a fix.
+    pub ai_check_after_apply: bool,
+    
+    /// Errors reported before the last AI edits were applied.
+    /// 
+    /// Present while the check of those edits runs, so only new errors are fed back.
+    pub ai_check_baseline: Option<Vec<CompilerMessage>>,
 
     // ========================================================================================
     // UI MANAGEMENT SYSTEM - Controls IDE layout, panels, and user interface state
@@ -133,6 +181,8 @@ pub struct IdeAppState {
     pub active_left_tab: String,
     /// Active tab in right panel
     pub active_right_tab: String,
+    /// Active page of the properties inspector ("properties" or "events")
+    pub active_inspector_tab: String,
     
     // ========================================================================================
     // SELECTION AND INTERACTION SYSTEM - Manages component selection and manipulation
@@ -168,6 +218,14 @@ pub struct IdeAppState {
     /// - Code folding and bracket matching
     /// - Integration with build and debug systems
     pub code_editor: CodeEditor,
+    
+    /// Syntax-aware refactoring engine for the project workspace.
+    /// 
+    /// Rewrites files on disk and keeps the undo history of refactorings.
+    pub refactoring_engine: AdvancedRefactoringEngine,
+    
+    /// Refactoring window for the selection it was opened on.
+    pub refactoring_view: Option<RefactoringView>,
 
     // ========================================================================================
     // INTELLIGENT ASSISTANCE SYSTEMS - AI and language server integration
@@ -225,6 +283,12 @@ pub struct IdeAppState {
     /// - Build output streaming and error parsing
     /// - Multiple build profiles (debug, release, test)
     pub build_system: BuildSystem,
+    
+    /// Plugin manager for out-of-process IDE extensions.
+    /// 
+    /// Discovers installed plugins, runs their code, and delivers
+    /// file, build and git events to the hooks they register.
+    pub plugin_manager: PluginManager,
 
     // ========================================================================================  
     // SPECIALIZED IDE FEATURES - Advanced IDE capabilities and tooling
@@ -327,6 +391,21 @@ pub struct IdeAppState {
     /// - Bidirectional sync with debouncing for performance
     pub realtime_sync: RealtimeSync,
     
+    /// Project-wide search, go-to-symbol and find-usages index
+    /// 
+    /// Opened for the current project from its `.ide-rs` snapshot and kept
+    /// current from the file manager's watcher events.
+    pub search_engine: Option<AdvancedSearchEngine>,
+    
+    /// Relay address the join action connects to, as `host:port`
+    pub collab_address: String,
+    
+    /// Shared editing session and the path of the tab it edits
+    /// 
+    /// Polled every frame: remote edits go into the tab, and the text and
+    /// cursor typed in the shown editor go to the relay.
+    pub collab_session: Option<(std::path::PathBuf, CollabSession)>,
+    
     // ========================================================================================
     // PROJECT CREATION SYSTEM - New GUI project creation with cargo integration
     // ========================================================================================
@@ -364,6 +443,14 @@ impl IdeAppState {
             ai_response: String::new(),
             ai_pending: false,
             ai_task: None,
+            ai_task_type: AiTaskType::CodeGeneration,
+            ai_settings: AiProviderSettings::default(),
+            ai_models: Vec::new(),
+            ai_model_listing: None,
+            ai_edits: EditWorkspace::new(std::env::current_dir().unwrap_or_default()),
+            ai_review: None,
+            ai_check_after_apply: true,
+            ai_check_baseline: None,
             menu: IdeMenu::new(),
             // Initialize panel visibility - start with key panels visible
             show_component_palette: true,
@@ -378,6 +465,8 @@ impl IdeAppState {
             advanced_code_editor: None,
             design_mode: true,
             code_editor: CodeEditor::with_content("rust", Self::default_rust_code()),
+            refactoring_engine: AdvancedRefactoringEngine::new(RefactoringSettings::default()),
+            refactoring_view: None,
             project_manager: ProjectManager::new(),
             build_system: {
                 let mut build_system = BuildSystem::new();
@@ -387,10 +476,20 @@ impl IdeAppState {
                 }
                 build_system
             },
+            plugin_manager: {
+                let mut plugin_manager = PluginManager::new();
+                plugin_manager.connect_event_bus();
+                if let Err(_e) = plugin_manager.scan_plugins() {
+                    crate::log_warn!("Failed to scan plugins: {}", _e);
+                }
+                plugin_manager.load_auto_load_plugins();
+                plugin_manager
+            },
             show_project_panel: true,
             show_modern_ide_panel: false,
             active_left_tab: "project".to_string(),
             active_right_tab: "objects".to_string(),
+            active_inspector_tab: "properties".to_string(),
             property_inspector: PropertyInspector::new(),
             object_inspector: ObjectInspector::new(),
             live_feedback: LiveFeedbackSystem::new(),
@@ -402,6 +501,9 @@ impl IdeAppState {
             movement_manager: super::animated_ui::MovementManager::new(),
             file_manager: FileManager::new(),
             realtime_sync: RealtimeSync::new(),
+            search_engine: None,
+            collab_address: format!("127.0.0.1:{}", DEFAULT_RELAY_PORT),
+            collab_session: None,
             new_project_name: String::new(),
             new_project_location: String::new(),
             clipboard_data: None,
@@ -471,23 +573,161 @@ impl IdeAppState {
     }
     
     /// Check and perform real-time sync if needed
-    pub fn update_realtime_sync(&mut self) {
+    ///
+    /// Shows the merge view while regenerated code conflicts with user edits.
+    pub fn update_realtime_sync(&mut self, ctx: &egui::Context) {
         // Check if visual designer has changed and sync to code
         if let Some(active_tab) = self.file_manager.get_active_tab_mut() {
             if let Some(code_editor) = &mut active_tab.code_editor {
-                if let Some(_sync_result) = self.realtime_sync.check_and_sync_designer(
+                if self.realtime_sync.render_merge_view(ctx, code_editor) {
+                    active_tab.mark_dirty();
+                } else if let Some(sync_result) = self.realtime_sync.check_and_sync_designer(
                     &self.visual_designer,
                     &self.root_form,
                     &self.components,
                     code_editor,
                 ) {
-                    // Code was updated, mark tab as dirty
-                    active_tab.mark_dirty();
+                    if sync_result.success {
+                        // Code was updated, mark tab as dirty
+                        active_tab.mark_dirty();
+                    } else if let Some(error) = sync_result.error {
+                        self.menu.output_panel.log(&format!("⚠ Designer sync: {}", error));
+                    }
                 }
             }
         }
     }
 
+    /// Open the search index of the current project and apply file changes to it
+    pub fn update_search_index(&mut self) {
+        let root = self.project_manager.get_current_project()
+            .map(|project| project.metadata.root_path.clone())
+            .filter(|root| root.is_dir());
+        let indexed = self.search_engine.as_ref().and_then(|engine| engine.workspace_root());
+        if root.as_deref() != indexed {
+            if let Some(old_root) = indexed.map(|root| root.to_path_buf()) {
+                let _ = self.file_manager.unwatch_path(&old_root);
+            }
+            self.search_engine = root.map(|root| self.open_search_index(&root));
+        }
+        
+        if let (Some(engine), Some(watcher)) = (self.search_engine.as_mut(), self.file_manager.file_watcher.as_ref()) {
+            if let Err(e) = engine.process_watcher_events(watcher) {
+                self.menu.output_panel.log(&format!("❌ Failed to update search index: {}", e));
+            }
+        }
+    }
+    
+    /// Create the search engine for `root` and watch the project for changes
+    fn open_search_index(&mut self, root: &std::path::Path) -> AdvancedSearchEngine {
+        let mut engine = AdvancedSearchEngine::new(SearchSettings::default());
+        match engine.open_workspace(root) {
+            Ok(update) => self.menu.output_panel.log(&format!(
+                "🔍 Indexed {} files ({} added, {} updated, {} removed)",
+                engine.indexed_file_count(), update.added, update.updated, update.removed
+            )),
+            Err(e) => self.menu.output_panel.log(&format!("❌ Failed to index {}: {}", root.display(), e)),
+        }
+        if let Err(e) = self.file_manager.watch_path(root) {
+            self.menu.output_panel.log(&format!("⚠️ Cannot watch {}: {}", root.display(), e));
+        }
+        engine
+    }
+    
+    /// Share the active tab through the relay at `collab_address`
+    ///
+    /// The document is named after the file's path in the project, so editors
+    /// with the same project open join the same document.
+    pub fn join_collaboration(&mut self) {
+        let Some(tab) = self.file_manager.get_active_tab() else {
+            self.menu.output_panel.log("⚠️ Open a file to share it");
+            return;
+        };
+        let path = tab.path.clone();
+        let document = self.project_manager.get_current_project()
+            .and_then(|project| path.strip_prefix(&project.metadata.root_path).ok())
+            .unwrap_or_else(|| path.file_name().map_or(path.as_path(), std::path::Path::new))
+            .to_string_lossy()
+            .replace('\\', "/");
+        let text = if self.design_mode { tab.content.clone() } else { self.code_editor.code.clone() };
+        let peer = PeerInfo { user_id: whoami::username(), display_name: whoami::realname() };
+
+        match CollabSession::connect(self.collab_address.as_str(), &document, peer, &TextBuffer::from_string(text)) {
+            Ok(session) => {
+                self.menu.output_panel.log(&format!("🤝 Sharing {} on {}", document, self.collab_address));
+                self.collab_session = Some((path, session));
+            }
+            Err(e) => self.menu.output_panel.log(&format!("❌ Cannot join {} on {}: {}", document, self.collab_address, e)),
+        }
+    }
+
+    /// Stop sharing the tab of the collaboration session
+    pub fn leave_collaboration(&mut self) {
+        if let Some((path, _)) = self.collab_session.take() {
+            self.code_editor.remote_cursors.clear();
+            self.menu.output_panel.log(&format!("🤝 Stopped sharing {}", path.display()));
+        }
+    }
+
+    /// Exchange edits and cursors of the shared tab with the relay
+    ///
+    /// Runs after the editor is rendered, so the shown editor holds this
+    /// frame's typing when the shared tab is active.
+    pub fn update_collaboration(&mut self, ctx: &egui::Context) {
+        let Some((path, session)) = self.collab_session.as_mut() else {
+            return;
+        };
+        let shown = !self.design_mode && self.file_manager.active_tab.as_ref() == Some(path);
+        let Some(tab) = self.file_manager.open_tabs.get_mut(path) else {
+            self.leave_collaboration();
+            return;
+        };
+
+        let mut buffer = TextBuffer::from_string(if shown { self.code_editor.code.clone() } else { tab.content.clone() });
+        let result = session.poll(&mut buffer).and_then(|changed| {
+            if shown {
+                // The cursor keeps its character index when remote edits land
+                let selection = selection_set(&buffer, self.code_editor.selection_chars())?;
+                session.set_selections(&buffer, &selection)?;
+            }
+            Ok(changed)
+        });
+        let changed = match result {
+            Ok(changed) => changed,
+            Err(e) => {
+                self.menu.output_panel.log(&format!("❌ Collaboration on {} ended: {}", path.display(), e));
+                self.leave_collaboration();
+                return;
+            }
+        };
+
+        let text = buffer.to_string();
+        if tab.content != text {
+            if let Some(editor) = tab.code_editor.as_mut() {
+                editor.code = text.clone();
+            }
+            tab.content = text.clone();
+            tab.mark_dirty();
+        }
+        if shown {
+            if changed {
+                self.code_editor.code = text;
+            }
+            self.code_editor.remote_cursors = session.peers().into_iter()
+                .flat_map(|peer| peer.selections.iter().map(move |selection| RemoteCursor {
+                    name: peer.peer.display_name.clone(),
+                    anchor: selection.anchor,
+                    head: selection.head,
+                    color: peer_color(peer.client_id),
+                }))
+                .collect();
+        } else {
+            self.code_editor.remote_cursors.clear();
+        }
+        // Remote edits arrive without input, so keep polling
+        ctx.request_repaint_after(std::time::Duration::from_millis(100));
+    }
+    
     /// Default Rust code template for new projects
     pub fn default_rust_code() -> String {
         r#"fn main() {
@@ -512,6 +752,28 @@ impl MyComponent {
     }
 }
 
+/// Selection set of `buffer` with one cursor between two character indices
+fn selection_set(buffer: &TextBuffer, (anchor, head): (usize, usize)) -> Result<SelectionSet, String> {
+    let position = |index: usize| {
+        let offset = buffer.rope.char_to_byte(index.min(buffer.rope.len_chars()));
+        buffer.offset_to_position(offset).map_err(|e| e.to_string())
+    };
+    Ok(SelectionSet::range(position(anchor)?, position(head)?))
+}
+
+/// Color of a participant's cursor, told apart by client id
+fn peer_color(client_id: u64) -> egui::Color32 {
+    const COLORS: [egui::Color32; 6] = [
+        egui::Color32::from_rgb(230, 126, 34),
+        egui::Color32::from_rgb(46, 204, 113),
+        egui::Color32::from_rgb(155, 89, 182),
+        egui::Color32::from_rgb(241, 196, 15),
+        egui::Color32::from_rgb(231, 76, 60),
+        egui::Color32::from_rgb(26, 188, 156),
+    ];
+    COLORS[(client_id % COLORS.len() as u64) as usize]
+}
+
 impl Default for IdeAppState {
     fn default() -> Self {
         Self::new()
@@ -6,6 +6,7 @@
 use eframe::egui;
 use super::app_state::IdeAppState;
 use super::drag_drop::DragState;
+use super::ui_manager::UiManager;
 
 /// # Content Manager
 /// 
@@ -261,6 +262,7 @@ impl ContentManager {
                 vec!["Option 1".to_string(), "Option 2".to_string()],
                 0
             )),
+            ComponentType::Custom(12) => Box::new(crate::rcl::network::websocket::WebSocketComponent::default()),
             _ => {
                 // For other component types, create a label as placeholder
                 Box::new(label::Label::new(format!("New {}", component_type.display_name())))
@@ -336,6 +338,9 @@ impl ContentManager {
                 if ui.button("🔍").on_hover_text("Find/Replace").clicked() {
                     // TODO: Open find/replace dialog
                 }
+                if ui.button("🔧").on_hover_text("Refactor Selection").clicked() {
+                    UiManager::open_refactoring_view(app_state);
+                }
                 if ui.button("🚀").on_hover_text("Run Code").clicked() {
                     // Execute cargo run using the build system
                     if let Err(e) = app_state.build_system.run() {
@@ -361,6 +366,24 @@ impl ContentManager {
                 if ui.button("⚡").on_hover_text("Enable Enhanced LSP Features").clicked() {
                     Self::enable_enhanced_code_editor(app_state);
                 }
+                
+                ui.separator();
+                
+                // Shared editing through a collaboration relay
+                if let Some((_, session)) = &app_state.collab_session {
+                    let names: Vec<String> = session.peers().iter().map(|peer| peer.peer.display_name.clone()).collect();
+                    ui.label(format!("🤝 {} editing", names.len() + 1))
+                        .on_hover_text(if names.is_empty() { "Nobody else has joined yet".to_string() } else { names.join("\n") });
+                    if ui.button("Leave").on_hover_text("Stop sharing this file").clicked() {
+                        app_state.leave_collaboration();
+                    }
+                } else {
+                    ui.add(egui::TextEdit::singleline(&mut app_state.collab_address).desired_width(140.0))
+                        .on_hover_text("Collaboration relay address (host:port)");
+                    if ui.button("🤝 Join").on_hover_text("Edit this file together with others on the relay").clicked() {
+                        app_state.join_collaboration();
+                    }
+                }
             });
             
             ui.separator();
@@ -645,6 +668,9 @@ impl ContentManager {
             // Update file manager with new content
             if let Some(tab) = app_state.file_manager.open_tabs.get_mut(&path) {
                 if tab.content != new_content {
+                    if let Some(editor) = tab.code_editor.as_mut() {
+                        editor.code = new_content.clone();
+                    }
                     tab.content = new_content;
                     tab.mark_dirty();
                 }
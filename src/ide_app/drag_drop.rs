@@ -411,6 +411,7 @@ impl ComponentType {
             ComponentType::Custom(9) => "File Picker",
             ComponentType::Custom(10) => "Rich Text Editor",
             ComponentType::Custom(11) => "Code Editor",
+            ComponentType::Custom(12) => "WebSocket",
             ComponentType::Custom(_) => "Custom",
         }
     }
@@ -440,6 +441,7 @@ impl ComponentType {
             ComponentType::Custom(9) => "📁", // File Picker
             ComponentType::Custom(10) => "📖", // Rich Text Editor
             ComponentType::Custom(11) => "⌨️", // Code Editor
+            ComponentType::Custom(12) => "🔌", // WebSocket
             ComponentType::Custom(_) => "🔧",
         }
     }
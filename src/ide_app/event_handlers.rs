@@ -256,14 +256,14 @@ impl EventHandlers {
                 if app_state.design_mode {
                     app_state.visual_designer.undo(&mut app_state.components);
                 } else {
-                    app_state.code_editor.undo();
+                    self.step_code_history(app_state, false);
                 }
             }
             IdeCommand::Redo => {
                 if app_state.design_mode {
                     app_state.visual_designer.redo(&mut app_state.components);
                 } else {
-                    app_state.code_editor.redo();
+                    self.step_code_history(app_state, true);
                 }
             }
             IdeCommand::Cut => {
@@ -417,6 +417,25 @@ impl EventHandlers {
         }
     }
     
+    /// Undo or redo in the active tab's editor, which keeps the file's history
+    fn step_code_history(&self, app_state: &mut IdeAppState, redo: bool) {
+        let shown = app_state.code_editor.code.clone();
+        if let Some(tab) = app_state.file_manager.get_active_tab_mut() {
+            if let Some(editor) = tab.code_editor.as_mut() {
+                // Text typed since the last edit becomes the step to undo
+                editor.code = shown;
+                if redo { editor.redo() } else { editor.undo() }
+                if tab.content != editor.code {
+                    tab.content = editor.code.clone();
+                    tab.mark_dirty();
+                }
+                app_state.code_editor.code = tab.content.clone();
+                return;
+            }
+        }
+        if redo { app_state.code_editor.redo() } else { app_state.code_editor.undo() }
+    }
+    
     // Helper methods for component operations
     
     fn cut_selected_components(&self, app_state: &mut IdeAppState) {
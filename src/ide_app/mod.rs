@@ -60,6 +60,31 @@ impl IdeApp {
         }
     }
     
+    /// Forward build lifecycle output to plugin build hooks
+    fn notify_plugins_of_build(&mut self, output: &crate::editor::build_system::BuildOutput) {
+        use crate::editor::build_system::BuildOutput;
+        use crate::editor::plugin_system::{BuildEvent, BuildEventType};
+        
+        let (event_type, details) = match output {
+            BuildOutput::Started(command) => (BuildEventType::Started, format!("{:?}", command)),
+            BuildOutput::Finished(result) => (
+                BuildEventType::Completed { success: result.success },
+                format!("exit code {}", result.exit_code),
+            ),
+            BuildOutput::Error(error) => (BuildEventType::Failed { error: error.clone() }, error.clone()),
+            BuildOutput::Progress(_) | BuildOutput::Diagnostic(_) => return,
+        };
+        
+        let project_path = self.app_state.build_system.get_project_path()
+            .map(|path| path.to_path_buf())
+            .unwrap_or_default();
+        self.app_state.plugin_manager.dispatch_build_event(BuildEvent {
+            project_path,
+            event_type,
+            details,
+        });
+    }
+    
     /// Get the default Rust code template for new projects
     fn default_rust_code() -> String {
         r#"fn main() {
@@ -96,14 +121,20 @@ impl eframe::App for IdeApp {
         // Poll build system for output and display it
         let build_outputs = self.app_state.build_system.poll_output();
         for output in build_outputs {
+            self.notify_plugins_of_build(&output);
             match output {
                 crate::editor::build_system::BuildOutput::Started(command) => {
+                    self.app_state.menu.output_panel.clear_diagnostics();
                     self.app_state.menu.output_panel.log(&format!("🔨 Started: {:?}", command));
                 }
                 crate::editor::build_system::BuildOutput::Progress(line) => {
                     self.app_state.menu.output_panel.log(&line);
                 }
+                crate::editor::build_system::BuildOutput::Diagnostic(message) => {
+                    self.app_state.menu.output_panel.add_diagnostic(message);
+                }
                 crate::editor::build_system::BuildOutput::Finished(result) => {
+                    UiManager::handle_ai_edit_check(&mut self.app_state, &result.errors);
                     if result.success {
                         self.app_state.menu.output_panel.log(&format!(
                             "✅ Build completed successfully in {:?}", 
@@ -122,17 +153,36 @@ impl eframe::App for IdeApp {
             }
         }
         
+        // Keep the project search index current
+        self.app_state.update_search_index();
+        
+        // Deliver queued IDE events to plugins and show what they report back
+        self.app_state.plugin_manager.process_ide_events();
+        for message in self.app_state.plugin_manager.drain_messages() {
+            let icon = match message.level {
+                crate::editor::plugin_system::PluginMessageLevel::Info => "🧩",
+                crate::editor::plugin_system::PluginMessageLevel::Warning => "⚠️",
+                crate::editor::plugin_system::PluginMessageLevel::Error => "❌",
+            };
+            self.app_state.menu.output_panel.log(&format!("{} [{}] {}", icon, message.plugin_id, message.text));
+        }
+        
         // Render UI panels in order
         UiManager::render_top_panel(&mut self.app_state, ctx);
         UiManager::render_left_panel(&mut self.app_state, ctx);
         UiManager::render_right_panel(&mut self.app_state, ctx);
         UiManager::render_bottom_panel(&mut self.app_state, ctx);
+        UiManager::render_ai_edit_review(&mut self.app_state, ctx);
+        UiManager::render_refactoring_view(&mut self.app_state, ctx);
         
         // Render main content area
         ContentManager::render_central_panel(&mut self.app_state, &mut self.drag_state, ctx);
         
+        // Exchange edits of the shared file with the collaboration relay
+        self.app_state.update_collaboration(ctx);
+        
         // Update real-time sync between visual designer and code
-        self.app_state.update_realtime_sync();
+        self.app_state.update_realtime_sync(ctx);
         
         // Process any pending events
         self.event_handlers.process_pending_events(&mut self.app_state);
@@ -339,7 +339,7 @@ impl UiManager {
         
         // Create UI components from project component data
         for comp_data in &project.designer_data.components {
-            let component: Box<dyn Component> = match comp_data.component_type.as_str() {
+            let mut component: Box<dyn Component> = match comp_data.component_type.as_str() {
                 "Button" => {
                     let label = comp_data.properties.get("label")
                         .cloned()
@@ -357,6 +357,10 @@ impl UiManager {
                     Box::new(crate::rcl::ui::basic::button::Button::new("Component".to_string()))
                 }
             };
+            // Restore saved properties, including event handler bindings
+            for (name, value) in &comp_data.properties {
+                component.set_property(name, value);
+            }
             
             app_state.components.push(component);
             
@@ -410,6 +414,7 @@ impl UiManager {
             ComponentType::Custom(9),  // File Picker
             ComponentType::Custom(10), // Rich Text Editor
             ComponentType::Custom(11), // Code Editor
+            ComponentType::Custom(12), // WebSocket
         ];
         
         // Group components by category
@@ -451,6 +456,35 @@ impl UiManager {
             });
         }
         
+        // Components contributed by installed packages
+        let mut packaged = app_state.menu.component_library.components.values().cloned().collect::<Vec<_>>();
+        packaged.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+        let mut packaged_actions = Vec::new();
+        if !packaged.is_empty() {
+            AnimatedCollapsing::new(
+                egui::Id::new("palette_category_packages"),
+                "📦 Installed Packages".to_string(),
+                &mut app_state.animation_manager
+            )
+            .default_open(true)
+            .show(ui, |ui| {
+                for metadata in &packaged {
+                    ui.horizontal(|ui| {
+                        ui.label(metadata.icon.as_deref().unwrap_or("🧩"));
+                        if ui.button(&metadata.display_name).on_hover_text(&metadata.description).clicked() {
+                            packaged_actions.push(metadata.display_name.clone());
+                        }
+                    });
+                }
+            });
+        }
+        for display_name in packaged_actions {
+            // Packaged components have no factory in the designer yet; place a named button
+            let component_idx = app_state.components.len();
+            app_state.components.push(Box::new(crate::rcl::ui::basic::button::Button::new(display_name)));
+            app_state.visual_designer.layout.positions.insert(component_idx, egui::Pos2::new(100.0, 100.0));
+        }
+        
         // Execute actions after borrowing is complete
         for (component_type, action, position) in component_actions {
             match action {
@@ -496,6 +530,12 @@ impl UiManager {
                     app_state.active_right_tab = "modern".to_string();
                 }
             }
+            if ui.selectable_label(app_state.active_right_tab == "plugins", "🧩 Plugins").clicked() {
+                app_state.active_right_tab = "plugins".to_string();
+            }
+            if ui.selectable_label(app_state.active_right_tab == "accessibility", "♿ Accessibility").clicked() {
+                app_state.active_right_tab = "accessibility".to_string();
+            }
         });
         
         ui.separator();
@@ -511,6 +551,14 @@ impl UiManager {
             "modern" if app_state.show_modern_ide_panel => {
                 Self::render_modern_ide_panel(app_state, ui);
             }
+            "plugins" => {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    app_state.plugin_manager.render_plugin_manager_ui(ui);
+                });
+            }
+            "accessibility" => {
+                Self::render_accessibility_audit(app_state, ui);
+            }
             _ => {
                 ui.label("No active panel");
             }
@@ -560,6 +608,45 @@ impl UiManager {
         }
     }
     
+    /// Render the accessibility audit of the designed form
+    ///
+    /// The audit runs on demand. Selecting an issue selects its component on
+    /// the canvas; fixing one applies the change and audits again.
+    fn render_accessibility_audit(app_state: &mut IdeAppState, ui: &mut egui::Ui) {
+        use crate::editor::visual_designer::accessibility::AuditAction;
+
+        let visuals = ui.visuals().clone();
+        if ui.button("▶ Run audit").clicked() {
+            let designer = &mut app_state.visual_designer;
+            designer.accessibility.validate(&app_state.components, &designer.layout, &app_state.root_form, &visuals);
+        }
+        ui.separator();
+
+        match app_state.visual_designer.accessibility.render_report(ui) {
+            Some(AuditAction::Select(index)) if index < app_state.components.len() => {
+                app_state.visual_designer.selection.selected.clear();
+                app_state.visual_designer.selection.selected.insert(index);
+                app_state.visual_designer.selection.primary = Some(index);
+                app_state.selected_component = Some(index);
+                app_state.object_inspector.select_component(Some(index));
+            }
+            Some(AuditAction::Fix(issue)) => {
+                let designer = &mut app_state.visual_designer;
+                let description = designer.accessibility.report().issues.get(issue)
+                    .and_then(|issue| issue.fix.as_ref())
+                    .map(|fix| fix.description())
+                    .unwrap_or_default();
+                if designer.accessibility.apply_fix(issue, &mut app_state.components, &mut designer.layout) {
+                    app_state.menu.output_panel.log(&format!("♿ {}", description));
+                } else {
+                    app_state.menu.output_panel.log(&format!("⚠️ Could not apply fix: {}", description));
+                }
+                designer.accessibility.validate(&app_state.components, &designer.layout, &app_state.root_form, &visuals);
+            }
+            _ => {}
+        }
+    }
+
     /// Render the properties inspector
     fn render_properties_inspector(app_state: &mut IdeAppState, ui: &mut egui::Ui) {
         ui.heading("Properties");
@@ -570,8 +657,21 @@ impl UiManager {
                 // Form is selected - show form properties
                 Self::render_form_properties(app_state, ui);
             } else if selected_idx < app_state.components.len() {
-                // Use the basic property inspector
-                app_state.property_inspector.render_component_properties(ui, &mut app_state.components[selected_idx]);
+                ui.horizontal(|ui| {
+                    for (tab, label) in [("properties", "🔧 Properties"), ("events", "⚡ Events")] {
+                        if ui.selectable_label(app_state.active_inspector_tab == tab, label).clicked() {
+                            app_state.active_inspector_tab = tab.to_string();
+                        }
+                    }
+                });
+                ui.separator();
+
+                if app_state.active_inspector_tab == "events" {
+                    Self::render_events_inspector(app_state, ui, selected_idx);
+                } else {
+                    // Use the basic property inspector
+                    app_state.property_inspector.render_component_properties(ui, &mut app_state.components[selected_idx]);
+                }
             } else {
                 ui.label("Invalid component selection");
             }
@@ -581,6 +681,116 @@ impl UiManager {
         }
     }
     
+    /// Render the Events tab for a component
+    ///
+    /// Lists each event with the name of its handler method. Double-clicking an
+    /// event creates the handler in the form source, or jumps to it if it exists.
+    fn render_events_inspector(app_state: &mut IdeAppState, ui: &mut egui::Ui, index: usize) {
+        use crate::editor::form_events::handler_property;
+
+        let component = &mut app_state.components[index];
+        let events = component.events();
+        if events.is_empty() {
+            ui.label(format!("{} has no events", component.name()));
+            return;
+        }
+
+        let mut open_event = None;
+        egui::Grid::new("events_inspector").num_columns(2).striped(true).show(ui, |ui| {
+            for event in &events {
+                let property = handler_property(&event.name);
+                let name = ui.add(egui::Label::new(&event.name).sense(egui::Sense::click()))
+                    .on_hover_text(&event.description);
+                let mut handler = component.get_property(&property).unwrap_or_default();
+                let field = ui.text_edit_singleline(&mut handler);
+                if field.changed() {
+                    component.set_property(&property, handler.trim());
+                }
+                if name.double_clicked() || field.double_clicked() {
+                    open_event = Some(event.name.clone());
+                }
+                ui.end_row();
+            }
+        });
+        ui.small("Double-click an event to create or show its handler");
+
+        if let Some(event) = open_event {
+            Self::open_event_handler(app_state, index, &event);
+        }
+    }
+
+    /// Create or jump to the handler bound to `event` of the component at `index`
+    ///
+    /// An unbound event gets a default handler name first. The form source is
+    /// opened in a code tab with the cursor on the handler's signature.
+    fn open_event_handler(app_state: &mut IdeAppState, index: usize, event: &str) {
+        use crate::editor::form_events::{default_handler_name, designer_component_id, ensure_handler, handler_property};
+
+        let component = &mut app_state.components[index];
+        let property = handler_property(event);
+        let mut handler = component.get_property(&property).unwrap_or_default();
+        if handler.trim().is_empty() {
+            handler = default_handler_name(&designer_component_id(component.name(), index), event);
+            component.set_property(&property, &handler);
+        }
+
+        let Some((path, form_type)) = Self::form_source(app_state) else {
+            app_state.menu.output_panel.log("⚠️ Open a form or project to add event handlers");
+            return;
+        };
+        let source = match app_state.file_manager.open_tabs.get(&path) {
+            Some(tab) => tab.get_current_content(),
+            None => std::fs::read_to_string(&path).unwrap_or_default(),
+        };
+
+        match ensure_handler(&source, &form_type, &handler) {
+            Ok((updated, location)) => {
+                if let Err(e) = app_state.file_manager.open_file(path.clone(), updated.clone()) {
+                    app_state.menu.output_panel.log(&format!("❌ Cannot open {}: {}", path.display(), e));
+                    return;
+                }
+                if let Some(tab) = app_state.file_manager.get_active_tab_mut() {
+                    if let Some(editor) = tab.code_editor.as_mut() {
+                        if location.created {
+                            editor.code = updated;
+                            editor.mark_dirty();
+                        }
+                        editor.cursor_pos = (location.line, 0);
+                    }
+                    if location.created {
+                        tab.mark_dirty();
+                    }
+                }
+                let action = if location.created { "Created" } else { "Opened" };
+                app_state.menu.output_panel.log(&format!("⚡ {} handler {} in {}", action, handler, path.display()));
+            }
+            Err(e) => {
+                app_state.menu.output_panel.log(&format!("❌ Cannot add handler {}: {}", handler, e));
+            }
+        }
+    }
+
+    /// Source file and type name of the form being designed
+    ///
+    /// A design file's form lives in the `.rs` file next to it; otherwise the
+    /// project's generated form is used.
+    fn form_source(app_state: &IdeAppState) -> Option<(std::path::PathBuf, String)> {
+        use crate::editor::file_manager::FileType;
+        use crate::editor::form_events::{form_source_path, form_type_name};
+
+        if let Some(tab) = app_state.file_manager.get_active_tab() {
+            if tab.file_type == FileType::UIDesign {
+                let stem = tab.path.file_stem()?.to_string_lossy().to_string();
+                return Some((tab.path.with_extension("rs"), form_type_name(&stem)));
+            }
+        }
+        let project = app_state.project_manager.get_current_project()?;
+        Some((
+            form_source_path(&project.metadata.root_path, &project.metadata.name),
+            form_type_name(&project.metadata.name),
+        ))
+    }
+
     /// Render form properties when form is selected using advanced property inspector
     fn render_form_properties(app_state: &mut IdeAppState, ui: &mut egui::Ui) {
         // Use the advanced property inspector for the form
@@ -646,31 +856,168 @@ impl UiManager {
     fn render_output_panel(app_state: &mut IdeAppState, ui: &mut egui::Ui) {
         ui.heading("Build Output");
         ui.separator();
+        if let Some(action) = app_state.menu.output_panel.render_diagnostics(ui) {
+            Self::handle_diagnostic_action(app_state, action);
+        }
         app_state.menu.output_panel.ui(ui);
     }
     
+    /// Open a diagnostic's span or apply one of its quick fixes
+    fn handle_diagnostic_action(app_state: &mut IdeAppState, action: crate::editor::output_panel::DiagnosticAction) {
+        use crate::editor::build_system::resolve_source_path;
+        use crate::editor::output_panel::DiagnosticAction;
+        use crate::editor::text_buffer::TextBuffer;
+        
+        let project_root = app_state.build_system.get_project_path()
+            .map(|path| path.to_path_buf())
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_default();
+        
+        match action {
+            DiagnosticAction::Open { file, line, column } => {
+                let path = resolve_source_path(&project_root, &file);
+                Self::open_file_in_editor(app_state, path.clone());
+                if let Some(tab) = app_state.file_manager.open_tabs.get_mut(&path) {
+                    if let Some(editor) = tab.code_editor.as_mut() {
+                        editor.cursor_pos = (line.saturating_sub(1), column.saturating_sub(1));
+                    }
+                }
+            }
+            DiagnosticAction::ApplyFix(fix) => {
+                // Offsets refer to the file as it was compiled, so unsaved edits would shift them
+                let paths: Vec<_> = fix.files().iter().map(|file| resolve_source_path(&project_root, file)).collect();
+                if paths.iter().any(|path| app_state.file_manager.open_tabs.get(path).is_some_and(|tab| tab.is_dirty)) {
+                    app_state.menu.output_panel.log("⚠️ Save the file before applying this fix");
+                    return;
+                }
+                
+                // Check every file before touching any, so a stale fix leaves nothing half applied
+                let mut planned = Vec::new();
+                for (file_name, path) in fix.files().into_iter().zip(paths) {
+                    if !app_state.file_manager.open_tabs.contains_key(&path) {
+                        Self::open_file_in_editor(app_state, path.clone());
+                    }
+                    let Some(editor) = app_state.file_manager.open_tabs.get(&path).and_then(|tab| tab.code_editor.as_ref()) else {
+                        app_state.menu.output_panel.log(&format!("❌ Failed to apply fix: {} is not open as code", path.display()));
+                        return;
+                    };
+                    match fix.text_edits(file_name, &TextBuffer::from_string(editor.code.clone())) {
+                        Ok(edits) => planned.push((path, edits)),
+                        Err(e) => {
+                            app_state.menu.output_panel.log(&format!("❌ Failed to apply fix: {}", e));
+                            return;
+                        }
+                    }
+                }
+                
+                for (path, edits) in planned {
+                    let Some(tab) = app_state.file_manager.open_tabs.get_mut(&path) else { continue };
+                    let Some(editor) = tab.code_editor.as_mut() else { continue };
+                    if let Err(e) = editor.apply_edits("Quick fix", &edits) {
+                        app_state.menu.output_panel.log(&format!("❌ Failed to apply fix: {}", e));
+                        return;
+                    }
+                    tab.content = editor.code.clone();
+                    tab.mark_dirty();
+                    if let Err(e) = app_state.file_manager.save_tab(&path) {
+                        app_state.menu.output_panel.log(&format!("❌ Failed to save {}: {}", path.display(), e));
+                        return;
+                    }
+                    if let Some(editor) = app_state.file_manager.open_tabs.get_mut(&path).and_then(|tab| tab.code_editor.as_mut()) {
+                        editor.mark_clean();
+                    }
+                }
+                app_state.menu.output_panel.log(&format!("🔧 Applied fix: {}", fix.message));
+            }
+        }
+    }
+    
     /// Render the AI assistant panel
     fn render_ai_panel(app_state: &mut IdeAppState, ui: &mut egui::Ui) {
+        use crate::ai_agent::AiTaskType;
+        use crate::ai_provider::{AiError, AiStreamEvent};
+        
         ui.heading("AI Assistant");
         ui.separator();
         
+        Self::render_ai_provider_settings(app_state, ui);
+        
         // AI prompt input
         ui.horizontal(|ui| {
             ui.label("Prompt:");
             ui.text_edit_singleline(&mut app_state.ai_prompt);
-            if ui.button("Send").clicked() && !app_state.ai_prompt.is_empty() {
-                // TODO: Integrate with AI system
-                app_state.ai_response = format!("AI response to: {}", app_state.ai_prompt);
-                app_state.ai_prompt.clear();
+            egui::ComboBox::from_id_source("ai_task_type")
+                .selected_text(format!("{:?}", app_state.ai_task_type))
+                .show_ui(ui, |ui| {
+                    for task_type in AiTaskType::ALL {
+                        let label = format!("{:?}", task_type);
+                        ui.selectable_value(&mut app_state.ai_task_type, task_type, label);
+                    }
+                });
+            if app_state.ai_pending {
+                ui.spinner();
+                if ui.button("⏹ Stop").clicked() {
+                    if let Some(exchange) = &app_state.ai_task {
+                        exchange.stream.cancel();
+                    }
+                }
+            } else {
+                if ui.button("Send").clicked() && !app_state.ai_prompt.is_empty() {
+                    if let Some(agent) = &app_state.ai_agent {
+                        app_state.ai_task = Some(agent.stream_with_context(&app_state.ai_prompt, app_state.ai_task_type.clone()));
+                        app_state.ai_pending = true;
+                        app_state.ai_response.clear();
+                        app_state.ai_prompt.clear();
+                    }
+                }
+                let active = app_state.file_manager.active_tab.clone();
+                let propose = ui.add_enabled(active.is_some(), egui::Button::new("✏ Propose edits"))
+                    .on_hover_text("Ask for edits to the active file and review them as a diff");
+                if propose.clicked() && !app_state.ai_prompt.is_empty() {
+                    let instruction = std::mem::take(&mut app_state.ai_prompt);
+                    let task_type = app_state.ai_task_type.clone();
+                    Self::request_ai_edits(app_state, &instruction, active.into_iter().collect(), task_type);
+                }
+            }
+            if app_state.ai_edits.can_undo() && ui.button("↶ Undo AI edits").clicked() {
+                Self::undo_ai_edits(app_state);
             }
         });
         
+        // Append streamed tokens as they arrive
+        let mut proposed_edits = None;
+        if let (Some(agent), Some(exchange)) = (app_state.ai_agent.as_mut(), app_state.ai_task.as_mut()) {
+            for event in agent.poll_exchange(exchange) {
+                match event {
+                    AiStreamEvent::Token(token) => app_state.ai_response.push_str(&token),
+                    AiStreamEvent::Finished(response) => {
+                        if exchange.expects_edits {
+                            proposed_edits = Some(response);
+                        }
+                    }
+                    AiStreamEvent::Failed(AiError::Cancelled) => app_state.ai_response.push_str("\n[Stopped]"),
+                    AiStreamEvent::Failed(error) => {
+                        app_state.ai_response.push_str(&format!("\nAI request failed: {}", error));
+                    }
+                }
+            }
+            if exchange.stream.is_finished() {
+                app_state.ai_task = None;
+                app_state.ai_pending = false;
+            } else {
+                ui.ctx().request_repaint_after(std::time::Duration::from_millis(50));
+            }
+        }
+        if let Some(response) = proposed_edits {
+            Self::review_ai_edits(app_state, &response);
+        }
+        
         ui.separator();
         
         // AI response display
         if !app_state.ai_response.is_empty() {
             ui.label("Response:");
-            egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
                 ui.label(&app_state.ai_response);
             });
         }
@@ -679,6 +1026,364 @@ impl UiManager {
         app_state.smart_ai.render_ai_panel(ui);
     }
     
+    /// Ask the AI agent for edits to `paths`, streamed into the AI panel
+    fn request_ai_edits(app_state: &mut IdeAppState, instruction: &str, paths: Vec<std::path::PathBuf>, task_type: crate::ai_agent::AiTaskType) {
+        if app_state.ai_task.is_some() {
+            app_state.menu.output_panel.log("⚠️ An AI request is already running");
+            return;
+        }
+        app_state.ai_edits.set_root(Self::project_root(app_state));
+        Self::sync_ai_edits(app_state);
+        
+        let mut files = Vec::new();
+        for path in paths {
+            let text = match app_state.file_manager.open_tabs.get(&path) {
+                Some(tab) => tab.get_current_content(),
+                None => std::fs::read_to_string(&path).unwrap_or_default(),
+            };
+            files.push((app_state.ai_edits.relative(&path), text));
+        }
+        if let Some(agent) = &app_state.ai_agent {
+            app_state.ai_task = Some(agent.stream_edits(instruction, &files, task_type));
+            app_state.ai_pending = true;
+            app_state.ai_response.clear();
+            app_state.show_ai_panel = true;
+        }
+    }
+    
+    /// Bring the AI edit buffers up to date with the open tabs
+    fn sync_ai_edits(app_state: &mut IdeAppState) {
+        for (path, tab) in &app_state.file_manager.open_tabs {
+            app_state.ai_edits.sync(path, &tab.get_current_content());
+        }
+    }
+    
+    /// Validate a finished edit response and open it for review
+    fn review_ai_edits(app_state: &mut IdeAppState, response: &str) {
+        use crate::editor::ai_edits::{parse_edits, EditReview};
+        
+        Self::sync_ai_edits(app_state);
+        match parse_edits(response) {
+            Ok(edits) => {
+                let proposal = app_state.ai_edits.validate(edits);
+                for (edit, error) in &proposal.invalid {
+                    app_state.menu.output_panel.log(&format!("⚠️ Skipped AI edit to {}: {}", edit.file, error));
+                }
+                if proposal.hunks.is_empty() {
+                    app_state.menu.output_panel.log("🤖 The AI proposed no applicable edits");
+                } else {
+                    app_state.ai_review = Some(EditReview::new(proposal));
+                }
+            }
+            Err(e) => app_state.ai_response.push_str(&format!("\n{}", e)),
+        }
+    }
+    
+    /// Show the review window of proposed AI edits and carry out the user's decision
+    pub fn render_ai_edit_review(app_state: &mut IdeAppState, ctx: &egui::Context) {
+        use crate::editor::ai_edits::EditReviewAction;
+        
+        let Some(review) = app_state.ai_review.as_mut() else { return };
+        match review.show(ctx, &mut app_state.ai_check_after_apply) {
+            Some(EditReviewAction::Apply) => {
+                Self::sync_ai_edits(app_state);
+                let Some(review) = app_state.ai_review.take() else { return };
+                match app_state.ai_edits.apply(&review.proposal) {
+                    Ok(changed) => {
+                        let hunks = review.proposal.accepted().count();
+                        app_state.menu.output_panel.log(&format!("🤖 Applied {} AI edit(s) to {} file(s)", hunks, changed.len()));
+                        let accepted: Vec<_> = review.proposal.accepted().collect();
+                        Self::write_back_ai_edits(app_state, &changed, &accepted);
+                        if app_state.ai_check_after_apply {
+                            Self::check_ai_edits(app_state, &changed);
+                        }
+                    }
+                    Err(e) => {
+                        app_state.menu.output_panel.log(&format!("❌ Failed to apply AI edits: {}", e));
+                        app_state.ai_review = Some(review);
+                    }
+                }
+            }
+            Some(EditReviewAction::Discard) => app_state.ai_review = None,
+            None => {}
+        }
+    }
+    
+    /// Undo the last applied AI edits in every file they changed
+    fn undo_ai_edits(app_state: &mut IdeAppState) {
+        Self::sync_ai_edits(app_state);
+        match app_state.ai_edits.undo() {
+            Ok(changed) => {
+                Self::write_back_ai_edits(app_state, &changed, &[]);
+                app_state.menu.output_panel.log(&format!("↶ Undid AI edits in {} file(s)", changed.len()));
+            }
+            Err(e) => app_state.menu.output_panel.log(&format!("⚠️ {}", e)),
+        }
+    }
+    
+    /// Show the edited buffers in their tabs, writing files that are not open
+    ///
+    /// Open tabs get `hunks` of their file, or the whole change when there are
+    /// none, as one step of the editor's undo history.
+    fn write_back_ai_edits(app_state: &mut IdeAppState, paths: &[std::path::PathBuf], hunks: &[&crate::editor::ai_edits::EditHunk]) {
+        for path in paths {
+            let Some(text) = app_state.ai_edits.buffer(path).map(|buffer| buffer.to_string()) else { continue };
+            match app_state.file_manager.open_tabs.get_mut(path) {
+                Some(tab) => {
+                    if let Some(editor) = tab.code_editor.as_mut() {
+                        let edits: Vec<_> = hunks.iter()
+                            .filter(|hunk| hunk.path == *path)
+                            .map(|hunk| (hunk.range.clone(), hunk.replacement.clone()))
+                            .collect();
+                        let applied = if edits.is_empty() {
+                            editor.replace_text("Undo AI edit", &text)
+                        } else {
+                            editor.apply_edits("AI edit", &edits)
+                        };
+                        // The buffers agree unless the tab changed since the sync
+                        if applied.is_err() || editor.code != text {
+                            editor.code = text.clone();
+                        }
+                    }
+                    tab.content = text;
+                    tab.mark_dirty();
+                }
+                None => {
+                    // Edits may create files in directories that do not exist yet
+                    let written = path.parent().map_or(Ok(()), std::fs::create_dir_all).and_then(|_| std::fs::write(path, &text));
+                    if let Err(e) = written {
+                        app_state.menu.output_panel.log(&format!("❌ Failed to write {}: {}", path.display(), e));
+                    }
+                }
+            }
+        }
+    }
+    
+    /// Save the edited files and run `cargo check`, remembering the errors known so far
+    fn check_ai_edits(app_state: &mut IdeAppState, paths: &[std::path::PathBuf]) {
+        use crate::editor::build_system::MessageLevel;
+        
+        for path in paths {
+            if let Err(e) = app_state.file_manager.save_tab(path) {
+                app_state.menu.output_panel.log(&format!("❌ Failed to save {}: {}", path.display(), e));
+            }
+        }
+        let baseline = app_state.menu.output_panel.diagnostics.iter()
+            .filter(|diagnostic| diagnostic.level == MessageLevel::Error)
+            .cloned()
+            .collect();
+        match app_state.build_system.check() {
+            Ok(()) => app_state.ai_check_baseline = Some(baseline),
+            Err(e) => app_state.menu.output_panel.log(&format!("❌ Failed to start cargo check: {}", e)),
+        }
+    }
+    
+    /// Send the errors introduced by the last AI edits back to the model
+    ///
+    /// Called with the errors of every finished build; does nothing unless
+    /// that build was checking AI edits.
+    pub fn handle_ai_edit_check(app_state: &mut IdeAppState, errors: &[crate::editor::build_system::CompilerMessage]) {
+        use crate::editor::ai_edits::{error_feedback, new_errors};
+        use crate::editor::build_system::resolve_source_path;
+        
+        let Some(baseline) = app_state.ai_check_baseline.take() else { return };
+        let errors = new_errors(&baseline, errors);
+        if errors.is_empty() {
+            app_state.menu.output_panel.log("✅ AI edits introduced no new errors");
+            return;
+        }
+        app_state.menu.output_panel.log(&format!("🤖 AI edits introduced {} new error(s), asking for a fix", errors.len()));
+        let mut paths: Vec<std::path::PathBuf> = errors.iter()
+            .filter_map(|error| error.file.as_deref())
+            .map(|file| resolve_source_path(app_state.ai_edits.root(), file))
+            .collect();
+        paths.sort();
+        paths.dedup();
+        Self::request_ai_edits(app_state, &error_feedback(&errors), paths, crate::ai_agent::AiTaskType::BugFixing);
+    }
+    
+    /// Root of the project being built, or the working directory
+    fn project_root(app_state: &IdeAppState) -> std::path::PathBuf {
+        app_state.build_system.get_project_path()
+            .map(|path| path.to_path_buf())
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_default()
+    }
+    
+    /// Open the refactoring window on the code editor's selection in the active file
+    pub fn open_refactoring_view(app_state: &mut IdeAppState) {
+        use crate::editor::advanced_refactoring::{CodeSelection, RefactoringView};
+        
+        let Some(path) = app_state.file_manager.active_tab.clone() else {
+            app_state.menu.output_panel.log("❌ No active file to refactor");
+            return;
+        };
+        let root = Self::project_root(app_state);
+        if app_state.refactoring_engine.workspace_root() != root {
+            app_state.refactoring_engine.set_workspace_root(root);
+        }
+        // Refactorings read the files from disk
+        Self::save_for_refactoring(app_state);
+        let editor = &app_state.code_editor;
+        let selection = CodeSelection::new(path, &editor.code, editor.selection_range());
+        app_state.refactoring_view = Some(RefactoringView::new(&app_state.refactoring_engine, selection));
+    }
+    
+    /// Show the refactoring window and carry out the user's decision
+    pub fn render_refactoring_view(app_state: &mut IdeAppState, ctx: &egui::Context) {
+        use crate::editor::advanced_refactoring::RefactoringViewAction;
+        
+        let Some(view) = app_state.refactoring_view.as_mut() else { return };
+        let Some(action) = view.show(ctx, &app_state.refactoring_engine) else { return };
+        match action {
+            RefactoringViewAction::Preview => {
+                Self::save_for_refactoring(app_state);
+                let engine = &app_state.refactoring_engine;
+                if let Some(view) = app_state.refactoring_view.as_mut() {
+                    let operation_id = view.operation_id.clone().unwrap_or_default();
+                    view.preview = Some(engine.preview_refactoring(&operation_id, &view.selection, &view.parameter_values()));
+                }
+            }
+            RefactoringViewAction::Apply => {
+                Self::save_for_refactoring(app_state);
+                let Some(view) = app_state.refactoring_view.as_mut() else { return };
+                let operation_id = view.operation_id.clone().unwrap_or_default();
+                match app_state.refactoring_engine.execute_refactoring(&operation_id, &view.selection, view.parameter_values()) {
+                    Ok(result) => {
+                        for warning in &result.warnings {
+                            app_state.menu.output_panel.log(&format!("⚠️ {}", warning));
+                        }
+                        let paths: Vec<_> = result.applied_changes.iter().map(|change| change.file_path.clone()).collect();
+                        app_state.menu.output_panel.log(&format!("🔧 Applied {} change(s)", result.applied_changes.len()));
+                        app_state.refactoring_view = None;
+                        Self::reload_refactored_tabs(app_state, paths);
+                    }
+                    Err(e) => view.preview = Some(Err(e)),
+                }
+            }
+            RefactoringViewAction::Undo | RefactoringViewAction::Redo => {
+                Self::save_for_refactoring(app_state);
+                let result = if action == RefactoringViewAction::Undo {
+                    app_state.refactoring_engine.undo_last_refactoring()
+                } else {
+                    app_state.refactoring_engine.redo_refactoring()
+                };
+                match result {
+                    Ok(transaction) => {
+                        let verb = if action == RefactoringViewAction::Undo { "Undid" } else { "Redid" };
+                        app_state.menu.output_panel.log(&format!("🔧 {} {}", verb, transaction.operation_name));
+                        let paths = transaction.changes.iter().map(|change| change.file_path.clone()).collect();
+                        Self::reload_refactored_tabs(app_state, paths);
+                    }
+                    Err(e) => app_state.menu.output_panel.log(&format!("❌ {}", e)),
+                }
+            }
+            RefactoringViewAction::Close => app_state.refactoring_view = None,
+        }
+    }
+    
+    /// Write unsaved tabs to disk so refactorings see what the editor shows
+    fn save_for_refactoring(app_state: &mut IdeAppState) {
+        for result in app_state.file_manager.save_all_tabs() {
+            if let Err(e) = result {
+                app_state.menu.output_panel.log(&format!("❌ Failed to save before refactoring: {}", e));
+            }
+        }
+    }
+    
+    /// Reload the open tabs of files a refactoring rewrote
+    fn reload_refactored_tabs(app_state: &mut IdeAppState, mut paths: Vec<std::path::PathBuf>) {
+        paths.sort();
+        paths.dedup();
+        paths.retain(|path| app_state.file_manager.open_tabs.contains_key(path));
+        for path in paths {
+            if let Err(e) = app_state.file_manager.reload_tab(&path) {
+                app_state.menu.output_panel.log(&format!("❌ Failed to reload {}: {}", path.display(), e));
+            }
+        }
+    }
+    
+    /// Render the model backend settings and the model of the selected task type
+    fn render_ai_provider_settings(app_state: &mut IdeAppState, ui: &mut egui::Ui) {
+        use crate::ai_provider::{ModelListing, ProviderKind};
+        
+        if let Some(result) = app_state.ai_model_listing.as_ref().and_then(ModelListing::poll) {
+            app_state.ai_model_listing = None;
+            match result {
+                Ok(models) => {
+                    app_state.menu.output_panel.log(&format!("🤖 {} models available", models.len()));
+                    app_state.ai_models = models;
+                }
+                Err(e) => app_state.menu.output_panel.log(&format!("❌ Cannot list models: {}", e)),
+            }
+        } else if app_state.ai_model_listing.is_some() {
+            ui.ctx().request_repaint();
+        }
+        
+        let Some(agent) = app_state.ai_agent.as_mut() else { return };
+        let provider = agent.provider();
+        egui::CollapsingHeader::new(format!("⚙ Provider: {} ({}) at {}", provider.name(), provider.default_model(), provider.base_url()))
+            .id_source("ai_provider_settings")
+            .show(ui, |ui| {
+                let models = &app_state.ai_models;
+                let settings = &mut app_state.ai_settings;
+                ui.horizontal(|ui| {
+                    for kind in ProviderKind::ALL {
+                        if ui.radio_value(&mut settings.kind, kind, kind.label()).changed() {
+                            settings.base_url = kind.default_url().to_string();
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("URL:");
+                    ui.text_edit_singleline(&mut settings.base_url);
+                });
+                if settings.kind == ProviderKind::OpenAiCompatible {
+                    ui.horizontal(|ui| {
+                        ui.label("API key:");
+                        ui.add(egui::TextEdit::singleline(&mut settings.api_key).password(true));
+                    });
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Default model:");
+                    ui.text_edit_singleline(&mut settings.default_model);
+                    Self::model_menu(ui, "ai_default_model", models, &mut settings.default_model);
+                    let listing = app_state.ai_model_listing.is_some();
+                    if ui.add_enabled(!listing, egui::Button::new("⟳")).on_hover_text("List the server's models").clicked() {
+                        app_state.ai_model_listing = Some(ModelListing::start(settings.build()));
+                    }
+                    if ui.button("Apply").clicked() {
+                        agent.set_provider(settings.build());
+                    }
+                });
+                
+                let task_type = app_state.ai_task_type.clone();
+                let mut model = agent.task_model(&task_type).unwrap_or_default().to_string();
+                ui.horizontal(|ui| {
+                    ui.label(format!("Model for {:?}:", task_type));
+                    let hint = agent.provider().default_model().to_string();
+                    let edited = ui.add(egui::TextEdit::singleline(&mut model).hint_text(hint)).changed();
+                    if Self::model_menu(ui, "ai_task_model", models, &mut model) || edited {
+                        agent.set_task_model(task_type, &model);
+                    }
+                });
+            });
+    }
+    
+    /// Drop-down of the listed models; returns whether one was picked
+    fn model_menu(ui: &mut egui::Ui, id: &str, models: &[String], model: &mut String) -> bool {
+        if models.is_empty() {
+            return false;
+        }
+        let mut picked = false;
+        egui::ComboBox::from_id_source(id).selected_text("▾").width(24.0).show_ui(ui, |ui| {
+            for name in models {
+                picked |= ui.selectable_value(model, name.clone(), name).clicked();
+            }
+        });
+        picked
+    }
+    
     /// Add a new component to the form
     fn add_component_to_form(app_state: &mut IdeAppState, component_type: super::drag_drop::ComponentType, position: egui::Pos2) {
         use crate::rcl::ui::component::Component;
@@ -699,6 +1404,9 @@ impl UiManager {
             super::drag_drop::ComponentType::Slider => {
                 Box::new(crate::rcl::ui::basic::slider::Slider::new(0.0, 0.0, 100.0))
             }
+            super::drag_drop::ComponentType::Custom(12) => {
+                Box::new(crate::rcl::network::websocket::WebSocketComponent::default())
+            }
             _ => {
                 // Default to button for unsupported types
                 Box::new(crate::rcl::ui::basic::button::Button::new("New Component".to_string()))
@@ -2,10 +2,12 @@
 pub mod rcl;
 pub mod editor;
 pub mod ai_agent;
+pub mod ai_provider;
 pub mod ai_development_assistant;
 pub mod ide_app;
 pub mod shared;
 pub mod core;
+pub mod cli;
 
 // Re-export main modules for easy access
 pub use ide_app::IdeApp;
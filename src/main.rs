@@ -8,19 +8,30 @@
 
 mod rcl;      // Rust Component Library - UI, system, and network components
 mod ai_agent; // AI integration for code assistance and automation
+mod ai_provider; // Model backends (Ollama, OpenAI-compatible) with streaming
 mod ai_development_assistant; // AI development assistance features
 mod ide_app;  // Main IDE application logic and UI
 mod editor;   // IDE editor features: panels, actions, project management
 mod core;     // Core infrastructure: logging, events, services
 mod shared;   // Shared utilities and serialization helpers
+mod cli;      // Headless command-line mode
 
 /// Main entry point for the Rust RAD IDE application
 /// 
 /// Initializes logging, then launches the eframe/egui application with default settings
-/// and starts the main IDE interface.
+/// and starts the main IDE interface. When command-line arguments are given, the
+/// matching headless command runs instead and its exit code is returned.
 fn main() {
     // Initialize logging as early as possible
     core::logging::init_logging();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        #[cfg(all(windows, not(debug_assertions)))]
+        attach_parent_console();
+        let code = cli::run(&args, &mut std::io::stdout(), &mut std::io::stderr());
+        std::process::exit(code);
+    }
     
     #[cfg(feature = "logging")]
     tracing::info!("Starting Rust RAD IDE");
@@ -37,3 +48,17 @@ fn main() {
         }),
     ).unwrap();
 }
+
+/// Connect stdout and stderr to the console of the shell that ran the command
+///
+/// Release builds use the Windows GUI subsystem, which starts without a
+/// console, so headless output would otherwise be lost.
+#[cfg(all(windows, not(debug_assertions)))]
+fn attach_parent_console() {
+    use winapi::um::wincon::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    // Without a parent console this fails and redirected output is unaffected
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
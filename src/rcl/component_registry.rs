@@ -7,6 +7,11 @@ use std::collections::HashMap;
 use std::any::{Any, TypeId};
 use serde::{Serialize, Deserialize};
 
+/// `#[derive(ComponentMeta)]`, implementing [`ComponentMeta`] and [`FromProperties`]
+#[cfg(feature = "proc-macros")]
+#[allow(unused_imports)] // Only used by library users; the binary re-declares this module
+pub use ide_rs_derive::ComponentMeta;
+
 /// Central registry for all RCL components with metadata and schemas
 pub struct ComponentRegistry {
     /// Registered components by name
@@ -390,6 +395,18 @@ pub struct EventDefinition {
     pub bubbles: bool,
 }
 
+impl EventDefinition {
+    /// Create a non-bubbling event without parameters
+    pub fn new(name: &str, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            parameters: Vec::new(),
+            bubbles: false,
+        }
+    }
+}
+
 /// Event parameter
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EventParameter {
@@ -413,6 +430,133 @@ pub trait ComponentMeta {
     fn events() -> Vec<EventDefinition>;
 }
 
+/// Trait for components that can be created from property values
+pub trait FromProperties: Sized {
+    /// Build the component; missing or mistyped properties take their defaults
+    fn from_properties(properties: &HashMap<String, PropertyValue>) -> Self;
+
+    /// Factory for [`ComponentRegistry::register_component`]
+    fn factory() -> ComponentFactory
+    where
+        Self: 'static,
+    {
+        Box::new(|properties| Box::new(Self::from_properties(properties)))
+    }
+}
+
+/// Conversion of a property value to a component field
+pub trait FromPropertyValue: Sized {
+    /// Convert the value, or `None` if it has the wrong type
+    fn from_property_value(value: &PropertyValue) -> Option<Self>;
+}
+
+impl FromPropertyValue for PropertyValue {
+    fn from_property_value(value: &PropertyValue) -> Option<Self> {
+        Some(value.clone())
+    }
+}
+
+impl FromPropertyValue for String {
+    fn from_property_value(value: &PropertyValue) -> Option<Self> {
+        match value {
+            PropertyValue::String(value)
+            | PropertyValue::EventHandler(value)
+            | PropertyValue::ComponentRef(value)
+            | PropertyValue::ResourceRef(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl FromPropertyValue for bool {
+    fn from_property_value(value: &PropertyValue) -> Option<Self> {
+        match value {
+            PropertyValue::Boolean(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! impl_from_integer_property {
+    ($($ty:ty),*) => {
+        $(
+            impl FromPropertyValue for $ty {
+                fn from_property_value(value: &PropertyValue) -> Option<Self> {
+                    match value {
+                        PropertyValue::Integer(value) => <$ty>::try_from(*value).ok(),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer_property!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl FromPropertyValue for f64 {
+    fn from_property_value(value: &PropertyValue) -> Option<Self> {
+        match value {
+            PropertyValue::Float(value) => Some(*value),
+            PropertyValue::Integer(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+}
+
+impl FromPropertyValue for f32 {
+    fn from_property_value(value: &PropertyValue) -> Option<Self> {
+        f64::from_property_value(value).map(|value| value as f32)
+    }
+}
+
+impl<T: FromPropertyValue> FromPropertyValue for Option<T> {
+    fn from_property_value(value: &PropertyValue) -> Option<Self> {
+        match value {
+            PropertyValue::Null | PropertyValue::Undefined => Some(None),
+            value => T::from_property_value(value).map(Some),
+        }
+    }
+}
+
+impl<T: FromPropertyValue> FromPropertyValue for Vec<T> {
+    fn from_property_value(value: &PropertyValue) -> Option<Self> {
+        match value {
+            PropertyValue::Array(items) => items.iter().map(T::from_property_value).collect(),
+            _ => None,
+        }
+    }
+}
+
+impl<T: FromPropertyValue> FromPropertyValue for HashMap<String, T> {
+    fn from_property_value(value: &PropertyValue) -> Option<Self> {
+        match value {
+            PropertyValue::Object(entries) => entries
+                .iter()
+                .map(|(key, value)| T::from_property_value(value).map(|value| (key.clone(), value)))
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! impl_from_ui_property {
+    ($($ty:ident),*) => {
+        $(
+            impl FromPropertyValue for $ty {
+                fn from_property_value(value: &PropertyValue) -> Option<Self> {
+                    match value {
+                        PropertyValue::$ty(value) => Some(value.clone()),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_ui_property!(Color, Size, Position, Margin, Padding, FlexDirection, Alignment, JustifyContent);
+
 impl ComponentRegistry {
     /// Create new component registry
     pub fn new() -> Self {
@@ -446,7 +590,12 @@ impl ComponentRegistry {
         }
     }
     
-    /// Register a component with direct metadata (used by derive macro)
+    /// Register a component that derives [`ComponentMeta`] and [`FromProperties`]
+    pub fn register<T: ComponentMeta + FromProperties + 'static>(&mut self) {
+        self.register_component::<T>(T::factory());
+    }
+
+    /// Register a component with direct metadata
     pub fn register_component_metadata(&mut self, metadata: ComponentMetadata) {
         let component_type = metadata.component_type.clone();
         let schema = metadata.schema.clone();
@@ -460,6 +609,14 @@ impl ComponentRegistry {
         self.inspectors.insert(component_type, Box::new(move |_| inspector.clone()));
     }
 
+    /// Remove a registered component, returning its metadata
+    pub fn unregister_component(&mut self, component_type: &str) -> Option<ComponentMetadata> {
+        self.schemas.remove(component_type);
+        self.factories.remove(component_type);
+        self.inspectors.remove(component_type);
+        self.components.remove(component_type)
+    }
+
     /// Get component metadata by type
     pub fn get_metadata(&self, component_type: &str) -> Option<&ComponentMetadata> {
         self.components.get(component_type)
@@ -732,28 +889,69 @@ impl ComponentRegistry {
     /// Validate individual property value
     fn validate_property_value(&self, value: &PropertyValue, expected_type: &PropertyType, constraints: &[PropertyConstraint]) -> Result<(), String> {
         // Type validation
-        match (value, expected_type) {
-            (PropertyValue::String(_), PropertyType::String) => {}
-            (PropertyValue::Integer(_), PropertyType::Integer) => {}
-            (PropertyValue::Float(_), PropertyType::Float) => {}
-            (PropertyValue::Boolean(_), PropertyType::Boolean) => {}
-            (PropertyValue::Color(_), PropertyType::Color) => {}
-            _ => return Err(format!("Type mismatch: expected {:?}", expected_type)),
+        let type_matches = match (value, expected_type) {
+            // Unset optional properties
+            (PropertyValue::Null | PropertyValue::Undefined, _) => true,
+            (_, PropertyType::Custom(_)) => true,
+            (PropertyValue::String(value), PropertyType::Enum(options)) => {
+                if !options.contains(value) {
+                    return Err(format!("Value {} is not one of {}", value, options.join(", ")));
+                }
+                true
+            }
+            (PropertyValue::Array(items), PropertyType::Array(item_type)) => {
+                for item in items {
+                    self.validate_property_value(item, item_type, &[])?;
+                }
+                true
+            }
+            (_, PropertyType::Union(types)) => types.iter().any(|ty| self.validate_property_value(value, ty, &[]).is_ok()),
+            (PropertyValue::String(_), PropertyType::String | PropertyType::Font)
+            | (PropertyValue::Integer(_), PropertyType::Integer)
+            | (PropertyValue::Float(_) | PropertyValue::Integer(_), PropertyType::Float)
+            | (PropertyValue::Boolean(_), PropertyType::Boolean)
+            | (PropertyValue::Object(_), PropertyType::Object(_))
+            | (PropertyValue::Color(_), PropertyType::Color)
+            | (PropertyValue::Size(_), PropertyType::Size)
+            | (PropertyValue::Position(_), PropertyType::Position)
+            | (PropertyValue::Margin(_), PropertyType::Margin)
+            | (PropertyValue::Padding(_), PropertyType::Padding)
+            | (PropertyValue::FlexDirection(_), PropertyType::FlexDirection)
+            | (PropertyValue::Alignment(_), PropertyType::Alignment)
+            | (PropertyValue::JustifyContent(_), PropertyType::JustifyContent)
+            | (PropertyValue::EventHandler(_) | PropertyValue::String(_), PropertyType::EventHandler)
+            | (PropertyValue::ComponentRef(_) | PropertyValue::String(_), PropertyType::ComponentRef)
+            | (PropertyValue::ResourceRef(_) | PropertyValue::String(_), PropertyType::ResourceRef) => true,
+            _ => false,
+        };
+        if !type_matches {
+            return Err(format!("Type mismatch: expected {:?}", expected_type));
         }
-        
+
+        let number = match value {
+            PropertyValue::Float(value) => Some(*value),
+            PropertyValue::Integer(value) => Some(*value as f64),
+            _ => None,
+        };
+
         // Constraint validation
         for constraint in constraints {
             match (constraint, value) {
-                (PropertyConstraint::Min(min), PropertyValue::Float(val)) => {
-                    if val < min {
+                (PropertyConstraint::Min(min), _) => {
+                    if let Some(val) = number.filter(|val| val < min) {
                         return Err(format!("Value {} is less than minimum {}", val, min));
                     }
                 }
-                (PropertyConstraint::Max(max), PropertyValue::Float(val)) => {
-                    if val > max {
+                (PropertyConstraint::Max(max), _) => {
+                    if let Some(val) = number.filter(|val| val > max) {
                         return Err(format!("Value {} is greater than maximum {}", val, max));
                     }
                 }
+                (PropertyConstraint::Range(min, max), _) => {
+                    if let Some(val) = number.filter(|val| val < min || val > max) {
+                        return Err(format!("Value {} is not between {} and {}", val, min, max));
+                    }
+                }
                 (PropertyConstraint::MinLength(min_len), PropertyValue::String(val)) => {
                     if val.len() < *min_len {
                         return Err(format!("String length {} is less than minimum {}", val.len(), min_len));
@@ -764,10 +962,27 @@ impl ComponentRegistry {
                         return Err(format!("String length {} is greater than maximum {}", val.len(), max_len));
                     }
                 }
+                (PropertyConstraint::Pattern(pattern), PropertyValue::String(val)) => {
+                    let regex = regex::Regex::new(pattern).map_err(|e| format!("Invalid pattern {}: {}", pattern, e))?;
+                    if !regex.is_match(val) {
+                        return Err(format!("Value {} does not match {}", val, pattern));
+                    }
+                }
+                (PropertyConstraint::MinItems(min_items), PropertyValue::Array(items)) if items.len() < *min_items => {
+                    return Err(format!("{} items is less than minimum {}", items.len(), min_items));
+                }
+                (PropertyConstraint::MaxItems(max_items), PropertyValue::Array(items)) if items.len() > *max_items => {
+                    return Err(format!("{} items is greater than maximum {}", items.len(), max_items));
+                }
+                (PropertyConstraint::UniqueItems, PropertyValue::Array(items))
+                    if items.iter().enumerate().any(|(i, item)| items[..i].contains(item)) =>
+                {
+                    return Err("Items must be unique".to_string());
+                }
                 _ => {}
             }
         }
-        
+
         Ok(())
     }
 }
@@ -912,6 +1127,22 @@ impl Default for PropertyUIHints {
     }
 }
 
+impl ControlType {
+    /// Default inspector control for a property type
+    pub fn for_property_type(property_type: &PropertyType) -> Self {
+        match property_type {
+            PropertyType::Integer | PropertyType::Float => ControlType::NumberInput,
+            PropertyType::Boolean => ControlType::Checkbox,
+            PropertyType::Enum(_) | PropertyType::FlexDirection | PropertyType::JustifyContent => ControlType::Select,
+            PropertyType::Color => ControlType::ColorPicker,
+            PropertyType::Margin | PropertyType::Padding => ControlType::SpacingControl,
+            PropertyType::Alignment => ControlType::AlignmentControl,
+            PropertyType::ResourceRef => ControlType::FilePicker,
+            _ => ControlType::TextInput,
+        }
+    }
+}
+
 impl Default for ComponentCategory {
     fn default() -> Self {
         ComponentCategory::Custom("Default".to_string())
@@ -946,13 +1177,13 @@ mod tests {
                     step: None,
                     options: None,
                     custom_renderer: None,
-                })
+                }),
             },
             enabled: {
                 type: PropertyType::Boolean,
                 display_name: "Enabled",
                 description: "Whether the button is enabled",
-                default: PropertyValue::Boolean(true)
+                default: PropertyValue::Boolean(true),
             }
         },
         events: [
@@ -49,18 +49,17 @@ pub mod component_registry;
 
 /// Derive macros for component metadata generation
 /// 
-/// Provides procedural macros for automatically generating ComponentMetadata
-/// and PropertySchema from struct definitions with attribute annotations.
+/// Provides `#[derive(ComponentMeta)]` for automatically generating ComponentMetadata,
+/// PropertySchema and a component factory from struct definitions with attribute annotations.
+#[cfg(feature = "proc-macros")]
 pub mod derive_macros;
 
 /// Examples of component metadata derive macro usage
 /// 
 /// Contains example components that demonstrate how to use the derive macros
 /// for automatic metadata generation.
+#[cfg(feature = "proc-macros")]
 pub mod examples;
 
-#[cfg(test)]
-mod derive_macros_test;
-
 // Re-export UI components from correct paths
 // Components are re-exported at the crate level for convenient access
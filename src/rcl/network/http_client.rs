@@ -17,6 +17,9 @@
 //! - **Error Handling**: Comprehensive HTTP status code and network error handling
 //! - **Timeouts**: Configurable request and response timeouts
 //! - **Redirects**: Automatic handling of HTTP redirects
+//! - **Retries**: Exponential backoff for transient failures, honoring `Retry-After`
+//! - **Cookies**: A cookie jar shared by all requests of a client
+//! - **Uploads and Downloads**: Multipart forms and streamed downloads with progress
 //!
 //! # Protocol Support
 //!
@@ -34,15 +37,22 @@
 //! - Authentication and authorization
 //! - File uploads and downloads
 //!
-//! # Current Implementation
+//! # Retries
 //!
-//! The current implementation provides mock functionality for development.
-//! A production implementation would integrate with reqwest or hyper for
-//! full HTTP client capabilities.
+//! Failed requests are retried according to [`RetryPolicy`]. Idempotent
+//! methods are retried after any transport error or a retryable status code;
+//! `POST` and `PATCH` only when the connection could not be established, so
+//! a request the server may already have processed is never sent twice.
 
 use anyhow::Error;
+use reqwest::cookie::{CookieStore, Jar};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER, USER_AGENT};
+use reqwest::multipart::{Form, Part};
 use std::collections::HashMap;
-use std::time::Duration;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
 
 /// HTTP request methods supported by the client
 #[derive(Debug, Clone, PartialEq)]
@@ -68,12 +78,35 @@ pub enum HttpMethod {
 pub struct HttpResponse {
     /// HTTP status code (200, 404, 500, etc.)
     pub status_code: u16,
-    /// Response headers
+    /// Canonical reason phrase for the status code ("OK", "Not Found", etc.)
+    pub status_text: String,
+    /// Response headers with lowercase names; repeated headers are joined with ", "
     pub headers: HashMap<String, String>,
     /// Response body as text
     pub body: String,
     /// Indicates if the request was successful (2xx status)
     pub success: bool,
+    /// Final URL after redirects
+    pub url: String,
+    /// Body length announced by the server, if any
+    pub content_length: Option<u64>,
+    /// Number of attempts made, including retries
+    pub attempts: u32,
+    /// Time from the first attempt until the response was complete
+    pub elapsed: Duration,
+}
+
+/// Retry behavior for failed requests
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each further retry
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between attempts
+    pub max_backoff: Duration,
+    /// Status codes that are retried
+    pub retry_on_status: Vec<u16>,
 }
 
 /// Configuration options for HTTP client
@@ -81,6 +114,8 @@ pub struct HttpResponse {
 pub struct HttpClientConfig {
     /// Request timeout duration
     pub timeout: Duration,
+    /// Timeout for establishing a connection
+    pub connect_timeout: Duration,
     /// Maximum number of redirects to follow
     pub max_redirects: u32,
     /// User agent string for requests
@@ -91,95 +126,139 @@ pub struct HttpClientConfig {
     pub auto_decompress: bool,
     /// Enable cookie storage and management
     pub enable_cookies: bool,
+    /// Retry behavior for failed requests
+    pub retry: RetryPolicy,
+}
+
+/// Progress of a streamed download
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadProgress {
+    /// Bytes received so far
+    pub downloaded: u64,
+    /// Total size announced by the server, if any
+    pub total: Option<u64>,
+}
+
+/// Multipart form for file uploads
+///
+/// Unlike `reqwest::multipart::Form` it can be sent more than once, which
+/// retries need.
+///
+/// # Examples
+///
+/// ```ignore
+/// let form = MultipartForm::new()
+///     .text("title", "Quarterly report")
+///     .file("report", "report.pdf", pdf_bytes, "application/pdf");
+/// let response = client.upload_multipart("https://api.example.com/upload", &form).await?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MultipartForm {
+    parts: Vec<MultipartPart>,
+}
+
+#[derive(Debug, Clone)]
+enum MultipartPart {
+    Text { name: String, value: String },
+    File { name: String, file_name: String, data: Vec<u8>, content_type: String },
+}
+
+/// Body of a request, rebuilt for every attempt
+enum RequestBody<'a> {
+    Empty,
+    Text(&'a str),
+    Multipart(&'a MultipartForm),
 }
 
 /// A comprehensive HTTP/HTTPS client for web communication
-/// 
+///
 /// The HttpClient provides a high-level interface for making HTTP requests
 /// to web services and APIs. It handles the complexities of HTTP communication
 /// including headers, authentication, error handling, and response processing.
-/// 
+///
 /// # Features
-/// 
+///
 /// - **HTTP Methods**: Full support for standard HTTP verbs
 /// - **JSON Support**: Automatic JSON request/response handling
 /// - **Authentication**: Built-in support for common auth mechanisms
 /// - **Error Handling**: Comprehensive error reporting and status code handling
 /// - **Configuration**: Extensive customization options
-/// 
+///
 /// # Use Cases
-/// 
+///
 /// - REST API client implementations
 /// - Web scraping and data extraction
 /// - Webhook and callback handling
 /// - File upload and download operations
 /// - Authentication and session management
-/// 
+///
 /// # Examples
-/// 
+///
 /// ```ignore
 /// use crate::rcl::network::http_client::{HttpClient, HttpMethod};
-/// 
+///
 /// #[tokio::main]
 /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
 ///     let client = HttpClient::new();
-///     
+///
 ///     // Simple GET request
 ///     let response = client.get("https://api.example.com/users").await?;
 ///     println!("Response: {}", response);
-///     
+///
 ///     // POST request with JSON body
 ///     let json_body = r#"{"name": "John", "email": "john@example.com"}"#;
 ///     let response = client.post("https://api.example.com/users", json_body).await?;
-///     
+///
 ///     Ok(())
 /// }
 /// ```
-#[allow(dead_code)]
 pub struct HttpClient {
     /// Client configuration settings
     config: HttpClientConfig,
-    // Future: Add fields for reqwest client, cookie store,
-    // connection pool, or authentication state
+    /// Underlying reqwest client, built from the configuration
+    client: reqwest::Client,
+    /// Cookie jar shared by all requests when cookies are enabled
+    cookie_jar: Option<Arc<Jar>>,
 }
 
-#[allow(dead_code)]
 impl HttpClient {
     /// Creates a new HTTP client with default configuration
-    /// 
+    ///
     /// The client is initialized with sensible defaults for timeout,
     /// redirects, and other common settings suitable for general web communication.
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// A new `HttpClient` instance ready for making HTTP requests
-    /// 
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let client = HttpClient::new();
     /// ```
     pub fn new() -> Self {
-        Self {
-            config: HttpClientConfig::default(),
-        }
+        Self::with_config(HttpClientConfig::default())
     }
-    
+
     /// Creates a new HTTP client with custom configuration
-    /// 
+    ///
     /// Allows specification of custom timeout values, headers, user agent,
     /// and other advanced options for specialized use cases.
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `config` - Configuration options for the HTTP client
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// A new `HttpClient` instance with the specified configuration
-    /// 
+    ///
+    /// # Panics
+    ///
+    /// Panics if the TLS backend cannot be initialized, like `reqwest::Client::new`.
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let config = HttpClientConfig {
     ///     timeout: Duration::from_secs(30),
@@ -189,106 +268,76 @@ impl HttpClient {
     /// let client = HttpClient::with_config(config);
     /// ```
     pub fn with_config(config: HttpClientConfig) -> Self {
-        Self { config }
+        let cookie_jar = config.enable_cookies.then(|| Arc::new(Jar::default()));
+        let client = build_client(&config, cookie_jar.clone());
+        Self { config, client, cookie_jar }
     }
-    
+
     /// Performs an HTTP GET request to retrieve data
-    /// 
+    ///
     /// This method sends a GET request to the specified URL and returns
     /// the response body as a string. GET requests are used for data retrieval
     /// and should not have side effects on the server.
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `url` - The URL to send the GET request to
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// * `Ok(String)` - The response body as a string
     /// * `Err(Error)` - If the request failed or returned an error status
-    /// 
+    ///
     /// # Errors
-    /// 
+    ///
     /// This method will return an error if:
     /// - The URL is malformed or invalid
     /// - Network connectivity issues occur
     /// - The server returns an error status code (4xx, 5xx)
     /// - The request times out
     /// - SSL/TLS certificate validation fails
-    /// 
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let client = HttpClient::new();
-    /// 
+    ///
     /// // Fetch JSON data from an API
     /// let response = client.get("https://api.example.com/users").await?;
     /// println!("API Response: {}", response);
-    /// 
+    ///
     /// // Fetch a web page
     /// let html = client.get("https://example.com").await?;
     /// ```
-    /// 
-    /// # Implementation Notes
-    /// 
-    /// A production implementation would:
-    /// - Parse and validate the URL
-    /// - Create an HTTP request with appropriate headers
-    /// - Handle redirects automatically
-    /// - Process response status codes
-    /// - Return detailed error information
     pub async fn get(&self, url: &str) -> Result<String, Error> {
-        // TODO: Implement actual HTTP GET request
-        // Production implementation would:
-        // 1. Validate the URL format
-        // 2. Create reqwest client with configuration
-        // 3. Send GET request with default headers
-        // 4. Handle response status codes
-        // 5. Return response body or error
-        
-        // Basic URL validation
-        if !url.starts_with("http://") && !url.starts_with("https://") {
-            return Err(Error::msg(format!("Invalid URL format: {}", url)));
-        }
-        
-        // Mock implementation for development
-        // Real implementation would use:
-        // let response = reqwest::Client::new()
-        //     .get(url)
-        //     .timeout(self.config.timeout)
-        //     .send()
-        //     .await?
-        //     .text()
-        //     .await?;
-        
-        Ok(format!("Mock GET response from: {}", url))
-    }
-    
+        self.request(HttpMethod::Get, url, None, None).await
+    }
+
     /// Performs an HTTP POST request to submit data
-    /// 
+    ///
     /// This method sends a POST request with the provided body to the specified URL.
     /// POST requests are used for data submission, resource creation, and operations
     /// that have side effects on the server.
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `url` - The URL to send the POST request to
     /// * `body` - The request body content as a string
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// * `Ok(String)` - The response body as a string
     /// * `Err(Error)` - If the request failed or returned an error status
-    /// 
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let client = HttpClient::new();
-    /// 
+    ///
     /// // Submit JSON data
     /// let json_data = r#"{"name": "John", "email": "john@example.com"}"#;
     /// let response = client.post("https://api.example.com/users", json_data).await?;
-    /// 
+    ///
     /// // Submit form data
     /// let form_data = "name=John&email=john@example.com";
     /// let response = client.post("https://api.example.com/contact", form_data).await?;
@@ -296,107 +345,108 @@ impl HttpClient {
     pub async fn post(&self, url: &str, body: &str) -> Result<String, Error> {
         self.request(HttpMethod::Post, url, Some(body), None).await
     }
-    
+
     /// Performs an HTTP PUT request to update or create data
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `url` - The URL to send the PUT request to
     /// * `body` - The request body content as a string
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// The response body as a string, or an error if the request failed
-    /// 
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let client = HttpClient::new();
-    /// 
+    ///
     /// let updated_data = r#"{"id": 123, "name": "Updated Name"}"#;
     /// let response = client.put("https://api.example.com/users/123", updated_data).await?;
     /// ```
     pub async fn put(&self, url: &str, body: &str) -> Result<String, Error> {
         self.request(HttpMethod::Put, url, Some(body), None).await
     }
-    
+
     /// Performs an HTTP DELETE request to remove data
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `url` - The URL to send the DELETE request to
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// The response body as a string, or an error if the request failed
-    /// 
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let client = HttpClient::new();
-    /// 
+    ///
     /// let response = client.delete("https://api.example.com/users/123").await?;
     /// ```
     pub async fn delete(&self, url: &str) -> Result<String, Error> {
         self.request(HttpMethod::Delete, url, None, None).await
     }
-    
+
     /// Performs an HTTP PATCH request for partial updates
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `url` - The URL to send the PATCH request to
     /// * `body` - The request body content as a string
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// The response body as a string, or an error if the request failed
-    /// 
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let client = HttpClient::new();
-    /// 
+    ///
     /// let patch_data = r#"{"name": "New Name Only"}"#;
     /// let response = client.patch("https://api.example.com/users/123", patch_data).await?;
     /// ```
     pub async fn patch(&self, url: &str, body: &str) -> Result<String, Error> {
         self.request(HttpMethod::Patch, url, Some(body), None).await
     }
-    
+
     /// Performs a generic HTTP request with detailed response information
-    /// 
+    ///
     /// This method provides the most control over the HTTP request, allowing
     /// specification of method, headers, and body content. It returns a detailed
-    /// response object with status code, headers, and body.
-    /// 
+    /// response object with status code, headers, and body. Error statuses are
+    /// returned as responses rather than errors.
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `method` - The HTTP method to use
     /// * `url` - The URL to send the request to
     /// * `body` - Optional request body content
     /// * `headers` - Optional additional headers to include
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// * `Ok(HttpResponse)` - Detailed response information
-    /// * `Err(Error)` - If the request failed
-    /// 
+    /// * `Err(Error)` - If the request could not be completed
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let client = HttpClient::new();
-    /// 
+    ///
     /// let mut headers = HashMap::new();
     /// headers.insert("Authorization".to_string(), "Bearer token123".to_string());
-    /// 
+    ///
     /// let response = client.request_detailed(
     ///     HttpMethod::Get,
     ///     "https://api.example.com/protected",
     ///     None,
     ///     Some(headers)
     /// ).await?;
-    /// 
+    ///
     /// println!("Status: {}, Body: {}", response.status_code, response.body);
     /// ```
     pub async fn request_detailed(
@@ -404,45 +454,108 @@ impl HttpClient {
         method: HttpMethod,
         url: &str,
         body: Option<&str>,
-        _headers: Option<HashMap<String, String>>,
+        headers: Option<HashMap<String, String>>,
+    ) -> Result<HttpResponse, Error> {
+        let body = body.map_or(RequestBody::Empty, RequestBody::Text);
+        let started = Instant::now();
+        let (response, attempts) = self.execute(&method, url, headers.as_ref(), body).await?;
+        read_response(response, attempts, started).await
+    }
+
+    /// Uploads a multipart form with a POST request
+    ///
+    /// # Returns
+    ///
+    /// The detailed response, including error statuses
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let form = MultipartForm::new().file("avatar", "me.png", png_bytes, "image/png");
+    /// let response = client.upload_multipart("https://api.example.com/avatar", &form).await?;
+    /// ```
+    pub async fn upload_multipart(&self, url: &str, form: &MultipartForm) -> Result<HttpResponse, Error> {
+        let started = Instant::now();
+        let (response, attempts) = self.execute(&HttpMethod::Post, url, None, RequestBody::Multipart(form)).await?;
+        read_response(response, attempts, started).await
+    }
+
+    /// Downloads `url` in chunks, passing each chunk and the progress so far to `on_chunk`
+    ///
+    /// The body is never held in memory as a whole. Returning an error from
+    /// `on_chunk` aborts the download.
+    ///
+    /// # Returns
+    ///
+    /// The response with an empty body
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, the server answers with a
+    /// non-2xx status, the connection drops, or `on_chunk` fails.
+    pub async fn download_stream(
+        &self,
+        url: &str,
+        mut on_chunk: impl FnMut(&[u8], DownloadProgress) -> Result<(), Error>,
     ) -> Result<HttpResponse, Error> {
-        // TODO: Implement detailed HTTP request with full response information
-        // Production implementation would:
-        // 1. Build request with method, URL, body, and headers
-        // 2. Send request and capture full response
-        // 3. Extract status code, headers, and body
-        // 4. Return structured response object
-        
-        // Basic URL validation
-        if !url.starts_with("http://") && !url.starts_with("https://") {
-            return Err(Error::msg(format!("Invalid URL format: {}", url)));
+        let started = Instant::now();
+        let (mut response, attempts) = self.start_download(url).await?;
+        let mut progress = DownloadProgress { downloaded: 0, total: response.content_length() };
+        let summary = summarize(&response, attempts);
+
+        while let Some(chunk) = response.chunk().await.map_err(|e| request_error(url, e))? {
+            progress.downloaded += chunk.len() as u64;
+            on_chunk(&chunk, progress)?;
         }
-        
-        // Mock implementation for development
-        let mut response_headers = HashMap::new();
-        response_headers.insert("content-type".to_string(), "application/json".to_string());
-        response_headers.insert("server".to_string(), "MockServer/1.0".to_string());
-        
-        let response_body = match method {
-            HttpMethod::Get => format!("Mock GET response from: {}", url),
-            HttpMethod::Post => format!("Mock POST response from: {} with body: {:?}", url, body),
-            HttpMethod::Put => format!("Mock PUT response from: {} with body: {:?}", url, body),
-            HttpMethod::Delete => format!("Mock DELETE response from: {}", url),
-            HttpMethod::Patch => format!("Mock PATCH response from: {} with body: {:?}", url, body),
-            HttpMethod::Head => String::new(), // HEAD responses have no body
-            HttpMethod::Options => "GET,POST,PUT,DELETE,PATCH,HEAD,OPTIONS".to_string(),
-        };
-        
-        Ok(HttpResponse {
-            status_code: 200,
-            headers: response_headers,
-            body: response_body,
-            success: true,
-        })
+        Ok(HttpResponse { elapsed: started.elapsed(), ..summary })
+    }
+
+    /// Downloads `url` into the file at `destination`, reporting progress after each chunk
+    ///
+    /// The file is removed again if the download fails part way.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// client.download_to_file(url, Path::new("dataset.zip"), |progress| {
+    ///     if let Some(total) = progress.total {
+    ///         println!("{:.0}%", progress.downloaded as f64 / total as f64 * 100.0);
+    ///     }
+    /// }).await?;
+    /// ```
+    pub async fn download_to_file(
+        &self,
+        url: &str,
+        destination: &Path,
+        mut on_progress: impl FnMut(DownloadProgress),
+    ) -> Result<HttpResponse, Error> {
+        let started = Instant::now();
+        let (mut response, attempts) = self.start_download(url).await?;
+        let mut progress = DownloadProgress { downloaded: 0, total: response.content_length() };
+        let summary = summarize(&response, attempts);
+
+        let mut file = tokio::fs::File::create(destination).await
+            .map_err(|e| Error::msg(format!("Cannot create {}: {}", destination.display(), e)))?;
+        let written: Result<(), Error> = async {
+            while let Some(chunk) = response.chunk().await.map_err(|e| request_error(url, e))? {
+                file.write_all(&chunk).await?;
+                progress.downloaded += chunk.len() as u64;
+                on_progress(progress);
+            }
+            file.flush().await?;
+            Ok(())
+        }.await;
+
+        if let Err(error) = written {
+            drop(file);
+            let _ = tokio::fs::remove_file(destination).await;
+            return Err(error);
+        }
+        Ok(HttpResponse { elapsed: started.elapsed(), ..summary })
     }
-    
+
     /// Internal method for making HTTP requests
-    /// 
+    ///
     /// This is a helper method that consolidates common request logic
     /// and returns just the response body as a string.
     async fn request(
@@ -453,7 +566,7 @@ impl HttpClient {
         headers: Option<HashMap<String, String>>,
     ) -> Result<String, Error> {
         let response = self.request_detailed(method, url, body, headers).await?;
-        
+
         if response.success {
             Ok(response.body)
         } else {
@@ -463,22 +576,93 @@ impl HttpClient {
             )))
         }
     }
-    
+
+    /// Sends a GET request for a download and checks its status
+    async fn start_download(&self, url: &str) -> Result<(reqwest::Response, u32), Error> {
+        let (response, attempts) = self.execute(&HttpMethod::Get, url, None, RequestBody::Empty).await?;
+        if !response.status().is_success() {
+            return Err(Error::msg(format!("Download of {} failed with status {}", url, response.status())));
+        }
+        Ok((response, attempts))
+    }
+
+    /// Sends a request, retrying according to the retry policy
+    ///
+    /// Returns the final response and the number of attempts made.
+    async fn execute(
+        &self,
+        method: &HttpMethod,
+        url: &str,
+        headers: Option<&HashMap<String, String>>,
+        body: RequestBody<'_>,
+    ) -> Result<(reqwest::Response, u32), Error> {
+        let parsed = reqwest::Url::parse(url)
+            .ok()
+            .filter(|parsed| matches!(parsed.scheme(), "http" | "https"))
+            .ok_or_else(|| Error::msg(format!("Invalid URL format: {}", url)))?;
+        let headers = self.request_headers(headers)?;
+        let policy = &self.config.retry;
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            let mut request = self.client
+                .request(method.to_reqwest(), parsed.clone())
+                .headers(headers.clone())
+                .timeout(self.config.timeout);
+            request = match &body {
+                RequestBody::Empty => request,
+                RequestBody::Text(text) => request.body(text.to_string()),
+                RequestBody::Multipart(form) => request.multipart(form.to_reqwest()?),
+            };
+
+            let retries_left = attempt <= policy.max_retries;
+            let delay = match request.send().await {
+                Ok(response) => {
+                    let status = response.status().as_u16();
+                    if !retries_left || !method.is_idempotent() || !policy.retry_on_status.contains(&status) {
+                        return Ok((response, attempt));
+                    }
+                    retry_after(&response).unwrap_or_else(|| policy.backoff(attempt))
+                }
+                Err(error) => {
+                    if !retries_left || !(method.is_idempotent() || error.is_connect()) {
+                        return Err(request_error(url, error));
+                    }
+                    policy.backoff(attempt)
+                }
+            };
+            tokio::time::sleep(delay.min(policy.max_backoff)).await;
+        }
+    }
+
+    /// Default headers, the user agent and `extra` combined, `extra` taking precedence
+    fn request_headers(&self, extra: Option<&HashMap<String, String>>) -> Result<HeaderMap, Error> {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, parse_header_value(&self.config.user_agent)?);
+        for (name, value) in self.config.default_headers.iter().chain(extra.into_iter().flatten()) {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| Error::msg(format!("Invalid header name: {}", name)))?;
+            headers.insert(name, parse_header_value(value)?);
+        }
+        Ok(headers)
+    }
+
     /// Sets the authorization header for subsequent requests
-    /// 
+    ///
     /// This method updates the default headers to include authorization
     /// information that will be sent with all future requests.
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `token` - The authorization token (will be prefixed with "Bearer ")
-    /// 
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let mut client = HttpClient::new();
     /// client.set_auth_token("abc123def456");
-    /// 
+    ///
     /// // All subsequent requests will include the auth header
     /// let response = client.get("https://api.example.com/protected").await?;
     /// ```
@@ -488,15 +672,15 @@ impl HttpClient {
             format!("Bearer {}", token),
         );
     }
-    
+
     /// Sets a custom user agent string for requests
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `user_agent` - The user agent string to use
-    /// 
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let mut client = HttpClient::new();
     /// client.set_user_agent("MyApplication/1.0 (compatible)");
@@ -504,15 +688,15 @@ impl HttpClient {
     pub fn set_user_agent(&mut self, user_agent: &str) {
         self.config.user_agent = user_agent.to_string();
     }
-    
+
     /// Sets the request timeout duration
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `timeout` - The timeout duration for requests
-    /// 
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let mut client = HttpClient::new();
     /// client.set_timeout(Duration::from_secs(60));
@@ -520,16 +704,21 @@ impl HttpClient {
     pub fn set_timeout(&mut self, timeout: Duration) {
         self.config.timeout = timeout;
     }
-    
+
+    /// Sets the retry behavior for subsequent requests
+    pub fn set_retry_policy(&mut self, retry: RetryPolicy) {
+        self.config.retry = retry;
+    }
+
     /// Adds a default header that will be included with all requests
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `name` - The header name
     /// * `value` - The header value
-    /// 
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let mut client = HttpClient::new();
     /// client.add_default_header("X-API-Key", "my-secret-key");
@@ -538,39 +727,219 @@ impl HttpClient {
     pub fn add_default_header(&mut self, name: &str, value: &str) {
         self.config.default_headers.insert(name.to_string(), value.to_string());
     }
-    
+
+    /// Returns the `Cookie` header the client would send to `url`
+    ///
+    /// Returns `None` if cookies are disabled or none match.
+    pub fn cookies(&self, url: &str) -> Option<String> {
+        let url = reqwest::Url::parse(url).ok()?;
+        let header = self.cookie_jar.as_ref()?.cookies(&url)?;
+        header.to_str().ok().map(str::to_string)
+    }
+
+    /// Stores a cookie as if `url` had sent it in a `Set-Cookie` header
+    ///
+    /// Does nothing if cookies are disabled or `url` is invalid.
+    pub fn add_cookie(&self, url: &str, cookie: &str) {
+        if let (Some(jar), Ok(url)) = (&self.cookie_jar, reqwest::Url::parse(url)) {
+            jar.add_cookie_str(cookie, &url);
+        }
+    }
+
+    /// Removes all stored cookies
+    pub fn clear_cookies(&mut self) {
+        if self.cookie_jar.is_some() {
+            let jar = Arc::new(Jar::default());
+            self.client = build_client(&self.config, Some(jar.clone()));
+            self.cookie_jar = Some(jar);
+        }
+    }
+
     /// Gets the current client configuration
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// A reference to the current configuration
     pub fn config(&self) -> &HttpClientConfig {
         &self.config
     }
 }
 
+impl HttpMethod {
+    fn to_reqwest(&self) -> reqwest::Method {
+        match self {
+            HttpMethod::Get => reqwest::Method::GET,
+            HttpMethod::Post => reqwest::Method::POST,
+            HttpMethod::Put => reqwest::Method::PUT,
+            HttpMethod::Delete => reqwest::Method::DELETE,
+            HttpMethod::Patch => reqwest::Method::PATCH,
+            HttpMethod::Head => reqwest::Method::HEAD,
+            HttpMethod::Options => reqwest::Method::OPTIONS,
+        }
+    }
+
+    /// Whether sending the request twice has the same effect as sending it once
+    pub fn is_idempotent(&self) -> bool {
+        !matches!(self, HttpMethod::Post | HttpMethod::Patch)
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries
+    pub fn none() -> Self {
+        Self { max_retries: 0, ..Self::default() }
+    }
+
+    /// Delay before retry number `attempt` (starting at 1)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+impl MultipartForm {
+    /// Creates an empty form
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a text field
+    pub fn text(mut self, name: &str, value: &str) -> Self {
+        self.parts.push(MultipartPart::Text { name: name.to_string(), value: value.to_string() });
+        self
+    }
+
+    /// Adds a file field
+    pub fn file(mut self, name: &str, file_name: &str, data: Vec<u8>, content_type: &str) -> Self {
+        self.parts.push(MultipartPart::File {
+            name: name.to_string(),
+            file_name: file_name.to_string(),
+            data,
+            content_type: content_type.to_string(),
+        });
+        self
+    }
+
+    fn to_reqwest(&self) -> Result<Form, Error> {
+        self.parts.iter().try_fold(Form::new(), |form, part| match part {
+            MultipartPart::Text { name, value } => Ok(form.text(name.clone(), value.clone())),
+            MultipartPart::File { name, file_name, data, content_type } => {
+                let part = Part::bytes(data.clone())
+                    .file_name(file_name.clone())
+                    .mime_str(content_type)
+                    .map_err(|_| Error::msg(format!("Invalid content type: {}", content_type)))?;
+                Ok(form.part(name.clone(), part))
+            }
+        })
+    }
+}
+
+fn build_client(config: &HttpClientConfig, cookie_jar: Option<Arc<Jar>>) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder()
+        .connect_timeout(config.connect_timeout)
+        .redirect(reqwest::redirect::Policy::limited(config.max_redirects as usize))
+        .gzip(config.auto_decompress)
+        .deflate(config.auto_decompress);
+    if let Some(jar) = cookie_jar {
+        builder = builder.cookie_provider(jar);
+    }
+    builder.build().expect("failed to initialize the HTTP client")
+}
+
+fn parse_header_value(value: &str) -> Result<HeaderValue, Error> {
+    HeaderValue::from_str(value).map_err(|_| Error::msg(format!("Invalid header value: {}", value)))
+}
+
+fn request_error(url: &str, error: reqwest::Error) -> Error {
+    let kind = if error.is_timeout() {
+        "timed out"
+    } else if error.is_connect() {
+        "could not connect"
+    } else if error.is_redirect() {
+        "too many redirects"
+    } else {
+        "failed"
+    };
+    Error::msg(format!("HTTP request to {} {}: {}", url, kind, error))
+}
+
+/// Delay requested by a `Retry-After` header given in seconds
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let seconds = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+/// Everything about a response except its body and timing
+fn summarize(response: &reqwest::Response, attempts: u32) -> HttpResponse {
+    let mut headers: HashMap<String, String> = HashMap::new();
+    for (name, value) in response.headers() {
+        let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+        headers.entry(name.as_str().to_string())
+            .and_modify(|existing| {
+                existing.push_str(", ");
+                existing.push_str(&value);
+            })
+            .or_insert(value);
+    }
+    let status = response.status();
+    HttpResponse {
+        status_code: status.as_u16(),
+        status_text: status.canonical_reason().unwrap_or_default().to_string(),
+        headers,
+        body: String::new(),
+        success: status.is_success(),
+        url: response.url().to_string(),
+        content_length: response.content_length(),
+        attempts,
+        elapsed: Duration::ZERO,
+    }
+}
+
+async fn read_response(response: reqwest::Response, attempts: u32, started: Instant) -> Result<HttpResponse, Error> {
+    let summary = summarize(&response, attempts);
+    let url = summary.url.clone();
+    let body = response.text().await.map_err(|e| request_error(&url, e))?;
+    Ok(HttpResponse { body, elapsed: started.elapsed(), ..summary })
+}
+
+/// Default implementation for RetryPolicy
+///
+/// Retries twice, starting at 200ms, on rate limiting and gateway errors.
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+            retry_on_status: vec![408, 429, 502, 503, 504],
+        }
+    }
+}
+
 /// Default implementation for HttpClientConfig
-/// 
+///
 /// Provides sensible defaults for HTTP client configuration.
 impl Default for HttpClientConfig {
     fn default() -> Self {
         let mut default_headers = HashMap::new();
         default_headers.insert("Accept".to_string(), "*/*".to_string());
         default_headers.insert("Accept-Encoding".to_string(), "gzip, deflate".to_string());
-        
+
         Self {
             timeout: Duration::from_secs(30),
+            connect_timeout: Duration::from_secs(10),
             max_redirects: 10,
             user_agent: "RCL-HttpClient/1.0".to_string(),
             default_headers,
             auto_decompress: true,
             enable_cookies: true,
+            retry: RetryPolicy::default(),
         }
     }
 }
 
 /// Default implementation for HttpClient
-/// 
+///
 /// Creates a new HTTP client with default configuration.
 impl Default for HttpClient {
     fn default() -> Self {
@@ -579,7 +948,7 @@ impl Default for HttpClient {
 }
 
 /// Display implementation for HttpMethod
-/// 
+///
 /// Provides string representation of HTTP methods.
 impl std::fmt::Display for HttpMethod {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -58,17 +58,17 @@
 //! # Examples
 //!
 //! ```ignore
-//! use crate::rcl::network::{http_client::HttpClient, websocket::WebSocket};
+//! use crate::rcl::network::{http_client::HttpClient, websocket::WebSocketClient};
 //!
 //! // HTTP client usage
 //! let client = HttpClient::new();
 //! let response = client.get("https://api.example.com/data").await?;
 //! let json_data = response.json().await?;
 //!
-//! // WebSocket connection
-//! let mut ws = WebSocket::connect("wss://api.example.com/ws").await?;
-//! ws.send_text("Hello, Server!").await?;
-//! let message = ws.receive().await?;
+//! // WebSocket connection, polled once per frame
+//! let mut ws = WebSocketClient::new();
+//! ws.connect("wss://api.example.com/ws")?;
+//! for event in ws.poll_events() { /* ... */ }
 //! ```
 
 /// HTTP/HTTPS client with comprehensive REST API support
@@ -79,8 +79,8 @@ pub mod http_client;
 
 /// WebSocket client for real-time bidirectional communication
 /// 
-/// Async WebSocket implementation supporting text and binary messages,
-/// connection lifecycle management, and automatic reconnection capabilities.
+/// WebSocket client supporting text and binary messages, keepalive pings,
+/// automatic reconnection and per-frame event polling for egui components.
 pub mod websocket;
 
 /// TCP socket client for reliable stream-based communication
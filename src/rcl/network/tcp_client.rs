@@ -15,6 +15,8 @@
 //! - **Data Transfer**: Efficient sending and receiving of binary and text data
 //! - **Error Handling**: Comprehensive error reporting with connection state tracking
 //! - **Timeout Support**: Configurable timeouts for connection and read/write operations
+//! - **Reconnection**: Optional automatic reconnection with exponential backoff
+//! - **Framing**: Length-prefixed and line-delimited message helpers
 //!
 //! # Protocol Support
 //!
@@ -31,14 +33,29 @@
 //! - Real-time data streaming
 //! - Remote API access
 //!
-//! # Current Implementation
+//! # Framing
 //!
-//! The current implementation provides mock functionality for development.
-//! A production implementation would integrate with tokio for async networking.
+//! TCP delivers a byte stream, so message boundaries have to be encoded by
+//! the application. [`TcpClient::send_frame`] and [`TcpClient::receive_frame`]
+//! prefix each message with its length as a big-endian `u32`;
+//! [`TcpClient::send_line`] and [`TcpClient::receive_line`] separate messages
+//! with `\n`. The encoding functions are also available on their own for
+//! servers written against plain tokio sockets.
 
 use anyhow::Error;
+use std::fmt::Display;
+use std::future::Future;
+use std::io;
 use std::time::Duration;
 use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{lookup_host, TcpSocket, TcpStream};
+
+/// Upper bound for the delay between reconnection attempts
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// Size of the length prefix used by length-prefixed framing
+pub const LENGTH_PREFIX_SIZE: usize = 4;
 
 /// Connection state for the TCP client
 #[derive(Debug, Clone, PartialEq)]
@@ -66,61 +83,62 @@ pub struct TcpClientConfig {
     pub auto_reconnect: bool,
     /// Maximum number of reconnection attempts
     pub max_reconnect_attempts: u32,
-    /// Delay between reconnection attempts
+    /// Delay before the first reconnection attempt, doubled for each further attempt
     pub reconnect_delay: Duration,
     /// Enable TCP keep-alive
     pub keep_alive: bool,
     /// TCP buffer sizes
     pub buffer_size: usize,
+    /// Largest frame accepted by the framing helpers
+    pub max_frame_size: usize,
 }
 
 /// An asynchronous TCP client for network communication
-/// 
+///
 /// The TcpClient provides a high-level interface for TCP socket operations,
 /// handling connection management, data transfer, and error recovery.
 /// It's designed for use in async contexts and provides comprehensive
 /// error handling and connection state management.
-/// 
+///
 /// # Features
-/// 
+///
 /// - **Async Operations**: All network operations are asynchronous
 /// - **Connection Lifecycle**: Automatic connection management and cleanup
 /// - **Data Transfer**: Efficient binary and text data transmission
-/// - **Error Recovery**: Robust error handling with retry mechanisms
+/// - **Error Recovery**: Reconnects with backoff when `auto_reconnect` is set
 /// - **Configuration**: Extensive configuration options for different use cases
-/// 
+///
 /// # Use Cases
-/// 
+///
 /// - HTTP/HTTPS client implementations
 /// - Database client connections (PostgreSQL, MySQL, etc.)
 /// - Message queue clients (Redis, RabbitMQ, etc.)
 /// - Custom protocol implementations
 /// - Real-time communication systems
-/// 
+///
 /// # Examples
-/// 
+///
 /// ```ignore
 /// use crate::rcl::network::tcp_client::TcpClient;
-/// 
+///
 /// #[tokio::main]
 /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
-///     let client = TcpClient::new();
-///     
+///     let mut client = TcpClient::new();
+///
 ///     // Connect to a server
 ///     client.connect("127.0.0.1:8080").await?;
-///     
+///
 ///     // Send data
 ///     let message = "Hello, server!";
 ///     client.send(message.as_bytes()).await?;
-///     
+///
 ///     // Receive response
 ///     let response = client.receive().await?;
 ///     println!("Server response: {:?}", response);
-///     
+///
 ///     Ok(())
 /// }
 /// ```
-#[allow(dead_code)]
 pub struct TcpClient {
     /// Current connection state
     state: ConnectionState,
@@ -128,49 +146,50 @@ pub struct TcpClient {
     config: TcpClientConfig,
     /// Remote server address
     remote_addr: Option<SocketAddr>,
-    // Future: Add fields for tokio TcpStream, connection pool,
-    // statistics, or connection handles
+    /// Address passed to `connect`, used when reconnecting
+    target: Option<String>,
+    /// Open connection
+    stream: Option<TcpStream>,
+    /// Bytes received but not yet returned to the caller
+    read_buffer: Vec<u8>,
+    /// Number of successful reconnections
+    reconnect_count: u32,
 }
 
-#[allow(dead_code)]
 impl TcpClient {
     /// Creates a new TCP client with default configuration
-    /// 
+    ///
     /// The client is initialized in disconnected state with default settings
     /// optimized for general-purpose TCP communication.
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// A new `TcpClient` instance ready for connection operations
-    /// 
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let client = TcpClient::new();
     /// ```
     pub fn new() -> Self {
-        Self {
-            state: ConnectionState::Disconnected,
-            config: TcpClientConfig::default(),
-            remote_addr: None,
-        }
+        Self::with_config(TcpClientConfig::default())
     }
-    
+
     /// Creates a new TCP client with custom configuration
-    /// 
+    ///
     /// Allows specification of custom timeout values, reconnection behavior,
     /// and other advanced options for specialized use cases.
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `config` - Configuration options for the TCP client
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// A new `TcpClient` instance with the specified configuration
-    /// 
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let config = TcpClientConfig {
     ///     connect_timeout: Duration::from_secs(10),
@@ -184,239 +203,267 @@ impl TcpClient {
             state: ConnectionState::Disconnected,
             config,
             remote_addr: None,
+            target: None,
+            stream: None,
+            read_buffer: Vec::new(),
+            reconnect_count: 0,
         }
     }
 
     /// Establishes a TCP connection to the specified address
-    /// 
-    /// This method attempts to connect to the given address using the configured
-    /// timeout settings. The connection is established asynchronously and the
-    /// method returns when the connection is ready or fails.
-    /// 
+    ///
+    /// The address is resolved and every resulting socket address is tried
+    /// in turn until one accepts the connection. Resolution and connecting
+    /// together are bounded by `connect_timeout`. Any existing connection is
+    /// closed first.
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `addr` - The address to connect to (e.g., "127.0.0.1:8080", "example.com:443")
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// * `Ok(())` - If the connection was established successfully
     /// * `Err(Error)` - If the connection failed
-    /// 
+    ///
     /// # Errors
-    /// 
+    ///
     /// This method will return an error if:
     /// - The address cannot be resolved
     /// - The connection times out
     /// - The remote server refuses the connection
     /// - Network connectivity issues occur
     /// - The address format is invalid
-    /// 
+    ///
+    /// The state is `Failed` with the reason afterwards.
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
-    /// let client = TcpClient::new();
-    /// 
+    /// let mut client = TcpClient::new();
+    ///
     /// // Connect to localhost
     /// client.connect("127.0.0.1:8080").await?;
-    /// 
+    ///
     /// // Connect to remote server
     /// client.connect("api.example.com:443").await?;
     /// ```
-    /// 
-    /// # Implementation Notes
-    /// 
-    /// A production implementation would:
-    /// - Parse the address and resolve DNS if needed
-    /// - Create a tokio TcpStream with timeout
-    /// - Handle IPv4/IPv6 resolution
-    /// - Set socket options (keep-alive, buffer sizes)
-    /// - Update connection state throughout the process
     pub async fn connect(&mut self, addr: &str) -> Result<(), Error> {
-        // TODO: Implement actual TCP connection
-        // Production implementation would:
-        // 1. Parse and validate the address
-        // 2. Resolve DNS for hostname addresses
-        // 3. Create tokio TcpStream with timeout
-        // 4. Set socket options and configuration
-        // 5. Update connection state
-        
-        self.state = ConnectionState::Connecting;
-        
-        // Parse address for validation
-        let _socket_addr: SocketAddr = addr.parse()
-            .map_err(|e| Error::msg(format!("Invalid address format '{}': {}", addr, e)))?;
-        
-        // Mock implementation for testing and development
-        // Real implementation would use:
-        // let stream = tokio::time::timeout(
-        //     self.config.connect_timeout,
-        //     TcpStream::connect(socket_addr)
-        // ).await??;
-        
-        if addr == "127.0.0.1:8080" || addr.starts_with("127.0.0.1:") {
-            self.state = ConnectionState::Connected;
-            self.remote_addr = Some(addr.parse()?);
-            Ok(())
-        } else {
-            self.state = ConnectionState::Failed("Connection failed".to_string());
-            Err(Error::msg(format!(
-                "TCP client not fully implemented yet. Use tokio for real implementation. Attempted to connect to: {}", 
-                addr
-            )))
+        self.close_stream().await;
+        self.target = Some(addr.to_string());
+        self.establish(addr).await
+    }
+
+    /// Reconnects to the address of the last `connect` call
+    ///
+    /// Makes up to `max_reconnect_attempts` attempts. The first one waits
+    /// `reconnect_delay` and each further one waits twice as long as the
+    /// previous one, up to 30 seconds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `connect` was never called or every attempt failed.
+    pub async fn reconnect(&mut self) -> Result<(), Error> {
+        let target = self.target.clone()
+            .ok_or_else(|| Error::msg("Cannot reconnect: no address to reconnect to"))?;
+        self.close_stream().await;
+
+        let mut delay = self.config.reconnect_delay;
+        let mut last_error = Error::msg("no reconnection attempts configured");
+        for _ in 0..self.config.max_reconnect_attempts {
+            tokio::time::sleep(delay).await;
+            match self.establish(&target).await {
+                Ok(()) => {
+                    self.reconnect_count += 1;
+                    return Ok(());
+                }
+                Err(error) => last_error = error,
+            }
+            delay = (delay * 2).min(MAX_RECONNECT_DELAY);
         }
+
+        let reason = format!(
+            "Reconnecting to {} failed after {} attempts: {}",
+            target, self.config.max_reconnect_attempts, last_error
+        );
+        self.state = ConnectionState::Failed(reason.clone());
+        Err(Error::msg(reason))
     }
-    
+
     /// Sends data over the established TCP connection
-    /// 
+    ///
     /// This method sends the provided data to the connected server.
     /// The operation is asynchronous and returns when all data has been sent
     /// or an error occurs.
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `data` - The data to send as a byte slice
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// * `Ok(usize)` - The number of bytes sent
     /// * `Err(Error)` - If the send operation failed
-    /// 
+    ///
     /// # Errors
-    /// 
+    ///
     /// This method will return an error if:
     /// - The client is not connected
     /// - The connection was lost during sending
     /// - A write timeout occurs
     /// - The remote server closes the connection
-    /// 
+    ///
+    /// With `auto_reconnect`, a lost connection is re-established and the
+    /// data sent again once before giving up.
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
-    /// let client = TcpClient::new();
+    /// let mut client = TcpClient::new();
     /// client.connect("127.0.0.1:8080").await?;
-    /// 
+    ///
     /// let message = "Hello, server!";
     /// let bytes_sent = client.send(message.as_bytes()).await?;
     /// println!("Sent {} bytes", bytes_sent);
     /// ```
-    pub async fn send(&self, data: &[u8]) -> Result<usize, Error> {
-        // TODO: Implement actual data sending
-        // Production implementation would:
-        // 1. Check connection state
-        // 2. Write data to TcpStream with timeout
-        // 3. Handle partial writes and retry logic
-        // 4. Update connection statistics
-        // 5. Handle connection errors and reconnection
-        
-        match self.state {
-            ConnectionState::Connected => {
-                // Mock successful send
-                Ok(data.len())
-            },
-            ConnectionState::Disconnected => {
-                Err(Error::msg("Cannot send data: client is not connected"))
-            },
-            ConnectionState::Connecting => {
-                Err(Error::msg("Cannot send data: connection in progress"))
-            },
-            ConnectionState::Failed(ref reason) => {
-                Err(Error::msg(format!("Cannot send data: connection failed - {}", reason)))
-            },
+    pub async fn send(&mut self, data: &[u8]) -> Result<usize, Error> {
+        self.ensure_connected("send data").await?;
+        match self.write_all(data).await {
+            Err(_) if self.config.auto_reconnect && !self.is_connected() => {
+                self.reconnect().await?;
+                self.write_all(data).await?;
+            }
+            result => result?,
         }
+        Ok(data.len())
     }
-    
+
     /// Receives data from the established TCP connection
-    /// 
-    /// This method reads available data from the connected server.
-    /// The operation is asynchronous and returns when data is received
-    /// or an error occurs.
-    /// 
+    ///
+    /// Returns whatever is available: buffered bytes left over from a framed
+    /// read, or the result of a single read of up to `buffer_size` bytes.
+    ///
     /// # Returns
-    /// 
+    ///
     /// * `Ok(Vec<u8>)` - The received data as a byte vector
     /// * `Err(Error)` - If the receive operation failed
-    /// 
+    ///
     /// # Errors
-    /// 
+    ///
     /// This method will return an error if:
     /// - The client is not connected
     /// - The connection was lost during receiving
     /// - A read timeout occurs
     /// - The remote server closes the connection
-    /// 
+    ///
+    /// A read timeout leaves the connection open; the other errors move the
+    /// client to the `Failed` state.
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
-    /// let client = TcpClient::new();
+    /// let mut client = TcpClient::new();
     /// client.connect("127.0.0.1:8080").await?;
-    /// 
+    ///
     /// let response = client.receive().await?;
     /// let text = String::from_utf8_lossy(&response);
     /// println!("Received: {}", text);
     /// ```
-    pub async fn receive(&self) -> Result<Vec<u8>, Error> {
-        // TODO: Implement actual data receiving
-        // Production implementation would:
-        // 1. Check connection state
-        // 2. Read data from TcpStream with timeout
-        // 3. Handle partial reads and buffering
-        // 4. Update connection statistics
-        // 5. Handle connection errors and reconnection
-        
-        match self.state {
-            ConnectionState::Connected => {
-                // Mock received data
-                Ok(b"Mock server response".to_vec())
-            },
-            ConnectionState::Disconnected => {
-                Err(Error::msg("Cannot receive data: client is not connected"))
-            },
-            ConnectionState::Connecting => {
-                Err(Error::msg("Cannot receive data: connection in progress"))
-            },
-            ConnectionState::Failed(ref reason) => {
-                Err(Error::msg(format!("Cannot receive data: connection failed - {}", reason)))
-            },
+    pub async fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        self.ensure_connected("receive data").await?;
+        if self.read_buffer.is_empty() {
+            self.fill_read_buffer().await?;
+        }
+        Ok(std::mem::take(&mut self.read_buffer))
+    }
+
+    /// Sends `payload` as one length-prefixed frame
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Self::send`], or if the payload is larger than `max_frame_size`.
+    pub async fn send_frame(&mut self, payload: &[u8]) -> Result<(), Error> {
+        let frame = encode_length_prefixed(payload, self.config.max_frame_size)?;
+        self.send(&frame).await.map(|_| ())
+    }
+
+    /// Receives the next length-prefixed frame
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Self::receive`]. A frame announcing more than
+    /// `max_frame_size` bytes fails the connection, since the stream can no
+    /// longer be split into frames.
+    pub async fn receive_frame(&mut self) -> Result<Vec<u8>, Error> {
+        self.ensure_connected("receive data").await?;
+        loop {
+            match decode_length_prefixed(&mut self.read_buffer, self.config.max_frame_size) {
+                Ok(Some(frame)) => return Ok(frame),
+                Ok(None) => self.fill_read_buffer().await?,
+                Err(error) => return Err(self.fail(error)),
+            }
+        }
+    }
+
+    /// Sends `line` followed by `\n`
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Self::send`], or if `line` itself contains a newline.
+    pub async fn send_line(&mut self, line: &str) -> Result<(), Error> {
+        let encoded = encode_line(line)?;
+        self.send(&encoded).await.map(|_| ())
+    }
+
+    /// Receives the next line, without its `\n` or `\r\n` terminator
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Self::receive`], if the line is not valid UTF-8, or if
+    /// more than `max_frame_size` bytes arrive without a newline.
+    pub async fn receive_line(&mut self) -> Result<String, Error> {
+        self.ensure_connected("receive data").await?;
+        loop {
+            if let Some(line) = decode_line(&mut self.read_buffer)? {
+                return Ok(line);
+            }
+            if self.read_buffer.len() > self.config.max_frame_size {
+                return Err(self.fail(format!("line exceeds {} bytes", self.config.max_frame_size)));
+            }
+            self.fill_read_buffer().await?;
         }
     }
-    
+
     /// Closes the TCP connection
-    /// 
+    ///
     /// This method gracefully closes the connection to the server,
     /// ensuring all pending data is sent before closing.
-    /// 
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
-    /// let client = TcpClient::new();
+    /// let mut client = TcpClient::new();
     /// client.connect("127.0.0.1:8080").await?;
     /// // ... perform operations ...
     /// client.disconnect().await;
     /// ```
     pub async fn disconnect(&mut self) {
-        // TODO: Implement graceful connection closure
-        // Production implementation would:
-        // 1. Flush any pending writes
-        // 2. Send connection close signal
-        // 3. Close the TcpStream
-        // 4. Clean up resources
-        // 5. Update connection state
-        
+        self.close_stream().await;
+        self.target = None;
         self.state = ConnectionState::Disconnected;
-        self.remote_addr = None;
     }
-    
+
     /// Returns the current connection state
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// The current `ConnectionState` of the client
-    /// 
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let client = TcpClient::new();
-    /// 
+    ///
     /// match client.connection_state() {
     ///     ConnectionState::Connected => println!("Ready to send/receive data"),
     ///     ConnectionState::Disconnected => println!("Need to connect first"),
@@ -426,18 +473,18 @@ impl TcpClient {
     pub fn connection_state(&self) -> &ConnectionState {
         &self.state
     }
-    
+
     /// Checks if the client is currently connected
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// `true` if connected, `false` otherwise
-    /// 
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let client = TcpClient::new();
-    /// 
+    ///
     /// if client.is_connected() {
     ///     // Safe to send/receive data
     /// }
@@ -445,20 +492,20 @@ impl TcpClient {
     pub fn is_connected(&self) -> bool {
         matches!(self.state, ConnectionState::Connected)
     }
-    
+
     /// Returns the remote server address if connected
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// * `Some(SocketAddr)` - The remote server address if connected
     /// * `None` - If not connected
-    /// 
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
-    /// let client = TcpClient::new();
+    /// let mut client = TcpClient::new();
     /// client.connect("127.0.0.1:8080").await?;
-    /// 
+    ///
     /// if let Some(addr) = client.remote_address() {
     ///     println!("Connected to: {}", addr);
     /// }
@@ -466,23 +513,28 @@ impl TcpClient {
     pub fn remote_address(&self) -> Option<SocketAddr> {
         self.remote_addr
     }
-    
+
+    /// Returns how many times the connection has been re-established
+    pub fn reconnect_count(&self) -> u32 {
+        self.reconnect_count
+    }
+
     /// Updates the client configuration
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `config` - New configuration to apply
-    /// 
+    ///
     /// # Note
-    /// 
-    /// Configuration changes take effect on the next connection attempt.
-    /// Existing connections are not affected.
-    /// 
+    ///
+    /// Socket options and the connect timeout take effect on the next
+    /// connection attempt; read and write timeouts apply immediately.
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```ignore
     /// let mut client = TcpClient::new();
-    /// 
+    ///
     /// let config = TcpClientConfig {
     ///     connect_timeout: Duration::from_secs(30),
     ///     ..Default::default()
@@ -492,19 +544,219 @@ impl TcpClient {
     pub fn set_config(&mut self, config: TcpClientConfig) {
         self.config = config;
     }
-    
+
     /// Gets the current client configuration
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// A reference to the current configuration
     pub fn config(&self) -> &TcpClientConfig {
         &self.config
     }
+
+    /// Connects to `addr`, moving through `Connecting` to `Connected` or `Failed`
+    async fn establish(&mut self, addr: &str) -> Result<(), Error> {
+        self.state = ConnectionState::Connecting;
+        let limit = self.config.connect_timeout;
+        let attempt = tokio::time::timeout(limit, open_stream(addr, &self.config)).await
+            .unwrap_or_else(|_| Err(Error::msg(format!("Connection to {} timed out after {:?}", addr, limit))));
+
+        match attempt {
+            Ok((stream, remote_addr)) => {
+                self.stream = Some(stream);
+                self.remote_addr = Some(remote_addr);
+                self.read_buffer.clear();
+                self.state = ConnectionState::Connected;
+                Ok(())
+            }
+            Err(error) => {
+                self.state = ConnectionState::Failed(error.to_string());
+                Err(error)
+            }
+        }
+    }
+
+    /// Checks that the client can perform `operation`, reconnecting a lost
+    /// connection if `auto_reconnect` is enabled
+    async fn ensure_connected(&mut self, operation: &str) -> Result<(), Error> {
+        match &self.state {
+            ConnectionState::Connected => Ok(()),
+            ConnectionState::Failed(_) if self.config.auto_reconnect && self.target.is_some() => {
+                self.reconnect().await
+            }
+            ConnectionState::Disconnected => {
+                Err(Error::msg(format!("Cannot {}: client is not connected", operation)))
+            }
+            ConnectionState::Connecting => {
+                Err(Error::msg(format!("Cannot {}: connection in progress", operation)))
+            }
+            ConnectionState::Failed(reason) => {
+                Err(Error::msg(format!("Cannot {}: connection failed - {}", operation, reason)))
+            }
+        }
+    }
+
+    async fn write_all(&mut self, data: &[u8]) -> Result<(), Error> {
+        let timeout = self.config.write_timeout;
+        let stream = self.stream.as_mut().ok_or_else(|| Error::msg("Cannot send data: client is not connected"))?;
+        match with_timeout(timeout, "Write", stream.write_all(data)).await? {
+            Ok(()) => Ok(()),
+            Err(error) => Err(self.fail(error)),
+        }
+    }
+
+    /// Reads once from the socket into the read buffer
+    async fn fill_read_buffer(&mut self) -> Result<(), Error> {
+        let timeout = self.config.read_timeout;
+        let mut chunk = vec![0u8; self.config.buffer_size.max(1)];
+        let stream = self.stream.as_mut().ok_or_else(|| Error::msg("Cannot receive data: client is not connected"))?;
+        match with_timeout(timeout, "Read", stream.read(&mut chunk)).await? {
+            Ok(0) => Err(self.fail("connection closed by peer")),
+            Ok(read) => {
+                self.read_buffer.extend_from_slice(&chunk[..read]);
+                Ok(())
+            }
+            Err(error) => Err(self.fail(error)),
+        }
+    }
+
+    /// Drops the connection after an I/O error and records why
+    fn fail(&mut self, reason: impl Display) -> Error {
+        self.stream = None;
+        self.remote_addr = None;
+        self.state = ConnectionState::Failed(reason.to_string());
+        Error::msg(format!("Connection lost: {}", reason))
+    }
+
+    async fn close_stream(&mut self) {
+        if let Some(mut stream) = self.stream.take() {
+            let _ = stream.shutdown().await;
+        }
+        self.remote_addr = None;
+        self.read_buffer.clear();
+    }
+}
+
+/// Resolves `addr` and connects to the first address that accepts
+async fn open_stream(addr: &str, config: &TcpClientConfig) -> Result<(TcpStream, SocketAddr), Error> {
+    let candidates = lookup_host(addr).await
+        .map_err(|e| Error::msg(format!("Invalid or unresolvable address '{}': {}", addr, e)))?;
+
+    let mut last_error = None;
+    for remote_addr in candidates {
+        match open_socket(remote_addr, config).await {
+            Ok(stream) => return Ok((stream, remote_addr)),
+            Err(error) => last_error = Some(error),
+        }
+    }
+    Err(match last_error {
+        Some(error) => Error::msg(format!("Connection to {} failed: {}", addr, error)),
+        None => Error::msg(format!("Address '{}' did not resolve to any socket address", addr)),
+    })
+}
+
+async fn open_socket(remote_addr: SocketAddr, config: &TcpClientConfig) -> io::Result<TcpStream> {
+    let socket = if remote_addr.is_ipv4() { TcpSocket::new_v4()? } else { TcpSocket::new_v6()? };
+    socket.set_keepalive(config.keep_alive)?;
+    let buffer_size = u32::try_from(config.buffer_size).unwrap_or(u32::MAX);
+    socket.set_recv_buffer_size(buffer_size)?;
+    socket.set_send_buffer_size(buffer_size)?;
+    socket.connect(remote_addr).await
+}
+
+/// Runs an I/O future with an optional timeout
+///
+/// The outer error is the timeout, the inner result the I/O outcome.
+async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    operation: &str,
+    future: impl Future<Output = io::Result<T>>,
+) -> Result<io::Result<T>, Error> {
+    match timeout {
+        Some(limit) => tokio::time::timeout(limit, future).await
+            .map_err(|_| Error::msg(format!("{} timed out after {:?}", operation, limit))),
+        None => Ok(future.await),
+    }
+}
+
+/// Encodes `payload` as a frame with a big-endian `u32` length prefix
+///
+/// # Errors
+///
+/// Returns an error if the payload is longer than `max_frame_size` or `u32::MAX`.
+pub fn encode_length_prefixed(payload: &[u8], max_frame_size: usize) -> Result<Vec<u8>, Error> {
+    let length = u32::try_from(payload.len())
+        .ok()
+        .filter(|_| payload.len() <= max_frame_size)
+        .ok_or_else(|| Error::msg(format!("Frame of {} bytes exceeds the limit of {} bytes", payload.len(), max_frame_size)))?;
+    let mut frame = Vec::with_capacity(LENGTH_PREFIX_SIZE + payload.len());
+    frame.extend_from_slice(&length.to_be_bytes());
+    frame.extend_from_slice(payload);
+    Ok(frame)
+}
+
+/// Removes the first complete length-prefixed frame from `buffer`
+///
+/// Returns `Ok(None)` while the frame is incomplete.
+///
+/// # Errors
+///
+/// Returns an error if the frame announces more than `max_frame_size` bytes.
+pub fn decode_length_prefixed(buffer: &mut Vec<u8>, max_frame_size: usize) -> Result<Option<Vec<u8>>, Error> {
+    let Some(prefix) = buffer.get(..LENGTH_PREFIX_SIZE) else {
+        return Ok(None);
+    };
+    let length = u32::from_be_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize;
+    if length > max_frame_size {
+        return Err(Error::msg(format!("Frame of {} bytes exceeds the limit of {} bytes", length, max_frame_size)));
+    }
+    if buffer.len() < LENGTH_PREFIX_SIZE + length {
+        return Ok(None);
+    }
+    let frame = buffer[LENGTH_PREFIX_SIZE..LENGTH_PREFIX_SIZE + length].to_vec();
+    buffer.drain(..LENGTH_PREFIX_SIZE + length);
+    Ok(Some(frame))
+}
+
+/// Encodes `line` followed by a `\n` terminator
+///
+/// # Errors
+///
+/// Returns an error if `line` contains a newline.
+pub fn encode_line(line: &str) -> Result<Vec<u8>, Error> {
+    if line.contains('\n') {
+        return Err(Error::msg("Line-delimited messages cannot contain a newline"));
+    }
+    let mut encoded = Vec::with_capacity(line.len() + 1);
+    encoded.extend_from_slice(line.as_bytes());
+    encoded.push(b'\n');
+    Ok(encoded)
+}
+
+/// Removes the first complete line from `buffer`, without its `\n` or `\r\n`
+///
+/// Returns `Ok(None)` while no newline has arrived.
+///
+/// # Errors
+///
+/// Returns an error if the line is not valid UTF-8. The line is removed
+/// from the buffer either way.
+pub fn decode_line(buffer: &mut Vec<u8>) -> Result<Option<String>, Error> {
+    let Some(end) = buffer.iter().position(|&byte| byte == b'\n') else {
+        return Ok(None);
+    };
+    let mut line: Vec<u8> = buffer.drain(..=end).collect();
+    line.pop();
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    String::from_utf8(line)
+        .map(Some)
+        .map_err(|e| Error::msg(format!("Received line is not valid UTF-8: {}", e)))
 }
 
 /// Default implementation for TcpClientConfig
-/// 
+///
 /// Provides sensible defaults for TCP client configuration.
 impl Default for TcpClientConfig {
     fn default() -> Self {
@@ -517,12 +769,13 @@ impl Default for TcpClientConfig {
             reconnect_delay: Duration::from_secs(1),
             keep_alive: true,
             buffer_size: 8192,
+            max_frame_size: 16 * 1024 * 1024,
         }
     }
 }
 
 /// Default implementation for TcpClient
-/// 
+///
 /// Creates a new TCP client with default configuration.
 impl Default for TcpClient {
     fn default() -> Self {
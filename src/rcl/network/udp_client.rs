@@ -1,21 +1,283 @@
-//! UDP client component for RCL networking
+//! # UDP Client Component
+//!
+//! An asynchronous UDP socket for connectionless datagram communication.
+//! The socket is bound on first use (or explicitly with [`UdpClient::bind`])
+//! and can send to arbitrary peers, be connected to a default peer, send
+//! broadcasts and join multicast groups.
+//!
+//! # Features
+//!
+//! - **Datagrams**: `send_to`/`recv_from` for any peer, `send`/`recv` for a connected one
+//! - **Timeouts**: Optional receive timeout from [`UdpClientConfig`]
+//! - **Broadcast**: Sending to the IPv4 broadcast address of a port
+//! - **Multicast**: Joining and leaving IPv4 and IPv6 groups, TTL and loopback control
+
 use anyhow::Error;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+use tokio::net::{lookup_host, UdpSocket};
+
+/// Largest payload of a single UDP datagram over IPv4
+pub const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+/// Configuration options for UDP sockets
+#[derive(Debug, Clone)]
+pub struct UdpClientConfig {
+    /// Receive operation timeout
+    pub receive_timeout: Option<Duration>,
+    /// Size of the buffer datagrams are received into; longer datagrams are truncated
+    pub buffer_size: usize,
+    /// Allow sending to broadcast addresses
+    pub broadcast: bool,
+    /// Time-to-live of outgoing IPv4 multicast datagrams
+    pub multicast_ttl: u32,
+    /// Deliver multicast datagrams sent by this socket back to it
+    pub multicast_loop: bool,
+}
 
-#[allow(dead_code)]
-pub struct UdpClient {}
+/// An asynchronous UDP socket
+///
+/// # Examples
+///
+/// ```ignore
+/// use crate::rcl::network::udp_client::UdpClient;
+///
+/// let mut client = UdpClient::new();
+/// client.send_to("127.0.0.1:9000", b"ping").await?;
+/// let (reply, from) = client.recv_from().await?;
+/// ```
+pub struct UdpClient {
+    /// Client configuration
+    config: UdpClientConfig,
+    /// Bound socket, created on first use
+    socket: Option<UdpSocket>,
+    /// Default peer set by `connect`
+    peer: Option<SocketAddr>,
+}
 
-#[allow(dead_code)]
 impl UdpClient {
+    /// Creates an unbound UDP client with default configuration
     pub fn new() -> Self {
-        Self {}
+        Self::with_config(UdpClientConfig::default())
+    }
+
+    /// Creates an unbound UDP client with custom configuration
+    pub fn with_config(config: UdpClientConfig) -> Self {
+        Self { config, socket: None, peer: None }
+    }
+
+    /// Binds the socket to `addr`, replacing any existing socket
+    ///
+    /// Use port 0 to let the system choose a port.
+    ///
+    /// # Returns
+    ///
+    /// The local address the socket is bound to
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address is invalid or already in use.
+    pub async fn bind(&mut self, addr: &str) -> Result<SocketAddr, Error> {
+        let socket = UdpSocket::bind(addr).await
+            .map_err(|e| Error::msg(format!("Cannot bind UDP socket to '{}': {}", addr, e)))?;
+        self.apply_options(&socket)?;
+        let local_addr = socket.local_addr()?;
+        self.socket = Some(socket);
+        self.peer = None;
+        Ok(local_addr)
+    }
+
+    /// Sets the default peer for [`Self::send`] and [`Self::recv`]
+    ///
+    /// Datagrams from other addresses are dropped afterwards. The socket is
+    /// bound to an unspecified address first if necessary.
+    pub async fn connect(&mut self, addr: &str) -> Result<(), Error> {
+        let remote = resolve(addr).await?;
+        let socket = self.socket_for(remote).await?;
+        socket.connect(remote).await
+            .map_err(|e| Error::msg(format!("Cannot connect UDP socket to {}: {}", remote, e)))?;
+        self.peer = Some(remote);
+        Ok(())
+    }
+
+    /// Sends one datagram to `addr`
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes sent
+    pub async fn send_to(&mut self, addr: &str, data: &[u8]) -> Result<usize, Error> {
+        let remote = resolve(addr).await?;
+        let socket = self.socket_for(remote).await?;
+        socket.send_to(data, remote).await
+            .map_err(|e| Error::msg(format!("Cannot send datagram to {}: {}", remote, e)))
+    }
+
+    /// Sends one datagram to the connected peer
+    pub async fn send(&self, data: &[u8]) -> Result<usize, Error> {
+        let socket = self.connected_socket("send datagram")?;
+        socket.send(data).await
+            .map_err(|e| Error::msg(format!("Cannot send datagram: {}", e)))
+    }
+
+    /// Sends one datagram to every host on the local IPv4 network at `port`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error unless broadcasting was enabled in the configuration
+    /// or with [`Self::set_broadcast`].
+    pub async fn broadcast(&mut self, port: u16, data: &[u8]) -> Result<usize, Error> {
+        if !self.config.broadcast {
+            return Err(Error::msg("Broadcast is disabled for this socket"));
+        }
+        let target = SocketAddr::new(IpAddr::V4(Ipv4Addr::BROADCAST), port);
+        let socket = self.socket_for(target).await?;
+        socket.send_to(data, target).await
+            .map_err(|e| Error::msg(format!("Cannot send broadcast to port {}: {}", port, e)))
+    }
+
+    /// Receives one datagram and the address it came from
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket is not bound or the receive timeout expires.
+    pub async fn recv_from(&self) -> Result<(Vec<u8>, SocketAddr), Error> {
+        let socket = self.socket.as_ref().ok_or_else(|| Error::msg("Cannot receive datagram: socket is not bound"))?;
+        let mut buffer = vec![0u8; self.config.buffer_size.max(1)];
+        let (length, from) = self.with_timeout(socket.recv_from(&mut buffer)).await?;
+        buffer.truncate(length);
+        Ok((buffer, from))
+    }
+
+    /// Receives one datagram from the connected peer
+    pub async fn recv(&self) -> Result<Vec<u8>, Error> {
+        let socket = self.connected_socket("receive datagram")?;
+        let mut buffer = vec![0u8; self.config.buffer_size.max(1)];
+        let length = self.with_timeout(socket.recv(&mut buffer)).await?;
+        buffer.truncate(length);
+        Ok(buffer)
+    }
+
+    /// Joins the multicast group `group`
+    ///
+    /// IPv4 groups are joined on the default interface, IPv6 groups on
+    /// interface index 0. Bind the socket to the group's port first to
+    /// receive its datagrams.
+    pub async fn join_multicast(&mut self, group: IpAddr) -> Result<(), Error> {
+        let socket = self.socket_for(SocketAddr::new(group, 0)).await?;
+        match group {
+            IpAddr::V4(group) => socket.join_multicast_v4(group, Ipv4Addr::UNSPECIFIED),
+            IpAddr::V6(group) => socket.join_multicast_v6(&group, 0),
+        }
+        .map_err(|e| Error::msg(format!("Cannot join multicast group {}: {}", group, e)))
+    }
+
+    /// Leaves the multicast group `group`
+    pub fn leave_multicast(&self, group: IpAddr) -> Result<(), Error> {
+        let socket = self.socket.as_ref().ok_or_else(|| Error::msg("Cannot leave multicast group: socket is not bound"))?;
+        match group {
+            IpAddr::V4(group) => socket.leave_multicast_v4(group, Ipv4Addr::UNSPECIFIED),
+            IpAddr::V6(group) => socket.leave_multicast_v6(&group, 0),
+        }
+        .map_err(|e| Error::msg(format!("Cannot leave multicast group {}: {}", group, e)))
+    }
+
+    /// Enables or disables sending to broadcast addresses
+    pub fn set_broadcast(&mut self, enabled: bool) -> Result<(), Error> {
+        self.config.broadcast = enabled;
+        if let Some(socket) = &self.socket {
+            socket.set_broadcast(enabled)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the local address if the socket is bound
+    pub fn local_address(&self) -> Option<SocketAddr> {
+        self.socket.as_ref().and_then(|socket| socket.local_addr().ok())
+    }
+
+    /// Returns the peer set by [`Self::connect`]
+    pub fn peer_address(&self) -> Option<SocketAddr> {
+        self.peer
+    }
+
+    /// Checks if the socket is bound
+    pub fn is_bound(&self) -> bool {
+        self.socket.is_some()
+    }
+
+    /// Closes the socket
+    pub fn close(&mut self) {
+        self.socket = None;
+        self.peer = None;
+    }
+
+    /// Gets the current client configuration
+    pub fn config(&self) -> &UdpClientConfig {
+        &self.config
+    }
+
+    /// The bound socket, binding to the unspecified address of `remote`'s family if needed
+    async fn socket_for(&mut self, remote: SocketAddr) -> Result<&UdpSocket, Error> {
+        if self.socket.is_none() {
+            let local: SocketAddr = match remote {
+                SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+                SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+            };
+            self.bind(&local.to_string()).await?;
+        }
+        self.socket.as_ref().ok_or_else(|| Error::msg("UDP socket is not bound"))
     }
 
-    pub async fn send_to(&self, _addr: &str, _data: &[u8]) -> Result<(), Error> {
-        // Mock implementation for testing
-        if _addr == "127.0.0.1:8080" {
-            Ok(())
+    fn connected_socket(&self, operation: &str) -> Result<&UdpSocket, Error> {
+        match (&self.socket, self.peer) {
+            (Some(socket), Some(_)) => Ok(socket),
+            _ => Err(Error::msg(format!("Cannot {}: socket is not connected", operation))),
+        }
+    }
+
+    fn apply_options(&self, socket: &UdpSocket) -> Result<(), Error> {
+        socket.set_broadcast(self.config.broadcast)?;
+        if socket.local_addr()?.is_ipv4() {
+            socket.set_multicast_ttl_v4(self.config.multicast_ttl)?;
+            socket.set_multicast_loop_v4(self.config.multicast_loop)?;
         } else {
-            Err(Error::msg("UDP client not implemented yet. Use tokio for real implementation."))
+            socket.set_multicast_loop_v6(self.config.multicast_loop)?;
+        }
+        Ok(())
+    }
+
+    async fn with_timeout<T>(&self, future: impl std::future::Future<Output = std::io::Result<T>>) -> Result<T, Error> {
+        let result = match self.config.receive_timeout {
+            Some(limit) => tokio::time::timeout(limit, future).await
+                .map_err(|_| Error::msg(format!("Receive timed out after {:?}", limit)))?,
+            None => future.await,
+        };
+        result.map_err(|e| Error::msg(format!("Cannot receive datagram: {}", e)))
+    }
+}
+
+/// Resolves `addr` to its first socket address
+async fn resolve(addr: &str) -> Result<SocketAddr, Error> {
+    lookup_host(addr).await
+        .map_err(|e| Error::msg(format!("Invalid or unresolvable address '{}': {}", addr, e)))?
+        .next()
+        .ok_or_else(|| Error::msg(format!("Address '{}' did not resolve to any socket address", addr)))
+}
+
+impl Default for UdpClientConfig {
+    fn default() -> Self {
+        Self {
+            receive_timeout: Some(Duration::from_secs(30)),
+            buffer_size: MAX_DATAGRAM_SIZE,
+            broadcast: false,
+            multicast_ttl: 1,
+            multicast_loop: true,
         }
     }
 }
+
+impl Default for UdpClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
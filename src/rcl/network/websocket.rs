@@ -1,21 +1,849 @@
-//! WebSocket client component for RCL networking
+//! # WebSocket Client Component
+//!
+//! A WebSocket client that keeps its connection on a background thread so
+//! it can be driven from egui without an async runtime. Connection changes
+//! and incoming messages are delivered as [`WebSocketEvent`]s that callers
+//! poll once per frame, either from the client itself or from any number of
+//! independent [`WebSocketSubscription`]s.
+//!
+//! # Features
+//!
+//! - **Messages**: Text and binary messages in both directions
+//! - **Keepalive**: Periodic pings; a missing pong counts as a lost connection
+//! - **Reconnect**: Exponential backoff after connection loss or abnormal closes
+//! - **Close handshake**: Clean closes with a status code and reason
+//! - **Designer**: [`WebSocketComponent`] places a client on a form as a
+//!   non-visual component with configurable URL and event handlers
+
+use crate::rcl::component_registry::EventDefinition;
+use crate::rcl::ui::component::Component;
 use anyhow::Error;
+use egui::Ui;
+use futures::{SinkExt, StreamExt};
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::time::Instant;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, WebSocketConfig as ProtocolConfig};
+use tokio_tungstenite::tungstenite::Message;
+
+/// Close code for a normal, intentional close
+pub const CLOSE_NORMAL: u16 = 1000;
+/// Close code sent when the client goes away without an explicit close
+pub const CLOSE_GOING_AWAY: u16 = 1001;
+/// Close code reported when the connection ended without a close frame
+pub const CLOSE_ABNORMAL: u16 = 1006;
+
+/// Configuration options for WebSocket connections
+#[derive(Debug, Clone)]
+pub struct WebSocketConfig {
+    /// Time allowed for the TCP connect and opening handshake
+    pub connect_timeout: Duration,
+    /// Interval between keepalive pings, `None` to disable them
+    pub ping_interval: Option<Duration>,
+    /// Time to wait for the pong answering a keepalive ping
+    pub pong_timeout: Duration,
+    /// Reconnect after connection loss or a close that was not normal
+    pub auto_reconnect: bool,
+    /// Delay before the first reconnection attempt, doubled for each further one
+    pub reconnect_delay: Duration,
+    /// Upper bound for the reconnection delay
+    pub max_reconnect_delay: Duration,
+    /// Maximum consecutive reconnection attempts, 0 for no limit
+    pub max_reconnect_attempts: u32,
+    /// Time to wait for the server to answer a close frame
+    pub close_timeout: Duration,
+    /// Largest incoming message accepted, `None` for no limit
+    pub max_message_size: Option<usize>,
+}
+
+/// Connection state of a [`WebSocketClient`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebSocketState {
+    /// Never connected
+    Disconnected,
+    /// Opening handshake in progress
+    Connecting,
+    /// Open and able to send messages
+    Connected,
+    /// Waiting to retry after the connection was lost
+    Reconnecting,
+    /// Close handshake in progress
+    Closing,
+    /// Closed for good, either on request or after giving up
+    Closed,
+}
+
+/// A data message sent or received over the connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebSocketMessage {
+    /// UTF-8 text message
+    Text(String),
+    /// Binary message
+    Binary(Vec<u8>),
+}
+
+/// Something that happened on the connection, in the order it happened
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebSocketEvent {
+    /// The opening handshake completed
+    Connected {
+        /// URL of the endpoint
+        url: String,
+    },
+    /// A data message arrived
+    Message(WebSocketMessage),
+    /// An open connection was lost; a reconnect may follow
+    Disconnected {
+        /// Why the connection ended
+        reason: String,
+    },
+    /// A reconnection attempt is scheduled
+    Reconnecting {
+        /// Attempt number, starting at 1
+        attempt: u32,
+        /// Delay before the attempt
+        delay: Duration,
+    },
+    /// The connection is closed and will not reconnect
+    Closed {
+        /// Close code from the close handshake, or [`CLOSE_ABNORMAL`]
+        code: u16,
+        /// Close reason
+        reason: String,
+    },
+    /// A connection attempt or send failed
+    Error(String),
+}
+
+/// A queue of events of one [`WebSocketClient`]
+///
+/// Every subscription receives every event published after it was created.
+/// Queues are unbounded, so poll or drop subscriptions that are no longer
+/// needed.
+pub struct WebSocketSubscription {
+    receiver: Receiver<WebSocketEvent>,
+}
+
+/// A WebSocket client running its connection on a background thread
+///
+/// # Examples
+///
+/// ```ignore
+/// use crate::rcl::network::websocket::{WebSocketClient, WebSocketEvent, WebSocketMessage};
+///
+/// let mut client = WebSocketClient::new();
+/// client.connect("wss://echo.example.com/ws")?;
+///
+/// // Once per frame
+/// for event in client.poll_events() {
+///     if let WebSocketEvent::Message(WebSocketMessage::Text(text)) = event {
+///         log.push(text);
+///     }
+/// }
+/// ```
+pub struct WebSocketClient {
+    /// Client configuration
+    config: WebSocketConfig,
+    /// URL passed to the last `connect`
+    url: Option<String>,
+    /// State and subscribers shared with the worker
+    shared: Arc<Mutex<Shared>>,
+    /// Commands for the worker of the current connection
+    commands: Option<UnboundedSender<Command>>,
+    /// The client's own subscription, drained by `poll_events`
+    events: WebSocketSubscription,
+}
+
+/// State the worker publishes to the client
+struct Shared {
+    /// Bumped by every `connect`; workers of older connections stay silent
+    generation: u64,
+    state: WebSocketState,
+    reconnect_count: u32,
+    subscribers: Vec<Sender<WebSocketEvent>>,
+}
+
+/// A worker's handle on [`Shared`], valid while its generation is current
+struct Link {
+    shared: Arc<Mutex<Shared>>,
+    generation: u64,
+}
+
+/// Requests from the client to its worker
+enum Command {
+    Send(Message),
+    Close { code: u16, reason: String },
+}
 
-#[allow(dead_code)]
-pub struct WebSocketClient {}
+/// How one connected session ended
+enum SessionEnd {
+    /// Closed with a normal close code; do not reconnect
+    Closed { code: u16, reason: String },
+    /// Lost without a normal close; reconnect if configured
+    Lost(String),
+    /// The client was dropped
+    Shutdown,
+}
 
-#[allow(dead_code)]
 impl WebSocketClient {
+    /// Creates a disconnected client with default configuration
     pub fn new() -> Self {
-        Self {}
+        Self::with_config(WebSocketConfig::default())
+    }
+
+    /// Creates a disconnected client with custom configuration
+    pub fn with_config(config: WebSocketConfig) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let shared = Shared {
+            generation: 0,
+            state: WebSocketState::Disconnected,
+            reconnect_count: 0,
+            subscribers: vec![sender],
+        };
+        Self {
+            config,
+            url: None,
+            shared: Arc::new(Mutex::new(shared)),
+            commands: None,
+            events: WebSocketSubscription { receiver },
+        }
+    }
+
+    /// Starts connecting to `url` in the background
+    ///
+    /// Returns once the worker is started; the outcome is reported as a
+    /// [`WebSocketEvent::Connected`] or [`WebSocketEvent::Error`] event. Any
+    /// previous connection is closed with [`CLOSE_GOING_AWAY`] first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `url` is not a `ws://` or `wss://` URL or the
+    /// worker thread cannot be started.
+    pub fn connect(&mut self, url: &str) -> Result<(), Error> {
+        if !(url.starts_with("ws://") || url.starts_with("wss://")) {
+            return Err(Error::msg(format!("Invalid WebSocket URL '{}': expected ws:// or wss://", url)));
+        }
+        // Dropping the old command channel makes its worker close the connection
+        self.commands = None;
+
+        let (commands, receiver) = unbounded_channel();
+        let generation = {
+            let mut shared = lock(&self.shared);
+            shared.generation += 1;
+            shared.state = WebSocketState::Connecting;
+            shared.reconnect_count = 0;
+            shared.generation
+        };
+        let link = Link { shared: Arc::clone(&self.shared), generation };
+        let worker_url = url.to_string();
+        let config = self.config.clone();
+        thread::Builder::new()
+            .name("websocket".to_string())
+            .spawn(move || {
+                match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                    Ok(runtime) => runtime.block_on(run_connection(worker_url, config, &link, receiver)),
+                    Err(e) => {
+                        link.publish(WebSocketEvent::Error(format!("Cannot start WebSocket runtime: {}", e)));
+                        link.finish(CLOSE_ABNORMAL, "WebSocket runtime unavailable");
+                    }
+                }
+            })
+            .map_err(|e| Error::msg(format!("Cannot start WebSocket worker: {}", e)))?;
+
+        self.url = Some(url.to_string());
+        self.commands = Some(commands);
+        Ok(())
+    }
+
+    /// Queues a text message
+    ///
+    /// # Errors
+    ///
+    /// Returns an error unless the connection is open.
+    pub fn send_text(&self, text: &str) -> Result<(), Error> {
+        self.send_message(Message::Text(text.to_string()))
+    }
+
+    /// Queues a binary message
+    ///
+    /// # Errors
+    ///
+    /// Returns an error unless the connection is open.
+    pub fn send_binary(&self, data: &[u8]) -> Result<(), Error> {
+        self.send_message(Message::Binary(data.to_vec()))
+    }
+
+    /// Queues a data message
+    pub fn send(&self, message: WebSocketMessage) -> Result<(), Error> {
+        match message {
+            WebSocketMessage::Text(text) => self.send_message(Message::Text(text)),
+            WebSocketMessage::Binary(data) => self.send_message(Message::Binary(data)),
+        }
+    }
+
+    /// Starts a normal close handshake
+    ///
+    /// Completion is reported as a [`WebSocketEvent::Closed`] event.
+    pub fn close(&mut self) {
+        self.close_with(CLOSE_NORMAL, "");
     }
 
-    pub async fn connect(&self, _url: &str) -> Result<(), Error> {
-        // Mock implementation for testing
-        if _url == "ws://localhost:8080" {
-            Ok(())
-        } else {
-            Err(Error::msg("WebSocket not implemented yet. Use tokio-tungstenite for real implementation."))
+    /// Starts a close handshake with a specific close code and reason
+    ///
+    /// No reconnect follows, whatever the code.
+    pub fn close_with(&mut self, code: u16, reason: &str) {
+        let Some(commands) = &self.commands else { return };
+        if commands.send(Command::Close { code, reason: reason.to_string() }).is_ok() {
+            let mut shared = lock(&self.shared);
+            if shared.state != WebSocketState::Closed {
+                shared.state = WebSocketState::Closing;
+            }
         }
     }
+
+    /// Returns the events published since the last call, oldest first
+    ///
+    /// Intended to be called once per frame from the UI thread.
+    pub fn poll_events(&self) -> Vec<WebSocketEvent> {
+        self.events.drain()
+    }
+
+    /// Creates an independent event queue for another consumer
+    pub fn subscribe(&self) -> WebSocketSubscription {
+        let (sender, receiver) = mpsc::channel();
+        lock(&self.shared).subscribers.push(sender);
+        WebSocketSubscription { receiver }
+    }
+
+    /// Gets the current connection state
+    pub fn state(&self) -> WebSocketState {
+        lock(&self.shared).state.clone()
+    }
+
+    /// Checks if the connection is open
+    pub fn is_connected(&self) -> bool {
+        self.state() == WebSocketState::Connected
+    }
+
+    /// Returns the URL passed to the last [`Self::connect`]
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    /// Number of reconnections since the last [`Self::connect`]
+    pub fn reconnect_count(&self) -> u32 {
+        lock(&self.shared).reconnect_count
+    }
+
+    /// Gets the current client configuration
+    pub fn config(&self) -> &WebSocketConfig {
+        &self.config
+    }
+
+    fn send_message(&self, message: Message) -> Result<(), Error> {
+        let state = self.state();
+        if state != WebSocketState::Connected {
+            return Err(Error::msg(format!("Cannot send WebSocket message: connection is {:?}", state)));
+        }
+        self.commands
+            .as_ref()
+            .ok_or_else(|| Error::msg("Cannot send WebSocket message: not connected"))?
+            .send(Command::Send(message))
+            .map_err(|_| Error::msg("Cannot send WebSocket message: connection worker stopped"))
+    }
+}
+
+impl WebSocketSubscription {
+    /// Returns the next queued event without waiting
+    pub fn try_recv(&self) -> Option<WebSocketEvent> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Returns all queued events without waiting, oldest first
+    pub fn drain(&self) -> Vec<WebSocketEvent> {
+        self.receiver.try_iter().collect()
+    }
+
+    /// Waits up to `timeout` for the next event
+    ///
+    /// Blocks the calling thread; do not call it from the UI thread or from
+    /// inside an async task.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<WebSocketEvent> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+}
+
+/// Connects, runs sessions and reconnects until closed or given up
+async fn run_connection(
+    url: String,
+    config: WebSocketConfig,
+    link: &Link,
+    mut commands: UnboundedReceiver<Command>,
+) {
+    let protocol = ProtocolConfig { max_message_size: config.max_message_size, ..Default::default() };
+    let mut attempt = 0u32;
+
+    loop {
+        let connected = tokio::time::timeout(
+            config.connect_timeout,
+            tokio_tungstenite::connect_async_with_config(url.as_str(), Some(protocol), true),
+        )
+        .await;
+        let failure = match connected {
+            Ok(Ok((stream, _response))) => {
+                if attempt > 0 {
+                    if let Some(mut shared) = link.current() {
+                        shared.reconnect_count += 1;
+                    }
+                }
+                attempt = 0;
+                link.set_state(WebSocketState::Connected);
+                link.publish(WebSocketEvent::Connected { url: url.clone() });
+                match run_session(stream, &config, link, &mut commands).await {
+                    SessionEnd::Closed { code, reason } => return link.finish(code, &reason),
+                    SessionEnd::Shutdown => return link.finish(CLOSE_GOING_AWAY, "Client dropped"),
+                    SessionEnd::Lost(reason) => {
+                        link.publish(WebSocketEvent::Disconnected { reason: reason.clone() });
+                        reason
+                    }
+                }
+            }
+            Ok(Err(e)) => {
+                let reason = format!("Cannot connect to {}: {}", url, e);
+                link.publish(WebSocketEvent::Error(reason.clone()));
+                reason
+            }
+            Err(_) => {
+                let reason = format!("Connecting to {} timed out after {:?}", url, config.connect_timeout);
+                link.publish(WebSocketEvent::Error(reason.clone()));
+                reason
+            }
+        };
+
+        if !config.auto_reconnect {
+            return link.finish(CLOSE_ABNORMAL, &failure);
+        }
+        if config.max_reconnect_attempts > 0 && attempt >= config.max_reconnect_attempts {
+            let reason = format!("Gave up reconnecting after {} attempts: {}", attempt, failure);
+            return link.finish(CLOSE_ABNORMAL, &reason);
+        }
+        attempt += 1;
+        let delay = backoff_delay(&config, attempt);
+        link.set_state(WebSocketState::Reconnecting);
+        link.publish(WebSocketEvent::Reconnecting { attempt, delay });
+
+        // Honour close requests while waiting; sends fail as there is no connection
+        let retry_at = Instant::now() + delay;
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(retry_at) => break,
+                command = commands.recv() => match command {
+                    Some(Command::Close { code, reason }) => return link.finish(code, &reason),
+                    Some(Command::Send(_)) => {
+                        link.publish(WebSocketEvent::Error("Message dropped: connection is down".to_string()));
+                    }
+                    None => return link.finish(CLOSE_GOING_AWAY, "Client dropped"),
+                },
+            }
+        }
+        link.set_state(WebSocketState::Connecting);
+    }
+}
+
+/// Pumps one open connection until it closes or is lost
+async fn run_session<S>(
+    stream: tokio_tungstenite::WebSocketStream<S>,
+    config: &WebSocketConfig,
+    link: &Link,
+    commands: &mut UnboundedReceiver<Command>,
+) -> SessionEnd
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let (mut sink, mut source) = stream.split();
+    let mut pinger = config.ping_interval.map(|period| tokio::time::interval_at(Instant::now() + period, period));
+    let mut pong_deadline: Option<Instant> = None;
+
+    loop {
+        tokio::select! {
+            incoming = source.next() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    link.publish(WebSocketEvent::Message(WebSocketMessage::Text(text)));
+                }
+                Some(Ok(Message::Binary(data))) => {
+                    link.publish(WebSocketEvent::Message(WebSocketMessage::Binary(data)));
+                }
+                Some(Ok(Message::Pong(_))) => pong_deadline = None,
+                // Pings are answered by the protocol layer on the next read
+                Some(Ok(Message::Ping(_) | Message::Frame(_))) => {}
+                Some(Ok(Message::Close(frame))) => {
+                    // The protocol layer echoes the close frame; wait for the server to hang up
+                    let (code, reason) = close_details(frame);
+                    let _ = tokio::time::timeout(config.close_timeout, async { while source.next().await.is_some() {} }).await;
+                    return if code == CLOSE_NORMAL {
+                        SessionEnd::Closed { code, reason }
+                    } else {
+                        SessionEnd::Lost(format!("Server closed the connection with code {}: {}", code, reason))
+                    };
+                }
+                Some(Err(e)) => return SessionEnd::Lost(format!("Connection error: {}", e)),
+                None => return SessionEnd::Lost("Connection closed without a close frame".to_string()),
+            },
+            command = commands.recv() => match command {
+                Some(Command::Send(message)) => {
+                    if let Err(e) = sink.send(message).await {
+                        return SessionEnd::Lost(format!("Cannot send message: {}", e));
+                    }
+                }
+                Some(Command::Close { code, reason }) => {
+                    close_session(&mut sink, &mut source, config, code, &reason).await;
+                    return SessionEnd::Closed { code, reason };
+                }
+                None => {
+                    close_session(&mut sink, &mut source, config, CLOSE_GOING_AWAY, "").await;
+                    return SessionEnd::Shutdown;
+                }
+            },
+            _ = async { pinger.as_mut().expect("pinger is enabled").tick().await }, if pinger.is_some() => {
+                if pong_deadline.is_none() {
+                    if let Err(e) = sink.send(Message::Ping(Vec::new())).await {
+                        return SessionEnd::Lost(format!("Cannot send keepalive ping: {}", e));
+                    }
+                    pong_deadline = Some(Instant::now() + config.pong_timeout);
+                }
+            }
+            _ = async { tokio::time::sleep_until(pong_deadline.expect("pong is awaited")).await }, if pong_deadline.is_some() => {
+                return SessionEnd::Lost(format!("No pong received within {:?}", config.pong_timeout));
+            }
+        }
+    }
+}
+
+/// Sends a close frame and waits for the server's answer
+async fn close_session<Si, So>(sink: &mut Si, source: &mut So, config: &WebSocketConfig, code: u16, reason: &str)
+where
+    Si: futures::Sink<Message> + Unpin,
+    So: futures::Stream + Unpin,
+{
+    let frame = CloseFrame { code: CloseCode::from(code), reason: reason.to_string().into() };
+    if sink.send(Message::Close(Some(frame))).await.is_ok() {
+        let _ = tokio::time::timeout(config.close_timeout, async { while source.next().await.is_some() {} }).await;
+    }
+}
+
+/// Delay before reconnection `attempt`, doubling from the configured delay
+fn backoff_delay(config: &WebSocketConfig, attempt: u32) -> Duration {
+    let factor = 1u32.checked_shl(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+    config.reconnect_delay.saturating_mul(factor).min(config.max_reconnect_delay)
+}
+
+fn close_details(frame: Option<CloseFrame<'_>>) -> (u16, String) {
+    match frame {
+        Some(frame) => (u16::from(frame.code), frame.reason.into_owned()),
+        None => (CLOSE_NORMAL, String::new()),
+    }
+}
+
+fn lock(shared: &Mutex<Shared>) -> MutexGuard<'_, Shared> {
+    shared.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl Link {
+    /// The shared state, or `None` once a newer connection replaced this one
+    fn current(&self) -> Option<MutexGuard<'_, Shared>> {
+        let shared = lock(&self.shared);
+        (shared.generation == self.generation).then_some(shared)
+    }
+
+    fn set_state(&self, state: WebSocketState) {
+        if let Some(mut shared) = self.current() {
+            shared.state = state;
+        }
+    }
+
+    /// Delivers `event` to every live subscription, forgetting dropped ones
+    fn publish(&self, event: WebSocketEvent) {
+        if let Some(mut shared) = self.current() {
+            shared.subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+        }
+    }
+
+    /// Marks the connection closed for good and reports it
+    fn finish(&self, code: u16, reason: &str) {
+        self.set_state(WebSocketState::Closed);
+        self.publish(WebSocketEvent::Closed { code, reason: reason.to_string() });
+    }
+}
+
+/// A WebSocket connection placed on a form as a non-visual component
+///
+/// In the designer it renders as a small badge showing its URL and state.
+/// At run time it connects to `url` on first render when `auto_connect` is
+/// set, and [`Self::poll`] maps connection events to the handler names bound
+/// to `on_open`, `on_message`, `on_close` and `on_error`. Generated forms use
+/// `take_events` instead and read message payloads with [`Self::next_message`].
+pub struct WebSocketComponent {
+    /// Endpoint URL
+    pub url: String,
+    /// Connect on first render
+    pub auto_connect: bool,
+    /// Reconnect after connection loss
+    pub auto_reconnect: bool,
+    /// Seconds between keepalive pings, 0 to disable them
+    pub ping_interval_secs: u64,
+    /// Handler called when the connection opens
+    pub on_open: String,
+    /// Handler called for each incoming message
+    pub on_message: String,
+    /// Handler called when the connection closes for good
+    pub on_close: String,
+    /// Handler called when connecting fails or the connection is lost
+    pub on_error: String,
+    /// Client created by `start`
+    client: Option<WebSocketClient>,
+    /// Events received by `render` and not yet taken by `poll`
+    pending: Vec<WebSocketEvent>,
+    /// Payloads of message events reported by `take_events`, oldest first
+    inbox: VecDeque<WebSocketMessage>,
+}
+
+impl WebSocketComponent {
+    /// Creates a component for `url` that does not connect automatically
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            auto_connect: false,
+            auto_reconnect: true,
+            ping_interval_secs: 30,
+            on_open: String::new(),
+            on_message: String::new(),
+            on_close: String::new(),
+            on_error: String::new(),
+            client: None,
+            pending: Vec::new(),
+            inbox: VecDeque::new(),
+        }
+    }
+
+    /// Connects to `url` with the component's settings
+    pub fn start(&mut self) -> Result<(), Error> {
+        let config = WebSocketConfig {
+            auto_reconnect: self.auto_reconnect,
+            ping_interval: (self.ping_interval_secs > 0).then(|| Duration::from_secs(self.ping_interval_secs)),
+            ..Default::default()
+        };
+        let mut client = WebSocketClient::with_config(config);
+        let started = client.connect(&self.url);
+        self.client = Some(client);
+        started
+    }
+
+    /// Starts a normal close handshake
+    pub fn stop(&mut self) {
+        if let Some(client) = &mut self.client {
+            client.close();
+        }
+    }
+
+    /// Sends a text message over the open connection
+    pub fn send_text(&self, text: &str) -> Result<(), Error> {
+        self.client
+            .as_ref()
+            .ok_or_else(|| Error::msg("WebSocket component is not started"))?
+            .send_text(text)
+    }
+
+    /// Returns the new events paired with the handler bound to each
+    ///
+    /// Events whose handler is not set are dropped.
+    pub fn poll(&mut self) -> Vec<(String, WebSocketEvent)> {
+        let mut events = std::mem::take(&mut self.pending);
+        if let Some(client) = &self.client {
+            events.extend(client.poll_events());
+        }
+        events
+            .into_iter()
+            .filter_map(|event| {
+                let handler = self.handler_for(&event)?;
+                (!handler.is_empty()).then(|| (handler.to_string(), event))
+            })
+            .collect()
+    }
+
+    /// Pops the payload of the oldest message event reported by `take_events`
+    pub fn next_message(&mut self) -> Option<WebSocketMessage> {
+        self.inbox.pop_front()
+    }
+
+    /// The client, once started
+    pub fn client(&self) -> Option<&WebSocketClient> {
+        self.client.as_ref()
+    }
+
+    /// Current connection state
+    pub fn state(&self) -> WebSocketState {
+        self.client.as_ref().map(WebSocketClient::state).unwrap_or(WebSocketState::Disconnected)
+    }
+
+    fn handler_for(&self, event: &WebSocketEvent) -> Option<&str> {
+        match event {
+            WebSocketEvent::Connected { .. } => Some(&self.on_open),
+            WebSocketEvent::Message(_) => Some(&self.on_message),
+            WebSocketEvent::Closed { .. } => Some(&self.on_close),
+            WebSocketEvent::Error(_) | WebSocketEvent::Disconnected { .. } => Some(&self.on_error),
+            WebSocketEvent::Reconnecting { .. } => None,
+        }
+    }
+
+    /// Name of the component event raised for a connection event
+    fn event_name(event: &WebSocketEvent) -> Option<&'static str> {
+        match event {
+            WebSocketEvent::Connected { .. } => Some("open"),
+            WebSocketEvent::Message(_) => Some("message"),
+            WebSocketEvent::Closed { .. } => Some("close"),
+            WebSocketEvent::Error(_) | WebSocketEvent::Disconnected { .. } => Some("error"),
+            WebSocketEvent::Reconnecting { .. } => None,
+        }
+    }
+}
+
+impl Component for WebSocketComponent {
+    fn name(&self) -> &str {
+        "WebSocket"
+    }
+
+    fn render(&mut self, ui: &mut Ui) {
+        if self.auto_connect && self.client.is_none() {
+            if let Err(e) = self.start() {
+                self.pending.push(WebSocketEvent::Error(e.to_string()));
+            }
+        }
+        if let Some(client) = &self.client {
+            self.pending.extend(client.poll_events());
+            if !matches!(client.state(), WebSocketState::Closed) {
+                // Keep polling while the connection may still produce events
+                ui.ctx().request_repaint_after(Duration::from_millis(100));
+            }
+        }
+
+        let state = self.state();
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("🔌");
+                ui.vertical(|ui| {
+                    ui.strong("WebSocket");
+                    ui.small(&self.url);
+                    ui.small(format!("{:?}", state));
+                });
+            });
+        });
+    }
+
+    fn get_property(&self, name: &str) -> Option<String> {
+        match name {
+            "url" => Some(self.url.clone()),
+            "auto_connect" => Some(self.auto_connect.to_string()),
+            "auto_reconnect" => Some(self.auto_reconnect.to_string()),
+            "ping_interval_secs" => Some(self.ping_interval_secs.to_string()),
+            "on_open" => Some(self.on_open.clone()),
+            "on_message" => Some(self.on_message.clone()),
+            "on_close" => Some(self.on_close.clone()),
+            "on_error" => Some(self.on_error.clone()),
+            _ => None,
+        }
+    }
+
+    fn set_property(&mut self, name: &str, value: &str) -> bool {
+        match name {
+            "url" => {
+                self.url = value.to_string();
+                true
+            }
+            "auto_connect" => value.parse().map(|parsed| self.auto_connect = parsed).is_ok(),
+            "auto_reconnect" => value.parse().map(|parsed| self.auto_reconnect = parsed).is_ok(),
+            "ping_interval_secs" => value.parse().map(|parsed| self.ping_interval_secs = parsed).is_ok(),
+            "on_open" => {
+                self.on_open = value.to_string();
+                true
+            }
+            "on_message" => {
+                self.on_message = value.to_string();
+                true
+            }
+            "on_close" => {
+                self.on_close = value.to_string();
+                true
+            }
+            "on_error" => {
+                self.on_error = value.to_string();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn get_property_names(&self) -> Vec<String> {
+        ["url", "auto_connect", "auto_reconnect", "ping_interval_secs", "on_open", "on_message", "on_close", "on_error"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    fn events(&self) -> Vec<EventDefinition> {
+        vec![
+            EventDefinition::new("open", "The connection opened"),
+            EventDefinition::new("message", "A message arrived; read it with next_message"),
+            EventDefinition::new("close", "The connection closed for good"),
+            EventDefinition::new("error", "Connecting failed or the connection was lost"),
+        ]
+    }
+
+    fn take_events(&mut self) -> Vec<String> {
+        let mut events = std::mem::take(&mut self.pending);
+        if let Some(client) = &self.client {
+            events.extend(client.poll_events());
+        }
+        let mut names = Vec::new();
+        for event in events {
+            let Some(name) = Self::event_name(&event) else { continue };
+            if let WebSocketEvent::Message(message) = event {
+                self.inbox.push_back(message);
+            }
+            names.push(name.to_string());
+        }
+        names
+    }
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            ping_interval: Some(Duration::from_secs(30)),
+            pong_timeout: Duration::from_secs(10),
+            auto_reconnect: true,
+            reconnect_delay: Duration::from_secs(1),
+            max_reconnect_delay: Duration::from_secs(30),
+            max_reconnect_attempts: 0,
+            close_timeout: Duration::from_secs(5),
+            max_message_size: Some(64 << 20),
+        }
+    }
+}
+
+impl Default for WebSocketClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for WebSocketComponent {
+    fn default() -> Self {
+        Self::new("ws://localhost:8080".to_string())
+    }
 }
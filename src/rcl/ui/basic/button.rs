@@ -3,6 +3,7 @@
 //! Provides a simple, clickable button component with text label.
 //! Supports both display and edit modes for design-time interaction.
 
+use crate::rcl::component_registry::EventDefinition;
 use crate::rcl::ui::component::Component;
 
 /// A basic button component with a text label
@@ -16,6 +17,10 @@ pub struct Button {
     label: String,
     /// Whether the button is in edit mode (for design-time editing)
     editable: bool,
+    /// Name of the handler method bound to the click event
+    on_click: String,
+    /// Events raised since the last `take_events` call
+    pending_events: Vec<String>,
 }
 
 impl Button {
@@ -40,6 +45,8 @@ impl Button {
         Self {
             label,
             editable: false,
+            on_click: String::new(),
+            pending_events: Vec::new(),
         }
     }
     
@@ -78,6 +85,11 @@ impl Button {
     pub fn set_label(&mut self, label: String) {
         self.label = label;
     }
+
+    /// Raises the click event as if the button had been pressed
+    pub fn click(&mut self) {
+        self.pending_events.push("click".to_string());
+    }
 }
 
 impl Component for Button {
@@ -105,7 +117,9 @@ impl Component for Button {
             ui.text_edit_singleline(&mut self.label);
         } else {
             // Display mode - show button
-            let _ = ui.button(&self.label);
+            if ui.button(&self.label).clicked() {
+                self.click();
+            }
         }
     }
     
@@ -113,6 +127,7 @@ impl Component for Button {
         match name {
             "label" => Some(self.label.clone()),
             "editable" => Some(self.editable.to_string()),
+            "on_click" => Some(self.on_click.clone()),
             _ => None,
         }
     }
@@ -131,12 +146,24 @@ impl Component for Button {
                     false
                 }
             }
+            "on_click" => {
+                self.on_click = value.to_string();
+                true
+            }
             _ => false,
         }
     }
     
     fn get_property_names(&self) -> Vec<String> {
-        vec!["label".to_string(), "editable".to_string()]
+        vec!["label".to_string(), "editable".to_string(), "on_click".to_string()]
+    }
+
+    fn events(&self) -> Vec<EventDefinition> {
+        vec![EventDefinition::new("click", "The button was pressed")]
+    }
+
+    fn take_events(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.pending_events)
     }
 }
 
@@ -8,6 +8,7 @@
 //! configuration interfaces.
 
 use egui::Ui;
+use crate::rcl::component_registry::EventDefinition;
 use crate::rcl::ui::component::Component;
 
 /// A checkbox component with toggleable state and editable label
@@ -62,6 +63,12 @@ pub struct Checkbox {
     /// When `true`, the label can be edited via a text input field.
     /// When `false`, the component displays as a normal interactive checkbox.
     pub editable: bool,
+    
+    /// Name of the handler method bound to the change event
+    pub on_change: String,
+    
+    /// Events raised since the last `take_events` call
+    pending_events: Vec<String>,
 }
 
 impl Checkbox {
@@ -92,6 +99,8 @@ impl Checkbox {
             label,
             checked,
             editable: false,
+            on_change: String::new(),
+            pending_events: Vec::new(),
         }
     }
     
@@ -190,7 +199,9 @@ impl Component for Checkbox {
             ui.text_edit_singleline(&mut self.label);
         } else {
             // Display mode - show interactive checkbox
-            ui.checkbox(&mut self.checked, &self.label);
+            if ui.checkbox(&mut self.checked, &self.label).changed() {
+                self.pending_events.push("change".to_string());
+            }
         }
         
         // Toggle button to switch between edit and normal modes
@@ -206,6 +217,7 @@ impl Component for Checkbox {
             "label" => Some(self.label.clone()),
             "checked" => Some(self.checked.to_string()),
             "editable" => Some(self.editable.to_string()),
+            "on_change" => Some(self.on_change.clone()),
             _ => None,
         }
     }
@@ -232,11 +244,23 @@ impl Component for Checkbox {
                     false
                 }
             }
+            "on_change" => {
+                self.on_change = value.to_string();
+                true
+            }
             _ => false,
         }
     }
     
     fn get_property_names(&self) -> Vec<String> {
-        vec!["label".to_string(), "checked".to_string(), "editable".to_string()]
+        vec!["label".to_string(), "checked".to_string(), "editable".to_string(), "on_change".to_string()]
+    }
+
+    fn events(&self) -> Vec<EventDefinition> {
+        vec![EventDefinition::new("change", "The checked state was toggled")]
+    }
+
+    fn take_events(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.pending_events)
     }
 }
@@ -9,6 +9,7 @@
 //! numeric value displays with optional editing capabilities.
 
 use egui::Ui;
+use crate::rcl::component_registry::EventDefinition;
 use crate::rcl::ui::component::Component;
 
 /// A numeric slider component with customizable range and editing modes
@@ -71,6 +72,12 @@ pub struct Slider {
     /// When `true`, the slider displays as an interactive control that allows
     /// value adjustment. When `false`, it displays as a read-only numeric label.
     pub editable: bool,
+    
+    /// Name of the handler method bound to the change event
+    pub on_change: String,
+    
+    /// Events raised since the last `take_events` call
+    pending_events: Vec<String>,
 }
 
 impl Slider {
@@ -110,6 +117,8 @@ impl Slider {
             min,
             max,
             editable: false,
+            on_change: String::new(),
+            pending_events: Vec::new(),
         }
     }
     
@@ -261,7 +270,9 @@ impl Component for Slider {
             // - Visual feedback during interaction
             // - Smooth dragging and positioning
             // - Value text display alongside the slider
-            ui.add(egui::Slider::new(&mut self.value, self.min..=self.max).text("Value"));
+            if ui.add(egui::Slider::new(&mut self.value, self.min..=self.max).text("Value")).changed() {
+                self.pending_events.push("change".to_string());
+            }
         } else {
             // Read-only mode - display current value as formatted text
             // Shows the value with 2 decimal precision for consistency
@@ -282,6 +293,7 @@ impl Component for Slider {
             "min" => Some(self.min.to_string()),
             "max" => Some(self.max.to_string()),
             "editable" => Some(self.editable.to_string()),
+            "on_change" => Some(self.on_change.clone()),
             _ => None,
         }
     }
@@ -328,11 +340,23 @@ impl Component for Slider {
                     false
                 }
             }
+            "on_change" => {
+                self.on_change = value.to_string();
+                true
+            }
             _ => false,
         }
     }
     
     fn get_property_names(&self) -> Vec<String> {
-        vec!["value".to_string(), "min".to_string(), "max".to_string(), "editable".to_string()]
+        vec!["value".to_string(), "min".to_string(), "max".to_string(), "editable".to_string(), "on_change".to_string()]
+    }
+
+    fn events(&self) -> Vec<EventDefinition> {
+        vec![EventDefinition::new("change", "The value was moved")]
+    }
+
+    fn take_events(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.pending_events)
     }
 }
@@ -8,6 +8,7 @@
 //! and any scenario requiring multi-line text input with optional editing controls.
 
 use egui::Ui;
+use crate::rcl::component_registry::EventDefinition;
 use crate::rcl::ui::component::Component;
 
 /// A multi-line text input component with toggleable editing mode
@@ -59,6 +60,12 @@ pub struct TextBox {
     /// text area that allows content modification. When `false`, the
     /// content is displayed as read-only text.
     pub editable: bool,
+    
+    /// Name of the handler method bound to the change event
+    pub on_change: String,
+    
+    /// Events raised since the last `take_events` call
+    pending_events: Vec<String>,
 }
 
 impl TextBox {
@@ -86,6 +93,8 @@ impl TextBox {
         Self {
             value,
             editable: false,
+            on_change: String::new(),
+            pending_events: Vec::new(),
         }
     }
     
@@ -201,7 +210,9 @@ impl Component for TextBox {
             // - Copy/paste operations
             // - Multi-line editing with line breaks
             // - Cursor positioning and navigation
-            ui.text_edit_multiline(&mut self.value);
+            if ui.text_edit_multiline(&mut self.value).changed() {
+                self.pending_events.push("change".to_string());
+            }
         } else {
             // Read-only mode - display text content as label
             // Preserves formatting and line breaks but prevents editing
@@ -220,6 +231,7 @@ impl Component for TextBox {
         match name {
             "value" => Some(self.value.clone()),
             "editable" => Some(self.editable.to_string()),
+            "on_change" => Some(self.on_change.clone()),
             _ => None,
         }
     }
@@ -238,11 +250,23 @@ impl Component for TextBox {
                     false
                 }
             }
+            "on_change" => {
+                self.on_change = value.to_string();
+                true
+            }
             _ => false,
         }
     }
     
     fn get_property_names(&self) -> Vec<String> {
-        vec!["value".to_string(), "editable".to_string()]
+        vec!["value".to_string(), "editable".to_string(), "on_change".to_string()]
+    }
+
+    fn events(&self) -> Vec<EventDefinition> {
+        vec![EventDefinition::new("change", "The text was edited")]
+    }
+
+    fn take_events(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.pending_events)
     }
 }
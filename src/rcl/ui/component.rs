@@ -33,6 +33,7 @@
 //! the specific implementation details.
 
 use egui::Ui;
+use crate::rcl::component_registry::EventDefinition;
 
 /// # Core Component Trait
 /// 
@@ -172,4 +173,29 @@ pub trait Component {
     /// 
     /// A vector of property names that can be used with `get_property` and `set_property`
     fn get_property_names(&self) -> Vec<String>;
+
+    /// Returns the events this component can raise.
+    ///
+    /// The IDE lists these in the Events tab of the object inspector. The handler
+    /// bound to an event is stored in the `on_<event>` property, so an event named
+    /// `click` is bound through `set_property("on_click", "button1_click")`.
+    ///
+    /// # Returns
+    ///
+    /// The event definitions, empty for components without events
+    fn events(&self) -> Vec<EventDefinition> {
+        Vec::new()
+    }
+
+    /// Drains the events raised since the last call.
+    ///
+    /// Generated forms call this after rendering and route each event name to its
+    /// bound handler method.
+    ///
+    /// # Returns
+    ///
+    /// The names of the raised events in the order they occurred
+    fn take_events(&mut self) -> Vec<String> {
+        Vec::new()
+    }
 }
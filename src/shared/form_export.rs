@@ -0,0 +1,565 @@
+//! Form Export
+//!
+//! Headless rendering of designer forms to SVG and PNG. A [`ComponentData`]
+//! tree is laid out, drawn as SVG, and rasterized on the CPU with `resvg`,
+//! so exports work without a GPU or an open window.
+//!
+//! Recognized component properties:
+//! - `x`, `y`, `width`, `height` - position (absolute layout only) and size
+//! - `layout` (`vertical`, `horizontal`, `absolute`), `spacing`, `padding`
+//! - `background_color`, `color`, `border_color`, `border_width`, `border_radius`
+//! - `text`, `label`, `title`, `value`, `placeholder`, `font_size`, `font_family`
+//! - `visible`, `checked`
+
+use std::fmt::Write as _;
+use std::sync::{Arc, OnceLock};
+
+use egui::{pos2, vec2, Rect, Vec2};
+
+use super::serialization::{parse_hex_color, ComponentData, PropertyValue, SerializationError};
+
+const DEFAULT_FONT_SIZE: f32 = 14.0;
+const DEFAULT_FONT_FAMILY: &str = "sans-serif";
+const DEFAULT_SPACING: f32 = 8.0;
+const DEFAULT_CONTAINER_PADDING: f32 = 8.0;
+/// Average glyph advance relative to the font size, used to estimate text width
+const CHAR_WIDTH_FACTOR: f32 = 0.6;
+const LINE_HEIGHT_FACTOR: f32 = 1.4;
+
+const WHITE: [u8; 4] = [255, 255, 255, 255];
+const TEXT_COLOR: [u8; 4] = [33, 33, 33, 255];
+const PLACEHOLDER_COLOR: [u8; 4] = [150, 150, 150, 255];
+const BORDER_COLOR: [u8; 4] = [180, 180, 180, 255];
+const BUTTON_COLOR: [u8; 4] = [230, 230, 230, 255];
+
+/// How a container arranges its children
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LayoutKind {
+    Vertical,
+    Horizontal,
+    Absolute,
+}
+
+/// Built-in visual style of a component type
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Widget {
+    Container,
+    Button,
+    Input,
+    Checkbox,
+    Label,
+    Image,
+}
+
+impl Widget {
+    fn of(component_type: &str) -> Self {
+        match component_type.to_ascii_lowercase().as_str() {
+            "button" => Widget::Button,
+            "input" | "text_input" | "textbox" | "text_box" | "text_edit" | "textarea" | "password" => Widget::Input,
+            "checkbox" | "check_box" | "radio" | "radio_button" | "toggle" => Widget::Checkbox,
+            "label" | "text" | "heading" | "title" => Widget::Label,
+            "image" | "picture" | "icon" => Widget::Image,
+            _ => Widget::Container,
+        }
+    }
+}
+
+/// A component positioned on the export canvas
+struct LaidOut<'a> {
+    component: &'a ComponentData,
+    rect: Rect,
+    children: Vec<LaidOut<'a>>,
+}
+
+/// Renders component trees to SVG and PNG
+#[derive(Clone, Debug)]
+pub struct FormRenderer {
+    /// Pixel density multiplier applied when rasterizing
+    pub scale: f32,
+    /// Canvas fill drawn behind the root component
+    pub background: [u8; 4],
+}
+
+impl Default for FormRenderer {
+    fn default() -> Self {
+        Self { scale: 1.0, background: WHITE }
+    }
+}
+
+impl FormRenderer {
+    /// Create a renderer with 1x scale on a white canvas
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render `component` and its children as a standalone SVG document
+    pub fn render_svg(&self, component: &ComponentData) -> String {
+        let root = layout(component, pos2(0.0, 0.0));
+        let size = root.rect.size();
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = fmt_num(size.x),
+            h = fmt_num(size.y),
+        );
+        let _ = writeln!(
+            svg,
+            r#"  <rect width="100%" height="100%" fill="{}"{}/>"#,
+            rgb(self.background),
+            opacity_attr("fill-opacity", self.background),
+        );
+        write_node(&mut svg, &root, 1);
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Render `component` to PNG bytes at [`Self::scale`]
+    pub fn render_png(&self, component: &ComponentData) -> Result<Vec<u8>, SerializationError> {
+        let render_error = |message: String| SerializationError::RenderError { message };
+        let svg = self.render_svg(component);
+
+        let options = resvg::usvg::Options { fontdb: system_fonts(), ..Default::default() };
+        let tree = resvg::usvg::Tree::from_str(&svg, &options).map_err(|e| render_error(e.to_string()))?;
+
+        let size = tree.size().to_int_size().scale_by(self.scale).ok_or_else(|| {
+            render_error(format!("invalid export scale {}", self.scale))
+        })?;
+        let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width(), size.height())
+            .ok_or_else(|| render_error(format!("cannot allocate {}x{} canvas", size.width(), size.height())))?;
+        resvg::render(&tree, resvg::tiny_skia::Transform::from_scale(self.scale, self.scale), &mut pixmap.as_mut());
+
+        pixmap.encode_png().map_err(|e| render_error(e.to_string()))
+    }
+}
+
+/// System font database, loaded once per process
+fn system_fonts() -> Arc<resvg::usvg::fontdb::Database> {
+    static FONTS: OnceLock<Arc<resvg::usvg::fontdb::Database>> = OnceLock::new();
+    FONTS
+        .get_or_init(|| {
+            let mut database = resvg::usvg::fontdb::Database::new();
+            database.load_system_fonts();
+            Arc::new(database)
+        })
+        .clone()
+}
+
+// ---------------------------------------------------------------------------
+// Layout
+// ---------------------------------------------------------------------------
+
+fn layout(component: &ComponentData, origin: egui::Pos2) -> LaidOut<'_> {
+    let size = measure(component);
+    place(component, Rect::from_min_size(origin, size))
+}
+
+/// Position the children of `component` inside `rect`
+fn place(component: &ComponentData, rect: Rect) -> LaidOut<'_> {
+    let padding = content_insets(component);
+    let spacing = number(component, "spacing").unwrap_or(DEFAULT_SPACING);
+    let content_min = rect.min + vec2(padding[3], padding[0]);
+    let mut cursor = content_min;
+
+    let children = visible_children(component)
+        .map(|child| {
+            let size = measure(child);
+            let min = match layout_kind(component) {
+                LayoutKind::Absolute => {
+                    content_min + vec2(number(child, "x").unwrap_or(0.0), number(child, "y").unwrap_or(0.0))
+                }
+                LayoutKind::Vertical => {
+                    let min = cursor;
+                    cursor.y += size.y + spacing;
+                    min
+                }
+                LayoutKind::Horizontal => {
+                    let min = cursor;
+                    cursor.x += size.x + spacing;
+                    min
+                }
+            };
+            place(child, Rect::from_min_size(min, size))
+        })
+        .collect();
+
+    LaidOut { component, rect, children }
+}
+
+/// Size of `component`, from explicit `width`/`height` or its content
+fn measure(component: &ComponentData) -> Vec2 {
+    let width = number(component, "width");
+    let height = number(component, "height");
+    if let (Some(width), Some(height)) = (width, height) {
+        return vec2(width, height);
+    }
+
+    let intrinsic = match Widget::of(&component.component_type) {
+        Widget::Container => container_content_size(component),
+        Widget::Button => {
+            let text = text_size(component, display_text(component).unwrap_or_default());
+            vec2(text.x.max(56.0) + 24.0, text.y + 12.0)
+        }
+        Widget::Input => vec2(200.0, text_size(component, "").y + 12.0),
+        Widget::Checkbox => {
+            let text = text_size(component, display_text(component).unwrap_or_default());
+            vec2(text.x + box_size(component) + 6.0, text.y.max(box_size(component)))
+        }
+        Widget::Label => text_size(component, display_text(component).unwrap_or_default()),
+        Widget::Image => vec2(64.0, 64.0),
+    };
+    vec2(width.unwrap_or(intrinsic.x), height.unwrap_or(intrinsic.y))
+}
+
+fn container_content_size(component: &ComponentData) -> Vec2 {
+    let padding = content_insets(component);
+    let spacing = number(component, "spacing").unwrap_or(DEFAULT_SPACING);
+    let kind = layout_kind(component);
+
+    let mut content = Vec2::ZERO;
+    let mut count = 0usize;
+    for child in visible_children(component) {
+        let size = measure(child);
+        match kind {
+            LayoutKind::Absolute => {
+                content.x = content.x.max(number(child, "x").unwrap_or(0.0) + size.x);
+                content.y = content.y.max(number(child, "y").unwrap_or(0.0) + size.y);
+            }
+            LayoutKind::Vertical => {
+                content.x = content.x.max(size.x);
+                content.y += size.y;
+            }
+            LayoutKind::Horizontal => {
+                content.x += size.x;
+                content.y = content.y.max(size.y);
+            }
+        }
+        count += 1;
+    }
+
+    let gaps = count.saturating_sub(1) as f32 * spacing;
+    match kind {
+        LayoutKind::Vertical => content.y += gaps,
+        LayoutKind::Horizontal => content.x += gaps,
+        LayoutKind::Absolute => {}
+    }
+    content + vec2(padding[1] + padding[3], padding[0] + padding[2])
+}
+
+fn layout_kind(component: &ComponentData) -> LayoutKind {
+    match string(component, "layout").map(str::to_ascii_lowercase).as_deref() {
+        Some("horizontal") | Some("row") => LayoutKind::Horizontal,
+        Some("absolute") | Some("free") => LayoutKind::Absolute,
+        _ => LayoutKind::Vertical,
+    }
+}
+
+fn visible_children(component: &ComponentData) -> impl Iterator<Item = &ComponentData> {
+    component.children.iter().filter(|child| !matches!(child.properties.get("visible"), Some(PropertyValue::Boolean(false))))
+}
+
+/// Padding plus room for a container title, as `[top, right, bottom, left]`
+fn content_insets(component: &ComponentData) -> [f32; 4] {
+    let mut insets = padding(component);
+    if Widget::of(&component.component_type) == Widget::Container {
+        if let Some(title) = display_text(component) {
+            insets[0] += text_size(component, title).y + DEFAULT_SPACING / 2.0;
+        }
+    }
+    insets
+}
+
+fn text_size(component: &ComponentData, text: &str) -> Vec2 {
+    let font_size = font_size(component);
+    let longest_line = text.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+    let lines = text.lines().count().max(1);
+    vec2(longest_line as f32 * font_size * CHAR_WIDTH_FACTOR, lines as f32 * font_size * LINE_HEIGHT_FACTOR)
+}
+
+fn box_size(component: &ComponentData) -> f32 {
+    font_size(component) + 2.0
+}
+
+// ---------------------------------------------------------------------------
+// SVG output
+// ---------------------------------------------------------------------------
+
+fn write_node(svg: &mut String, node: &LaidOut<'_>, depth: usize) {
+    let component = node.component;
+    let indent = "  ".repeat(depth);
+    let widget = Widget::of(&component.component_type);
+    let _ = writeln!(
+        svg,
+        r#"{}<g id="{}" class="{}">"#,
+        indent,
+        escape(&component.id),
+        escape(&component.component_type)
+    );
+
+    let inner = format!("{}  ", indent);
+    match widget {
+        Widget::Checkbox => write_checkbox(svg, node, &inner),
+        Widget::Image => write_box(svg, &inner, node.rect, component, Some([238, 238, 238, 255]), Some(BORDER_COLOR)),
+        _ => {
+            let (fill, border) = match widget {
+                Widget::Button => (Some(BUTTON_COLOR), Some(BORDER_COLOR)),
+                Widget::Input => (Some(WHITE), Some(BORDER_COLOR)),
+                _ => (None, None),
+            };
+            write_box(svg, &inner, node.rect, component, fill, border);
+            write_text_content(svg, node, widget, &inner);
+        }
+    }
+
+    for child in &node.children {
+        write_node(svg, child, depth + 1);
+    }
+    let _ = writeln!(svg, "{}</g>", indent);
+}
+
+/// Background and border of a component; nothing is written if both are absent
+fn write_box(
+    svg: &mut String,
+    indent: &str,
+    rect: Rect,
+    component: &ComponentData,
+    default_fill: Option<[u8; 4]>,
+    default_border: Option<[u8; 4]>,
+) {
+    let fill = color(component, "background_color").or(default_fill);
+    let border_width = number(component, "border_width").unwrap_or(if default_border.is_some() { 1.0 } else { 0.0 });
+    let border = color(component, "border_color").or(default_border).filter(|_| border_width > 0.0);
+    if fill.is_none() && border.is_none() {
+        return;
+    }
+
+    // Inset by half the stroke so borders stay inside the component bounds
+    let inset = if border.is_some() { border_width / 2.0 } else { 0.0 };
+    let rect = rect.shrink(inset);
+    let radius = number(component, "border_radius").unwrap_or(if default_border.is_some() { 3.0 } else { 0.0 });
+
+    let _ = write!(
+        svg,
+        r#"{}<rect x="{}" y="{}" width="{}" height="{}""#,
+        indent,
+        fmt_num(rect.min.x),
+        fmt_num(rect.min.y),
+        fmt_num(rect.width().max(0.0)),
+        fmt_num(rect.height().max(0.0))
+    );
+    if radius > 0.0 {
+        let _ = write!(svg, r#" rx="{}""#, fmt_num(radius));
+    }
+    match fill {
+        Some(fill) => {
+            let _ = write!(svg, r#" fill="{}"{}"#, rgb(fill), opacity_attr("fill-opacity", fill));
+        }
+        None => svg.push_str(r#" fill="none""#),
+    }
+    if let Some(border) = border {
+        let _ = write!(
+            svg,
+            r#" stroke="{}" stroke-width="{}"{}"#,
+            rgb(border),
+            fmt_num(border_width),
+            opacity_attr("stroke-opacity", border)
+        );
+    }
+    svg.push_str("/>\n");
+}
+
+fn write_text_content(svg: &mut String, node: &LaidOut<'_>, widget: Widget, indent: &str) {
+    let component = node.component;
+    let rect = node.rect;
+    let (text, text_color) = match (display_text(component), widget) {
+        (Some(text), _) => (text, color(component, "color").unwrap_or(TEXT_COLOR)),
+        (None, Widget::Input) => match string(component, "placeholder") {
+            Some(placeholder) => (placeholder, PLACEHOLDER_COLOR),
+            None => return,
+        },
+        (None, _) => return,
+    };
+
+    let (x, anchor) = match widget {
+        Widget::Button => (rect.center().x, "middle"),
+        Widget::Input => (rect.min.x + 6.0, "start"),
+        _ => (rect.min.x + padding(component)[3], "start"),
+    };
+    let y = match widget {
+        // Containers show their title above their content
+        Widget::Container => rect.min.y + padding(component)[0] + font_size(component),
+        _ => rect.center().y,
+    };
+    write_text(svg, indent, component, text, x, y, anchor, text_color, widget != Widget::Container);
+}
+
+fn write_checkbox(svg: &mut String, node: &LaidOut<'_>, indent: &str) {
+    let component = node.component;
+    let size = box_size(component);
+    let box_rect = Rect::from_min_size(pos2(node.rect.min.x, node.rect.center().y - size / 2.0), vec2(size, size));
+    write_box(svg, indent, box_rect, component, Some(WHITE), Some([120, 120, 120, 255]));
+
+    if matches!(component.properties.get("checked"), Some(PropertyValue::Boolean(true))) {
+        let check = color(component, "color").unwrap_or(TEXT_COLOR);
+        let _ = writeln!(
+            svg,
+            r#"{}<path d="M{} {} L{} {} L{} {}" fill="none" stroke="{}" stroke-width="2"/>"#,
+            indent,
+            fmt_num(box_rect.min.x + size * 0.2),
+            fmt_num(box_rect.min.y + size * 0.5),
+            fmt_num(box_rect.min.x + size * 0.42),
+            fmt_num(box_rect.min.y + size * 0.75),
+            fmt_num(box_rect.min.x + size * 0.8),
+            fmt_num(box_rect.min.y + size * 0.25),
+            rgb(check)
+        );
+    }
+
+    if let Some(text) = display_text(component) {
+        let text_color = color(component, "color").unwrap_or(TEXT_COLOR);
+        let x = box_rect.max.x + 6.0;
+        write_text(svg, indent, component, text, x, node.rect.center().y, "start", text_color, true);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_text(
+    svg: &mut String,
+    indent: &str,
+    component: &ComponentData,
+    text: &str,
+    x: f32,
+    y: f32,
+    anchor: &str,
+    fill: [u8; 4],
+    centered: bool,
+) {
+    let font_size = font_size(component);
+    let family = string(component, "font_family").unwrap_or(DEFAULT_FONT_FAMILY);
+    let lines: Vec<&str> = text.lines().collect();
+    let line_height = font_size * LINE_HEIGHT_FACTOR;
+    // Baseline of the first line; centered text is balanced around `y`
+    let first_baseline = if centered {
+        y - (lines.len().saturating_sub(1) as f32 * line_height) / 2.0 + font_size * 0.35
+    } else {
+        y
+    };
+
+    let _ = write!(
+        svg,
+        r#"{}<text x="{}" y="{}" font-family="{}" font-size="{}" fill="{}"{}"#,
+        indent,
+        fmt_num(x),
+        fmt_num(first_baseline),
+        escape(family),
+        fmt_num(font_size),
+        rgb(fill),
+        opacity_attr("fill-opacity", fill)
+    );
+    if anchor != "start" {
+        let _ = write!(svg, r#" text-anchor="{}""#, anchor);
+    }
+    svg.push('>');
+    if lines.len() <= 1 {
+        svg.push_str(&escape(text));
+    } else {
+        for (i, line) in lines.iter().enumerate() {
+            let dy = if i == 0 { 0.0 } else { line_height };
+            let _ = write!(svg, r#"<tspan x="{}" dy="{}">{}</tspan>"#, fmt_num(x), fmt_num(dy), escape(line));
+        }
+    }
+    svg.push_str("</text>\n");
+}
+
+// ---------------------------------------------------------------------------
+// Property access
+// ---------------------------------------------------------------------------
+
+fn number(component: &ComponentData, key: &str) -> Option<f32> {
+    match component.properties.get(key)? {
+        PropertyValue::Number(n) if n.is_finite() => Some(*n as f32),
+        PropertyValue::String(s) => s.trim().trim_end_matches("px").parse().ok(),
+        _ => None,
+    }
+}
+
+fn string<'a>(component: &'a ComponentData, key: &str) -> Option<&'a str> {
+    match component.properties.get(key)? {
+        PropertyValue::String(s) if !s.is_empty() => Some(s),
+        _ => None,
+    }
+}
+
+fn color(component: &ComponentData, key: &str) -> Option<[u8; 4]> {
+    match component.properties.get(key)? {
+        PropertyValue::Color(color) => Some(*color),
+        PropertyValue::String(s) => parse_hex_color(s),
+        _ => None,
+    }
+}
+
+fn display_text(component: &ComponentData) -> Option<&str> {
+    ["text", "label", "title", "value"].iter().find_map(|key| string(component, key))
+}
+
+fn font_size(component: &ComponentData) -> f32 {
+    number(component, "font_size").filter(|size| *size > 0.0).unwrap_or(DEFAULT_FONT_SIZE)
+}
+
+/// Padding as `[top, right, bottom, left]`
+fn padding(component: &ComponentData) -> [f32; 4] {
+    match component.properties.get("padding") {
+        Some(PropertyValue::Number(n)) => [*n as f32; 4],
+        Some(PropertyValue::Object(sides)) => {
+            let side = |key: &str| match sides.get(key) {
+                Some(PropertyValue::Number(n)) => *n as f32,
+                _ => 0.0,
+            };
+            [side("top"), side("right"), side("bottom"), side("left")]
+        }
+        _ if Widget::of(&component.component_type) == Widget::Container => [DEFAULT_CONTAINER_PADDING; 4],
+        _ => [0.0; 4],
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Formatting
+// ---------------------------------------------------------------------------
+
+fn rgb(color: [u8; 4]) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+fn opacity_attr(attribute: &str, color: [u8; 4]) -> String {
+    if color[3] == 255 {
+        String::new()
+    } else {
+        format!(r#" {}="{}""#, attribute, fmt_num(color[3] as f32 / 255.0))
+    }
+}
+
+/// Format a coordinate with at most two decimals and no trailing zeros
+fn fmt_num(value: f32) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    if rounded == rounded.trunc() {
+        format!("{}", rounded as i64)
+    } else {
+        format!("{}", rounded)
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
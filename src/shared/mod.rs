@@ -7,6 +7,7 @@ pub mod geometry;
 pub mod color_utils;
 pub mod validation;
 pub mod serialization;
+pub mod form_export;
 pub mod performance;
 pub mod serde_system_time;
 pub mod serde_instant;
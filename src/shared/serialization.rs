@@ -4,7 +4,19 @@
 //! project data, and export/import functionality.
 
 use std::collections::HashMap;
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::form_export::FormRenderer;
+
+/// Schema version written by [`SerializationUtils`]
+///
+/// Bump this and register a [`SchemaMigration`] from the previous version
+/// whenever the serialized shape of components or projects changes.
+pub const CURRENT_SCHEMA_VERSION: &str = "1.0.0";
+
+/// Schema version assumed for data written before `schema_version` existed
+pub const INITIAL_SCHEMA_VERSION: &str = "1.0.0";
 
 /// Trait for serializable components
 pub trait SerializableComponent {
@@ -24,7 +36,8 @@ pub trait SerializableComponent {
 }
 
 /// Export format options
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ExportFormat {
     /// Native IDE format (JSON)
     Native,
@@ -71,12 +84,15 @@ pub enum SerializationError {
     #[error("Export format not supported: {format:?}")]
     UnsupportedFormat { format: ExportFormat },
     
+    #[error("Render error: {message}")]
+    RenderError { message: String },
+    
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 }
 
 /// Component serialization data
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ComponentData {
     /// Component type identifier
     pub component_type: String,
@@ -93,7 +109,7 @@ pub struct ComponentData {
 }
 
 /// Component metadata
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ComponentMetadata {
     /// Creation timestamp
     pub created_at: chrono::DateTime<chrono::Utc>,
@@ -110,7 +126,10 @@ pub struct ComponentMetadata {
 }
 
 /// Property value for serialization
-#[derive(Clone, Debug)]
+///
+/// Serialized as `{"type": "...", "value": ...}`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum PropertyValue {
     String(String),
     Number(f64),
@@ -122,7 +141,7 @@ pub enum PropertyValue {
 }
 
 /// Project serialization data
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ProjectData {
     /// Project metadata
     pub metadata: ProjectMetadata,
@@ -139,7 +158,7 @@ pub struct ProjectData {
 }
 
 /// Project metadata
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ProjectMetadata {
     /// Project name
     pub name: String,
@@ -160,7 +179,7 @@ pub struct ProjectMetadata {
 }
 
 /// Asset data
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AssetData {
     /// Asset ID
     pub id: String,
@@ -175,7 +194,8 @@ pub struct AssetData {
 }
 
 /// Asset type enumeration
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AssetType {
     Image,
     Font,
@@ -187,7 +207,7 @@ pub enum AssetType {
 }
 
 /// Design system serialization data
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct DesignSystemData {
     /// Design tokens
     pub tokens: HashMap<String, DesignToken>,
@@ -198,7 +218,7 @@ pub struct DesignSystemData {
 }
 
 /// Design token data
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct DesignToken {
     /// Token name
     pub name: String,
@@ -211,7 +231,7 @@ pub struct DesignToken {
 }
 
 /// Component template data
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ComponentTemplate {
     /// Template name
     pub name: String,
@@ -224,7 +244,7 @@ pub struct ComponentTemplate {
 }
 
 /// Style guide data
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct StyleGuideData {
     /// Color palette
     pub colors: Vec<String>,
@@ -237,7 +257,7 @@ pub struct StyleGuideData {
 }
 
 /// Typography settings
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TypographySettings {
     /// Font families
     pub font_families: Vec<String>,
@@ -250,7 +270,7 @@ pub struct TypographySettings {
 }
 
 /// Project settings
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ProjectSettings {
     /// Default canvas size
     pub canvas_size: (f64, f64),
@@ -263,7 +283,7 @@ pub struct ProjectSettings {
 }
 
 /// Grid settings
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GridSettings {
     /// Grid enabled
     pub enabled: bool,
@@ -276,7 +296,7 @@ pub struct GridSettings {
 }
 
 /// Snap settings
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SnapSettings {
     /// Snap to grid enabled
     pub to_grid: bool,
@@ -287,7 +307,7 @@ pub struct SnapSettings {
 }
 
 /// Export settings
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ExportSettings {
     /// Default export format
     pub default_format: ExportFormat,
@@ -360,27 +380,82 @@ impl Default for ExportSettings {
 }
 
 impl SerializationUtils {
-    /// Serialize component to JSON string (placeholder)
-    pub fn serialize_component(_component: &ComponentData) -> Result<String, SerializationError> {
-        Err(SerializationError::MigrationError { message: "Serialization not implemented".to_string() })
+    /// Serialize component to JSON, stamped with [`CURRENT_SCHEMA_VERSION`]
+    ///
+    /// Object keys are written in sorted order so output is stable across runs.
+    pub fn serialize_component(component: &ComponentData) -> Result<String, SerializationError> {
+        let mut value = to_json_value(component)?;
+        set_component_version(&mut value, CURRENT_SCHEMA_VERSION);
+        to_json_string(&value)
     }
     
-    /// Deserialize component from JSON string (placeholder)
-    pub fn deserialize_component(_json: &str) -> Result<ComponentData, SerializationError> {
-        Err(SerializationError::MigrationError { message: "Deserialization not implemented".to_string() })
+    /// Deserialize component from JSON, migrating older schema versions
+    pub fn deserialize_component(json: &str) -> Result<ComponentData, SerializationError> {
+        let mut value = parse_json(json)?;
+        Self::migrate_component_value(&mut value, CURRENT_SCHEMA_VERSION)?;
+        serde_json::from_value(value).map_err(|e| SerializationError::JsonError(e.to_string()))
     }
     
-    /// Serialize project to JSON string (placeholder)
-    pub fn serialize_project(_project: &ProjectData) -> Result<String, SerializationError> {
-        Err(SerializationError::MigrationError { message: "Project serialization not implemented".to_string() })
+    /// Serialize project to JSON, stamped with [`CURRENT_SCHEMA_VERSION`]
+    ///
+    /// Object keys are written in sorted order so output is stable across runs.
+    pub fn serialize_project(project: &ProjectData) -> Result<String, SerializationError> {
+        let mut value = to_json_value(project)?;
+        value["schema_version"] = Value::from(CURRENT_SCHEMA_VERSION);
+        for_each_project_component(&mut value, &mut |component| {
+            set_component_version(component, CURRENT_SCHEMA_VERSION);
+            Ok(())
+        })?;
+        to_json_string(&value)
+    }
+    
+    /// Deserialize project from JSON, migrating older schema versions
+    pub fn deserialize_project(json: &str) -> Result<ProjectData, SerializationError> {
+        let mut value = parse_json(json)?;
+        Self::migrate_project_value(&mut value, CURRENT_SCHEMA_VERSION)?;
+        serde_json::from_value(value).map_err(|e| SerializationError::JsonError(e.to_string()))
     }
     
-    /// Deserialize project from JSON string (placeholder)
-    pub fn deserialize_project(_json: &str) -> Result<ProjectData, SerializationError> {
-        Err(SerializationError::MigrationError { message: "Project deserialization not implemented".to_string() })
+    /// Registered schema migrations, oldest first
+    pub fn migrations() -> &'static [SchemaMigration] {
+        MIGRATIONS
+    }
+    
+    /// Migrate serialized component JSON, including its children, to `target_version`
+    pub fn migrate_component_value(value: &mut Value, target_version: &str) -> Result<(), SerializationError> {
+        let version = schema_version_of(value)?;
+        if value.get("schema_version").is_none() {
+            as_object_mut(value, "component")?;
+            set_component_version(value, &version);
+        }
+        for migration in migration_path(&version, target_version)? {
+            migrate_component_tree(migration, value)?;
+        }
+        Ok(())
+    }
+    
+    /// Migrate serialized project JSON, including all nested components, to `target_version`
+    pub fn migrate_project_value(value: &mut Value, target_version: &str) -> Result<(), SerializationError> {
+        let version = schema_version_of(value)?;
+        if value.get("schema_version").is_none() {
+            as_object_mut(value, "project")?;
+            value["schema_version"] = Value::from(version.as_str());
+            for_each_project_component(value, &mut |component| {
+                set_component_version(component, &version);
+                Ok(())
+            })?;
+        }
+        for migration in migration_path(&version, target_version)? {
+            (migration.project)(as_object_mut(value, "project")?)?;
+            value["schema_version"] = Value::from(migration.to);
+            for_each_project_component(value, &mut |component| migrate_component_tree(migration, component))?;
+        }
+        Ok(())
     }
     
     /// Convert component to different export format
+    ///
+    /// Binary formats such as PNG are produced by [`Self::export_component_bytes`].
     pub fn export_component(
         component: &ComponentData,
         format: &ExportFormat,
@@ -391,12 +466,27 @@ impl SerializationUtils {
             ExportFormat::VueTemplate => Self::to_vue_template(component),
             ExportFormat::AngularTemplate => Self::to_angular_template(component),
             ExportFormat::HtmlCss => Self::to_html_css(component),
+            ExportFormat::Svg => Ok(FormRenderer::new().render_svg(component)),
             _ => Err(SerializationError::UnsupportedFormat {
                 format: format.clone(),
             }),
         }
     }
     
+    /// Convert component to an export format, including binary image formats
+    ///
+    /// PNG is rasterized at 1x; use [`FormRenderer`] directly for other scales.
+    /// Text formats are returned as UTF-8.
+    pub fn export_component_bytes(
+        component: &ComponentData,
+        format: &ExportFormat,
+    ) -> Result<Vec<u8>, SerializationError> {
+        match format {
+            ExportFormat::Png => FormRenderer::new().render_png(component),
+            _ => Self::export_component(component, format).map(String::into_bytes),
+        }
+    }
+    
     /// Convert component to React JSX
     fn to_react_jsx(component: &ComponentData) -> Result<String, SerializationError> {
         let mut jsx = String::new();
@@ -629,24 +719,147 @@ impl SerializationUtils {
         }
     }
     
-    /// Migrate data from older schema version
+    /// Bring in-memory component data up to `target_version`
+    ///
+    /// `ComponentData` always has the current shape, so this checks that a
+    /// migration path exists and relabels the component tree. Serialized data
+    /// from older versions is migrated by [`Self::deserialize_component`].
     pub fn migrate_component_data(
         mut data: ComponentData,
         target_version: &str,
     ) -> Result<ComponentData, SerializationError> {
-        // Simple migration example
-        if data.schema_version == "1.0.0" && target_version == "1.1.0" {
-            // Add default metadata if missing
-            if data.metadata.tags.is_empty() {
-                data.metadata.tags.push("migrated".to_string());
+        migration_path(&data.schema_version, target_version)?;
+        fn relabel(data: &mut ComponentData, version: &str) {
+            data.schema_version = version.to_string();
+            for child in &mut data.children {
+                relabel(child, version);
             }
-            data.schema_version = target_version.to_string();
         }
-        
+        relabel(&mut data, target_version);
         Ok(data)
     }
 }
 
+/// One step in the schema migration chain
+///
+/// Migrations operate on raw JSON so that files whose shape no longer
+/// matches the current data types can still be loaded.
+pub struct SchemaMigration {
+    /// Version migrated from
+    pub from: &'static str,
+    /// Version migrated to
+    pub to: &'static str,
+    /// Summary of the schema change
+    pub description: &'static str,
+    /// Applied to every component object, children included
+    component: fn(&mut Map<String, Value>) -> Result<(), SerializationError>,
+    /// Applied to the project object before its components
+    project: fn(&mut Map<String, Value>) -> Result<(), SerializationError>,
+}
+
+/// Registered migrations, oldest first; empty until the schema first changes
+const MIGRATIONS: &[SchemaMigration] = &[];
+
+pub(crate) fn parse_hex_color(s: &str) -> Option<[u8; 4]> {
+    let hex = s.strip_prefix('#')?;
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some([channel(0)?, channel(2)?, channel(4)?, alpha])
+}
+
+/// Migrations leading from `from` to `to`, in order
+fn migration_path(from: &str, to: &str) -> Result<Vec<&'static SchemaMigration>, SerializationError> {
+    let parse = |version: &str| {
+        semver::Version::parse(version).map_err(|_| SerializationError::InvalidFormat {
+            message: format!("invalid schema version '{}'", version),
+        })
+    };
+    if parse(from)? > parse(to)? {
+        return Err(SerializationError::VersionMismatch { expected: to.to_string(), found: from.to_string() });
+    }
+
+    let mut path = Vec::new();
+    let mut current = from;
+    while current != to {
+        let migration = MIGRATIONS.iter().find(|m| m.from == current).ok_or_else(|| {
+            SerializationError::MigrationError { message: format!("no migration from schema version {}", current) }
+        })?;
+        path.push(migration);
+        current = migration.to;
+    }
+    Ok(path)
+}
+
+fn migrate_component_tree(migration: &SchemaMigration, value: &mut Value) -> Result<(), SerializationError> {
+    let component = as_object_mut(value, "component")?;
+    (migration.component)(component)?;
+    component.insert("schema_version".to_string(), Value::from(migration.to));
+    if let Some(Value::Array(children)) = component.get_mut("children") {
+        for child in children {
+            migrate_component_tree(migration, child)?;
+        }
+    }
+    Ok(())
+}
+
+/// Visit the root components and design system templates of a project
+fn for_each_project_component(
+    project: &mut Value,
+    visit: &mut dyn FnMut(&mut Value) -> Result<(), SerializationError>,
+) -> Result<(), SerializationError> {
+    if let Some(Value::Array(components)) = project.get_mut("components") {
+        for component in components {
+            visit(component)?;
+        }
+    }
+    if let Some(Value::Array(templates)) = project.pointer_mut("/design_system/components") {
+        for template in templates {
+            if let Some(component) = template.get_mut("template") {
+                visit(component)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn set_component_version(value: &mut Value, version: &str) {
+    value["schema_version"] = Value::from(version);
+    if let Some(Value::Array(children)) = value.get_mut("children") {
+        for child in children {
+            set_component_version(child, version);
+        }
+    }
+}
+
+fn schema_version_of(value: &Value) -> Result<String, SerializationError> {
+    match value.get("schema_version") {
+        None => Ok(INITIAL_SCHEMA_VERSION.to_string()),
+        Some(Value::String(version)) => Ok(version.clone()),
+        Some(_) => Err(SerializationError::InvalidFormat { message: "schema_version must be a string".to_string() }),
+    }
+}
+
+fn as_object_mut<'a>(value: &'a mut Value, what: &str) -> Result<&'a mut Map<String, Value>, SerializationError> {
+    value.as_object_mut().ok_or_else(|| SerializationError::InvalidFormat {
+        message: format!("{} must be a JSON object", what),
+    })
+}
+
+fn parse_json(json: &str) -> Result<Value, SerializationError> {
+    serde_json::from_str(json).map_err(|e| SerializationError::JsonError(e.to_string()))
+}
+
+fn to_json_value<T: Serialize>(data: &T) -> Result<Value, SerializationError> {
+    serde_json::to_value(data).map_err(|e| SerializationError::JsonError(e.to_string()))
+}
+
+fn to_json_string(value: &Value) -> Result<String, SerializationError> {
+    serde_json::to_string_pretty(value).map_err(|e| SerializationError::JsonError(e.to_string()))
+}
+
 /// Custom serializer for DateTime (placeholder)
 pub fn serialize_datetime(date: &chrono::DateTime<chrono::Utc>) -> String {
     date.to_rfc3339()
@@ -0,0 +1,166 @@
+//! Scripted language server shared by the LSP tests
+//!
+//! The fake server is the test binary itself re-executed with
+//! `FAKE_LSP_SCRIPT` set: it reads framed messages from stdin, answers from
+//! the script and writes its output to fd 3, leaving stdout to the test
+//! harness. Every received message is appended to a log for assertions.
+#![allow(dead_code)]
+
+use ide_rs::editor::lsp_integration::{read_message, write_message, Position, Range};
+use ide_rs::editor::lsp_service::{path_to_uri, LspServerConfig};
+use ide_rs::editor::rust_analyzer::RustAnalyzerService;
+use ide_rs::editor::language_service::LanguageService;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, Write};
+use std::os::fd::FromRawFd;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+#[test]
+pub fn fake_language_server() {
+    let Ok(script) = std::env::var("FAKE_LSP_SCRIPT") else {
+        return;
+    };
+    let script: Value = serde_json::from_str(&std::fs::read_to_string(script).unwrap()).unwrap();
+    let mut log = OpenOptions::new().create(true).append(true).open(script["log"].as_str().unwrap()).unwrap();
+    let mut input = BufReader::new(std::io::stdin());
+    let mut output = unsafe { File::from_raw_fd(3) };
+
+    let mut receive = |input: &mut BufReader<std::io::Stdin>| {
+        let message = read_message(input).unwrap()?;
+        writeln!(log, "{}", message).unwrap();
+        Some(message)
+    };
+    // Messages that arrived while waiting for the client to answer a request
+    let mut backlog = VecDeque::new();
+
+    while let Some(message) = backlog.pop_front().or_else(|| receive(&mut input)) {
+        let Some(method) = message["method"].as_str() else {
+            continue;
+        };
+        if method == "exit" || script["exit_on"].as_str() == Some(method) {
+            std::process::exit(0);
+        }
+
+        for outgoing in script["on"][method].as_array().into_iter().flatten() {
+            write_message(&mut output, outgoing).unwrap();
+            // Wait for the client to answer requests sent by the server
+            if outgoing.get("id").is_some() {
+                while let Some(reply) = receive(&mut input) {
+                    if reply.get("method").is_none() && reply["id"] == outgoing["id"] {
+                        break;
+                    }
+                    backlog.push_back(reply);
+                }
+            }
+        }
+
+        let Some(id) = message.get("id") else {
+            continue;
+        };
+        if script["ignore"].as_array().into_iter().flatten().any(|ignored| ignored == method) {
+            continue;
+        }
+        let reply = match script["errors"].get(method) {
+            Some(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+            None => json!({ "jsonrpc": "2.0", "id": id, "result": script["responses"][method] }),
+        };
+        write_message(&mut output, &reply).unwrap();
+    }
+}
+
+/// Stands for the workspace root URI in scripts
+pub const ROOT: &str = "file:///workspace-root";
+
+/// URI of a file in the workspace, for use in scripts
+pub fn uri(name: &str) -> String {
+    format!("{}/{}", ROOT, name)
+}
+
+/// Workspace directory, script and message log for one fake server
+pub struct FakeServer {
+    dir: TempDir,
+    script: PathBuf,
+}
+
+impl FakeServer {
+    pub fn new(mut script: Value) -> Self {
+        let dir = TempDir::new().unwrap();
+        let log = dir.path().join("received.jsonl");
+        script["log"] = json!(log.to_string_lossy());
+        if script["responses"].get("initialize").is_none() {
+            script["responses"]["initialize"] = json!({ "capabilities": {} });
+        }
+        let path = dir.path().join("script.json");
+        let script = script.to_string().replace(ROOT, &path_to_uri(dir.path()));
+        std::fs::write(&path, script).unwrap();
+        Self { dir, script: path }
+    }
+
+    pub fn root(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub fn file(&self, name: &str) -> PathBuf {
+        self.dir.path().join(name)
+    }
+
+    /// Command and arguments launching this server
+    pub fn command(&self) -> (String, Vec<String>) {
+        let exe = std::env::current_exe().unwrap();
+        let args = vec![
+            "-c".to_string(),
+            "FAKE_LSP_SCRIPT=\"$1\" exec \"$0\" fake_lsp::fake_language_server --exact 3>&1 1>&2".to_string(),
+            exe.to_string_lossy().into_owned(),
+            self.script.to_string_lossy().into_owned(),
+        ];
+        ("sh".to_string(), args)
+    }
+
+    /// Configuration launching this server for `language`
+    pub fn config(&self, language: &str, extensions: &[&str]) -> LspServerConfig {
+        let (command, args) = self.command();
+        LspServerConfig::new(language, command).with_args(args).with_extensions(extensions.iter().copied())
+    }
+
+    pub fn service(&self) -> RustAnalyzerService {
+        let (command, args) = self.command();
+        RustAnalyzerService::with_command(command, args)
+    }
+
+    pub async fn start(&self) -> RustAnalyzerService {
+        let mut service = self.service();
+        service.initialize(self.root()).await.unwrap();
+        service
+    }
+
+    /// Messages the server received so far
+    pub fn received(&self) -> Vec<Value> {
+        std::fs::read_to_string(self.file("received.jsonl"))
+            .unwrap_or_default()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    pub fn received_method(&self, method: &str) -> Vec<Value> {
+        self.received().into_iter().filter(|message| message["method"] == method).collect()
+    }
+}
+
+pub fn position(line: u64, character: u64) -> Position {
+    Position { line, character }
+}
+
+pub fn range(start: (u64, u64), end: (u64, u64)) -> Value {
+    json!({
+        "start": { "line": start.0, "character": start.1 },
+        "end": { "line": end.0, "character": end.1 }
+    })
+}
+
+pub fn span(range: &Range) -> (u64, u64, u64, u64) {
+    (range.start.line, range.start.character, range.end.line, range.end.character)
+}
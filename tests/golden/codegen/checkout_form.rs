@@ -0,0 +1,103 @@
+//! CheckoutForm form component
+//!
+//! This form was generated by the visual designer.
+
+use ide_rs::rcl::ui::component::Component;
+use ide_rs::rcl::ui::basic::button::Button;
+use ide_rs::rcl::ui::basic::label::Label;
+
+/// CheckoutForm form
+pub struct CheckoutForm {
+    pub pay: Button,
+    pub total: Label,
+    // <codegen:guard:form_fields:start>
+    pub paid: bool,
+    // <codegen:guard:form_fields:end>
+}
+
+impl CheckoutForm {
+    pub fn new() -> Self {
+        let mut form = Self {
+            pay: Button::new(String::new()),
+            total: Label::new(String::new()),
+            // <codegen:guard:form_init:start>
+            paid: false,
+            // <codegen:guard:form_init:end>
+        };
+        form.pay.set_property("label", "Pay");
+        form.total.set_property("text", "Total: 0");
+        form
+    }
+
+    // <codegen:guard:form_methods:start>
+    // Form methods
+    // <codegen:guard:form_methods:end>
+
+    /// Rect of each component relative to the form's top-left corner, as laid out in the designer
+    pub fn layout() -> Vec<(&'static str, egui::Rect)> {
+        vec![
+            ("pay", egui::Rect::from_min_size(egui::pos2(20.0, 20.0), egui::vec2(100.0, 30.0))),
+            ("total", egui::Rect::from_min_size(egui::pos2(20.0, 60.0), egui::vec2(100.0, 30.0))),
+        ]
+    }
+
+    // <codegen:generated:event_dispatch:start>
+    /// Route the events raised by components to their handlers
+    pub fn dispatch_events(&mut self) {
+        for event in self.pay.take_events() {
+            match event.as_str() {
+                "click" => self.pay_click(),
+                _ => {}
+            }
+        }
+    }
+    // <codegen:generated:event_dispatch:end>
+}
+
+impl CheckoutForm {
+    // <codegen:guard:event_handlers:start>
+    pub fn pay_click(&mut self) {
+        self.paid = true;
+    }
+    // <codegen:guard:event_handlers:end>
+}
+
+impl Default for CheckoutForm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Component for CheckoutForm {
+    fn name(&self) -> &str {
+        "CheckoutForm"
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        let origin = ui.max_rect().min.to_vec2();
+        for (key, rect) in Self::layout() {
+            let rect = rect.translate(origin);
+            match key {
+                "pay" => ui.allocate_ui_at_rect(rect, |ui| self.pay.render(ui)),
+                "total" => ui.allocate_ui_at_rect(rect, |ui| self.total.render(ui)),
+                _ => continue,
+            };
+        }
+        // <codegen:guard:form_render:start>
+        // Form render logic
+        // <codegen:guard:form_render:end>
+        self.dispatch_events();
+    }
+
+    fn get_property(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    fn set_property(&mut self, _name: &str, _value: &str) -> bool {
+        false
+    }
+
+    fn get_property_names(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,325 @@
+//! Tests for structured AI edits: parsing, validation, review decisions, undo and error feedback
+use ide_rs::ai_agent::AiAgent;
+use ide_rs::ai_provider::OllamaProvider;
+use ide_rs::editor::ai_edits::*;
+use ide_rs::editor::build_system::{CompilerMessage, MessageLevel};
+use ide_rs::editor::code_editor::CodeEditor;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tempfile::TempDir;
+
+const LIB: &str = "fn add(a: i32, b: i32) -> i32 {\n    a - b\n}\n\nfn main() {\n    println!(\"{}\", add(1, 2));\n}\n";
+
+fn edit(file: &str, start_line: usize, end_line: usize, original: Option<&str>, replacement: &str) -> ProposedEdit {
+    ProposedEdit {
+        file: file.to_string(),
+        start_line,
+        end_line,
+        original: original.map(str::to_string),
+        replacement: replacement.to_string(),
+    }
+}
+
+fn response(edits: Vec<ProposedEdit>) -> EditResponse {
+    EditResponse { explanation: "Fix add".to_string(), edits }
+}
+
+fn workspace() -> (TempDir, EditWorkspace) {
+    let dir = TempDir::new().unwrap();
+    let mut workspace = EditWorkspace::new(dir.path());
+    workspace.sync(&dir.path().join("src/lib.rs"), LIB);
+    (dir, workspace)
+}
+
+fn text(workspace: &EditWorkspace, path: &Path) -> String {
+    workspace.buffer(path).unwrap().to_string()
+}
+
+fn error(file: &str, message: &str) -> CompilerMessage {
+    CompilerMessage {
+        level: MessageLevel::Error,
+        message: message.to_string(),
+        file: Some(file.to_string()),
+        line: Some(2),
+        column: Some(5),
+        code: Some("E0308".to_string()),
+        spans: vec![],
+        children: vec![],
+        rendered: None,
+    }
+}
+
+#[test]
+fn test_parse_edits_from_model_responses() {
+    let fenced = "Here you go:\n```json\n{\"explanation\": \"x\", \"edits\": [{\"file\": \"a.rs\", \"start_line\": 2, \"end_line\": 2, \"replacement\": \"b\"}]}\n```\nDone.";
+    let parsed = parse_edits(fenced).unwrap();
+    assert_eq!(parsed.explanation, "x");
+    assert_eq!(parsed.edits, vec![edit("a.rs", 2, 2, None, "b")]);
+
+    let bare = "Sure. {\"edits\": [{\"file\": \"a.rs\", \"start_line\": 1, \"end_line\": 0, \"original\": \"\", \"replacement\": \"use std::fmt;\"}]} Hope this helps";
+    assert_eq!(parse_edits(bare).unwrap().edits, vec![edit("a.rs", 1, 0, Some(""), "use std::fmt;")]);
+
+    assert!(matches!(parse_edits("I cannot help with that"), Err(AiEditError::Malformed(_))));
+    assert!(matches!(parse_edits("{\"edits\": [{\"file\": 3}]}"), Err(AiEditError::Malformed(_))));
+}
+
+#[test]
+fn test_validate_checks_edits_against_buffers() {
+    let (dir, mut workspace) = workspace();
+    let lib = dir.path().join("src/lib.rs");
+    let proposal = workspace.validate(response(vec![
+        edit("src/lib.rs", 2, 2, Some("    a - b"), "    a + b"),
+        // Off by one, but the original text occurs exactly once
+        edit("./src/lib.rs", 4, 4, Some("fn main() {"), "pub fn main() {"),
+        edit("src/lib.rs", 6, 7, Some("something else\n}"), "}"),
+        edit("src/lib.rs", 7, 9, None, ""),
+        edit("../secrets.txt", 1, 1, None, ""),
+        edit("src/lib.rs", 2, 3, Some("    a - b\n}"), "    a * b\n}"),
+    ]));
+
+    assert_eq!(proposal.explanation, "Fix add");
+    assert_eq!(proposal.hunks.len(), 2);
+    let hunk = &proposal.hunks[0];
+    assert_eq!((hunk.path.clone(), hunk.start_line, hunk.original.as_str()), (lib.clone(), 2, "    a - b\n"));
+    assert_eq!(hunk.replacement, "    a + b\n", "whole-line replacements keep their newline");
+    assert_eq!((hunk.range.start.line, hunk.range.end.line), (1, 2));
+    assert_eq!(hunk.decision, HunkDecision::Pending);
+    assert_eq!(proposal.hunks[1].start_line, 5);
+
+    let errors: Vec<&AiEditError> = proposal.invalid.iter().map(|(_, error)| error).collect();
+    assert_eq!(errors.len(), 4);
+    assert!(matches!(errors[0], AiEditError::OriginalMismatch { start_line: 6, end_line: 7, .. }));
+    assert!(matches!(errors[1], AiEditError::LinesOutOfRange { line_count: 7, .. }));
+    assert_eq!(errors[2], &AiEditError::OutsideWorkspace("../secrets.txt".to_string()));
+    assert!(matches!(errors[3], AiEditError::Overlap { line: 2, .. }));
+}
+
+#[test]
+fn test_insertions_and_new_files() {
+    let (dir, mut workspace) = workspace();
+    std::fs::write(dir.path().join("notes.txt"), "last line without newline").unwrap();
+    let mut proposal = workspace.validate(response(vec![
+        edit("src/lib.rs", 1, 0, Some(""), "use std::fmt;"),
+        edit("src/lib.rs", 8, 7, None, "// end"),
+        edit("notes.txt", 2, 1, None, "appended"),
+        edit("tests/add.rs", 1, 0, Some(""), "#[test]\nfn adds() {}"),
+        edit("tests/other.rs", 2, 2, None, "x"),
+    ]));
+    assert_eq!(proposal.hunks.len(), 4, "{:?}", proposal.invalid);
+    assert!(matches!(proposal.invalid[0].1, AiEditError::LinesOutOfRange { line_count: 0, .. }));
+
+    proposal.decide_all(HunkDecision::Accepted);
+    let mut changed = workspace.apply(&proposal).unwrap();
+    changed.sort();
+    assert_eq!(changed.len(), 3);
+    let lib = text(&workspace, &dir.path().join("src/lib.rs"));
+    assert!(lib.starts_with("use std::fmt;\nfn add"));
+    assert!(lib.ends_with("}\n// end\n"));
+    assert_eq!(text(&workspace, &dir.path().join("notes.txt")), "last line without newline\nappended");
+    assert_eq!(text(&workspace, &dir.path().join("tests/add.rs")), "#[test]\nfn adds() {}\n");
+}
+
+#[test]
+fn test_apply_accepted_hunks_as_one_undo_step() {
+    let (dir, mut workspace) = workspace();
+    let lib = dir.path().join("src/lib.rs");
+    workspace.sync(&dir.path().join("src/util.rs"), "pub const ONE: i32 = 1;\r\n");
+    let mut proposal = workspace.validate(response(vec![
+        edit("src/lib.rs", 2, 2, Some("    a - b"), "    a + b"),
+        edit("src/lib.rs", 6, 6, None, "    println!(\"{}\", add(2, 2));"),
+        edit("src/lib.rs", 5, 5, Some("fn main() {"), "pub fn main() {"),
+        edit("src/util.rs", 1, 1, Some("pub const ONE: i32 = 1;"), "pub const ONE: i32 = 1;\npub const TWO: i32 = 2;"),
+    ]));
+    assert_eq!(proposal.hunks.len(), 4);
+    assert!(!workspace.can_undo());
+
+    // Nothing accepted yet
+    assert_eq!(workspace.apply(&proposal).unwrap(), Vec::<std::path::PathBuf>::new());
+
+    proposal.hunks[0].decision = HunkDecision::Accepted;
+    proposal.hunks[1].decision = HunkDecision::Rejected;
+    proposal.hunks[2].decision = HunkDecision::Accepted;
+    proposal.hunks[3].decision = HunkDecision::Accepted;
+    let changed = workspace.apply(&proposal).unwrap();
+    assert_eq!(changed.len(), 2);
+    assert_eq!(text(&workspace, &lib), LIB.replace("a - b", "a + b").replace("println!(\"{}\", add(1, 2))", "println!(\"{}\", add(2, 2))"));
+    assert_eq!(
+        text(&workspace, &dir.path().join("src/util.rs")),
+        "pub const ONE: i32 = 1;\r\npub const TWO: i32 = 2;\r\n",
+        "replacements follow the file's line endings"
+    );
+
+    // Each file took one transaction, and the workspace undoes them together
+    assert!(workspace.can_undo());
+    let mut undone = workspace.undo().unwrap();
+    undone.sort();
+    assert_eq!(undone.len(), 2);
+    assert_eq!(text(&workspace, &lib), LIB);
+    assert_eq!(text(&workspace, &dir.path().join("src/util.rs")), "pub const ONE: i32 = 1;\r\n");
+    assert_eq!(workspace.undo(), Err(AiEditError::NothingToUndo));
+}
+
+#[test]
+fn test_stale_hunks_and_user_edits() {
+    let (dir, mut workspace) = workspace();
+    let lib = dir.path().join("src/lib.rs");
+    let mut proposal = workspace.validate(response(vec![
+        edit("src/lib.rs", 1, 1, Some("fn add(a: i32, b: i32) -> i32 {"), "pub fn add(a: i32, b: i32) -> i32 {"),
+        edit("src/lib.rs", 2, 2, Some("    a - b"), "    a + b"),
+    ]));
+    proposal.decide_all(HunkDecision::Accepted);
+
+    // The user changed the second line in the editor meanwhile
+    workspace.sync(&lib, &LIB.replace("a - b", "b - a"));
+    assert_eq!(workspace.apply(&proposal), Err(AiEditError::Stale("src/lib.rs".to_string())));
+    assert_eq!(text(&workspace, &lib), LIB.replace("a - b", "b - a"), "no hunk is applied");
+
+    workspace.sync(&lib, LIB);
+    workspace.apply(&proposal).unwrap();
+    assert!(workspace.can_undo());
+    // Typing on top of the applied edits makes them impossible to undo
+    let typed = format!("{}// more\n", text(&workspace, &lib));
+    workspace.sync(&lib, &typed);
+    assert!(!workspace.can_undo());
+}
+
+#[test]
+fn test_hunk_diff_shows_context_and_changes() {
+    let (_dir, mut workspace) = workspace();
+    let proposal = workspace.validate(response(vec![edit(
+        "src/lib.rs",
+        1,
+        3,
+        Some("fn add(a: i32, b: i32) -> i32 {\n    a - b\n}"),
+        "fn add(a: i32, b: i32) -> i32 {\n    // Sum\n    a + b\n}",
+    )]));
+    assert_eq!(
+        proposal.hunks[0].diff(),
+        vec![
+            DiffLine::Context("fn add(a: i32, b: i32) -> i32 {".to_string()),
+            DiffLine::Removed("    a - b".to_string()),
+            DiffLine::Added("    // Sum".to_string()),
+            DiffLine::Added("    a + b".to_string()),
+            DiffLine::Context("}".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_editor_applies_hunks_as_one_undo_step() {
+    let (dir, mut workspace) = workspace();
+    let mut editor = CodeEditor::with_content("rust", LIB.to_string());
+    // Typed after the file was opened, undone separately from the AI edits
+    editor.code = LIB.replace("fn main() {", "fn main() { // café");
+    workspace.sync(&dir.path().join("src/lib.rs"), &editor.code);
+
+    let mut proposal = workspace.validate(response(vec![
+        edit("src/lib.rs", 2, 2, Some("    a - b"), "    a + b"),
+        edit("src/lib.rs", 6, 6, None, "    println!(\"{}\", add(2, 2));"),
+    ]));
+    proposal.decide_all(HunkDecision::Accepted);
+    workspace.apply(&proposal).unwrap();
+    let edits: Vec<_> = proposal.accepted().map(|hunk| (hunk.range.clone(), hunk.replacement.clone())).collect();
+    editor.apply_edits("AI edit", &edits).unwrap();
+    assert_eq!(editor.code, text(&workspace, &dir.path().join("src/lib.rs")));
+    assert!(editor.is_dirty);
+
+    editor.undo();
+    assert_eq!(editor.code, LIB.replace("fn main() {", "fn main() { // café"));
+    editor.undo();
+    assert_eq!(editor.code, LIB);
+    editor.redo();
+    editor.redo();
+    assert!(editor.code.contains("a + b") && editor.code.contains("add(2, 2)") && editor.code.contains("café"));
+
+    // Replacing the whole text only records the part that changed
+    let renamed = editor.code.replace("café", "cafe");
+    editor.replace_text("Undo AI edit", &renamed).unwrap();
+    assert_eq!(editor.code, renamed);
+    editor.undo();
+    assert!(editor.code.contains("café"));
+    let undone = editor.buffer.undo_stack.redo_stack.back().unwrap();
+    assert_eq!(undone.description, "Undo AI edit");
+    assert_eq!((undone.operations.len(), undone.operations[0].text.as_str(), undone.operations[0].new_text.as_str()), (1, "é", "e"));
+}
+
+#[test]
+fn test_new_errors_are_fed_back() {
+    let before = vec![error("src/lib.rs", "mismatched types"), error("src/main.rs", "unused variable")];
+    let mut warning = error("src/lib.rs", "unused import");
+    warning.level = MessageLevel::Warning;
+    let after = vec![
+        error("src/lib.rs", "mismatched types"),
+        error("src/lib.rs", "mismatched types"),
+        error("src/lib.rs", "cannot find value `c` in this scope"),
+        warning,
+    ];
+
+    let new = new_errors(&before, &after);
+    assert_eq!(new.iter().map(|error| error.message.as_str()).collect::<Vec<_>>(), vec!["mismatched types", "cannot find value `c` in this scope"]);
+
+    let mut rendered = error("src/lib.rs", "cannot find value `c` in this scope");
+    rendered.rendered = Some("error[E0425]: cannot find value `c` in this scope\n --> src/lib.rs:2:5\n".to_string());
+    let feedback = error_feedback(&[new[0].clone(), rendered]);
+    assert!(feedback.starts_with("Applying your edits introduced these compiler errors."));
+    assert!(feedback.contains("\nsrc/lib.rs:2: mismatched types\n"));
+    assert!(feedback.contains("\nerror[E0425]: cannot find value `c` in this scope\n --> src/lib.rs:2:5\n"));
+}
+
+#[test]
+fn test_agent_requests_structured_edits() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let requests: Arc<Mutex<Vec<Value>>> = Arc::default();
+    let recorded = requests.clone();
+    thread::spawn(move || {
+        for socket in listener.incoming().flatten() {
+            let mut reader = BufReader::new(socket.try_clone().unwrap());
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            recorded.lock().unwrap().push(serde_json::from_slice(&body).unwrap());
+
+            let edits = json!({"explanation": "Use addition", "edits": [{"file": "src/lib.rs", "start_line": 2, "end_line": 2, "original": "    a - b", "replacement": "    a + b"}]});
+            let answer = format!("```json\n{}\n```", edits);
+            let body = format!("{}\n{}\n", json!({"response": answer, "done": false}), json!({"response": "", "done": true}));
+            let mut socket = socket;
+            write!(socket, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body).unwrap();
+        }
+    });
+
+    let mut agent = AiAgent::with_provider(Arc::new(OllamaProvider::new(&base, "llama2")));
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    let edits = runtime.block_on(agent.propose_bug_fix("src/lib.rs", LIB, "test add ... FAILED: left: -1, right: 3")).unwrap();
+    assert_eq!(edits.explanation, "Use addition");
+    assert_eq!(edits.edits, vec![edit("src/lib.rs", 2, 2, Some("    a - b"), "    a + b")]);
+
+    let request = requests.lock().unwrap()[0].clone();
+    assert!(request["system"].as_str().unwrap().ends_with(EDIT_FORMAT));
+    let prompt = request["prompt"].as_str().unwrap();
+    assert!(prompt.starts_with("Fix this Rust error with minimal edits:\ntest add ... FAILED"));
+    assert!(prompt.contains("\nFile src/lib.rs:\n   1| fn add(a: i32, b: i32) -> i32 {\n   2|     a - b\n"));
+    assert_eq!(agent.get_conversation_history().len(), 1);
+
+    // The proposal validates against the same text
+    let (_dir, mut workspace) = workspace();
+    let proposal = workspace.validate(edits);
+    assert_eq!(proposal.hunks.len(), 1);
+    assert!(proposal.invalid.is_empty());
+}
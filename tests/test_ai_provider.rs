@@ -0,0 +1,293 @@
+//! Tests for the AI providers and streamed agent requests against a local stub server
+use ide_rs::ai_agent::{AiAgent, AiTaskType};
+use ide_rs::ai_provider::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// A request as seen by the stub server
+#[derive(Debug, Clone)]
+struct StubRequest {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    body: Value,
+}
+
+/// Serve `handler` on a localhost thread; it answers each request by writing to the socket
+fn stub_server(handler: impl Fn(&StubRequest, &mut TcpStream) + Send + Sync + 'static) -> (String, Arc<Mutex<Vec<StubRequest>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    let handler = Arc::new(handler);
+    thread::spawn(move || {
+        for mut socket in listener.incoming().flatten() {
+            let handler = handler.clone();
+            let recorded = recorded.clone();
+            thread::spawn(move || {
+                let Some(request) = read_request(&mut socket) else { return };
+                recorded.lock().unwrap().push(request.clone());
+                handler(&request, &mut socket);
+            });
+        }
+    });
+    (base, requests)
+}
+
+fn read_request(socket: &mut TcpStream) -> Option<StubRequest> {
+    let mut reader = BufReader::new(socket.try_clone().ok()?);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut request_line = line.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let mut headers = HashMap::new();
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).ok()?;
+        let Some((name, value)) = header.trim_end().split_once(':') else { break };
+        headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+    }
+    let length: usize = headers.get("content-length").and_then(|value| value.parse().ok()).unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+    let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    Some(StubRequest { method, path, headers, body })
+}
+
+/// Writes response headers for a body that ends when the connection closes
+fn start_response(socket: &mut TcpStream, status: &str, content_type: &str) {
+    let head = format!("HTTP/1.1 {}\r\nContent-Type: {}\r\nConnection: close\r\n\r\n", status, content_type);
+    socket.write_all(head.as_bytes()).unwrap();
+}
+
+fn respond(socket: &mut TcpStream, status: &str, body: &str) {
+    start_response(socket, status, "application/json");
+    socket.write_all(body.as_bytes()).unwrap();
+}
+
+fn ollama_lines(tokens: &[&str]) -> String {
+    let mut lines: String = tokens.iter().map(|token| format!("{}\n", json!({"response": token, "done": false}))).collect();
+    lines.push_str(&format!("{}\n", json!({"response": "", "done": true})));
+    lines
+}
+
+fn sse_event(token: &str) -> String {
+    format!("data: {}\n\n", json!({"choices": [{"delta": {"content": token}}]}))
+}
+
+fn block_on<T>(future: impl std::future::Future<Output = T>) -> T {
+    tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(future)
+}
+
+/// Runs `request` collecting the tokens as they are reported
+fn stream_tokens(provider: &dyn AiProvider, request: &CompletionRequest) -> (Vec<String>, Result<String, AiError>) {
+    let mut tokens = Vec::new();
+    let result = block_on(provider.stream(request, &mut |token: &str| tokens.push(token.to_string()), &CancellationToken::new()));
+    (tokens, result)
+}
+
+/// Polls `stream` until the next event arrives, giving up after five seconds
+fn next_event(stream: &mut AiStream) -> Option<AiStreamEvent> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if let Some(event) = stream.poll_events().into_iter().next() {
+            return Some(event);
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    None
+}
+
+#[test]
+fn test_ollama_streams_generate_responses() {
+    let (base, requests) = stub_server(|_, socket| {
+        start_response(socket, "200 OK", "application/x-ndjson");
+        // Split a line and a multi-byte character across writes
+        let lines = ollama_lines(&["Hel", "lo ", "wörld"]);
+        let split = lines.find('ö').unwrap() + 1;
+        socket.write_all(&lines.as_bytes()[..split]).unwrap();
+        socket.flush().unwrap();
+        thread::sleep(Duration::from_millis(20));
+        socket.write_all(&lines.as_bytes()[split..]).unwrap();
+    });
+
+    let provider = OllamaProvider::new(&format!("{}/", base), "llama2");
+    let mut request = CompletionRequest::new("", "Say hello").with_system("Be brief");
+    request.temperature = Some(0.5);
+    let (tokens, result) = stream_tokens(&provider, &request);
+    assert_eq!(tokens, vec!["Hel", "lo ", "wörld"]);
+    assert_eq!(result.unwrap(), "Hello wörld");
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/api/generate");
+    assert_eq!(
+        requests[0].body,
+        json!({"model": "llama2", "prompt": "Say hello", "system": "Be brief", "stream": true, "options": {"temperature": 0.5}})
+    );
+}
+
+#[test]
+fn test_openai_compatible_streams_chat_completions() {
+    let (base, requests) = stub_server(|_, socket| {
+        start_response(socket, "200 OK", "text/event-stream");
+        let events = format!(": keep-alive\n\n{}{}data: [DONE]\n\n{}", sse_event("fn "), sse_event("main()"), sse_event("ignored"));
+        socket.write_all(events.as_bytes()).unwrap();
+    });
+
+    let provider = OpenAiCompatibleProvider::new(&base, "qwen2.5-coder").with_api_key("secret");
+    assert_eq!(provider.base_url(), format!("{}/v1", base));
+    let mut request = CompletionRequest::new("codellama", "Write main").with_system("Rust only");
+    request.max_tokens = Some(64);
+    let (tokens, result) = stream_tokens(&provider, &request);
+    assert_eq!(tokens, vec!["fn ", "main()"]);
+    assert_eq!(result.unwrap(), "fn main()");
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests[0].path, "/v1/chat/completions");
+    assert_eq!(requests[0].headers["authorization"], "Bearer secret");
+    assert_eq!(
+        requests[0].body,
+        json!({
+            "model": "codellama",
+            "messages": [{"role": "system", "content": "Rust only"}, {"role": "user", "content": "Write main"}],
+            "stream": true,
+            "max_tokens": 64,
+        })
+    );
+}
+
+#[test]
+fn test_list_models() {
+    let (base, _) = stub_server(|request, socket| match request.path.as_str() {
+        "/api/tags" => respond(socket, "200 OK", r#"{"models": [{"name": "llama2:latest"}, {"name": "codellama:7b"}]}"#),
+        "/v1/models" => respond(socket, "200 OK", r#"{"object": "list", "data": [{"id": "qwen2.5-coder"}]}"#),
+        _ => respond(socket, "404 Not Found", "{}"),
+    });
+
+    let models = block_on(OllamaProvider::new(&base, "llama2").list_models()).unwrap();
+    assert_eq!(models, vec!["llama2:latest", "codellama:7b"]);
+    let models = block_on(OpenAiCompatibleProvider::new(&format!("{}/v1/", base), "").list_models()).unwrap();
+    assert_eq!(models, vec!["qwen2.5-coder"]);
+
+    // The settings panel lists models in the background
+    let settings = AiProviderSettings { kind: ProviderKind::OpenAiCompatible, base_url: base.clone(), ..Default::default() };
+    let provider = settings.build();
+    assert_eq!(provider.base_url(), format!("{}/v1", base));
+    let listing = ModelListing::start(provider);
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut result = None;
+    while result.is_none() && Instant::now() < deadline {
+        result = listing.poll();
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(result, Some(Ok(vec!["qwen2.5-coder".to_string()])));
+}
+
+#[test]
+fn test_errors_are_reported() {
+    let (base, _) = stub_server(|request, socket| match request.body["model"].as_str() {
+        Some("missing") => respond(socket, "404 Not Found", r#"{"error": "model 'missing' not found"}"#),
+        Some("overloaded") => respond(socket, "503 Service Unavailable", r#"{"error": {"message": "server busy"}}"#),
+        _ => {
+            start_response(socket, "200 OK", "application/x-ndjson");
+            socket.write_all(b"{\"response\": \"partial\", \"done\": false}\n{\"error\": \"out of memory\"}\n").unwrap();
+        }
+    });
+
+    let ollama = OllamaProvider::new(&base, "llama2");
+    let (_, result) = stream_tokens(&ollama, &CompletionRequest::new("missing", "hi"));
+    assert_eq!(result, Err(AiError::Status { status: 404, message: "model 'missing' not found".to_string() }));
+    let (tokens, result) = stream_tokens(&ollama, &CompletionRequest::new("", "hi"));
+    assert_eq!(tokens, vec!["partial"]);
+    assert_eq!(result, Err(AiError::Server("out of memory".to_string())));
+
+    let openai = OpenAiCompatibleProvider::new(&base, "overloaded");
+    let (_, result) = stream_tokens(&openai, &CompletionRequest::new("", "hi"));
+    assert_eq!(result, Err(AiError::Status { status: 503, message: "server busy".to_string() }));
+
+    // Nothing listens on a port that was just released
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let unreachable = OllamaProvider::new(&format!("http://127.0.0.1:{}", port), "llama2");
+    assert!(matches!(block_on(unreachable.complete(&CompletionRequest::new("", "hi"))), Err(AiError::Connection { .. })));
+}
+
+#[test]
+fn test_stream_delivers_tokens_before_completion_and_cancels() {
+    let (release, released): (Sender<()>, Receiver<()>) = mpsc::channel();
+    let released = Mutex::new(released);
+    let (base, _) = stub_server(move |_, socket| {
+        start_response(socket, "200 OK", "text/event-stream");
+        socket.write_all(sse_event("first").as_bytes()).unwrap();
+        socket.flush().unwrap();
+        // Hold the response open until the test is done with it
+        let _ = released.lock().unwrap().recv_timeout(Duration::from_secs(10));
+    });
+
+    let provider: Arc<dyn AiProvider> = Arc::new(OpenAiCompatibleProvider::new(&base, "model"));
+    let mut stream = AiStream::start(provider, CompletionRequest::new("", "Tell me a long story"));
+    assert_eq!(next_event(&mut stream), Some(AiStreamEvent::Token("first".to_string())));
+    assert!(!stream.is_finished());
+    assert!(stream.poll_events().is_empty());
+
+    let cancelled_at = Instant::now();
+    stream.cancel();
+    assert_eq!(next_event(&mut stream), Some(AiStreamEvent::Failed(AiError::Cancelled)));
+    assert!(cancelled_at.elapsed() < Duration::from_secs(2));
+    assert!(stream.is_finished());
+    release.send(()).unwrap();
+}
+
+#[test]
+fn test_agent_picks_model_per_task_and_records_streams() {
+    // Echo the model and system prompt back so the test can see what was sent
+    let (base, requests) = stub_server(|request, socket| {
+        start_response(socket, "200 OK", "application/x-ndjson");
+        let model = request.body["model"].as_str().unwrap_or_default();
+        socket.write_all(ollama_lines(&["model=", model]).as_bytes()).unwrap();
+    });
+
+    let mut agent = AiAgent::with_provider(Arc::new(OllamaProvider::new(&base, "llama2")));
+    agent.set_task_model(AiTaskType::Testing, "codellama");
+    assert_eq!(agent.model_for(&AiTaskType::Testing), "codellama");
+    assert_eq!(agent.model_for(&AiTaskType::CodeReview), "llama2");
+
+    assert_eq!(block_on(agent.generate_tests("fn add() {}")).unwrap(), "model=codellama");
+    assert_eq!(block_on(agent.review_code("fn add() {}")).unwrap(), "model=llama2");
+    assert_eq!(block_on(agent.ask("plain")).unwrap(), "model=llama2");
+    {
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].body["system"].as_str().unwrap().contains("Rust testing expert"));
+        assert!(requests[0].body["prompt"].as_str().unwrap().contains("fn add() {}"));
+        assert!(requests[2].body.get("system").is_none());
+    }
+
+    agent.set_task_model(AiTaskType::Testing, "");
+    assert_eq!(agent.task_model(&AiTaskType::Testing), None);
+    agent.set_task_model(AiTaskType::BugFixing, "deepseek-coder");
+    let mut exchange = agent.stream_with_context("Why does this panic?", AiTaskType::BugFixing);
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut tokens = String::new();
+    while !exchange.stream.is_finished() && Instant::now() < deadline {
+        for event in agent.poll_exchange(&mut exchange) {
+            if let AiStreamEvent::Token(token) = event {
+                tokens.push_str(&token);
+            }
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(tokens, "model=deepseek-coder");
+    let history = agent.get_conversation_history();
+    assert_eq!(history.len(), 3);
+    assert_eq!(history[2].user_input, "Why does this panic?");
+    assert_eq!(history[2].ai_response, "model=deepseek-coder");
+    assert_eq!(history[2].task_type, AiTaskType::BugFixing);
+}
@@ -0,0 +1,164 @@
+//! Integration tests for structured cargo diagnostics
+//!
+//! Fixtures are records captured from `cargo check --message-format=json`.
+
+use ide_rs::editor::build_system::*;
+use ide_rs::editor::text_buffer::TextBuffer;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const WARNING_RECORD: &str = r#"{"reason":"compiler-message","package_id":"path+file:///tmp/diagdemo#0.1.0","manifest_path":"/tmp/diagdemo/Cargo.toml","target":{"kind":["bin"],"crate_types":["bin"],"name":"diagdemo","src_path":"/tmp/diagdemo/src/main.rs","edition":"2021","doc":true,"doctest":false,"test":true},"message":{"rendered":"warning: unused variable: `x`\n --> src/main.rs:2:9\n  |\n2 |     let x = 5;\n  |         ^ help: if this is intentional, prefix it with an underscore: `_x`\n  |\n  = note: `#[warn(unused_variables)]` on by default\n\n","$message_type":"diagnostic","children":[{"children":[],"code":null,"level":"note","message":"`#[warn(unused_variables)]` on by default","rendered":null,"spans":[]},{"children":[],"code":null,"level":"help","message":"if this is intentional, prefix it with an underscore","rendered":null,"spans":[{"byte_end":21,"byte_start":20,"column_end":10,"column_start":9,"expansion":null,"file_name":"src/main.rs","is_primary":true,"label":null,"line_end":2,"line_start":2,"suggested_replacement":"_x","suggestion_applicability":"MachineApplicable","text":[{"highlight_end":10,"highlight_start":9,"text":"    let x = 5;"}]}]}],"level":"warning","message":"unused variable: `x`","spans":[{"byte_end":21,"byte_start":20,"column_end":10,"column_start":9,"expansion":null,"file_name":"src/main.rs","is_primary":true,"label":null,"line_end":2,"line_start":2,"suggested_replacement":null,"suggestion_applicability":null,"text":[{"highlight_end":10,"highlight_start":9,"text":"    let x = 5;"}]}],"code":{"code":"unused_variables","explanation":null}}}"#;
+
+const ERROR_RECORD: &str = r#"{"reason":"compiler-message","package_id":"path+file:///tmp/diagdemo#0.1.0","manifest_path":"/tmp/diagdemo/Cargo.toml","target":{"kind":["bin"],"crate_types":["bin"],"name":"diagdemo","src_path":"/tmp/diagdemo/src/main.rs","edition":"2021","doc":true,"doctest":false,"test":true},"message":{"rendered":"error[E0308]: mismatched types\n --> crates/core/src/types.rs:3:18\n","$message_type":"diagnostic","children":[],"level":"error","message":"mismatched types","spans":[{"byte_end":41,"byte_start":38,"column_end":15,"column_start":12,"expansion":null,"file_name":"crates/core/src/types.rs","is_primary":false,"label":"expected due to this","line_end":3,"line_start":3,"suggested_replacement":null,"suggestion_applicability":null,"text":[]},{"byte_end":47,"byte_start":44,"column_end":21,"column_start":18,"expansion":null,"file_name":"crates/core/src/types.rs","is_primary":true,"label":"expected `i32`, found `&str`","line_end":3,"line_start":3,"suggested_replacement":null,"suggestion_applicability":null,"text":[]}],"code":{"code":"E0308","explanation":"Expected type did not match the received type."}}}"#;
+
+const ARTIFACT_RECORD: &str = r#"{"reason":"compiler-artifact","package_id":"path+file:///tmp/diagdemo#0.1.0","manifest_path":"/tmp/diagdemo/Cargo.toml","target":{"kind":["bin"],"crate_types":["bin"],"name":"diagdemo","src_path":"/tmp/diagdemo/src/main.rs","edition":"2021","doc":true,"doctest":false,"test":true},"profile":{"opt_level":"0","debuginfo":2,"debug_assertions":true,"overflow_checks":true,"test":false},"features":[],"filenames":["/tmp/diagdemo/target/debug/diagdemo"],"executable":"/tmp/diagdemo/target/debug/diagdemo","fresh":true}"#;
+
+fn compiler_message(record: &str) -> CompilerMessage {
+    match parse_cargo_message(record) {
+        Some(CargoMessage::CompilerMessage(message)) => message,
+        other => panic!("expected a compiler message, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_warning_with_suggestion() {
+    let message = compiler_message(WARNING_RECORD);
+
+    assert_eq!(message.level, MessageLevel::Warning);
+    assert_eq!(message.message, "unused variable: `x`");
+    assert_eq!(message.code.as_deref(), Some("unused_variables"));
+    assert_eq!(message.file.as_deref(), Some("src/main.rs"));
+    assert_eq!((message.line, message.column), (Some(2), Some(9)));
+    assert!(message.rendered.as_deref().unwrap().starts_with("warning: unused variable"));
+
+    assert_eq!(message.children.len(), 2);
+    assert_eq!(message.children[0].level, MessageLevel::Note);
+    assert_eq!(message.children[1].level, MessageLevel::Help);
+    let help_span = &message.children[1].spans[0];
+    assert_eq!(help_span.suggested_replacement.as_deref(), Some("_x"));
+    assert_eq!(help_span.suggestion_applicability, Some(Applicability::MachineApplicable));
+
+    let fixes = message.suggestions();
+    assert_eq!(fixes.len(), 1);
+    assert_eq!(fixes[0].message, "if this is intentional, prefix it with an underscore");
+    assert_eq!(fixes[0].files(), vec!["src/main.rs"]);
+
+    let source = "fn main() {\n    let x = 5;\n}\n";
+    assert_eq!(
+        fixes[0].apply_to_source("src/main.rs", source).unwrap(),
+        "fn main() {\n    let _x = 5;\n}\n"
+    );
+}
+
+#[test]
+fn test_parse_error_keeps_all_spans() {
+    let message = compiler_message(ERROR_RECORD);
+
+    assert_eq!(message.level, MessageLevel::Error);
+    assert_eq!(message.code.as_deref(), Some("E0308"));
+    assert_eq!(message.spans.len(), 2);
+
+    // Location comes from the primary span even when it is not listed first
+    let primary = message.primary_span().unwrap();
+    assert_eq!(primary.label.as_deref(), Some("expected `i32`, found `&str`"));
+    assert_eq!(message.file.as_deref(), Some("crates/core/src/types.rs"));
+    assert_eq!((message.line, message.column), (Some(3), Some(18)));
+    assert_eq!((primary.byte_start, primary.byte_end), (44, 47));
+    assert_eq!(message.spans[0].label.as_deref(), Some("expected due to this"));
+    assert!(message.suggestions().is_empty());
+}
+
+#[test]
+fn test_parse_artifact_and_build_finished() {
+    match parse_cargo_message(ARTIFACT_RECORD) {
+        Some(CargoMessage::CompilerArtifact(artifact)) => {
+            assert_eq!(artifact.target_name, "diagdemo");
+            assert_eq!(artifact.kind, vec!["bin".to_string()]);
+            assert_eq!(artifact.executable, Some(PathBuf::from("/tmp/diagdemo/target/debug/diagdemo")));
+            assert!(artifact.fresh);
+        }
+        other => panic!("expected an artifact, got {:?}", other),
+    }
+
+    assert_eq!(
+        parse_cargo_message(r#"{"reason":"build-finished","success":false}"#),
+        Some(CargoMessage::BuildFinished { success: false })
+    );
+    assert_eq!(parse_cargo_message(r#"{"reason":"build-script-executed","package_id":"x"}"#), None);
+    assert_eq!(parse_cargo_message("Hello from the program"), None);
+    assert_eq!(parse_cargo_message("{ not json"), None);
+}
+
+#[test]
+fn test_fix_rejects_stale_offsets() {
+    let fix = SuggestedFix {
+        message: "rename".to_string(),
+        applicability: Applicability::MaybeIncorrect,
+        edits: vec![
+            FixEdit { file_name: "a.rs".to_string(), byte_start: 0, byte_end: 3, replacement: "let".to_string() },
+            FixEdit { file_name: "a.rs".to_string(), byte_start: 8, byte_end: 9, replacement: "y".to_string() },
+        ],
+    };
+    assert_eq!(fix.apply_to_source("a.rs", "var foo x").unwrap(), "let foo y");
+    assert!(fix.apply_to_source("a.rs", "var").is_err());
+    // Edits for other files leave the source untouched
+    assert_eq!(fix.apply_to_source("b.rs", "var").unwrap(), "var");
+
+    let edits = fix.text_edits("a.rs", &TextBuffer::from_string("var foo x".to_string())).unwrap();
+    assert_eq!(edits.len(), 2);
+    assert_eq!((edits[1].0.start.column, edits[1].0.end.column), (8, 9));
+    assert!(fix.text_edits("a.rs", &TextBuffer::from_string("var".to_string())).is_err());
+    assert!(fix.text_edits("b.rs", &TextBuffer::from_string("var".to_string())).unwrap().is_empty());
+}
+
+#[test]
+fn test_cargo_check_reports_structured_diagnostics() {
+    let root = std::env::temp_dir().join(format!("ide-rs-build-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"diagdemo\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n",
+    ).unwrap();
+    std::fs::write(root.join("src/main.rs"), "fn main() {\n    let x = 5;\n}\n").unwrap();
+
+    let mut build_system = BuildSystem::new();
+    build_system.set_project_path(root.clone());
+    build_system.initialize().unwrap();
+    build_system.check().unwrap();
+
+    let mut diagnostics = Vec::new();
+    let mut result = None;
+    let deadline = Instant::now() + Duration::from_secs(120);
+    while result.is_none() && Instant::now() < deadline {
+        for output in build_system.poll_output() {
+            match output {
+                BuildOutput::Diagnostic(message) => diagnostics.push(message),
+                BuildOutput::Finished(finished) => result = Some(finished),
+                BuildOutput::Error(error) => panic!("build error: {}", error),
+                _ => {}
+            }
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    let result = result.expect("cargo check did not finish");
+    assert!(result.success);
+    assert!(result.errors.is_empty());
+    assert_eq!(result.warnings.len(), 1);
+    assert!(result.artifacts.iter().any(|artifact| artifact.target_name == "diagdemo"));
+    // JSON records are not echoed as program output
+    assert!(!result.stdout.contains("compiler-message"));
+
+    let warning = &result.warnings[0];
+    assert_eq!(diagnostics, vec![warning.clone()]);
+    assert_eq!(warning.file.as_deref(), Some("src/main.rs"));
+    assert_eq!((warning.line, warning.column), (Some(2), Some(9)));
+    assert_eq!(resolve_source_path(&root, "src/main.rs"), root.join("src/main.rs"));
+
+    let fix = &warning.suggestions()[0];
+    let source = std::fs::read_to_string(root.join("src/main.rs")).unwrap();
+    assert_eq!(fix.files(), vec!["src/main.rs"]);
+    assert_eq!(fix.apply_to_source("src/main.rs", &source).unwrap(), "fn main() {\n    let _x = 5;\n}\n");
+
+    let _ = std::fs::remove_dir_all(root);
+}
@@ -0,0 +1,462 @@
+//! Tests for the headless command-line mode
+
+use ide_rs::cli::{run, EXIT_FAILURE, EXIT_SUCCESS, EXIT_USAGE};
+use ide_rs::editor::packaging::{PackageManifest, MANIFEST_FILE_NAME};
+use ide_rs::editor::project_manager::project::ComponentData as DesignerComponent;
+use ide_rs::editor::project_manager::project::LayoutType as DesignerLayout;
+use ide_rs::editor::project_manager::IdeProject;
+use ide_rs::editor::project_scaffolding::{Dependency, ProjectConfiguration, ProjectScaffoldingEngine, TemplateVariable, VariableType};
+use ide_rs::rcl::ui::component::Component;
+use ide_rs::shared::serialization::*;
+use semver::Version;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Generated form with handler code in its guards, compiled as part of the tests
+#[path = "golden/codegen/checkout_form.rs"]
+#[allow(clippy::single_match)]
+mod checkout_form;
+
+/// Run a command and return its exit code, stdout and stderr
+fn ide(args: &[&str]) -> (i32, String, String) {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    let code = run(&args, &mut out, &mut err);
+    (code, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+}
+
+/// Run a command with `--json` and parse its single output document
+fn ide_json(args: &[&str]) -> (i32, Value) {
+    let mut args = args.to_vec();
+    args.insert(0, "--json");
+    let (code, out, err) = ide(&args);
+    assert!(err.is_empty(), "JSON mode wrote to stderr: {}", err);
+    assert_eq!(out.lines().count(), 1, "expected a single JSON document: {}", out);
+    (code, serde_json::from_str(&out).unwrap())
+}
+
+fn new_project(root: &Path, name: &str) -> String {
+    let path = root.to_str().unwrap();
+    let (code, output) = ide_json(&["new", "--template", "rust-app-basic", "--name", name, "--path", path, "--author", "Ada", "--no-git"]);
+    assert_eq!(code, EXIT_SUCCESS, "{}", output);
+    output["path"].as_str().unwrap().to_string()
+}
+
+fn designer_component(component_type: &str, id: &str, z_order: i32) -> DesignerComponent {
+    DesignerComponent {
+        component_type: component_type.to_string(),
+        properties: HashMap::new(),
+        position: (0.0, 0.0),
+        size: (100.0, 30.0),
+        z_order,
+        locked: false,
+        id: id.to_string(),
+    }
+}
+
+fn set_components(project_path: &str, components: Vec<DesignerComponent>) {
+    let file = Path::new(project_path).join("project.ide");
+    let mut project: IdeProject = serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+    project.designer_data.components = components;
+    fs::write(&file, serde_json::to_string_pretty(&project).unwrap()).unwrap();
+}
+
+#[test]
+fn test_new_validate_codegen_pipeline() {
+    let temp = TempDir::new().unwrap();
+    let project = new_project(temp.path(), "inventory");
+    let root = Path::new(&project);
+    assert_eq!(root, temp.path().join("inventory"));
+    let manifest = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+    assert!(manifest.contains("name = \"inventory\""));
+    assert!(manifest.contains("authors = [\"Ada\"]"));
+    assert!(fs::read_to_string(root.join("src/main.rs")).unwrap().contains("Hello from inventory!"));
+    assert!(!root.join(".git").exists());
+
+    let (code, report) = ide_json(&["validate", &project]);
+    assert_eq!(code, EXIT_SUCCESS);
+    assert_eq!(report["valid"], Value::Bool(true));
+    assert_eq!(report["project"], "inventory");
+
+    set_components(&project, vec![designer_component("text-box", "itemName", 1), designer_component("button", "save", 0)]);
+    let (code, report) = ide_json(&["codegen", &project]);
+    assert_eq!(code, EXIT_SUCCESS);
+    assert_eq!(report["components"], 2);
+    let output = root.join("src/generated/inventory_form.rs");
+    assert_eq!(report["output"], output.display().to_string());
+
+    let code = fs::read_to_string(&output).unwrap();
+    assert!(code.contains("pub struct InventoryForm {\n    pub save: Button,\n    pub item_name: TextBox,\n"), "{}", code);
+    assert!(code.contains("            item_name: TextBox::new(String::new()),"));
+    assert!(code.contains("use ide_rs::rcl::ui::basic::button::Button;\nuse ide_rs::rcl::ui::basic::textbox::TextBox;\n"), "{}", code);
+    assert_eq!(code.matches("// <codegen:guard:form_methods:start>").count(), 1);
+
+    // User code inside guards survives regeneration
+    let edited = code.replace("    // Form methods", "    pub fn total(&self) -> u32 {\n        42\n    }");
+    fs::write(&output, &edited).unwrap();
+    let (code, _, _) = ide(&["codegen", &project]);
+    assert_eq!(code, EXIT_SUCCESS);
+    assert_eq!(fs::read_to_string(&output).unwrap(), edited);
+}
+
+#[test]
+fn test_codegen_event_handlers() {
+    let temp = TempDir::new().unwrap();
+    let project = new_project(temp.path(), "checkout");
+    let output = Path::new(&project).join("src/generated/checkout_form.rs");
+
+    let mut pay = designer_component("Button", "pay", 0);
+    pay.properties.insert("on_click".to_string(), "pay_click".to_string());
+    set_components(&project, vec![pay.clone()]);
+    let (code, report) = ide_json(&["codegen", &project]);
+    assert_eq!(code, EXIT_SUCCESS);
+    assert_eq!(report["events"], 1);
+
+    let code = fs::read_to_string(&output).unwrap();
+    assert!(code.contains("        for event in self.pay.take_events() {\n            match event.as_str() {\n                \"click\" => self.pay_click(),"), "{}", code);
+    assert!(code.contains("    // <codegen:guard:event_handlers:start>\n    pub fn pay_click(&mut self) {\n    }\n"), "{}", code);
+    assert!(code.contains("        self.dispatch_events();"));
+
+    // Handler bodies survive regeneration and new bindings get their own stubs
+    let edited = code.replace("    pub fn pay_click(&mut self) {\n    }", "    pub fn pay_click(&mut self) {\n        self.paid = true;\n    }");
+    fs::write(&output, &edited).unwrap();
+    pay.properties.insert("on_focus".to_string(), "pay_focus".to_string());
+    set_components(&project, vec![pay]);
+    let (code, report) = ide_json(&["codegen", &project]);
+    assert_eq!(code, EXIT_SUCCESS);
+    assert_eq!(report["events"], 2);
+    let code = fs::read_to_string(&output).unwrap();
+    assert!(code.contains("        self.paid = true;\n    }\n\n    pub fn pay_focus(&mut self) {\n    }\n"), "{}", code);
+    assert!(code.contains("\"focus\" => self.pay_focus(),"));
+    assert_eq!(code.matches("fn pay_click").count(), 1);
+}
+
+#[test]
+fn test_generated_form_compiles_and_dispatches_events() {
+    let temp = TempDir::new().unwrap();
+    let project = new_project(temp.path(), "checkout");
+    let output = Path::new(&project).join("src/generated/checkout_form.rs");
+
+    let mut pay = designer_component("Button", "pay", 0);
+    pay.properties.insert("label".to_string(), "Pay".to_string());
+    pay.properties.insert("on_click".to_string(), "pay_click".to_string());
+    let mut total = designer_component("Label", "total", 1);
+    total.position = (0.0, 40.0);
+    total.properties.insert("text".to_string(), "Total: 0".to_string());
+    set_components(&project, vec![total, pay]);
+    let file = Path::new(&project).join("project.ide");
+    let mut saved: IdeProject = serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+    saved.designer_data.layout_config.layout_type = DesignerLayout::Vertical;
+    fs::write(&file, serde_json::to_string_pretty(&saved).unwrap()).unwrap();
+    assert_eq!(ide_json(&["codegen", &project]).0, EXIT_SUCCESS);
+
+    // The golden file is the generated form with code added to its guards only
+    let golden = include_str!("golden/codegen/checkout_form.rs");
+    let generated = fs::read_to_string(&output).unwrap()
+        .replace("    // Form fields", "    pub paid: bool,")
+        .replace("            // Form initialization", "            paid: false,")
+        .replace("    pub fn pay_click(&mut self) {\n    }", "    pub fn pay_click(&mut self) {\n        self.paid = true;\n    }");
+    assert_eq!(generated, golden);
+    fs::write(&output, golden).unwrap();
+    assert_eq!(ide_json(&["codegen", &project]).0, EXIT_SUCCESS);
+    assert_eq!(fs::read_to_string(&output).unwrap(), golden);
+
+    // Components flow down the form in designer order, spaced by the layout's gap
+    assert_eq!(checkout_form::CheckoutForm::layout(), vec![
+        ("pay", egui::Rect::from_min_size(egui::pos2(20.0, 20.0), egui::vec2(100.0, 30.0))),
+        ("total", egui::Rect::from_min_size(egui::pos2(20.0, 60.0), egui::vec2(100.0, 30.0))),
+    ]);
+
+    let mut form = checkout_form::CheckoutForm::new();
+    assert_eq!(form.pay.get_property("label").as_deref(), Some("Pay"));
+    assert_eq!(form.total.get_property("text").as_deref(), Some("Total: 0"));
+    let _ = egui::Context::default().run(Default::default(), |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| form.render(ui));
+    });
+    assert!(!form.paid);
+
+    form.pay.click();
+    form.dispatch_events();
+    assert!(form.paid);
+}
+
+#[test]
+fn test_scaffolded_project_builds_with_generated_form() {
+    let temp = TempDir::new().unwrap();
+    let project = new_project(temp.path(), "checkout");
+    let mut pay = designer_component("Button", "pay", 0);
+    pay.properties.insert("on_click".to_string(), "pay_click".to_string());
+    set_components(&project, vec![pay, designer_component("Label", "total", 1)]);
+    assert_eq!(ide_json(&["codegen", &project]).0, EXIT_SUCCESS);
+    let modules = fs::read_to_string(Path::new(&project).join("src/generated/mod.rs")).unwrap();
+    assert!(modules.ends_with("\npub mod checkout_form;\n"), "{}", modules);
+    // Regenerating does not declare the module twice
+    assert_eq!(ide_json(&["codegen", &project]).0, EXIT_SUCCESS);
+    assert_eq!(fs::read_to_string(Path::new(&project).join("src/generated/mod.rs")).unwrap(), modules);
+
+    // The form needs only the dependencies the template gives the project
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    fs::copy(manifest_dir.join("Cargo.lock"), Path::new(&project).join("Cargo.lock")).unwrap();
+    let output = std::process::Command::new(env!("CARGO"))
+        .args(["check", "--offline", "--quiet"])
+        .current_dir(&project)
+        .env("CARGO_TARGET_DIR", manifest_dir.join("target").join("scaffold-check"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn test_codegen_merges_edits_outside_guards() {
+    let temp = TempDir::new().unwrap();
+    let project = new_project(temp.path(), "shop");
+    let output = Path::new(&project).join("src/generated/shop_form.rs");
+
+    set_components(&project, vec![designer_component("Button", "buy", 0)]);
+    assert_eq!(ide_json(&["codegen", &project]).0, EXIT_SUCCESS);
+    let edited = fs::read_to_string(&output).unwrap()
+        .replace("use ide_rs::rcl::ui::component::Component;\n", "use std::fmt;\nuse ide_rs::rcl::ui::component::Component;\n")
+        .replace("/// ShopForm form\n", "/// ShopForm form, shown at checkout\n");
+    fs::write(&output, &edited).unwrap();
+
+    // Designer changes merge with edits the template has no guard for
+    set_components(&project, vec![designer_component("Button", "buy", 0), designer_component("Label", "total", 1)]);
+    assert_eq!(ide_json(&["codegen", &project]).0, EXIT_SUCCESS);
+    let merged = fs::read_to_string(&output).unwrap();
+    assert!(merged.contains("use std::fmt;\n"), "{}", merged);
+    assert!(merged.contains("/// ShopForm form, shown at checkout\n"));
+    assert!(merged.contains("    pub buy: Button,\n    pub total: Label,\n"));
+
+    // Editing a line the designer also changes is reported, not overwritten
+    fs::write(&output, merged.replace("    pub total: Label,\n", "    pub total: Label, // grand total\n")).unwrap();
+    set_components(&project, vec![designer_component("Button", "buy", 0), designer_component("TextBox", "total", 1)]);
+    let (code, report) = ide_json(&["codegen", &project]);
+    assert_eq!(code, EXIT_FAILURE);
+    assert!(report["error"].as_str().unwrap().starts_with("1 merge conflict(s)"), "{}", report);
+    let conflicted = fs::read_to_string(&output).unwrap();
+    assert!(conflicted.contains(
+        "<<<<<<< your edits\n    pub total: Label, // grand total\n||||||| last generated\n    pub total: Label,\n=======\n    pub total: TextBox,\n>>>>>>> designer\n"
+    ), "{}", conflicted);
+    assert!(conflicted.contains("use std::fmt;\n"));
+
+    // Once resolved, the next run merges cleanly against the new output
+    fs::write(&output, conflicted.replace(
+        "<<<<<<< your edits\n    pub total: Label, // grand total\n||||||| last generated\n    pub total: Label,\n=======\n    pub total: TextBox,\n>>>>>>> designer\n",
+        "    pub total: TextBox, // grand total\n",
+    )).unwrap();
+    assert_eq!(ide_json(&["codegen", &project]).0, EXIT_SUCCESS);
+    assert!(fs::read_to_string(&output).unwrap().contains("    pub total: TextBox, // grand total\n"));
+}
+
+#[test]
+fn test_validate_reports_errors() {
+    let temp = TempDir::new().unwrap();
+    let project = new_project(temp.path(), "broken");
+    set_components(&project, vec![designer_component("button", "ok", 0), designer_component("label", "ok", 1)]);
+    fs::remove_file(Path::new(&project).join("Cargo.toml")).unwrap();
+
+    let (code, report) = ide_json(&["validate", &project]);
+    assert_eq!(code, EXIT_FAILURE);
+    assert_eq!(report["success"], Value::Bool(false));
+    assert_eq!(report["valid"], Value::Bool(false));
+    assert_eq!(report["errors"], serde_json::json!(["Duplicate component ID: ok"]));
+    assert_eq!(report["warnings"], serde_json::json!(["Cargo.toml not found"]));
+
+    let (code, out, _) = ide(&["validate", &project]);
+    assert_eq!(code, EXIT_FAILURE);
+    assert!(out.contains("error: Duplicate component ID: ok"));
+    assert!(out.ends_with("broken: invalid (1 errors, 1 warnings)\n"), "{}", out);
+
+    let (code, report) = ide_json(&["validate", temp.path().join("missing").to_str().unwrap()]);
+    assert_eq!(code, EXIT_FAILURE);
+    assert!(report["error"].as_str().unwrap().starts_with("Cannot load project"));
+}
+
+#[test]
+fn test_export_form() {
+    let temp = TempDir::new().unwrap();
+    let mut form = ComponentData {
+        component_type: "form".to_string(),
+        id: "login".to_string(),
+        properties: HashMap::new(),
+        children: Vec::new(),
+        metadata: ComponentMetadata::default(),
+        schema_version: CURRENT_SCHEMA_VERSION.to_string(),
+    };
+    form.children.push(ComponentData {
+        component_type: "button".to_string(),
+        id: "submit".to_string(),
+        properties: HashMap::from([("text".to_string(), PropertyValue::String("Sign in".to_string()))]),
+        ..form.clone()
+    });
+    let form_file = temp.path().join("login.json");
+    fs::write(&form_file, SerializationUtils::serialize_component(&form).unwrap()).unwrap();
+    let form_path = form_file.to_str().unwrap();
+
+    let (code, report) = ide_json(&["export", "--format", "react", form_path]);
+    assert_eq!(code, EXIT_SUCCESS);
+    assert_eq!(report["format"], "react_jsx");
+    assert_eq!(report["content"], "<form><button text=\"Sign in\" /></form>");
+
+    let (code, out, _) = ide(&["export", form_path, "--format", "html"]);
+    assert_eq!(code, EXIT_SUCCESS);
+    assert_eq!(out.trim_end(), SerializationUtils::export_component(&form, &ExportFormat::HtmlCss).unwrap());
+
+    let png = temp.path().join("login.png");
+    let (code, report) = ide_json(&["export", "--format", "png", form_path, "--output", png.to_str().unwrap()]);
+    assert_eq!(code, EXIT_SUCCESS);
+    let bytes = fs::read(&png).unwrap();
+    assert!(bytes.starts_with(b"\x89PNG"));
+    assert_eq!(report["bytes"], bytes.len());
+
+    assert_eq!(ide_json(&["export", "--format", "png", form_path]).0, EXIT_USAGE);
+    assert_eq!(ide_json(&["export", "--format", "pdf", form_path]).0, EXIT_USAGE);
+    let (code, report) = ide_json(&["export", "--format", "vue", temp.path().join("none.json").to_str().unwrap()]);
+    assert_eq!(code, EXIT_FAILURE);
+    assert!(report["error"].as_str().unwrap().starts_with("Cannot read"));
+}
+
+#[test]
+fn test_package_component() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.path().join("gauge");
+    fs::create_dir_all(source.join("src")).unwrap();
+    fs::write(source.join("src/lib.rs"), "// gauge\n").unwrap();
+    fs::write(source.join(MANIFEST_FILE_NAME), PackageManifest::new("gauge", Version::new(0, 3, 1)).to_json()).unwrap();
+    let output = temp.path().join("dist");
+
+    let (code, report) = ide_json(&["package", source.to_str().unwrap(), "--output", output.to_str().unwrap()]);
+    assert_eq!(code, EXIT_SUCCESS);
+    assert_eq!(report["package"], "gauge");
+    assert_eq!(report["version"], "0.3.1");
+    assert_eq!(report["files"], 1);
+    assert_eq!(report["archive"], output.join("gauge-0.3.1.rclpkg").display().to_string());
+    assert!(output.join("gauge-0.3.1.rclpkg").exists());
+
+    let (code, report) = ide_json(&["package", output.to_str().unwrap()]);
+    assert_eq!(code, EXIT_FAILURE);
+    assert!(report["error"].as_str().unwrap().starts_with("I/O error"));
+}
+
+#[test]
+fn test_usage_errors() {
+    let (code, _, err) = ide(&[]);
+    assert_eq!(code, EXIT_USAGE);
+    assert!(err.starts_with("ide-rs: No command given"));
+
+    let (code, out, _) = ide(&["--help"]);
+    assert_eq!(code, EXIT_SUCCESS);
+    assert!(out.starts_with("Usage: ide-rs"));
+
+    let (code, report) = ide_json(&["deploy"]);
+    assert_eq!(code, EXIT_USAGE);
+    assert_eq!(report, serde_json::json!({ "command": "deploy", "success": false, "error": "Unknown command 'deploy'" }));
+
+    assert_eq!(ide_json(&["validate"]).1["error"], "Missing project directory");
+    assert_eq!(ide_json(&["codegen", "a", "b"]).1["error"], "Expected a single project directory");
+    assert_eq!(ide_json(&["export", "form.json", "--format"]).1["error"], "--format needs a value");
+    assert_eq!(ide_json(&["package", "--force", "dir"]).1["error"], "Unknown option '--force'");
+
+    // `--json` is only a global flag before the command; afterwards it is an ordinary argument
+    let (code, out, err) = ide(&["validate", "dir", "--json"]);
+    assert_eq!((code, out.as_str()), (EXIT_USAGE, ""));
+    assert!(err.contains("Unknown option '--json'"), "{}", err);
+    let (code, report) = ide_json(&["export", "--format", "--json", "form.json"]);
+    assert_eq!(code, EXIT_USAGE);
+    assert!(report["error"].as_str().unwrap().contains("--json"), "{}", report);
+
+    let (code, report) = ide_json(&["new", "--template", "rust-gui"]);
+    assert_eq!(code, EXIT_USAGE);
+    assert_eq!(report["error"], "Unknown template 'rust-gui' (available: rust-app-basic, rust-cli, rust-web-api)");
+}
+
+#[test]
+fn test_scaffolding_variables_and_dependencies() {
+    let temp = TempDir::new().unwrap();
+    let mut engine = ProjectScaffoldingEngine::new();
+    let mut template = engine.get_template("rust-web-api").unwrap().clone();
+    template.id = "service".to_string();
+    template.post_scripts.clear();
+    template.dependencies.push(Dependency {
+        name: "sqlx".to_string(),
+        version: "0.7".to_string(),
+        features: vec!["postgres".to_string()],
+        optional: true,
+        default_features: false,
+        git: None,
+        git_ref: None,
+        path: None,
+    });
+    template.dev_dependencies.push(Dependency {
+        name: "insta".to_string(),
+        version: "1.34".to_string(),
+        features: Vec::new(),
+        optional: false,
+        default_features: true,
+        git: None,
+        git_ref: None,
+        path: None,
+    });
+    template.variables.push(TemplateVariable {
+        name: "port".to_string(),
+        label: "Port".to_string(),
+        description: "Listening port".to_string(),
+        var_type: VariableType::Number,
+        default_value: String::new(),
+        required: true,
+        validation: Some(r"^\d+$".to_string()),
+        choices: Vec::new(),
+    });
+    engine.add_custom_template(template);
+
+    let config = ProjectConfiguration {
+        name: "orders".to_string(),
+        path: temp.path().to_path_buf(),
+        init_git: false,
+        initial_commit: false,
+        ..ProjectConfiguration::default()
+    };
+    let mut variables = HashMap::new();
+    assert_eq!(engine.create_project("service", &config, &variables).unwrap_err(), "Missing value for required variable 'port'");
+    variables.insert("port".to_string(), "80a".to_string());
+    assert_eq!(engine.create_project("service", &config, &variables).unwrap_err(), "Invalid value '80a' for variable 'port'");
+    variables.insert("port".to_string(), "8080".to_string());
+    variables.insert("database".to_string(), "oracle".to_string());
+    assert!(engine.create_project("service", &config, &variables).unwrap_err().starts_with("Invalid value 'oracle' for variable 'database'"));
+    variables.insert("database".to_string(), "sqlite".to_string());
+
+    let path = engine.create_project("service", &config, &variables).unwrap();
+    assert_eq!(path, temp.path().join("orders"));
+    let manifest = fs::read_to_string(path.join("Cargo.toml")).unwrap();
+    // Dependencies already in the template manifest are not repeated
+    assert_eq!(manifest.matches("axum").count(), 1);
+    assert!(manifest.contains("uuid = { version = \"1.0\", features = [\"v4\"] }\nsqlx = { version = \"0.7\", features = [\"postgres\"], default-features = false, optional = true }\n"), "{}", manifest);
+    assert!(manifest.ends_with("\n[dev-dependencies]\ninsta = \"1.34\"\n"), "{}", manifest);
+    assert!(path.join("src/routes/mod.rs").exists());
+    assert_eq!(engine.get_template("service").unwrap().usage_count, 1);
+    assert_eq!(engine.recent_templates, vec!["service".to_string()]);
+
+    assert!(engine.create_project("service", &config, &variables).unwrap_err().contains("already exists"));
+}
+
+#[test]
+fn test_binary_exit_codes() {
+    let temp = TempDir::new().unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_ide-rs"))
+        .args(["--json", "validate"])
+        .arg(temp.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(EXIT_FAILURE));
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["command"], "validate");
+    assert_eq!(report["success"], Value::Bool(false));
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_ide-rs")).arg("frobnicate").output().unwrap();
+    assert_eq!(output.status.code(), Some(EXIT_USAGE));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown command 'frobnicate'"));
+}
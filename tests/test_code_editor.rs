@@ -1,5 +1,5 @@
 //! Integration test for CodeEditor
-use ide_rs::editor::code_editor::CodeEditor;
+use ide_rs::editor::code_editor::{CodeEditor, TextSelection};
 
 #[test]
 fn test_code_editor_initialization() {
@@ -7,3 +7,14 @@ fn test_code_editor_initialization() {
     assert_eq!(editor.language, "rust");
     assert_eq!(editor.code, "");
 }
+
+#[test]
+fn test_selection_chars_are_shared_as_character_indices() {
+    let mut editor = CodeEditor::with_content("rust", "let é = 1;\nlet b = é;\n".to_string());
+    // Cursor after "é" on the second line, then "é" selected right to left
+    editor.cursor_pos = (1, 10);
+    assert_eq!(editor.selection_chars(), (20, 20));
+    editor.selection = Some(TextSelection { start: (1, 8), end: (1, 10) });
+    editor.cursor_pos = (1, 8);
+    assert_eq!(editor.selection_chars(), (20, 19));
+}
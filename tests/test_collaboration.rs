@@ -0,0 +1,483 @@
+//! Tests for collaborative editing: operational transformation, the
+//! client/server protocol and the TCP relay
+
+use ide_rs::editor::collaboration::client::CollabClient;
+use ide_rs::editor::collaboration::ot::{self, OpComponent, TextOp};
+use ide_rs::editor::collaboration::protocol::{ClientId, CollabMessage, PeerInfo, SelectionRange};
+use ide_rs::editor::collaboration::relay::RelayServer;
+use ide_rs::editor::collaboration::server::CollabServer;
+use ide_rs::editor::collaboration::session::CollabSession;
+use ide_rs::editor::collaborative_development::{CollaborationSettings, CollaborativeDevelopmentEngine, CursorPosition};
+use ide_rs::editor::text_buffer::{SelectionSet, TextBuffer, TextPosition, TextRange};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Small deterministic generator so failures reproduce
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound.max(1) as u64) as usize
+    }
+
+    fn text(&mut self, max_len: usize) -> String {
+        const ALPHABET: &[char] = &['a', 'b', 'c', ' ', '\n', 'é'];
+        (0..self.below(max_len) + 1).map(|_| ALPHABET[self.below(ALPHABET.len())]).collect()
+    }
+
+    fn op(&mut self, len: usize) -> TextOp {
+        let mut op = TextOp::new();
+        let mut remaining = len;
+        while remaining > 0 {
+            let count = self.below(remaining) + 1;
+            match self.below(3) {
+                0 => {
+                    op.retain(count);
+                    remaining -= count;
+                }
+                1 => {
+                    op.delete(count);
+                    remaining -= count;
+                }
+                _ => {
+                    let text = self.text(4);
+                    op.insert(&text);
+                }
+            }
+        }
+        if self.below(2) == 0 {
+            let text = self.text(4);
+            op.insert(&text);
+        }
+        op
+    }
+}
+
+fn peer(name: &str) -> PeerInfo {
+    PeerInfo { user_id: name.to_lowercase(), display_name: name.to_string() }
+}
+
+#[test]
+fn test_apply_compose_and_diff() {
+    let mut op = TextOp::new();
+    op.retain(6).delete(5).insert("there");
+    assert_eq!(op.apply("hello world").unwrap(), "hello there");
+    assert!(op.apply("hello").is_err());
+
+    let second = TextOp::insert_at(11, 0, "> ");
+    let composed = op.compose(&second).unwrap();
+    assert_eq!(composed.apply("hello world").unwrap(), "> hello there");
+
+    let diff = TextOp::diff("fn main() {}", "fn main() { run(); }");
+    assert_eq!(diff.apply("fn main() {}").unwrap(), "fn main() { run(); }");
+    assert_eq!(
+        diff.components(),
+        &[OpComponent::Retain(11), OpComponent::Insert(" run(); ".to_string()), OpComponent::Retain(1)]
+    );
+    assert!(TextOp::diff("same", "same").is_noop());
+}
+
+#[test]
+fn test_concurrent_inserts_at_the_same_position() {
+    let a = TextOp::insert_at(3, 1, "A");
+    let b = TextOp::insert_at(3, 1, "B");
+    let (a_prime, b_prime) = TextOp::transform(&a, &b).unwrap();
+    let via_a = b_prime.apply(&a.apply("xyz").unwrap()).unwrap();
+    let via_b = a_prime.apply(&b.apply("xyz").unwrap()).unwrap();
+    assert_eq!(via_a, "xAByz");
+    assert_eq!(via_b, via_a);
+}
+
+#[test]
+fn test_delete_spanning_a_concurrent_insert() {
+    let delete = TextOp::delete_range(10, 2, 8);
+    let insert = TextOp::insert_at(10, 5, "new");
+    let (delete_prime, insert_prime) = TextOp::transform(&delete, &insert).unwrap();
+    let text = "0123456789";
+    let result = insert_prime.apply(&delete.apply(text).unwrap()).unwrap();
+    assert_eq!(result, "01new89");
+    assert_eq!(delete_prime.apply(&insert.apply(text).unwrap()).unwrap(), result);
+}
+
+#[test]
+fn test_delete_range_accepts_reversed_bounds() {
+    assert_eq!(TextOp::delete_range(10, 8, 2), TextOp::delete_range(10, 2, 8));
+    assert_eq!(TextOp::delete_range(10, 8, 2).apply("0123456789").unwrap(), "0189");
+}
+
+#[test]
+fn test_transform_and_compose_properties_on_random_operations() {
+    let mut rng = Rng(7);
+    for _ in 0..500 {
+        let text = rng.text(20);
+        let len = text.chars().count();
+        let a = rng.op(len);
+        let b = rng.op(len);
+
+        let (a_prime, b_prime) = TextOp::transform(&a, &b).unwrap();
+        let after_a = a.apply(&text).unwrap();
+        let after_b = b.apply(&text).unwrap();
+        assert_eq!(b_prime.apply(&after_a).unwrap(), a_prime.apply(&after_b).unwrap(), "diverged on {:?}", text);
+
+        let c = rng.op(after_a.chars().count());
+        let composed = a.compose(&c).unwrap();
+        assert_eq!(composed.apply(&text).unwrap(), c.apply(&after_a).unwrap());
+
+        let diff = TextOp::diff(&text, &after_b);
+        assert_eq!(diff.apply(&text).unwrap(), after_b);
+    }
+}
+
+#[test]
+fn test_transform_index() {
+    let op = TextOp::insert_at(10, 4, "abc");
+    assert_eq!(op.transform_index(2), 2);
+    assert_eq!(op.transform_index(4), 7);
+    assert_eq!(op.transform_index(9), 12);
+
+    let op = TextOp::delete_range(10, 2, 6);
+    assert_eq!(op.transform_index(4), 2);
+    assert_eq!(op.transform_index(8), 4);
+}
+
+#[test]
+fn test_messages_round_trip_as_json_lines() {
+    let message = CollabMessage::Edit { revision: 4, op: TextOp::insert_at(2, 1, "x") };
+    let line = message.to_line().unwrap();
+    assert!(line.ends_with('\n'));
+    assert!(!line.trim_end().contains('\n'));
+    assert_eq!(CollabMessage::from_line(&line).unwrap(), message);
+    assert!(CollabMessage::from_line("{\"type\":\"bogus\"}").is_err());
+}
+
+/// Editors talking to an in-memory server, with every message queued so
+/// deliveries can be interleaved arbitrarily
+struct Simulation {
+    server: CollabServer,
+    editors: Vec<(ClientId, CollabClient, TextBuffer)>,
+    to_server: HashMap<ClientId, VecDeque<CollabMessage>>,
+    to_editor: HashMap<ClientId, VecDeque<CollabMessage>>,
+}
+
+impl Simulation {
+    fn new(names: &[&str], content: &str) -> Self {
+        let mut simulation = Self {
+            server: CollabServer::new(),
+            editors: Vec::new(),
+            to_server: HashMap::new(),
+            to_editor: HashMap::new(),
+        };
+        for name in names {
+            let id = simulation.server.connect();
+            let buffer = TextBuffer::from_string(content.to_string());
+            let mut client = CollabClient::new("src/lib.rs", peer(name));
+            client.join(&buffer.to_string());
+            simulation.editors.push((id, client, buffer));
+            simulation.collect_outgoing(simulation.editors.len() - 1);
+        }
+        simulation.settle();
+        simulation
+    }
+
+    fn collect_outgoing(&mut self, editor: usize) {
+        let (id, client, _) = &mut self.editors[editor];
+        self.to_server.entry(*id).or_default().extend(client.take_outgoing());
+    }
+
+    /// Edit a buffer the way the editor does, then let the client notice
+    fn edit(&mut self, editor: usize, rng: &mut Rng) {
+        let buffer = &mut self.editors[editor].2;
+        let len = buffer.rope.len_chars();
+        let start = rng.below(len + 1);
+        let position = |buffer: &TextBuffer, index: usize| ot::char_position(buffer, index);
+        if rng.below(3) == 0 && start < len {
+            let end = (start + rng.below(4) + 1).min(len);
+            let range = TextRange { start: position(buffer, start), end: position(buffer, end) };
+            buffer.delete(range, SelectionSet::new()).unwrap();
+        } else {
+            let text: String = rng.text(3).chars().filter(char::is_ascii).collect();
+            buffer.insert(position(buffer, start), &text, SelectionSet::new()).unwrap();
+        }
+        let (_, client, buffer) = &mut self.editors[editor];
+        client.sync_text(&buffer.to_string()).unwrap();
+        self.collect_outgoing(editor);
+    }
+
+    fn deliver_to_server(&mut self, id: ClientId) -> bool {
+        let Some(message) = self.to_server.get_mut(&id).and_then(VecDeque::pop_front) else {
+            return false;
+        };
+        for (recipient, message) in self.server.handle(id, message) {
+            assert!(!matches!(message, CollabMessage::Error { .. }), "{:?}", message);
+            self.to_editor.entry(recipient).or_default().push_back(message);
+        }
+        true
+    }
+
+    fn deliver_to_editor(&mut self, editor: usize) -> bool {
+        let id = self.editors[editor].0;
+        let Some(message) = self.to_editor.get_mut(&id).and_then(VecDeque::pop_front) else {
+            return false;
+        };
+        let (_, client, buffer) = &mut self.editors[editor];
+        if let Some(op) = client.receive(message).unwrap() {
+            op.apply_to_buffer(buffer).unwrap();
+        }
+        self.collect_outgoing(editor);
+        true
+    }
+
+    fn settle(&mut self) {
+        loop {
+            let ids: Vec<ClientId> = self.editors.iter().map(|(id, _, _)| *id).collect();
+            let mut progressed = false;
+            for (editor, id) in ids.into_iter().enumerate() {
+                progressed |= self.deliver_to_server(id);
+                progressed |= self.deliver_to_editor(editor);
+            }
+            if !progressed {
+                break;
+            }
+        }
+    }
+
+    fn assert_converged(&self) {
+        let content = &self.server.document("src/lib.rs").unwrap().content;
+        for (_, client, buffer) in &self.editors {
+            assert!(client.is_synchronized());
+            assert_eq!(client.text(), content);
+            assert_eq!(&buffer.to_string(), content);
+        }
+    }
+}
+
+#[test]
+fn test_first_editor_seeds_the_document() {
+    let mut simulation = Simulation::new(&["Ada"], "fn main() {}\n");
+    let mut late = CollabClient::new("src/lib.rs", peer("Bob"));
+    late.join("stale local copy");
+    let id = simulation.server.connect();
+    let welcome = simulation.server.handle(id, late.take_outgoing().remove(0));
+    let (_, welcome) = welcome.into_iter().find(|(recipient, _)| *recipient == id).unwrap();
+
+    let op = late.receive(welcome).unwrap().unwrap();
+    assert_eq!(op.apply("stale local copy").unwrap(), "fn main() {}\n");
+    assert_eq!(late.text(), "fn main() {}\n");
+    assert_eq!(late.peers().len(), 1);
+    simulation.assert_converged();
+}
+
+#[test]
+fn test_interleaved_edits_from_multiple_clients_converge() {
+    for seed in 1..=20 {
+        let mut rng = Rng(seed);
+        let mut simulation = Simulation::new(&["Ada", "Bob", "Cy"], "fn main() {\n    println!(\"hi\");\n}\n");
+        for _ in 0..300 {
+            let editor = rng.below(3);
+            match rng.below(3) {
+                0 => simulation.edit(editor, &mut rng),
+                1 => {
+                    let id = simulation.editors[editor].0;
+                    simulation.deliver_to_server(id);
+                }
+                _ => {
+                    simulation.deliver_to_editor(editor);
+                }
+            }
+        }
+        simulation.settle();
+        simulation.assert_converged();
+        assert!(simulation.server.document("src/lib.rs").unwrap().revision() > 0);
+    }
+}
+
+#[test]
+fn test_edits_made_while_waiting_for_an_ack_are_buffered() {
+    let mut server = CollabServer::new();
+    let (a, b) = (server.connect(), server.connect());
+    let mut ada = CollabClient::new("doc", peer("Ada"));
+    let mut bob = CollabClient::new("doc", peer("Bob"));
+    let mut deliver = |from: ClientId, client: &mut CollabClient| server.handle(from, client.take_outgoing().remove(0));
+
+    ada.join("abc");
+    for (_, message) in deliver(a, &mut ada) {
+        ada.receive(message).unwrap();
+    }
+    bob.join("");
+    for (to, message) in deliver(b, &mut bob) {
+        if to == b {
+            bob.receive(message).unwrap();
+        }
+    }
+
+    ada.sync_text("abcd").unwrap();
+    ada.sync_text("abcde").unwrap();
+    ada.sync_text("Xabcde").unwrap();
+    let messages = ada.take_outgoing();
+    assert_eq!(messages.len(), 1, "only the first edit is in flight");
+    assert!(!ada.is_synchronized());
+
+    bob.sync_text("abc!").unwrap();
+    let bob_edit = server.handle(b, bob.take_outgoing().remove(0));
+    let ada_edit = server.handle(a, messages.into_iter().next().unwrap());
+
+    for (to, message) in bob_edit.into_iter().chain(ada_edit) {
+        let client = if to == a { &mut ada } else { &mut bob };
+        client.receive(message).unwrap();
+    }
+    // Ada's buffered edits go out after the ack
+    let buffered = ada.take_outgoing();
+    assert_eq!(buffered.len(), 1);
+    for (to, message) in server.handle(a, buffered.into_iter().next().unwrap()) {
+        let client = if to == a { &mut ada } else { &mut bob };
+        client.receive(message).unwrap();
+    }
+
+    assert!(ada.is_synchronized() && bob.is_synchronized());
+    assert_eq!(ada.text(), bob.text());
+    assert_eq!(server.document("doc").unwrap().content, ada.text());
+    assert_eq!(ada.text(), "Xabcde!");
+}
+
+#[test]
+fn test_selections_are_broadcast_and_follow_edits() {
+    let mut simulation = Simulation::new(&["Ada", "Bob"], "let x = 1;\n");
+    let bob_id = simulation.editors[1].0;
+
+    simulation.editors[1].1.set_selections(vec![SelectionRange { anchor: 4, head: 5 }]);
+    simulation.collect_outgoing(1);
+    simulation.settle();
+
+    let ada = &simulation.editors[0].1;
+    let bob = ada.peers().into_iter().find(|peer| peer.client_id == bob_id).unwrap();
+    assert_eq!(bob.peer.display_name, "Bob");
+    assert_eq!(bob.selections, vec![SelectionRange { anchor: 4, head: 5 }]);
+
+    // Ada types in front of Bob's selection
+    let (_, client, buffer) = &mut simulation.editors[0];
+    buffer.insert(TextPosition { line: 0, column: 0, offset: 0 }, "pub ", SelectionSet::new()).unwrap();
+    client.sync_text(&buffer.to_string()).unwrap();
+    let moved = SelectionRange { anchor: 8, head: 9 };
+    assert_eq!(client.peers()[0].selections, vec![moved]);
+    simulation.collect_outgoing(0);
+    simulation.settle();
+    assert_eq!(simulation.server.document("src/lib.rs").unwrap().participants[&bob_id].selections, vec![moved]);
+
+    // Leaving removes the cursor for everyone
+    let outgoing = simulation.server.disconnect(bob_id);
+    assert_eq!(outgoing, vec![(simulation.editors[0].0, CollabMessage::PeerLeft { client_id: bob_id })]);
+    simulation.editors[0].1.receive(outgoing[0].1.clone()).unwrap();
+    assert!(simulation.editors[0].1.peers().is_empty());
+}
+
+#[test]
+fn test_edit_before_join_is_rejected() {
+    let mut server = CollabServer::new();
+    let id = server.connect();
+    let replies = server.handle(id, CollabMessage::Edit { revision: 0, op: TextOp::insert_at(0, 0, "x") });
+    assert!(matches!(replies[0].1, CollabMessage::Error { .. }));
+
+    let mut client = CollabClient::new("doc", peer("Ada"));
+    assert!(client.local_edit(TextOp::insert_at(0, 0, "x")).is_err());
+}
+
+#[test]
+fn test_two_editors_co_edit_through_the_tcp_relay() {
+    let relay = RelayServer::bind("127.0.0.1:0").unwrap();
+    let addr = relay.local_addr().unwrap();
+    let server = relay.server();
+    relay.spawn();
+
+    let mut ada_buffer = TextBuffer::from_string("fn main() {\n}\n".to_string());
+    let mut ada = CollabSession::connect(addr, "main.rs", peer("Ada"), &ada_buffer).unwrap();
+    let mut bob_buffer = TextBuffer::new();
+    let mut bob = CollabSession::connect(addr, "main.rs", peer("Bob"), &bob_buffer).unwrap();
+
+    let poll_until = |condition: &dyn Fn(&TextBuffer, &TextBuffer) -> bool,
+                      ada: &mut CollabSession,
+                      ada_buffer: &mut TextBuffer,
+                      bob: &mut CollabSession,
+                      bob_buffer: &mut TextBuffer| {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !condition(ada_buffer, bob_buffer) {
+            assert!(Instant::now() < deadline, "editors did not converge");
+            ada.poll(ada_buffer).unwrap();
+            bob.poll(bob_buffer).unwrap();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    };
+
+    // Bob receives the document Ada opened first
+    poll_until(&|_, bob| bob.to_string() == "fn main() {\n}\n", &mut ada, &mut ada_buffer, &mut bob, &mut bob_buffer);
+
+    // Both type at once
+    ada_buffer.insert(TextPosition { line: 1, column: 0, offset: 12 }, "    run();\n", SelectionSet::new()).unwrap();
+    bob_buffer.insert(TextPosition { line: 0, column: 0, offset: 0 }, "use app::run;\n\n", SelectionSet::new()).unwrap();
+    let expected = "use app::run;\n\nfn main() {\n    run();\n}\n";
+    poll_until(
+        &|ada, bob| ada.to_string() == expected && bob.to_string() == expected,
+        &mut ada,
+        &mut ada_buffer,
+        &mut bob,
+        &mut bob_buffer,
+    );
+
+    // Cursors are shared
+    let cursor = SelectionSet::single(TextPosition { line: 3, column: 4, offset: 31 });
+    bob.set_selections(&bob_buffer, &cursor).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while ada.peers().first().map(|peer| peer.selections.clone()) != Some(vec![SelectionRange::caret(31)]) {
+        assert!(Instant::now() < deadline, "cursor was not shared");
+        ada.poll(&mut ada_buffer).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(ada.peers()[0].peer.display_name, "Bob");
+
+    let server = server.lock().unwrap();
+    assert_eq!(server.document("main.rs").unwrap().content, expected);
+}
+
+#[test]
+fn test_realtime_editing_engine_merges_stale_edits() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notes.txt");
+    std::fs::write(&path, "one\ntwo\n").unwrap();
+
+    let mut engine = CollaborativeDevelopmentEngine::new(CollaborationSettings::default());
+    let session = engine.start_editing_session(path.clone(), "ada".to_string()).unwrap();
+    let version = engine.join_editing_session(&session, "bob".to_string()).unwrap();
+    assert_eq!(version, 1);
+    assert_eq!(engine.document_state(&session).unwrap().content, "one\ntwo\n");
+
+    engine.update_cursor(CursorPosition {
+        user_id: "bob".to_string(),
+        file_path: path.clone(),
+        line: 1,
+        column: 3,
+        selection_start: None,
+        selection_end: None,
+        last_updated: SystemTime::now(),
+    });
+
+    // Both edit version 1
+    engine.apply_edit(&session, "ada", 1, &TextOp::insert_at(8, 0, "zero\n")).unwrap();
+    let applied = engine.apply_edit(&session, "bob", 1, &TextOp::insert_at(8, 7, "!")).unwrap();
+    assert_eq!(applied.base_len(), 13);
+
+    let state = engine.document_state(&session).unwrap();
+    assert_eq!(state.content, "zero\none\ntwo!\n");
+    assert_eq!(state.version, 3);
+
+    // Bob's cursor moved down a line with Ada's insert, then past his own
+    let cursors = engine.cursors_in(Path::new(&path));
+    assert_eq!((cursors[0].line, cursors[0].column), (2, 4));
+
+    assert!(engine.apply_edit(&session, "eve", 3, &TextOp::identity(14)).is_err());
+    assert!(engine.apply_edit(&session, "ada", 9, &TextOp::identity(14)).is_err());
+}
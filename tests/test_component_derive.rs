@@ -0,0 +1,210 @@
+//! Tests for `#[derive(ComponentMeta)]`
+
+use ide_rs::rcl::component_registry::*;
+use ide_rs::rcl::examples::button_component::Button;
+use std::collections::HashMap;
+
+#[derive(Debug, Default, PartialEq, ComponentMeta)]
+#[component(name = "Slider", description = "Pick a value from a range", category = "Input", icon = "slider.svg", tags("range"))]
+#[event(name = "change", description = "Value changed", param(name = "value", type = "float", description = "New value"))]
+struct Slider {
+    #[property(default = 50, range(0, 100), step = 0.5, control = "slider", unit = "%", category = "Value")]
+    value: f64,
+
+    #[property(description = "Label next to the slider", placeholder = "Volume", max_length = 20, required)]
+    label: String,
+
+    #[property(options("horizontal", "vertical"), default = "horizontal", category = "Layout")]
+    orientation: String,
+
+    #[property(default = ["a", "b"], unique_items, max_items = 3, category = "Value")]
+    marks: Vec<String>,
+
+    tint: Option<Color>,
+
+    #[property(readonly, advanced, display_name = "Internal Id")]
+    id: u32,
+
+    #[event(bubbles)]
+    on_drag_end: String,
+
+    #[property(skip)]
+    dragging: bool,
+}
+
+#[derive(Default, ComponentMeta)]
+struct Plain {
+    r#type: String,
+}
+
+#[test]
+fn test_metadata() {
+    let metadata = Slider::metadata();
+    assert_eq!(metadata.component_type, "Slider");
+    assert_eq!(metadata.display_name, "Slider");
+    assert_eq!(metadata.category, ComponentCategory::Input);
+    assert_eq!(metadata.icon.as_deref(), Some("slider.svg"));
+    assert_eq!(metadata.tags, vec!["range".to_string()]);
+    assert_eq!(metadata.events, vec!["drag_end".to_string(), "change".to_string()]);
+
+    let plain = Plain::metadata();
+    assert_eq!(plain.component_type, "Plain");
+    assert_eq!(plain.description, "Plain component");
+    assert_eq!(plain.category, ComponentCategory::Custom("Custom".to_string()));
+    assert!(plain.schema.properties.contains_key("type"));
+
+    let mut properties = HashMap::new();
+    properties.insert("type".to_string(), PropertyValue::String("range".to_string()));
+    assert_eq!(Plain::from_properties(&properties).r#type, "range");
+}
+
+#[test]
+fn test_property_schema() {
+    let schema = Slider::schema();
+    assert_eq!(schema.properties.len(), 7);
+    assert!(!schema.properties.contains_key("dragging"));
+    assert_eq!(schema.required, vec!["label".to_string()]);
+
+    let value = &schema.properties["value"];
+    assert_eq!(value.property_type, PropertyType::Float);
+    assert_eq!(value.display_name, "Value");
+    assert_eq!(value.default_value, PropertyValue::Float(50.0));
+    assert_eq!(value.constraints, vec![PropertyConstraint::Range(0.0, 100.0)]);
+    assert_eq!(value.ui_hints.control_type, ControlType::Slider);
+    assert_eq!(value.ui_hints.step, Some(0.5));
+    assert_eq!(value.ui_hints.unit.as_deref(), Some("%"));
+
+    let label = &schema.properties["label"];
+    assert_eq!(label.description, "Label next to the slider");
+    assert_eq!(label.ui_hints.control_type, ControlType::TextInput);
+    assert_eq!(label.ui_hints.placeholder.as_deref(), Some("Volume"));
+    assert_eq!(label.default_value, PropertyValue::Null);
+
+    let orientation = &schema.properties["orientation"];
+    assert_eq!(orientation.property_type, PropertyType::Enum(vec!["horizontal".to_string(), "vertical".to_string()]));
+    assert_eq!(orientation.ui_hints.control_type, ControlType::Select);
+    assert_eq!(orientation.ui_hints.options.as_ref().map(Vec::len), Some(2));
+
+    let marks = &schema.properties["marks"];
+    assert_eq!(marks.property_type, PropertyType::Array(Box::new(PropertyType::String)));
+    assert_eq!(marks.constraints, vec![PropertyConstraint::UniqueItems, PropertyConstraint::MaxItems(3)]);
+
+    assert_eq!(schema.properties["tint"].property_type, PropertyType::Color);
+    assert_eq!(schema.properties["tint"].ui_hints.control_type, ControlType::ColorPicker);
+
+    let id = &schema.properties["id"];
+    assert_eq!(id.display_name, "Internal Id");
+    assert!(id.readonly && id.advanced);
+    assert_eq!(id.ui_hints.control_type, ControlType::NumberInput);
+
+    assert_eq!(schema.properties["on_drag_end"].property_type, PropertyType::EventHandler);
+
+    let groups: Vec<(&str, &Vec<String>)> = schema.groups.iter().map(|group| (group.name.as_str(), &group.properties)).collect();
+    assert_eq!(
+        groups,
+        vec![
+            ("value", &vec!["value".to_string(), "marks".to_string()]),
+            ("layout", &vec!["orientation".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn test_events() {
+    let events = Slider::events();
+    assert_eq!(events[0].name, "drag_end");
+    assert!(events[0].bubbles);
+    assert_eq!(events[0].description, "Drag End event");
+
+    assert_eq!(events[1].name, "change");
+    assert_eq!(events[1].description, "Value changed");
+    assert!(!events[1].bubbles);
+    assert_eq!(events[1].parameters[0].name, "value");
+    assert_eq!(events[1].parameters[0].parameter_type, PropertyType::Float);
+}
+
+#[test]
+fn test_factory_builds_components_from_properties() {
+    let mut registry = ComponentRegistry::new();
+    registry.register::<Slider>();
+    assert!(registry.events.contains_key("change"));
+
+    let defaults = registry.create_component("Slider", &HashMap::new()).unwrap();
+    let defaults = defaults.downcast_ref::<Slider>().unwrap();
+    assert_eq!(defaults.value, 50.0);
+    assert_eq!(defaults.orientation, "horizontal");
+    assert_eq!(defaults.marks, vec!["a".to_string(), "b".to_string()]);
+    assert_eq!(defaults.label, "");
+    assert_eq!(defaults.tint, None);
+
+    let mut properties = HashMap::new();
+    properties.insert("value".to_string(), PropertyValue::Integer(75));
+    properties.insert("label".to_string(), PropertyValue::String("Volume".to_string()));
+    properties.insert("tint".to_string(), PropertyValue::Color(Color { r: 255, g: 0, b: 0, a: 255 }));
+    properties.insert("id".to_string(), PropertyValue::Integer(-1));
+    properties.insert("on_drag_end".to_string(), PropertyValue::EventHandler("save_volume".to_string()));
+    let slider = Slider::from_properties(&properties);
+    assert_eq!(
+        slider,
+        Slider {
+            value: 75.0,
+            label: "Volume".to_string(),
+            orientation: "horizontal".to_string(),
+            marks: vec!["a".to_string(), "b".to_string()],
+            tint: Some(Color { r: 255, g: 0, b: 0, a: 255 }),
+            // Out of range for u32, so the default is used
+            id: 0,
+            on_drag_end: "save_volume".to_string(),
+            dragging: false,
+        }
+    );
+}
+
+#[test]
+fn test_validation_uses_generated_constraints() {
+    let mut registry = ComponentRegistry::new();
+    registry.register::<Slider>();
+
+    let mut properties = HashMap::new();
+    properties.insert("label".to_string(), PropertyValue::String("Volume".to_string()));
+    properties.insert("value".to_string(), PropertyValue::Integer(40));
+    properties.insert("marks".to_string(), PropertyValue::Array(vec![PropertyValue::String("low".to_string())]));
+    assert!(registry.validate_properties("Slider", &properties).is_empty());
+
+    properties.insert("value".to_string(), PropertyValue::Float(120.0));
+    properties.insert("orientation".to_string(), PropertyValue::String("diagonal".to_string()));
+    let marks = vec![PropertyValue::String("a".to_string()), PropertyValue::String("a".to_string())];
+    properties.insert("marks".to_string(), PropertyValue::Array(marks));
+    properties.remove("label");
+    let mut invalid: Vec<String> = registry.validate_properties("Slider", &properties).into_iter().map(|error| error.property).collect();
+    invalid.sort();
+    assert_eq!(invalid, vec!["label", "marks", "orientation", "value"]);
+}
+
+#[test]
+fn test_inspector_groups_follow_categories() {
+    let registry = ComponentRegistry::new();
+    let inspector = registry.generate_inspector(&Slider::schema());
+    let titles: Vec<&str> = inspector.sections.iter().map(|section| section.title.as_str()).collect();
+    assert_eq!(titles, vec!["Value", "Layout", "Properties"]);
+    assert_eq!(inspector.sections[2].controls.len(), 4);
+}
+
+#[test]
+fn test_example_button() {
+    let mut registry = ComponentRegistry::new();
+    registry.register::<Button>();
+    let metadata = registry.get_metadata("Button").unwrap();
+    assert_eq!(metadata.category, ComponentCategory::Input);
+    assert_eq!(metadata.defaults.get("font_size"), Some(&PropertyValue::Integer(16)));
+
+    let mut properties = HashMap::new();
+    properties.insert("text".to_string(), PropertyValue::String("OK".to_string()));
+    properties.insert("classes".to_string(), PropertyValue::Array(vec![PropertyValue::String("primary".to_string())]));
+    let button = registry.create_component("Button", &properties).unwrap();
+    let button = button.downcast_ref::<Button>().unwrap();
+    assert_eq!(button.text, "OK");
+    assert!(button.enabled);
+    assert_eq!(button.classes, vec!["primary".to_string()]);
+    assert_eq!(button.font_size, 16);
+}
@@ -0,0 +1,432 @@
+//! Integration tests for the Debug Adapter Protocol backend
+//!
+//! A scripted mock adapter listens on a localhost port and answers the requests
+//! a real lldb-dap/gdb session would see, so no debugger needs to be installed.
+
+use ide_rs::editor::dap_client::{DapDebugAdapter, DapTransport};
+use ide_rs::editor::debugger::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const SOURCE: &str = "/work/demo/src/main.rs";
+
+fn read_message(reader: &mut BufReader<TcpStream>) -> Option<Value> {
+    let mut length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(value) = line.strip_prefix("Content-Length:") {
+            length = value.trim().parse().ok()?;
+        }
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+    serde_json::from_slice(&body).ok()
+}
+
+struct MockWriter {
+    stream: TcpStream,
+    seq: i64,
+}
+
+impl MockWriter {
+    fn send(&mut self, mut message: Value) {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        let body = message.to_string();
+        write!(self.stream, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+        self.stream.flush().unwrap();
+    }
+
+    fn respond(&mut self, request: &Value, body: Value) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": true,
+            "body": body,
+        }));
+    }
+
+    fn fail(&mut self, request: &Value, message: &str) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": false,
+            "message": message,
+        }));
+    }
+
+    fn event(&mut self, event: &str, body: Value) {
+        self.send(json!({ "type": "event", "event": event, "body": body }));
+    }
+}
+
+/// Start the mock adapter; returns its address and a log of received requests
+fn start_mock_adapter() -> (String, Arc<Mutex<Vec<Value>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let requests = log.clone();
+
+    std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        stream.set_nodelay(true).unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut writer = MockWriter { stream, seq: 0 };
+        let mut next_breakpoint_id = 100;
+        let mut line = 3;
+        let mut first_hit = Value::Null;
+        let mut stop_after_configuration = true;
+
+        while let Some(request) = read_message(&mut reader) {
+            requests.lock().unwrap().push(request.clone());
+            let arguments = &request["arguments"];
+
+            match request["command"].as_str().unwrap_or_default() {
+                "initialize" => {
+                    writer.respond(&request, json!({ "supportsConfigurationDoneRequest": true }));
+                    writer.event("initialized", Value::Null);
+                }
+                "launch" => {
+                    stop_after_configuration = !arguments["args"].as_array().unwrap().contains(&json!("--no-stop"));
+                    writer.respond(&request, Value::Null);
+                }
+                "setBreakpoints" => {
+                    let breakpoints: Vec<Value> = arguments["breakpoints"].as_array().unwrap().iter().map(|bp| {
+                        next_breakpoint_id += 1;
+                        if bp["line"] == json!(999) {
+                            json!({ "id": next_breakpoint_id, "verified": false, "message": "no code at line 999" })
+                        } else {
+                            json!({ "id": next_breakpoint_id, "verified": true, "line": bp["line"] })
+                        }
+                    }).collect();
+                    if first_hit.is_null() {
+                        first_hit = breakpoints.first().map(|bp| bp["id"].clone()).unwrap_or(Value::Null);
+                    }
+                    writer.respond(&request, json!({ "breakpoints": breakpoints }));
+                }
+                "configurationDone" => {
+                    writer.respond(&request, Value::Null);
+                    writer.event("output", json!({ "category": "stdout", "output": "starting demo\n" }));
+                    if stop_after_configuration {
+                        writer.event("stopped", json!({
+                            "reason": "breakpoint",
+                            "threadId": 5,
+                            "allThreadsStopped": true,
+                            "hitBreakpointIds": [first_hit],
+                        }));
+                    }
+                }
+                "threads" => writer.respond(&request, json!({
+                    "threads": [{ "id": 5, "name": "main" }, { "id": 6, "name": "worker" }],
+                })),
+                "stackTrace" => writer.respond(&request, json!({
+                    "stackFrames": [
+                        { "id": 1000, "name": "demo::main", "source": { "path": SOURCE }, "line": line, "column": 5 },
+                        { "id": 1001, "name": "std::rt::lang_start", "line": 0, "column": 0, "presentationHint": "subtle" },
+                    ],
+                    "totalFrames": 2,
+                })),
+                "scopes" => writer.respond(&request, json!({
+                    "scopes": [
+                        { "name": "Locals", "variablesReference": 1, "expensive": false },
+                        { "name": "Registers", "variablesReference": 9, "expensive": true },
+                    ],
+                })),
+                "variables" => {
+                    let variables = match arguments["variablesReference"].as_u64() {
+                        Some(1) => json!([
+                            { "name": "x", "value": "42", "type": "i32", "variablesReference": 0 },
+                            { "name": "v", "value": "size=2", "type": "Vec<i32>", "variablesReference": 2, "indexedVariables": 2 },
+                        ]),
+                        Some(2) => json!([
+                            { "name": "[0]", "value": "1", "type": "i32", "variablesReference": 0 },
+                            { "name": "[1]", "value": "2", "type": "i32", "variablesReference": 0 },
+                        ]),
+                        _ => json!([]),
+                    };
+                    writer.respond(&request, json!({ "variables": variables }));
+                }
+                "evaluate" => match arguments["expression"].as_str() {
+                    Some("x * 2") => writer.respond(&request, json!({ "result": "84", "type": "i32", "variablesReference": 0 })),
+                    Some("x") => writer.respond(&request, json!({ "result": "42", "type": "i32", "variablesReference": 0 })),
+                    _ => writer.fail(&request, "use of undeclared identifier"),
+                },
+                "next" => {
+                    line += 1;
+                    writer.respond(&request, Value::Null);
+                    writer.event("stopped", json!({ "reason": "step", "threadId": 5 }));
+                }
+                "pause" => {
+                    writer.respond(&request, Value::Null);
+                    writer.event("stopped", json!({ "reason": "pause", "threadId": arguments["threadId"] }));
+                }
+                "continue" => {
+                    writer.respond(&request, json!({ "allThreadsContinued": true }));
+                    writer.event("output", json!({ "category": "stderr", "output": "done\n" }));
+                    writer.event("exited", json!({ "exitCode": 3 }));
+                    writer.event("terminated", Value::Null);
+                }
+                "disconnect" => {
+                    writer.respond(&request, Value::Null);
+                    break;
+                }
+                _ => writer.fail(&request, "unsupported request"),
+            }
+        }
+    });
+
+    (address, log)
+}
+
+fn config() -> DebugConfiguration {
+    DebugConfiguration {
+        name: "Debug demo".to_string(),
+        adapter_type: "mock".to_string(),
+        program: PathBuf::from("/work/demo/target/debug/demo"),
+        args: vec!["--verbose".to_string()],
+        cwd: PathBuf::from("/work/demo"),
+        env: HashMap::new(),
+        stop_at_entry: false,
+        additional_config: HashMap::new(),
+    }
+}
+
+fn debugger_with_mock() -> (Debugger, Arc<Mutex<Vec<Value>>>) {
+    let (address, log) = start_mock_adapter();
+    let mut debugger = Debugger::new();
+    debugger.register_adapter("mock".to_string(), Box::new(DapDebugAdapter::new(DapTransport::Tcp { address })));
+    (debugger, log)
+}
+
+fn commands(log: &Arc<Mutex<Vec<Value>>>) -> Vec<String> {
+    log.lock().unwrap().iter()
+        .map(|request| request["command"].as_str().unwrap_or_default().to_string())
+        .collect()
+}
+
+fn wait_until<F: FnMut(&mut Debugger) -> bool>(debugger: &mut Debugger, mut condition: F) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        debugger.update();
+        if condition(debugger) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
+
+fn is_paused(debugger: &mut Debugger) -> bool {
+    matches!(debugger.session.as_ref().map(|s| &s.state), Some(ExecutionState::Paused { .. }))
+}
+
+#[test]
+fn test_session_stops_at_breakpoint_and_maps_state() {
+    let (mut debugger, log) = debugger_with_mock();
+    let breakpoint = debugger.add_breakpoint(SourceLocation { file: PathBuf::from(SOURCE), line: 3, column: None });
+    assert_eq!(debugger.breakpoints.verification_status.get(&breakpoint), None);
+
+    debugger.start_debug_session(config()).unwrap();
+    assert!(wait_until(&mut debugger, is_paused));
+
+    // Configuration phase happens between `initialized` and `configurationDone`
+    assert_eq!(
+        &commands(&log)[..4],
+        ["initialize", "launch", "setBreakpoints", "configurationDone"]
+    );
+    let launch = log.lock().unwrap().iter().find(|r| r["command"] == "launch").cloned().unwrap();
+    assert_eq!(launch["arguments"]["program"], "/work/demo/target/debug/demo");
+    assert_eq!(launch["arguments"]["args"], json!(["--verbose"]));
+
+    assert_eq!(debugger.breakpoints.verification_status[&breakpoint], VerificationStatus::Verified);
+    assert_eq!(debugger.breakpoints.breakpoints[&breakpoint].hit_count, 1);
+
+    let session = debugger.session.as_ref().unwrap();
+    assert_eq!(session.active_thread, Some(5));
+    assert_eq!(session.threads.len(), 2);
+    assert_eq!(session.threads[1].name, "worker");
+    match &session.state {
+        ExecutionState::Paused { reason, location } => {
+            assert_eq!(reason, &PauseReason::Breakpoint { breakpoint_id: breakpoint });
+            assert_eq!(location.as_ref().unwrap().line, 3);
+        }
+        other => panic!("unexpected state {:?}", other),
+    }
+
+    let frames = &debugger.call_stack.frames;
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].name, "demo::main");
+    assert_eq!(frames[0].location.as_ref().unwrap().file, PathBuf::from(SOURCE));
+    assert_eq!(frames[0].location.as_ref().unwrap().column, Some(5));
+    assert_eq!(frames[1].presentation_hint, Some(FramePresentationHint::Subtle));
+    assert!(frames[1].location.is_none());
+
+    let scopes = &debugger.variable_inspector.scopes;
+    assert_eq!(scopes.len(), 2);
+    assert_eq!(scopes[0].name, "Locals");
+    assert!(matches!(&scopes[0].variables[0].value, VariableValue::Simple(v) if v == "42"));
+    assert_eq!(scopes[0].variables[0].var_type, "i32");
+    assert!(matches!(
+        &scopes[0].variables[1].value,
+        VariableValue::Complex { summary, children: None, child_count: Some(2) } if summary == "size=2"
+    ));
+    // Expensive scopes are not fetched eagerly
+    assert!(scopes[1].expensive && scopes[1].variables.is_empty());
+
+    debugger.load_variable_children(2).unwrap();
+    match &debugger.variable_inspector.scopes[0].variables[1].value {
+        VariableValue::Complex { children: Some(children), .. } => {
+            assert_eq!(children.len(), 2);
+            assert_eq!(children[1].name, "[1]");
+        }
+        other => panic!("children not loaded: {:?}", other),
+    }
+
+    assert!(debugger.console.output.iter().any(|m| m.text == "starting demo"));
+    debugger.stop_debug_session().unwrap();
+    assert!(debugger.session.is_none());
+}
+
+#[test]
+fn test_stepping_continue_and_exit() {
+    let (mut debugger, log) = debugger_with_mock();
+    debugger.add_breakpoint(SourceLocation { file: PathBuf::from(SOURCE), line: 3, column: None });
+    debugger.variable_inspector.add_watch("x * 2".to_string());
+    debugger.variable_inspector.add_watch("bogus".to_string());
+
+    debugger.start_debug_session(config()).unwrap();
+    assert!(wait_until(&mut debugger, is_paused));
+
+    let watches = &debugger.variable_inspector.watches;
+    assert!(matches!(&watches[0].value, Some(VariableValue::Simple(v)) if v == "84"));
+    assert!(!watches[1].valid);
+    assert!(watches[1].error.as_deref().unwrap().contains("undeclared identifier"));
+
+    debugger.step_over().unwrap();
+    assert!(wait_until(&mut debugger, |d| {
+        d.session.as_ref().and_then(|s| s.current_frame.as_ref())
+            .and_then(|f| f.location.as_ref())
+            .map(|l| l.line) == Some(4)
+    }));
+    assert!(matches!(
+        debugger.session.as_ref().unwrap().state,
+        ExecutionState::Paused { reason: PauseReason::Step, .. }
+    ));
+    let next = log.lock().unwrap().iter().find(|r| r["command"] == "next").cloned().unwrap();
+    assert_eq!(next["arguments"]["threadId"], 5);
+
+    debugger.continue_execution().unwrap();
+    assert!(wait_until(&mut debugger, |d| d.session.is_none()));
+    assert!(debugger.console.output.iter().any(|m| m.text == "done" && m.message_type == ConsoleMessageType::Error));
+    assert!(debugger.console.output.iter().any(|m| m.text == "Program exited with code 3."));
+    assert!(debugger.call_stack.frames.is_empty());
+    assert_eq!(commands(&log).last().map(String::as_str), Some("disconnect"));
+}
+
+#[test]
+fn test_pause_targets_a_thread_the_adapter_reports() {
+    let (mut debugger, log) = debugger_with_mock();
+    let mut config = config();
+    config.args.push("--no-stop".to_string());
+    debugger.start_debug_session(config).unwrap();
+
+    // Nothing has stopped yet, so the thread comes from a `threads` request
+    debugger.pause().unwrap();
+    assert!(wait_until(&mut debugger, is_paused));
+    let pause = log.lock().unwrap().iter().find(|r| r["command"] == "pause").cloned().unwrap();
+    assert_eq!(pause["arguments"]["threadId"], 5);
+    assert_eq!(debugger.session.as_ref().unwrap().active_thread, Some(5));
+}
+
+#[test]
+fn test_console_commands_drive_session() {
+    let (mut debugger, log) = debugger_with_mock();
+    debugger.start_debug_session(config()).unwrap();
+    assert!(wait_until(&mut debugger, is_paused));
+
+    assert_eq!(debugger.execute_console_command("print x".to_string()), "42");
+
+    let result = debugger.execute_console_command(format!("break {}:10", SOURCE));
+    assert!(result.contains("verified"), "{}", result);
+    let result = debugger.execute_console_command(format!("b {}:999", SOURCE));
+    assert!(result.contains("failed: no code at line 999"), "{}", result);
+
+    // Each setBreakpoints request carries the full list for the file
+    let last = log.lock().unwrap().iter().rev().find(|r| r["command"] == "setBreakpoints").cloned().unwrap();
+    assert_eq!(last["arguments"]["breakpoints"], json!([{ "line": 10 }, { "line": 999 }]));
+
+    let list = debugger.execute_console_command("list".to_string());
+    assert!(list.contains("main.rs:10 (verified"), "{}", list);
+    assert!(list.contains("main.rs:999 (failed"), "{}", list);
+
+    let ids: Vec<u64> = {
+        let mut ids: Vec<u64> = debugger.breakpoints.breakpoints.keys().copied().collect();
+        ids.sort();
+        ids
+    };
+    debugger.execute_console_command(format!("delete {}", ids[1]));
+    let last = log.lock().unwrap().iter().rev().find(|r| r["command"] == "setBreakpoints").cloned().unwrap();
+    assert_eq!(last["arguments"]["breakpoints"], json!([{ "line": 10 }]));
+
+    let error = debugger.execute_console_command("print bogus".to_string());
+    assert!(error.contains("undeclared identifier"));
+    assert_eq!(debugger.console.output.last().unwrap().message_type, ConsoleMessageType::Error);
+
+    debugger.execute_console_command("continue".to_string());
+    assert!(wait_until(&mut debugger, |d| d.session.is_none()));
+}
+
+#[test]
+fn test_console_without_session() {
+    let mut debugger = Debugger::new();
+    let result = debugger.execute_console_command("break src/lib.rs:12".to_string());
+    assert!(result.contains("pending"), "{}", result);
+    assert_eq!(debugger.breakpoints.breakpoints.len(), 1);
+
+    assert_eq!(debugger.execute_console_command("step".to_string()), "No active debug session.");
+    assert!(debugger.execute_console_command("break nowhere".to_string()).starts_with("Invalid breakpoint location"));
+}
+
+#[test]
+fn test_launch_failure_is_reported() {
+    let mut adapter = DapDebugAdapter::new(DapTransport::Stdio {
+        command: "ide-rs-no-such-debug-adapter".to_string(),
+        args: Vec::new(),
+    });
+    assert!(matches!(adapter.start_session(&config()), Err(DebugError::CommunicationError(_))));
+
+    let mut debugger = Debugger::new();
+    assert!(matches!(
+        debugger.start_debug_session(config()),
+        Err(DebugError::AdapterNotFound(_))
+    ));
+}
+
+#[test]
+fn test_adapter_presets() {
+    assert_eq!(
+        DapTransport::for_adapter_type("gdb"),
+        Some(DapTransport::Stdio { command: "gdb".to_string(), args: vec!["--interpreter=dap".to_string()] })
+    );
+    assert_eq!(DapTransport::for_adapter_type("lldb"), Some(DapTransport::lldb_dap()));
+    assert!(matches!(DapTransport::for_adapter_type("codelldb"), Some(DapTransport::TcpLaunch { .. })));
+    assert_eq!(DapTransport::for_adapter_type("node"), None);
+
+    let debugger = Debugger::new();
+    assert!(debugger.adapters.contains_key("lldb-dap"));
+    assert!(debugger.adapters.contains_key("gdb"));
+}
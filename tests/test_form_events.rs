@@ -0,0 +1,140 @@
+//! Tests for event handler stubs and dispatch in designer forms
+use ide_rs::editor::codegen_markers::{CodeLanguage, CodeRewriter};
+use ide_rs::editor::form_events::*;
+use ide_rs::rcl::ui::basic::button::Button;
+use ide_rs::rcl::ui::basic::checkbox::Checkbox;
+use ide_rs::rcl::ui::component::Component;
+use std::collections::HashMap;
+
+const FORM: &str = "pub struct MainForm {
+    pub ok: Button,
+}
+
+impl MainForm {
+    // <codegen:generated:event_dispatch:start>
+    // <codegen:generated:event_dispatch:end>
+}
+
+impl MainForm {
+    // <codegen:guard:event_handlers:start>
+    // <codegen:guard:event_handlers:end>
+}
+";
+
+#[test]
+fn test_ensure_handler_creates_then_jumps() {
+    let (source, location) = ensure_handler(FORM, "MainForm", "ok_click").unwrap();
+    assert!(location.created);
+    assert_eq!(source.lines().nth(location.line), Some("    pub fn ok_click(&mut self) {"));
+    assert!(source.contains("    // <codegen:guard:event_handlers:start>\n    pub fn ok_click(&mut self) {\n    }\n    // <codegen:guard:event_handlers:end>\n"));
+
+    let (second, location) = ensure_handler(&source, "MainForm", "ok_focus").unwrap();
+    assert!(location.created);
+    assert!(second.contains("    pub fn ok_click(&mut self) {\n    }\n\n    pub fn ok_focus(&mut self) {\n    }\n"));
+
+    let (unchanged, location) = ensure_handler(&second, "MainForm", "ok_click").unwrap();
+    assert!(!location.created);
+    assert_eq!(unchanged, second);
+    assert_eq!(unchanged.lines().nth(location.line), Some("    pub fn ok_click(&mut self) {"));
+}
+
+#[test]
+fn test_ensure_handler_finds_moved_handlers_and_rejects_bad_names() {
+    let source = format!("{}\nimpl MainForm {{\n    fn ok_click (&mut self) {{}}\n}}\n", FORM);
+    let (unchanged, location) = ensure_handler(&source, "MainForm", "ok_click").unwrap();
+    assert!(!location.created);
+    assert_eq!(unchanged, source);
+    assert!(find_handler("fn book_click() {}", "ok_click").is_none());
+
+    assert!(ensure_handler(FORM, "MainForm", "ok click").is_err());
+    assert!(ensure_handler(FORM, "MainForm", "1st").is_err());
+    assert!(ensure_handler("// <codegen:guard:event_handlers:start>\n", "MainForm", "ok_click").is_err());
+}
+
+#[test]
+fn test_ensure_handler_adds_missing_guard() {
+    let (source, location) = ensure_handler("pub struct MainForm;", "MainForm", "form_load").unwrap();
+    assert!(location.created);
+    assert_eq!(
+        source,
+        "pub struct MainForm;\n\nimpl MainForm {\n    // <codegen:guard:event_handlers:start>\n    pub fn form_load(&mut self) {\n    }\n    // <codegen:guard:event_handlers:end>\n}\n"
+    );
+}
+
+#[test]
+fn test_dispatch_code_routes_events_to_handlers() {
+    let mut properties = HashMap::from([
+        ("label".to_string(), "OK".to_string()),
+        ("on_click".to_string(), "ok_click".to_string()),
+        ("on_focus".to_string(), " ".to_string()),
+    ]);
+    let bindings = EventBinding::from_properties("okButton", &properties);
+    assert_eq!(
+        bindings,
+        vec![EventBinding { component: "okButton".to_string(), event: "click".to_string(), handler: "ok_click".to_string() }]
+    );
+
+    let source = update_dispatch(FORM, &bindings).unwrap();
+    assert!(source.contains(
+        "    // <codegen:generated:event_dispatch:start>
+    /// Route the events raised by components to their handlers
+    pub fn dispatch_events(&mut self) {
+        for event in self.ok_button.take_events() {
+            match event.as_str() {
+                \"click\" => self.ok_click(),
+                _ => {}
+            }
+        }
+    }
+    // <codegen:generated:event_dispatch:end>"
+    ));
+
+    // Regenerating replaces the section instead of stacking another one
+    properties.clear();
+    let cleared = update_dispatch(&source, &EventBinding::from_properties("okButton", &properties)).unwrap();
+    assert!(cleared.contains("    pub fn dispatch_events(&mut self) {}\n    // <codegen:generated:event_dispatch:end>"));
+    assert_eq!(cleared.matches("dispatch_events").count(), 1);
+}
+
+#[test]
+fn test_preserve_guards_keeps_user_code_and_indentation() {
+    let (edited, _) = ensure_handler(FORM, "MainForm", "ok_click").unwrap();
+    let edited = edited.replace("ok_click(&mut self) {\n", "ok_click(&mut self) {\n        self.close();\n");
+
+    let regenerated = CodeRewriter::new(CodeLanguage::Rust, FORM.replace("pub ok: Button", "pub ok: Button,\n    pub cancel: Button"))
+        .preserve_guards(&edited)
+        .unwrap();
+    assert!(regenerated.contains("    pub cancel: Button"));
+    assert!(regenerated.contains("    // <codegen:guard:event_handlers:start>\n    pub fn ok_click(&mut self) {\n        self.close();\n    }\n    // <codegen:guard:event_handlers:end>\n"));
+}
+
+#[test]
+fn test_handler_names() {
+    assert_eq!(default_handler_name("button_0", "click"), "button_0_click");
+    assert_eq!(default_handler_name("okButton", "Click"), "ok_button_click");
+    assert_eq!(designer_component_id("Button", 2), "button_2");
+    assert_eq!(handler_property("change"), "on_change");
+    assert_eq!(form_type_name("my-app"), "MyAppForm");
+    assert!(is_valid_handler_name("_private"));
+    assert!(!is_valid_handler_name("_"));
+}
+
+#[test]
+fn test_components_raise_bound_events() {
+    let mut button = Button::new("OK".to_string());
+    assert_eq!(button.events()[0].name, "click");
+    assert!(button.set_property("on_click", "ok_click"));
+    assert_eq!(button.get_property("on_click"), Some("ok_click".to_string()));
+    assert!(button.get_property_names().contains(&"on_click".to_string()));
+
+    assert!(button.take_events().is_empty());
+    button.click();
+    button.click();
+    assert_eq!(button.take_events(), vec!["click".to_string(), "click".to_string()]);
+    assert!(button.take_events().is_empty());
+
+    let mut checkbox = Checkbox::new("Remember me".to_string(), false);
+    assert_eq!(checkbox.events()[0].name, "change");
+    assert!(checkbox.set_property("on_change", "remember_changed"));
+    assert_eq!(checkbox.on_change, "remember_changed");
+}
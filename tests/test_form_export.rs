@@ -0,0 +1,120 @@
+//! Tests for headless SVG and PNG export of designer forms
+
+use ide_rs::shared::form_export::FormRenderer;
+use ide_rs::shared::serialization::*;
+use std::collections::HashMap;
+
+fn component(component_type: &str, id: &str, properties: Vec<(&str, PropertyValue)>) -> ComponentData {
+    ComponentData {
+        component_type: component_type.to_string(),
+        id: id.to_string(),
+        properties: properties.into_iter().map(|(k, v)| (k.to_string(), v)).collect::<HashMap<_, _>>(),
+        children: Vec::new(),
+        metadata: ComponentMetadata::default(),
+        schema_version: CURRENT_SCHEMA_VERSION.to_string(),
+    }
+}
+
+fn login_form() -> ComponentData {
+    let mut form = component("form", "login", vec![
+        ("padding", PropertyValue::Number(10.0)),
+        ("spacing", PropertyValue::Number(5.0)),
+        ("background_color", PropertyValue::Color([240, 240, 240, 255])),
+    ]);
+    form.children = vec![
+        component("label", "caption", vec![("text", PropertyValue::String("User <name> & \"id\"".to_string()))]),
+        component("input", "username", vec![
+            ("placeholder", PropertyValue::String("User name".to_string())),
+            ("width", PropertyValue::Number(180.0)),
+            ("height", PropertyValue::Number(30.0)),
+        ]),
+        component("button", "submit", vec![
+            ("text", PropertyValue::String("Sign in".to_string())),
+            ("width", PropertyValue::Number(100.0)),
+            ("height", PropertyValue::Number(40.0)),
+            ("background_color", PropertyValue::String("#3366ff".to_string())),
+            ("border_width", PropertyValue::Number(0.0)),
+        ]),
+        component("button", "hidden", vec![("visible", PropertyValue::Boolean(false))]),
+    ];
+    form
+}
+
+#[test]
+fn test_svg_lays_out_children_vertically_with_padding_and_spacing() {
+    let svg = FormRenderer::new().render_svg(&login_form());
+
+    // label: 14px * 1.4 = 19.6 high; input 30; button 40; two gaps of 5; padding 10 on each side
+    // (the hidden button takes no space)
+    assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="119.6""#), "{}", svg);
+    assert!(svg.contains(r#"<g id="submit" class="button">"#));
+    assert!(svg.contains(r##"<rect x="10" y="69.6" width="100" height="40" rx="3" fill="#3366ff"/>"##), "{}", svg);
+    assert!(!svg.contains("hidden"));
+}
+
+#[test]
+fn test_svg_renders_text_colors_and_escapes_markup() {
+    let svg = FormRenderer::new().render_svg(&login_form());
+
+    assert!(svg.contains("User &lt;name&gt; &amp; &quot;id&quot;"));
+    assert!(svg.contains(r##"fill="#969696">User name</text>"##), "placeholder should be drawn in gray");
+    assert!(svg.contains(r#"text-anchor="middle">Sign in</text>"#));
+    assert!(svg.contains(r##"fill="#f0f0f0""##));
+}
+
+#[test]
+fn test_absolute_layout_uses_child_positions() {
+    let mut panel = component("panel", "canvas", vec![
+        ("layout", PropertyValue::String("absolute".to_string())),
+        ("padding", PropertyValue::Number(0.0)),
+        ("border_color", PropertyValue::Color([0, 0, 0, 128])),
+        ("border_width", PropertyValue::Number(2.0)),
+    ]);
+    panel.children = vec![component("image", "logo", vec![
+        ("x", PropertyValue::Number(50.0)),
+        ("y", PropertyValue::Number(20.0)),
+    ])];
+
+    let svg = FormRenderer::new().render_svg(&panel);
+    assert!(svg.contains(r#"width="114" height="84""#), "{}", svg);
+    assert!(svg.contains(r#"<rect x="50.5" y="20.5" width="63" height="63" rx="3""#), "{}", svg);
+    assert!(svg.contains(r#"stroke-width="2" stroke-opacity="0.5""#));
+}
+
+#[test]
+fn test_png_export_rasterizes_at_scale() {
+    let form = login_form();
+    let renderer = FormRenderer { scale: 2.0, ..FormRenderer::new() };
+    let png = renderer.render_png(&form).unwrap();
+
+    let pixmap = resvg::tiny_skia::Pixmap::decode_png(&png).unwrap();
+    assert_eq!((pixmap.width(), pixmap.height()), (400, 240));
+
+    // Corner of the button, away from its label and rounded edge
+    let pixel = pixmap.pixel(2 * 14, 2 * 73).unwrap();
+    assert_eq!((pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()), (0x33, 0x66, 0xff, 255));
+    let background = pixmap.pixel(2 * 190, 2).unwrap();
+    assert_eq!((background.red(), background.green(), background.blue()), (240, 240, 240));
+}
+
+#[test]
+fn test_export_component_routes_image_formats() {
+    let form = login_form();
+
+    let svg = SerializationUtils::export_component(&form, &ExportFormat::Svg).unwrap();
+    assert!(svg.contains("<svg"));
+
+    assert!(matches!(
+        SerializationUtils::export_component(&form, &ExportFormat::Png),
+        Err(SerializationError::UnsupportedFormat { format: ExportFormat::Png })
+    ));
+    let png = SerializationUtils::export_component_bytes(&form, &ExportFormat::Png).unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+    let html = SerializationUtils::export_component_bytes(&form, &ExportFormat::HtmlCss).unwrap();
+    assert!(String::from_utf8(html).unwrap().starts_with("<div"));
+    assert!(matches!(
+        SerializationUtils::export_component_bytes(&form, &ExportFormat::Pdf),
+        Err(SerializationError::UnsupportedFormat { .. })
+    ));
+}
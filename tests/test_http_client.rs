@@ -1,10 +1,302 @@
-//! Integration test for HttpClient
-use ide_rs::rcl::network::http_client::HttpClient;
+//! Integration tests for HttpClient against a local stub server
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use ide_rs::rcl::network::http_client::{
+    DownloadProgress, HttpClient, HttpClientConfig, HttpMethod, MultipartForm, RetryPolicy,
+};
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// A request as seen by the stub server
+#[derive(Debug, Clone)]
+struct StubRequest {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+type Handler = dyn Fn(&StubRequest, usize) -> Vec<u8> + Send + Sync;
+
+/// Serve `handler` on localhost; it gets each request and how many came before it
+async fn stub_server(handler: impl Fn(&StubRequest, usize) -> Vec<u8> + Send + Sync + 'static) -> (String, Arc<Mutex<Vec<StubRequest>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let handler: Arc<Handler> = Arc::new(handler);
+    let recorded = requests.clone();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            let handler = handler.clone();
+            let recorded = recorded.clone();
+            tokio::spawn(async move {
+                if let Some(request) = read_request(socket).await {
+                    let (mut socket, request) = request;
+                    let index = {
+                        let mut recorded = recorded.lock().unwrap();
+                        recorded.push(request.clone());
+                        recorded.len() - 1
+                    };
+                    let response = handler(&request, index);
+                    let _ = socket.write_all(&response).await;
+                    let _ = socket.shutdown().await;
+                }
+            });
+        }
+    });
+    (base, requests)
+}
+
+async fn read_request(mut socket: TcpStream) -> Option<(TcpStream, StubRequest)> {
+    let mut data = Vec::new();
+    let mut buffer = [0u8; 4096];
+    let header_end = loop {
+        let read = socket.read(&mut buffer).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        data.extend_from_slice(&buffer[..read]);
+        if let Some(end) = data.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&data[..header_end]).into_owned();
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let headers: HashMap<String, String> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let length: usize = headers.get("content-length").and_then(|value| value.parse().ok()).unwrap_or(0);
+    let mut body = data[header_end..].to_vec();
+    while body.len() < length {
+        let read = socket.read(&mut buffer).await.ok()?;
+        if read == 0 {
+            break;
+        }
+        body.extend_from_slice(&buffer[..read]);
+    }
+    Some((socket, StubRequest { method, path, headers, body }))
+}
+
+fn response(status: &str, headers: &[(&str, &str)], body: &[u8]) -> Vec<u8> {
+    let mut response = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n", status, body.len());
+    for (name, value) in headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str("\r\n");
+    let mut response = response.into_bytes();
+    response.extend_from_slice(body);
+    response
+}
+
+fn fast_config() -> HttpClientConfig {
+    HttpClientConfig {
+        timeout: Duration::from_secs(5),
+        retry: RetryPolicy {
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+            ..RetryPolicy::default()
+        },
+        ..HttpClientConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn test_http_client_get_sends_configured_headers() {
+    let (base, requests) = stub_server(|_, _| {
+        response("200 OK", &[("Content-Type", "text/plain"), ("X-Trace", "a"), ("X-Trace", "b")], b"hello")
+    }).await;
+    let mut client = HttpClient::with_config(fast_config());
+    client.set_user_agent("TestAgent/2.0");
+    client.set_auth_token("secret");
+    client.add_default_header("X-Api-Key", "key");
+
+    assert_eq!(client.get(&format!("{}/greeting", base)).await.unwrap(), "hello");
+
+    let request = requests.lock().unwrap()[0].clone();
+    assert_eq!(request.method, "GET");
+    assert_eq!(request.path, "/greeting");
+    assert_eq!(request.headers["user-agent"], "TestAgent/2.0");
+    assert_eq!(request.headers["authorization"], "Bearer secret");
+    assert_eq!(request.headers["x-api-key"], "key");
+
+    let detailed = client.request_detailed(HttpMethod::Get, &format!("{}/greeting", base), None, None).await.unwrap();
+    assert_eq!(detailed.status_code, 200);
+    assert_eq!(detailed.status_text, "OK");
+    assert!(detailed.success);
+    assert_eq!(detailed.headers["content-type"], "text/plain");
+    assert_eq!(detailed.headers["x-trace"], "a, b");
+    assert_eq!(detailed.content_length, Some(5));
+    assert_eq!(detailed.url, format!("{}/greeting", base));
+    assert_eq!(detailed.attempts, 1);
+}
+
+#[tokio::test]
+async fn test_http_client_request_bodies_and_error_status() {
+    let (base, requests) = stub_server(|request, _| match request.path.as_str() {
+        "/missing" => response("404 Not Found", &[], b"nope"),
+        _ => response("201 Created", &[], &request.body),
+    }).await;
+    let client = HttpClient::with_config(fast_config());
+
+    assert_eq!(client.post(&format!("{}/items", base), "{\"a\":1}").await.unwrap(), "{\"a\":1}");
+    assert_eq!(client.put(&format!("{}/items/1", base), "put").await.unwrap(), "put");
+    assert_eq!(client.patch(&format!("{}/items/1", base), "patch").await.unwrap(), "patch");
+    assert_eq!(client.delete(&format!("{}/items/1", base)).await.unwrap(), "");
+    let methods: Vec<String> = requests.lock().unwrap().iter().map(|request| request.method.clone()).collect();
+    assert_eq!(methods, ["POST", "PUT", "PATCH", "DELETE"]);
+
+    let error = client.get(&format!("{}/missing", base)).await.unwrap_err();
+    assert!(error.to_string().contains("404"));
+    let detailed = client.request_detailed(HttpMethod::Get, &format!("{}/missing", base), None, None).await.unwrap();
+    assert!(!detailed.success);
+    assert_eq!(detailed.status_text, "Not Found");
+    assert_eq!(detailed.body, "nope");
+
+    assert!(client.get("ftp://example.com/file").await.is_err());
+}
 
 #[tokio::test]
-async fn test_http_client_get() {
-    let client = HttpClient::new();
-    let result = client.get("http://example.com").await;
-    assert!(result.is_ok());
-    assert!(result.unwrap().contains("GET request"));
+async fn test_http_client_retries_idempotent_requests() {
+    let (base, requests) = stub_server(|_, index| {
+        if index < 2 {
+            response("503 Service Unavailable", &[("Retry-After", "0")], b"busy")
+        } else {
+            response("200 OK", &[], b"ready")
+        }
+    }).await;
+    let client = HttpClient::with_config(fast_config());
+
+    let detailed = client.request_detailed(HttpMethod::Get, &format!("{}/", base), None, None).await.unwrap();
+    assert_eq!(detailed.body, "ready");
+    assert_eq!(detailed.attempts, 3);
+    assert_eq!(requests.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_http_client_does_not_retry_post_after_response() {
+    let (base, requests) = stub_server(|_, _| response("503 Service Unavailable", &[], b"busy")).await;
+    let client = HttpClient::with_config(fast_config());
+
+    assert!(client.post(&format!("{}/", base), "data").await.is_err());
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_http_client_timeout() {
+    // Accept connections but never answer
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let mut open = Vec::new();
+        while let Ok((socket, _)) = listener.accept().await {
+            open.push(socket);
+        }
+    });
+    let mut client = HttpClient::with_config(fast_config());
+    client.set_timeout(Duration::from_millis(100));
+    client.set_retry_policy(RetryPolicy::none());
+
+    let error = client.get(&format!("{}/", base)).await.unwrap_err();
+    assert!(error.to_string().contains("timed out"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_http_client_cookie_jar() {
+    let (base, requests) = stub_server(|request, _| match request.path.as_str() {
+        "/login" => response("200 OK", &[("Set-Cookie", "session=abc; Path=/")], b"welcome"),
+        _ => response("200 OK", &[], b"ok"),
+    }).await;
+    let mut client = HttpClient::with_config(fast_config());
+
+    client.get(&format!("{}/login", base)).await.unwrap();
+    client.get(&format!("{}/profile", base)).await.unwrap();
+    assert_eq!(requests.lock().unwrap()[1].headers["cookie"], "session=abc");
+    assert_eq!(client.cookies(&base).as_deref(), Some("session=abc"));
+
+    client.clear_cookies();
+    client.add_cookie(&base, "theme=dark");
+    client.get(&format!("{}/profile", base)).await.unwrap();
+    assert_eq!(requests.lock().unwrap()[2].headers["cookie"], "theme=dark");
+
+    let cookieless = HttpClient::with_config(HttpClientConfig { enable_cookies: false, ..fast_config() });
+    cookieless.get(&format!("{}/login", base)).await.unwrap();
+    assert!(cookieless.cookies(&base).is_none());
+}
+
+#[tokio::test]
+async fn test_http_client_multipart_upload() {
+    let (base, requests) = stub_server(|_, _| response("200 OK", &[], b"stored")).await;
+    let client = HttpClient::with_config(fast_config());
+    let form = MultipartForm::new()
+        .text("title", "Report")
+        .file("attachment", "report.txt", b"file contents".to_vec(), "text/plain");
+
+    let uploaded = client.upload_multipart(&format!("{}/upload", base), &form).await.unwrap();
+    assert_eq!(uploaded.body, "stored");
+
+    let request = requests.lock().unwrap()[0].clone();
+    assert!(request.headers["content-type"].starts_with("multipart/form-data; boundary="));
+    let body = String::from_utf8(request.body).unwrap();
+    assert!(body.contains("name=\"title\"\r\n\r\nReport"));
+    assert!(body.contains("filename=\"report.txt\""));
+    assert!(body.contains("Content-Type: text/plain\r\n\r\nfile contents"));
+}
+
+#[tokio::test]
+async fn test_http_client_streams_download_with_progress() {
+    let payload: Vec<u8> = (0..300_000u32).map(|i| (i % 251) as u8).collect();
+    let served = payload.clone();
+    let (base, _) = stub_server(move |request, _| match request.path.as_str() {
+        "/missing" => response("404 Not Found", &[], b""),
+        _ => response("200 OK", &[("Content-Type", "application/octet-stream")], &served),
+    }).await;
+    let client = HttpClient::with_config(fast_config());
+    let dir = TempDir::new().unwrap();
+    let destination = dir.path().join("data.bin");
+
+    let mut updates: Vec<DownloadProgress> = Vec::new();
+    let downloaded = client.download_to_file(&format!("{}/data.bin", base), &destination, |progress| updates.push(progress))
+        .await
+        .unwrap();
+    assert_eq!(downloaded.content_length, Some(payload.len() as u64));
+    assert_eq!(std::fs::read(&destination).unwrap(), payload);
+    assert!(updates.len() > 1);
+    assert!(updates.windows(2).all(|pair| pair[0].downloaded < pair[1].downloaded));
+    assert_eq!(updates.last().unwrap(), &DownloadProgress { downloaded: payload.len() as u64, total: Some(payload.len() as u64) });
+
+    // Aborting from the callback stops the download
+    let mut received = 0;
+    let aborted = client.download_stream(&format!("{}/data.bin", base), |chunk, _| {
+        received += chunk.len();
+        Err(anyhow::Error::msg("enough"))
+    }).await;
+    assert!(aborted.is_err());
+    assert!(received < payload.len());
+
+    let missing = dir.path().join("missing.bin");
+    assert!(client.download_to_file(&format!("{}/missing", base), &missing, |_| {}).await.is_err());
+    assert!(!missing.exists());
+}
+
+#[tokio::test]
+async fn test_http_client_decompresses_gzip() {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(b"compressed body").unwrap();
+    let compressed = encoder.finish().unwrap();
+    let (base, requests) = stub_server(move |_, _| response("200 OK", &[("Content-Encoding", "gzip")], &compressed)).await;
+    let client = HttpClient::with_config(fast_config());
+
+    assert_eq!(client.get(&format!("{}/", base)).await.unwrap(), "compressed body");
+    assert!(requests.lock().unwrap()[0].headers["accept-encoding"].contains("gzip"));
 }
@@ -0,0 +1,412 @@
+//! Tests for the flex and grid layout engine
+use egui::{pos2, vec2, Rect, Vec2};
+use ide_rs::editor::layout_engine::*;
+use ide_rs::editor::project_manager::project::{ComponentData, LayoutType as DesignerLayout, ProjectType, VerticalAlign};
+use ide_rs::editor::project_manager::IdeProject;
+use ide_rs::editor::scene_store::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use ide_rs::rcl::component_registry::PropertyValue;
+
+fn flex(direction: FlexDirection, justify_content: JustifyContent, align_items: AlignItems, wrap: FlexWrap, gap: f32) -> LayoutType {
+    LayoutType::Flex(FlexLayout { direction, justify_content, align_items, wrap, gap })
+}
+
+fn grid(columns: Vec<GridTrack>, rows: Vec<GridTrack>, row_gap: f32, column_gap: f32, auto_flow: GridAutoFlow) -> LayoutType {
+    LayoutType::Grid(GridLayout { columns, rows, gap: GridGap { row: row_gap, column: column_gap }, auto_flow })
+}
+
+fn item(key: &'static str, width: f32, height: f32) -> LayoutNode<&'static str> {
+    LayoutNode::new(key, vec2(width, height))
+}
+
+fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect {
+    Rect::from_min_size(pos2(x, y), vec2(width, height))
+}
+
+fn viewport(width: f32, height: f32) -> Rect {
+    rect(0.0, 0.0, width, height)
+}
+
+fn assert_rect(actual: Rect, expected: Rect) {
+    let close = (actual.min - expected.min).length() < 0.01 && (actual.max - expected.max).length() < 0.01;
+    assert!(close, "expected {:?}, got {:?}", expected, actual);
+}
+
+fn minmax(min: GridTrack, max: GridTrack) -> GridTrack {
+    GridTrack::Minmax(Box::new(min), Box::new(max))
+}
+
+#[test]
+fn test_flex_grow_respects_max_size() {
+    let mut a = item("a", 50.0, 20.0);
+    a.flex_grow = 1.0;
+    let mut b = item("b", 50.0, 40.0);
+    b.flex_grow = 2.0;
+    b.constraints.max_size = Some(Size { width: 100.0, height: 60.0 });
+    let root = item("root", 0.0, 0.0)
+        .with_layout(flex(FlexDirection::Row, JustifyContent::FlexStart, AlignItems::Stretch, FlexWrap::NoWrap, 10.0))
+        .with_child(a)
+        .with_child(b)
+        .with_child(item("c", 50.0, 20.0));
+
+    let rects = solve(&root, viewport(300.0, 100.0));
+    assert_rect(rects["root"], viewport(300.0, 100.0));
+    // b stops at its max width and a takes the rest of the free space
+    assert_rect(rects["a"], rect(0.0, 0.0, 130.0, 100.0));
+    assert_rect(rects["b"], rect(140.0, 0.0, 100.0, 60.0));
+    assert_rect(rects["c"], rect(250.0, 0.0, 50.0, 100.0));
+}
+
+#[test]
+fn test_flex_shrink_respects_min_size() {
+    let mut b = item("b", 150.0, 10.0);
+    b.constraints.min_size = Some(Size { width: 120.0, height: 0.0 });
+    let mut fixed = item("fixed", 40.0, 10.0);
+    fixed.flex_shrink = 0.0;
+    let root = item("root", 0.0, 0.0)
+        .with_layout(flex(FlexDirection::Row, JustifyContent::FlexStart, AlignItems::FlexStart, FlexWrap::NoWrap, 0.0))
+        .with_child(item("a", 150.0, 10.0))
+        .with_child(b)
+        .with_child(fixed);
+
+    let rects = solve(&root, viewport(240.0, 50.0));
+    assert_rect(rects["a"], rect(0.0, 0.0, 80.0, 10.0));
+    assert_rect(rects["b"], rect(80.0, 0.0, 120.0, 10.0));
+    assert_rect(rects["fixed"], rect(200.0, 0.0, 40.0, 10.0));
+}
+
+#[test]
+fn test_flex_justify_and_align() {
+    let row = |justify: JustifyContent, align: AlignItems, direction: FlexDirection| {
+        let root = item("root", 0.0, 0.0)
+            .with_layout(flex(direction, justify, align, FlexWrap::NoWrap, 0.0))
+            .with_child(item("a", 50.0, 20.0))
+            .with_child(item("b", 50.0, 20.0))
+            .with_child(item("c", 50.0, 20.0));
+        let rects = solve(&root, viewport(300.0, 100.0));
+        ["a", "b", "c"].map(|key| rects[key].min)
+    };
+
+    let positions = row(JustifyContent::SpaceBetween, AlignItems::FlexStart, FlexDirection::Row);
+    assert_eq!(positions, [pos2(0.0, 0.0), pos2(125.0, 0.0), pos2(250.0, 0.0)]);
+    let positions = row(JustifyContent::SpaceEvenly, AlignItems::Center, FlexDirection::Row);
+    assert_eq!(positions, [pos2(37.5, 40.0), pos2(125.0, 40.0), pos2(212.5, 40.0)]);
+    let positions = row(JustifyContent::SpaceAround, AlignItems::FlexEnd, FlexDirection::Row);
+    assert_eq!(positions, [pos2(25.0, 80.0), pos2(125.0, 80.0), pos2(225.0, 80.0)]);
+    let positions = row(JustifyContent::Center, AlignItems::FlexStart, FlexDirection::Row);
+    assert_eq!(positions, [pos2(75.0, 0.0), pos2(125.0, 0.0), pos2(175.0, 0.0)]);
+    let positions = row(JustifyContent::FlexStart, AlignItems::FlexStart, FlexDirection::RowReverse);
+    assert_eq!(positions, [pos2(250.0, 0.0), pos2(200.0, 0.0), pos2(150.0, 0.0)]);
+    let positions = row(JustifyContent::FlexEnd, AlignItems::FlexStart, FlexDirection::Column);
+    assert_eq!(positions, [pos2(0.0, 40.0), pos2(0.0, 60.0), pos2(0.0, 80.0)]);
+}
+
+#[test]
+fn test_flex_wrap_onto_lines() {
+    let wrapped = |wrap: FlexWrap| {
+        let mut root = item("root", 0.0, 0.0).with_layout(flex(FlexDirection::Row, JustifyContent::FlexStart, AlignItems::Stretch, wrap, 10.0));
+        for key in ["a", "b", "c", "d", "e"] {
+            root = root.with_child(item(key, 100.0, 30.0));
+        }
+        solve(&root, viewport(250.0, 200.0))
+    };
+
+    // Three lines of 30 share the 90 spare pixels and items stretch to the line
+    let rects = wrapped(FlexWrap::Wrap);
+    assert_rect(rects["a"], rect(0.0, 0.0, 100.0, 60.0));
+    assert_rect(rects["b"], rect(110.0, 0.0, 100.0, 60.0));
+    assert_rect(rects["c"], rect(0.0, 70.0, 100.0, 60.0));
+    assert_rect(rects["e"], rect(0.0, 140.0, 100.0, 60.0));
+
+    let rects = wrapped(FlexWrap::WrapReverse);
+    assert_rect(rects["a"], rect(0.0, 140.0, 100.0, 60.0));
+    assert_rect(rects["e"], rect(0.0, 0.0, 100.0, 60.0));
+
+    // Without wrapping the items shrink to share one line
+    let rects = wrapped(FlexWrap::NoWrap);
+    assert_rect(rects["a"], rect(0.0, 0.0, 42.0, 200.0));
+    assert_rect(rects["e"], rect(208.0, 0.0, 42.0, 200.0));
+}
+
+#[test]
+fn test_grid_fr_auto_and_gaps() {
+    let root = item("root", 0.0, 0.0)
+        .with_layout(grid(
+            vec![GridTrack::Px(100.0), GridTrack::Fr(1.0), GridTrack::Fr(3.0)],
+            vec![GridTrack::Auto, GridTrack::Fr(1.0)],
+            10.0,
+            20.0,
+            GridAutoFlow::Row,
+        ))
+        .with_child(item("a", 50.0, 40.0))
+        .with_child(item("b", 50.0, 40.0))
+        .with_child(item("c", 50.0, 60.0))
+        .with_child(item("d", 50.0, 50.0));
+
+    let rects = solve(&root, viewport(460.0, 300.0));
+    // The auto row fits its tallest item and the fr row takes the rest
+    assert_rect(rects["a"], rect(0.0, 0.0, 100.0, 60.0));
+    assert_rect(rects["b"], rect(120.0, 0.0, 80.0, 60.0));
+    assert_rect(rects["c"], rect(220.0, 0.0, 240.0, 60.0));
+    assert_rect(rects["d"], rect(0.0, 70.0, 100.0, 230.0));
+}
+
+#[test]
+fn test_grid_minmax_and_content_tracks() {
+    // A track whose minimum exceeds its fr share keeps its minimum
+    let root = item("root", 0.0, 0.0)
+        .with_layout(grid(vec![minmax(GridTrack::Px(150.0), GridTrack::Fr(1.0)), GridTrack::Fr(1.0), GridTrack::Fr(1.0)], vec![], 0.0, 0.0, GridAutoFlow::Row))
+        .with_child(item("a", 10.0, 10.0))
+        .with_child(item("b", 10.0, 10.0))
+        .with_child(item("c", 10.0, 10.0));
+    let rects = solve(&root, viewport(300.0, 100.0));
+    assert_eq!([rects["a"].width(), rects["b"].width(), rects["c"].width()], [150.0, 75.0, 75.0]);
+    assert_eq!(rects["c"].min.x, 225.0);
+
+    // Content tracks grow to their items, then auto tracks take what is left
+    let mut narrow = item("c", 10.0, 10.0);
+    narrow.constraints.max_size = Some(Size { width: 30.0, height: 100.0 });
+    let mut smallest = item("d", 200.0, 10.0);
+    smallest.constraints.min_size = Some(Size { width: 70.0, height: 0.0 });
+    let root = item("root", 0.0, 0.0)
+        .with_layout(grid(
+            vec![GridTrack::Auto, minmax(GridTrack::Px(50.0), GridTrack::MaxContent), GridTrack::Px(40.0), GridTrack::MinContent],
+            vec![],
+            0.0,
+            0.0,
+            GridAutoFlow::Row,
+        ))
+        .with_child(item("a", 80.0, 10.0))
+        .with_child(item("b", 120.0, 10.0))
+        .with_child(narrow)
+        .with_child(smallest);
+    let rects = solve(&root, viewport(400.0, 100.0));
+    assert_eq!(["a", "b", "c", "d"].map(|key| rects[key].width()), [170.0, 120.0, 30.0, 70.0]);
+
+    // Items spanning several tracks spread their minimum over them
+    let mut wide = item("wide", 10.0, 10.0);
+    wide.grid.column_span = 2;
+    wide.constraints.min_size = Some(Size { width: 300.0, height: 0.0 });
+    let root = item("root", 0.0, 0.0)
+        .with_layout(grid(vec![GridTrack::Auto, GridTrack::Auto], vec![], 0.0, 0.0, GridAutoFlow::Row))
+        .with_child(wide)
+        .with_child(item("next", 10.0, 10.0));
+    let rects = solve(&root, viewport(100.0, 100.0));
+    assert_eq!(rects["wide"].width(), 300.0);
+    assert_rect(rects["next"], rect(0.0, 50.0, 150.0, 50.0));
+}
+
+#[test]
+fn test_grid_placement_and_dense_flow() {
+    let placed = |auto_flow: GridAutoFlow| {
+        let mut explicit = item("explicit", 50.0, 50.0);
+        explicit.grid.row = Some(0);
+        explicit.grid.column = Some(0);
+        let mut wide = item("wide", 50.0, 50.0);
+        wide.grid.column_span = 3;
+        let root = item("root", 0.0, 0.0)
+            .with_layout(grid(vec![GridTrack::Px(50.0); 3], vec![], 0.0, 0.0, auto_flow))
+            .with_child(wide)
+            .with_child(item("small", 50.0, 50.0))
+            .with_child(explicit);
+        solve(&root, viewport(150.0, 300.0))
+    };
+
+    // Sparse flow never goes back, so the small item lands below the wide one
+    let rects = placed(GridAutoFlow::Row);
+    assert_rect(rects["explicit"], rect(0.0, 0.0, 50.0, 100.0));
+    assert_rect(rects["wide"], rect(0.0, 100.0, 150.0, 100.0));
+    assert_rect(rects["small"], rect(0.0, 200.0, 50.0, 100.0));
+
+    // Dense flow fills the hole next to the explicit item
+    let rects = placed(GridAutoFlow::RowDense);
+    assert_rect(rects["small"], rect(50.0, 0.0, 50.0, 150.0));
+    assert_rect(rects["wide"], rect(0.0, 150.0, 150.0, 150.0));
+
+    // Column flow fills down the explicit rows first
+    let root = item("root", 0.0, 0.0)
+        .with_layout(grid(vec![], vec![GridTrack::Px(20.0); 2], 0.0, 0.0, GridAutoFlow::Column))
+        .with_child(item("a", 30.0, 20.0))
+        .with_child(item("b", 30.0, 20.0))
+        .with_child(item("c", 30.0, 20.0));
+    let rects = solve(&root, viewport(60.0, 40.0));
+    assert_eq!(["a", "b", "c"].map(|key| rects[key].min), [pos2(0.0, 0.0), pos2(0.0, 20.0), pos2(30.0, 0.0)]);
+}
+
+#[test]
+fn test_nested_containers_and_positioning() {
+    let mut body = item("body", 0.0, 0.0).with_layout(flex(FlexDirection::Row, JustifyContent::FlexStart, AlignItems::Stretch, FlexWrap::NoWrap, 0.0));
+    body.flex_grow = 1.0;
+    for key in ["left", "right"] {
+        let mut column = item(key, 0.0, 0.0);
+        column.flex_grow = 1.0;
+        body = body.with_child(column);
+    }
+    let mut nudged = item("nudged", 20.0, 20.0).with_layout(LayoutType::Relative);
+    nudged.position = vec2(5.0, 5.0);
+    let mut toast = item("toast", 100.0, 20.0).with_layout(LayoutType::Fixed);
+    toast.constraints.anchors.right = Some(10.0);
+    toast.constraints.anchors.bottom = Some(10.0);
+    let header = item("header", 0.0, 50.0).with_child(nudged).with_child(toast);
+
+    let root = item("root", 0.0, 0.0)
+        .with_layout(flex(FlexDirection::Column, JustifyContent::FlexStart, AlignItems::Stretch, FlexWrap::NoWrap, 0.0))
+        .with_child(header)
+        .with_child(body);
+    let rects = solve(&root, rect(10.0, 10.0, 200.0, 300.0));
+
+    assert_rect(rects["header"], rect(10.0, 10.0, 200.0, 50.0));
+    assert_rect(rects["body"], rect(10.0, 60.0, 200.0, 250.0));
+    assert_rect(rects["left"], rect(10.0, 60.0, 100.0, 250.0));
+    assert_rect(rects["right"], rect(110.0, 60.0, 100.0, 250.0));
+    // Relative children keep their offset inside an absolute parent
+    assert_rect(rects["nudged"], rect(15.0, 15.0, 20.0, 20.0));
+    // Fixed children are anchored to the viewport, not their parent
+    assert_rect(rects["toast"], rect(100.0, 280.0, 100.0, 20.0));
+
+    // Relative items in a flex container are shifted from their flow position
+    let mut shifted = item("shifted", 20.0, 20.0).with_layout(LayoutType::Relative);
+    shifted.position = vec2(3.0, 4.0);
+    let root = item("root", 0.0, 0.0)
+        .with_layout(flex(FlexDirection::Row, JustifyContent::FlexStart, AlignItems::FlexStart, FlexWrap::NoWrap, 0.0))
+        .with_child(item("first", 20.0, 20.0))
+        .with_child(shifted);
+    assert_rect(solve(&root, viewport(100.0, 100.0))["shifted"], rect(23.0, 4.0, 20.0, 20.0));
+}
+
+#[test]
+fn test_anchors_and_aspect_ratio() {
+    let mut stretched = item("stretched", 50.0, 40.0);
+    stretched.position = vec2(0.0, 20.0);
+    stretched.constraints.anchors.left = Some(10.0);
+    stretched.constraints.anchors.right = Some(20.0);
+    let mut bottom = item("bottom", 50.0, 40.0);
+    bottom.position = vec2(30.0, 0.0);
+    bottom.constraints.anchors.bottom = Some(30.0);
+    let mut capped = item("capped", 50.0, 40.0);
+    capped.constraints.anchors.top = Some(0.0);
+    capped.constraints.anchors.bottom = Some(0.0);
+    capped.constraints.max_size = Some(Size { width: 100.0, height: 200.0 });
+    let mut ratio = item("ratio", 100.0, 10.0);
+    ratio.constraints.aspect_ratio = Some(2.0);
+
+    let root = item("root", 0.0, 0.0).with_child(stretched).with_child(bottom).with_child(capped).with_child(ratio);
+    let rects = solve(&root, viewport(400.0, 300.0));
+    assert_rect(rects["stretched"], rect(10.0, 20.0, 370.0, 40.0));
+    assert_rect(rects["bottom"], rect(30.0, 230.0, 50.0, 40.0));
+    assert_rect(rects["capped"], rect(0.0, 0.0, 50.0, 200.0));
+    assert_eq!(rects["ratio"].size(), Vec2::new(100.0, 50.0));
+}
+
+#[test]
+fn test_scene_layout_and_generated_placement() {
+    let mut store = SceneStore::new();
+    let root = store.add_component("Form", None).unwrap();
+    let ok = store.add_component("Button", Some(root)).unwrap();
+    let cancel = store.add_component("Button", Some(root)).unwrap();
+    let hidden = store.add_component("Label", Some(root)).unwrap();
+
+    store.get_component_mut(root).unwrap().layout_meta.layout_type =
+        flex(FlexDirection::Row, JustifyContent::FlexEnd, AlignItems::Center, FlexWrap::NoWrap, 10.0);
+    for (id, name) in [(ok, "Ok Button"), (cancel, "cancelButton")] {
+        let component = store.get_component_mut(id).unwrap();
+        component.name = name.to_string();
+        component.layout_meta.size = Size { width: 80.0, height: 30.0 };
+    }
+    store.get_component_mut(cancel).unwrap().properties.insert("flex_grow".to_string(), PropertyValue::Integer(1));
+    store.get_component_mut(ok).unwrap().layout_meta.z_index = 1;
+    store.get_component_mut(hidden).unwrap().visible = false;
+
+    let form = rect(100.0, 50.0, 300.0, 100.0);
+    let rects = solve_scene(&store, form);
+    assert_rect(rects[&root], form);
+    assert_rect(rects[&ok], rect(100.0, 85.0, 80.0, 30.0));
+    assert_rect(rects[&cancel], rect(190.0, 85.0, 210.0, 30.0));
+    assert!(!rects.contains_key(&hidden));
+}
+
+#[test]
+fn test_designer_layout_and_generated_placement() {
+    let mut project = IdeProject::new("shop".to_string(), PathBuf::from("shop"), ProjectType::GuiApplication);
+    let data = &mut project.designer_data;
+    data.layout_config.layout_type = DesignerLayout::Horizontal;
+    data.layout_config.spacing = 8.0;
+    data.layout_config.alignment.vertical = VerticalAlign::Center;
+    for (id, x, z_order) in [("Cancel Button", 200.0, 0), ("ok", 0.0, 1)] {
+        let mut component = ComponentData {
+            component_type: "Button".to_string(),
+            properties: HashMap::new(),
+            position: (x, 0.0),
+            size: (80.0, 30.0),
+            z_order,
+            locked: false,
+            id: id.to_string(),
+        };
+        if z_order == 0 {
+            component.properties.insert("flex_grow".to_string(), "1".to_string());
+        }
+        data.components.push(component);
+    }
+
+    // Components flow along the row in the order the designer shows them
+    let layout = LayoutNode::from_designer(&project.designer_data);
+    let rects = solve(&layout, rect(0.0, 0.0, 300.0, 100.0));
+    assert_rect(rects["ok"], rect(0.0, 35.0, 80.0, 30.0));
+    assert_rect(rects["cancel_button"], rect(88.0, 35.0, 212.0, 30.0));
+
+    // Generated forms embed the solved rects, relative to the form's corner
+    assert_eq!(
+        layout_rects_code(&layout, vec2(340.0, 140.0), 20.0, 0),
+        "vec![\n\
+         \x20   (\"ok\", egui::Rect::from_min_size(egui::pos2(20.0, 55.0), egui::vec2(80.0, 30.0))),\n\
+         \x20   (\"cancel_button\", egui::Rect::from_min_size(egui::pos2(108.0, 55.0), egui::vec2(212.0, 30.0))),\n\
+         ]"
+    );
+    // and render each component at its rect, moved to the form's `ui`
+    assert_eq!(
+        egui_placement_code(&layout),
+        "let origin = ui.max_rect().min.to_vec2();\n\
+         for (key, rect) in Self::layout() {\n\
+         \x20   let rect = rect.translate(origin);\n\
+         \x20   match key {\n\
+         \x20       \"ok\" => ui.allocate_ui_at_rect(rect, |ui| self.ok.render(ui)),\n\
+         \x20       \"cancel_button\" => ui.allocate_ui_at_rect(rect, |ui| self.cancel_button.render(ui)),\n\
+         \x20       _ => continue,\n\
+         \x20   };\n\
+         }\n"
+    );
+
+    // Free layouts keep the designer positions
+    project.designer_data.layout_config.layout_type = DesignerLayout::Free;
+    let layout = LayoutNode::from_designer(&project.designer_data);
+    assert_rect(solve(&layout, rect(10.0, 10.0, 300.0, 100.0))["cancel_button"], rect(210.0, 10.0, 80.0, 30.0));
+    assert!(layout_rects_code(&layout, vec2(300.0, 100.0), 0.0, 0)
+        .contains("(\"cancel_button\", egui::Rect::from_min_size(egui::pos2(200.0, 0.0), egui::vec2(80.0, 30.0)))"));
+}
+
+#[test]
+fn test_layout_rects_code_lists_nested_components_in_tree_order() {
+    let mut cell = LayoutNode::new("cell".to_string(), vec2(10.0, 10.0)).with_grid(GridPlacement { row: Some(1), column: None, row_span: 1, column_span: 2 });
+    cell.constraints.min_size = Some(Size { width: 20.0, height: 5.0 });
+    let header = LayoutNode::new("header".to_string(), vec2(30.0, 12.0))
+        .with_child(LayoutNode::new("title".to_string(), vec2(20.0, 8.0)).with_position(vec2(2.0, 2.0)));
+    let root = LayoutNode::new(String::new(), Vec2::ZERO)
+        .with_layout(grid(vec![GridTrack::Px(40.0), minmax(GridTrack::Auto, GridTrack::Fr(1.0))], vec![], 2.0, 3.0, GridAutoFlow::RowDense))
+        .with_child(header)
+        .with_child(cell);
+
+    let rects = solve(&root, rect(10.0, 10.0, 100.0, 50.0));
+    let expected: Vec<String> = ["header", "title", "cell"].iter().map(|key| {
+        let rect = rects[&key.to_string()];
+        format!(
+            "    ({:?}, egui::Rect::from_min_size(egui::pos2({:?}, {:?}), egui::vec2({:?}, {:?}))),",
+            key, rect.min.x, rect.min.y, rect.width(), rect.height()
+        )
+    }).collect();
+    assert_eq!(layout_rects_code(&root, vec2(120.0, 70.0), 10.0, 0), format!("vec![\n{}\n]", expected.join("\n")));
+
+    let empty = LayoutNode::new(String::new(), Vec2::ZERO);
+    assert_eq!(layout_rects_code(&empty, vec2(400.0, 300.0), 16.0, 8), "Vec::new()");
+    assert_eq!(egui_placement_code(&empty), "");
+}
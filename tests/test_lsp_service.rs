@@ -0,0 +1,199 @@
+//! Tests for configurable stdio language servers
+#![cfg(unix)]
+
+mod fake_lsp;
+
+use fake_lsp::{position, FakeServer};
+use ide_rs::editor::language_service::{LanguageService, LanguageServiceError, LanguageServiceManager};
+use ide_rs::editor::lsp_service::{path_to_uri, LanguageServerSettings, LspServerConfig, LspService, LANGUAGE_SERVERS_FILE};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::Path;
+use tempfile::TempDir;
+
+#[test]
+fn test_settings_parsing() {
+    let settings = LanguageServerSettings::from_json(
+        r#"{
+            "languages": {
+                "python": {
+                    "command": "pylsp",
+                    "args": ["--check-parent-process"],
+                    "extensions": ["py", "pyi"],
+                    "initializationOptions": { "plugins": [] },
+                    "rootMarkers": ["pyproject.toml"]
+                },
+                "markdown": { "language": "md", "command": "marksman", "args": ["server"], "extensions": ["md"] }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let languages: Vec<&str> = settings.servers().iter().map(|config| config.language.as_str()).collect();
+    assert_eq!(languages, vec!["md", "python"]);
+
+    let python = &settings.languages["python"];
+    assert_eq!(python.command, "pylsp");
+    assert_eq!(python.args, vec!["--check-parent-process".to_string()]);
+    assert_eq!(python.initialization_options, Some(json!({ "plugins": [] })));
+    assert_eq!(python.root_markers, vec!["pyproject.toml".to_string()]);
+    assert!(python.language_ids.is_empty());
+
+    let error = LanguageServerSettings::from_json(r#"{ "languages": { "json": { "extensions": ["json"] } } }"#).unwrap_err();
+    assert_eq!(error, "Language server for json has no command");
+    assert!(LanguageServerSettings::from_json("{ languages").unwrap_err().starts_with("Invalid language server settings"));
+}
+
+#[test]
+fn test_load_settings_file() {
+    let dir = TempDir::new().unwrap();
+    let defaults = LanguageServerSettings::load(dir.path()).unwrap();
+    assert_eq!(defaults, LanguageServerSettings::default_servers());
+    assert_eq!(defaults.languages["rust"].command, "rust-analyzer");
+
+    std::fs::create_dir(dir.path().join(".ide-rs")).unwrap();
+    std::fs::write(dir.path().join(LANGUAGE_SERVERS_FILE), r#"{ "languages": { "toml": { "command": "taplo", "args": ["lsp", "stdio"], "extensions": ["toml"] } } }"#).unwrap();
+    let settings = LanguageServerSettings::load(dir.path()).unwrap();
+    assert_eq!(settings.languages.len(), 1);
+    assert_eq!(settings.languages["toml"], LspServerConfig::new("toml", "taplo").with_args(["lsp", "stdio"]).with_extensions(["toml"]));
+
+    std::fs::write(dir.path().join(LANGUAGE_SERVERS_FILE), r#"{ "languages": { "toml": "taplo" } }"#).unwrap();
+    assert!(LanguageServerSettings::load(dir.path()).is_err());
+}
+
+#[test]
+fn test_config_file_matching_and_root() {
+    let mut config = LspServerConfig::new("web", "vscode-html-language-server").with_extensions(["html", "css"]);
+    config.language_ids.insert("css".to_string(), "css".to_string());
+    assert!(config.handles(Path::new("index.html")));
+    assert!(!config.handles(Path::new("main.rs")));
+    assert!(!config.handles(Path::new("Makefile")));
+    assert_eq!(config.language_id(Path::new("index.html")), "web");
+    assert_eq!(config.language_id(Path::new("site.css")), "css");
+
+    let dir = TempDir::new().unwrap();
+    let nested = dir.path().join("crates").join("app");
+    std::fs::create_dir_all(&nested).unwrap();
+    assert_eq!(config.project_root(&nested), nested);
+
+    config.root_markers = vec!["package.json".to_string(), ".git".to_string()];
+    assert_eq!(config.project_root(&nested), nested);
+    std::fs::create_dir(dir.path().join(".git")).unwrap();
+    assert_eq!(config.project_root(&nested), dir.path());
+    std::fs::write(nested.join("package.json"), "{}").unwrap();
+    assert_eq!(config.project_root(&nested), nested);
+}
+
+#[tokio::test]
+async fn test_initialize_uses_configuration() {
+    let server = FakeServer::new(json!({
+        "on": {
+            "initialized": [{
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "workspace/configuration",
+                "params": { "items": [{ "section": "pylsp.plugins" }, { "section": "missing" }, {}] }
+            }]
+        }
+    }));
+    std::fs::write(server.file("pyproject.toml"), "").unwrap();
+    let src = server.file("src");
+    std::fs::create_dir(&src).unwrap();
+
+    let mut config = server.config("python", &["py"]);
+    config.initialization_options = Some(json!({ "maxLineLength": 100 }));
+    config.root_markers = vec!["pyproject.toml".to_string()];
+    config.settings = json!({ "pylsp": { "plugins": { "ruff": { "enabled": true } } } });
+    let mut service = LspService::new(config);
+
+    service.initialize(&src).await.unwrap();
+    assert_eq!(service.project_root(), Some(server.root()));
+    service.open_document(&src.join("main.py"), "print('hi')\n").await.unwrap();
+    assert!(service.hover(&src.join("main.py"), position(0, 0)).await.unwrap().is_none());
+
+    let initialize = &server.received_method("initialize")[0]["params"];
+    assert_eq!(initialize["rootUri"], json!(path_to_uri(server.root())));
+    assert_eq!(initialize["initializationOptions"], json!({ "maxLineLength": 100 }));
+    assert_eq!(server.received_method("textDocument/didOpen")[0]["params"]["textDocument"]["languageId"], json!("python"));
+
+    let reply = server.received().into_iter().find(|message| message["id"] == 1 && message.get("method").is_none()).unwrap();
+    assert_eq!(
+        reply["result"],
+        json!([
+            { "ruff": { "enabled": true } },
+            null,
+            { "pylsp": { "plugins": { "ruff": { "enabled": true } } } }
+        ])
+    );
+
+    assert!(service.supports_file(Path::new("tool.py")));
+    assert_eq!(service.supported_extensions(), vec!["py".to_string()]);
+    service.shutdown().await.unwrap();
+    assert_eq!(service.project_root(), None);
+}
+
+#[tokio::test]
+async fn test_servers_run_side_by_side() {
+    let python = FakeServer::new(json!({
+        "responses": { "textDocument/hover": { "contents": "def greet() -> None" } }
+    }));
+    let toml = FakeServer::new(json!({
+        "responses": { "textDocument/hover": { "contents": { "kind": "plaintext", "value": "package.name: string" } } }
+    }));
+    let workspace = TempDir::new().unwrap();
+    let settings = LanguageServerSettings {
+        languages: HashMap::from([
+            ("python".to_string(), python.config("python", &["py"])),
+            ("toml".to_string(), toml.config("toml", &["toml"])),
+        ]),
+    };
+
+    let mut manager = LanguageServiceManager::new();
+    manager.register_servers(&settings);
+    let mut services: Vec<&String> = manager.list_services();
+    services.sort();
+    assert_eq!(services, vec!["python", "toml"]);
+    manager.initialize_all(workspace.path()).await.unwrap();
+
+    let script = workspace.path().join("app.py");
+    let manifest = workspace.path().join("pyproject.toml");
+    manager.service_for_file(&script).unwrap().open_document(&script, "greet()\n").await.unwrap();
+    manager.service_for_file(&manifest).unwrap().open_document(&manifest, "[project]\n").await.unwrap();
+
+    let hover = manager.service_for_file(&script).unwrap().hover(&script, position(0, 1)).await.unwrap().unwrap();
+    assert_eq!(hover.contents, "def greet() -> None");
+    let hover = manager.service_for_file(&manifest).unwrap().hover(&manifest, position(0, 1)).await.unwrap().unwrap();
+    assert_eq!(hover.contents, "package.name: string");
+
+    // Each server only saw the documents it handles
+    let opened = |server: &FakeServer| -> Vec<String> {
+        server.received_method("textDocument/didOpen").iter().map(|message| message["params"]["textDocument"]["languageId"].as_str().unwrap().to_string()).collect()
+    };
+    assert_eq!(opened(&python), vec!["python"]);
+    assert_eq!(opened(&toml), vec!["toml"]);
+
+    assert!(matches!(manager.service_for_file(Path::new("shader.wgsl")), Err(LanguageServiceError::FileNotSupported(_))));
+    manager.shutdown_all().await.unwrap();
+    assert_eq!(python.received_method("exit").len(), 1);
+    assert_eq!(toml.received_method("exit").len(), 1);
+}
+
+#[tokio::test]
+async fn test_register_workspace_servers() {
+    let server = FakeServer::new(json!({}));
+    let settings = LanguageServerSettings {
+        languages: HashMap::from([("wgsl".to_string(), server.config("wgsl", &["wgsl"]))]),
+    };
+    std::fs::create_dir(server.file(".ide-rs")).unwrap();
+    std::fs::write(server.file(LANGUAGE_SERVERS_FILE), serde_json::to_string_pretty(&settings).unwrap()).unwrap();
+
+    let mut manager = LanguageServiceManager::new();
+    assert_eq!(manager.register_workspace_servers(server.root()).unwrap(), 1);
+    assert_eq!(manager.get_service_for_file(Path::new("shaders/light.wgsl")), Some("wgsl"));
+    assert_eq!(manager.get_service_for_file(Path::new("main.rs")), None);
+
+    manager.initialize_all(server.root()).await.unwrap();
+    manager.shutdown_all().await.unwrap();
+    let methods: Vec<String> = server.received().iter().map(|message| message["method"].as_str().unwrap().to_string()).collect();
+    assert_eq!(methods, vec!["initialize", "initialized", "shutdown", "exit"]);
+}
@@ -0,0 +1,190 @@
+//! Integration tests for RCL component packages and the local package registry
+
+use ide_rs::editor::packaging::*;
+use ide_rs::rcl::component_registry::{ComponentCategory, ComponentMetadata, ComponentRegistry, PropertySchema};
+use semver::{Version, VersionReq};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn component(component_type: &str) -> ComponentMetadata {
+    ComponentMetadata {
+        component_type: component_type.to_string(),
+        display_name: component_type.to_string(),
+        description: format!("{} component", component_type),
+        category: ComponentCategory::Display,
+        version: "1.0.0".to_string(),
+        schema: PropertySchema { properties: HashMap::new(), required: Vec::new(), groups: Vec::new() },
+        defaults: HashMap::new(),
+        events: Vec::new(),
+        icon: None,
+        tags: vec!["packaged".to_string()],
+    }
+}
+
+/// Write a component source directory and package it into `output`
+fn build_package(
+    root: &Path,
+    output: &Path,
+    name: &str,
+    version: &str,
+    dependencies: &[(&str, &str)],
+    components: &[&str],
+) -> PathBuf {
+    let source = root.join(format!("{}-{}-src", name, version));
+    fs::create_dir_all(source.join("src")).unwrap();
+    fs::write(source.join("src/lib.rs"), format!("// {} {}\n", name, version)).unwrap();
+    fs::write(source.join("README.md"), "docs").unwrap();
+    fs::create_dir_all(source.join("target")).unwrap();
+    fs::write(source.join("target/ignored.o"), "build output").unwrap();
+
+    let mut manifest = PackageManifest::new(name, Version::parse(version).unwrap());
+    for (dependency, requirement) in dependencies {
+        manifest.dependencies.insert(dependency.to_string(), VersionReq::parse(requirement).unwrap());
+    }
+    manifest.components = components.iter().map(|c| component(c)).collect();
+    fs::write(source.join(MANIFEST_FILE_NAME), manifest.to_json()).unwrap();
+
+    package_component(&source, output).unwrap()
+}
+
+#[test]
+fn test_package_round_trip() {
+    let temp = TempDir::new().unwrap();
+    let output = temp.path().join("packages");
+    let path = build_package(temp.path(), &output, "gauges", "1.2.0", &[], &["Gauge"]);
+
+    assert_eq!(path, output.join("gauges-1.2.0.rclpkg"));
+
+    let archive = PackageArchive::open(&path).unwrap();
+    assert_eq!(archive.manifest.name, "gauges");
+    assert_eq!(archive.manifest.version, Version::new(1, 2, 0));
+    assert_eq!(archive.manifest.components[0].component_type, "Gauge");
+    assert_eq!(archive.files.keys().collect::<Vec<_>>(), vec!["README.md", "src/lib.rs"]);
+    assert_eq!(archive.manifest.files["README.md"], sha256_hex(b"docs"));
+}
+
+#[test]
+fn test_tampered_archive_is_rejected() {
+    let temp = TempDir::new().unwrap();
+    let path = build_package(temp.path(), temp.path(), "gauges", "1.0.0", &[], &["Gauge"]);
+
+    let mut archive = PackageArchive::open(&path).unwrap();
+    archive.files.insert("src/lib.rs".to_string(), b"fn injected() {}".to_vec());
+    let tampered = temp.path().join("tampered.rclpkg");
+    archive.write(&tampered).unwrap();
+
+    match PackageArchive::open(&tampered) {
+        Err(PackageError::IntegrityMismatch { package, file }) => {
+            assert_eq!(package, "gauges");
+            assert_eq!(file, "src/lib.rs");
+        }
+        other => panic!("expected integrity mismatch, got {:?}", other.map(|a| a.manifest.name)),
+    }
+
+    // A manifest that escapes the package directory is refused outright
+    let mut manifest = archive.manifest.clone();
+    manifest.files.insert("../evil.rs".to_string(), sha256_hex(b""));
+    assert!(matches!(manifest.validate(), Err(PackageError::InvalidManifest(_))));
+
+    let mut file = fs::File::create(temp.path().join("garbage.rclpkg")).unwrap();
+    file.write_all(b"not a package").unwrap();
+    assert!(PackageArchive::open(&temp.path().join("garbage.rclpkg")).is_err());
+}
+
+#[test]
+fn test_install_resolves_dependencies_and_registers_components() {
+    let temp = TempDir::new().unwrap();
+    let repo = temp.path().join("repo");
+    build_package(temp.path(), &repo, "core-widgets", "1.0.0", &[], &["Knob"]);
+    build_package(temp.path(), &repo, "core-widgets", "1.4.0", &[], &["Knob"]);
+    build_package(temp.path(), &repo, "core-widgets", "2.0.0", &[], &["Knob"]);
+    let dashboard = build_package(temp.path(), &repo, "dashboard", "0.3.0", &[("core-widgets", "^1.1")], &["Gauge", "Meter"]);
+
+    let registry = PackageRegistry::new(temp.path().join("installed"));
+    let mut components = ComponentRegistry::new();
+
+    let installed = registry.install(&dashboard, &mut components).unwrap();
+    let names: Vec<_> = installed.iter().map(|m| (m.name.as_str(), m.version.to_string())).collect();
+    assert_eq!(names, vec![("core-widgets", "1.4.0".to_string()), ("dashboard", "0.3.0".to_string())]);
+
+    assert_eq!(registry.installed().unwrap().len(), 2);
+    assert!(registry.files_dir("dashboard").join("src/lib.rs").is_file());
+    registry.verify("dashboard").unwrap();
+
+    let mut types = components.get_component_types();
+    types.sort();
+    assert_eq!(types, vec!["Gauge", "Knob", "Meter"]);
+    assert!(components.get_schema("Gauge").is_some());
+
+    // Components are restored into a fresh registry from disk
+    let mut reloaded = ComponentRegistry::new();
+    assert_eq!(registry.register_installed(&mut reloaded).unwrap(), 3);
+
+    // Tampering with installed files is detected
+    fs::write(registry.files_dir("dashboard").join("README.md"), "changed").unwrap();
+    assert!(matches!(registry.verify("dashboard"), Err(PackageError::IntegrityMismatch { .. })));
+}
+
+#[test]
+fn test_install_failures_leave_registry_untouched() {
+    let temp = TempDir::new().unwrap();
+    let repo = temp.path().join("repo");
+    let missing = build_package(temp.path(), &repo, "charts", "1.0.0", &[("plotting", "^3")], &["Chart"]);
+
+    let registry = PackageRegistry::new(temp.path().join("installed"));
+    let mut components = ComponentRegistry::new();
+
+    match registry.install(&missing, &mut components) {
+        Err(PackageError::DependencyNotFound { name, required_by, .. }) => {
+            assert_eq!(name, "plotting");
+            assert_eq!(required_by, "charts");
+        }
+        other => panic!("expected missing dependency, got {:?}", other),
+    }
+    assert!(registry.installed().unwrap().is_empty());
+    assert!(components.get_component_types().is_empty());
+
+    // Packages built for a different RCL API are refused
+    let source = temp.path().join("future-src");
+    fs::create_dir_all(&source).unwrap();
+    let mut manifest = PackageManifest::new("future", Version::new(1, 0, 0));
+    manifest.rcl_api_version = VersionReq::parse("^9.0").unwrap();
+    fs::write(source.join(MANIFEST_FILE_NAME), manifest.to_json()).unwrap();
+    let future = package_component(&source, &repo).unwrap();
+    assert!(matches!(registry.install(&future, &mut components), Err(PackageError::IncompatibleApi { .. })));
+}
+
+#[test]
+fn test_uninstall_refuses_to_orphan_dependents() {
+    let temp = TempDir::new().unwrap();
+    let repo = temp.path().join("repo");
+    let base = build_package(temp.path(), &repo, "base", "1.0.0", &[], &["Base"]);
+    let app = build_package(temp.path(), &repo, "app", "1.0.0", &[("base", "^1")], &["App"]);
+
+    let registry = PackageRegistry::new(temp.path().join("installed"));
+    let mut components = ComponentRegistry::new();
+    registry.install(&app, &mut components).unwrap();
+
+    match registry.uninstall("base", &mut components) {
+        Err(PackageError::HasDependents { name, dependents }) => {
+            assert_eq!(name, "base");
+            assert_eq!(dependents, vec!["app".to_string()]);
+        }
+        other => panic!("expected dependents error, got {:?}", other.map(|m| m.name)),
+    }
+    assert!(registry.is_installed("base"));
+
+    // Upgrading a dependency to a version the dependent rejects is refused too
+    let base2 = build_package(temp.path(), &repo, "base", "2.0.0", &[], &["Base"]);
+    assert!(matches!(registry.install(&base2, &mut components), Err(PackageError::DependencyConflict { .. })));
+    registry.install(&base, &mut components).unwrap();
+
+    registry.uninstall("app", &mut components).unwrap();
+    assert!(components.get_metadata("App").is_none());
+    registry.uninstall("base", &mut components).unwrap();
+    assert!(components.get_component_types().is_empty());
+    assert!(matches!(registry.uninstall("base", &mut components), Err(PackageError::NotInstalled(_))));
+}
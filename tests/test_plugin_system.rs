@@ -0,0 +1,233 @@
+//! Integration tests for out-of-process plugin loading
+//!
+//! A small POSIX shell script stands in for a real plugin executable and speaks
+//! the line-delimited JSON-RPC protocol used by the plugin host.
+
+#![cfg(unix)]
+
+use ide_rs::core::event_bus::{global_event_bus, IdeEvent};
+use ide_rs::editor::plugin_system::*;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const STUB_PLUGIN: &str = r#"#!/bin/sh
+while IFS= read -r line; do
+  id=$(printf '%s\n' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *'"method":"initialize"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"commands":[{"id":"hello.greet","name":"Greet","description":"Say hello"}],"events":["fileSaved","buildCompleted","gitCommit"],"extensionPoints":[{"id":"hello.format","type":"formatterProvider"}]}}\n' "$id" ;;
+    *'"method":"executeCommand"'*)
+      printf '{"jsonrpc":"2.0","method":"window/showMessage","params":{"type":3,"message":"hello from plugin"}}\n'
+      printf '{"jsonrpc":"2.0","id":%s,"result":null}\n' "$id" ;;
+    *'"method":"extension/request"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":"formatted"}\n' "$id" ;;
+    *'"method":"event/'*)
+      event=$(printf '%s\n' "$line" | sed -n 's/.*"method":"event\/\([a-zA-Z]*\)".*/\1/p')
+      printf '{"jsonrpc":"2.0","method":"window/log","params":{"level":"info","message":"event %s"}}\n' "$event" ;;
+    *'"method":"shutdown"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":null}\n' "$id"
+      exit 0 ;;
+    *'"id":'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":null}\n' "$id" ;;
+  esac
+done
+"#;
+
+fn plugin_root(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("ide-rs-plugins-{}-{}", name, uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&root).unwrap();
+    root
+}
+
+fn install_plugin(root: &Path, id: &str, entry_point: &str, script: Option<&str>) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = root.join(id);
+    std::fs::create_dir_all(&dir).unwrap();
+    let manifest = serde_json::json!({
+        "id": id,
+        "name": "Hello Plugin",
+        "version": "0.1.0",
+        "description": "Test plugin",
+        "author": "ide-rs",
+        "license": "MIT",
+        "keywords": [],
+        "min_ide_version": "0.1.0",
+        "dependencies": [],
+        "entry_point": entry_point,
+        "category": "Utility",
+    });
+    std::fs::write(dir.join("plugin.json"), manifest.to_string()).unwrap();
+
+    if let Some(script) = script {
+        let path = dir.join(entry_point);
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+}
+
+fn wait_for_message(manager: &mut PluginManager, text: &str) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if manager.drain_messages().iter().any(|m| m.text == text) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    false
+}
+
+#[test]
+fn test_process_plugin_registers_command() {
+    let root = plugin_root("command");
+    install_plugin(&root, "hello", "plugin.sh", Some(STUB_PLUGIN));
+
+    let mut manager = PluginManager::with_plugin_directory(root.clone());
+    manager.scan_plugins().unwrap();
+    manager.load_plugin("hello").unwrap();
+
+    assert_eq!(manager.plugins["hello"].state, PluginState::Active);
+    let commands = manager.list_commands();
+    assert_eq!(commands.len(), 1);
+    assert_eq!(commands[0].0, "hello");
+    assert_eq!(commands[0].1.id, "hello.greet");
+    assert_eq!(commands[0].1.name, "Greet");
+
+    manager.execute_plugin_command("hello.greet").unwrap();
+    let messages = manager.drain_messages();
+    assert!(messages.iter().any(|m| m.text == "hello from plugin" && m.level == PluginMessageLevel::Info));
+
+    assert!(matches!(
+        manager.execute_plugin_command("missing.command"),
+        Err(PluginError::CommandNotFound(_))
+    ));
+
+    manager.unload_plugin("hello").unwrap();
+    assert!(manager.list_commands().is_empty());
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_process_plugin_receives_hooks() {
+    let root = plugin_root("hooks");
+    install_plugin(&root, "hello", "plugin.sh", Some(STUB_PLUGIN));
+
+    let mut manager = PluginManager::with_plugin_directory(root.clone());
+    manager.scan_plugins().unwrap();
+    manager.load_plugin("hello").unwrap();
+    assert_eq!(manager.hooks.handler_count(), 3);
+
+    manager.dispatch_file_event(FileEvent {
+        file_path: PathBuf::from("src/main.rs"),
+        event_type: FileEventType::Saved,
+        timestamp: std::time::SystemTime::now(),
+    });
+    assert!(wait_for_message(&mut manager, "event fileSaved"));
+
+    manager.dispatch_build_event(BuildEvent {
+        project_path: root.clone(),
+        event_type: BuildEventType::Completed { success: true },
+        details: "exit code 0".to_string(),
+    });
+    assert!(wait_for_message(&mut manager, "event buildCompleted"));
+
+    // File events opened by the IDE are not subscribed and must not reach the plugin
+    manager.dispatch_file_event(FileEvent {
+        file_path: PathBuf::from("src/main.rs"),
+        event_type: FileEventType::Opened,
+        timestamp: std::time::SystemTime::now(),
+    });
+
+    // Hooks of paused plugins are skipped
+    manager.deactivate_plugin("hello").unwrap();
+    manager.dispatch_build_event(BuildEvent {
+        project_path: root.clone(),
+        event_type: BuildEventType::Completed { success: false },
+        details: "exit code 1".to_string(),
+    });
+    std::thread::sleep(Duration::from_millis(200));
+    assert!(manager.drain_messages().iter().all(|m| !m.text.starts_with("event")));
+
+    manager.unload_plugin("hello").unwrap();
+    assert_eq!(manager.hooks.handler_count(), 0);
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_process_plugin_receives_event_bus_events() {
+    let root = plugin_root("bus");
+    install_plugin(&root, "hello", "plugin.sh", Some(STUB_PLUGIN));
+
+    let mut manager = PluginManager::with_plugin_directory(root.clone());
+    manager.connect_event_bus();
+    manager.scan_plugins().unwrap();
+    manager.load_plugin("hello").unwrap();
+
+    global_event_bus().publish(IdeEvent::GitCommitted {
+        repository_path: root.clone(),
+        hash: "abc123".to_string(),
+        message: "Initial commit".to_string(),
+    });
+    manager.process_ide_events();
+    assert!(wait_for_message(&mut manager, "event gitCommit"));
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_process_plugin_extension_point() {
+    let root = plugin_root("extension");
+    install_plugin(&root, "hello", "plugin.sh", Some(STUB_PLUGIN));
+
+    let mut manager = PluginManager::with_plugin_directory(root.clone());
+    manager.scan_plugins().unwrap();
+    manager.load_plugin("hello").unwrap();
+
+    let plugin = manager.plugins.get_mut("hello").unwrap();
+    assert_eq!(plugin.extension_points.len(), 1);
+    let point = &mut plugin.extension_points[0];
+    assert_eq!(point.point_type, ExtensionPointType::FormatterProvider);
+
+    let response = point.implementation.handle_request(ExtensionRequest::FormatCode {
+        file_path: PathBuf::from("src/lib.rs"),
+        content: "fn  main(){}".to_string(),
+        options: FormatOptions {
+            tab_size: 4,
+            insert_spaces: true,
+            trim_trailing_whitespace: true,
+        },
+    }).unwrap();
+    assert!(matches!(response, ExtensionResponse::FormattedCode(ref code) if code == "formatted"));
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_declarative_plugin_loads_without_process() {
+    let root = plugin_root("declarative");
+    install_plugin(&root, "theme-pack", "", None);
+
+    let mut manager = PluginManager::with_plugin_directory(root.clone());
+    manager.scan_plugins().unwrap();
+    manager.load_plugin("theme-pack").unwrap();
+
+    assert_eq!(manager.plugins["theme-pack"].state, PluginState::Active);
+    assert!(manager.list_commands().is_empty());
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_missing_entry_point_fails_to_load() {
+    let root = plugin_root("missing");
+    install_plugin(&root, "broken", "does-not-exist", None);
+
+    let mut manager = PluginManager::with_plugin_directory(root.clone());
+    manager.scan_plugins().unwrap();
+
+    assert!(matches!(
+        manager.load_plugin("broken"),
+        Err(PluginError::InitializationFailed(_))
+    ));
+    assert!(manager.plugins.is_empty());
+    let _ = std::fs::remove_dir_all(root);
+}
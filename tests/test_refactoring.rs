@@ -0,0 +1,423 @@
+//! Tests for syn-based refactorings in the advanced refactoring engine
+
+use ide_rs::editor::advanced_refactoring::*;
+use std::collections::HashMap;
+use std::fs;
+use tempfile::TempDir;
+
+fn workspace(files: &[(&str, &str)]) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"sample\"\nversion = \"0.1.0\"\n").unwrap();
+    for (path, content) in files {
+        let path = dir.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    dir
+}
+
+fn engine(dir: &TempDir) -> AdvancedRefactoringEngine {
+    let mut engine = AdvancedRefactoringEngine::new(RefactoringSettings::default());
+    engine.set_workspace_root(dir.path());
+    engine
+}
+
+/// Selection of the `nth` occurrence of `needle` in `file`
+fn select(dir: &TempDir, file: &str, needle: &str, nth: usize) -> CodeSelection {
+    let path = dir.path().join(file);
+    let source = fs::read_to_string(&path).unwrap();
+    let start = source.match_indices(needle).nth(nth).unwrap_or_else(|| panic!("{:?} not found", needle)).0;
+    CodeSelection::new(path, &source, start..start + needle.len())
+}
+
+/// Cursor at the start of the `nth` occurrence of `needle` in `file`
+fn cursor(dir: &TempDir, file: &str, needle: &str, nth: usize) -> CodeSelection {
+    let selection = select(dir, file, needle, nth);
+    let source = fs::read_to_string(&selection.file_path).unwrap();
+    CodeSelection::new(selection.file_path, &source, selection.start.offset..selection.start.offset)
+}
+
+fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+fn read(dir: &TempDir, file: &str) -> String {
+    fs::read_to_string(dir.path().join(file)).unwrap()
+}
+
+const LIB: &str = "pub mod shapes;
+
+use shapes::area;
+
+pub fn total(sizes: &[f64]) -> f64 {
+    sizes.iter().map(|s| area(*s)).sum()
+}
+";
+
+const SHAPES: &str = "pub fn area(side: f64) -> f64 {
+    side * side
+}
+
+pub struct Square {
+    pub area: f64,
+}
+
+pub fn describe(side: f64) -> String {
+    let area = area(side);
+    format!(\"{}\", area)
+}
+";
+
+#[test]
+fn test_rename_function_updates_workspace_and_undoes_as_one_transaction() {
+    let dir = workspace(&[("src/lib.rs", LIB), ("src/shapes.rs", SHAPES)]);
+    let mut engine = engine(&dir);
+
+    let selection = cursor(&dir, "src/shapes.rs", "area", 0);
+    let result = engine.execute_refactoring("rename_symbol", &selection, params(&[("new_name", "square_area")])).unwrap();
+    assert!(result.success);
+
+    assert_eq!(read(&dir, "src/lib.rs"), LIB.replace("area", "square_area"));
+    // The field and the local variable of the same name are left alone
+    assert_eq!(
+        read(&dir, "src/shapes.rs"),
+        SHAPES
+            .replacen("pub fn area", "pub fn square_area", 1)
+            .replace("let area = area(side)", "let area = square_area(side)")
+    );
+
+    engine.undo_last_refactoring().unwrap();
+    assert_eq!(read(&dir, "src/lib.rs"), LIB);
+    assert_eq!(read(&dir, "src/shapes.rs"), SHAPES);
+    assert!(engine.can_redo());
+
+    engine.redo_refactoring().unwrap();
+    assert!(read(&dir, "src/lib.rs").contains("use shapes::square_area;"));
+}
+
+#[test]
+fn test_rename_local_stays_in_its_function_and_expands_shorthand() {
+    let source = "struct Point { x: i32 }
+
+fn make(y: i32) -> Point {
+    let x = y + 1;
+    Point { x }
+}
+
+fn other() -> i32 {
+    let x = 2;
+    x
+}
+";
+    let dir = workspace(&[("src/lib.rs", source)]);
+    let mut engine = engine(&dir);
+
+    let selection = cursor(&dir, "src/lib.rs", "x = y", 0);
+    engine.execute_refactoring("rename_symbol", &selection, params(&[("new_name", "next")])).unwrap();
+
+    let updated = read(&dir, "src/lib.rs");
+    assert!(updated.contains("let next = y + 1;\n    Point { x: next }"), "{}", updated);
+    assert!(updated.contains("let x = 2;\n    x\n"));
+
+    let conflict = engine.preview_refactoring("rename_symbol", &cursor(&dir, "src/lib.rs", "next", 0), &params(&[("new_name", "y")]));
+    assert!(conflict.unwrap_err().contains("already bound"));
+}
+
+#[test]
+fn test_preview_does_not_write_and_rejects_stale_changes() {
+    let dir = workspace(&[("src/lib.rs", LIB), ("src/shapes.rs", SHAPES)]);
+    let engine = engine(&dir);
+
+    let selection = cursor(&dir, "src/shapes.rs", "Square", 0);
+    let changes = engine.preview_refactoring("rename_symbol", &selection, &params(&[("new_name", "Tile")])).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].position.line, 4);
+    assert_eq!(read(&dir, "src/shapes.rs"), SHAPES);
+
+    fs::write(dir.path().join("src/shapes.rs"), format!("// edited\n{}", SHAPES)).unwrap();
+    let error = plan_edits(&changes).unwrap_err();
+    assert!(error.contains("has changed"), "{}", error);
+
+    assert!(engine.preview_refactoring("rename_symbol", &selection, &params(&[("new_name", "not valid")])).is_err());
+}
+
+#[test]
+fn test_extract_variable_inserts_let_before_statement() {
+    let source = "fn price(qty: u32, unit: u32) -> u32 {
+    let total = qty * unit + 5;
+    total
+}
+";
+    let dir = workspace(&[("src/lib.rs", source)]);
+    let mut engine = engine(&dir);
+
+    let selection = select(&dir, "src/lib.rs", "qty * unit", 0);
+    engine.execute_refactoring("extract_variable", &selection, params(&[("variable_name", "subtotal")])).unwrap();
+
+    assert_eq!(
+        read(&dir, "src/lib.rs"),
+        "fn price(qty: u32, unit: u32) -> u32 {
+    let subtotal = qty * unit;
+    let total = subtotal + 5;
+    total
+}
+"
+    );
+}
+
+#[test]
+fn test_inline_variable_parenthesizes_and_removes_declaration() {
+    let source = "fn scaled(a: i32, b: i32) -> i32 {
+    let sum = a + b;
+    let doubled = sum * 2;
+    doubled + sum
+}
+";
+    let dir = workspace(&[("src/lib.rs", source)]);
+    let mut engine = engine(&dir);
+
+    let selection = cursor(&dir, "src/lib.rs", "sum", 0);
+    engine.execute_refactoring("inline_variable", &selection, HashMap::new()).unwrap();
+
+    assert_eq!(
+        read(&dir, "src/lib.rs"),
+        "fn scaled(a: i32, b: i32) -> i32 {
+    let doubled = (a + b) * 2;
+    doubled + (a + b)
+}
+"
+    );
+
+    engine.undo_last_refactoring().unwrap();
+    assert_eq!(read(&dir, "src/lib.rs"), source);
+}
+
+#[test]
+fn test_extract_function_passes_inputs_and_returns_outputs() {
+    let source = "pub fn report(name: String, count: usize) -> String {
+    let label = name.to_uppercase();
+    let line = format!(\"{}: {}\", label, count);
+    line
+}
+";
+    let dir = workspace(&[("src/lib.rs", source)]);
+    let mut engine = engine(&dir);
+
+    let selection = select(
+        &dir,
+        "src/lib.rs",
+        "let label = name.to_uppercase();\n    let line = format!(\"{}: {}\", label, count);",
+        0,
+    );
+    engine.execute_refactoring("extract_function", &selection, params(&[("function_name", "format_line")])).unwrap();
+
+    assert_eq!(
+        read(&dir, "src/lib.rs"),
+        "pub fn report(name: String, count: usize) -> String {
+    let line = format_line(&name, count);
+    line
+}
+
+fn format_line(name: &String, count: usize) -> String {
+    let label = name.to_uppercase();
+    let line = format!(\"{}: {}\", label, count);
+    line
+}
+"
+    );
+}
+
+#[test]
+fn test_extract_method_uses_self_receiver() {
+    let source = "pub struct Counter {
+    value: i32,
+}
+
+impl Counter {
+    pub fn bump(&mut self, by: i32) {
+        self.value += by;
+        self.value *= 2;
+    }
+}
+";
+    let dir = workspace(&[("src/lib.rs", source)]);
+    let mut engine = engine(&dir);
+
+    let selection = select(&dir, "src/lib.rs", "self.value += by;\n        self.value *= 2;", 0);
+    engine.execute_refactoring("extract_method", &selection, params(&[("method_name", "grow")])).unwrap();
+
+    assert_eq!(
+        read(&dir, "src/lib.rs"),
+        "pub struct Counter {
+    value: i32,
+}
+
+impl Counter {
+    pub fn bump(&mut self, by: i32) {
+        self.grow(by);
+    }
+
+    fn grow(&mut self, by: i32) {
+        self.value += by;
+        self.value *= 2;
+    }
+}
+"
+    );
+}
+
+#[test]
+fn test_extract_rejects_early_return() {
+    let source = "fn check(v: i32) -> i32 {
+    if v < 0 { return 0; }
+    v
+}
+";
+    let dir = workspace(&[("src/lib.rs", source)]);
+    let engine = engine(&dir);
+
+    let selection = select(&dir, "src/lib.rs", "if v < 0 { return 0; }", 0);
+    let error = engine.preview_refactoring("extract_function", &selection, &params(&[("function_name", "guard")])).unwrap_err();
+    assert!(error.contains("return"), "{}", error);
+}
+
+#[test]
+fn test_move_item_creates_module_and_updates_paths() {
+    let lib = "pub mod shapes;
+
+pub fn total() -> f64 {
+    crate::shapes::area(2.0)
+}
+";
+    let shapes = "use std::f64::consts::PI;
+
+pub fn area(r: f64) -> f64 {
+    PI * r * r
+}
+
+pub fn ring(r: f64) -> f64 {
+    area(r) - area(r / 2.0)
+}
+";
+    let dir = workspace(&[("src/lib.rs", lib), ("src/shapes.rs", shapes)]);
+    let mut engine = engine(&dir);
+
+    let selection = cursor(&dir, "src/shapes.rs", "pub fn area", 0);
+    engine.execute_refactoring("move_item_to_module", &selection, params(&[("target_module", "crate::geometry")])).unwrap();
+
+    assert_eq!(
+        read(&dir, "src/geometry.rs"),
+        "use std::f64::consts::PI;
+
+pub fn area(r: f64) -> f64 {
+    PI * r * r
+}
+"
+    );
+    assert_eq!(
+        read(&dir, "src/shapes.rs"),
+        "use std::f64::consts::PI;
+use crate::geometry::area;
+
+pub fn ring(r: f64) -> f64 {
+    area(r) - area(r / 2.0)
+}
+"
+    );
+    assert_eq!(
+        read(&dir, "src/lib.rs"),
+        "pub mod shapes;
+pub mod geometry;
+
+pub fn total() -> f64 {
+    crate::geometry::area(2.0)
+}
+"
+    );
+
+    engine.undo_last_refactoring().unwrap();
+    assert!(!dir.path().join("src/geometry.rs").exists());
+    assert_eq!(read(&dir, "src/lib.rs"), lib);
+    assert_eq!(read(&dir, "src/shapes.rs"), shapes);
+}
+
+#[test]
+fn test_batch_applies_all_selections_or_none() {
+    let source = "fn a() -> i32 {
+    let x = 1;
+    x + 1
+}
+
+fn b() -> i32 {
+    let y = 2;
+    y * 3
+}
+";
+    let dir = workspace(&[("src/lib.rs", source)]);
+    let mut engine = engine(&dir);
+    engine.add_batch_template(BatchTemplate {
+        template_id: "inline_all".to_string(),
+        name: "Inline locals".to_string(),
+        description: "Inline every selected local".to_string(),
+        operations: vec!["inline_variable".to_string()],
+        parameters: Vec::new(),
+        applicability_rules: Vec::new(),
+    });
+    assert!(engine.start_batch_operation("missing", HashMap::new()).is_err());
+
+    // The second selection is not a variable, so nothing is written
+    let batch = engine.start_batch_operation("inline_all", HashMap::new()).unwrap();
+    let selections = [cursor(&dir, "src/lib.rs", "x = 1", 0), cursor(&dir, "src/lib.rs", "fn b", 0)];
+    assert!(engine.run_batch(&batch, &selections).is_err());
+    assert_eq!(read(&dir, "src/lib.rs"), source);
+    assert_eq!(engine.get_batch(&batch).unwrap().progress.failed_operations, 1);
+
+    let batch = engine.start_batch_operation("inline_all", HashMap::new()).unwrap();
+    let selections = [cursor(&dir, "src/lib.rs", "x = 1", 0), cursor(&dir, "src/lib.rs", "y = 2", 0)];
+    let result = engine.run_batch(&batch, &selections).unwrap();
+    assert_eq!(result.applied_changes.len(), 4);
+    assert_eq!(read(&dir, "src/lib.rs"), "fn a() -> i32 {
+    1 + 1
+}
+
+fn b() -> i32 {
+    2 * 3
+}
+");
+
+    engine.undo_last_refactoring().unwrap();
+    assert_eq!(read(&dir, "src/lib.rs"), source);
+    assert!(!engine.can_undo());
+}
+
+#[test]
+fn test_suggestions_follow_selection_context() {
+    let source = "fn f(a: i32) -> i32 {
+    let b = a * 2;
+    b
+}
+";
+    let dir = workspace(&[("src/lib.rs", source)]);
+    let engine = engine(&dir);
+
+    let ids = |selection: &CodeSelection| {
+        let mut ids: Vec<String> = engine.get_suggestions(selection).into_iter().map(|s| s.operation_id).collect();
+        ids.sort();
+        ids
+    };
+    assert_eq!(ids(&cursor(&dir, "src/lib.rs", "b =", 0)), ["inline_variable", "rename_symbol"]);
+    assert_eq!(
+        ids(&select(&dir, "src/lib.rs", "a * 2", 0)),
+        ["extract_function", "extract_method", "extract_variable"]
+    );
+}
+
+#[test]
+fn test_refactoring_view_prefills_rename_with_selected_name() {
+    let dir = workspace(&[("src/lib.rs", LIB), ("src/shapes.rs", SHAPES)]);
+    let engine = engine(&dir);
+
+    let mut view = RefactoringView::new(&engine, select(&dir, "src/shapes.rs", "area", 0));
+    view.choose(&engine, "rename_symbol".to_string());
+    assert_eq!(view.parameter_values().get("new_name").map(String::as_str), Some("area"));
+}
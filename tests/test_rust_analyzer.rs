@@ -1,9 +1,426 @@
-//! Integration test for RustAnalyzerClient
-use ide_rs::editor::rust_analyzer::RustAnalyzerClient;
+//! Tests for RustAnalyzerService against a scripted language server
+#![cfg(unix)]
+
+mod fake_lsp;
+
+use fake_lsp::{position, range, span, uri, FakeServer};
+use ide_rs::editor::language_service::{CodeActionKind, FoldingRangeKind, LanguageService, LanguageServiceError, SymbolKind};
+use ide_rs::editor::lsp_integration::{DiagnosticSeverity, Range};
+use ide_rs::editor::lsp_service::{path_to_uri, uri_to_path, WorkDoneProgress};
+use ide_rs::editor::rust_analyzer::RustAnalyzerService;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tempfile::TempDir;
 
 #[test]
-fn test_rust_analyzer_analyze() {
-    let analyzer = RustAnalyzerClient::new();
-    let diagnostics = analyzer.analyze("fn main() {}\n");
-    assert_eq!(diagnostics, vec!["No errors (mocked)".to_string()]);
+fn test_uri_conversion() {
+    let path = Path::new("/tmp/my project/src/naïve.rs");
+    let uri = path_to_uri(path);
+    assert_eq!(uri, "file:///tmp/my%20project/src/na%C3%AFve.rs");
+    assert_eq!(uri_to_path(&uri), path);
+    assert_eq!(uri_to_path("file:///C:/src/main.rs"), PathBuf::from("C:/src/main.rs"));
+}
+
+#[tokio::test]
+async fn test_navigation_responses() {
+    let (main, lib) = (uri("main.rs"), uri("lib.rs"));
+    let server = FakeServer::new(json!({
+        "responses": {
+            "initialize": {
+                "capabilities": {
+                    "semanticTokensProvider": {
+                        "legend": { "tokenTypes": ["function", "variable"], "tokenModifiers": ["declaration"] }
+                    }
+                }
+            },
+            "textDocument/definition": [{
+                "targetUri": lib,
+                "targetRange": range((0, 0), (5, 1)),
+                "targetSelectionRange": range((0, 7), (0, 12))
+            }],
+            "textDocument/references": [
+                { "uri": lib, "range": range((0, 7), (0, 12)) },
+                { "uri": main, "range": range((3, 4), (3, 9)) }
+            ],
+            "textDocument/hover": {
+                "contents": { "kind": "markdown", "value": "```rust\nfn greet()\n```" },
+                "range": range((3, 4), (3, 9))
+            },
+            "textDocument/completion": {
+                "isIncomplete": false,
+                "items": [
+                    { "label": "greet", "kind": 3, "detail": "fn()", "textEdit": { "range": range((3, 4), (3, 6)), "newText": "greet()" } },
+                    { "label": "String", "kind": 22, "documentation": { "kind": "markdown", "value": "A UTF-8 string" } }
+                ]
+            },
+            "textDocument/signatureHelp": {
+                "signatures": [{
+                    "label": "fn greet(name: &str, times: u32)",
+                    "parameters": [{ "label": [9, 19] }, { "label": "times: u32", "documentation": "Repeat count" }]
+                }],
+                "activeSignature": 0,
+                "activeParameter": 1
+            }
+        }
+    }));
+    let mut service = server.service();
+
+    assert!(matches!(service.hover(&server.file("main.rs"), position(0, 0)).await, Err(LanguageServiceError::NotInitialized)));
+    service.initialize(server.root()).await.unwrap();
+    assert!(service.is_initialized());
+    assert_eq!(service.semantic_token_legend(), (vec!["function".to_string(), "variable".to_string()], vec!["declaration".to_string()]));
+
+    service.open_document(&server.file("main.rs"), "fn main() {}\n").await.unwrap();
+    service.open_document(&server.file("Cargo.toml"), "[package]\n").await.unwrap();
+
+    let definitions = service.goto_definition(&server.file("main.rs"), position(3, 5)).await.unwrap();
+    assert_eq!(definitions.len(), 1);
+    assert_eq!(definitions[0].uri, server.file("lib.rs"));
+    assert_eq!(span(&definitions[0].range), (0, 7, 0, 12));
+
+    let references = service.find_references(&server.file("main.rs"), position(3, 5)).await.unwrap();
+    let references: Vec<(PathBuf, u64)> = references.into_iter().map(|location| (location.uri, location.range.start.line)).collect();
+    assert_eq!(references, vec![(server.file("lib.rs"), 0), (server.file("main.rs"), 3)]);
+
+    let hover = service.hover(&server.file("main.rs"), position(3, 5)).await.unwrap().unwrap();
+    assert_eq!(hover.contents, "```rust\nfn greet()\n```");
+    assert_eq!(span(hover.range.as_ref().unwrap()), (3, 4, 3, 9));
+
+    let completions = service.completion(&server.file("main.rs"), position(3, 6)).await.unwrap();
+    assert_eq!(completions.len(), 2);
+    assert_eq!(completions[0].insert_text.as_deref(), Some("greet()"));
+    assert_eq!(completions[0].detail.as_deref(), Some("fn()"));
+    assert_eq!(completions[1].documentation.as_deref(), Some("A UTF-8 string"));
+
+    let signature = service.signature_help(&server.file("main.rs"), position(3, 10)).await.unwrap().unwrap();
+    assert_eq!(signature.active_parameter, Some(1));
+    let parameters: Vec<&str> = signature.signatures[0].parameters.iter().map(|parameter| parameter.label.as_str()).collect();
+    assert_eq!(parameters, vec!["name: &str", "times: u32"]);
+    assert_eq!(signature.signatures[0].parameters[1].documentation.as_deref(), Some("Repeat count"));
+
+    let opened: Vec<Value> = server.received_method("textDocument/didOpen").into_iter().map(|message| message["params"]["textDocument"]["languageId"].clone()).collect();
+    assert_eq!(opened, vec![json!("rust"), json!("toml")]);
+    let definition_request = &server.received_method("textDocument/definition")[0];
+    assert_eq!(definition_request["params"]["textDocument"]["uri"], json!(path_to_uri(&server.file("main.rs"))));
+    assert_eq!(definition_request["params"]["position"], json!({ "line": 3, "character": 5 }));
+}
+
+#[tokio::test]
+async fn test_symbol_and_token_responses() {
+    let uri = uri("lib.rs");
+    let server = FakeServer::new(json!({
+        "responses": {
+            "textDocument/documentSymbol": [{
+                "name": "Greeter",
+                "kind": 23,
+                "range": range((0, 0), (4, 1)),
+                "selectionRange": range((0, 11), (0, 18)),
+                "children": [{ "name": "name", "detail": "String", "kind": 8, "range": range((1, 4), (1, 20)), "selectionRange": range((1, 8), (1, 12)) }]
+            }],
+            "workspace/symbol": [
+                { "name": "greet", "kind": 12, "location": { "uri": uri, "range": range((6, 0), (8, 1)) }, "containerName": "lib" },
+                { "name": "Greeter", "kind": 23, "location": { "uri": uri } }
+            ],
+            "textDocument/semanticTokens/full": { "data": [0, 3, 5, 0, 1, 0, 6, 4, 1, 0, 2, 4, 3, 0, 0] },
+            "textDocument/foldingRange": [
+                { "startLine": 0, "endLine": 4 },
+                { "startLine": 6, "startCharacter": 0, "endLine": 7, "endCharacter": 2, "kind": "comment" }
+            ]
+        }
+    }));
+    let mut service = server.start().await;
+    let file = server.file("lib.rs");
+
+    let symbols = service.document_symbols(&file).await.unwrap();
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].name, "Greeter");
+    assert!(matches!(symbols[0].kind, SymbolKind::Struct));
+    assert_eq!(span(&symbols[0].selection_range), (0, 11, 0, 18));
+    assert_eq!(symbols[0].children[0].detail.as_deref(), Some("String"));
+    assert!(matches!(symbols[0].children[0].kind, SymbolKind::Field));
+
+    let workspace = service.workspace_symbols("gre").await.unwrap();
+    assert_eq!(workspace.len(), 2);
+    assert!(matches!(workspace[0].kind, SymbolKind::Function));
+    assert_eq!(workspace[0].container_name.as_deref(), Some("lib"));
+    assert_eq!(workspace[0].location.uri, file);
+    assert_eq!(span(&workspace[1].location.range), (0, 0, 0, 0));
+    assert_eq!(server.received_method("workspace/symbol")[0]["params"], json!({ "query": "gre" }));
+
+    let tokens = service.semantic_tokens(&file).await.unwrap();
+    let tokens: Vec<(u32, u32, u32, u32, u32)> = tokens.iter().map(|token| (token.line, token.start_char, token.length, token.token_type, token.token_modifiers)).collect();
+    assert_eq!(tokens, vec![(0, 3, 5, 0, 1), (0, 9, 4, 1, 0), (2, 4, 3, 0, 0)]);
+
+    let folding = service.folding_ranges(&file).await.unwrap();
+    assert_eq!((folding[0].start_line, folding[0].end_line, folding[0].start_character), (0, 4, None));
+    assert_eq!(folding[1].end_character, Some(2));
+    assert!(matches!(folding[1].kind, Some(FoldingRangeKind::Comment)));
+}
+
+#[tokio::test]
+async fn test_edit_responses() {
+    let (main, lib) = (uri("main.rs"), uri("lib.rs"));
+    let server = FakeServer::new(json!({
+        "responses": {
+            "textDocument/formatting": [{ "range": range((0, 0), (0, 12)), "newText": "fn main() {}" }],
+            "textDocument/rangeFormatting": [],
+            "textDocument/rename": {
+                "documentChanges": [
+                    { "textDocument": { "uri": lib, "version": 1 }, "edits": [{ "range": range((0, 7), (0, 12)), "newText": "welcome" }] },
+                    { "kind": "create", "uri": uri("new.rs") },
+                    { "textDocument": { "uri": main, "version": 3 }, "edits": [
+                        { "range": range((3, 4), (3, 9)), "newText": "welcome" },
+                        { "range": range((4, 4), (4, 9)), "newText": "welcome" }
+                    ] }
+                ]
+            },
+            "textDocument/codeAction": [
+                {
+                    "title": "Add missing semicolon",
+                    "kind": "quickfix",
+                    "diagnostics": [{ "range": range((1, 0), (1, 5)), "severity": 1, "code": "E0308", "message": "mismatched types" }],
+                    "edit": { "changes": { (main.clone()): [{ "range": range((1, 5), (1, 5)), "newText": ";" }] } }
+                },
+                { "title": "Extract into function", "kind": "refactor.extract.function", "command": { "title": "Extract", "command": "rust-analyzer.extract", "arguments": [1] } },
+                { "title": "Run test", "command": "rust-analyzer.runSingle", "arguments": [{ "name": "it_works" }] }
+            ]
+        }
+    }));
+    let mut service = server.start().await;
+    let file = server.file("main.rs");
+
+    let edits = service.format_document(&file).await.unwrap();
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].new_text, "fn main() {}");
+    assert_eq!(span(&edits[0].range), (0, 0, 0, 12));
+    assert_eq!(server.received_method("textDocument/formatting")[0]["params"]["options"]["tabSize"], json!(4));
+
+    let selection = Range { start: position(1, 0), end: position(2, 0) };
+    assert!(service.format_range(&file, selection.clone()).await.unwrap().is_empty());
+    assert_eq!(server.received_method("textDocument/rangeFormatting")[0]["params"]["range"], range((1, 0), (2, 0)));
+
+    let renamed = service.rename(&server.file("lib.rs"), position(0, 8), "welcome").await.unwrap();
+    assert_eq!(renamed.len(), 2);
+    assert_eq!(renamed[&server.file("lib.rs")].len(), 1);
+    assert_eq!(renamed[&server.file("main.rs")].len(), 2);
+    assert!(renamed[&server.file("main.rs")].iter().all(|edit| edit.new_text == "welcome"));
+    assert_eq!(server.received_method("textDocument/rename")[0]["params"]["newName"], json!("welcome"));
+
+    let actions = service.code_actions(&file, selection).await.unwrap();
+    assert_eq!(actions.len(), 3);
+    assert!(matches!(actions[0].kind, CodeActionKind::QuickFix));
+    assert_eq!(actions[0].diagnostics[0].code.as_deref(), Some("E0308"));
+    assert_eq!(actions[0].edit.as_ref().unwrap().changes[&file][0].new_text, ";");
+    assert!(matches!(actions[1].kind, CodeActionKind::RefactorExtract));
+    assert_eq!(actions[1].command.as_ref().unwrap().arguments, vec![json!(1)]);
+    assert_eq!(actions[2].title, "Run test");
+    assert_eq!(actions[2].command.as_ref().unwrap().command, "rust-analyzer.runSingle");
+}
+
+#[tokio::test]
+async fn test_diagnostics_and_progress_notifications() {
+    let uri = uri("main.rs");
+    let server = FakeServer::new(json!({
+        "on": {
+            "initialize": [{ "jsonrpc": "2.0", "id": "progress-1", "method": "window/workDoneProgress/create", "params": { "token": "indexing" } }],
+            "textDocument/didOpen": [
+                { "jsonrpc": "2.0", "method": "$/progress", "params": { "token": "indexing", "value": { "kind": "begin", "title": "Indexing", "percentage": 0 } } },
+                { "jsonrpc": "2.0", "method": "$/progress", "params": { "token": "indexing", "value": { "kind": "report", "message": "3/4 crates", "percentage": 75 } } },
+                { "jsonrpc": "2.0", "method": "$/progress", "params": { "token": 7, "value": { "kind": "begin", "title": "Building" } } },
+                { "jsonrpc": "2.0", "method": "textDocument/publishDiagnostics", "params": { "uri": uri, "diagnostics": [
+                    { "range": range((1, 4), (1, 9)), "severity": 1, "code": "E0425", "source": "rustc", "message": "cannot find value `x`",
+                      "relatedInformation": [{ "location": { "uri": uri, "range": range((0, 0), (0, 3)) }, "message": "defined here" }] },
+                    { "range": range((5, 0), (5, 1)), "severity": 2, "code": 42, "message": "unused" }
+                ] } }
+            ],
+            "textDocument/didChange": [
+                { "jsonrpc": "2.0", "method": "$/progress", "params": { "token": "indexing", "value": { "kind": "end" } } },
+                { "jsonrpc": "2.0", "method": "textDocument/publishDiagnostics", "params": { "uri": uri, "diagnostics": [] } }
+            ]
+        },
+        "responses": {
+            "textDocument/codeAction": []
+        }
+    }));
+    let mut service = server.start().await;
+    let file = server.file("main.rs");
+
+    // The client accepted the progress token while initializing
+    let replies: Vec<Value> = server.received().into_iter().filter(|message| message["id"] == "progress-1").collect();
+    assert_eq!(replies, vec![json!({ "jsonrpc": "2.0", "id": "progress-1", "result": null })]);
+
+    service.open_document(&file, "fn main() {\n    x;\n}\n").await.unwrap();
+    // A round trip guarantees the notifications sent before the reply were handled
+    assert!(service.hover(&file, position(0, 0)).await.unwrap().is_none());
+
+    let diagnostics = service.diagnostics(&file).await.unwrap();
+    assert_eq!(diagnostics.len(), 2);
+    assert!(matches!(diagnostics[0].severity, Some(DiagnosticSeverity::Error)));
+    assert_eq!(diagnostics[0].code.as_deref(), Some("E0425"));
+    assert_eq!(diagnostics[0].source.as_deref(), Some("rustc"));
+    assert_eq!(diagnostics[0].related_information.as_ref().unwrap()[0].message, "defined here");
+    assert_eq!(diagnostics[1].code.as_deref(), Some("42"));
+    assert!(service.diagnostics(&server.file("other.rs")).await.unwrap().is_empty());
+
+    // Only the diagnostic overlapping the requested range is sent as context
+    service.code_actions(&file, Range { start: position(1, 0), end: position(1, 10) }).await.unwrap();
+    let context = &server.received_method("textDocument/codeAction")[0]["params"]["context"]["diagnostics"];
+    assert_eq!(context.as_array().unwrap().len(), 1);
+    assert_eq!(context[0]["code"], json!("E0425"));
+
+    assert_eq!(
+        service.progress(),
+        vec![
+            WorkDoneProgress { token: "7".to_string(), title: "Building".to_string(), message: None, percentage: None, done: false },
+            WorkDoneProgress {
+                token: "indexing".to_string(),
+                title: "Indexing".to_string(),
+                message: Some("3/4 crates".to_string()),
+                percentage: Some(75),
+                done: false,
+            },
+        ]
+    );
+
+    service.update_document(&file, "fn main() {}\n", 2).await.unwrap();
+    assert!(service.hover(&file, position(0, 0)).await.unwrap().is_none());
+    assert!(service.diagnostics(&file).await.unwrap().is_empty());
+    assert!(service.progress().iter().any(|progress| progress.token == "indexing" && progress.done));
+}
+
+#[tokio::test]
+async fn test_apply_edit_requests() {
+    let uri = uri("main.rs");
+    let apply_edit = |id: &str| {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "workspace/applyEdit",
+            "params": { "label": "Fill match arms", "edit": { "changes": { (uri.clone()): [{ "range": range((2, 0), (2, 0)), "newText": "    None => {}\n" }] } } }
+        })
+    };
+    let server = FakeServer::new(json!({
+        "on": {
+            "workspace/executeCommand": [apply_edit("edit-1")],
+            "textDocument/hover": [
+                apply_edit("edit-2"),
+                { "jsonrpc": "2.0", "id": 99, "method": "experimental/unknown" },
+                { "jsonrpc": "2.0", "id": 100, "method": "workspace/configuration", "params": { "items": [{ "section": "rust-analyzer" }, { "section": "files" }] } }
+            ]
+        },
+        "responses": {
+            "workspace/executeCommand": null,
+            "textDocument/hover": null
+        }
+    }));
+    let mut service = server.start().await;
+    let file = server.file("main.rs");
+
+    // Without a handler edits are queued and reported as applied
+    let command = ide_rs::editor::language_service::Command {
+        title: "Fill match arms".to_string(),
+        command: "rust-analyzer.applySourceChange".to_string(),
+        arguments: vec![json!({ "id": 1 })],
+    };
+    assert_eq!(service.execute_command(&command).await.unwrap(), Value::Null);
+    let request = &server.received_method("workspace/executeCommand")[0]["params"];
+    assert_eq!(request, &json!({ "command": "rust-analyzer.applySourceChange", "arguments": [{ "id": 1 }] }));
+
+    let edits = service.take_workspace_edits();
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].changes[&file][0].new_text, "    None => {}\n");
+    assert!(service.take_workspace_edits().is_empty());
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let handler_seen = seen.clone();
+    service.set_apply_edit_handler(move |edit| {
+        handler_seen.lock().unwrap().push(edit.changes.len());
+        Err("document changed".to_string())
+    });
+    assert!(service.hover(&file, position(0, 0)).await.unwrap().is_none());
+    assert_eq!(*seen.lock().unwrap(), vec![1]);
+    assert!(service.take_workspace_edits().is_empty());
+
+    let reply = |id: Value| server.received().into_iter().find(|message| message["id"] == id && message.get("method").is_none()).unwrap();
+    assert_eq!(reply(json!("edit-1"))["result"], json!({ "applied": true }));
+    assert_eq!(reply(json!("edit-2"))["result"], json!({ "applied": false, "failureReason": "document changed" }));
+    assert_eq!(reply(json!(99))["error"]["code"], json!(-32601));
+    assert_eq!(reply(json!(100))["result"], json!([null, null]));
+}
+
+#[tokio::test]
+async fn test_error_responses_and_timeout() {
+    let server = FakeServer::new(json!({
+        "errors": {
+            "textDocument/hover": { "code": -32602, "message": "invalid position" },
+            "textDocument/definition": { "code": -32603, "message": "index not ready" }
+        },
+        "ignore": ["textDocument/references"],
+        "responses": {
+            "textDocument/formatting": null
+        }
+    }));
+    let mut service = server.start().await;
+    let file = server.file("main.rs");
+
+    match service.hover(&file, position(0, 0)).await {
+        Err(LanguageServiceError::InvalidRequest(message)) => assert_eq!(message, "textDocument/hover: invalid position"),
+        other => panic!("unexpected hover result: {:?}", other),
+    }
+    match service.goto_definition(&file, position(0, 0)).await {
+        Err(LanguageServiceError::Other(message)) => assert_eq!(message, "textDocument/definition failed: index not ready"),
+        other => panic!("unexpected definition result: {:?}", other),
+    }
+
+    service.set_request_timeout(Duration::from_millis(200));
+    assert!(matches!(service.find_references(&file, position(0, 0)).await, Err(LanguageServiceError::Timeout)));
+
+    // The timed-out request is cancelled and later requests still work
+    assert!(service.format_document(&file).await.unwrap().is_empty());
+    let references_id = server.received_method("textDocument/references")[0]["id"].clone();
+    let cancelled = server.received_method("$/cancelRequest");
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!(cancelled[0]["params"]["id"], references_id);
+}
+
+#[tokio::test]
+async fn test_server_exit_fails_pending_requests() {
+    let server = FakeServer::new(json!({ "exit_on": "textDocument/references" }));
+    let mut service = server.start().await;
+    let file = server.file("main.rs");
+
+    assert!(matches!(service.find_references(&file, position(0, 0)).await, Err(LanguageServiceError::ServerUnresponsive)));
+    assert!(matches!(service.hover(&file, position(0, 0)).await, Err(LanguageServiceError::ServerUnresponsive)));
+}
+
+#[tokio::test]
+async fn test_shutdown() {
+    let server = FakeServer::new(json!({ "responses": { "shutdown": null } }));
+    let mut service = server.start().await;
+
+    service.shutdown().await.unwrap();
+    assert!(!service.is_initialized());
+    assert_eq!(service.server_capabilities(), &Value::Null);
+
+    let methods: Vec<Value> = server.received().into_iter().map(|message| message["method"].clone()).collect();
+    assert_eq!(methods, vec![json!("initialize"), json!("initialized"), json!("shutdown"), json!("exit")]);
+    assert!(server.received_method("shutdown")[0].get("params").is_none());
+
+    assert!(matches!(service.hover(&server.file("main.rs"), position(0, 0)).await, Err(LanguageServiceError::NotInitialized)));
+    // Shutting down twice is a no-op
+    service.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_start_failure() {
+    let dir = TempDir::new().unwrap();
+    let mut service = RustAnalyzerService::with_command(dir.path().join("missing-server").to_string_lossy(), Vec::<String>::new());
+
+    match service.initialize(dir.path()).await {
+        Err(LanguageServiceError::Other(message)) => assert!(message.starts_with("Failed to start")),
+        other => panic!("unexpected initialize result: {:?}", other),
+    }
+    assert!(!service.is_initialized());
 }
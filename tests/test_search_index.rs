@@ -0,0 +1,231 @@
+//! Tests for the persistent workspace index behind the advanced search engine
+
+use ide_rs::editor::advanced_search::*;
+use ide_rs::editor::file_watcher::FileWatchEvent;
+use ide_rs::editor::workspace_index::{extract_rust_symbols, trigrams, IndexSnapshot};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const LIB: &str = r#"
+pub mod shapes;
+
+/// Entry point of the sample crate
+pub fn run() {
+    let area = shapes::area(2.0);
+    println!("{}", area);
+}
+"#;
+
+const SHAPES: &str = r#"
+pub struct Square {
+    pub side: f64,
+}
+
+impl Square {
+    pub fn new(side: f64) -> Self {
+        Square { side }
+    }
+}
+
+pub fn area(side: f64) -> f64 {
+    let square = Square::new(side);
+    square.side * square.side
+}
+"#;
+
+fn workspace(files: &[(&str, &str)]) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    for (path, content) in files {
+        let path = dir.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    dir
+}
+
+fn sample() -> TempDir {
+    workspace(&[
+        ("Cargo.toml", "[package]\nname = \"sample\"\nversion = \"0.1.0\"\n"),
+        ("src/lib.rs", LIB),
+        ("src/shapes.rs", SHAPES),
+        ("README.md", "# Sample\n\nComputes the area of a square.\n"),
+        ("target/debug/build.rs", "fn ignored() {}\n"),
+    ])
+}
+
+fn open(dir: &Path) -> (AdvancedSearchEngine, IndexUpdate) {
+    let mut engine = AdvancedSearchEngine::new(SearchSettings { group_results: false, ..SearchSettings::default() });
+    let update = engine.open_workspace(dir).unwrap();
+    (engine, update)
+}
+
+#[test]
+fn test_trigrams_are_lowercased() {
+    let grams = trigrams("AbcD");
+    assert!(grams.contains("abc"));
+    assert!(grams.contains("bcd"));
+    assert_eq!(grams.len(), 2);
+}
+
+#[test]
+fn test_extract_rust_symbols() {
+    let symbols = extract_rust_symbols(Path::new("src/shapes.rs"), SHAPES, "crate::shapes").unwrap();
+    let defined: Vec<(&str, &str)> = symbols
+        .definitions
+        .iter()
+        .map(|definition| (definition.symbol.name.as_str(), definition.symbol.scope.as_str()))
+        .collect();
+    assert!(defined.contains(&("Square", "crate::shapes")));
+    assert!(defined.contains(&("side", "crate::shapes::Square")));
+    assert!(defined.contains(&("new", "crate::shapes::Square")));
+    assert!(defined.contains(&("area", "crate::shapes")));
+
+    let new = symbols.definitions.iter().find(|definition| definition.symbol.name == "new").unwrap();
+    assert_eq!(new.signature.as_deref(), Some("fn new(side: f64) -> Self"));
+    assert_eq!(new.symbol.definition.position.line, 6);
+
+    let call = symbols.references.iter().find(|reference| reference.symbol_name == "new").unwrap();
+    assert_eq!(call.reference_type, ReferenceType::Call);
+    assert_eq!(call.context, "let square = Square::new(side);");
+}
+
+#[tokio::test]
+async fn test_text_search_skips_ignored_directories() {
+    let dir = sample();
+    let (mut engine, update) = open(dir.path());
+    assert_eq!(update.added, 4);
+    assert_eq!(engine.indexed_file_count(), 4);
+
+    let results = engine.search("AREA", SearchType::Text, Vec::new()).await.unwrap();
+    let files: Vec<&Path> = results.results.iter().map(|result| result.file_path.as_path()).collect();
+    assert!(files.contains(&dir.path().join("src/lib.rs").as_path()));
+    assert!(files.contains(&dir.path().join("README.md").as_path()));
+
+    let readme = results.results.iter().find(|result| result.file_path.ends_with("README.md")).unwrap();
+    assert_eq!(readme.location.line, 2);
+    assert_eq!(readme.location.column, 13);
+
+    let ignored = engine.search("ignored", SearchType::Text, Vec::new()).await.unwrap();
+    assert!(ignored.results.is_empty());
+}
+
+#[tokio::test]
+async fn test_search_filters_and_fuzzy_matching() {
+    let dir = sample();
+    let (mut engine, _) = open(dir.path());
+
+    let rust_only = SearchFilter {
+        name: "rust".to_string(),
+        filter_type: FilterType::FileType,
+        value: "rs".to_string(),
+        active: true,
+        operator: FilterOperator::Equals,
+    };
+    let results = engine.search("area", SearchType::Text, vec![rust_only]).await.unwrap();
+    assert!(!results.results.is_empty());
+    assert!(results.results.iter().all(|result| result.file_path.extension().unwrap() == "rs"));
+
+    let fuzzy = engine.search("sqare", SearchType::Fuzzy, Vec::new()).await.unwrap();
+    assert!(fuzzy.results.iter().any(|result| result.context.contains("Square")));
+}
+
+#[test]
+fn test_navigation() {
+    let dir = sample();
+    let (engine, _) = open(dir.path());
+
+    let definitions = engine.find_definitions("crate::shapes::Square::new");
+    assert_eq!(definitions.len(), 1);
+    assert!(definitions[0].symbol.definition.file_path.ends_with("src/shapes.rs"));
+
+    let usages = engine.find_usages("area");
+    assert!(usages.iter().any(|usage| usage.location.file_path.ends_with("src/lib.rs")));
+
+    let symbols = engine.go_to_symbol("squ");
+    assert_eq!(symbols[0].context, "crate::shapes::Square");
+
+    assert_eq!(engine.callers_of("area"), vec!["crate::run".to_string()]);
+    assert_eq!(engine.callers_of("new"), vec!["crate::shapes::area".to_string()]);
+}
+
+#[test]
+fn test_index_is_restored_after_restart() {
+    let dir = sample();
+    let (engine, _) = open(dir.path());
+    drop(engine);
+    assert!(IndexSnapshot::location(dir.path()).exists());
+
+    let (engine, update) = open(dir.path());
+    assert_eq!(update, IndexUpdate { unchanged: 4, ..IndexUpdate::default() });
+    assert_eq!(engine.find_definitions("Square").len(), 1);
+    assert!(engine.find_definitions("Square")[0].symbol.definition.file_path.starts_with(dir.path()));
+}
+
+#[test]
+fn test_changes_made_while_closed_are_picked_up() {
+    let dir = sample();
+    drop(open(dir.path()));
+
+    fs::remove_file(dir.path().join("README.md")).unwrap();
+    fs::write(dir.path().join("src/circle.rs"), "pub struct Circle;\n").unwrap();
+
+    let (engine, update) = open(dir.path());
+    assert_eq!(update, IndexUpdate { added: 1, removed: 1, unchanged: 3, ..IndexUpdate::default() });
+    assert_eq!(engine.find_definitions("Circle").len(), 1);
+}
+
+#[tokio::test]
+async fn test_watch_events_update_the_index() {
+    let dir = sample();
+    let (mut engine, _) = open(dir.path());
+
+    let shapes = dir.path().join("src/shapes.rs");
+    fs::write(&shapes, SHAPES.replace("Square", "Rectangle")).unwrap();
+    assert!(engine.apply_watch_event(&FileWatchEvent::Modified(shapes.clone())).unwrap());
+    assert!(engine.find_definitions("Square").is_empty());
+    assert_eq!(engine.find_definitions("Rectangle").len(), 1);
+
+    let moved = dir.path().join("src/geometry.rs");
+    fs::rename(&shapes, &moved).unwrap();
+    engine.apply_watch_event(&FileWatchEvent::Renamed(shapes, moved.clone())).unwrap();
+    assert!(engine.find_definitions("Rectangle")[0].symbol.definition.file_path == moved);
+
+    fs::remove_file(&moved).unwrap();
+    engine
+        .apply_watch_event(&FileWatchEvent::Batch(vec![FileWatchEvent::Deleted(moved)]))
+        .unwrap();
+    assert!(engine.find_definitions("Rectangle").is_empty());
+    let results = engine.search("Rectangle", SearchType::Text, Vec::new()).await.unwrap();
+    assert!(results.results.is_empty());
+
+    // Events under ignored directories are dropped
+    let build = dir.path().join("target/debug/build.rs");
+    assert!(!engine.apply_watch_event(&FileWatchEvent::Modified(build)).unwrap());
+
+    engine.save_index().unwrap();
+    let (engine, update) = open(dir.path());
+    assert_eq!(update.unchanged, 3);
+    assert!(engine.find_definitions("Rectangle").is_empty());
+}
+
+#[tokio::test]
+async fn test_suggestions() {
+    let dir = sample();
+    let (mut engine, _) = open(dir.path());
+    engine.search("square side", SearchType::Text, Vec::new()).await.unwrap();
+
+    let context = SearchContext {
+        current_file: None,
+        cursor_position: None,
+        selected_text: Some("square.side".to_string()),
+        project_type: None,
+        recent_files: Vec::new(),
+    };
+    let suggestions = engine.get_suggestions("squ", context).await;
+    let of_type = |kind: SuggestionType| suggestions.iter().filter(move |suggestion| suggestion.suggestion_type == kind);
+
+    assert!(of_type(SuggestionType::HistoryBased).any(|suggestion| suggestion.text == "square side"));
+    assert!(of_type(SuggestionType::SymbolBased).any(|suggestion| suggestion.text == "Square"));
+    assert!(of_type(SuggestionType::ContextBased).any(|suggestion| suggestion.text == "square.side"));
+}
@@ -0,0 +1,206 @@
+//! Golden-file tests for versioned component and project serialization
+//!
+//! Golden files live in `tests/golden/serialization`. Set `UPDATE_GOLDEN=1`
+//! to rewrite the current-version files after an intentional schema change,
+//! and add a migration for the previous version when doing so.
+
+use ide_rs::shared::serialization::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden/serialization").join(name)
+}
+
+fn read_golden(name: &str) -> String {
+    std::fs::read_to_string(golden_path(name)).unwrap()
+}
+
+fn assert_golden(name: &str, actual: &str) {
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(golden_path(name), format!("{}\n", actual)).unwrap();
+    }
+    assert_eq!(actual, read_golden(name).trim_end(), "output differs from golden file {}", name);
+}
+
+fn timestamp(s: &str) -> chrono::DateTime<chrono::Utc> {
+    deserialize_datetime(s).unwrap()
+}
+
+fn metadata() -> ComponentMetadata {
+    ComponentMetadata {
+        created_at: timestamp("2024-03-01T09:30:00Z"),
+        modified_at: timestamp("2024-03-02T17:45:10Z"),
+        version: "1.0.0".to_string(),
+        creator: Some("designer".to_string()),
+        tags: vec!["form".to_string()],
+        custom: HashMap::from([("locked".to_string(), serde_json::json!(false))]),
+    }
+}
+
+fn login_form() -> ComponentData {
+    let button = ComponentData {
+        component_type: "button".to_string(),
+        id: "submit".to_string(),
+        properties: HashMap::from([
+            ("text".to_string(), PropertyValue::String("Sign in".to_string())),
+            ("width".to_string(), PropertyValue::Number(120.0)),
+            ("enabled".to_string(), PropertyValue::Boolean(true)),
+            ("background_color".to_string(), PropertyValue::Color([51, 102, 255, 255])),
+        ]),
+        children: Vec::new(),
+        metadata: metadata(),
+        schema_version: CURRENT_SCHEMA_VERSION.to_string(),
+    };
+    let input = ComponentData {
+        component_type: "input".to_string(),
+        id: "username".to_string(),
+        properties: HashMap::from([
+            ("placeholder".to_string(), PropertyValue::String("User name".to_string())),
+            ("suggestions".to_string(), PropertyValue::Array(vec![
+                PropertyValue::String("admin".to_string()),
+                PropertyValue::Null,
+            ])),
+            ("padding".to_string(), PropertyValue::Object(HashMap::from([
+                ("top".to_string(), PropertyValue::Number(4.0)),
+                ("left".to_string(), PropertyValue::Number(8.5)),
+            ]))),
+        ]),
+        children: Vec::new(),
+        metadata: metadata(),
+        schema_version: CURRENT_SCHEMA_VERSION.to_string(),
+    };
+    ComponentData {
+        component_type: "container".to_string(),
+        id: "login_form".to_string(),
+        properties: HashMap::from([("padding".to_string(), PropertyValue::Number(16.0))]),
+        children: vec![input, button],
+        metadata: metadata(),
+        schema_version: CURRENT_SCHEMA_VERSION.to_string(),
+    }
+}
+
+fn sample_project() -> ProjectData {
+    ProjectData {
+        metadata: ProjectMetadata {
+            name: "Login Demo".to_string(),
+            description: Some("Golden file fixture".to_string()),
+            version: "0.2.0".to_string(),
+            created_at: timestamp("2024-03-01T09:00:00Z"),
+            modified_at: timestamp("2024-03-02T18:00:00Z"),
+            author: Some("designer".to_string()),
+            tags: vec!["demo".to_string()],
+            target_platforms: vec!["desktop".to_string(), "web".to_string()],
+        },
+        components: vec![login_form()],
+        assets: vec![AssetData {
+            id: "logo".to_string(),
+            name: "logo.svg".to_string(),
+            asset_type: AssetType::Image,
+            data: "PHN2Zy8+".to_string(),
+            metadata: HashMap::from([("width".to_string(), serde_json::json!(64))]),
+        }],
+        design_system: Some(DesignSystemData {
+            tokens: HashMap::from([(
+                "primary".to_string(),
+                DesignToken {
+                    name: "primary".to_string(),
+                    value: PropertyValue::Color([51, 102, 255, 255]),
+                    category: "color".to_string(),
+                    description: None,
+                },
+            )]),
+            components: Vec::new(),
+            style_guide: StyleGuideData {
+                colors: vec!["#3366ff".to_string()],
+                typography: TypographySettings {
+                    font_families: vec!["Inter".to_string()],
+                    font_sizes: vec![12.0, 14.0, 20.0],
+                    line_heights: vec![1.2, 1.5],
+                    font_weights: vec![400, 700],
+                },
+                spacing: vec![4.0, 8.0, 16.0],
+                border_radius: vec![2.0, 4.0],
+            },
+        }),
+        settings: ProjectSettings::default(),
+        schema_version: CURRENT_SCHEMA_VERSION.to_string(),
+    }
+}
+
+#[test]
+fn test_project_matches_golden_file() {
+    let json = SerializationUtils::serialize_project(&sample_project()).unwrap();
+    assert_golden("project_current.json", &json);
+}
+
+#[test]
+fn test_golden_project_reloads_identically() {
+    let golden = read_golden("project_current.json");
+
+    let project = SerializationUtils::deserialize_project(&golden).unwrap();
+    assert_eq!(project, sample_project());
+
+    let resaved = SerializationUtils::serialize_project(&project).unwrap();
+    assert_eq!(resaved, golden.trim_end());
+}
+
+#[test]
+fn test_component_round_trip() {
+    let component = login_form();
+    let json = SerializationUtils::serialize_component(&component).unwrap();
+    assert_golden("component_current.json", &json);
+
+    let restored = SerializationUtils::deserialize_component(&json).unwrap();
+    assert_eq!(restored, component);
+}
+
+#[test]
+fn test_migration_chain_is_contiguous() {
+    let migrations = SerializationUtils::migrations();
+    if let (Some(first), Some(last)) = (migrations.first(), migrations.last()) {
+        assert_eq!(first.from, INITIAL_SCHEMA_VERSION);
+        assert_eq!(last.to, CURRENT_SCHEMA_VERSION);
+    }
+    for pair in migrations.windows(2) {
+        assert_eq!(pair[0].to, pair[1].from);
+    }
+}
+
+#[test]
+fn test_unversioned_component_loads_as_initial_version() {
+    let mut value: serde_json::Value =
+        serde_json::from_str(&SerializationUtils::serialize_component(&login_form()).unwrap()).unwrap();
+    value.as_object_mut().unwrap().remove("schema_version");
+
+    let component = SerializationUtils::deserialize_component(&value.to_string()).unwrap();
+    assert_eq!(component.schema_version, CURRENT_SCHEMA_VERSION);
+}
+
+#[test]
+fn test_unsupported_versions_are_rejected() {
+    let future = r#"{"component_type":"button","schema_version":"9.0.0"}"#;
+    assert!(matches!(
+        SerializationUtils::deserialize_component(future),
+        Err(SerializationError::VersionMismatch { .. })
+    ));
+
+    let unknown = r#"{"component_type":"button","schema_version":"0.9.0"}"#;
+    assert!(matches!(
+        SerializationUtils::deserialize_component(unknown),
+        Err(SerializationError::MigrationError { .. })
+    ));
+
+    assert!(matches!(
+        SerializationUtils::deserialize_project("{not json"),
+        Err(SerializationError::JsonError(_))
+    ));
+
+    assert!(matches!(
+        SerializationUtils::migrate_component_data(
+            ComponentData { schema_version: "0.9.0".to_string(), ..login_form() },
+            CURRENT_SCHEMA_VERSION,
+        ),
+        Err(SerializationError::MigrationError { .. })
+    ));
+}
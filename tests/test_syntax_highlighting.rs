@@ -0,0 +1,128 @@
+//! Tests for stateful and incremental syntax highlighting
+
+use egui::Color32;
+use ide_rs::editor::performance::syntax_cache::SyntaxHighlightCache;
+use ide_rs::editor::syntax_highlighter::SyntaxHighlighter;
+use std::time::{Duration, Instant};
+
+/// Colors of every non-whitespace segment of a line
+fn colors(segments: &[(String, Color32)]) -> Vec<Color32> {
+    segments.iter()
+        .filter(|(text, _)| !text.trim().is_empty())
+        .map(|(_, color)| *color)
+        .collect()
+}
+
+fn comment_color(highlighter: &SyntaxHighlighter) -> Color32 {
+    colors(&highlighter.highlight_line("// note\n", "rust"))[0]
+}
+
+#[test]
+fn test_block_comment_spans_lines() {
+    let highlighter = SyntaxHighlighter::new("base16-ocean.dark");
+    let lines = highlighter.highlight_text("/* start\nlet x = 1;\n*/\nlet y = 2;\n", "rust");
+    let comment = comment_color(&highlighter);
+
+    assert_eq!(lines.len(), 4);
+    assert!(colors(&lines[1]).iter().all(|color| *color == comment));
+    assert!(colors(&lines[3]).iter().any(|color| *color != comment));
+    assert_eq!(lines[3].iter().map(|(text, _)| text.as_str()).collect::<String>(), "let y = 2;");
+}
+
+#[test]
+fn test_raw_string_spans_lines() {
+    let highlighter = SyntaxHighlighter::new("base16-ocean.dark");
+    let lines = highlighter.highlight_text("let s = r#\"\nfn not_code() {}\n\"#;\nfn code() {}\n", "rust");
+    let string = *colors(&lines[0]).last().unwrap();
+
+    assert!(colors(&lines[1]).iter().all(|color| *color == string));
+    assert_ne!(colors(&lines[3])[0], string);
+}
+
+#[test]
+fn test_edit_rehighlights_only_changed_lines() {
+    let highlighter = SyntaxHighlighter::new("base16-ocean.dark");
+    let text: String = (0..500).map(|i| format!("let value_{} = {};\n", i, i)).collect();
+    let mut document = highlighter.document("rust");
+    document.set_text(&text);
+    document.highlight_all();
+    assert_eq!(document.lines_highlighted(), 500);
+
+    let edited = text.replace("let value_250 = 250;", "let value_250 = 251;");
+    assert!(document.update(&edited));
+    document.highlight_all();
+
+    assert_eq!(document.lines_highlighted(), 501);
+    assert_eq!(document.line_text(250), Some("let value_250 = 251;"));
+    assert!(!document.update(&edited));
+}
+
+#[test]
+fn test_opening_comment_propagates_to_end() {
+    let highlighter = SyntaxHighlighter::new("base16-ocean.dark");
+    let comment = comment_color(&highlighter);
+    let text: String = (0..50).map(|i| format!("let value_{} = {};\n", i, i)).collect();
+    let mut document = highlighter.document("rust");
+    document.set_text(&text);
+    document.highlight_all();
+
+    let mut lines: Vec<&str> = text.lines().collect();
+    lines[10] = "/* let value_10 = 10;";
+    document.update(&lines.join("\n"));
+    document.highlight_all();
+    for index in 10..50 {
+        assert!(colors(document.line(index).unwrap()).iter().all(|color| *color == comment), "line {}", index);
+    }
+    assert!(colors(document.line(9).unwrap()).iter().any(|color| *color != comment));
+
+    // Closing it again restores the lines after it
+    lines[20] = "*/";
+    document.update(&lines.join("\n"));
+    document.highlight_all();
+    assert!(colors(document.line(21).unwrap()).iter().any(|color| *color != comment));
+    let expected = highlighter.highlight_text(&lines.join("\n"), "rust");
+    assert_eq!(document.line(30), Some(expected[30].as_slice()));
+}
+
+#[test]
+fn test_background_highlighting_converges() {
+    let highlighter = SyntaxHighlighter::new("base16-ocean.dark");
+    let mut text: String = (0..300).map(|i| format!("fn f{}() {{ let s = \"{}\"; }}\n", i, i)).collect();
+    let mut cache = SyntaxHighlightCache::with_background_threshold(100);
+    cache.update_document(&text, "rust");
+    assert!(cache.is_background());
+
+    // Open a block comment half way through while highlighting is in progress
+    text = text.replacen("fn f150()", "/* fn f150()", 1);
+    cache.update_document(&text, "rust");
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while cache.process_background_results() {
+        assert!(Instant::now() < deadline, "background highlighting did not finish");
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    let expected = highlighter.highlight_text(&text, "rust");
+    for (index, line) in text.lines().enumerate() {
+        let highlighted = cache.get_or_highlight_line(index, line, "rust");
+        assert_eq!(highlighted.segments, expected[index], "line {}", index);
+    }
+    let stats = cache.get_stats();
+    assert_eq!(stats.dirty_lines_count, 0);
+    assert_eq!(stats.cache_size, 300);
+    assert_eq!(stats.cache_hits, 300);
+}
+
+#[test]
+fn test_theme_change_rehighlights_cached_lines() {
+    let mut cache = SyntaxHighlightCache::new();
+    cache.update_document("// note\nlet x = 1;\n", "rust");
+    let dark = cache.get_or_highlight_line(0, "// note", "rust").segments;
+    assert_eq!(colors(&dark)[0], comment_color(&SyntaxHighlighter::new("base16-ocean.dark")));
+
+    cache.set_theme("InspiredGitHub");
+    cache.update_document("// note\nlet x = 1;\n", "rust");
+    let light = cache.get_or_highlight_line(0, "// note", "rust").segments;
+    assert_eq!(colors(&light)[0], comment_color(&SyntaxHighlighter::new("InspiredGitHub")));
+    assert_ne!(colors(&light), colors(&dark));
+}
@@ -1,9 +1,174 @@
-//! Integration test for TcpClient
-use ide_rs::rcl::network::tcp_client::TcpClient;
+//! Integration tests for TcpClient against localhost listeners
+use ide_rs::rcl::network::tcp_client::{
+    decode_length_prefixed, decode_line, encode_length_prefixed, ConnectionState, TcpClient, TcpClientConfig,
+};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+fn fast_config() -> TcpClientConfig {
+    TcpClientConfig {
+        connect_timeout: Duration::from_secs(2),
+        read_timeout: Some(Duration::from_secs(2)),
+        write_timeout: Some(Duration::from_secs(2)),
+        reconnect_delay: Duration::from_millis(10),
+        ..Default::default()
+    }
+}
+
+/// Start a listener that echoes everything back on every accepted connection
+async fn echo_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut buffer = [0u8; 1024];
+                while let Ok(read) = socket.read(&mut buffer).await {
+                    if read == 0 || socket.write_all(&buffer[..read]).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    addr
+}
+
+#[tokio::test]
+async fn test_tcp_client_connect_send_receive() {
+    let addr = echo_server().await;
+    let mut client = TcpClient::with_config(fast_config());
+    assert_eq!(client.connection_state(), &ConnectionState::Disconnected);
+
+    client.connect(&addr).await.unwrap();
+    assert!(client.is_connected());
+    assert_eq!(client.remote_address().unwrap().to_string(), addr);
+
+    assert_eq!(client.send(b"hello").await.unwrap(), 5);
+    assert_eq!(client.receive().await.unwrap(), b"hello");
+
+    client.disconnect().await;
+    assert_eq!(client.connection_state(), &ConnectionState::Disconnected);
+    assert!(client.send(b"again").await.is_err());
+}
+
+#[tokio::test]
+async fn test_tcp_client_connect_failure_sets_failed_state() {
+    // Bind and drop a listener to get a port nothing listens on
+    let addr = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().to_string();
+    let mut client = TcpClient::with_config(fast_config());
+
+    assert!(client.connect(&addr).await.is_err());
+    assert!(matches!(client.connection_state(), ConnectionState::Failed(_)));
+    assert!(client.connect("not an address").await.is_err());
+}
+
+#[tokio::test]
+async fn test_tcp_client_read_timeout_keeps_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let server = tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        drop(socket);
+    });
+
+    let mut client = TcpClient::with_config(TcpClientConfig {
+        read_timeout: Some(Duration::from_millis(50)),
+        ..fast_config()
+    });
+    client.connect(&addr).await.unwrap();
+    let error = client.receive().await.unwrap_err();
+    assert!(error.to_string().contains("timed out"));
+    assert!(client.is_connected());
+
+    server.await.unwrap();
+    assert!(client.receive().await.is_err());
+    assert!(matches!(client.connection_state(), ConnectionState::Failed(_)));
+}
 
 #[tokio::test]
-async fn test_tcp_client_connect() {
-    let mut client = TcpClient::new();
-    let result = client.connect("127.0.0.1:8080").await;
-    assert!(result.is_ok());
+async fn test_tcp_client_framing_round_trip() {
+    let addr = echo_server().await;
+    let mut client = TcpClient::with_config(fast_config());
+    client.connect(&addr).await.unwrap();
+
+    client.send_frame(b"first").await.unwrap();
+    client.send_frame(b"").await.unwrap();
+    client.send_frame(&[7u8; 3000]).await.unwrap();
+    assert_eq!(client.receive_frame().await.unwrap(), b"first");
+    assert_eq!(client.receive_frame().await.unwrap(), b"");
+    assert_eq!(client.receive_frame().await.unwrap(), vec![7u8; 3000]);
+
+    client.send(b"one\r\ntwo\nthr").await.unwrap();
+    client.send_line("ee").await.unwrap();
+    assert_eq!(client.receive_line().await.unwrap(), "one");
+    assert_eq!(client.receive_line().await.unwrap(), "two");
+    assert_eq!(client.receive_line().await.unwrap(), "three");
+    assert!(client.send_line("a\nb").await.is_err());
+}
+
+#[test]
+fn test_framing_helpers() {
+    let mut buffer = encode_length_prefixed(b"abc", 16).unwrap();
+    assert_eq!(buffer, [0, 0, 0, 3, b'a', b'b', b'c']);
+    buffer.extend_from_slice(&[0, 0]);
+    assert_eq!(decode_length_prefixed(&mut buffer, 16).unwrap(), Some(b"abc".to_vec()));
+    assert_eq!(decode_length_prefixed(&mut buffer, 16).unwrap(), None);
+    assert!(encode_length_prefixed(&[0u8; 17], 16).is_err());
+
+    let mut oversized = vec![0, 0, 1, 0];
+    assert!(decode_length_prefixed(&mut oversized, 16).is_err());
+
+    let mut lines = b"a\r\nb".to_vec();
+    assert_eq!(decode_line(&mut lines).unwrap(), Some("a".to_string()));
+    assert_eq!(decode_line(&mut lines).unwrap(), None);
+    assert_eq!(lines, b"b");
+}
+
+#[tokio::test]
+async fn test_tcp_client_reconnects_after_connection_loss() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        // The first connection is closed right away, later ones echo lines
+        let (first, _) = listener.accept().await.unwrap();
+        drop(first);
+        while let Ok((mut socket, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut buffer = [0u8; 1024];
+                while let Ok(read) = socket.read(&mut buffer).await {
+                    if read == 0 || socket.write_all(&buffer[..read]).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    let mut client = TcpClient::with_config(TcpClientConfig { auto_reconnect: true, ..fast_config() });
+    client.connect(&addr).await.unwrap();
+    assert!(client.receive().await.is_err());
+    assert!(matches!(client.connection_state(), ConnectionState::Failed(_)));
+
+    client.send_line("after reconnect").await.unwrap();
+    assert!(client.is_connected());
+    assert_eq!(client.reconnect_count(), 1);
+    assert_eq!(client.receive_line().await.unwrap(), "after reconnect");
+}
+
+#[tokio::test]
+async fn test_tcp_client_reconnect_gives_up() {
+    let addr = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().to_string();
+    let mut client = TcpClient::with_config(TcpClientConfig {
+        auto_reconnect: true,
+        max_reconnect_attempts: 2,
+        ..fast_config()
+    });
+
+    assert!(client.connect(&addr).await.is_err());
+    let error = client.reconnect().await.unwrap_err();
+    assert!(error.to_string().contains("after 2 attempts"));
+    assert!(matches!(client.connection_state(), ConnectionState::Failed(_)));
 }
@@ -0,0 +1,192 @@
+//! Tests for the VT screen model and the Unix pseudo-terminal backend
+
+use ide_rs::editor::terminal::vt::{VtColor, VtScreen};
+
+fn screen(cols: u16, rows: u16, output: &str) -> VtScreen {
+    let mut screen = VtScreen::new(cols, rows);
+    screen.process(output.as_bytes());
+    screen
+}
+
+#[test]
+fn test_cursor_addressing_and_erasing() {
+    let mut vt = screen(20, 5, "hello\r\nworld");
+    assert_eq!(vt.row_text(0), "hello");
+    assert_eq!(vt.row_text(1), "world");
+    assert_eq!(vt.cursor_position(), (1, 5));
+
+    vt.process(b"\x1b[1;3HX\x1b[2;2H\x1b[K");
+    assert_eq!(vt.row_text(0), "heXlo");
+    assert_eq!(vt.row_text(1), "w");
+
+    vt.process(b"\x1b[5;10Hend\x1b[3A\x1b[4Dup");
+    assert_eq!(vt.row_text(4), "         end");
+    assert_eq!(vt.row_text(1), "w       up");
+
+    vt.process(b"\x1b[2J");
+    assert!((0..5).all(|row| vt.row_text(row).is_empty()));
+
+    // Cursor position report
+    vt.process(b"\x1b[3;7H\x1b[6n");
+    assert_eq!(vt.take_responses(), b"\x1b[3;7R");
+}
+
+#[test]
+fn test_autowrap_and_utf8_split_across_chunks() {
+    let mut vt = screen(4, 3, "abcdef");
+    assert_eq!(vt.row_text(0), "abcd");
+    assert_eq!(vt.row_text(1), "ef");
+
+    let text = "é→".as_bytes();
+    vt.process(&text[..1]);
+    vt.process(&text[1..4]);
+    vt.process(&text[4..]);
+    assert_eq!(vt.row_text(1), "efé→");
+}
+
+#[test]
+fn test_alternate_screen_restores_primary() {
+    let mut vt = screen(10, 3, "prompt$ ");
+    vt.process(b"\x1b[?1049h\x1b[H\x1b[2Jeditor");
+    assert!(vt.is_alternate_screen());
+    assert_eq!(vt.row_text(0), "editor");
+
+    vt.process(b"\x1b[?1049l");
+    assert!(!vt.is_alternate_screen());
+    assert_eq!(vt.row_text(0), "prompt$");
+    assert_eq!(vt.cursor_position(), (0, 8));
+    assert!(vt.take_scrollback().is_empty());
+}
+
+#[test]
+fn test_scroll_region() {
+    let mut vt = screen(10, 5, "header\r\n1\r\n2\r\n3\r\nstatus");
+    // Scroll rows 2-4 only, keeping the header and status line in place
+    vt.process(b"\x1b[2;4r\x1b[4;1H\n4");
+    assert_eq!(
+        (0..5).map(|row| vt.row_text(row)).collect::<Vec<_>>(),
+        vec!["header", "2", "3", "4", "status"]
+    );
+    // Lines leaving a region below the top of the screen are not scrollback
+    assert!(vt.take_scrollback().is_empty());
+
+    vt.process(b"\x1b[2;1H\x1bMnew");
+    assert_eq!(vt.row_text(1), "new");
+    assert_eq!(vt.row_text(3), "3");
+    assert_eq!(vt.row_text(4), "status");
+}
+
+#[test]
+fn test_scrollback_and_styles() {
+    let mut vt = screen(10, 2, "\x1b[1;31mfirst\x1b[0m\r\nsecond\r\nthird\r\n");
+    let scrollback = vt.take_scrollback();
+    assert_eq!(scrollback.iter().map(|line| line.content.as_str()).collect::<Vec<_>>(), vec!["first", "second"]);
+
+    let style = scrollback[0].ansi_metadata.as_ref().unwrap();
+    assert!(style.bold);
+    assert_eq!(style.fg_color.as_ref().map(|color| (color.r, color.g, color.b)), Some((205, 0, 0)));
+    assert!(scrollback[1].ansi_metadata.is_none());
+
+    vt.process(b"\x1b[38;2;1;2;3mrgb\x1b[48;5;196m!");
+    let row = vt.row(1);
+    assert_eq!(row[0].style.fg, Some(VtColor::Rgb(1, 2, 3)));
+    assert_eq!(row[3].style.bg, Some(VtColor::Indexed(196)));
+}
+
+#[test]
+fn test_resize_keeps_cursor_line() {
+    let mut vt = screen(10, 4, "a\r\nb\r\nc\r\nd");
+    vt.resize(5, 2);
+    assert_eq!(vt.size(), (5, 2));
+    assert_eq!(vt.row_text(0), "c");
+    assert_eq!(vt.row_text(1), "d");
+    assert_eq!(vt.cursor_position(), (1, 1));
+    let scrollback = vt.take_scrollback();
+    assert_eq!(scrollback.iter().map(|line| line.content.as_str()).collect::<Vec<_>>(), vec!["a", "b"]);
+}
+
+#[test]
+fn test_title_and_insert_delete() {
+    let mut vt = screen(10, 2, "\x1b]0;build\x07abcdef");
+    assert_eq!(vt.title(), "build");
+    vt.process(b"\x1b[1;2H\x1b[2P");
+    assert_eq!(vt.row_text(0), "adef");
+    vt.process(b"\x1b[1@X");
+    assert_eq!(vt.row_text(0), "aXdef");
+}
+
+#[cfg(unix)]
+mod unix_pty {
+    use ide_rs::editor::terminal::vt::VtScreen;
+    use ide_rs::editor::terminal::{PtyConfig, PtyFactory, PtySession};
+    use std::io::Read;
+    use std::sync::mpsc::{channel, Receiver};
+    use std::time::{Duration, Instant};
+
+    /// Run a shell script on a new pseudo-terminal, collecting its output on a channel
+    fn spawn(script: &str) -> (Box<dyn PtySession>, Receiver<Vec<u8>>) {
+        let config = PtyConfig { initial_size: (80, 24), ..PtyConfig::default() };
+        let mut session = PtyFactory::create_with_config(config).spawn("sh", &["-c", script], None).unwrap();
+        let mut reader = session.take_reader().unwrap();
+        let (sender, receiver) = channel();
+        std::thread::spawn(move || {
+            let mut buffer = [0u8; 1024];
+            while let Ok(n) = reader.read(&mut buffer) {
+                if n == 0 || sender.send(buffer[..n].to_vec()).is_err() {
+                    break;
+                }
+            }
+        });
+        (session, receiver)
+    }
+
+    /// Feed output to the screen until `expected` appears
+    fn wait_for(receiver: &Receiver<Vec<u8>>, screen: &mut VtScreen, expected: &str) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let visible = |screen: &VtScreen| {
+            let (_, rows) = screen.size();
+            (0..rows as usize).any(|row| screen.row_text(row).contains(expected))
+        };
+        while !visible(screen) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match receiver.recv_timeout(remaining) {
+                Ok(bytes) => screen.process(&bytes),
+                Err(_) => panic!("timed out waiting for {:?}", expected),
+            }
+        }
+    }
+
+    #[test]
+    fn test_shell_runs_on_a_tty() {
+        let (mut session, receiver) = spawn("test -t 0 && test -t 1 && echo on-a-tty; stty size");
+        let mut screen = VtScreen::new(80, 24);
+        wait_for(&receiver, &mut screen, "on-a-tty");
+        wait_for(&receiver, &mut screen, "24 80");
+        assert_eq!(session.wait_with_timeout(Duration::from_secs(5)).unwrap(), Some(0));
+    }
+
+    #[test]
+    fn test_resize_sends_sigwinch() {
+        let (mut session, receiver) = spawn("trap 'echo winch; stty size' WINCH; echo ready; while :; do sleep 0.05; done");
+        let mut screen = VtScreen::new(80, 24);
+        wait_for(&receiver, &mut screen, "ready");
+
+        session.resize(100, 30).unwrap();
+        assert_eq!(session.get_size().unwrap(), (100, 30));
+        wait_for(&receiver, &mut screen, "winch");
+        wait_for(&receiver, &mut screen, "30 100");
+
+        session.kill().unwrap();
+        assert!(session.wait_with_timeout(Duration::from_secs(5)).unwrap().is_some());
+        assert!(!session.is_alive());
+    }
+
+    #[test]
+    fn test_input_is_echoed_by_the_tty() {
+        let (mut session, receiver) = spawn("read line; echo \"got:$line\"");
+        let mut screen = VtScreen::new(80, 24);
+        session.write_input(b"hello\n").unwrap();
+        wait_for(&receiver, &mut screen, "got:hello");
+        assert_eq!(screen.row_text(0), "hello");
+    }
+}
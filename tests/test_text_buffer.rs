@@ -0,0 +1,192 @@
+//! Tests for multi-cursor and column-selection editing in the text buffer
+use ide_rs::editor::text_buffer::*;
+
+/// Caret at a character index of a buffer
+fn caret(buffer: &TextBuffer, index: usize) -> Cursor {
+    Cursor::new(buffer.offset_to_position(buffer.rope.char_to_byte(index)).unwrap())
+}
+
+/// Selection from `anchor` to `head`, both character indexes
+fn span(buffer: &TextBuffer, anchor: usize, head: usize) -> Cursor {
+    let mut cursor = caret(buffer, head);
+    cursor.anchor = Some(caret(buffer, anchor).position);
+    cursor
+}
+
+fn selection(cursors: Vec<Cursor>) -> SelectionSet {
+    SelectionSet { cursors, primary: 0 }
+}
+
+/// Cursors as (anchor, head) character indexes
+fn spans(buffer: &TextBuffer, selection: &SelectionSet) -> Vec<(usize, usize)> {
+    let index = |position: &TextPosition| buffer.rope.byte_to_char(position.offset);
+    selection.cursors.iter()
+        .map(|cursor| (cursor.anchor.as_ref().map_or(index(&cursor.position), index), index(&cursor.position)))
+        .collect()
+}
+
+#[test]
+fn test_insert_at_every_cursor() {
+    let mut buffer = TextBuffer::from_string("let a;\nlet b;\nlet c;\n".to_string());
+    let cursors = selection(vec![caret(&buffer, 19), caret(&buffer, 3), span(&buffer, 11, 12)]);
+
+    let result = buffer.insert_at_cursors("_x", cursors).unwrap();
+    assert_eq!(buffer.to_string(), "let_x a;\nlet _x;\nlet c_x;\n");
+    assert_eq!(spans(&buffer, &result), vec![(5, 5), (15, 15), (24, 24)]);
+    assert_eq!(result.primary, 2, "the primary cursor follows its text");
+    assert_eq!(result.cursors[1].position.line, 1);
+    assert_eq!(result.cursors[1].position.column, 6);
+
+    // One undo step restores the text and the original cursors
+    let restored = buffer.undo().unwrap();
+    assert_eq!(buffer.to_string(), "let a;\nlet b;\nlet c;\n");
+    assert_eq!(spans(&buffer, &restored), vec![(19, 19), (3, 3), (11, 12)]);
+    assert!(buffer.undo().is_err());
+
+    let redone = buffer.redo().unwrap();
+    assert_eq!(buffer.to_string(), "let_x a;\nlet _x;\nlet c_x;\n");
+    assert_eq!(spans(&buffer, &redone), vec![(5, 5), (15, 15), (24, 24)]);
+}
+
+#[test]
+fn test_delete_at_every_cursor() {
+    let mut buffer = TextBuffer::from_string("abc\ndef\nghi".to_string());
+    let cursors = selection(vec![caret(&buffer, 0), caret(&buffer, 3), caret(&buffer, 7), span(&buffer, 9, 11)]);
+
+    let result = buffer.delete_at_cursors(DeleteDirection::Backward, cursors).unwrap();
+    assert_eq!(buffer.to_string(), "ab\nde\ng");
+    assert_eq!(spans(&buffer, &result), vec![(0, 0), (2, 2), (5, 5), (7, 7)]);
+
+    let result = buffer.delete_at_cursors(DeleteDirection::Forward, result).unwrap();
+    assert_eq!(buffer.to_string(), "bdeg");
+    assert_eq!(spans(&buffer, &result), vec![(0, 0), (1, 1), (3, 3), (4, 4)]);
+
+    buffer.undo().unwrap();
+    assert_eq!(buffer.to_string(), "ab\nde\ng");
+    buffer.undo().unwrap();
+    assert_eq!(buffer.to_string(), "abc\ndef\nghi");
+}
+
+#[test]
+fn test_overlapping_cursors_edit_once() {
+    let mut buffer = TextBuffer::from_string("hello world".to_string());
+    let cursors = selection(vec![span(&buffer, 0, 5), span(&buffer, 3, 8), caret(&buffer, 8)]);
+    let result = buffer.insert_at_cursors("X", cursors).unwrap();
+    assert_eq!(buffer.to_string(), "XXrld");
+    assert_eq!(result.cursors.len(), 2);
+
+    let mut buffer = TextBuffer::from_string("ab".to_string());
+    let cursors = selection(vec![caret(&buffer, 1), caret(&buffer, 2)]);
+    assert!(matches!(
+        buffer.replace_at_cursors(&["1".to_string()], cursors.clone()),
+        Err(TextBufferError::CursorCountMismatch { cursors: 2, texts: 1 })
+    ));
+    buffer.replace_at_cursors(&["1".to_string(), "2".to_string()], cursors).unwrap();
+    assert_eq!(buffer.to_string(), "a1b2");
+}
+
+#[test]
+fn test_single_edits_move_every_cursor() {
+    let mut buffer = TextBuffer::from_string("one two three".to_string());
+    let cursors = selection(vec![caret(&buffer, 0), span(&buffer, 4, 7), caret(&buffer, 13)]);
+    let range = TextRange { start: caret(&buffer, 4).position, end: caret(&buffer, 7).position };
+
+    let result = buffer.replace(range, "2", cursors).unwrap();
+    assert_eq!(buffer.to_string(), "one 2 three");
+    assert_eq!(spans(&buffer, &result), vec![(0, 0), (4, 5), (11, 11)]);
+}
+
+#[test]
+fn test_unicode_edits_use_character_positions() {
+    let mut buffer = TextBuffer::from_string("🦀 a\n🌍 b\n".to_string());
+    let cursors = selection(vec![caret(&buffer, 2), caret(&buffer, 6)]);
+    let result = buffer.insert_at_cursors("é", cursors).unwrap();
+    assert_eq!(buffer.to_string(), "🦀 éa\n🌍 éb\n");
+    assert_eq!(result.cursors[1].position.column, 3);
+    buffer.undo().unwrap();
+    assert_eq!(buffer.to_string(), "🦀 a\n🌍 b\n");
+}
+
+#[test]
+fn test_add_next_occurrence() {
+    let buffer = TextBuffer::from_string("foo food foo\nfoo".to_string());
+
+    // The first press selects the word under the caret
+    let word = buffer.add_next_occurrence(&selection(vec![caret(&buffer, 1)]));
+    assert_eq!(spans(&buffer, &word), vec![(0, 3)]);
+
+    let second = buffer.add_next_occurrence(&word);
+    assert_eq!(spans(&buffer, &second), vec![(0, 3), (4, 7)]);
+    assert_eq!(second.primary, 1);
+
+    let mut all = second;
+    for _ in 0..3 {
+        all = buffer.add_next_occurrence(&all);
+    }
+    assert_eq!(spans(&buffer, &all), vec![(0, 3), (4, 7), (9, 12), (13, 16)]);
+
+    // Searching wraps around to the start
+    let wrapped = buffer.add_next_occurrence(&selection(vec![span(&buffer, 9, 12)]));
+    assert_eq!(spans(&buffer, &wrapped), vec![(9, 12), (13, 16)]);
+    let wrapped = buffer.add_next_occurrence(&wrapped);
+    assert_eq!(spans(&buffer, &wrapped), vec![(9, 12), (13, 16), (0, 3)]);
+}
+
+#[test]
+fn test_select_all_occurrences() {
+    let mut buffer = TextBuffer::from_string("foo food foo\nfoo".to_string());
+
+    // From a caret, only whole words match
+    let words = buffer.select_all_occurrences(&selection(vec![caret(&buffer, 10)]));
+    assert_eq!(spans(&buffer, &words), vec![(0, 3), (9, 12), (13, 16)]);
+    assert_eq!(words.primary, 1);
+
+    // From a selection, every match does
+    let matches = buffer.select_all_occurrences(&selection(vec![span(&buffer, 0, 3)]));
+    assert_eq!(matches.cursors.len(), 4);
+
+    buffer.insert_at_cursors("bar", words).unwrap();
+    assert_eq!(buffer.to_string(), "bar food bar\nbar");
+}
+
+#[test]
+fn test_column_selection() {
+    let mut buffer = TextBuffer::from_string("alpha\nbe\n\ngamma ray\n".to_string());
+    let anchor = TextPosition { line: 0, column: 1, offset: 0 };
+    let head = TextPosition { line: 3, column: 4, offset: 0 };
+
+    let boxed = buffer.column_selection(&anchor, &head);
+    assert_eq!(spans(&buffer, &boxed), vec![(1, 4), (7, 8), (11, 14)]);
+    assert_eq!(boxed.primary, 2);
+
+    let result = buffer.insert_at_cursors("|", boxed).unwrap();
+    assert_eq!(buffer.to_string(), "a|a\nb|\n\ng|a ray\n");
+    assert_eq!(result.cursors.len(), 3);
+
+    // A zero-width box puts a caret on each line
+    let head = TextPosition { line: 1, column: 1, offset: 0 };
+    let carets = buffer.column_selection(&TextPosition { line: 0, column: 1, offset: 0 }, &head);
+    assert!(carets.cursors.iter().all(|cursor| !cursor.has_selection()));
+    assert_eq!(carets.cursors.len(), 2);
+}
+
+#[test]
+fn test_replace_all_is_one_undo_step() {
+    let mut buffer = TextBuffer::from_string("a-b-c".to_string());
+    let count = buffer.replace_all("-", ", ", SelectionSet::single(caret(&buffer, 0).position)).unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(buffer.to_string(), "a, b, c");
+    assert_eq!(buffer.replace_all("", "x", SelectionSet::new()).unwrap(), 0);
+
+    buffer.undo().unwrap();
+    assert_eq!(buffer.to_string(), "a-b-c");
+
+    // Explicit transactions group separate edits
+    buffer.undo_stack.begin_transaction("wrap".to_string());
+    buffer.insert(caret(&buffer, 0).position, "(", SelectionSet::new()).unwrap();
+    buffer.insert(caret(&buffer, 6).position, ")", SelectionSet::new()).unwrap();
+    buffer.undo_stack.end_transaction();
+    assert_eq!(buffer.to_string(), "(a-b-c)");
+    buffer.undo().unwrap();
+    assert_eq!(buffer.to_string(), "a-b-c");
+}
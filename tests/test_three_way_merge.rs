@@ -0,0 +1,120 @@
+//! Tests for merging regenerated code with user edits
+use ide_rs::editor::code_generator::{CodeGenerator, CodeTemplate};
+use ide_rs::editor::three_way_merge::*;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const BASE: &str = "fn main() {\n    let a = 1;\n    let b = 2;\n    let c = 3;\n}\n";
+
+#[test]
+fn test_non_overlapping_edits_merge() {
+    let ours = BASE.replace("let a = 1;", "let a = 10;");
+    let theirs = BASE.replace("let c = 3;", "let c = 3;\n    let d = 4;");
+    let result = merge(BASE, &ours, &theirs);
+    assert!(!result.has_conflicts());
+    assert_eq!(
+        result.merged_text().unwrap(),
+        "fn main() {\n    let a = 10;\n    let b = 2;\n    let c = 3;\n    let d = 4;\n}\n"
+    );
+
+    // Deletions on one side are kept as well
+    let result = merge(BASE, &BASE.replace("    let b = 2;\n", ""), &theirs);
+    assert_eq!(result.merged_text().unwrap(), "fn main() {\n    let a = 1;\n    let c = 3;\n    let d = 4;\n}\n");
+}
+
+#[test]
+fn test_identical_and_one_sided_changes() {
+    let changed = BASE.replace("let b = 2;", "let b = 20;");
+    assert_eq!(merge(BASE, &changed, &changed).merged_text().unwrap(), changed);
+    assert_eq!(merge(BASE, BASE, &changed).merged_text().unwrap(), changed);
+    assert_eq!(merge(BASE, &changed, BASE).merged_text().unwrap(), changed);
+    assert_eq!(merge("", "", "new\n").merged_text().unwrap(), "new\n");
+    assert_eq!(merge(BASE, "", BASE).merged_text().unwrap(), "");
+}
+
+#[test]
+fn test_conflicts_are_marked_and_resolved() {
+    let ours = BASE.replace("let b = 2;", "let b = 20;");
+    let theirs = BASE.replace("let b = 2;", "let b = 200;");
+    let mut result = merge(BASE, &ours, &theirs);
+    assert_eq!(result.conflict_count(), 1);
+    assert_eq!(result.merged_text(), None);
+
+    let conflict = result.conflicts().next().unwrap();
+    assert_eq!(conflict.ours_line, 2);
+    assert_eq!(conflict.base, vec!["    let b = 2;\n".to_string()]);
+    assert_eq!(
+        result.text_with_markers(),
+        "fn main() {\n    let a = 1;\n<<<<<<< your edits\n    let b = 20;\n||||||| last generated\n    let b = 2;\n=======\n    let b = 200;\n>>>>>>> designer\n    let c = 3;\n}\n"
+    );
+
+    assert!(result.resolve(0, ConflictResolution::Both));
+    assert_eq!(
+        result.merged_text().unwrap(),
+        "fn main() {\n    let a = 1;\n    let b = 20;\n    let b = 200;\n    let c = 3;\n}\n"
+    );
+    assert!(result.resolve(0, ConflictResolution::Custom("    let b = 7;\n".to_string())));
+    assert_eq!(result.merged_text().unwrap(), BASE.replace("let b = 2;", "let b = 7;"));
+    assert_eq!(result.unresolved_count(), 0);
+    assert!(!result.resolve(1, ConflictResolution::Ours));
+}
+
+#[test]
+fn test_missing_final_newline_keeps_markers_on_their_own_lines() {
+    let mut result = merge("a\nb", "a\nours", "a\ntheirs");
+    assert_eq!(result.text_with_markers(), "a\n<<<<<<< your edits\nours\n||||||| last generated\nb\n=======\ntheirs\n>>>>>>> designer\n");
+    result.resolve(0, ConflictResolution::Theirs);
+    assert_eq!(result.merged_text().unwrap(), "a\ntheirs");
+}
+
+#[test]
+fn test_regenerate_keeps_edits_outside_guards() {
+    let mut generator = CodeGenerator::new(PathBuf::from("generated"));
+    let mut template = CodeTemplate::new(
+        "struct".to_string(),
+        "// header\npub struct {{name}} {\n{{fields}}\n}\n\nimpl {{name}} {\n    {{guard:methods}}\n}\n".to_string(),
+    );
+    template.add_guard("methods".to_string(), "    // methods".to_string());
+    generator.register_template(template);
+    let file = PathBuf::from("generated/form.rs");
+    let id = "struct".to_string();
+
+    let first = generator
+        .regenerate(&id, HashMap::from([("name".to_string(), json!("Form")), ("fields".to_string(), json!("    a: u8,"))]), file.clone(), Some(""))
+        .unwrap();
+    let generated = first.merged_code().unwrap();
+    assert_eq!(generated, first.generated_code);
+    generator.commit_regeneration(&first);
+    assert_eq!(generator.get_snapshot(&file).unwrap().generated_code, generated);
+
+    let edited = generated
+        .replace("// header\n", "// header\n#[allow(dead_code)]\n")
+        .replace("    // methods\n", "    fn go(&self) {}\n");
+    let second = generator
+        .regenerate(&id, HashMap::from([("name".to_string(), json!("Form")), ("fields".to_string(), json!("    a: u8,\n    b: u8,"))]), file.clone(), Some(&edited))
+        .unwrap();
+    assert!(!second.has_conflicts());
+    assert_eq!(
+        second.merged_code().unwrap(),
+        "// header\n#[allow(dead_code)]\npub struct Form {\n    a: u8,\n    b: u8,\n}\n\nimpl Form {\n    // <guard:methods:start>\n    fn go(&self) {}\n    // <guard:methods:end>\n}\n"
+    );
+}
+
+#[test]
+fn test_one_sided_rewrites_round_trip() {
+    let texts = [
+        "",
+        "a\nb\nc\na\nb\nb\na\n",
+        "c\nb\na\nb\na\nc\n",
+        "x\ny\nz\n",
+        "a\na\na\nb\n",
+        "b\na\na\na",
+    ];
+    for base in texts {
+        for other in texts {
+            assert_eq!(merge(base, other, base).merged_text().unwrap(), other, "{:?} -> {:?}", base, other);
+            assert_eq!(merge(base, base, other).merged_text().unwrap(), other, "{:?} -> {:?}", base, other);
+        }
+    }
+}
@@ -1,9 +1,84 @@
-//! Integration test for UdpClient
-use ide_rs::rcl::network::udp_client::UdpClient;
+//! Integration tests for UdpClient against localhost sockets
+use ide_rs::rcl::network::udp_client::{UdpClient, UdpClientConfig};
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::Duration;
+
+fn fast_config() -> UdpClientConfig {
+    UdpClientConfig {
+        receive_timeout: Some(Duration::from_secs(2)),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_udp_client_send_to_and_reply() {
+    let mut server = UdpClient::with_config(fast_config());
+    let server_addr = server.bind("127.0.0.1:0").await.unwrap().to_string();
+
+    let mut client = UdpClient::with_config(fast_config());
+    assert!(!client.is_bound());
+    assert_eq!(client.send_to(&server_addr, b"hello").await.unwrap(), 5);
+    assert!(client.is_bound());
+
+    let (data, from) = server.recv_from().await.unwrap();
+    assert_eq!(data, b"hello");
+    server.send_to(&from.to_string(), b"world").await.unwrap();
+
+    let (reply, reply_from) = client.recv_from().await.unwrap();
+    assert_eq!(reply, b"world");
+    assert_eq!(reply_from.to_string(), server_addr);
+}
+
+#[tokio::test]
+async fn test_udp_client_connected_peer() {
+    let mut server = UdpClient::with_config(fast_config());
+    let server_addr = server.bind("127.0.0.1:0").await.unwrap().to_string();
+
+    let mut client = UdpClient::with_config(fast_config());
+    assert!(client.send(b"early").await.is_err());
+    client.bind("127.0.0.1:0").await.unwrap();
+    client.connect(&server_addr).await.unwrap();
+    assert_eq!(client.peer_address().unwrap().to_string(), server_addr);
+
+    client.send(b"ping").await.unwrap();
+    let (data, from) = server.recv_from().await.unwrap();
+    assert_eq!(data, b"ping");
+    server.send_to(&from.to_string(), b"pong").await.unwrap();
+    assert_eq!(client.recv().await.unwrap(), b"pong");
+}
+
+#[tokio::test]
+async fn test_udp_client_receive_timeout() {
+    let mut client = UdpClient::with_config(UdpClientConfig {
+        receive_timeout: Some(Duration::from_millis(50)),
+        ..Default::default()
+    });
+    assert!(client.recv_from().await.is_err());
+
+    client.bind("127.0.0.1:0").await.unwrap();
+    let error = client.recv_from().await.unwrap_err();
+    assert!(error.to_string().contains("timed out"));
+}
 
 #[tokio::test]
-async fn test_udp_client_send_to() {
-    let client = UdpClient::new();
-    let result = client.send_to("127.0.0.1:8080", b"hello").await;
-    assert!(result.is_ok());
+async fn test_udp_client_broadcast_requires_opt_in() {
+    let mut client = UdpClient::with_config(fast_config());
+    client.bind("127.0.0.1:0").await.unwrap();
+    assert!(client.broadcast(9, b"hello").await.is_err());
+
+    client.set_broadcast(true).unwrap();
+    assert!(client.config().broadcast);
+}
+
+#[tokio::test]
+async fn test_udp_client_multicast_membership() {
+    let mut client = UdpClient::with_config(fast_config());
+    client.bind("0.0.0.0:0").await.unwrap();
+    let group = IpAddr::V4(Ipv4Addr::new(239, 255, 42, 99));
+
+    // Sandboxes without a multicast-capable interface cannot join groups
+    if client.join_multicast(group).await.is_ok() {
+        client.leave_multicast(group).unwrap();
+    }
+    assert!(client.join_multicast(IpAddr::V4(Ipv4Addr::LOCALHOST)).await.is_err());
 }
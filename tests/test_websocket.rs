@@ -1,9 +1,271 @@
-//! Integration test for WebSocketClient
-use ide_rs::rcl::network::websocket::WebSocketClient;
-
-#[tokio::test]
-async fn test_websocket_client_connect() {
-    let client = WebSocketClient::new();
-    let result = client.connect("ws://localhost:8080").await;
-    assert!(result.is_ok());
+//! Integration tests for WebSocketClient against localhost servers
+use futures::{SinkExt, StreamExt};
+use ide_rs::rcl::network::websocket::{
+    WebSocketClient, WebSocketComponent, WebSocketConfig, WebSocketEvent, WebSocketMessage, WebSocketState,
+    WebSocketSubscription, CLOSE_ABNORMAL, CLOSE_NORMAL,
+};
+use ide_rs::rcl::ui::component::Component;
+use std::future::Future;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+const WAIT: Duration = Duration::from_secs(5);
+
+fn fast_config() -> WebSocketConfig {
+    WebSocketConfig {
+        connect_timeout: Duration::from_secs(2),
+        reconnect_delay: Duration::from_millis(10),
+        close_timeout: Duration::from_secs(1),
+        ..Default::default()
+    }
+}
+
+/// Run a WebSocket server on its own thread, calling `handle` with the
+/// zero-based index of each accepted connection
+fn serve<F, Fut>(handle: F) -> String
+where
+    F: Fn(usize, WebSocketStream<TcpStream>) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + 'static,
+{
+    let (address_sender, address_receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let local = tokio::task::LocalSet::new();
+        local.block_on(&runtime, async move {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            address_sender.send(listener.local_addr().unwrap()).unwrap();
+            let mut index = 0;
+            while let Ok((socket, _)) = listener.accept().await {
+                if let Ok(stream) = tokio_tungstenite::accept_async(socket).await {
+                    tokio::task::spawn_local(handle(index, stream));
+                }
+                index += 1;
+            }
+        });
+    });
+    format!("ws://{}", address_receiver.recv().unwrap())
+}
+
+/// Echoes data messages until the client closes
+async fn echo(mut stream: WebSocketStream<TcpStream>) {
+    while let Some(Ok(message)) = stream.next().await {
+        if (message.is_text() || message.is_binary()) && stream.send(message).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Waits for the first event matching `predicate`, skipping others
+fn wait_for(events: &WebSocketSubscription, predicate: impl Fn(&WebSocketEvent) -> bool) -> WebSocketEvent {
+    let deadline = std::time::Instant::now() + WAIT;
+    while let Some(event) = events.recv_timeout(deadline.saturating_duration_since(std::time::Instant::now())) {
+        if predicate(&event) {
+            return event;
+        }
+    }
+    panic!("timed out waiting for WebSocket event");
+}
+
+#[test]
+fn test_websocket_echo_and_close_handshake() {
+    let url = serve(|_, stream| echo(stream));
+    let mut client = WebSocketClient::with_config(fast_config());
+    assert_eq!(client.state(), WebSocketState::Disconnected);
+    assert!(client.send_text("too early").is_err());
+
+    let events = client.subscribe();
+    client.connect(&url).unwrap();
+    assert_eq!(wait_for(&events, |_| true), WebSocketEvent::Connected { url: url.clone() });
+    assert!(client.is_connected());
+
+    client.send_text("hello").unwrap();
+    client.send_binary(&[1, 2, 3]).unwrap();
+    let text = wait_for(&events, |event| matches!(event, WebSocketEvent::Message(_)));
+    assert_eq!(text, WebSocketEvent::Message(WebSocketMessage::Text("hello".to_string())));
+    let binary = wait_for(&events, |event| matches!(event, WebSocketEvent::Message(_)));
+    assert_eq!(binary, WebSocketEvent::Message(WebSocketMessage::Binary(vec![1, 2, 3])));
+
+    client.close_with(CLOSE_NORMAL, "done");
+    let closed = wait_for(&events, |event| matches!(event, WebSocketEvent::Closed { .. }));
+    assert_eq!(closed, WebSocketEvent::Closed { code: CLOSE_NORMAL, reason: "done".to_string() });
+    assert_eq!(client.state(), WebSocketState::Closed);
+    assert!(client.send_text("after close").is_err());
+}
+
+#[test]
+fn test_websocket_rejects_invalid_urls() {
+    let mut client = WebSocketClient::new();
+    assert!(client.connect("http://localhost:8080").is_err());
+    assert!(client.connect("localhost:8080").is_err());
+    assert_eq!(client.state(), WebSocketState::Disconnected);
+}
+
+#[test]
+fn test_websocket_connect_failure_without_reconnect() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let mut client = WebSocketClient::with_config(WebSocketConfig { auto_reconnect: false, ..fast_config() });
+    let events = client.subscribe();
+    client.connect(&format!("ws://{}", addr)).unwrap();
+
+    assert!(matches!(wait_for(&events, |_| true), WebSocketEvent::Error(_)));
+    let closed = wait_for(&events, |_| true);
+    assert!(matches!(closed, WebSocketEvent::Closed { code: CLOSE_ABNORMAL, .. }));
+    assert_eq!(client.state(), WebSocketState::Closed);
+}
+
+#[test]
+fn test_websocket_reconnects_after_connection_loss() {
+    // The first connection is dropped without a close frame, later ones echo
+    let url = serve(|index, stream| async move {
+        if index > 0 {
+            echo(stream).await;
+        }
+    });
+    let mut client = WebSocketClient::with_config(fast_config());
+    let events = client.subscribe();
+    client.connect(&url).unwrap();
+
+    assert!(matches!(wait_for(&events, |_| true), WebSocketEvent::Connected { .. }));
+    assert!(matches!(wait_for(&events, |_| true), WebSocketEvent::Disconnected { .. }));
+    assert_eq!(
+        wait_for(&events, |_| true),
+        WebSocketEvent::Reconnecting { attempt: 1, delay: Duration::from_millis(10) }
+    );
+    assert!(matches!(wait_for(&events, |_| true), WebSocketEvent::Connected { .. }));
+    assert_eq!(client.reconnect_count(), 1);
+
+    client.send_text("after reconnect").unwrap();
+    let echoed = wait_for(&events, |event| matches!(event, WebSocketEvent::Message(_)));
+    assert_eq!(echoed, WebSocketEvent::Message(WebSocketMessage::Text("after reconnect".to_string())));
+}
+
+#[test]
+fn test_websocket_server_close_is_final() {
+    let url = serve(|_, mut stream| async move {
+        let frame = CloseFrame { code: CloseCode::Normal, reason: "bye".into() };
+        let _ = stream.close(Some(frame)).await;
+        while stream.next().await.is_some() {}
+    });
+    let mut client = WebSocketClient::with_config(fast_config());
+    let events = client.subscribe();
+    client.connect(&url).unwrap();
+
+    let closed = wait_for(&events, |event| matches!(event, WebSocketEvent::Closed { .. }));
+    assert_eq!(closed, WebSocketEvent::Closed { code: CLOSE_NORMAL, reason: "bye".to_string() });
+    assert_eq!(client.reconnect_count(), 0);
+}
+
+#[test]
+fn test_websocket_keepalive() {
+    // The first server answers pings while it reads; the second never reads
+    let url = serve(|index, stream| async move {
+        if index == 0 {
+            echo(stream).await;
+        } else {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            drop(stream);
+        }
+    });
+    let config = WebSocketConfig {
+        ping_interval: Some(Duration::from_millis(20)),
+        pong_timeout: Duration::from_millis(200),
+        auto_reconnect: false,
+        ..fast_config()
+    };
+
+    let mut answered = WebSocketClient::with_config(config.clone());
+    let answered_events = answered.subscribe();
+    answered.connect(&url).unwrap();
+    wait_for(&answered_events, |event| matches!(event, WebSocketEvent::Connected { .. }));
+    std::thread::sleep(Duration::from_millis(400));
+    assert!(answered.is_connected());
+    assert!(answered_events.try_recv().is_none());
+
+    let mut silent = WebSocketClient::with_config(config);
+    let silent_events = silent.subscribe();
+    silent.connect(&url).unwrap();
+    let lost = wait_for(&silent_events, |event| matches!(event, WebSocketEvent::Disconnected { .. }));
+    assert!(matches!(lost, WebSocketEvent::Disconnected { reason } if reason.contains("pong")));
+    wait_for(&silent_events, |event| matches!(event, WebSocketEvent::Closed { .. }));
+}
+
+#[test]
+fn test_websocket_subscriptions_receive_every_event() {
+    let url = serve(|_, stream| echo(stream));
+    let mut client = WebSocketClient::with_config(fast_config());
+    let subscription = client.subscribe();
+    client.connect(&url).unwrap();
+
+    assert!(matches!(subscription.recv_timeout(WAIT), Some(WebSocketEvent::Connected { .. })));
+    client.send_text("shared").unwrap();
+    let expected = WebSocketEvent::Message(WebSocketMessage::Text("shared".to_string()));
+    assert_eq!(subscription.recv_timeout(WAIT), Some(expected.clone()));
+    assert!(subscription.try_recv().is_none());
+
+    // The client's own queue saw the same events
+    let polled = client.poll_events();
+    assert!(matches!(polled[0], WebSocketEvent::Connected { .. }));
+    assert_eq!(polled[1..], [expected]);
+}
+
+#[test]
+fn test_websocket_component_properties_and_handlers() {
+    let url = serve(|_, stream| echo(stream));
+    let mut component = WebSocketComponent::default();
+    assert_eq!(component.name(), "WebSocket");
+    assert!(component.set_property("url", &url));
+    assert!(component.set_property("on_open", "socket_opened"));
+    assert!(component.set_property("on_message", "socket_message"));
+    assert!(component.set_property("ping_interval_secs", "0"));
+    assert!(!component.set_property("auto_connect", "sometimes"));
+    assert_eq!(component.get_property("url"), Some(url.clone()));
+    assert!(component.get_property_names().contains(&"on_close".to_string()));
+
+    component.start().unwrap();
+    let mut handled = Vec::new();
+    let deadline = std::time::Instant::now() + WAIT;
+    while handled.len() < 2 && std::time::Instant::now() < deadline {
+        for (handler, event) in component.poll() {
+            if handler == "socket_opened" {
+                component.send_text("ping").unwrap();
+            }
+            handled.push((handler, event));
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(handled.len(), 2);
+    assert_eq!(handled[0].0, "socket_opened");
+    assert_eq!(
+        handled[1],
+        ("socket_message".to_string(), WebSocketEvent::Message(WebSocketMessage::Text("ping".to_string())))
+    );
+}
+
+#[test]
+fn test_websocket_component_raises_form_events() {
+    let url = serve(|_, stream| echo(stream));
+    let mut component = WebSocketComponent::default();
+    assert!(component.set_property("url", &url));
+    let events: Vec<String> = component.events().into_iter().map(|event| event.name).collect();
+    assert_eq!(events, ["open", "message", "close", "error"]);
+
+    component.start().unwrap();
+    let mut raised = Vec::new();
+    let deadline = std::time::Instant::now() + WAIT;
+    while raised.len() < 2 && std::time::Instant::now() < deadline {
+        for event in component.take_events() {
+            if event == "open" {
+                component.send_text("ping").unwrap();
+            }
+            raised.push(event);
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(raised, ["open", "message"]);
+    assert_eq!(component.next_message(), Some(WebSocketMessage::Text("ping".to_string())));
+    assert_eq!(component.next_message(), None);
 }